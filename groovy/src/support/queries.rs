@@ -155,6 +155,11 @@ pub static IDENT_QUERY: LazyLock<Query> = LazyLock::new(|| {
     (function_declaration type: (type_identifier) @return_name)
     (modifiers [(marker_annotation name: (identifier) @annotation)
         (annotation name: (identifier) @annotation)])
+    (class_literal (type_identifier) @class_literal)
+    (class_literal
+        (scoped_type_identifier
+            (_) @scoped_class_literal_qualifier
+            (type_identifier) @scoped_class_literal))
 "#,
     )
     .unwrap()
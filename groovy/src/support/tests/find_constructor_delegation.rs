@@ -0,0 +1,94 @@
+#![allow(unused_imports)]
+
+use crate::GroovySupport;
+use lsp_core::language_support::LanguageSupport;
+
+use tower_lsp::lsp_types::{Position, Range};
+
+use super::*;
+
+const CONTENT: &str = r#"
+        class Child extends Base {
+            Child(String name) {
+                this(name, 0);
+            }
+
+            Child(String name, int age) {
+                super(name);
+            }
+        }
+    "#;
+
+#[test]
+fn test_this_delegation() {
+    let support = GroovySupport::new();
+    let parsed = support.parse_str(CONTENT).expect("cannot parse content");
+    let pos = find_position(CONTENT, "this(");
+
+    let delegation = support
+        .find_constructor_delegation(&parsed.0, &parsed.1, &pos)
+        .expect("no delegation");
+
+    assert!(!delegation.is_super);
+    assert_eq!(delegation.arg_count, 2);
+}
+
+#[test]
+fn test_super_delegation() {
+    let support = GroovySupport::new();
+    let parsed = support.parse_str(CONTENT).expect("cannot parse content");
+    let pos = find_position(CONTENT, "super(");
+
+    let delegation = support
+        .find_constructor_delegation(&parsed.0, &parsed.1, &pos)
+        .expect("no delegation");
+
+    assert!(delegation.is_super);
+    assert_eq!(delegation.arg_count, 1);
+}
+
+#[test]
+fn test_no_delegation_in_arguments() {
+    let support = GroovySupport::new();
+    let parsed = support.parse_str(CONTENT).expect("cannot parse content");
+    let pos = find_position(CONTENT, "name, 0");
+
+    assert!(
+        support
+            .find_constructor_delegation(&parsed.0, &parsed.1, &pos)
+            .is_none()
+    );
+}
+
+#[test]
+fn test_get_constructors() {
+    let support = GroovySupport::new();
+    let parsed = support.parse_str(CONTENT).expect("cannot parse content");
+
+    let constructors = support.get_constructors(&parsed.0, &parsed.1, "Child");
+
+    let found: Vec<(&str, usize, Position)> = constructors
+        .iter()
+        .map(|c| (c.parameters.as_str(), c.param_count, c.range.start))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            (
+                "(String name)",
+                1,
+                find_position(CONTENT, "Child(String name)")
+            ),
+            (
+                "(String name, int age)",
+                2,
+                find_position(CONTENT, "Child(String name, int age)")
+            ),
+        ]
+    );
+    assert!(
+        support
+            .get_constructors(&parsed.0, &parsed.1, "Base")
+            .is_empty()
+    );
+}
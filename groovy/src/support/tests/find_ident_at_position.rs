@@ -20,7 +20,7 @@ fn test_simple_identifier() {
         "#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "bar;");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("bar".to_string(), None)));
 }
 
@@ -37,7 +37,7 @@ fn test_method_invocation_with_qualifier() {
         "#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "baz");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("baz".to_string(), Some("bar".to_string()))));
 }
 
@@ -54,7 +54,7 @@ fn test_field_access_with_qualifier() {
         "#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "name");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("name".to_string(), Some("bar".to_string()))));
 }
 
@@ -70,7 +70,7 @@ fn test_identifier_in_argument_list() {
         }"#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "bar)");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("bar".to_string(), None)));
 }
 
@@ -85,7 +85,7 @@ fn test_identifier_in_variable_declarator() {
         }"#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "someOtherVar");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("someOtherVar".to_string(), None)));
 }
 
@@ -100,7 +100,7 @@ fn test_this_qualified_method_invocation() {
         }"#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "doSomething");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(
         ident,
         Some(("doSomething".to_string(), Some("this".to_string())))
@@ -118,13 +118,49 @@ fn test_chained_method_invocation() {
         }"#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "getName");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(
         ident,
         Some(("getName".to_string(), Some("user#getProfile".to_string())))
     );
 }
 
+#[test]
+fn test_qualifier_skips_nested_call_arguments() {
+    let support = GroovySupport::new();
+    let content = r#"
+        class Foo {
+            void test() {
+                user.find(ids.get(0), ")").getName()
+            }
+        }"#;
+    let parsed = support.parse_str(&content).expect("cannot parse content");
+    let pos = find_position(content, "getName");
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
+    assert_eq!(
+        ident,
+        Some(("getName".to_string(), Some("user#find".to_string())))
+    );
+}
+
+#[test]
+fn test_qualifier_skips_closure_arguments() {
+    let support = GroovySupport::new();
+    let content = r#"
+        class Foo {
+            void test() {
+                users.findAll({ it.active() }).first()
+            }
+        }"#;
+    let parsed = support.parse_str(&content).expect("cannot parse content");
+    let pos = find_position(content, "first");
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
+    assert_eq!(
+        ident,
+        Some(("first".to_string(), Some("users#findAll".to_string())))
+    );
+}
+
 #[test]
 fn test_static_method_invocation() {
     let support = GroovySupport::new();
@@ -136,7 +172,7 @@ fn test_static_method_invocation() {
         }"#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "createUser");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(
         ident,
         Some(("createUser".to_string(), Some("UserService".to_string())))
@@ -154,7 +190,7 @@ fn test_chained_field_access() {
         }"#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "name");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(
         ident,
         Some(("name".to_string(), Some("user#profile".to_string())))
@@ -172,7 +208,7 @@ fn test_method_parameter_identifier() {
         }"#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "user;");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("user".to_string(), None)));
 }
 
@@ -190,7 +226,7 @@ fn test_closure_parameter_identifier() {
         }"#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "item)");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("item".to_string(), None)));
 }
 
@@ -205,7 +241,7 @@ fn test_constructor_type_in_new_expression() {
         }"#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "ArrayList");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("ArrayList".to_string(), None)));
 }
 
@@ -220,7 +256,7 @@ fn test_type_argument_in_generics() {
         }"#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "MyClass");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("MyClass".to_string(), None)));
 }
 
@@ -235,7 +271,7 @@ fn test_nested_type_arguments_in_generics() {
         }"#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "UserProfile");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("UserProfile".to_string(), None)));
 }
 
@@ -250,7 +286,7 @@ fn test_cast_expression_type() {
         }"#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "MyClass");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("MyClass".to_string(), None)));
 }
 
@@ -264,7 +300,7 @@ fn test_import_statement_type() {
         }"#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "Bar");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(
         ident,
         Some(("Bar".to_string(), Some("com.example".to_string())))
@@ -284,12 +320,12 @@ fn test_nested_qualifier_in_chain() {
 
     // deepest qualifier
     let pos = find_position(content, "user");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("user".to_string(), None)));
 
     // middle of chain
     let pos = find_position(content, "getProfile");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(
         ident,
         Some(("getProfile".to_string(), Some("user".to_string())))
@@ -297,7 +333,7 @@ fn test_nested_qualifier_in_chain() {
 
     // field access at end of chain
     let pos = find_position(content, "name");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(
         ident,
         Some(("name".to_string(), Some("user#getProfile".to_string())))
@@ -317,12 +353,12 @@ fn test_simple_constructor_in_chain() {
 
     // constructor type
     let pos = find_position(content, "Class");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("Class".to_string(), None)));
 
     // method on constructor
     let pos = find_position(content, "process");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(
         ident,
         Some(("process".to_string(), Some("Class".to_string())))
@@ -330,7 +366,7 @@ fn test_simple_constructor_in_chain() {
 
     // field at end of chain
     let pos = find_position(content, "message");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(
         ident,
         Some(("message".to_string(), Some("Class#process".to_string())))
@@ -350,12 +386,12 @@ fn test_scoped_constructor_in_chain() {
 
     // outer qualifier
     let pos = find_position(content, "Outer");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("Outer".to_string(), None)));
 
     // inner constructor type
     let pos = find_position(content, "Inner");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(
         ident,
         Some(("Inner".to_string(), Some("Outer".to_string())))
@@ -363,7 +399,7 @@ fn test_scoped_constructor_in_chain() {
 
     // method on constructor
     let pos = find_position(content, "process");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(
         ident,
         Some(("process".to_string(), Some("Outer#Inner".to_string())))
@@ -371,7 +407,7 @@ fn test_scoped_constructor_in_chain() {
 
     // field at end of chain
     let pos = find_position(content, "message");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(
         ident,
         Some((
@@ -392,7 +428,7 @@ fn test_nested_method_calls() {
         }"#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "message");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(
         ident,
         Some(("message".to_string(), Some("MyClass#process".to_string())))
@@ -410,7 +446,7 @@ fn test_return_type_detection() {
         }"#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "String");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("String".to_string(), None)));
 }
 
@@ -426,7 +462,7 @@ fn test_annotation_on_class() {
         }"#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "Controller");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("Controller".to_string(), None)));
 }
 
@@ -442,7 +478,7 @@ fn test_annotation_on_method() {
         }"#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "GetMapping");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("GetMapping".to_string(), None)));
 }
 
@@ -456,7 +492,7 @@ fn test_annotation_on_field() {
         }"#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "Autowired");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("Autowired".to_string(), None)));
 }
 
@@ -472,6 +508,19 @@ fn test_annotation_with_parameters() {
         }"#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "RequestMapping");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("RequestMapping".to_string(), None)));
 }
+
+#[test]
+fn test_class_literal_in_annotation() {
+    let support = GroovySupport::new();
+    let content = r#"
+        @RunWith(SpringRunner.class)
+        class FooSpec {
+        }"#;
+    let parsed = support.parse_str(&content).expect("cannot parse content");
+    let pos = find_position(content, "SpringRunner");
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
+    assert_eq!(ident, Some(("SpringRunner".to_string(), None)));
+}
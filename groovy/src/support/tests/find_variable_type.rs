@@ -225,7 +225,7 @@ fn test_def_infer_list_literal() {
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "items.size");
     let var_type = support.find_variable_type(&parsed.0, &parsed.1, "items", &pos);
-    assert_eq!(var_type, Some("List".to_string()));
+    assert_eq!(var_type, Some("List<Integer>".to_string()));
 }
 
 #[test]
@@ -242,7 +242,7 @@ fn test_def_infer_map_literal() {
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "config.get");
     let var_type = support.find_variable_type(&parsed.0, &parsed.1, "config", &pos);
-    assert_eq!(var_type, Some("Map".to_string()));
+    assert_eq!(var_type, Some("Map<String, String>".to_string()));
 }
 
 #[test]
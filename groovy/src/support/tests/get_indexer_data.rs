@@ -289,3 +289,31 @@ fn test_get_return() {
     let ret = support.get_return(&node, &parsed.1);
     assert_eq!(ret, Some("String".to_string()));
 }
+
+#[test]
+fn test_get_constant_value() {
+    let support = GroovySupport::new();
+    let test_cases = vec![
+        (
+            r#"class Foo { static final String NAME = 'v' + 2 + '.0' }"#,
+            Some(r#""v2.0""#),
+        ),
+        ("class Foo { static final int SIZE = 10 }", Some("10")),
+        (
+            r#"class Foo { static final String NAME = "${PREFIX}x" }"#,
+            None,
+        ),
+        ("class Foo { final int size = 10 }", None),
+    ];
+    for (content, expected) in test_cases {
+        let parsed = support.parse_str(content).expect("cannot parse content");
+        let node = find_node_by_kind(parsed.0.root_node(), "field_declaration").unwrap();
+        let value = support.get_constant_value(&node, &parsed.1);
+        assert_eq!(
+            value.map(|v| v.to_string()),
+            expected.map(str::to_string),
+            "Failed for: {}",
+            content
+        );
+    }
+}
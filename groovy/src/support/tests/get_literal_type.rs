@@ -32,8 +32,8 @@ fn test_detect_literal_type() {
     let parsed = support.parse_str(&content).expect("cannot parse content");
 
     let test_cases = vec![
-        ("[key: 'value']", Some("Map".to_string())),
-        ("['a', 'b', 'c']", Some("List".to_string())),
+        ("[key: 'value']", Some("Map<String, String>".to_string())),
+        ("['a', 'b', 'c']", Some("List<String>".to_string())),
         ("123", Some("Integer".to_string())),
         ("123L", Some("Long".to_string())),
         ("1.5f", Some("Float".to_string())),
@@ -54,3 +54,42 @@ fn test_detect_literal_type() {
         assert_eq!(literal_type, expected, "Failed for literal: {}", literal);
     }
 }
+
+#[test]
+fn test_collection_literal_element_types() {
+    let support = GroovySupport::new();
+    let content = r#"
+        class TestClass {
+            void testLiterals() {
+                def numbers = [1, 2L, 3.0]
+                def nested = [[1], [2]]
+                def mixed = [1, 'a']
+                def unknown = [foo, bar]
+                def counts = [a: 1, b: 2]
+                def empty = [:]
+            }
+        }
+    "#;
+
+    let parsed = support.parse_str(&content).expect("cannot parse content");
+
+    let test_cases = vec![
+        ("[1, 2L", "List<Number>"),
+        ("[[1]", "List<List<Integer>>"),
+        ("[1, 'a']", "List<Object>"),
+        ("[foo", "List"),
+        ("[a: 1", "Map<String, Integer>"),
+        ("[:]", "Map"),
+    ];
+
+    for (literal, expected) in test_cases {
+        let pos = find_position(content, literal);
+        let literal_type = support.get_literal_type(&parsed.0, &parsed.1, &pos);
+        assert_eq!(
+            literal_type,
+            Some(expected.to_string()),
+            "Failed for literal: {}",
+            literal
+        );
+    }
+}
@@ -1,8 +1,11 @@
 use lsp_core::{
-    language_support::{CallArgData, ClassDeclarationData, GenericTypeUsage, IdentResult, LanguageSupport, MemberAccessData, MethodCallSiteData, MethodSig, NarrowingCandidateData, ObjectCreationData, OverrideMethodData, ParameterResult, ParseResult},
+    constant_value::ConstantValue,
+    language_support::{CallArgData, ClassDeclarationData, ConstructorData, ConstructorDelegationData, EnumConstantData, GenericTypeUsage, IdentResult, LanguageSupport, LocalVariableData, MemberAccessData, MethodCallSiteData, MethodDeclarationData, MethodSig, NarrowingCandidateData, ObjectCreationData, OverrideMethodData, ParameterResult, ParseResult},
     languages::Language,
     node_kind::NodeKind,
+    qualifier::{QualifierChain, Segment, SegmentKind},
     ts_helper::{self, collect_syntax_errors, get_node_at_position, node_contains_position},
+    util::common_element_type,
 };
 use std::{cell::RefCell, collections::HashSet, fs, path::Path, sync::LazyLock};
 
@@ -63,19 +66,7 @@ impl JavaSupport {
                     .captures
                     .iter()
                     .find(|c| Some(c.index) == qual_idx)
-                    .and_then(|cap| cap.node.utf8_text(content.as_bytes()).ok())
-                    .map(|s| {
-                        let mut result = s.to_string();
-                        let regex = regex::Regex::new(r"\([^()]*\)").unwrap();
-                        loop {
-                            let new_result = regex.replace_all(&result, "").to_string();
-                            if new_result == result {
-                                break;
-                            }
-                            result = new_result;
-                        }
-                        result.replace(".", "#").replace("new ", "")
-                    })
+                    .map(|cap| QualifierChain::from_node(&cap.node, content))
             } else {
                 None
             };
@@ -151,6 +142,11 @@ impl JavaSupport {
                     ("superclass", None),
                     ("return_name", None),
                     ("annotation", None),
+                    ("class_literal", None),
+                    (
+                        "scoped_class_literal",
+                        Some("scoped_class_literal_qualifier"),
+                    ),
                 ]
                 .into_iter()
                 .for_each(|(name, qual)| {
@@ -201,7 +197,11 @@ impl JavaSupport {
         let name_text = &content[name_node.byte_range()];
         let scope_text = full_text.strip_suffix(&format!(".{}", name_text))?;
 
-        Some((name_text.to_string(), Some(scope_text.to_string())))
+        // The package or outer type the import is qualified by, as one segment.
+        let scope = QualifierChain {
+            segments: vec![Segment::new(scope_text, SegmentKind::Type)],
+        };
+        Some((name_text.to_string(), Some(scope)))
     }
 
     fn traverse_scope_nodes<F>(
@@ -383,6 +383,7 @@ impl JavaSupport {
                 }
             }
             "true" | "false" => Some("Boolean".to_string()),
+            "array_creation_expression" => array_creation_type(value_node, content),
             _ => None,
         }
     }
@@ -821,7 +822,9 @@ impl LanguageSupport for JavaSupport {
 
     fn get_ident_range(&self, node: &Node) -> Option<Range> {
         let ident_node = match node.kind() {
-            "class_declaration" | "function_declaration" => node.child_by_field_name("name")?,
+            "class_declaration" | "function_declaration" | "annotation_type_element_declaration" => {
+                node.child_by_field_name("name")?
+            }
             "field_declaration" | "constant_declaration" => {
                 let declarator = node
                     .children(&mut node.walk())
@@ -861,12 +864,20 @@ impl LanguageSupport for JavaSupport {
                 _ => None,
             }),
             "annotation_type_declaration" => Some(NodeKind::Annotation),
+            "annotation_type_element_declaration" => Some(NodeKind::Function),
             "constant_declaration" => Some(NodeKind::Field),
             _ => None,
         }
     }
 
     fn get_short_name(&self, node: &Node, source: &str) -> Option<String> {
+        if node.kind() == "annotation_type_element_declaration" {
+            return node
+                .child_by_field_name("name")?
+                .utf8_text(source.as_bytes())
+                .ok()
+                .map(str::to_string);
+        }
         let node_kind = self.get_kind(node);
 
         match node_kind {
@@ -917,6 +928,18 @@ impl LanguageSupport for JavaSupport {
     }
 
     fn get_return(&self, node: &Node, source: &str) -> Option<String> {
+        // Annotation elements: `Class<?>[] value() default {}`.
+        if node.kind() == "annotation_type_element_declaration" {
+            let element_type = node.child_by_field_name("type")?;
+            let dimensions = node
+                .child_by_field_name("dimensions")
+                .and_then(|d| d.utf8_text(source.as_bytes()).ok())
+                .unwrap_or_default();
+            return Some(format!(
+                "{}{dimensions}",
+                element_type.utf8_text(source.as_bytes()).ok()?
+            ));
+        }
         let node_kind = self.get_kind(node);
 
         match node_kind {
@@ -928,6 +951,25 @@ impl LanguageSupport for JavaSupport {
         }
     }
 
+    fn get_constant_value(&self, node: &Node, source: &str) -> Option<ConstantValue> {
+        let is_constant = match node.kind() {
+            // Interface fields are implicitly `static final`.
+            "constant_declaration" => true,
+            "field_declaration" => {
+                let modifiers = self.get_modifiers(node, source);
+                modifiers.iter().any(|m| m == "static") && modifiers.iter().any(|m| m == "final")
+            }
+            _ => false,
+        };
+        if !is_constant {
+            return None;
+        }
+        let value = node
+            .child_by_field_name("declarator")?
+            .child_by_field_name("value")?;
+        constant_node_value(&value, source.as_bytes())
+    }
+
     fn get_imports(&self, tree: &Tree, source: &str) -> Vec<String> {
         let explicit_imports =
             ts_helper::get_many(&tree.root_node(), source, &GET_IMPORTS_QUERY, Some(1))
@@ -1038,45 +1080,12 @@ impl LanguageSupport for JavaSupport {
         let mut node = tree.root_node().descendant_for_point_range(point, point)?;
 
         loop {
-            match node.kind() {
-                "map_literal" => return Some("Map".to_string()),
-                "array_literal" => return Some("List".to_string()),
-
-                // Numeric literals
-                "decimal_integer_literal"
-                | "hex_integer_literal"
-                | "octal_integer_literal"
-                | "binary_integer_literal" => {
-                    // Check for long suffix (l or L)
-                    if let Ok(text) = node.utf8_text(content.as_bytes())
-                        && (text.ends_with('l') || text.ends_with('L'))
-                    {
-                        return Some("Long".to_string());
-                    }
-                    return Some("Integer".to_string());
-                }
-
-                "decimal_floating_point_literal" | "hex_floating_point_literal" => {
-                    if let Ok(text) = node.utf8_text(content.as_bytes()) {
-                        let lower = text.to_lowercase();
-                        if lower.ends_with('f') {
-                            return Some("Float".to_string());
-                        }
-                    }
-                    return Some("Double".to_string());
-                }
-
-                "true" | "false" => return Some("Boolean".to_string()),
-
-                "string_literal" | "text_block" => return Some("String".to_string()),
-
-                "null_literal" => return None,
-
-                "regex_literal" => return Some("Pattern".to_string()),
-
-                _ => {}
+            if node.kind() == "null_literal" {
+                return None;
+            }
+            if let Some(literal_type) = literal_node_type(&node, content) {
+                return Some(literal_type);
             }
-
             node = node.parent()?;
         }
     }
@@ -1463,6 +1472,69 @@ impl LanguageSupport for JavaSupport {
         results
     }
 
+    fn get_method_declarations(&self, tree: &Tree, source: &str) -> Vec<MethodDeclarationData> {
+        let bytes = source.as_bytes();
+        let mut results = Vec::new();
+        let mut stack = vec![tree.root_node()];
+
+        while let Some(node) = stack.pop() {
+            if node.kind() == "function_declaration"
+                && let Some(containing_class) = find_declaring_type(node, bytes)
+                && let Some(name_node) = node.child_by_field_name("name")
+                && let Ok(method_name) = name_node.utf8_text(bytes)
+            {
+                results.push(MethodDeclarationData {
+                    containing_class,
+                    signature: MethodSig::new(method_name, extract_param_types(node, bytes)),
+                    modifiers: self.get_modifiers(&node, source),
+                    annotations: self.get_annotations(&node, source),
+                    name_range: node_to_range(&name_node),
+                    decl_start: node_to_range(&node).start,
+                });
+            }
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                stack.push(child);
+            }
+        }
+
+        results
+    }
+
+    fn get_local_variable_declarations(&self, tree: &Tree, source: &str) -> Vec<LocalVariableData> {
+        let bytes = source.as_bytes();
+        let mut results = Vec::new();
+        let mut stack = vec![tree.root_node()];
+
+        while let Some(node) = stack.pop() {
+            if node.kind() == "variable_declaration"
+                && node.parent().is_some_and(|p| p.kind() == "block")
+            {
+                let mut cursor = node.walk();
+                let declarators: Vec<Node> = node
+                    .children(&mut cursor)
+                    .filter(|c| c.kind() == "variable_declarator")
+                    .collect();
+                let statement_range = (declarators.len() == 1).then(|| node_to_range(&node));
+                for declarator in declarators {
+                    let Some(name_node) = declarator.child_by_field_name("name") else { continue };
+                    let Ok(name) = name_node.utf8_text(bytes) else { continue };
+                    results.push(LocalVariableData {
+                        name: name.to_string(),
+                        ident_range: node_to_range(&name_node),
+                        statement_range,
+                    });
+                }
+            }
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                stack.push(child);
+            }
+        }
+
+        results
+    }
+
     fn get_narrowing_candidates(&self, tree: &Tree, source: &str) -> Vec<NarrowingCandidateData> {
         let bytes = source.as_bytes();
         let mut cursor = QueryCursor::new();
@@ -1545,6 +1617,111 @@ impl LanguageSupport for JavaSupport {
         results
     }
 
+    fn find_lambda_initializer(
+        &self,
+        tree: &Tree,
+        source: &str,
+        decl_position: &Position,
+    ) -> Option<(Range, Option<String>)> {
+        let mut node = get_node_at_position(tree, source, decl_position)?;
+        while node.kind() != "variable_declarator" {
+            node = node.parent()?;
+        }
+        // A lambda parameter's own declarator is the enclosing variable's; skip it.
+        let name = node.child_by_field_name("name")?;
+        if !node_contains_position(&name, decl_position) {
+            return None;
+        }
+        let value = node.child_by_field_name("value")?;
+        matches!(value.kind(), "lambda_expression" | "method_reference")
+            .then(|| (node_to_range(&value), None))
+    }
+
+    fn find_constructor_delegation(
+        &self,
+        tree: &Tree,
+        source: &str,
+        position: &Position,
+    ) -> Option<ConstructorDelegationData> {
+        let keyword = get_node_at_position(tree, source, position)?;
+        let call = keyword.parent()?;
+        if call.kind() != "explicit_constructor_invocation"
+            || call.child_by_field_name("constructor") != Some(keyword)
+        {
+            return None;
+        }
+        let arg_count = call.child_by_field_name("arguments").map_or(0, |args| {
+            let mut cursor = args.walk();
+            args.named_children(&mut cursor)
+                .filter(|a| !a.is_extra())
+                .count()
+        });
+        Some(ConstructorDelegationData {
+            is_super: keyword.kind() == "super",
+            arg_count,
+        })
+    }
+
+    fn get_constructors(
+        &self,
+        tree: &Tree,
+        source: &str,
+        class_name: &str,
+    ) -> Vec<ConstructorData> {
+        let bytes = source.as_bytes();
+        let mut constructors = Vec::new();
+        collect_kind(tree.root_node(), "constructor_declaration", &mut |node| {
+            if find_declaring_type(node, bytes).as_deref() != Some(class_name) {
+                return;
+            }
+            let Some(name) = node.child_by_field_name("name") else {
+                return;
+            };
+            let Some(params) = node.child_by_field_name("parameters") else {
+                return;
+            };
+            let mut cursor = params.walk();
+            let param_count = params
+                .named_children(&mut cursor)
+                .filter(|p| p.kind().ends_with("parameter"))
+                .count();
+            constructors.push(ConstructorData {
+                parameters: params.utf8_text(bytes).unwrap_or_default().to_string(),
+                param_count,
+                range: node_to_range(&name),
+            });
+        });
+        constructors
+    }
+
+    fn get_enum_constants(
+        &self,
+        tree: &Tree,
+        source: &str,
+        enum_name: &str,
+    ) -> Vec<EnumConstantData> {
+        let bytes = source.as_bytes();
+        let mut constants = Vec::new();
+        collect_kind(tree.root_node(), "enum_constant", &mut |node| {
+            if find_declaring_type(node, bytes).as_deref() != Some(enum_name) {
+                return;
+            }
+            let Some(name) = node.child_by_field_name("name") else {
+                return;
+            };
+            let arguments = node
+                .child_by_field_name("arguments")
+                .map(|args| argument_texts(args, bytes))
+                .unwrap_or_default();
+            constants.push(EnumConstantData {
+                name: name.utf8_text(bytes).unwrap_or_default().to_string(),
+                arguments,
+                range: node_to_range(&name),
+            });
+        });
+        constants
+    }
+
     fn reserved_keywords(&self) -> &'static HashSet<&'static str> {
         &JAVA_KEYWORDS
     }
@@ -1563,6 +1740,21 @@ impl LanguageSupport for JavaSupport {
             JAVA_SCOPE_NODE_KINDS,
         )
     }
+
+    fn find_all_local_references(
+        &self,
+        tree: &Tree,
+        content: &str,
+        decl_positions: &[Position],
+    ) -> Vec<Vec<Range>> {
+        lsp_core::local_refs::find_all_local_references(
+            tree,
+            content,
+            decl_positions,
+            JAVA_DECL_NODE_KINDS,
+            JAVA_SCOPE_NODE_KINDS,
+        )
+    }
 }
 
 static JAVA_KEYWORDS: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
@@ -1622,6 +1814,169 @@ fn find_containing_class(mut node: Node, bytes: &[u8]) -> Option<String> {
     None
 }
 
+/// Returns the name of the type whose body directly declares `member`.  Members of
+/// anonymous class bodies have no declaring type name and yield `None`.
+fn find_declaring_type(member: Node, bytes: &[u8]) -> Option<String> {
+    let mut body = member.parent()?;
+    if body.kind() == "enum_body_declarations" {
+        body = body.parent()?;
+    }
+    if !matches!(body.kind(), "class_body" | "interface_body" | "enum_body") {
+        return None;
+    }
+    let decl = body.parent()?;
+    decl.child_by_field_name("name")?
+        .utf8_text(bytes)
+        .ok()
+        .map(|s| s.to_string())
+}
+
+/// The value of a constant initializer made of literals, string concatenation and
+/// parentheses.
+fn constant_node_value(node: &Node, bytes: &[u8]) -> Option<ConstantValue> {
+    let text = node.utf8_text(bytes).ok()?;
+    match node.kind() {
+        "string_literal" | "text_block" => ConstantValue::from_string_literal(text),
+        "decimal_integer_literal"
+        | "hex_integer_literal"
+        | "octal_integer_literal"
+        | "binary_integer_literal"
+        | "decimal_floating_point_literal"
+        | "hex_floating_point_literal"
+        | "character_literal"
+        | "true"
+        | "false" => Some(ConstantValue::Literal(text.to_string())),
+        "parenthesized_expression" => constant_node_value(&node.named_child(0)?, bytes),
+        "unary_expression" if text.starts_with('-') => {
+            let operand = node.child_by_field_name("operand")?;
+            match constant_node_value(&operand, bytes)? {
+                ConstantValue::Literal(literal) => {
+                    Some(ConstantValue::Literal(format!("-{literal}")))
+                }
+                ConstantValue::String(_) => None,
+            }
+        }
+        "binary_expression"
+            if node
+                .child_by_field_name("operator")
+                .is_some_and(|op| op.kind() == "+") =>
+        {
+            let left = constant_node_value(&node.child_by_field_name("left")?, bytes)?;
+            let right = constant_node_value(&node.child_by_field_name("right")?, bytes)?;
+            left.concat(right)
+        }
+        _ => None,
+    }
+}
+
+/// Calls `f` on every node of `kind` under `node`, in source order.
+fn collect_kind<'a>(node: Node<'a>, kind: &str, f: &mut impl FnMut(Node<'a>)) {
+    if node.kind() == kind {
+        f(node);
+    }
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_kind(child, kind, f);
+    }
+}
+
+/// The text of each argument in an argument list.
+fn argument_texts(arguments: Node, bytes: &[u8]) -> Vec<String> {
+    let mut cursor = arguments.walk();
+    arguments
+        .named_children(&mut cursor)
+        .filter(|a| !a.is_extra())
+        .filter_map(|a| a.utf8_text(bytes).ok().map(str::to_string))
+        .collect()
+}
+
+/// The type of the literal `node`, or `None` when it is not one.
+fn literal_node_type(node: &Node, content: &str) -> Option<String> {
+    let text = || node.utf8_text(content.as_bytes()).unwrap_or_default();
+    let literal_type = match node.kind() {
+        "map_literal" => "Map",
+        "array_literal" => "List",
+        "array_creation_expression" => return array_creation_type(node, content),
+        "array_initializer" => return array_initializer_type(node, content),
+
+        "decimal_integer_literal"
+        | "hex_integer_literal"
+        | "octal_integer_literal"
+        | "binary_integer_literal" => {
+            if text().ends_with(['l', 'L']) {
+                "Long"
+            } else {
+                "Integer"
+            }
+        }
+
+        "decimal_floating_point_literal" | "hex_floating_point_literal" => {
+            if text().ends_with(['f', 'F']) {
+                "Float"
+            } else {
+                "Double"
+            }
+        }
+
+        "true" | "false" => "Boolean",
+        "string_literal" | "text_block" => "String",
+        "regex_literal" => "Pattern",
+        _ => return None,
+    };
+    Some(literal_type.to_string())
+}
+
+/// `T[]` for `new T[n]` or `new T[] {...}`, with one `[]` per dimension.
+fn array_creation_type(node: &Node, content: &str) -> Option<String> {
+    let bytes = content.as_bytes();
+    let element = node.child_by_field_name("type")?.utf8_text(bytes).ok()?;
+    let mut cursor = node.walk();
+    let rank: usize = node
+        .named_children(&mut cursor)
+        .map(|child| match child.kind() {
+            "dimensions_expr" => 1,
+            "dimensions" => child.utf8_text(bytes).map_or(0, |t| t.matches('[').count()),
+            _ => 0,
+        })
+        .sum();
+    Some(format!("{element}{}", "[]".repeat(rank.max(1))))
+}
+
+/// The array type an initializer `{...}` builds: that of the `new T[]` or the
+/// declaration it belongs to, or else its elements' common type.
+fn array_initializer_type(node: &Node, content: &str) -> Option<String> {
+    let bytes = content.as_bytes();
+    let parent = node.parent()?;
+    match parent.kind() {
+        "array_creation_expression" => array_creation_type(&parent, content),
+        "variable_declarator" => {
+            let declaration = parent.parent()?;
+            let declared = declaration
+                .child_by_field_name("type")?
+                .utf8_text(bytes)
+                .ok()?;
+            let dimensions = parent
+                .child_by_field_name("dimensions")
+                .and_then(|d| d.utf8_text(bytes).ok())
+                .unwrap_or_default();
+            Some(format!("{declared}{dimensions}"))
+        }
+        "array_initializer" => array_initializer_type(&parent, content)?
+            .strip_suffix("[]")
+            .map(str::to_string),
+        _ => {
+            let mut cursor = node.walk();
+            let element_types: Vec<Option<String>> = node
+                .named_children(&mut cursor)
+                .filter(|c| !c.is_extra())
+                .map(|c| literal_node_type(&c, content))
+                .collect();
+            let element = common_element_type(&element_types, "Object")?;
+            Some(format!("{element}[]"))
+        }
+    }
+}
+
 fn is_numeric_primitive(t: &str) -> bool {
     matches!(t, "byte" | "short" | "int" | "long" | "float" | "double")
 }
@@ -1924,20 +2279,28 @@ fn check_java_return_literal(
 
 #[allow(dead_code)]
 mod tests {
+    use lsp_core::language_support::IdentResult;
     use tower_lsp::lsp_types::Position;
     use tree_sitter::Node;
 
     mod collect_diagnostics;
     mod extract_call_arguments;
+    mod find_constructor_delegation;
     mod find_declarations_in_scope;
     mod find_ident_at_position;
     mod find_variable_type;
+    mod get_enum_constants;
     mod get_imports;
     mod get_indexer_data;
     mod get_literal_type;
     mod get_method_receiver_and_params;
     mod get_type_at_position;
 
+    /// An ident result with its qualifier chain in `#`-joined form.
+    fn joined((ident, qualifier): IdentResult) -> (String, Option<String>) {
+        (ident, qualifier.map(|chain| chain.to_string()))
+    }
+
     fn find_position(content: &str, marker: &str) -> Position {
         content
             .lines()
@@ -0,0 +1,54 @@
+#![allow(unused_imports)]
+
+use crate::JavaSupport;
+use lsp_core::language_support::LanguageSupport;
+
+use tower_lsp::lsp_types::{Position, Range};
+
+use super::*;
+
+const CONTENT: &str = r#"
+        enum Priority {
+            LOW(1, "low"),
+            HIGH(10, "high"),
+            NONE;
+
+            Priority(int weight, String label) {}
+            Priority() {}
+        }
+
+        class Other {}
+    "#;
+
+#[test]
+fn test_get_enum_constants() {
+    let support = JavaSupport::new();
+    let parsed = support.parse_str(CONTENT).expect("cannot parse content");
+
+    let constants = support.get_enum_constants(&parsed.0, &parsed.1, "Priority");
+    let summary: Vec<(String, Vec<String>)> = constants
+        .iter()
+        .map(|c| (c.name.clone(), c.arguments.clone()))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (
+                "LOW".to_string(),
+                vec!["1".to_string(), "\"low\"".to_string()]
+            ),
+            (
+                "HIGH".to_string(),
+                vec!["10".to_string(), "\"high\"".to_string()]
+            ),
+            ("NONE".to_string(), vec![]),
+        ]
+    );
+    assert_eq!(constants[1].range.start, find_position(CONTENT, "HIGH"));
+
+    assert!(
+        support
+            .get_enum_constants(&parsed.0, &parsed.1, "Other")
+            .is_empty()
+    );
+}
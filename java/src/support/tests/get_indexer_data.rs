@@ -280,3 +280,54 @@ fn test_get_return() {
     let ret = support.get_return(&node, &parsed.1);
     assert_eq!(ret, Some("String".to_string()));
 }
+
+#[test]
+fn test_annotation_element() {
+    let support = JavaSupport::new();
+
+    let content = "@interface Audited { Class<?>[] value() default {}; Level level(); }";
+    let parsed = support.parse_str(&content).expect("cannot parse content");
+    let node =
+        find_node_by_kind(parsed.0.root_node(), "annotation_type_element_declaration").unwrap();
+    assert_eq!(support.get_kind(&node), Some(NodeKind::Function));
+    assert_eq!(
+        support.get_short_name(&node, &parsed.1),
+        Some("value".to_string())
+    );
+    assert_eq!(
+        support.get_return(&node, &parsed.1),
+        Some("Class<?>[]".to_string())
+    );
+}
+
+#[test]
+fn test_get_constant_value() {
+    let support = JavaSupport::new();
+    let test_cases = vec![
+        (
+            r#"class Foo { static final String NAME = "v" + 2 + ".0"; }"#,
+            Some(r#""v2.0""#),
+        ),
+        (
+            "class Foo { public static final long MAX = -42L; }",
+            Some("-42L"),
+        ),
+        ("class Foo { static final int SIZE = (10); }", Some("10")),
+        (
+            "class Foo { static final String NAME = PREFIX + \"x\"; }",
+            None,
+        ),
+        ("class Foo { final int size = 10; }", None),
+    ];
+    for (content, expected) in test_cases {
+        let parsed = support.parse_str(content).expect("cannot parse content");
+        let node = find_node_by_kind(parsed.0.root_node(), "field_declaration").unwrap();
+        let value = support.get_constant_value(&node, &parsed.1);
+        assert_eq!(
+            value.map(|v| v.to_string()),
+            expected.map(str::to_string),
+            "Failed for: {}",
+            content
+        );
+    }
+}
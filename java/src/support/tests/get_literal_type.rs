@@ -46,3 +46,33 @@ fn test_detect_literal_type() {
         assert_eq!(literal_type, expected, "Failed for literal: {}", literal);
     }
 }
+
+#[test]
+fn test_array_literal_types() {
+    let support = JavaSupport::new();
+    let content = r#"
+        class TestClass {
+            void testArrays() {
+                int[] numbers = new int[]{1, 2};
+                String[][] grid = new String[2][3];
+                String[] names = {"a", "b"};
+            }
+        }
+    "#;
+    let parsed = support.parse_str(&content).expect("cannot parse content");
+    let test_cases = vec![
+        ("new int", "int[]"),
+        ("new String", "String[][]"),
+        ("{\"a\"", "String[]"),
+    ];
+    for (literal, expected) in test_cases {
+        let pos = find_position(content, literal);
+        let literal_type = support.get_literal_type(&parsed.0, &parsed.1, &pos);
+        assert_eq!(
+            literal_type,
+            Some(expected.to_string()),
+            "Failed for literal: {}",
+            literal
+        );
+    }
+}
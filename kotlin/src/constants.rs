@@ -10,3 +10,13 @@ pub const KOTLIN_IMPLICIT_IMPORTS: [&str; 10] = [
     "java.lang.*",
     "kotlin.jvm.*",
 ];
+
+/// Kotest DSL functions that declare a test or container from a string name, across the
+/// spec styles (FunSpec, ShouldSpec, DescribeSpec, BehaviorSpec, FeatureSpec, ExpectSpec)
+/// and their `x`-prefixed disabled variants.
+pub const KOTEST_TEST_FUNCTIONS: &[&str] = &[
+    "test", "context", "describe", "it", "should", "given", "when", "then", "and", "feature",
+    "scenario", "expect", "Given", "When", "Then", "And", "xtest", "xcontext", "xdescribe",
+    "xit", "xshould", "xgiven", "xwhen", "xthen", "xfeature", "xscenario", "xexpect", "xGiven",
+    "xWhen", "xThen",
+];
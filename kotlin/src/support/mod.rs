@@ -1,8 +1,11 @@
 use lsp_core::{
-    language_support::{CallArgData, ClassDeclarationData, GenericTypeUsage, IdentResult, LanguageSupport, MemberAccessData, MethodCallSiteData, MethodSig, OverrideMethodData, ParameterResult, ParseResult},
+    constant_value::ConstantValue,
+    language_support::{CallArgData, ClassDeclarationData, ConstructorData, ConstructorDelegationData, EnumConstantData, GenericTypeUsage, IdentResult, LanguageSupport, LocalVariableData, MemberAccessData, MethodCallSiteData, MethodSig, OverrideMethodData, ParameterResult, ParseResult, PlatformDereferenceData, StringNamedTestData},
     languages::Language,
     node_kind::NodeKind,
+    qualifier::QualifierChain,
     ts_helper::{self, collect_syntax_errors, get_node_at_position, node_contains_position},
+    util::{common_element_type, parameterized_type},
 };
 use std::{cell::RefCell, collections::HashSet, fs, path::Path, sync::LazyLock};
 
@@ -10,7 +13,7 @@ use tower_lsp::lsp_types::{Position, Range};
 use tree_sitter::{Node, Parser, Point, Query, QueryCursor, QueryMatch, StreamingIterator, Tree};
 
 use crate::{
-    constants::KOTLIN_IMPLICIT_IMPORTS,
+    constants::{KOTEST_TEST_FUNCTIONS, KOTLIN_IMPLICIT_IMPORTS},
     support::queries::{
         DECLARED_TYPES_QUERY, DECLARES_VARIABLE_QUERY,
         FUNCTION_WITH_RETURN_QUERY, GET_ANNOTATIONS_QUERY, GET_EXTENDS_QUERY,
@@ -62,19 +65,7 @@ impl KotlinSupport {
                     .captures
                     .iter()
                     .find(|c| Some(c.index) == qual_idx)
-                    .and_then(|cap| cap.node.utf8_text(content.as_bytes()).ok())
-                    .map(|s| {
-                        let mut result = s.to_string();
-                        let regex = regex::Regex::new(r"\([^()]*\)").unwrap();
-                        loop {
-                            let new_result = regex.replace_all(&result, "").to_string();
-                            if new_result == result {
-                                break;
-                            }
-                            result = new_result;
-                        }
-                        result.replace(".", "#").replace("new ", "")
-                    })
+                    .map(|cap| QualifierChain::from_node(&cap.node, content))
             } else {
                 None
             };
@@ -145,6 +136,7 @@ impl KotlinSupport {
                     ("superclass", None),
                     ("return_name", None),
                     ("annotation", None),
+                    ("class_literal", None),
                 ]
                 .into_iter()
                 .for_each(|(name, qual)| {
@@ -540,7 +532,9 @@ impl KotlinSupport {
 
     fn infer_type_from_value(&self, value_node: Node, content: &str) -> Option<String> {
         match value_node.kind() {
-            "call_expression" => Self::extract_invocation_chain(&value_node, content),
+            "call_expression" => collection_factory_type(&value_node, content)
+                .or_else(|| Self::extract_invocation_chain(&value_node, content)),
+            "collection_literal" => literal_node_type(&value_node, content),
             "string_literal" => Some("String".to_string()),
             "decimal_integer_literal" => Some("Int".to_string()),
             "long_literal" => Some("Long".to_string()),
@@ -838,6 +832,12 @@ fn collect_missing_returns(
         });
 }
 
+/// The name of a `typealias` declaration: its first identifier child.
+fn type_alias_name<'a>(node: &Node<'a>) -> Option<Node<'a>> {
+    node.children(&mut node.walk())
+        .find(|n| matches!(n.kind(), "type_identifier" | "identifier" | "simple_identifier"))
+}
+
 fn node_to_range(node: &tree_sitter::Node) -> Range {
     Range {
         start: tower_lsp::lsp_types::Position {
@@ -1151,6 +1151,7 @@ impl LanguageSupport for KotlinSupport {
                     .find(|n| n.kind() == "variable_declarator")?;
                 declarator.child_by_field_name("name")?
             }
+            "type_alias" => type_alias_name(node)?,
             _ => node
                 .children(&mut node.walk())
                 .find(|n| n.kind() == "identifier")?,
@@ -1188,6 +1189,7 @@ impl LanguageSupport for KotlinSupport {
             "interface_declaration" => Some(NodeKind::Interface),
             "function_declaration" => Some(NodeKind::Function),
             "property_declaration" => Some(NodeKind::Field),
+            "type_alias" => Some(NodeKind::TypeAlias),
             "class_parameter" => {
                 let mut cursor = node.walk();
                 for child in node.children(&mut cursor) {
@@ -1206,6 +1208,10 @@ impl LanguageSupport for KotlinSupport {
 
         match node_kind {
             Some(NodeKind::Field) => ts_helper::get_one(node, source, &GET_FIELD_SHORT_NAME_QUERY),
+            Some(NodeKind::TypeAlias) => type_alias_name(node)?
+                .utf8_text(source.as_bytes())
+                .ok()
+                .map(str::to_string),
             Some(_) => ts_helper::get_one(node, source, &GET_SHORT_NAME_QUERY),
             None => None,
         }
@@ -1262,10 +1268,25 @@ impl LanguageSupport for KotlinSupport {
             Some(NodeKind::Function) => {
                 ts_helper::get_one(node, source, &GET_FUNCTION_RETURN_QUERY)
             }
+            // The aliased type: everything after `=`.
+            Some(NodeKind::TypeAlias) => node
+                .utf8_text(source.as_bytes())
+                .ok()?
+                .split_once('=')
+                .map(|(_, aliased)| aliased.trim().to_string()),
             _ => None,
         }
     }
 
+    fn get_constant_value(&self, node: &Node, source: &str) -> Option<ConstantValue> {
+        if node.kind() != "property_declaration"
+            || !self.get_modifiers(node, source).iter().any(|m| m == "const")
+        {
+            return None;
+        }
+        constant_node_value(&node.child_by_field_name("value")?, source.as_bytes())
+    }
+
     fn get_imports(&self, tree: &Tree, source: &str) -> Vec<String> {
         let explicit_imports =
             ts_helper::get_many(&tree.root_node(), source, &GET_IMPORTS_QUERY, None)
@@ -1380,78 +1401,25 @@ impl LanguageSupport for KotlinSupport {
 
     fn get_literal_type(&self, tree: &Tree, content: &str, position: &Position) -> Option<String> {
         let point = Point::new(position.line as usize, position.character as usize);
-        let mut node = tree.root_node().descendant_for_point_range(point, point)?;
+        let start = tree.root_node().descendant_for_point_range(point, point)?;
+        let mut node = start;
 
         loop {
-            match node.kind() {
-                "collection_literal" => return Some("List".to_string()),
-
-                "decimal_integer_literal" | "hex_literal" | "bin_literal" => {
-                    if node.utf8_text(content.as_bytes()).is_ok() {
-                        if let Some(parent) = node.parent() {
-                            match parent.kind() {
-                                "long_literal" => {
-                                    return Some("Long".to_string());
-                                }
-                                "unsigned_literal" => {
-                                    if let Ok(parent_text) = parent.utf8_text(content.as_bytes()) {
-                                        let parent_lower = parent_text.trim().to_lowercase();
-                                        if parent_lower.ends_with("ul") {
-                                            return Some("ULong".to_string());
-                                        }
-                                        return Some("UInt".to_string());
-                                    }
-                                    return Some("UInt".to_string());
-                                }
-                                _ => {
-                                    return Some("Int".to_string());
-                                }
-                            }
-                        }
-
-                        return Some("Int".to_string());
-                    }
-                    return Some("Int".to_string());
-                }
-
-                "long_literal" => {
-                    return Some("Long".to_string());
-                }
-
-                "unsigned_literal" => {
-                    if let Ok(text) = node.utf8_text(content.as_bytes()) {
-                        let lower = text.to_lowercase();
-                        if lower.ends_with("ul") {
-                            return Some("ULong".to_string());
-                        }
-                        return Some("UInt".to_string());
-                    }
-                    return Some("UInt".to_string());
-                }
-
-                "real_literal" => {
-                    if let Ok(text) = node.utf8_text(content.as_bytes()) {
-                        let lower = text.to_lowercase();
-                        if lower.ends_with('f') || lower.ends_with('F') {
-                            return Some("Float".to_string());
-                        }
-                        // In Kotlin, real literals without suffix are Double
-                        return Some("Double".to_string());
-                    }
-                    return Some("Double".to_string());
-                }
-
-                "boolean_literal" => return Some("Boolean".to_string()),
-
-                "character_literal" => return Some("Char".to_string()),
-
-                "null_literal" => return None,
-
-                "string_literal" => return Some("String".to_string()),
-
-                _ => {}
+            if node.kind() == "null_literal" {
+                return None;
+            }
+            if let Some(literal_type) = literal_node_type(&node, content) {
+                return Some(literal_type);
+            }
+            // `listOf(...)` only from its callee: an argument is not the list.
+            if node.kind() == "call_expression"
+                && node
+                    .child(0)
+                    .is_some_and(|callee| callee.byte_range().contains(&start.start_byte()))
+                && let Some(collection_type) = collection_factory_type(&node, content)
+            {
+                return Some(collection_type);
             }
-
             node = node.parent()?;
         }
     }
@@ -1638,6 +1606,15 @@ impl LanguageSupport for KotlinSupport {
                 }
             });
 
+        // Type aliases are top-level only.
+        let root = tree.root_node();
+        names.extend(
+            root.children(&mut root.walk())
+                .filter(|n| n.kind() == "type_alias")
+                .filter_map(|n| type_alias_name(&n)?.utf8_text(bytes).ok())
+                .map(str::to_string),
+        );
+
         names
     }
 
@@ -1806,6 +1783,40 @@ impl LanguageSupport for KotlinSupport {
         results
     }
 
+    fn get_local_variable_declarations(&self, tree: &Tree, source: &str) -> Vec<LocalVariableData> {
+        let bytes = source.as_bytes();
+        let mut results = Vec::new();
+        let mut stack = vec![tree.root_node()];
+
+        while let Some(node) = stack.pop() {
+            if node.kind() == "property_declaration"
+                && node.parent().is_some_and(|p| p.kind() == "statements")
+            {
+                let mut cursor = node.walk();
+                let declarators: Vec<Node> = node
+                    .children(&mut cursor)
+                    .filter(|c| c.kind() == "variable_declaration")
+                    .collect();
+                let statement_range = (declarators.len() == 1).then(|| node_to_range(&node));
+                for declarator in declarators {
+                    let Some(name_node) = declarator.child_by_field_name("name") else { continue };
+                    let Ok(name) = name_node.utf8_text(bytes) else { continue };
+                    results.push(LocalVariableData {
+                        name: name.to_string(),
+                        ident_range: node_to_range(&name_node),
+                        statement_range,
+                    });
+                }
+            }
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                stack.push(child);
+            }
+        }
+
+        results
+    }
+
     fn get_method_call_sites(&self, tree: &Tree, source: &str) -> Vec<MethodCallSiteData> {
         let bytes = source.as_bytes();
         let mut cursor = QueryCursor::new();
@@ -1863,6 +1874,258 @@ impl LanguageSupport for KotlinSupport {
         results
     }
 
+    fn get_platform_dereferences(&self, tree: &Tree, source: &str) -> Vec<PlatformDereferenceData> {
+        let bytes = source.as_bytes();
+        let mut results = Vec::new();
+        let mut stack = vec![tree.root_node()];
+
+        while let Some(node) = stack.pop() {
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                stack.push(child);
+            }
+            if node.kind() != "navigation_expression" {
+                continue;
+            }
+            let (Some(receiver), Some(suffix)) = (node.child(0), node.child(1)) else {
+                continue;
+            };
+            if !is_plain_member_access(suffix, bytes) {
+                continue;
+            }
+
+            // `a.b().c` — the call result is dereferenced directly.
+            // `x.c` — `x` is an untyped `val` initialised from `a.b()`.
+            let (call, range) = match receiver.kind() {
+                "call_expression" => (receiver, node_to_range(&receiver)),
+                "identifier" => {
+                    let Ok(name) = receiver.utf8_text(bytes) else { continue };
+                    let Some(decl) = find_preceding_local(receiver, name, bytes) else {
+                        continue;
+                    };
+                    let Some(value) = untyped_val_initializer(decl, bytes) else { continue };
+                    let between = &source[decl.end_byte()..receiver.start_byte()];
+                    if null_checked(between, name) {
+                        continue;
+                    }
+                    (value, node_to_range(&receiver))
+                }
+                _ => continue,
+            };
+            let Some((call_receiver, method)) = qualified_call_parts(call, bytes) else {
+                continue;
+            };
+            let (Ok(receiver_name), Ok(method_name)) =
+                (call_receiver.utf8_text(bytes), method.utf8_text(bytes))
+            else {
+                continue;
+            };
+            results.push(PlatformDereferenceData {
+                receiver_name: receiver_name.to_string(),
+                receiver_range: node_to_range(&call_receiver),
+                method_name: method_name.to_string(),
+                range,
+            });
+        }
+
+        results
+    }
+
+    fn get_string_named_tests(&self, tree: &Tree, source: &str) -> Vec<StringNamedTestData> {
+        let bytes = source.as_bytes();
+        let mut results = Vec::new();
+        let mut stack = vec![tree.root_node()];
+
+        while let Some(node) = stack.pop() {
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                stack.push(child);
+            }
+            let Some((name, name_node)) = string_named_test(node, bytes) else {
+                continue;
+            };
+            let Some(containing_class) = find_containing_class(node, bytes) else {
+                continue;
+            };
+            let mut parents = Vec::new();
+            let mut current = node.parent();
+            while let Some(ancestor) = current {
+                if ancestor.kind() == "class_declaration" {
+                    break;
+                }
+                if let Some((parent_name, _)) = string_named_test(ancestor, bytes) {
+                    parents.push(parent_name);
+                }
+                current = ancestor.parent();
+            }
+            parents.reverse();
+            results.push(StringNamedTestData {
+                containing_class,
+                parents,
+                name,
+                range: node_to_range(&name_node),
+            });
+        }
+
+        results
+    }
+
+    fn find_lambda_initializer(
+        &self,
+        tree: &Tree,
+        source: &str,
+        decl_position: &Position,
+    ) -> Option<(Range, Option<String>)> {
+        let bytes = source.as_bytes();
+        let mut node = get_node_at_position(tree, source, decl_position)?;
+        while node.kind() != "property_declaration" {
+            node = node.parent()?;
+        }
+        // A lambda parameter's enclosing property is not its own declaration; skip it.
+        let mut cursor = node.walk();
+        let declared = node
+            .children(&mut cursor)
+            .find(|c| c.kind() == "variable_declaration")?;
+        if !node_contains_position(&declared, decl_position) {
+            return None;
+        }
+        let value = node.child_by_field_name("value")?;
+        match value.kind() {
+            "lambda_literal" | "callable_reference" => Some((node_to_range(&value), None)),
+            // SAM constructor: `Runnable { ... }`
+            "call_expression" => {
+                let callee = value.child(0).filter(|c| c.kind() == "identifier")?;
+                let suffix = value.child(1).filter(|c| c.kind() == "call_suffix")?;
+                if suffix.named_child_count() != 1 {
+                    return None;
+                }
+                let lambda = suffix
+                    .named_child(0)
+                    .filter(|c| c.kind() == "annotated_lambda")?;
+                let sam_type = callee.utf8_text(bytes).ok()?.to_string();
+                Some((node_to_range(&lambda), Some(sam_type)))
+            }
+            _ => None,
+        }
+    }
+
+    fn find_constructor_delegation(
+        &self,
+        tree: &Tree,
+        source: &str,
+        position: &Position,
+    ) -> Option<ConstructorDelegationData> {
+        let mut call = get_node_at_position(tree, source, position)?;
+        if call.kind() != "constructor_delegation_call" {
+            call = call.parent()?;
+        }
+        if call.kind() != "constructor_delegation_call" {
+            return None;
+        }
+        let keyword = call
+            .child(0)
+            .filter(|k| matches!(k.kind(), "this" | "super"))?;
+        if !node_contains_position(&keyword, position) {
+            return None;
+        }
+        let mut cursor = call.walk();
+        let arg_count = call
+            .named_children(&mut cursor)
+            .find(|c| c.kind() == "value_arguments")
+            .map_or(0, |args| {
+                let mut cursor = args.walk();
+                args.named_children(&mut cursor)
+                    .filter(|a| a.kind() == "value_argument")
+                    .count()
+            });
+        Some(ConstructorDelegationData {
+            is_super: keyword.kind() == "super",
+            arg_count,
+        })
+    }
+
+    fn get_constructors(
+        &self,
+        tree: &Tree,
+        source: &str,
+        class_name: &str,
+    ) -> Vec<ConstructorData> {
+        let bytes = source.as_bytes();
+        let mut constructors = Vec::new();
+        collect_kind(tree.root_node(), "class_declaration", &mut |class| {
+            let name = class
+                .child_by_field_name("name")
+                .and_then(|n| n.utf8_text(bytes).ok());
+            if name != Some(class_name) {
+                return;
+            }
+            let mut cursor = class.walk();
+            for child in class.named_children(&mut cursor) {
+                if child.kind() == "primary_constructor" {
+                    constructors.push(constructor_data(child, child, bytes));
+                }
+                if child.kind() == "class_body" {
+                    let mut body_cursor = child.walk();
+                    for member in child.named_children(&mut body_cursor) {
+                        if member.kind() != "secondary_constructor" {
+                            continue;
+                        }
+                        let keyword = member.child(0).filter(|k| k.kind() == "constructor");
+                        let anchor = keyword.unwrap_or(member);
+                        constructors.push(constructor_data(member, anchor, bytes));
+                    }
+                }
+            }
+        });
+        constructors
+    }
+
+    fn get_enum_constants(
+        &self,
+        tree: &Tree,
+        source: &str,
+        enum_name: &str,
+    ) -> Vec<EnumConstantData> {
+        let bytes = source.as_bytes();
+        let mut constants = Vec::new();
+        collect_kind(tree.root_node(), "class_declaration", &mut |class| {
+            let name = class
+                .child_by_field_name("name")
+                .and_then(|n| n.utf8_text(bytes).ok());
+            if name != Some(enum_name) {
+                return;
+            }
+            let Some(body) = class.child_by_field_name("body") else {
+                return;
+            };
+            let mut cursor = body.walk();
+            for entry in body.named_children(&mut cursor) {
+                if entry.kind() != "enum_entry" {
+                    continue;
+                }
+                let mut entry_cursor = entry.walk();
+                let children: Vec<Node> = entry.named_children(&mut entry_cursor).collect();
+                let Some(name) = children
+                    .iter()
+                    .find(|c| matches!(c.kind(), "identifier" | "simple_identifier"))
+                else {
+                    continue;
+                };
+                let arguments = children
+                    .iter()
+                    .find(|c| c.kind() == "value_arguments")
+                    .map(|args| argument_texts(*args, bytes))
+                    .unwrap_or_default();
+                constants.push(EnumConstantData {
+                    name: name.utf8_text(bytes).unwrap_or_default().to_string(),
+                    arguments,
+                    range: node_to_range(name),
+                });
+            }
+        });
+        constants
+    }
+
     fn reserved_keywords(&self) -> &'static HashSet<&'static str> {
         &KOTLIN_KEYWORDS
     }
@@ -1881,6 +2144,21 @@ impl LanguageSupport for KotlinSupport {
             KOTLIN_SCOPE_NODE_KINDS,
         )
     }
+
+    fn find_all_local_references(
+        &self,
+        tree: &Tree,
+        content: &str,
+        decl_positions: &[Position],
+    ) -> Vec<Vec<Range>> {
+        lsp_core::local_refs::find_all_local_references(
+            tree,
+            content,
+            decl_positions,
+            KOTLIN_DECL_NODE_KINDS,
+            KOTLIN_SCOPE_NODE_KINDS,
+        )
+    }
 }
 
 static KOTLIN_KEYWORDS: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
@@ -1944,6 +2222,342 @@ fn find_containing_class(mut node: Node, bytes: &[u8]) -> Option<String> {
     None
 }
 
+/// The value of a `const val` initializer made of literals, string concatenation
+/// and parentheses.
+fn constant_node_value(node: &Node, bytes: &[u8]) -> Option<ConstantValue> {
+    let text = node.utf8_text(bytes).ok()?;
+    match node.kind() {
+        // Templates are not constants.
+        "string_literal" | "multiline_string_literal" if !text.contains('$') => {
+            ConstantValue::from_string_literal(text)
+        }
+        "decimal_integer_literal"
+        | "hex_literal"
+        | "bin_literal"
+        | "long_literal"
+        | "unsigned_literal"
+        | "real_literal"
+        | "boolean_literal"
+        | "character_literal" => Some(ConstantValue::Literal(text.to_string())),
+        "parenthesized_expression" => constant_node_value(&node.named_child(0)?, bytes),
+        "prefix_expression" if text.starts_with('-') => {
+            match constant_node_value(&node.named_child(0)?, bytes)? {
+                ConstantValue::Literal(literal) => {
+                    Some(ConstantValue::Literal(format!("-{literal}")))
+                }
+                ConstantValue::String(_) => None,
+            }
+        }
+        "additive_expression" if node.child(1).is_some_and(|op| op.kind() == "+") => {
+            let left = constant_node_value(&node.named_child(0)?, bytes)?;
+            let right = constant_node_value(&node.named_child(1)?, bytes)?;
+            left.concat(right)
+        }
+        _ => None,
+    }
+}
+
+/// Calls `f` on every node of `kind` under `node`, in source order.
+fn collect_kind<'a>(node: Node<'a>, kind: &str, f: &mut impl FnMut(Node<'a>)) {
+    if node.kind() == kind {
+        f(node);
+    }
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_kind(child, kind, f);
+    }
+}
+
+/// The text of each argument in an argument list.
+fn argument_texts(arguments: Node, bytes: &[u8]) -> Vec<String> {
+    let mut cursor = arguments.walk();
+    arguments
+        .named_children(&mut cursor)
+        .filter(|a| !a.is_extra())
+        .filter_map(|a| a.utf8_text(bytes).ok().map(str::to_string))
+        .collect()
+}
+
+/// A primary or secondary constructor, located at `anchor`.  Primary constructors
+/// hold their parameters directly; secondary ones in a parameter list.
+fn constructor_data(constructor: Node, anchor: Node, bytes: &[u8]) -> ConstructorData {
+    let mut cursor = constructor.walk();
+    let list = constructor
+        .named_children(&mut cursor)
+        .find(|c| c.kind().ends_with("parameters"))
+        .unwrap_or(constructor);
+    let mut cursor = list.walk();
+    let param_count = list
+        .named_children(&mut cursor)
+        .filter(|c| matches!(c.kind(), "parameter" | "class_parameter"))
+        .count();
+    // Drop a primary constructor's annotations and `constructor` keyword.
+    let text = list.utf8_text(bytes).unwrap_or_default();
+    ConstructorData {
+        parameters: text.find('(').map_or(text, |i| &text[i..]).to_string(),
+        param_count,
+        range: node_to_range(&anchor),
+    }
+}
+
+/// The type of the literal `node`, or `None` when it is not one.
+fn literal_node_type(node: &Node, content: &str) -> Option<String> {
+    let unsigned = |node: &Node| {
+        let text = node.utf8_text(content.as_bytes()).unwrap_or_default();
+        if text.trim().to_lowercase().ends_with("ul") {
+            "ULong"
+        } else {
+            "UInt"
+        }
+    };
+    let literal_type = match node.kind() {
+        "collection_literal" => {
+            let mut cursor = node.walk();
+            let element_types: Vec<Option<String>> = node
+                .named_children(&mut cursor)
+                .filter(|c| !c.is_extra())
+                .map(|c| literal_node_type(&c, content))
+                .collect();
+            let element = common_element_type(&element_types, "Any");
+            return Some(parameterized_type("List", &[element]));
+        }
+
+        "decimal_integer_literal" | "hex_literal" | "bin_literal" => match node.parent() {
+            Some(parent) if parent.kind() == "long_literal" => "Long",
+            Some(parent) if parent.kind() == "unsigned_literal" => unsigned(&parent),
+            _ => "Int",
+        },
+        "long_literal" => "Long",
+        "unsigned_literal" => unsigned(node),
+
+        "real_literal" => {
+            let text = node.utf8_text(content.as_bytes()).unwrap_or_default();
+            if text.to_lowercase().ends_with('f') {
+                "Float"
+            } else {
+                // In Kotlin, real literals without suffix are Double
+                "Double"
+            }
+        }
+
+        "boolean_literal" => "Boolean",
+        "character_literal" => "Char",
+        "string_literal" => "String",
+        _ => return None,
+    };
+    Some(literal_type.to_string())
+}
+
+/// Kotlin's collection factory functions and the type each builds.
+const COLLECTION_FACTORIES: &[(&str, &str)] = &[
+    ("listOf", "List"),
+    ("mutableListOf", "MutableList"),
+    ("arrayListOf", "ArrayList"),
+    ("setOf", "Set"),
+    ("mutableSetOf", "MutableSet"),
+    ("hashSetOf", "HashSet"),
+    ("arrayOf", "Array"),
+    ("mapOf", "Map"),
+    ("mutableMapOf", "MutableMap"),
+    ("hashMapOf", "HashMap"),
+];
+
+/// The collection a factory call such as `listOf("a")` or `mapOf("a" to 1)` builds,
+/// typed by its explicit type arguments or else by what its arguments have in
+/// common.  `None` for any other call.
+fn collection_factory_type(call: &Node, content: &str) -> Option<String> {
+    let bytes = content.as_bytes();
+    let callee = call.child(0)?.utf8_text(bytes).ok()?;
+    let (_, collection) = COLLECTION_FACTORIES.iter().find(|(f, _)| *f == callee)?;
+    let suffix = call.child(1).filter(|c| c.kind() == "call_suffix")?;
+
+    let mut cursor = suffix.walk();
+    let children: Vec<Node> = suffix.named_children(&mut cursor).collect();
+    if let Some(type_arguments) = children.iter().find(|c| c.kind() == "type_arguments") {
+        let type_arguments = type_arguments.utf8_text(bytes).ok()?;
+        return Some(format!("{collection}{type_arguments}"));
+    }
+    let Some(arguments) = children.iter().find(|c| c.kind() == "value_arguments") else {
+        return Some(collection.to_string());
+    };
+    let mut cursor = arguments.walk();
+    let values: Vec<Node> = arguments
+        .named_children(&mut cursor)
+        .filter(|a| a.kind() == "value_argument")
+        .filter_map(|a| a.named_child(a.named_child_count().checked_sub(1)?))
+        .collect();
+
+    let is_map = collection.ends_with("Map");
+    let mut element_types = Vec::new();
+    let mut key_types = Vec::new();
+    for value in &values {
+        if !is_map {
+            element_types.push(literal_node_type(value, content));
+            continue;
+        }
+        // `key to value`
+        let mut cursor = value.walk();
+        let parts: Vec<Node> = value.named_children(&mut cursor).collect();
+        match parts.as_slice() {
+            [key, infix, mapped]
+                if value.kind() == "infix_expression"
+                    && infix.utf8_text(bytes).ok() == Some("to") =>
+            {
+                key_types.push(literal_node_type(key, content));
+                element_types.push(literal_node_type(mapped, content));
+            }
+            _ => key_types.push(None),
+        }
+    }
+
+    let element = common_element_type(&element_types, "Any");
+    let type_arguments = if is_map {
+        vec![common_element_type(&key_types, "Any"), element]
+    } else {
+        vec![element]
+    };
+    Some(parameterized_type(collection, &type_arguments))
+}
+
+/// Recognises the Kotest test forms and returns the test name with its string literal:
+/// `test("name") { }` (any of [`KOTEST_TEST_FUNCTIONS`]), `"name" { }`,
+/// `"name" should { }` and `"name" - { }`.
+fn string_named_test<'a>(node: Node<'a>, bytes: &[u8]) -> Option<(String, Node<'a>)> {
+    let literal = match node.kind() {
+        "call_expression" => {
+            let callee = node.child(0)?;
+            let suffix = node.child(1).filter(|n| n.kind() == "call_suffix")?;
+            let mut cursor = suffix.walk();
+            let children: Vec<Node> = suffix.children(&mut cursor).collect();
+            if !children.iter().any(|c| c.kind() == "annotated_lambda") {
+                return None;
+            }
+            match callee.kind() {
+                "string_literal" => callee,
+                "identifier" => {
+                    let function = callee.utf8_text(bytes).ok()?.trim_matches('`');
+                    if !KOTEST_TEST_FUNCTIONS.contains(&function) {
+                        return None;
+                    }
+                    let args = children.iter().find(|c| c.kind() == "value_arguments")?;
+                    let first = args.named_child(0)?.named_child(0)?;
+                    (first.kind() == "string_literal").then_some(first)?
+                }
+                _ => return None,
+            }
+        }
+        "infix_expression" | "additive_expression" => {
+            let (left, right) = (node.named_child(0)?, node.child(node.child_count() - 1)?);
+            if left.kind() != "string_literal" || right.kind() != "lambda_literal" {
+                return None;
+            }
+            left
+        }
+        _ => return None,
+    };
+    let text = literal.utf8_text(bytes).ok()?;
+    let name = text.strip_prefix('"')?.strip_suffix('"')?;
+    // Interpolated names are only known at runtime.
+    if name.contains('$') {
+        return None;
+    }
+    Some((name.to_string(), literal))
+}
+
+/// True when a `navigation_suffix` uses the plain `.` operator (not `?.` or `::`).
+fn is_plain_member_access(suffix: Node, bytes: &[u8]) -> bool {
+    suffix
+        .utf8_text(bytes)
+        .is_ok_and(|t| t.trim_start().starts_with('.'))
+}
+
+/// Splits `receiver.method(...)` into its receiver identifier and method name nodes.
+/// Returns `None` for unqualified calls, safe calls and non-identifier receivers.
+fn qualified_call_parts<'a>(call: Node<'a>, bytes: &[u8]) -> Option<(Node<'a>, Node<'a>)> {
+    if call.kind() != "call_expression" {
+        return None;
+    }
+    let nav = call.child(0).filter(|n| n.kind() == "navigation_expression")?;
+    let receiver = nav.child(0).filter(|n| n.kind() == "identifier")?;
+    let suffix = nav.child(1)?;
+    if !is_plain_member_access(suffix, bytes) {
+        return None;
+    }
+    let method = suffix.named_child(0).filter(|n| n.kind() == "identifier")?;
+    Some((receiver, method))
+}
+
+/// Finds the nearest `property_declaration` of `name` that precedes `use_node` in an
+/// enclosing `statements` block.
+fn find_preceding_local<'a>(use_node: Node<'a>, name: &str, bytes: &[u8]) -> Option<Node<'a>> {
+    let mut current = use_node.parent();
+    while let Some(scope) = current {
+        if scope.kind() == "statements" {
+            let mut cursor = scope.walk();
+            let decl = scope
+                .children(&mut cursor)
+                .filter(|stmt| {
+                    stmt.kind() == "property_declaration"
+                        && stmt.end_byte() <= use_node.start_byte()
+                })
+                .filter(|stmt| {
+                    let mut c = stmt.walk();
+                    stmt.children(&mut c)
+                        .filter(|n| n.kind() == "variable_declaration")
+                        .filter_map(|n| n.child_by_field_name("name"))
+                        .any(|n| n.utf8_text(bytes).is_ok_and(|t| t == name))
+                })
+                .last();
+            if decl.is_some() {
+                return decl;
+            }
+        }
+        current = scope.parent();
+    }
+    None
+}
+
+/// Returns the initializer of a `val` declared without a type annotation.  An explicit type
+/// makes the compiler null-check the value at the assignment, and a `var` may be reassigned.
+fn untyped_val_initializer<'a>(decl: Node<'a>, bytes: &[u8]) -> Option<Node<'a>> {
+    let mut cursor = decl.walk();
+    let mut is_val = false;
+    let mut typed = false;
+    for child in decl.children(&mut cursor) {
+        match child.kind() {
+            "variable_declaration" => typed = child.child_by_field_name("type").is_some(),
+            _ if child.utf8_text(bytes).is_ok_and(|t| t == "val") => is_val = true,
+            _ => {}
+        }
+    }
+    if !is_val || typed {
+        return None;
+    }
+    decl.child_by_field_name("value")
+}
+
+/// True when `text` contains a null check of `name`: a comparison against `null`, a safe
+/// call, an elvis, a `!!` assertion, an `is` check or `requireNotNull`/`checkNotNull`.
+fn null_checked(text: &str, name: &str) -> bool {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    text.match_indices(name).any(|(idx, _)| {
+        let end = idx + name.len();
+        if text[..idx].chars().next_back().is_some_and(is_ident)
+            || text[end..].chars().next().is_some_and(is_ident)
+        {
+            return false;
+        }
+        let before = text[..idx].trim_end();
+        let after = text[end..].trim_start();
+        ["!= null", "== null", "!=null", "==null", "?.", "?:", "!!", "is "]
+            .iter()
+            .any(|p| after.starts_with(p))
+            || ["null !=", "null ==", "requireNotNull(", "checkNotNull("]
+                .iter()
+                .any(|p| before.ends_with(p))
+    })
+}
+
 /// Walks the AST looking for `statements` nodes (Kotlin function/lambda bodies) and, within
 /// each, linearly tracks variables declared without an initializer (`val x: T` / `var x: T`),
 /// flagging any read that occurs before the first plain assignment to that variable.
@@ -2432,20 +3046,28 @@ fn check_kotlin_return_literal(
 
 #[allow(dead_code)]
 mod tests {
+    use lsp_core::language_support::IdentResult;
     use tower_lsp::lsp_types::Position;
     use tree_sitter::Node;
 
     mod collect_diagnostics;
     mod extract_call_arguments;
+    mod find_constructor_delegation;
     mod find_declarations_in_scope;
     mod find_ident_at_position;
     mod find_variable_type;
+    mod get_enum_constants;
     mod get_imports;
     mod get_indexer_data;
     mod get_literal_type;
     mod get_method_receiver_and_params;
     mod get_type_at_position;
 
+    /// An ident result with its qualifier chain in `#`-joined form.
+    fn joined((ident, qualifier): IdentResult) -> (String, Option<String>) {
+        (ident, qualifier.map(|chain| chain.to_string()))
+    }
+
     fn find_position(content: &str, marker: &str) -> Position {
         content
             .lines()
@@ -192,6 +192,7 @@ pub static IDENT_QUERY: LazyLock<Query> = LazyLock::new(|| {
               (annotation (constructor_invocation (user_type (type_identifier) @annotation)))
             ])
         ]
+        (callable_reference (user_type (type_identifier) @class_literal))
         "#,
     )
     .unwrap()
@@ -0,0 +1,77 @@
+#![allow(unused_imports)]
+
+use crate::KotlinSupport;
+use lsp_core::language_support::LanguageSupport;
+
+use tower_lsp::lsp_types::{Position, Range};
+
+use super::*;
+
+const CONTENT: &str = r#"
+        class Child(val name: String, val age: Int) : Base(name) {
+            constructor(name: String) : this(name, 0)
+
+            constructor() : this("anonymous")
+        }
+
+        class View : Base {
+            constructor(context: String) : super(context)
+        }
+    "#;
+
+#[test]
+fn test_this_delegation() {
+    let support = KotlinSupport::new();
+    let parsed = support.parse_str(CONTENT).expect("cannot parse content");
+    let pos = find_position(CONTENT, "this(name");
+
+    let delegation = support
+        .find_constructor_delegation(&parsed.0, &parsed.1, &pos)
+        .expect("no delegation");
+
+    assert!(!delegation.is_super);
+    assert_eq!(delegation.arg_count, 2);
+}
+
+#[test]
+fn test_super_delegation() {
+    let support = KotlinSupport::new();
+    let parsed = support.parse_str(CONTENT).expect("cannot parse content");
+    let pos = find_position(CONTENT, "super(context)");
+
+    let delegation = support
+        .find_constructor_delegation(&parsed.0, &parsed.1, &pos)
+        .expect("no delegation");
+
+    assert!(delegation.is_super);
+    assert_eq!(delegation.arg_count, 1);
+}
+
+#[test]
+fn test_get_constructors() {
+    let support = KotlinSupport::new();
+    let parsed = support.parse_str(CONTENT).expect("cannot parse content");
+
+    let constructors = support.get_constructors(&parsed.0, &parsed.1, "Child");
+
+    let found: Vec<(&str, usize, Position)> = constructors
+        .iter()
+        .map(|c| (c.parameters.as_str(), c.param_count, c.range.start))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            (
+                "(val name: String, val age: Int)",
+                2,
+                find_position(CONTENT, "(val name")
+            ),
+            (
+                "(name: String)",
+                1,
+                find_position(CONTENT, "constructor(name")
+            ),
+            ("()", 0, find_position(CONTENT, "constructor()")),
+        ]
+    );
+}
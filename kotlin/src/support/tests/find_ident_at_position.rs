@@ -1,7 +1,11 @@
 #![allow(unused_imports)]
 
 use crate::KotlinSupport;
-use lsp_core::{language_support::LanguageSupport, node_kind::NodeKind};
+use lsp_core::{
+    language_support::LanguageSupport,
+    node_kind::NodeKind,
+    qualifier::{Segment, SegmentKind},
+};
 
 use tower_lsp::lsp_types::{Position, Range};
 use tree_sitter::Node;
@@ -21,7 +25,7 @@ fn test_simple_identifier() {
         "#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "bar /");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("bar".to_string(), None)));
 }
 
@@ -38,7 +42,7 @@ fn test_method_invocation_with_qualifier() {
         "#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "baz");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("baz".to_string(), Some("bar".to_string()))));
 }
 
@@ -55,7 +59,7 @@ fn test_field_access_with_qualifier() {
         "#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "name");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("name".to_string(), Some("bar".to_string()))));
 }
 
@@ -71,7 +75,7 @@ fn test_identifier_in_argument_list() {
         }"#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "bar)");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("bar".to_string(), None)));
 }
 
@@ -86,7 +90,7 @@ fn test_identifier_in_variable_declarator() {
         }"#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "someOtherVar");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("someOtherVar".to_string(), None)));
 }
 
@@ -101,7 +105,7 @@ fn test_this_qualified_method_invocation() {
         }"#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "doSomething");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(
         ident,
         Some(("doSomething".to_string(), Some("this".to_string())))
@@ -119,13 +123,74 @@ fn test_chained_method_invocation() {
         }"#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "getName");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(
         ident,
         Some(("getName".to_string(), Some("user#getProfile".to_string())))
     );
 }
 
+#[test]
+fn test_qualifier_segment_kinds() {
+    let support = KotlinSupport::new();
+    let content = r#"
+        class Foo {
+            fun test() {
+                val n = Config.instance.get("x").name
+            }
+        }"#;
+    let parsed = support.parse_str(&content).expect("cannot parse content");
+    let pos = find_position(content, "name");
+    let (ident, qualifier) = support
+        .find_ident_at_position(&parsed.0, &parsed.1, &pos)
+        .expect("no ident at position");
+    assert_eq!(ident, "name");
+    assert_eq!(
+        qualifier.map(|chain| chain.segments),
+        Some(vec![
+            Segment::new("Config", SegmentKind::Variable),
+            Segment::new("instance", SegmentKind::Variable),
+            Segment::new("get", SegmentKind::Call),
+        ])
+    );
+}
+
+#[test]
+fn test_qualifier_skips_nested_call_arguments() {
+    let support = KotlinSupport::new();
+    let content = r#"
+        class Foo {
+            fun test() {
+                user.find(ids.get(0), ")").getName()
+            }
+        }"#;
+    let parsed = support.parse_str(&content).expect("cannot parse content");
+    let pos = find_position(content, "getName");
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
+    assert_eq!(
+        ident,
+        Some(("getName".to_string(), Some("user#find".to_string())))
+    );
+}
+
+#[test]
+fn test_qualifier_skips_trailing_lambda() {
+    let support = KotlinSupport::new();
+    let content = r#"
+        class Foo {
+            fun test() {
+                users.filter { it.active() }.first()
+            }
+        }"#;
+    let parsed = support.parse_str(&content).expect("cannot parse content");
+    let pos = find_position(content, "first");
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
+    assert_eq!(
+        ident,
+        Some(("first".to_string(), Some("users#filter".to_string())))
+    );
+}
+
 #[test]
 fn test_static_method_invocation() {
     let support = KotlinSupport::new();
@@ -137,7 +202,7 @@ fn test_static_method_invocation() {
         }"#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "createUser");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(
         ident,
         Some(("createUser".to_string(), Some("UserService".to_string())))
@@ -155,7 +220,7 @@ fn test_chained_field_access() {
         }"#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "name");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(
         ident,
         Some(("name".to_string(), Some("user#profile".to_string())))
@@ -173,7 +238,7 @@ fn test_method_parameter_identifier() {
         }"#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "user /");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("user".to_string(), None)));
 }
 
@@ -191,7 +256,7 @@ fn test_lambda_parameter_identifier() {
         }"#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "item)");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("item".to_string(), None)));
 }
 
@@ -206,7 +271,7 @@ fn test_constructor_type_in_new_expression() {
         }"#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "ArrayList");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("ArrayList".to_string(), None)));
 }
 
@@ -221,7 +286,7 @@ fn test_type_argument_in_generics() {
         }"#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "MyClass");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("MyClass".to_string(), None)));
 }
 
@@ -236,7 +301,7 @@ fn test_nested_type_arguments_in_generics() {
         }"#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "UserProfile");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("UserProfile".to_string(), None)));
 }
 
@@ -252,7 +317,7 @@ fn test_cast_expression_type() {
         }"#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "MyClass");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("MyClass".to_string(), None)));
 }
 
@@ -266,7 +331,7 @@ fn test_import_statement_type() {
         }"#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "Bar");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(
         ident,
         Some(("Bar".to_string(), Some("com.example".to_string())))
@@ -286,12 +351,12 @@ fn test_nested_qualifier_in_chain() {
 
     // deepest qualifier
     let pos = find_position(content, "user");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("user".to_string(), None)));
 
     // middle of chain
     let pos = find_position(content, "getProfile");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(
         ident,
         Some(("getProfile".to_string(), Some("user".to_string())))
@@ -299,7 +364,7 @@ fn test_nested_qualifier_in_chain() {
 
     // field access at end of chain
     let pos = find_position(content, "name");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(
         ident,
         Some(("name".to_string(), Some("user#getProfile".to_string())))
@@ -319,12 +384,12 @@ fn test_simple_constructor_in_chain() {
 
     // constructor type
     let pos = find_position(content, "MyClass");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("MyClass".to_string(), None)));
 
     // method on constructor
     let pos = find_position(content, "process");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(
         ident,
         Some(("process".to_string(), Some("MyClass".to_string())))
@@ -332,7 +397,7 @@ fn test_simple_constructor_in_chain() {
 
     // field at end of chain
     let pos = find_position(content, "message");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(
         ident,
         Some(("message".to_string(), Some("MyClass#process".to_string())))
@@ -352,12 +417,12 @@ fn test_scoped_constructor_in_chain() {
 
     // outer qualifier
     let pos = find_position(content, "Outer");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("Outer".to_string(), None)));
 
     // inner constructor type
     let pos = find_position(content, "Inner");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(
         ident,
         Some(("Inner".to_string(), Some("Outer".to_string())))
@@ -365,7 +430,7 @@ fn test_scoped_constructor_in_chain() {
 
     // method on constructor
     let pos = find_position(content, "process");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(
         ident,
         Some(("process".to_string(), Some("Outer#Inner".to_string())))
@@ -373,7 +438,7 @@ fn test_scoped_constructor_in_chain() {
 
     // field at end of chain
     let pos = find_position(content, "message");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(
         ident,
         Some((
@@ -393,7 +458,7 @@ fn test_class_declaration_name() {
     "#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "MyClass");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("MyClass".to_string(), None)));
 }
 
@@ -407,7 +472,7 @@ fn test_interface_declaration_name() {
     "#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "MyInterface");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("MyInterface".to_string(), None)));
 }
 
@@ -423,7 +488,7 @@ fn test_function_declaration_name() {
     "#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "myFunction");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("myFunction".to_string(), None)));
 }
 
@@ -437,7 +502,7 @@ fn test_property_declaration_name() {
     "#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "myProperty");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("myProperty".to_string(), None)));
 }
 
@@ -450,7 +515,7 @@ fn test_delegation_superclass() {
     "#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "BaseClass");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("BaseClass".to_string(), None)));
 }
 
@@ -463,7 +528,7 @@ fn test_delegation_interface() {
     "#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "MyInterface");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("MyInterface".to_string(), None)));
 }
 
@@ -477,15 +542,15 @@ fn test_delegation_multiple() {
     let parsed = support.parse_str(&content).expect("cannot parse content");
 
     let pos = find_position(content, "BaseClass");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("BaseClass".to_string(), None)));
 
     let pos = find_position(content, "Interface1");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("Interface1".to_string(), None)));
 
     let pos = find_position(content, "Interface2");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("Interface2".to_string(), None)));
 }
 
@@ -500,7 +565,7 @@ fn test_return_type_detection() {
         }"#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "String");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("String".to_string(), None)));
 }
 
@@ -516,7 +581,7 @@ fn test_annotation_on_class() {
         }"#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "Controller");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("Controller".to_string(), None)));
 }
 
@@ -532,7 +597,7 @@ fn test_annotation_on_method() {
         }"#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "GetMapping");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("GetMapping".to_string(), None)));
 }
 
@@ -546,7 +611,7 @@ fn test_annotation_on_field() {
         }"#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "Autowired");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("Autowired".to_string(), None)));
 }
 
@@ -562,7 +627,7 @@ fn test_annotation_with_parameters() {
         }"#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "RequestMapping");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("RequestMapping".to_string(), None)));
 }
 
@@ -577,7 +642,7 @@ fn test_annotation_on_parameter() {
         }"#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "PathVariable");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("PathVariable".to_string(), None)));
 }
 
@@ -589,7 +654,7 @@ fn test_multi_annotation_on_class() {
         class Foo {}"#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "Component");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("Component".to_string(), None)));
 }
 
@@ -601,6 +666,18 @@ fn test_multi_annotation_with_constructor_invocation() {
         class Foo {}"#;
     let parsed = support.parse_str(&content).expect("cannot parse content");
     let pos = find_position(content, "RequestMapping");
-    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos);
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
     assert_eq!(ident, Some(("RequestMapping".to_string(), None)));
 }
+
+#[test]
+fn test_class_literal_in_annotation() {
+    let support = KotlinSupport::new();
+    let content = r#"
+        @Import(Config::class)
+        class Foo {}"#;
+    let parsed = support.parse_str(&content).expect("cannot parse content");
+    let pos = find_position(content, "Config");
+    let ident = support.find_ident_at_position(&parsed.0, &parsed.1, &pos).map(joined);
+    assert_eq!(ident, Some(("Config".to_string(), None)));
+}
@@ -291,3 +291,59 @@ fn test_get_return() {
     let ret = support.get_return(&node, &parsed.1);
     assert_eq!(ret, Some("String".to_string()));
 }
+
+#[test]
+fn test_type_alias() {
+    let support = KotlinSupport::new();
+
+    let content = "package com.example.app\n\ntypealias Handler = (Request) -> Response";
+    let parsed = support.parse_str(&content).expect("cannot parse content");
+    let node = find_node_by_kind(parsed.0.root_node(), "type_alias").unwrap();
+    assert_eq!(support.get_kind(&node), Some(NodeKind::TypeAlias));
+    assert_eq!(
+        support.get_short_name(&node, &parsed.1),
+        Some("Handler".to_string())
+    );
+    assert_eq!(
+        support.get_return(&node, &parsed.1),
+        Some("(Request) -> Response".to_string())
+    );
+    assert_eq!(
+        support.get_ident_range(&node),
+        Some(Range {
+            start: Position {
+                line: 2u32,
+                character: 10u32,
+            },
+            end: Position {
+                line: 2u32,
+                character: 17u32,
+            },
+        })
+    );
+}
+
+#[test]
+fn test_get_constant_value() {
+    let support = KotlinSupport::new();
+    let test_cases = vec![
+        (
+            r#"object Foo { const val NAME = "v" + 2 + ".0" }"#,
+            Some(r#""v2.0""#),
+        ),
+        ("object Foo { const val MAX = 42L }", Some("42L")),
+        (r#"object Foo { const val NAME = "${PREFIX}x" }"#, None),
+        ("object Foo { val size = 10 }", None),
+    ];
+    for (content, expected) in test_cases {
+        let parsed = support.parse_str(content).expect("cannot parse content");
+        let node = find_node_by_kind(parsed.0.root_node(), "property_declaration").unwrap();
+        let value = support.get_constant_value(&node, &parsed.1);
+        assert_eq!(
+            value.map(|v| v.to_string()),
+            expected.map(str::to_string),
+            "Failed for: {}",
+            content
+        );
+    }
+}
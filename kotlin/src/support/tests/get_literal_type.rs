@@ -54,3 +54,39 @@ fn test_detect_literal_type() {
         assert_eq!(literal_type, expected, "Failed for literal: {}", literal);
     }
 }
+
+#[test]
+fn test_collection_factory_types() {
+    let support = KotlinSupport::new();
+    let content = r#"
+        class TestClass {
+            fun testCollections() {
+                val names = listOf("a", "b")
+                val numbers = setOf(1, 2L)
+                val counts = mapOf("a" to 1, "b" to 2)
+                val empty = mutableListOf<String>()
+                val ints = arrayOf(1, 2)
+                val unknown = listOf(foo)
+            }
+        }
+    "#;
+    let parsed = support.parse_str(&content).expect("cannot parse content");
+    let test_cases = vec![
+        ("listOf(\"a\"", "List<String>"),
+        ("setOf", "Set<Number>"),
+        ("mapOf", "Map<String, Int>"),
+        ("mutableListOf", "MutableList<String>"),
+        ("arrayOf", "Array<Int>"),
+        ("listOf(foo", "List"),
+    ];
+    for (literal, expected) in test_cases {
+        let pos = find_position(content, literal);
+        let literal_type = support.get_literal_type(&parsed.0, &parsed.1, &pos);
+        assert_eq!(
+            literal_type,
+            Some(expected.to_string()),
+            "Failed for literal: {}",
+            literal
+        );
+    }
+}
@@ -0,0 +1,361 @@
+//! Dependency discovery without a Gradle executable.
+//!
+//! When neither `./gradlew` nor `gradle` is available, the `group:name:version`
+//! coordinates written literally in the project's build files are looked up in
+//! the local Gradle cache (`~/.gradle/caches/modules-2/files-2.1`).  Only direct
+//! dependencies that were downloaded before are found: transitive dependencies,
+//! versions from variables or catalogs, and platform-managed versions are not.
+//!
+//! The cache also tells which Gradle plugins were resolved before, through the
+//! marker artifacts `plugins {}` requests go through.
+
+use std::{
+    cmp::Ordering,
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use walkdir::WalkDir;
+
+use crate::build_tools::script_lexer::{ScriptToken, script_tokens};
+
+/// Build files are searched no deeper than this below the project root.
+const BUILD_FILE_MAX_DEPTH: usize = 4;
+
+/// The `(bytecode jar, sources jar)` pairs of the cached dependencies declared in
+/// `root`'s build files.
+pub fn cached_dependency_paths(root: &Path) -> Vec<(Option<PathBuf>, Option<PathBuf>)> {
+    let Some(cache) = gradle_module_cache() else {
+        return vec![];
+    };
+    let mut coordinates: Vec<(String, String, String)> = build_files(root)
+        .iter()
+        .filter_map(|file| std::fs::read_to_string(file).ok())
+        .flat_map(|content| dependency_coordinates(&content))
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    coordinates.sort();
+
+    coordinates
+        .iter()
+        .filter_map(|(group, name, version)| {
+            let dir = cache.join(group).join(name).join(version);
+            let jar = find_artifact(&dir, &format!("{name}-{version}.jar"));
+            let sources = find_artifact(&dir, &format!("{name}-{version}-sources.jar"));
+            (jar.is_some() || sources.is_some()).then_some((jar, sources))
+        })
+        .collect()
+}
+
+/// The cache directory holding every cached version of the module `jar` was
+/// cached for, `None` when `jar` is not in the Gradle cache.
+pub fn cached_module_dir(jar: &Path) -> Option<PathBuf> {
+    module_dir_in(&gradle_module_cache()?, jar)
+}
+
+/// The versions of a module cached in `module_dir`.
+pub fn cached_versions(module_dir: &Path) -> HashSet<String> {
+    std::fs::read_dir(module_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter(|e| e.path().is_dir())
+                .filter_map(|e| e.file_name().to_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Orders versions as Gradle does for the common cases: `1.10` after `1.9`, a
+/// numeric part after a textual one, `1.0.1` after `1.0`, and a qualified
+/// `1.0-rc1` before `1.0`.  Textual parts compare case-insensitively.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let (a, b) = (version_parts(a), version_parts(b));
+    for (x, y) in a.iter().zip(&b) {
+        let ordering = match (x.parse::<u64>(), y.parse::<u64>()) {
+            (Ok(x), Ok(y)) => x.cmp(&y),
+            (Ok(_), Err(_)) => Ordering::Greater,
+            (Err(_), Ok(_)) => Ordering::Less,
+            (Err(_), Err(_)) => x.to_lowercase().cmp(&y.to_lowercase()),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    // The longer version is later when its extra part is numeric, earlier when
+    // it is a qualifier.
+    let extra = |parts: &[&str]| parts[a.len().min(b.len())].parse::<u64>().is_ok();
+    match a.len().cmp(&b.len()) {
+        Ordering::Greater if extra(&a) => Ordering::Greater,
+        Ordering::Greater => Ordering::Less,
+        Ordering::Less if extra(&b) => Ordering::Less,
+        Ordering::Less => Ordering::Greater,
+        Ordering::Equal => Ordering::Equal,
+    }
+}
+
+/// The parts of a version: split at `.`, `-`, `_` and `+`, and where digits meet
+/// letters.
+fn version_parts(version: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    for part in version
+        .split(['.', '-', '_', '+'])
+        .filter(|p| !p.is_empty())
+    {
+        let mut start = 0;
+        let bytes = part.as_bytes();
+        for i in 1..bytes.len() {
+            if bytes[i].is_ascii_digit() != bytes[i - 1].is_ascii_digit() {
+                parts.push(&part[start..i]);
+                start = i;
+            }
+        }
+        parts.push(&part[start..]);
+    }
+    parts
+}
+
+/// The `(group, name, version)` of `jar`, `None` when it is not in the Gradle cache.
+pub fn cached_coordinates(jar: &Path) -> Option<(String, String, String)> {
+    coordinates_in(&gradle_module_cache()?, jar)
+}
+
+/// The cached versions of the marker artifact of Gradle plugin `id`,
+/// `<id>:<id>.gradle.plugin`.  `None` when there is no Gradle cache to look in.
+pub fn cached_plugin_versions(id: &str) -> Option<HashSet<String>> {
+    let cache = gradle_module_cache()?;
+    Some(cached_versions(
+        &cache.join(id).join(format!("{id}.gradle.plugin")),
+    ))
+}
+
+/// The cached JAR implementing `version` of Gradle plugin `id`: the artifact its
+/// cached marker POM depends on.
+pub fn cached_plugin_jar(id: &str, version: &str) -> Option<PathBuf> {
+    let cache = gradle_module_cache()?;
+    let marker = format!("{id}.gradle.plugin");
+    let pom = find_artifact(
+        &cache.join(id).join(&marker).join(version),
+        &format!("{marker}-{version}.pom"),
+    )?;
+    let (group, name, version) = marker_dependency(&std::fs::read_to_string(pom).ok()?)?;
+    find_artifact(
+        &cache.join(&group).join(&name).join(&version),
+        &format!("{name}-{version}.jar"),
+    )
+}
+
+/// The `(group, name, version)` of the first dependency of a plugin marker POM.
+fn marker_dependency(pom: &str) -> Option<(String, String, String)> {
+    let dependency = pom.split("<dependency>").nth(1)?;
+    let dependency = &dependency[..dependency.find("</dependency>")?];
+    let element = |tag: &str| {
+        let start = dependency.find(&format!("<{tag}>"))? + tag.len() + 2;
+        let end = start + dependency[start..].find(&format!("</{tag}>"))?;
+        Some(dependency[start..end].trim().to_string())
+    };
+    Some((
+        element("groupId")?,
+        element("artifactId")?,
+        element("version")?,
+    ))
+}
+
+fn coordinates_in(cache: &Path, jar: &Path) -> Option<(String, String, String)> {
+    let version_dir = jar.parent()?.parent()?;
+    let module_dir = module_dir_in(cache, jar)?;
+    let name_of = |dir: &Path| Some(dir.file_name()?.to_str()?.to_string());
+    Some((
+        name_of(module_dir.parent()?)?,
+        name_of(&module_dir)?,
+        name_of(version_dir)?,
+    ))
+}
+
+/// `<cache>/<group>/<name>` for `<cache>/<group>/<name>/<version>/<hash>/<file>`.
+fn module_dir_in(cache: &Path, jar: &Path) -> Option<PathBuf> {
+    let module_dir = jar.ancestors().nth(3)?;
+    (module_dir.parent()?.parent()? == cache).then(|| module_dir.to_path_buf())
+}
+
+fn gradle_module_cache() -> Option<PathBuf> {
+    let gradle_home = std::env::var_os("GRADLE_USER_HOME")
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".gradle")))?;
+    let cache = gradle_home.join("caches/modules-2/files-2.1");
+    cache.is_dir().then_some(cache)
+}
+
+fn build_files(root: &Path) -> Vec<PathBuf> {
+    WalkDir::new(root)
+        .max_depth(BUILD_FILE_MAX_DEPTH)
+        .into_iter()
+        .filter_entry(|e| {
+            e.depth() == 0
+                || !e
+                    .file_name()
+                    .to_str()
+                    .is_some_and(|n| n.starts_with('.') || n == "build")
+        })
+        .filter_map(|e| e.ok())
+        .filter(|e| {
+            matches!(
+                e.file_name().to_str(),
+                Some("build.gradle" | "build.gradle.kts")
+            )
+        })
+        .map(|e| e.into_path())
+        .collect()
+}
+
+/// Each artifact version directory holds one sub-directory per file hash.
+fn find_artifact(version_dir: &Path, file_name: &str) -> Option<PathBuf> {
+    std::fs::read_dir(version_dir)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path().join(file_name))
+        .find(|p| p.is_file())
+}
+
+/// The literal `group:name:version` coordinates in a build file.  Quoted strings
+/// with a classifier or an interpolated version are skipped, and so are comments.
+fn dependency_coordinates(content: &str) -> Vec<(String, String, String)> {
+    let is_coordinate_part = |s: &str| {
+        !s.is_empty()
+            && s.chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, '.' | '-' | '_' | '+'))
+    };
+    script_tokens(content)
+        .into_iter()
+        .filter_map(|token| match token {
+            ScriptToken::Literal(literal, _) => Some(literal),
+            ScriptToken::Word(..) => None,
+        })
+        .filter_map(|literal| {
+            let segments: Vec<&str> = literal.split(':').collect();
+            let [group, name, version] = segments[..] else {
+                return None;
+            };
+            ([group, name, version].into_iter().all(is_coordinate_part) && !version.contains('+'))
+                .then(|| (group.to_string(), name.to_string(), version.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dependency_coordinates() {
+        let content = r#"
+            plugins { id 'java' }
+            dependencies {
+                implementation 'org.slf4j:slf4j-api:2.0.9'
+                implementation("com.google.guava:guava:33.0.0-jre")
+                testImplementation "org.junit.jupiter:junit-jupiter:$junitVersion"
+                runtimeOnly 'io.netty:netty-all:4.+'
+                implementation 'org.lwjgl:lwjgl:3.3.3:natives-linux'
+                // implementation 'org.apache:commons-lang3:3.14.0'
+                /* implementation "it's:a:comment" */
+                implementation "org.example:tools:1.0" // don't resolve 'x:y:z'
+            }
+        "#;
+
+        let mut coordinates = dependency_coordinates(content);
+        coordinates.sort();
+        assert_eq!(
+            coordinates,
+            vec![
+                (
+                    "com.google.guava".to_string(),
+                    "guava".to_string(),
+                    "33.0.0-jre".to_string()
+                ),
+                (
+                    "org.example".to_string(),
+                    "tools".to_string(),
+                    "1.0".to_string()
+                ),
+                (
+                    "org.slf4j".to_string(),
+                    "slf4j-api".to_string(),
+                    "2.0.9".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(compare_versions("1.10", "1.9"), Ordering::Greater);
+        assert_eq!(compare_versions("8.1.1", "8.1"), Ordering::Greater);
+        assert_eq!(compare_versions("1.0-rc1", "1.0"), Ordering::Less);
+        assert_eq!(compare_versions("1.0-rc2", "1.0-RC10"), Ordering::Less);
+        assert_eq!(compare_versions("2.0.0", "2.0.0"), Ordering::Equal);
+
+        let versions = ["1.9.0", "1.10.0", "1.10.0-beta", "1.2"];
+        assert_eq!(
+            versions.into_iter().max_by(|a, b| compare_versions(a, b)),
+            Some("1.10.0")
+        );
+    }
+
+    #[test]
+    fn test_marker_dependency() {
+        let pom = r#"<?xml version="1.0" encoding="UTF-8"?>
+<project>
+  <modelVersion>4.0.0</modelVersion>
+  <groupId>com.github.johnrengelman.shadow</groupId>
+  <artifactId>com.github.johnrengelman.shadow.gradle.plugin</artifactId>
+  <version>8.1.1</version>
+  <packaging>pom</packaging>
+  <dependencies>
+    <dependency>
+      <groupId>com.github.johnrengelman</groupId>
+      <artifactId>shadow</artifactId>
+      <version>8.1.1</version>
+    </dependency>
+  </dependencies>
+</project>
+"#;
+        assert_eq!(
+            marker_dependency(pom),
+            Some((
+                "com.github.johnrengelman".to_string(),
+                "shadow".to_string(),
+                "8.1.1".to_string()
+            ))
+        );
+        assert_eq!(marker_dependency("<project></project>"), None);
+    }
+
+    #[test]
+    fn test_module_dir_in() {
+        let cache = Path::new("/home/u/.gradle/caches/modules-2/files-2.1");
+        let jar = cache.join("org.slf4j/slf4j-api/2.0.9/abc123/slf4j-api-2.0.9.jar");
+        assert_eq!(
+            module_dir_in(cache, &jar),
+            Some(cache.join("org.slf4j/slf4j-api"))
+        );
+        assert_eq!(
+            module_dir_in(cache, Path::new("/home/u/.m2/repository/a/b/1.0/b-1.0.jar")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_coordinates_in() {
+        let cache = Path::new("/home/u/.gradle/caches/modules-2/files-2.1");
+        let jar = cache.join("org.slf4j/slf4j-api/2.0.9/abc123/slf4j-api-2.0.9.jar");
+        assert_eq!(
+            coordinates_in(cache, &jar),
+            Some((
+                "org.slf4j".to_string(),
+                "slf4j-api".to_string(),
+                "2.0.9".to_string()
+            ))
+        );
+    }
+}
@@ -1,9 +1,11 @@
 pub mod gradle;
+pub mod gradle_cache;
 pub mod no_build_tool;
+pub mod script_lexer;
 
 use std::{
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{Arc, RwLock},
 };
 
 use anyhow::Result;
@@ -18,17 +20,69 @@ pub enum BuildTool {
 }
 
 /// Maps a single sub-project's source roots to the JARs on its compile/runtime classpath.
+/// The test source set is kept apart: its sources also see `test_jar_paths`
+/// (`testImplementation` and friends), which main sources do not.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SubprojectClasspath {
     pub source_dirs: Vec<PathBuf>,
     pub jar_paths: Vec<PathBuf>,
+    #[serde(default)]
+    pub test_source_dirs: Vec<PathBuf>,
+    /// JARs only on the test classpath.
+    #[serde(default)]
+    pub test_jar_paths: Vec<PathBuf>,
 }
 
 impl SubprojectClasspath {
     /// Returns true if `file` lives under one of this sub-project's source roots.
     pub fn contains_file(&self, file: &Path) -> bool {
-        self.source_dirs.iter().any(|d| file.starts_with(d))
+        self.source_dirs.iter().any(|d| file.starts_with(d)) || self.is_test_file(file)
     }
+
+    /// Returns true if `file` lives under one of this sub-project's test source roots.
+    pub fn is_test_file(&self, file: &Path) -> bool {
+        self.test_source_dirs.iter().any(|d| file.starts_with(d))
+    }
+
+    /// The JARs visible from `file`, which must belong to this sub-project.
+    pub fn jar_paths_for(&self, file: &Path) -> impl Iterator<Item = &PathBuf> {
+        let test_jars = if self.is_test_file(file) {
+            self.test_jar_paths.as_slice()
+        } else {
+            &[]
+        };
+        self.jar_paths.iter().chain(test_jars)
+    }
+}
+
+/// How build tools may fetch dependency artifacts such as sources jars.  Read from
+/// the client's initialization options and applied to every build tool run.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct ArtifactSettings {
+    /// Forbids network access: dependencies and their sources come from local caches.
+    pub offline: bool,
+    /// Proxy for downloads, as `http://[user:password@]host:port`.
+    pub proxy: Option<String>,
+    /// Maven repositories searched in addition to the project's own.
+    pub maven_repositories: Vec<String>,
+}
+
+static ARTIFACT_SETTINGS: RwLock<ArtifactSettings> = RwLock::new(ArtifactSettings {
+    offline: false,
+    proxy: None,
+    maven_repositories: Vec::new(),
+});
+
+pub fn set_artifact_settings(settings: ArtifactSettings) {
+    *ARTIFACT_SETTINGS.write().unwrap_or_else(|e| e.into_inner()) = settings;
+}
+
+pub fn artifact_settings() -> ArtifactSettings {
+    ARTIFACT_SETTINGS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
 }
 
 pub fn get_build_tool(root: &Path) -> Arc<dyn BuildToolHandler + Send + Sync> {
@@ -39,6 +93,16 @@ pub fn get_build_tool(root: &Path) -> Arc<dyn BuildToolHandler + Send + Sync> {
         .unwrap_or_else(|| Arc::new(NoBuildTool))
 }
 
+/// Locates the JDK source archive under `JAVA_HOME`, for files opened without a
+/// build tool to ask.
+pub fn find_java_home_sources() -> Option<PathBuf> {
+    let java_home = PathBuf::from(std::env::var_os("JAVA_HOME")?);
+    // Java 9+ keeps it under lib/, Java 8 at the top level.
+    [java_home.join("lib/src.zip"), java_home.join("src.zip")]
+        .into_iter()
+        .find(|p| p.exists())
+}
+
 pub trait BuildToolHandler: Send + Sync {
     fn is_project(&self, root: &Path) -> bool;
     fn get_dependency_paths(&self, root: &Path) -> Result<Vec<(Option<PathBuf>, Option<PathBuf>)>>;
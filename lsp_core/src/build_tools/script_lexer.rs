@@ -0,0 +1,70 @@
+//! A textual lexer for Gradle build scripts, reading Groovy and Kotlin DSL alike:
+//! the identifiers and the string literals of a script, outside its comments.
+
+/// A token of a build script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptToken<'a> {
+    /// An identifier, with the byte offset just past it.
+    Word(&'a str, usize),
+    /// The text between the quotes of a string literal, with the byte offset it
+    /// starts at.  Literals not closed on their line are left out.
+    Literal(&'a str, usize),
+}
+
+/// Whether `c` may be part of an identifier.
+pub fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+/// The identifiers and string literals of `content`, in order.  Comments are
+/// skipped, and so are identifiers inside strings.
+pub fn script_tokens(content: &str) -> Vec<ScriptToken<'_>> {
+    let mut tokens = Vec::new();
+    let mut chars = content.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '/' if content[i..].starts_with("//") => {
+                while chars.next_if(|(_, c)| *c != '\n').is_some() {}
+            }
+            '/' if content[i..].starts_with("/*") => {
+                let end = content[i + 2..]
+                    .find("*/")
+                    .map_or(content.len(), |e| i + 2 + e + 2);
+                while chars.next_if(|(j, _)| *j < end).is_some() {}
+            }
+            '"' | '\'' => match string_end(content, i) {
+                Some(end) => {
+                    tokens.push(ScriptToken::Literal(&content[i + 1..end - 1], i + 1));
+                    while chars.next_if(|(j, _)| *j < end).is_some() {}
+                }
+                None => while chars.next_if(|(_, c)| *c != '\n').is_some() {},
+            },
+            c if is_identifier_char(c) => {
+                let mut end = i + c.len_utf8();
+                while let Some((j, c)) = chars.next_if(|(_, c)| is_identifier_char(*c)) {
+                    end = j + c.len_utf8();
+                }
+                tokens.push(ScriptToken::Word(&content[i..end], end));
+            }
+            _ => {}
+        }
+    }
+    tokens
+}
+
+/// Byte offset just past the string literal opening at `start`, or `None` when it
+/// is not closed on its line.
+pub fn string_end(content: &str, start: usize) -> Option<usize> {
+    let quote = content[start..].chars().next()?;
+    let mut escaped = false;
+    for (i, c) in content[start + 1..].char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '\n' => return None,
+            c if c == quote => return Some(start + 1 + i + 1),
+            _ => {}
+        }
+    }
+    None
+}
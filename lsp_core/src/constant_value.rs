@@ -0,0 +1,130 @@
+//! Compile-time constant values, as hover shows them.
+//!
+//! A constant's value comes from one of two places: its initializer in source,
+//! folded by the language support when it is a literal or a concatenation of
+//! literals, or the `ConstantValue` attribute the compiler wrote into the class
+//! file.  Both end up here so they are spelled the same way: strings quoted and
+//! escaped, every other literal as written.
+
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConstantValue {
+    /// A string, unescaped.
+    String(String),
+    /// A number, character or boolean literal, as written in source.
+    Literal(String),
+}
+
+impl ConstantValue {
+    /// The string a quoted literal such as `"a\tb"`, `'a'` or `"""a"""` stands for.
+    /// `None` when `text` is not quoted.
+    pub fn from_string_literal(text: &str) -> Option<Self> {
+        let quote = ["\"\"\"", "'''", "\"", "'"]
+            .into_iter()
+            .find(|q| text.len() >= 2 * q.len() && text.starts_with(q) && text.ends_with(q))?;
+        let inner = &text[quote.len()..text.len() - quote.len()];
+        Some(Self::String(unescape(inner)))
+    }
+
+    /// `self + other`, folded the way Java, Groovy and Kotlin fold string
+    /// concatenation.  `None` unless one side is a string and the other a string or
+    /// a literal that reads the same once concatenated (a decimal integer, a
+    /// boolean).
+    pub fn concat(self, other: Self) -> Option<Self> {
+        let text = |value: Self| match value {
+            Self::String(s) => Some(s),
+            Self::Literal(l) if is_plain_literal(&l) => Some(l),
+            Self::Literal(_) => None,
+        };
+        let is_string = |value: &Self| matches!(value, Self::String(_));
+        if !is_string(&self) && !is_string(&other) {
+            return None;
+        }
+        Some(Self::String(text(self)? + &text(other)?))
+    }
+}
+
+impl fmt::Display for ConstantValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::String(s) => write!(f, "\"{}\"", escape(s)),
+            Self::Literal(l) => f.write_str(l),
+        }
+    }
+}
+
+fn is_plain_literal(literal: &str) -> bool {
+    let digits = literal.strip_prefix('-').unwrap_or(literal);
+    matches!(literal, "true" | "false")
+        || (!digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()))
+}
+
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('t') => unescaped.push('\t'),
+            Some('r') => unescaped.push('\r'),
+            Some('b') => unescaped.push('\u{8}'),
+            Some('0') => unescaped.push('\0'),
+            Some(c @ ('"' | '\'' | '\\' | '$')) => unescaped.push(c),
+            // Unicode and octal escapes are kept as written.
+            Some(c) => {
+                unescaped.push('\\');
+                unescaped.push(c);
+            }
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            '\r' => escaped.push_str("\\r"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_string_literal_round_trip() {
+        let value = ConstantValue::from_string_literal(r#""say \"hi\"\n""#).unwrap();
+        assert_eq!(value, ConstantValue::String("say \"hi\"\n".to_string()));
+        assert_eq!(value.to_string(), r#""say \"hi\"\n""#);
+        assert_eq!(
+            ConstantValue::from_string_literal("'''a'''"),
+            Some(ConstantValue::String("a".to_string()))
+        );
+        assert_eq!(ConstantValue::from_string_literal("abc"), None);
+    }
+
+    #[test]
+    fn test_concat() {
+        let string = |s: &str| ConstantValue::String(s.to_string());
+        let literal = |l: &str| ConstantValue::Literal(l.to_string());
+        assert_eq!(string("v").concat(literal("2")), Some(string("v2")));
+        assert_eq!(literal("true").concat(string("!")), Some(string("true!")));
+        assert_eq!(string("a").concat(string("b")), Some(string("ab")));
+        assert_eq!(string("v").concat(literal("2L")), None);
+        assert_eq!(literal("1").concat(literal("2")), None);
+    }
+}
@@ -3,12 +3,15 @@ use std::{collections::HashSet, path::Path};
 use tower_lsp::lsp_types::{Diagnostic, Position, Range};
 use tree_sitter::{Node, Tree};
 
-use crate::{languages::Language, node_kind::NodeKind};
+use crate::{
+    constant_value::ConstantValue, languages::Language, node_kind::NodeKind,
+    qualifier::QualifierChain,
+};
 
 pub type ParseResult = (Tree, String);
 
 // (name, qualifier)
-pub type IdentResult = (String, Option<String>);
+pub type IdentResult = (String, Option<QualifierChain>);
 
 // (name, type_name, default_value)
 pub type ParameterResult = (String, Option<String>, Option<String>);
@@ -48,6 +51,13 @@ pub trait LanguageSupport: Send + Sync {
     fn get_parameters(&self, node: &Node, source: &str) -> Option<Vec<ParameterResult>>;
     fn get_return(&self, node: &Node, source: &str) -> Option<String>;
 
+    /// Returns the compile-time value of a constant field — `static final` in Java
+    /// and Groovy, `const val` in Kotlin — when its initializer is a literal or a
+    /// concatenation of literals.
+    fn get_constant_value(&self, _node: &Node, _source: &str) -> Option<ConstantValue> {
+        None
+    }
+
     // should also return implicit imports
     fn get_imports(&self, tree: &Tree, source: &str) -> Vec<String>;
 
@@ -158,6 +168,19 @@ pub trait LanguageSupport: Send + Sync {
         vec![]
     }
 
+    /// Returns every method declared directly in a type body, with its modifiers and
+    /// annotations.  Used to detect overrides that are missing an `@Override` annotation.
+    /// Java/Groovy implement this; Kotlin enforces `override` at the language level.
+    fn get_method_declarations(&self, _tree: &Tree, _source: &str) -> Vec<MethodDeclarationData> {
+        vec![]
+    }
+
+    /// Returns local variable declarations made as standalone statements in method, constructor
+    /// or lambda bodies.  Used together with `find_local_references` to detect unused locals.
+    fn get_local_variable_declarations(&self, _tree: &Tree, _source: &str) -> Vec<LocalVariableData> {
+        vec![]
+    }
+
     /// Returns variable declarations where a numeric primitive is initialised from an identifier,
     /// so the server can check whether that identifier has a wider numeric type (narrowing_conversion).
     /// Only Java and Groovy implement this; Kotlin outlaws implicit numeric conversions at the
@@ -173,6 +196,73 @@ pub trait LanguageSupport: Send + Sync {
         vec![]
     }
 
+    /// Returns `.` dereferences of values produced by a qualified call `receiver.method()`,
+    /// either chained directly (`a.b().c`) or through an untyped `val` initialised from the
+    /// call and not null-checked before use.  The server reports those whose call resolves
+    /// to a Java method annotated `@Nullable` (platform_null_dereference).
+    /// Only Kotlin implements this; Java and Groovy have no null-safe type system to bypass.
+    fn get_platform_dereferences(&self, _tree: &Tree, _source: &str) -> Vec<PlatformDereferenceData> {
+        vec![]
+    }
+
+    /// Returns tests declared by calling a string-named DSL function with a lambda inside a
+    /// class, e.g. Kotest's `test("adds numbers") { }`, `"adds numbers" { }` or
+    /// `"math" should { }`.  Used for test discovery; the server decides whether the
+    /// containing class is actually a spec.
+    /// Only Kotlin implements this; Spock features are ordinary string-named methods.
+    fn get_string_named_tests(&self, _tree: &Tree, _source: &str) -> Vec<StringNamedTestData> {
+        vec![]
+    }
+
+    /// Returns the lambda or method reference that the local declared at `decl_position`
+    /// (its name identifier) is initialised with, as `(range, sam_type)`.  `sam_type` is
+    /// the functional interface named at the lambda itself, as with Kotlin's SAM
+    /// constructor `Runnable { ... }`; otherwise the declared type applies.
+    /// Used to navigate from `r.run()` back to the lambda body.
+    fn find_lambda_initializer(
+        &self,
+        _tree: &Tree,
+        _source: &str,
+        _decl_position: &Position,
+    ) -> Option<(Range, Option<String>)> {
+        None
+    }
+
+    /// Returns the `this(...)` or `super(...)` constructor delegation whose keyword is at
+    /// `position`: Java/Groovy's explicit constructor invocation, Kotlin's
+    /// `constructor(...) : this(...)`.  Used to navigate to the constructor delegated to.
+    fn find_constructor_delegation(
+        &self,
+        _tree: &Tree,
+        _source: &str,
+        _position: &Position,
+    ) -> Option<ConstructorDelegationData> {
+        None
+    }
+
+    /// Returns the constructors declared by the type named `class_name` in the file,
+    /// Kotlin's primary constructor included.  Types with only an implicit constructor
+    /// have none.
+    fn get_constructors(
+        &self,
+        _tree: &Tree,
+        _source: &str,
+        _class_name: &str,
+    ) -> Vec<ConstructorData> {
+        vec![]
+    }
+
+    /// Returns the constants the enum named `enum_name` declares in the file, in
+    /// declaration order.
+    fn get_enum_constants(
+        &self,
+        _tree: &Tree,
+        _source: &str,
+        _enum_name: &str,
+    ) -> Vec<EnumConstantData> {
+        vec![]
+    }
+
     /// Returns true when `name` is a syntactically valid identifier in this language
     /// and is not a reserved keyword.  Default checks ASCII rules
     /// (letter or `_`/`$` followed by letters, digits, `_`, `$`) and delegates
@@ -213,6 +303,23 @@ pub trait LanguageSupport: Send + Sync {
     ) -> Option<Vec<Range>> {
         None
     }
+
+    /// `find_local_references` for each of `decl_positions`, in that order.  A
+    /// declaration the language cannot resolve has no references.
+    fn find_all_local_references(
+        &self,
+        tree: &Tree,
+        content: &str,
+        decl_positions: &[Position],
+    ) -> Vec<Vec<Range>> {
+        decl_positions
+            .iter()
+            .map(|position| {
+                self.find_local_references(tree, content, position)
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
 }
 
 /// One argument at a method call site, with enough information for the server to
@@ -407,6 +514,57 @@ pub fn normalize_param_type(t: &str) -> String {
     format!("{canon}{trailing_array}")
 }
 
+/// A `.` dereference of a call result, e.g. `user.getEmail().length` or `email.length`
+/// after `val email = user.getEmail()`.
+pub struct PlatformDereferenceData {
+    /// The call's receiver identifier as written in source (e.g. `"user"`).
+    pub receiver_name: String,
+    /// Range of the call's receiver — used to look up its declared type.
+    pub receiver_range: Range,
+    /// The called method name (e.g. `"getEmail"`).
+    pub method_name: String,
+    /// Range of the dereferenced expression — where the diagnostic is anchored.
+    pub range: Range,
+}
+
+/// A test or test container named by a string literal, e.g. `context("math") { ... }`.
+pub struct StringNamedTestData {
+    /// Short name of the class whose body declares the test.
+    pub containing_class: String,
+    /// Names of the enclosing string-named containers, outermost first.
+    pub parents: Vec<String>,
+    /// The test name without quotes.
+    pub name: String,
+    /// Range of the string literal naming the test — where navigation lands.
+    pub range: Range,
+}
+
+/// A `this(...)` or `super(...)` call delegating to another constructor.
+pub struct ConstructorDelegationData {
+    /// `true` for `super(...)`, `false` for `this(...)`.
+    pub is_super: bool,
+    pub arg_count: usize,
+}
+
+/// A constructor declaration.
+pub struct ConstructorData {
+    /// The parameter list as written, e.g. `"(String name, int age)"`.
+    pub parameters: String,
+    pub param_count: usize,
+    /// Range of the constructor's name, or of its `constructor` keyword or parameter
+    /// list when it has none.
+    pub range: Range,
+}
+
+/// A constant declared by an enum.
+pub struct EnumConstantData {
+    pub name: String,
+    /// The arguments passed to the enum's constructor, as written.
+    pub arguments: Vec<String>,
+    /// Range of the constant's name.
+    pub range: Range,
+}
+
 /// A `new T(...)` expression site.
 pub struct ObjectCreationData {
     /// The short type name as written in source, e.g. `"ArrayList"`.
@@ -450,6 +608,32 @@ pub struct OverrideMethodData {
     pub range: Range,
 }
 
+/// A method declared directly in a class, interface or enum body.
+pub struct MethodDeclarationData {
+    /// Short name of the type that directly declares this method.
+    pub containing_class: String,
+    /// Method name plus normalized parameter types.
+    pub signature: MethodSig,
+    pub modifiers: Vec<String>,
+    /// Annotation names as written in source, without the leading `@`.
+    pub annotations: Vec<String>,
+    /// Range of the method name identifier — where diagnostics are anchored.
+    pub name_range: Range,
+    /// Start of the declaration, including any leading modifiers or annotations.
+    /// Quick fixes that add an annotation insert text here.
+    pub decl_start: Position,
+}
+
+/// A local variable declared as a standalone statement.
+pub struct LocalVariableData {
+    pub name: String,
+    /// Range of the variable name identifier — where diagnostics are anchored.
+    pub ident_range: Range,
+    /// Range of the whole declaration statement when it declares only this variable, so it
+    /// can be removed as a unit.  `None` when the statement declares several variables.
+    pub statement_range: Option<Range>,
+}
+
 /// A variable declaration where a numeric primitive is assigned from a simple identifier,
 /// allowing the server to check for narrowing conversions.
 pub struct NarrowingCandidateData {
@@ -1,9 +1,11 @@
 pub mod build_tools;
+pub mod constant_value;
 pub mod language_support;
 pub mod languages;
 pub mod local_refs;
 pub mod lsp_logging;
 pub mod node_kind;
+pub mod qualifier;
 pub mod ts_helper;
 pub mod util;
 pub mod vcs;
@@ -2,6 +2,8 @@
 //! given local variable or parameter declaration, honouring lexical scope and
 //! shadowing.
 
+use std::collections::HashMap;
+
 use tower_lsp::lsp_types::{Position, Range};
 use tree_sitter::{Node, Tree};
 
@@ -51,6 +53,116 @@ pub fn find_local_references(
     Some(out)
 }
 
+/// [`find_local_references`] for each of `decl_positions`, in that order, from a
+/// single walk of the tree.
+///
+/// Each declaration binds its name in its scope; below another declaration node
+/// of the same name, the innermost binding wins.  A declaration that cannot be
+/// located has no references.
+pub fn find_all_local_references(
+    tree: &Tree,
+    content: &str,
+    decl_positions: &[Position],
+    decl_node_kinds: &[&str],
+    scope_node_kinds: &[&str],
+) -> Vec<Vec<Range>> {
+    let bytes = content.as_bytes();
+    let mut walk = BindingWalk {
+        bytes,
+        decl_node_kinds,
+        names: vec![""; decl_positions.len()],
+        by_scope: HashMap::new(),
+        by_binding: HashMap::new(),
+        active: HashMap::new(),
+        out: vec![Vec::new(); decl_positions.len()],
+    };
+    for (index, position) in decl_positions.iter().enumerate() {
+        let Some(decl_node) = get_node_at_position(tree, content, position) else {
+            continue;
+        };
+        let Ok(name) = decl_node.utf8_text(bytes) else {
+            continue;
+        };
+        if name.is_empty() {
+            continue;
+        }
+        let binding_node = ancestor_of_kinds(decl_node, decl_node_kinds).unwrap_or(decl_node);
+        let scope = ancestor_of_kinds(binding_node, scope_node_kinds)
+            .or_else(|| binding_node.parent())
+            .unwrap_or(binding_node);
+        walk.names[index] = name;
+        walk.by_scope.entry(scope.id()).or_default().push(index);
+        walk.by_binding
+            .entry(binding_node.id())
+            .or_default()
+            .push(index);
+    }
+    walk.visit(tree.root_node());
+    walk.out
+}
+
+/// The state of [`find_all_local_references`]: the declarations by the scope
+/// they bind in and by their declaration node, and the binding each name
+/// currently resolves to.
+struct BindingWalk<'a, 'k> {
+    bytes: &'a [u8],
+    decl_node_kinds: &'k [&'k str],
+    names: Vec<&'a str>,
+    by_scope: HashMap<usize, Vec<usize>>,
+    by_binding: HashMap<usize, Vec<usize>>,
+    /// Per name, the bindings entered so far; `None` where a declaration that is
+    /// none of the requested ones shadows the name.
+    active: HashMap<&'a str, Vec<Option<usize>>>,
+    out: Vec<Vec<Range>>,
+}
+
+impl<'a> BindingWalk<'a, '_> {
+    fn visit(&mut self, node: Node) {
+        let mut entered: Vec<&'a str> = Vec::new();
+        for &index in self.by_scope.get(&node.id()).into_iter().flatten() {
+            let name = self.names[index];
+            self.active.entry(name).or_default().push(Some(index));
+            entered.push(name);
+        }
+        if self.decl_node_kinds.contains(&node.kind()) {
+            let shadowed: Vec<&'a str> = self
+                .active
+                .keys()
+                .copied()
+                .filter(|name| declares_name(node, self.bytes, name))
+                .collect();
+            for name in shadowed {
+                let own = self
+                    .by_binding
+                    .get(&node.id())
+                    .and_then(|indices| indices.iter().find(|&&i| self.names[i] == name))
+                    .copied();
+                self.active.entry(name).or_default().push(own);
+                entered.push(name);
+            }
+        }
+
+        if (node.kind() == "identifier" || node.kind() == "simple_identifier")
+            && let Ok(text) = node.utf8_text(self.bytes)
+            && let Some(Some(index)) = self.active.get(text).and_then(|b| b.last())
+            && !is_member_access_rhs(node)
+            && !is_label_or_type_context(node)
+        {
+            self.out[*index].push(node_to_range(&node));
+        }
+
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            self.visit(child);
+        }
+        for name in entered {
+            if let Some(bindings) = self.active.get_mut(name) {
+                bindings.pop();
+            }
+        }
+    }
+}
+
 fn ancestor_of_kinds<'a>(node: Node<'a>, kinds: &[&str]) -> Option<Node<'a>> {
     let mut cur = Some(node);
     while let Some(n) = cur {
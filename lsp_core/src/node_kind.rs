@@ -1,6 +1,6 @@
 use std::fmt::{self, Display, Formatter};
 
-use tower_lsp::lsp_types::CompletionItemKind;
+use tower_lsp::lsp_types::{CompletionItemKind, SymbolKind};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum NodeKind {
@@ -10,6 +10,7 @@ pub enum NodeKind {
     Field,
     Enum,
     Annotation,
+    TypeAlias,
 }
 
 impl Display for NodeKind {
@@ -21,6 +22,7 @@ impl Display for NodeKind {
             NodeKind::Field => write!(f, "Field"),
             NodeKind::Enum => write!(f, "Enum"),
             NodeKind::Annotation => write!(f, "Annotation"),
+            NodeKind::TypeAlias => write!(f, "TypeAlias"),
         }
     }
 }
@@ -34,6 +36,7 @@ impl NodeKind {
             "Field" => Some(NodeKind::Field),
             "Enum" => Some(NodeKind::Enum),
             "Annotation" => Some(NodeKind::Annotation),
+            "TypeAlias" => Some(NodeKind::TypeAlias),
             _ => None,
         }
     }
@@ -54,6 +57,7 @@ impl NodeKind {
                 _ => Some("enum"),
             },
             NodeKind::Annotation => Some("@interface"),
+            NodeKind::TypeAlias => Some("typealias"),
             NodeKind::Field => None, // just show type + name
         }
     }
@@ -65,7 +69,148 @@ impl NodeKind {
             NodeKind::Function => Some(CompletionItemKind::FUNCTION),
             NodeKind::Field => Some(CompletionItemKind::FIELD),
             NodeKind::Enum => Some(CompletionItemKind::ENUM),
-            NodeKind::Annotation => Some(CompletionItemKind::CLASS),
+            NodeKind::Annotation => Some(CompletionItemKind::INTERFACE),
+            NodeKind::TypeAlias => Some(CompletionItemKind::CLASS),
         }
     }
+
+    pub fn to_lsp_symbol_kind(&self) -> SymbolKind {
+        match self {
+            NodeKind::Class => SymbolKind::CLASS,
+            NodeKind::Interface => SymbolKind::INTERFACE,
+            NodeKind::Function => SymbolKind::METHOD,
+            NodeKind::Field => SymbolKind::FIELD,
+            NodeKind::Enum => SymbolKind::ENUM,
+            NodeKind::Annotation => SymbolKind::INTERFACE,
+            NodeKind::TypeAlias => SymbolKind::CLASS,
+        }
+    }
+
+    /// The completion kind of a declaration of this kind named `name` and typed
+    /// `type_name`, told apart by where it is declared: see [`Placement`].
+    pub fn completion_kind(
+        &self,
+        name: &str,
+        type_name: Option<&str>,
+        placement: Placement,
+    ) -> CompletionItemKind {
+        match (self, placement.role(self, name, type_name)) {
+            (_, Role::Constructor) => CompletionItemKind::CONSTRUCTOR,
+            (_, Role::Method) => CompletionItemKind::METHOD,
+            (_, Role::EnumMember) => CompletionItemKind::ENUM_MEMBER,
+            (kind, Role::Other) => kind.to_lsp_kind().unwrap_or(CompletionItemKind::CLASS),
+        }
+    }
+
+    /// The symbol kind of a declaration of this kind named `name` and typed
+    /// `type_name`, told apart by where it is declared: see [`Placement`].
+    pub fn symbol_kind(
+        &self,
+        name: &str,
+        type_name: Option<&str>,
+        placement: Placement,
+    ) -> SymbolKind {
+        match (self, placement.role(self, name, type_name)) {
+            (_, Role::Constructor) => SymbolKind::CONSTRUCTOR,
+            (_, Role::Method) => SymbolKind::METHOD,
+            (_, Role::EnumMember) => SymbolKind::ENUM_MEMBER,
+            (NodeKind::Function, Role::Other) => SymbolKind::FUNCTION,
+            (NodeKind::Field, Role::Other) if placement.parent.is_none() => SymbolKind::PROPERTY,
+            (kind, Role::Other) => kind.to_lsp_symbol_kind(),
+        }
+    }
+}
+
+/// Where a declaration is declared, which refines the LSP kind of its [`NodeKind`]:
+/// a function named as its type (or `<init>`) is a constructor, other functions in
+/// a type are methods and top-level ones functions, and a field of an enum typed as
+/// the enum is one of its constants.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Placement<'a> {
+    /// The short name of the type declaring it; `None` at the top level.
+    pub parent: Option<&'a str>,
+    /// The kind of that type, when known.
+    pub parent_kind: Option<&'a NodeKind>,
+}
+
+enum Role {
+    Constructor,
+    Method,
+    EnumMember,
+    Other,
+}
+
+impl Placement<'_> {
+    /// The short name of the type named `parent_fqn`, e.g. `Inner` for
+    /// `com.example.Outer$Inner`.
+    pub fn short_name(parent_fqn: &str) -> &str {
+        parent_fqn.rsplit(['.', '$']).next().unwrap_or(parent_fqn)
+    }
+
+    fn role(&self, kind: &NodeKind, name: &str, type_name: Option<&str>) -> Role {
+        match (kind, self.parent) {
+            (NodeKind::Function, Some(parent)) if name == parent || name == "<init>" => {
+                Role::Constructor
+            }
+            (NodeKind::Function, Some(_)) => Role::Method,
+            (NodeKind::Field, Some(parent))
+                if self.parent_kind == Some(&NodeKind::Enum)
+                    && type_name.map(Placement::short_name) == Some(parent) =>
+            {
+                Role::EnumMember
+            }
+            _ => Role::Other,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn functions_are_told_apart_by_placement() {
+        let in_class = Placement {
+            parent: Some("UserService"),
+            parent_kind: Some(&NodeKind::Class),
+        };
+        let function = NodeKind::Function;
+        assert_eq!(
+            function.symbol_kind("UserService", None, in_class),
+            SymbolKind::CONSTRUCTOR
+        );
+        assert_eq!(
+            function.completion_kind("<init>", None, in_class),
+            CompletionItemKind::CONSTRUCTOR
+        );
+        assert_eq!(
+            function.symbol_kind("save", None, in_class),
+            SymbolKind::METHOD
+        );
+        assert_eq!(
+            function.symbol_kind("main", None, Placement::default()),
+            SymbolKind::FUNCTION
+        );
+    }
+
+    #[test]
+    fn enum_constants_are_enum_members() {
+        let in_enum = Placement {
+            parent: Some("Priority"),
+            parent_kind: Some(&NodeKind::Enum),
+        };
+        assert_eq!(
+            NodeKind::Field.completion_kind("HIGH", Some("com.example.Priority"), in_enum),
+            CompletionItemKind::ENUM_MEMBER
+        );
+        assert_eq!(
+            NodeKind::Field.completion_kind("label", Some("java.lang.String"), in_enum),
+            CompletionItemKind::FIELD
+        );
+        assert_eq!(
+            NodeKind::Annotation.completion_kind("Deprecated", None, Placement::default()),
+            CompletionItemKind::INTERFACE
+        );
+        assert_eq!(Placement::short_name("com.example.Outer$Inner"), "Inner");
+    }
 }
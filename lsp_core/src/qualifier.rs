@@ -0,0 +1,238 @@
+//! Qualifier chains: what a member access is made on.
+//!
+//! `Config.instance.get("x").name` is the member `name` qualified by the chain
+//! `Config` → `instance` → `get(...)`.  Each segment records what the syntax says
+//! it is — a variable or field, a type, a call, `this` or `super` — so resolution
+//! can look a call up among methods and a field among fields instead of guessing
+//! from a bare name.  The `#`-joined form (`Config#instance#get`) is still what
+//! [`Display`](fmt::Display) gives and what [`QualifierChain::parse`] reads, for the
+//! chains `find_variable_type` encodes as strings.
+
+use std::fmt;
+
+use tree_sitter::Node;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SegmentKind {
+    /// A local, parameter, field or property — or a name the syntax cannot tell
+    /// from a type, like `Config` in `Config.instance`.
+    Variable,
+    Type,
+    Call,
+    This,
+    Super,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    pub name: String,
+    pub kind: SegmentKind,
+}
+
+impl Segment {
+    pub fn new(name: impl Into<String>, kind: SegmentKind) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QualifierChain {
+    pub segments: Vec<Segment>,
+}
+
+/// Node kinds that hold a call's arguments or trailing lambda.  They are not part of
+/// the chain the call is made on: `a.b(c.d()).e` is qualified by `a#b`.
+const ARGUMENT_KINDS: &[&str] = &[
+    "argument_list",
+    "value_arguments",
+    "annotated_lambda",
+    "lambda_literal",
+    "closure",
+    "class_body",
+];
+
+/// Node kinds kept verbatim as one segment.
+const OPAQUE_KINDS: &[&str] = &["generic_type", "array_access", "indexing_expression"];
+
+const STRING_KINDS: &[&str] = &["string_literal", "line_string_literal", "string"];
+
+impl QualifierChain {
+    /// The chain `node` stands for, walked on the syntax tree so that parentheses
+    /// or dots inside arguments, lambdas and comments never leak into it.
+    pub fn from_node(node: &Node, content: &str) -> Self {
+        let mut chain = Self::default();
+        chain.collect(node, content);
+        chain
+    }
+
+    /// Reads a `#`-joined chain.  Only `this` and `super` are recognised; every
+    /// other segment is a [`SegmentKind::Variable`].
+    pub fn parse(chain: &str) -> Self {
+        let segments = chain
+            .split('#')
+            .filter(|s| !s.is_empty())
+            .map(|name| {
+                let kind = match name {
+                    "this" => SegmentKind::This,
+                    "super" => SegmentKind::Super,
+                    _ => SegmentKind::Variable,
+                };
+                Segment::new(name, kind)
+            })
+            .collect();
+        Self { segments }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    fn push(&mut self, name: impl Into<String>, kind: SegmentKind) {
+        self.segments.push(Segment::new(name, kind));
+    }
+
+    fn collect(&mut self, node: &Node, content: &str) {
+        let kind = node.kind();
+        if ARGUMENT_KINDS.contains(&kind) || node.is_extra() {
+            return;
+        }
+        let text = |node: &Node| {
+            node.utf8_text(content.as_bytes())
+                .unwrap_or_default()
+                .to_string()
+        };
+        if STRING_KINDS.contains(&kind) {
+            self.push("String", SegmentKind::Type);
+            return;
+        }
+        if OPAQUE_KINDS.contains(&kind) {
+            let segment_kind = if kind == "generic_type" {
+                SegmentKind::Type
+            } else {
+                SegmentKind::Variable
+            };
+            self.push(text(node), segment_kind);
+            return;
+        }
+        match kind {
+            "this" | "this_expression" => return self.push(text(node), SegmentKind::This),
+            "super" | "super_expression" => return self.push(text(node), SegmentKind::Super),
+            "type_identifier" => return self.push(text(node), SegmentKind::Type),
+            _ if node.named_child_count() == 0 => {
+                return self.push(text(node), SegmentKind::Variable);
+            }
+            _ => {}
+        }
+        match kind {
+            // Kotlin puts a constructor's type arguments in the call suffix: `Box<Int>()`.
+            "call_suffix" => {
+                let mut cursor = node.walk();
+                let type_arguments = node
+                    .named_children(&mut cursor)
+                    .find(|c| c.kind() == "type_arguments");
+                if let Some(type_arguments) = type_arguments
+                    && let Some(last) = self.segments.last_mut()
+                {
+                    last.name.push_str(&text(&type_arguments));
+                }
+            }
+            // `(x as Foo).bar` and `((Foo) x).bar` are qualified by the type cast to.
+            "cast_expression" | "as_expression" => {
+                let target = node
+                    .child_by_field_name("type")
+                    .or_else(|| node.named_child(node.named_child_count() - 1));
+                if let Some(target) = target {
+                    self.push(text(&target), SegmentKind::Type);
+                }
+            }
+            "class_literal" => {
+                let mut cursor = node.walk();
+                for child in node.named_children(&mut cursor) {
+                    self.push(text(&child), SegmentKind::Type);
+                }
+                self.push("class", SegmentKind::Variable);
+            }
+            _ => {
+                let mut cursor = node.walk();
+                for child in node.named_children(&mut cursor) {
+                    // Java's `a.<T>call()`: explicit type arguments are not a segment.
+                    if child.kind() != "type_arguments" {
+                        self.collect(&child, content);
+                    }
+                }
+                if matches!(kind, "method_invocation" | "call_expression")
+                    && let Some(last) = self.segments.last_mut()
+                {
+                    last.kind = SegmentKind::Call;
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for QualifierChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, segment) in self.segments.iter().enumerate() {
+            if i > 0 {
+                f.write_str("#")?;
+            }
+            f.write_str(&segment.name)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_round_trip() {
+        let chain = QualifierChain::parse("this#repo#findAll");
+        assert_eq!(
+            chain.segments,
+            vec![
+                Segment::new("this", SegmentKind::This),
+                Segment::new("repo", SegmentKind::Variable),
+                Segment::new("findAll", SegmentKind::Variable),
+            ]
+        );
+        assert_eq!(chain.to_string(), "this#repo#findAll");
+    }
+
+    #[test]
+    fn test_kinds_from_java_tree() {
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&tree_sitter_java::LANGUAGE.into())
+            .expect("cannot load java grammar");
+        let content = r#"class A { void m() { Config.instance.get("x").name(); } }"#;
+        let tree = parser.parse(content, None).expect("cannot parse content");
+        let call = find_kind(tree.root_node(), "method_invocation").expect("no call");
+        let qualifier = call.child_by_field_name("object").expect("no qualifier");
+
+        let chain = QualifierChain::from_node(&qualifier, content);
+
+        assert_eq!(
+            chain.segments,
+            vec![
+                Segment::new("Config", SegmentKind::Variable),
+                Segment::new("instance", SegmentKind::Variable),
+                Segment::new("get", SegmentKind::Call),
+            ]
+        );
+        assert_eq!(chain.to_string(), "Config#instance#get");
+    }
+
+    fn find_kind<'a>(node: Node<'a>, kind: &str) -> Option<Node<'a>> {
+        if node.kind() == kind {
+            return Some(node);
+        }
+        let mut cursor = node.walk();
+        let children: Vec<Node<'a>> = node.children(&mut cursor).collect();
+        children.into_iter().find_map(|c| find_kind(c, kind))
+    }
+}
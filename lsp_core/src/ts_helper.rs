@@ -180,23 +180,31 @@ pub fn node_contains_position(node: &Node, position: &Position) -> bool {
         && (line < end.row || (line == end.row && char <= end.column))
 }
 
+/// The byte offset of `position`.  `position.character` counts characters from the
+/// start of the line; a column past the end of its line is clamped to the line end.
 pub fn position_to_byte_offset(content: &str, position: &Position) -> usize {
-    let mut line = 0usize;
-    let mut col = 0usize;
-    let mut byte = 0usize;
-    for ch in content.chars() {
-        if line == position.line as usize && col == position.character as usize {
-            break;
-        }
-        if ch == '\n' {
-            line += 1;
-            col = 0;
-        } else {
-            col += 1;
-        }
-        byte += ch.len_utf8();
-    }
-    byte
+    let line_start: usize = content
+        .split_inclusive('\n')
+        .take(position.line as usize)
+        .map(str::len)
+        .sum();
+    let line = content[line_start..].split('\n').next().unwrap_or_default();
+    line_start
+        + line
+            .char_indices()
+            .nth(position.character as usize)
+            .map_or(line.len(), |(i, _)| i)
+}
+
+/// The position of the byte `offset`, its column counted in characters as
+/// [`position_to_byte_offset`] reads it.
+pub fn byte_offset_to_position(content: &str, offset: usize) -> Position {
+    let before = &content[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    Position::new(
+        before.matches('\n').count() as u32,
+        before[line_start..].chars().count() as u32,
+    )
 }
 
 pub fn get_node_at_position<'a>(
@@ -204,25 +212,7 @@ pub fn get_node_at_position<'a>(
     content: &str,
     position: &Position,
 ) -> Option<Node<'a>> {
-    let mut line = 0;
-    let mut col = 0;
-    let mut byte_offset = 0;
-
-    for ch in content.chars() {
-        if line == position.line as usize && col == position.character as usize {
-            break;
-        }
-
-        if ch == '\n' {
-            line += 1;
-            col = 0;
-        } else {
-            col += 1;
-        }
-
-        byte_offset += ch.len_utf8();
-    }
-
+    let byte_offset = position_to_byte_offset(content, position);
     tree.root_node()
         .descendant_for_byte_range(byte_offset, byte_offset)
 }
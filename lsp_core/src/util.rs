@@ -14,13 +14,68 @@ pub fn capitalize(s: &str) -> String {
     }
 }
 
-// Only find direct import match
-pub fn naive_resolve_fqn(name: &str, imports: &[String]) -> Option<String> {
-    if let Some(import) = imports
+/// Splits an import into the imported name and its Kotlin alias:
+/// `com.foo.Bar as Baz` → (`com.foo.Bar`, `Some("Baz")`).
+pub fn split_import_alias(import: &str) -> (&str, Option<&str>) {
+    match import.split_once(" as ") {
+        Some((fqn, alias)) => (fqn.trim(), Some(alias.trim())),
+        None => (import, None),
+    }
+}
+
+/// The name an import brings into scope: its alias, otherwise its last segment.
+pub fn imported_name(import: &str) -> &str {
+    match split_import_alias(import) {
+        (_, Some(alias)) => alias,
+        (fqn, None) => fqn.rsplit('.').next().unwrap_or(fqn),
+    }
+}
+
+/// Numeric types that widen to `Number` when a collection literal mixes them.
+const NUMBER_TYPES: &[&str] = &[
+    "Byte",
+    "Short",
+    "Integer",
+    "Int",
+    "Long",
+    "Float",
+    "Double",
+    "BigInteger",
+    "BigDecimal",
+];
+
+/// The element type of a collection literal, from the types of its elements: the
+/// type they all share, `Number` when they are mixed numbers, `top` (`Object`,
+/// `Any`) otherwise.  `None` when the literal is empty or an element's type is not
+/// known from the literal alone.
+pub fn common_element_type(types: &[Option<String>], top: &str) -> Option<String> {
+    let types: Vec<&str> = types.iter().map(Option::as_deref).collect::<Option<_>>()?;
+    let first = *types.first()?;
+    if types.iter().all(|t| *t == first) {
+        Some(first.to_string())
+    } else if types.iter().all(|t| NUMBER_TYPES.contains(t)) {
+        Some("Number".to_string())
+    } else {
+        Some(top.to_string())
+    }
+}
+
+/// `base<args>` when every type argument is known, the raw `base` otherwise.
+pub fn parameterized_type(base: &str, args: &[Option<String>]) -> String {
+    match args
         .iter()
-        .find(|i| i.split('.').next_back() == Some(name))
+        .map(Option::as_deref)
+        .collect::<Option<Vec<&str>>>()
     {
-        return Some(import.clone());
+        Some(args) if !args.is_empty() => format!("{base}<{}>", args.join(", ")),
+        _ => base.to_string(),
+    }
+}
+
+// Only find direct import match
+pub fn naive_resolve_fqn(name: &str, imports: &[String]) -> Option<String> {
+    if let Some(import) = imports.iter().find(|i| imported_name(i) == name) {
+        return Some(split_import_alias(import).0.to_string());
     }
 
     None
@@ -283,6 +338,54 @@ pub fn get_import_text_edit(
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_import_alias() {
+        assert_eq!(
+            split_import_alias("com.foo.Bar as Baz"),
+            ("com.foo.Bar", Some("Baz"))
+        );
+        assert_eq!(split_import_alias("com.foo.Bar"), ("com.foo.Bar", None));
+
+        let imports = vec!["com.foo.Bar as Baz".to_string(), "com.foo.Qux".to_string()];
+        assert_eq!(
+            naive_resolve_fqn("Baz", &imports),
+            Some("com.foo.Bar".to_string())
+        );
+        assert_eq!(naive_resolve_fqn("Bar", &imports), None);
+        assert_eq!(
+            naive_resolve_fqn("Qux", &imports),
+            Some("com.foo.Qux".to_string())
+        );
+    }
+
+    #[test]
+    fn test_common_element_type() {
+        let types = |ts: &[&str]| ts.iter().map(|t| Some(t.to_string())).collect::<Vec<_>>();
+        assert_eq!(
+            common_element_type(&types(&["String", "String"]), "Object"),
+            Some("String".to_string())
+        );
+        assert_eq!(
+            common_element_type(&types(&["Integer", "Long"]), "Object"),
+            Some("Number".to_string())
+        );
+        assert_eq!(
+            common_element_type(&types(&["Int", "String"]), "Any"),
+            Some("Any".to_string())
+        );
+        assert_eq!(common_element_type(&[], "Object"), None);
+        assert_eq!(
+            common_element_type(&[Some("String".to_string()), None], "Object"),
+            None
+        );
+
+        assert_eq!(
+            parameterized_type("Map", &types(&["String", "Integer"])),
+            "Map<String, Integer>"
+        );
+        assert_eq!(parameterized_type("List", &[None]), "List");
+    }
+
     #[test]
     fn test_get_import_text_edit() {
         let cases = vec![
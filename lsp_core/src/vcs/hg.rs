@@ -0,0 +1,83 @@
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::vcs::VcsHandler;
+
+pub struct HgHandler;
+
+impl VcsHandler for HgHandler {
+    fn is_repository(&self, root: &Path) -> bool {
+        root.join(".hg").exists()
+    }
+
+    fn get_current_revision(&self) -> Result<String> {
+        let output = Command::new("hg")
+            .args(["log", "-r", ".", "--template", "{node}"])
+            .output()
+            .context("Failed to execute hg command")?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "Hg command failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+        String::from_utf8(output.stdout)
+            .context("Invalid UTF-8 in hg output")
+            .map(|s| s.trim().to_string())
+    }
+
+    fn get_changed_files(&self, old_rev: &str, new_rev: &str, root: &Path) -> Result<Vec<PathBuf>> {
+        // `-n` drops the status prefix; paths are relative to the working directory.
+        let output = Command::new("hg")
+            .args(["status", "-n", "--rev", old_rev, "--rev", new_rev])
+            .current_dir(root)
+            .output()
+            .context("Failed to execute hg command")?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "Hg command failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+        let out = String::from_utf8(output.stdout).context("Invalid UTF-8 in hg output")?;
+        Ok(out
+            .lines()
+            .filter(|l| !l.is_empty())
+            .map(|l| root.join(l))
+            .collect())
+    }
+
+    fn get_revision_file(&self, root: &Path) -> Option<PathBuf> {
+        // Rewritten whenever the working directory parent changes (update, commit).
+        Some(root.join(".hg/dirstate"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn get_revision_file_returns_hg_dirstate() {
+        let root = Path::new("/some/project");
+        assert_eq!(
+            HgHandler.get_revision_file(root),
+            Some(PathBuf::from("/some/project/.hg/dirstate"))
+        );
+    }
+
+    #[test]
+    fn is_repository_true_when_hg_dir_exists() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join(".hg")).unwrap();
+        assert!(HgHandler.is_repository(dir.path()));
+    }
+
+    #[test]
+    fn is_repository_false_when_no_hg_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(!HgHandler.is_repository(dir.path()));
+    }
+}
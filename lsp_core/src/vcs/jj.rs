@@ -0,0 +1,112 @@
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::vcs::VcsHandler;
+
+pub struct JjHandler;
+
+impl VcsHandler for JjHandler {
+    fn is_repository(&self, root: &Path) -> bool {
+        root.join(".jj").exists()
+    }
+
+    fn get_current_revision(&self) -> Result<String> {
+        // `--ignore-working-copy` avoids snapshotting (and thereby rewriting) the
+        // working-copy commit just to read its id.
+        let output = Command::new("jj")
+            .args([
+                "log",
+                "-r",
+                "@",
+                "--no-graph",
+                "--ignore-working-copy",
+                "-T",
+                "commit_id",
+            ])
+            .output()
+            .context("Failed to execute jj command")?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "Jj command failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+        String::from_utf8(output.stdout)
+            .context("Invalid UTF-8 in jj output")
+            .map(|s| s.trim().to_string())
+    }
+
+    fn get_changed_files(&self, old_rev: &str, new_rev: &str, root: &Path) -> Result<Vec<PathBuf>> {
+        let output = Command::new("jj")
+            .args([
+                "diff",
+                "--ignore-working-copy",
+                "--name-only",
+                "--from",
+                old_rev,
+                "--to",
+                new_rev,
+            ])
+            .current_dir(root)
+            .output()
+            .context("Failed to execute jj command")?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "Jj command failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+        let out = String::from_utf8(output.stdout).context("Invalid UTF-8 in jj output")?;
+        Ok(out
+            .lines()
+            .filter(|l| !l.is_empty())
+            .map(|l| root.join(l))
+            .collect())
+    }
+
+    fn get_revision_file(&self, root: &Path) -> Option<PathBuf> {
+        // Rewritten whenever the workspace checks out a different commit.
+        Some(root.join(".jj/working_copy/checkout"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn get_revision_file_returns_jj_checkout() {
+        let root = Path::new("/some/project");
+        assert_eq!(
+            JjHandler.get_revision_file(root),
+            Some(PathBuf::from("/some/project/.jj/working_copy/checkout"))
+        );
+    }
+
+    #[test]
+    fn is_repository_true_when_jj_dir_exists() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join(".jj")).unwrap();
+        assert!(JjHandler.is_repository(dir.path()));
+    }
+
+    #[test]
+    fn is_repository_false_when_no_jj_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(!JjHandler.is_repository(dir.path()));
+    }
+
+    #[test]
+    fn colocated_repository_prefers_jj() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join(".jj")).unwrap();
+        fs::create_dir(dir.path().join(".git")).unwrap();
+        let handler = crate::vcs::get_vcs_handler(dir.path());
+        assert_eq!(
+            handler.get_revision_file(dir.path()),
+            Some(dir.path().join(".jj/working_copy/checkout"))
+        );
+    }
+}
@@ -3,15 +3,22 @@ use std::{
     sync::Arc,
 };
 
-use crate::vcs::{git::GitHandler, no_vcs::NoVcs};
+use crate::vcs::{git::GitHandler, hg::HgHandler, jj::JjHandler, no_vcs::NoVcs};
 
 use anyhow::Result;
 
 pub mod git;
+pub mod hg;
+pub mod jj;
 pub mod no_vcs;
 
 pub fn get_vcs_handler(root: &Path) -> Arc<dyn VcsHandler> {
-    let providers: Vec<Arc<dyn VcsHandler>> = vec![Arc::new(GitHandler)];
+    // Jujutsu goes first: colocated jj repositories also contain a `.git` directory.
+    let providers: Vec<Arc<dyn VcsHandler>> = vec![
+        Arc::new(JjHandler),
+        Arc::new(GitHandler),
+        Arc::new(HgHandler),
+    ];
     providers
         .into_iter()
         .find(|p| p.is_repository(root))
@@ -0,0 +1,6 @@
+-- Content hash of every indexed project source file, used on startup to find
+-- files that changed while the server was not running.
+CREATE TABLE IF NOT EXISTS file_hashes (
+    file_path TEXT PRIMARY KEY,
+    content_hash TEXT NOT NULL
+);
@@ -0,0 +1,7 @@
+-- External classes whose sources have been parsed and their member signatures
+-- stored in `external_symbols`, so they are never parsed again.
+CREATE TABLE IF NOT EXISTS external_class_sources (
+    jar_path TEXT NOT NULL,
+    class_fqn TEXT NOT NULL,
+    PRIMARY KEY (jar_path, class_fqn)
+);
@@ -0,0 +1,7 @@
+-- Directories that recorded paths are relative to.  A path under one is stored as
+-- `${name}/...` and made absolute again with the directory recorded here, so the
+-- index survives moving the checkout.
+CREATE TABLE IF NOT EXISTS path_roots (
+    name TEXT PRIMARY KEY,
+    path TEXT NOT NULL
+);
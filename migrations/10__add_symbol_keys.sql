@@ -0,0 +1,8 @@
+-- Stable key of each symbol, a hash of its FQN and parameter types, so overloads
+-- sharing an FQN can be told apart without comparing their metadata.  Rows indexed
+-- before the column existed are filled in by `Repository::backfill_symbol_keys`.
+ALTER TABLE symbols ADD COLUMN symbol_key TEXT;
+ALTER TABLE external_symbols ADD COLUMN symbol_key TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_symbol_key ON symbols(symbol_key);
+CREATE INDEX IF NOT EXISTS idx_ext_symbol_key ON external_symbols(symbol_key);
@@ -0,0 +1,287 @@
+//! "Generate getter/setter" actions on Java field declarations.
+//!
+//! On a field declaration, `source.generateAccessors` actions append to the class
+//! body a getter, a setter, both, or a fluent `withX` setter returning `this` for
+//! builder-style use, for each variable the declaration declares.  An accessor the
+//! class already declares by that name is not generated again, nor is one Lombok's
+//! `@Getter`, `@Setter`, `@Data` or `@Value` provides.  Final fields get no setters,
+//! and static fields no fluent setter.
+
+use std::collections::{HashMap, HashSet};
+
+use lsp_core::ts_helper::get_node_at_position;
+use tower_lsp::lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOrCommand, Position, Range, Url, WorkspaceEdit,
+};
+use tree_sitter::Node;
+
+use crate::{
+    implement_members::{class_body_end, insertion},
+    server::Backend,
+};
+
+pub(crate) const GENERATE_ACCESSORS_KIND: &str = "source.generateAccessors";
+
+/// A variable of a field declaration.
+struct Field {
+    name: String,
+    type_name: String,
+    is_static: bool,
+    is_final: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Accessor {
+    Getter,
+    Setter,
+    Fluent,
+}
+
+impl Accessor {
+    fn method_name(self, field: &Field) -> String {
+        let mut chars = field.name.chars();
+        let capitalized: String = chars
+            .next()
+            .map(|c| c.to_uppercase().chain(chars).collect())
+            .unwrap_or_default();
+        match self {
+            Accessor::Getter if field.type_name == "boolean" => format!("is{capitalized}"),
+            Accessor::Getter => format!("get{capitalized}"),
+            Accessor::Setter => format!("set{capitalized}"),
+            Accessor::Fluent => format!("with{capitalized}"),
+        }
+    }
+
+    /// Whether `field` may have this accessor at all.
+    fn applies_to(self, field: &Field) -> bool {
+        match self {
+            Accessor::Getter => true,
+            Accessor::Setter => !field.is_final,
+            Accessor::Fluent => !field.is_final && !field.is_static,
+        }
+    }
+
+    /// The Lombok annotations generating this accessor.
+    fn lombok_annotations(self) -> &'static [&'static str] {
+        match self {
+            Accessor::Getter => &["Getter", "Data", "Value"],
+            Accessor::Setter => &["Setter", "Data"],
+            Accessor::Fluent => &[],
+        }
+    }
+
+    /// The accessor of `field` in class `class_name`, indented one level below `indent`.
+    fn method(self, field: &Field, class_name: &str, indent: &str) -> String {
+        let member = format!("{indent}    ");
+        let body = format!("{indent}        ");
+        let name = self.method_name(field);
+        let Field {
+            name: field_name,
+            type_name,
+            ..
+        } = field;
+        let (modifiers, target) = if field.is_static {
+            ("public static", class_name)
+        } else {
+            ("public", "this")
+        };
+        match self {
+            Accessor::Getter => format!(
+                "{member}{modifiers} {type_name} {name}() {{\n\
+                 {body}return {field_name};\n\
+                 {member}}}\n"
+            ),
+            Accessor::Setter => format!(
+                "{member}{modifiers} void {name}({type_name} {field_name}) {{\n\
+                 {body}{target}.{field_name} = {field_name};\n\
+                 {member}}}\n"
+            ),
+            Accessor::Fluent => format!(
+                "{member}{modifiers} {class_name} {name}({type_name} {field_name}) {{\n\
+                 {body}this.{field_name} = {field_name};\n\
+                 {body}return this;\n\
+                 {member}}}\n"
+            ),
+        }
+    }
+}
+
+/// The names of the annotations in the `modifiers` of `declaration`, without
+/// qualifier or arguments.
+fn annotation_names(declaration: &Node, content: &str) -> HashSet<String> {
+    let mut cursor = declaration.walk();
+    let Some(modifiers) = declaration
+        .children(&mut cursor)
+        .find(|n| n.kind() == "modifiers")
+    else {
+        return HashSet::new();
+    };
+    let text = modifiers.utf8_text(content.as_bytes()).unwrap_or_default();
+    text.split('@')
+        .skip(1)
+        .filter_map(|a| {
+            let name = a.split(|c: char| c == '(' || c.is_whitespace()).next()?;
+            Some(name.rsplit('.').next().unwrap_or(name).to_string())
+        })
+        .collect()
+}
+
+fn has_modifier(declaration: &Node, content: &str, modifier: &str) -> bool {
+    let mut cursor = declaration.walk();
+    declaration
+        .children(&mut cursor)
+        .find(|n| n.kind() == "modifiers")
+        .and_then(|m| m.utf8_text(content.as_bytes()).ok())
+        .is_some_and(|text| text.split_whitespace().any(|word| word == modifier))
+}
+
+fn fields(declaration: &Node, content: &str) -> Vec<Field> {
+    let bytes = content.as_bytes();
+    let Some(type_name) = declaration
+        .child_by_field_name("type")
+        .and_then(|t| t.utf8_text(bytes).ok())
+    else {
+        return vec![];
+    };
+    let is_static = has_modifier(declaration, content, "static");
+    let is_final = has_modifier(declaration, content, "final");
+    let mut cursor = declaration.walk();
+    declaration
+        .children(&mut cursor)
+        .filter(|n| n.kind() == "variable_declarator")
+        .filter_map(|d| d.child_by_field_name("name")?.utf8_text(bytes).ok())
+        .map(|name| Field {
+            name: name.to_string(),
+            type_name: type_name.to_string(),
+            is_static,
+            is_final,
+        })
+        .collect()
+}
+
+fn node_range(node: &Node) -> Range {
+    let (start, end) = (node.start_position(), node.end_position());
+    Range::new(
+        Position::new(start.row as u32, start.column as u32),
+        Position::new(end.row as u32, end.column as u32),
+    )
+}
+
+impl Backend {
+    /// Accessor generation actions for the Java field declaration at `position`.
+    pub(crate) async fn accessor_actions(
+        &self,
+        uri: &Url,
+        position: Position,
+    ) -> Vec<CodeActionOrCommand> {
+        if !uri.path().ends_with(".java") {
+            return vec![];
+        }
+        let Some(lang) = self.languages.get("java") else {
+            return vec![];
+        };
+        let Some(content) = self.document_text(uri) else {
+            return vec![];
+        };
+        let Some((tree, _)) = lang.parse_str(&content) else {
+            return vec![];
+        };
+        let Some(declaration) = get_node_at_position(&tree, &content, &position).and_then(|n| {
+            std::iter::successors(Some(n), |n| n.parent()).find(|n| n.kind() == "field_declaration")
+        }) else {
+            return vec![];
+        };
+        let Some(body) = declaration.parent().filter(|n| n.kind() == "class_body") else {
+            return vec![];
+        };
+        let Some(class) = body.parent() else {
+            return vec![];
+        };
+        let Some(class_name_node) = class.child_by_field_name("name") else {
+            return vec![];
+        };
+        let class_name = class_name_node
+            .utf8_text(content.as_bytes())
+            .unwrap_or_default();
+        let Some(body_end) = class_body_end(&tree, &content, node_range(&class_name_node)) else {
+            return vec![];
+        };
+
+        let mut cursor = body.walk();
+        let declared: HashSet<String> = body
+            .children(&mut cursor)
+            .filter(|n| n.kind() == "function_declaration")
+            .filter_map(|f| lang.get_short_name(&f, &content))
+            .collect();
+        let annotations: HashSet<String> = annotation_names(&declaration, &content)
+            .into_iter()
+            .chain(annotation_names(&class, &content))
+            .collect();
+        let fields = fields(&declaration, &content);
+        let missing = |accessor: Accessor| -> Vec<&Field> {
+            if accessor
+                .lombok_annotations()
+                .iter()
+                .any(|a| annotations.contains(*a))
+            {
+                return vec![];
+            }
+            fields
+                .iter()
+                .filter(|f| accessor.applies_to(f) && !declared.contains(&accessor.method_name(f)))
+                .collect()
+        };
+        let names: Vec<String> = fields.iter().map(|f| format!("'{}'", f.name)).collect();
+        let names = names.join(", ");
+
+        let offers = [
+            (
+                format!("Generate getter for {names}"),
+                vec![Accessor::Getter],
+            ),
+            (
+                format!("Generate setter for {names}"),
+                vec![Accessor::Setter],
+            ),
+            (
+                format!("Generate getter and setter for {names}"),
+                vec![Accessor::Getter, Accessor::Setter],
+            ),
+            (
+                format!("Generate fluent setter for {names}"),
+                vec![Accessor::Fluent],
+            ),
+        ];
+        let mut actions = Vec::new();
+        for (title, accessors) in offers {
+            let per_accessor: Vec<Vec<&Field>> = accessors.iter().map(|a| missing(*a)).collect();
+            if per_accessor.iter().any(Vec::is_empty) {
+                continue;
+            }
+            let methods: Vec<String> = fields
+                .iter()
+                .flat_map(|field| {
+                    accessors
+                        .iter()
+                        .zip(&per_accessor)
+                        .filter(|(_, missing)| missing.iter().any(|f| f.name == field.name))
+                        .map(|(accessor, _)| accessor.method(field, class_name, &body_end.indent))
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+            actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+                title,
+                kind: Some(CodeActionKind::new(GENERATE_ACCESSORS_KIND)),
+                edit: Some(WorkspaceEdit {
+                    changes: Some(HashMap::from([(
+                        uri.clone(),
+                        vec![insertion(&body_end, &methods.join("\n"))],
+                    )])),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }));
+        }
+        actions
+    }
+}
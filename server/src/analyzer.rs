@@ -0,0 +1,195 @@
+//! lspintar without LSP.
+//!
+//! [`Analyzer`] indexes a workspace and answers the questions an editor would ask
+//! of it (where a symbol is declared, what it is, what a file declares, what is
+//! wrong with it) for tools that embed the analysis instead of speaking to a
+//! server: batch indexers, CI checks, code search.
+//!
+//! ```no_run
+//! # async fn run() -> anyhow::Result<()> {
+//! use lspintar_server::analyzer::Analyzer;
+//! use tower_lsp::lsp_types::Position;
+//!
+//! let analyzer = Analyzer::open_workspace("/path/to/project").await?;
+//! let file = "/path/to/project/src/main/java/com/example/App.java";
+//! for location in analyzer.resolve(file, Position::new(12, 8)).await? {
+//!     println!("{} {:?}", location.uri, location.range.start);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The analyzer runs the same code as the server: it drives a [`Backend`] whose
+//! client is never listened to.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, anyhow};
+use tower_lsp::{
+    LanguageServer, LspService,
+    lsp_types::{
+        DocumentSymbol, GotoDefinitionParams, GotoDefinitionResponse, HoverContents, HoverParams,
+        InitializeParams, InitializedParams, Location, MarkedString, Position,
+        TextDocumentIdentifier, TextDocumentPositionParams, Url,
+    },
+};
+
+use crate::{check::FileDiagnostics, server::Backend};
+
+pub struct Analyzer {
+    backend: Backend,
+}
+
+impl Analyzer {
+    /// Indexes the workspace at `root`, its dependencies included, and returns once
+    /// it is ready for queries.  The index is kept under `root/.lspintar`, as the
+    /// server keeps it, and reused on the next open.
+    pub async fn open_workspace(root: impl AsRef<Path>) -> Result<Self> {
+        Self::open_workspace_with_options(root, serde_json::Value::Null).await
+    }
+
+    /// Like [`Analyzer::open_workspace`], with the settings a client would send as
+    /// initialization options, e.g. `{"project_root": "services/billing"}`.
+    pub async fn open_workspace_with_options(
+        root: impl AsRef<Path>,
+        options: serde_json::Value,
+    ) -> Result<Self> {
+        let root = root.as_ref().canonicalize()?;
+        let root_uri = Url::from_file_path(&root)
+            .map_err(|_| anyhow!("not an absolute path: {}", root.display()))?;
+        let (service, _socket) = LspService::new(Backend::new);
+        let backend = service.inner().clone();
+        backend
+            .initialize(InitializeParams {
+                root_uri: Some(root_uri),
+                initialization_options: (!options.is_null()).then_some(options),
+                ..Default::default()
+            })
+            .await
+            .map_err(|e| anyhow!("failed to open {}: {e}", root.display()))?;
+        backend.initialized(InitializedParams {}).await;
+        Ok(Self { backend })
+    }
+
+    /// The workspace root in use: the one opened, or the one the options chose.
+    pub async fn root(&self) -> Option<PathBuf> {
+        self.backend.workspace_root.read().await.clone()
+    }
+
+    /// Where the symbol at `position` in `file` is declared.  Several locations when
+    /// it is ambiguous, none when it does not resolve.  Declarations in libraries
+    /// point into sources extracted to the cache, or into decompiled classes.
+    pub async fn resolve(
+        &self,
+        file: impl AsRef<Path>,
+        position: Position,
+    ) -> Result<Vec<Location>> {
+        let params = GotoDefinitionParams {
+            text_document_position_params: position_params(file.as_ref(), position)?,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        let response = self
+            .backend
+            .goto_definition(params)
+            .await
+            .map_err(|e| anyhow!("{e}"))?;
+        Ok(match response {
+            None => vec![],
+            Some(GotoDefinitionResponse::Scalar(location)) => vec![location],
+            Some(GotoDefinitionResponse::Array(locations)) => locations,
+            Some(GotoDefinitionResponse::Link(links)) => links
+                .into_iter()
+                .map(|l| Location::new(l.target_uri, l.target_selection_range))
+                .collect(),
+        })
+    }
+
+    /// What the symbol at `position` in `file` is, as the Markdown an editor shows
+    /// on hover.
+    pub async fn hover(
+        &self,
+        file: impl AsRef<Path>,
+        position: Position,
+    ) -> Result<Option<String>> {
+        let params = HoverParams {
+            text_document_position_params: position_params(file.as_ref(), position)?,
+            work_done_progress_params: Default::default(),
+        };
+        let hover = self
+            .backend
+            .hover(params)
+            .await
+            .map_err(|e| anyhow!("{e}"))?;
+        Ok(hover.map(|h| match h.contents {
+            HoverContents::Markup(markup) => markup.value,
+            HoverContents::Scalar(marked) => marked_text(marked),
+            HoverContents::Array(marked) => marked
+                .into_iter()
+                .map(marked_text)
+                .collect::<Vec<_>>()
+                .join("\n\n"),
+        }))
+    }
+
+    /// The declarations in `file`, nested as in the editor's outline.
+    pub async fn symbols(&self, file: impl AsRef<Path>) -> Result<Vec<DocumentSymbol>> {
+        let uri = file_uri(file.as_ref())?;
+        Ok(self
+            .backend
+            .document_symbols(&uri)
+            .await
+            .unwrap_or_default())
+    }
+
+    /// The diagnostics of every indexed source file, by file, for those that have
+    /// any.  The same the server publishes for an open file.
+    pub async fn workspace_diagnostics(&self) -> Vec<FileDiagnostics> {
+        let Some(repo) = self.backend.repo.get() else {
+            return vec![];
+        };
+        let paths = repo.find_all_source_file_paths().await.unwrap_or_default();
+        let mut results = Vec::new();
+        for path in paths.into_iter().map(PathBuf::from) {
+            let Ok(uri) = Url::from_file_path(&path) else {
+                continue;
+            };
+            let diagnostics = self
+                .backend
+                .compute_diagnostics(&uri)
+                .await
+                .unwrap_or_default();
+            if !diagnostics.is_empty() {
+                results.push(FileDiagnostics { path, diagnostics });
+            }
+        }
+        results
+    }
+
+    /// The server behind the analyzer, for what the facade does not cover.
+    pub fn backend(&self) -> &Backend {
+        &self.backend
+    }
+}
+
+fn file_uri(file: &Path) -> Result<Url> {
+    Url::from_file_path(file).map_err(|_| anyhow!("not an absolute path: {}", file.display()))
+}
+
+fn position_params(file: &Path, position: Position) -> Result<TextDocumentPositionParams> {
+    Ok(TextDocumentPositionParams {
+        text_document: TextDocumentIdentifier {
+            uri: file_uri(file)?,
+        },
+        position,
+    })
+}
+
+fn marked_text(marked: MarkedString) -> String {
+    match marked {
+        MarkedString::String(text) => text,
+        MarkedString::LanguageString(code) => {
+            format!("```{}\n{}\n```", code.language, code.value)
+        }
+    }
+}
@@ -0,0 +1,843 @@
+//! Type checks for annotation attribute values.
+//!
+//! Each argument of an annotation usage is checked against the declared type of
+//! the element it sets, as indexed: the elements of a Java `@interface`, the
+//! parameters of a Kotlin `annotation class`, or the methods of a library
+//! annotation.  A positional argument sets `value`, or in Kotlin the parameter at
+//! its position.  Values are told apart by their syntax — string, class literal,
+//! enum constant, number, boolean, nested annotation, array — and a value of the
+//! wrong kind is reported as `annotation_attribute_type_mismatch`.  Braces make an
+//! array in Java but a closure in Groovy, which is not checked.  A single value
+//! stands for a one-element array.  Values whose kind the text does not tell,
+//! such as references to constants, are left alone; so are enum constants whose
+//! qualifier does not resolve to an enum.
+//!
+//! The element types drive completion and hover inside argument lists as well.
+//! In the value of an enum element, completion offers the enum's constants —
+//! qualified by the enum, or bare after `Qualifier.` — and hover on a constant
+//! there shows it even when the qualifier alone would not resolve.  Hover on an
+//! attribute name shows the element's declared type.
+
+use std::path::Path;
+
+use lsp_core::{
+    language_support::LanguageSupport,
+    languages::Language,
+    node_kind::NodeKind,
+    ts_helper::{byte_offset_to_position, position_to_byte_offset},
+    util::get_import_text_edit,
+};
+use tower_lsp::lsp_types::{
+    CompletionItem, CompletionItemKind, Diagnostic, DiagnosticSeverity, Hover, HoverContents,
+    MarkupContent, MarkupKind, NumberOrString, Position, Range, TextDocumentPositionParams,
+};
+use tree_sitter::Tree;
+
+use crate::{enum_constant::enum_constant_hover, lsp_convert::AsLspHover, server::Backend};
+
+/// The type an annotation element is declared with.
+#[derive(Debug, Clone, PartialEq)]
+enum ElementType {
+    String,
+    Class,
+    Boolean,
+    Number,
+    /// An enum, by FQN.
+    Enum(String),
+    Annotation,
+    Array(Box<ElementType>),
+    /// A type name not looked up yet.
+    Named(String),
+    /// A type values are not checked against.
+    Other,
+}
+
+/// The kind of an attribute value, as written.
+#[derive(Debug, Clone, PartialEq)]
+enum ValueKind {
+    String,
+    Class,
+    Boolean,
+    Number,
+    /// `Qualifier.CONSTANT`, or a bare `CONSTANT`.
+    EnumConstant { qualifier: Option<String> },
+    Annotation,
+    Array(Vec<AttributeValue>),
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct AttributeValue {
+    kind: ValueKind,
+    /// Byte range in the source.
+    start: usize,
+    end: usize,
+}
+
+struct AttributeArgument {
+    /// `None` for a positional argument.
+    name: Option<String>,
+    value: AttributeValue,
+}
+
+/// An annotation usage with arguments: the annotation name as written, and the
+/// arguments.
+struct AnnotationUsage {
+    name: String,
+    arguments: Vec<AttributeArgument>,
+}
+
+/// A declared annotation element.
+struct Element {
+    name: String,
+    /// The declared type as written, for messages.
+    declared: String,
+    element_type: ElementType,
+}
+
+/// The top-level pieces of `content[start..end]` separated by commas, as trimmed
+/// byte ranges.
+fn split_top_level(content: &str, start: usize, end: usize) -> Vec<(usize, usize)> {
+    let mut pieces = Vec::new();
+    let mut depth = 0usize;
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    let mut piece_start = start;
+    for (i, c) in content[start..end].char_indices() {
+        let at = start + i;
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' => quote = Some(c),
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                pieces.push((piece_start, at));
+                piece_start = at + 1;
+            }
+            _ => {}
+        }
+    }
+    pieces.push((piece_start, end));
+    pieces
+        .into_iter()
+        .filter_map(|(s, e)| {
+            let text = &content[s..e];
+            let trimmed = text.trim();
+            (!trimmed.is_empty()).then(|| {
+                let s = s + (text.len() - text.trim_start().len());
+                (s, s + trimmed.len())
+            })
+        })
+        .collect()
+}
+
+/// The byte offset of the `)` closing the `(` at `open`.
+fn matching_paren(content: &str, open: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    for (i, c) in content[open..].char_indices() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' => quote = Some(c),
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open + i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn is_constant_name(name: &str) -> bool {
+    name.chars().next().is_some_and(|c| c.is_ascii_uppercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+fn classify_value(content: &str, start: usize, end: usize, groovy: bool) -> AttributeValue {
+    let text = &content[start..end];
+    let array_elements = |open: usize, close: usize| {
+        split_top_level(content, open, close)
+            .into_iter()
+            .map(|(s, e)| classify_value(content, s, e, groovy))
+            .collect()
+    };
+    let is_array_of_call = text.ends_with(')')
+        && text
+            .split_once('(')
+            .is_some_and(|(callee, _)| callee == "arrayOf" || callee.ends_with("ArrayOf"));
+
+    let kind = if groovy && text.starts_with('{') {
+        // A closure, which Groovy compiles to a class: Spock's `@IgnoreIf({ ... })`.
+        ValueKind::Unknown
+    } else if (text.starts_with('{') && text.ends_with('}'))
+        || (text.starts_with('[') && text.ends_with(']'))
+    {
+        ValueKind::Array(array_elements(start + 1, end - 1))
+    } else if is_array_of_call {
+        ValueKind::Array(array_elements(start + text.find('(').unwrap_or(0) + 1, end - 1))
+    } else if text.starts_with('"') || (groovy && text.starts_with('\'')) {
+        ValueKind::String
+    } else if text.starts_with('\'') {
+        // A character is a number as far as annotation elements go.
+        ValueKind::Number
+    } else if text.starts_with('@') {
+        ValueKind::Annotation
+    } else if text.ends_with(".class") || text.ends_with("::class") {
+        ValueKind::Class
+    } else if text == "true" || text == "false" {
+        ValueKind::Boolean
+    } else if text
+        .trim_start_matches('-')
+        .starts_with(|c: char| c.is_ascii_digit())
+    {
+        ValueKind::Number
+    } else if text.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '.')
+        && is_constant_name(text.rsplit('.').next().unwrap_or(text))
+    {
+        ValueKind::EnumConstant {
+            qualifier: text.rsplit_once('.').map(|(q, _)| q.to_string()),
+        }
+    } else {
+        ValueKind::Unknown
+    };
+    AttributeValue { kind, start, end }
+}
+
+/// Splits `name = value` arguments; anything else is positional.
+fn parse_argument(content: &str, start: usize, end: usize, groovy: bool) -> AttributeArgument {
+    let text = &content[start..end];
+    if let Some(eq) = text.find('=')
+        && !text[eq + 1..].starts_with('=')
+    {
+        let name = text[..eq].trim();
+        if !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            let value = &text[eq + 1..];
+            let value_start = start + eq + 1 + (value.len() - value.trim_start().len());
+            return AttributeArgument {
+                name: Some(name.to_string()),
+                value: classify_value(content, value_start, end, groovy),
+            };
+        }
+    }
+    AttributeArgument {
+        name: None,
+        value: classify_value(content, start, end, groovy),
+    }
+}
+
+fn annotation_usages(tree: &Tree, content: &str, groovy: bool) -> Vec<AnnotationUsage> {
+    let mut usages = Vec::new();
+    let mut seen = Vec::new();
+    let mut stack = vec![tree.root_node()];
+    while let Some(node) = stack.pop() {
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor));
+
+        if !node.kind().contains("annotation") || seen.contains(&node.start_byte()) {
+            continue;
+        }
+        let Some(rest) = content[node.start_byte()..node.end_byte()].strip_prefix('@') else {
+            continue;
+        };
+        let name_len = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+            .unwrap_or(rest.len());
+        let name = &rest[..name_len];
+        let after = &rest[name_len..];
+        if name.is_empty() || name == "interface" || !after.trim_start().starts_with('(') {
+            continue;
+        }
+        let open = node.start_byte() + 1 + name_len + (after.len() - after.trim_start().len());
+        let Some(close) = matching_paren(content, open) else {
+            continue;
+        };
+        seen.push(node.start_byte());
+        usages.push(AnnotationUsage {
+            name: name.to_string(),
+            arguments: split_top_level(content, open + 1, close)
+                .into_iter()
+                .map(|(s, e)| parse_argument(content, s, e, groovy))
+                .collect(),
+        });
+    }
+    usages
+}
+
+/// Where an offset falls in the argument list of an annotation usage.
+#[derive(Debug, Clone, PartialEq)]
+struct ArgumentSite {
+    /// The annotation name as written.
+    annotation: String,
+    /// The attribute the argument sets, when it is named.
+    name: Option<String>,
+    /// The position of the argument in the list.
+    index: usize,
+    /// Whether the offset is on the attribute name rather than in the value.
+    on_name: bool,
+    /// The byte range of the identifier at the offset, possibly empty.
+    word: (usize, usize),
+    /// The dotted name before the identifier, e.g. `RequestMethod` in
+    /// `RequestMethod.GET`.
+    qualifier: Option<String>,
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// The argument of the annotation usage around `offset`, if any.
+fn argument_site(content: &str, offset: usize) -> Option<ArgumentSite> {
+    // The `(` opening the argument list: the first one before `offset` not closed
+    // before it.
+    let mut depth = 0usize;
+    let mut open = None;
+    for (i, c) in content[..offset].char_indices().rev() {
+        match c {
+            ')' | ']' | '}' => depth += 1,
+            '(' if depth == 0 => {
+                open = Some(i);
+                break;
+            }
+            '(' | '[' | '{' => depth = depth.saturating_sub(1),
+            ';' => return None,
+            _ => {}
+        }
+    }
+    let open = open?;
+
+    let before = content[..open].trim_end();
+    let name_start = before
+        .rfind(|c: char| !(is_name_char(c) || c == '.'))
+        .map_or(0, |i| i + 1);
+    if !before[..name_start].ends_with('@') || name_start == before.len() {
+        return None;
+    }
+    let annotation = before[name_start..].to_string();
+
+    let word_start = content[..offset]
+        .rfind(|c: char| !is_name_char(c))
+        .map_or(0, |i| i + 1);
+    let word_end = content[offset..]
+        .find(|c: char| !is_name_char(c))
+        .map_or(content.len(), |i| offset + i);
+
+    let written = &content[open + 1..offset];
+    let pieces = split_top_level(content, open + 1, offset);
+    let starts_new = written.trim().is_empty() || written.trim_end().ends_with(',');
+    let index = if starts_new {
+        pieces.len()
+    } else {
+        pieces.len().saturating_sub(1)
+    };
+
+    let after = content[word_end..].trim_start();
+    let on_name = after.starts_with('=') && !after.starts_with("==");
+    let name = if on_name {
+        Some(content[word_start..word_end].to_string())
+    } else if starts_new {
+        None
+    } else {
+        let (start, end) = *pieces.last()?;
+        parse_argument(content, start, end, false).name
+    };
+
+    let qualifier = content[..word_start].strip_suffix('.').map(|before| {
+        let start = before
+            .rfind(|c: char| !(is_name_char(c) || c == '.'))
+            .map_or(0, |i| i + 1);
+        before[start..].to_string()
+    });
+
+    Some(ArgumentSite {
+        annotation,
+        name,
+        index,
+        on_name,
+        word: (word_start, word_end),
+        qualifier: qualifier.filter(|q| !q.is_empty()),
+    })
+}
+
+fn parse_element_type(declared: &str) -> ElementType {
+    let declared = declared.trim().trim_end_matches('?');
+    if let Some(element) = declared.strip_suffix("[]") {
+        return ElementType::Array(Box::new(parse_element_type(element)));
+    }
+    if let Some(element) = declared
+        .strip_prefix("Array<")
+        .and_then(|rest| rest.strip_suffix('>'))
+    {
+        let element = element.trim().trim_start_matches("out ");
+        return ElementType::Array(Box::new(parse_element_type(element)));
+    }
+    let base = declared.split('<').next().unwrap_or(declared).trim();
+    match base.rsplit('.').next().unwrap_or(base) {
+        "String" => ElementType::String,
+        "Class" | "KClass" => ElementType::Class,
+        "boolean" | "Boolean" => ElementType::Boolean,
+        "byte" | "short" | "int" | "long" | "float" | "double" | "char" | "Byte" | "Short"
+        | "Int" | "Long" | "Float" | "Double" | "Char" => ElementType::Number,
+        "BooleanArray" => ElementType::Array(Box::new(ElementType::Boolean)),
+        "ByteArray" | "ShortArray" | "IntArray" | "LongArray" | "FloatArray"
+        | "DoubleArray" | "CharArray" => ElementType::Array(Box::new(ElementType::Number)),
+        _ => ElementType::Named(base.to_string()),
+    }
+}
+
+fn value_label(kind: &ValueKind) -> Option<&'static str> {
+    match kind {
+        ValueKind::String => Some("a string"),
+        ValueKind::Class => Some("a class literal"),
+        ValueKind::Boolean => Some("a boolean"),
+        ValueKind::Number => Some("a number"),
+        ValueKind::Annotation => Some("an annotation"),
+        ValueKind::Array(_) => Some("an array"),
+        ValueKind::EnumConstant { .. } | ValueKind::Unknown => None,
+    }
+}
+
+impl Backend {
+    /// Reports annotation arguments whose value does not fit the element's type.
+    pub(crate) async fn annotation_attribute_diagnostics(
+        &self,
+        tree: &Tree,
+        content: &str,
+        lang: &dyn LanguageSupport,
+    ) -> Vec<Diagnostic> {
+        let groovy = lang.get_language() == Language::Groovy;
+        let usages = annotation_usages(tree, content, groovy);
+        if usages.is_empty() {
+            return vec![];
+        }
+        let mut imports = lang.get_imports(tree, content);
+        imports.extend(lang.get_implicit_imports());
+        let package_name = lang.get_package_name(tree, content);
+
+        let mut diagnostics = Vec::new();
+        for usage in usages {
+            let Some(fqn) = self
+                .resolve_fqn(&usage.name, imports.clone(), package_name.clone())
+                .await
+            else {
+                continue;
+            };
+            let Some((elements, positional_names)) = self.annotation_elements(&fqn).await else {
+                continue;
+            };
+
+            for (index, argument) in usage.arguments.iter().enumerate() {
+                let name = match &argument.name {
+                    Some(name) => name.as_str(),
+                    None => positional_names
+                        .get(index)
+                        .map(String::as_str)
+                        .unwrap_or("value"),
+                };
+                let Some(element) = elements.iter().find(|e| e.name == name) else {
+                    continue;
+                };
+                let (expected, values): (&ElementType, Vec<&AttributeValue>) =
+                    match (&element.element_type, &argument.value.kind) {
+                        (ElementType::Array(inner), ValueKind::Array(values)) => {
+                            (inner.as_ref(), values.iter().collect())
+                        }
+                        (ElementType::Array(inner), _) => (inner.as_ref(), vec![&argument.value]),
+                        (expected, _) => (expected, vec![&argument.value]),
+                    };
+                for value in values {
+                    let Some(found) = self
+                        .value_mismatch(expected, value, &imports, package_name.clone())
+                        .await
+                    else {
+                        continue;
+                    };
+                    diagnostics.push(Diagnostic {
+                        range: Range::new(
+                            byte_offset_to_position(content, value.start),
+                            byte_offset_to_position(content, value.end),
+                        ),
+                        severity: Some(DiagnosticSeverity::ERROR),
+                        code: Some(NumberOrString::String(
+                            "annotation_attribute_type_mismatch".to_string(),
+                        )),
+                        source: Some("lspintar".to_string()),
+                        message: format!(
+                            "Incompatible value for '{}': expected {}, found {}",
+                            element.name, element.declared, found
+                        ),
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+        diagnostics
+    }
+
+    /// What `value` is when it cannot set an element of type `expected`; `None` when
+    /// it can, or when that cannot be told.
+    async fn value_mismatch(
+        &self,
+        expected: &ElementType,
+        value: &AttributeValue,
+        imports: &[String],
+        package_name: Option<String>,
+    ) -> Option<String> {
+        if matches!(expected, ElementType::Other | ElementType::Named(_)) {
+            return None;
+        }
+        if let ValueKind::EnumConstant { qualifier } = &value.kind {
+            let qualifier = qualifier.as_deref()?;
+            let fqn = self
+                .resolve_fqn(qualifier, imports.to_vec(), package_name)
+                .await?;
+            if self.named_element_type(&fqn, None).await != ElementType::Enum(fqn.clone()) {
+                return None;
+            }
+            return match expected {
+                ElementType::Enum(expected_fqn) if *expected_fqn == fqn => None,
+                _ => Some(format!("a constant of {qualifier}")),
+            };
+        }
+
+        let compatible = matches!(
+            (expected, &value.kind),
+            (ElementType::String, ValueKind::String)
+                | (ElementType::Class, ValueKind::Class)
+                | (ElementType::Boolean, ValueKind::Boolean)
+                | (ElementType::Number, ValueKind::Number)
+                | (ElementType::Annotation, ValueKind::Annotation)
+        );
+        if compatible {
+            return None;
+        }
+        value_label(&value.kind).map(str::to_string)
+    }
+
+    /// The elements of the annotation `fqn`, and the element names Kotlin positional
+    /// arguments map to.  `None` when the annotation is not indexed.
+    async fn annotation_elements(&self, fqn: &str) -> Option<(Vec<Element>, Vec<String>)> {
+        let repo = self.repo.get()?;
+        let mut elements = Vec::new();
+
+        if let Ok(Some(annotation)) = repo.find_symbol_by_fqn(fqn).await {
+            let is_annotation = annotation.symbol_type == "Annotation"
+                || annotation.modifiers.0.iter().any(|m| m == "annotation");
+            if !is_annotation {
+                return None;
+            }
+            let declared_in = Path::new(&annotation.file_path);
+            for member in repo.find_symbols_by_parent_name(fqn).await.ok()? {
+                let Some(declared) = member.metadata.return_type.clone() else {
+                    continue;
+                };
+                if !matches!(member.symbol_type.as_str(), "Function" | "Field") {
+                    continue;
+                }
+                let mut element_type = self
+                    .resolve_element_type(parse_element_type(&declared), Some(declared_in))
+                    .await;
+                if member.modifiers.0.iter().any(|m| m == "vararg") {
+                    element_type = ElementType::Array(Box::new(element_type));
+                }
+                elements.push(Element {
+                    name: member.short_name,
+                    declared,
+                    element_type,
+                });
+            }
+            let positional_names = annotation
+                .metadata
+                .parameters
+                .as_ref()
+                .map(|params| params.iter().map(|p| p.name.clone()).collect())
+                .unwrap_or_default();
+            return Some((elements, positional_names));
+        }
+
+        for member in repo.find_external_symbols_by_parent_name(fqn).await.ok()? {
+            let Some(declared) = member.metadata.return_type.clone() else {
+                continue;
+            };
+            if member.symbol_type != "Function" {
+                continue;
+            }
+            let element_type = self
+                .resolve_element_type(parse_element_type(&declared), None)
+                .await;
+            elements.push(Element {
+                name: member.short_name,
+                declared,
+                element_type,
+            });
+        }
+        (!elements.is_empty()).then_some((elements, vec![]))
+    }
+
+    /// Looks up the type names in `parsed`; names in project sources are resolved
+    /// against `declared_in`, library names are already qualified.
+    async fn resolve_element_type(
+        &self,
+        parsed: ElementType,
+        declared_in: Option<&Path>,
+    ) -> ElementType {
+        match parsed {
+            ElementType::Named(name) => self.named_element_type(&name, declared_in).await,
+            ElementType::Array(element) => match *element {
+                ElementType::Named(name) => ElementType::Array(Box::new(
+                    self.named_element_type(&name, declared_in).await,
+                )),
+                other => ElementType::Array(Box::new(other)),
+            },
+            other => other,
+        }
+    }
+
+    async fn named_element_type(&self, name: &str, declared_in: Option<&Path>) -> ElementType {
+        let Some(repo) = self.repo.get() else {
+            return ElementType::Other;
+        };
+        let fqn = match declared_in {
+            Some(path) => self.resolve_fqn_in_file(path, name).await,
+            None => Some(name.to_string()),
+        };
+        let Some(fqn) = fqn else {
+            return ElementType::Other;
+        };
+
+        let (symbol_type, is_annotation) = match repo.find_symbol_by_fqn(&fqn).await {
+            Ok(Some(symbol)) => {
+                let is_annotation = symbol.symbol_type == "Annotation"
+                    || symbol.modifiers.0.iter().any(|m| m == "annotation");
+                (symbol.symbol_type, is_annotation)
+            }
+            _ => match repo.find_external_symbol_by_fqn(&fqn).await {
+                Ok(Some(symbol)) => (symbol.symbol_type, false),
+                _ => return ElementType::Other,
+            },
+        };
+        match symbol_type.as_str() {
+            "Enum" => ElementType::Enum(fqn),
+            _ if is_annotation => ElementType::Annotation,
+            _ => ElementType::Other,
+        }
+    }
+
+    /// The element the argument at `site` sets, and the FQN of its annotation.
+    async fn site_element(
+        &self,
+        site: &ArgumentSite,
+        imports: &[String],
+        package_name: Option<String>,
+    ) -> Option<(Element, String)> {
+        let fqn = self
+            .resolve_fqn(&site.annotation, imports.to_vec(), package_name)
+            .await?;
+        let (elements, positional_names) = self.annotation_elements(&fqn).await?;
+        let name = match &site.name {
+            Some(name) => name.as_str(),
+            None => positional_names
+                .get(site.index)
+                .map(String::as_str)
+                .unwrap_or("value"),
+        };
+        let element = elements.into_iter().find(|e| e.name == name)?;
+        Some((element, fqn))
+    }
+
+    /// The constant names of the enum `fqn`: read from its file for a project enum,
+    /// from its static fields of its own type for a library one.
+    async fn enum_constant_names(&self, fqn: &str) -> Vec<String> {
+        let Some(repo) = self.repo.get() else {
+            return vec![];
+        };
+        if let Ok(Some(declaring)) = repo.find_symbol_by_fqn(fqn).await {
+            return self
+                .declared_enum_constants(&declaring)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|c| c.name)
+                .collect();
+        }
+        let short_name = fqn.rsplit('.').next().unwrap_or(fqn);
+        repo.find_external_symbols_by_parent_name(fqn)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|s| {
+                s.symbol_type == "Field"
+                    && is_constant_name(&s.short_name)
+                    && s.metadata
+                        .return_type
+                        .as_deref()
+                        .is_some_and(|t| t.rsplit('.').next() == Some(short_name))
+            })
+            .map(|s| s.short_name)
+            .collect()
+    }
+
+    /// Completion of the enum constants an annotation element takes, when
+    /// `position` is in the value of one.  `None` anywhere else, so regular
+    /// completion applies.
+    pub(crate) async fn annotation_value_completions(
+        &self,
+        content: &str,
+        position: &Position,
+        lang: &dyn LanguageSupport,
+        imports: &[String],
+        package_name: Option<String>,
+    ) -> Option<Vec<CompletionItem>> {
+        let offset = position_to_byte_offset(content, position);
+        let site = argument_site(content, offset)?;
+        if site.on_name {
+            return None;
+        }
+        let mut all_imports = imports.to_vec();
+        all_imports.extend(lang.get_implicit_imports());
+        let (element, _) = self
+            .site_element(&site, &all_imports, package_name.clone())
+            .await?;
+        let enum_fqn = match element.element_type {
+            ElementType::Enum(fqn) => fqn,
+            ElementType::Array(inner) => match *inner {
+                ElementType::Enum(fqn) => fqn,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        let (enum_package, short_name) = enum_fqn.rsplit_once('.').unwrap_or(("", &enum_fqn));
+        if let Some(qualifier) = &site.qualifier
+            && qualifier.rsplit('.').next() != Some(short_name)
+        {
+            return None;
+        }
+
+        let typed = &content[site.word.0..offset];
+        let needs_import = site.qualifier.is_none()
+            && !all_imports.contains(&enum_fqn)
+            && !all_imports.contains(&format!("{enum_package}.*"))
+            && package_name.as_deref().unwrap_or_default() != enum_package;
+        let items: Vec<CompletionItem> = self
+            .enum_constant_names(&enum_fqn)
+            .await
+            .into_iter()
+            .filter(|constant| {
+                constant.starts_with(typed)
+                    || (site.qualifier.is_none() && short_name.starts_with(typed))
+            })
+            .map(|constant| {
+                let label = match site.qualifier {
+                    Some(_) => constant,
+                    None => format!("{short_name}.{constant}"),
+                };
+                CompletionItem {
+                    label,
+                    kind: Some(CompletionItemKind::ENUM_MEMBER),
+                    detail: Some(enum_fqn.clone()),
+                    additional_text_edits: needs_import.then(|| {
+                        vec![get_import_text_edit(
+                            content,
+                            &enum_fqn,
+                            enum_package,
+                            enum_package,
+                            lang.get_language(),
+                        )]
+                    }),
+                    ..Default::default()
+                }
+            })
+            .collect();
+        (!items.is_empty()).then_some(items)
+    }
+
+    /// Hover on an attribute name or an enum constant value of an annotation usage.
+    /// `None` anywhere else, so regular hover applies.
+    pub(crate) async fn annotation_attribute_hover(
+        &self,
+        params: &TextDocumentPositionParams,
+    ) -> Option<Hover> {
+        let uri = &params.text_document.uri;
+        let path = uri.to_file_path().ok()?;
+        let lang = self.languages.get(path.extension()?.to_str()?)?;
+        let content = self.document_text(uri)?;
+        let offset = position_to_byte_offset(&content, &params.position);
+        let site = argument_site(&content, offset)?;
+        if site.word.0 == site.word.1 {
+            return None;
+        }
+        let (tree, _) = lang.parse_str(&content)?;
+        let mut imports = lang.get_imports(&tree, &content);
+        imports.extend(lang.get_implicit_imports());
+        let package_name = lang.get_package_name(&tree, &content);
+        let (element, annotation_fqn) = self.site_element(&site, &imports, package_name).await?;
+
+        if site.on_name {
+            return Some(Hover {
+                contents: HoverContents::Markup(MarkupContent {
+                    kind: MarkupKind::Markdown,
+                    value: format!(
+                        "```{}\n{} {}\n```\nElement of `@{annotation_fqn}`",
+                        lang.get_language(),
+                        element.declared,
+                        element.name
+                    ),
+                }),
+                range: Some(Range::new(
+                    byte_offset_to_position(&content, site.word.0),
+                    byte_offset_to_position(&content, site.word.1),
+                )),
+            });
+        }
+
+        let enum_fqn = match element.element_type {
+            ElementType::Enum(fqn) => fqn,
+            ElementType::Array(inner) => match *inner {
+                ElementType::Enum(fqn) => fqn,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        let constant = &content[site.word.0..site.word.1];
+        let repo = self.repo.get()?;
+        if let Ok(Some(declaring)) = repo.find_symbol_by_fqn(&enum_fqn).await {
+            if NodeKind::from_string(&declaring.symbol_type) != Some(NodeKind::Enum) {
+                return None;
+            }
+            let constants = self.declared_enum_constants(&declaring)?;
+            let (ordinal, data) = constants
+                .iter()
+                .enumerate()
+                .find(|(_, c)| c.name == constant)?;
+            return Some(enum_constant_hover(&declaring, data, ordinal));
+        }
+        let symbol = repo
+            .find_external_symbol_by_fqn(&format!("{enum_fqn}#{constant}"))
+            .await
+            .ok()??;
+        symbol.as_lsp_hover()
+    }
+}
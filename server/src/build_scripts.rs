@@ -0,0 +1,168 @@
+//! Workspace classes named in Gradle build scripts.
+//!
+//! Build scripts are not indexed as sources, yet they use the project's classes —
+//! custom task types from `buildSrc` above all: `tasks.register("docs", DocsTask)`,
+//! `import com.acme.DocsTask`.  Every script under the workspace is scanned for the
+//! type names it mentions outside comments and strings, and find-references and
+//! rename of a class take in the mentions that name it.  A mention names the class
+//! when it is qualified by the class's package, or the script imports the class or
+//! its package, or the class is in the default package, as `buildSrc` classes often
+//! are.
+//!
+//! The scan is textual, like that of task names (see [`crate::gradle_tasks`]), so
+//! it reads Groovy and Kotlin DSL scripts alike.
+
+use std::path::{Path, PathBuf};
+
+use lsp_core::ts_helper::byte_offset_to_position;
+use tower_lsp::lsp_types::{Range, Url};
+use walkdir::WalkDir;
+
+use crate::{
+    context::import_lines,
+    gradle_tasks::{is_gradle_script, words},
+    indexer::is_excluded,
+    server::Backend,
+};
+
+/// A type name in a build script, with the package it is written qualified by.
+/// `range` covers the name only.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeMention {
+    pub name: String,
+    pub qualifier: Option<String>,
+    pub range: Range,
+}
+
+/// The imports of a build script and the type names it mentions.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScriptReferences {
+    pub imports: Vec<String>,
+    pub mentions: Vec<TypeMention>,
+}
+
+impl ScriptReferences {
+    pub fn parse(content: &str) -> Self {
+        let imports = import_lines(content)
+            .iter()
+            .map(|import| import.path.to_string())
+            .collect();
+
+        let words = words(content);
+        let mut mentions = Vec::new();
+        for (i, (word, end)) in words.iter().enumerate() {
+            if !word.starts_with(char::is_uppercase) {
+                continue;
+            }
+            let start = end - word.len();
+            // `com.acme.DocsTask`: the lower-case words joined to it by dots.
+            let mut segments = Vec::new();
+            let mut at = start;
+            for (previous, previous_end) in words[..i].iter().rev() {
+                if !content[..at].ends_with('.')
+                    || *previous_end != at - 1
+                    || !previous.starts_with(char::is_lowercase)
+                {
+                    break;
+                }
+                segments.push(*previous);
+                at = previous_end - previous.len();
+            }
+            segments.reverse();
+            mentions.push(TypeMention {
+                name: word.to_string(),
+                qualifier: (!segments.is_empty()).then(|| segments.join(".")),
+                range: Range::new(
+                    byte_offset_to_position(content, start),
+                    byte_offset_to_position(content, *end),
+                ),
+            });
+        }
+        Self { imports, mentions }
+    }
+
+    /// Whether `mention` names the class `fqn` here.
+    fn names(&self, mention: &TypeMention, fqn: &str) -> bool {
+        let (package, name) = fqn.rsplit_once('.').unwrap_or(("", fqn));
+        if mention.name != name {
+            return false;
+        }
+        if let Some(qualifier) = &mention.qualifier {
+            return qualifier == package;
+        }
+        if package.is_empty() {
+            let suffix = format!(".{name}");
+            return !self.imports.iter().any(|i| i.ends_with(&suffix));
+        }
+        self.imports
+            .iter()
+            .any(|i| i == fqn || i.strip_suffix(".*") == Some(package))
+    }
+
+    /// The ranges of the mentions naming the class `fqn`.
+    pub fn usages(&self, fqn: &str) -> Vec<Range> {
+        self.mentions
+            .iter()
+            .filter(|m| self.names(m, fqn))
+            .map(|m| m.range)
+            .collect()
+    }
+}
+
+impl Backend {
+    /// Scans every build script under `root` for the types it mentions.
+    pub(crate) async fn index_build_scripts(&self, root: &Path) {
+        let root = root.to_path_buf();
+        let files = tokio::task::spawn_blocking(move || {
+            WalkDir::new(root)
+                .follow_links(true)
+                .into_iter()
+                .filter_entry(|e| !is_excluded(e))
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_type().is_file() && is_gradle_script(e.path()))
+                .filter_map(|e| {
+                    let content = std::fs::read_to_string(e.path()).ok()?;
+                    Some((e.into_path(), ScriptReferences::parse(&content)))
+                })
+                .collect::<Vec<_>>()
+        })
+        .await
+        .unwrap_or_default();
+
+        let mut index = self.build_scripts.write().await;
+        index.clear();
+        index.extend(files);
+    }
+
+    /// Re-reads one build script, dropping it from the index when it is gone, and
+    /// forgets the sub-project dependencies read from the build files.
+    pub(crate) async fn reindex_build_script(&self, path: &Path) {
+        self.project_graph.clear().await;
+        let mut index = self.build_scripts.write().await;
+        match std::fs::read_to_string(path) {
+            Ok(content) => {
+                index.insert(path.to_path_buf(), ScriptReferences::parse(&content));
+            }
+            Err(_) => {
+                index.remove(path);
+            }
+        }
+    }
+
+    /// The mentions of the class `fqn` in build scripts.
+    pub(crate) async fn build_script_class_usages(&self, fqn: &str) -> Vec<(Url, Range)> {
+        let index = self.build_scripts.read().await;
+        let mut files: Vec<(&PathBuf, &ScriptReferences)> = index.iter().collect();
+        files.sort_by(|a, b| a.0.cmp(b.0));
+        files
+            .into_iter()
+            .filter_map(|(path, references)| Some((Url::from_file_path(path).ok()?, references)))
+            .flat_map(|(uri, references)| {
+                references
+                    .usages(fqn)
+                    .into_iter()
+                    .map(move |range| (uri.clone(), range))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,241 @@
+//! `lspintar check <path>`: the workspace's diagnostics, without an editor.
+//!
+//! Indexes the workspace as the server does, computes the diagnostics of every
+//! indexed source file (unresolved imports and symbols, unused imports, duplicate
+//! classes, and the rest the server publishes) and prints them, readably or as
+//! SARIF for code-scanning tools.  Exits with a nonzero status when any is an
+//! error, so a CI job can gate on it.
+//!
+//! ```text
+//! lspintar check [--format human|sarif] [--warnings-as-errors] <path>
+//! ```
+
+use std::{
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use serde_json::{Value, json};
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString};
+
+use crate::{analyzer::Analyzer, constants::APP_VERSION};
+
+pub const CHECK_COMMAND: &str = "check";
+
+const USAGE: &str = "usage: lspintar check [--format human|sarif] [--warnings-as-errors] <path>";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CheckFormat {
+    #[default]
+    Human,
+    Sarif,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckOptions {
+    pub root: PathBuf,
+    pub format: CheckFormat,
+    /// Fail on warnings too, not only on errors.
+    pub warnings_as_errors: bool,
+}
+
+impl CheckOptions {
+    /// Parses the arguments following `check`.
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut root = None;
+        let mut format = CheckFormat::default();
+        let mut warnings_as_errors = false;
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--format" => {
+                    format = match args.next().map(String::as_str) {
+                        Some("human") => CheckFormat::Human,
+                        Some("sarif") => CheckFormat::Sarif,
+                        other => {
+                            return Err(format!("unknown format {}", other.unwrap_or("(none)")));
+                        }
+                    }
+                }
+                "--warnings-as-errors" => warnings_as_errors = true,
+                flag if flag.starts_with("--") => return Err(format!("unknown option {flag}")),
+                path if root.is_none() => root = Some(PathBuf::from(path)),
+                extra => return Err(format!("unexpected argument {extra}")),
+            }
+        }
+        Ok(Self {
+            root: root.ok_or("missing path")?,
+            format,
+            warnings_as_errors,
+        })
+    }
+}
+
+/// The diagnostics of one file.
+#[derive(Debug, Clone)]
+pub struct FileDiagnostics {
+    pub path: PathBuf,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Runs `lspintar check` with the arguments following `check`.
+pub async fn run(args: &[String]) -> ExitCode {
+    let options = match CheckOptions::parse(args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{e}\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    let analyzer = match Analyzer::open_workspace(&options.root).await {
+        Ok(analyzer) => analyzer,
+        Err(e) => {
+            eprintln!("cannot open {}: {e}", options.root.display());
+            return ExitCode::from(2);
+        }
+    };
+    let root = analyzer.root().await.unwrap_or(options.root.clone());
+    let results = analyzer.workspace_diagnostics().await;
+
+    match options.format {
+        CheckFormat::Human => print!("{}", render_human(&results, &root)),
+        CheckFormat::Sarif => println!(
+            "{}",
+            serde_json::to_string_pretty(&render_sarif(&results, &root)).unwrap_or_default()
+        ),
+    }
+
+    if fails(&results, options.warnings_as_errors) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Whether `results` should fail the check.
+pub fn fails(results: &[FileDiagnostics], warnings_as_errors: bool) -> bool {
+    results
+        .iter()
+        .flat_map(|file| &file.diagnostics)
+        .any(|d| match d.severity {
+            Some(DiagnosticSeverity::ERROR) => true,
+            Some(DiagnosticSeverity::WARNING) => warnings_as_errors,
+            _ => false,
+        })
+}
+
+/// One line per diagnostic, `path:line:column: severity[code]: message` with the
+/// path relative to `root` and 1-based positions, then a count.
+pub fn render_human(results: &[FileDiagnostics], root: &Path) -> String {
+    let mut out = String::new();
+    let (mut errors, mut warnings) = (0, 0);
+    for file in results {
+        let path = relative(&file.path, root);
+        for d in &file.diagnostics {
+            match d.severity {
+                Some(DiagnosticSeverity::ERROR) => errors += 1,
+                Some(DiagnosticSeverity::WARNING) => warnings += 1,
+                _ => {}
+            }
+            let code = code(d).map(|c| format!("[{c}]")).unwrap_or_default();
+            out.push_str(&format!(
+                "{path}:{}:{}: {}{code}: {}\n",
+                d.range.start.line + 1,
+                d.range.start.character + 1,
+                severity_name(d.severity),
+                d.message
+            ));
+        }
+    }
+    out.push_str(&format!(
+        "{errors} error{}, {warnings} warning{}\n",
+        if errors == 1 { "" } else { "s" },
+        if warnings == 1 { "" } else { "s" }
+    ));
+    out
+}
+
+/// A SARIF 2.1.0 log of `results`, with artifact URIs relative to `root`.
+pub fn render_sarif(results: &[FileDiagnostics], root: &Path) -> Value {
+    let mut rules: Vec<String> = results
+        .iter()
+        .flat_map(|file| &file.diagnostics)
+        .filter_map(code)
+        .collect();
+    rules.sort();
+    rules.dedup();
+
+    let sarif_results: Vec<Value> = results
+        .iter()
+        .flat_map(|file| {
+            let uri = relative(&file.path, root);
+            file.diagnostics.iter().map(move |d| {
+                let mut result = json!({
+                    "level": sarif_level(d.severity),
+                    "message": { "text": d.message },
+                    "locations": [{
+                        "physicalLocation": {
+                            "artifactLocation": { "uri": uri, "uriBaseId": "%SRCROOT%" },
+                            "region": {
+                                "startLine": d.range.start.line + 1,
+                                "startColumn": d.range.start.character + 1,
+                                "endLine": d.range.end.line + 1,
+                                "endColumn": d.range.end.character + 1,
+                            },
+                        },
+                    }],
+                });
+                if let Some(code) = code(d) {
+                    result["ruleId"] = json!(code);
+                }
+                result
+            })
+        })
+        .collect();
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "lspintar",
+                    "version": APP_VERSION,
+                    "rules": rules.iter().map(|id| json!({ "id": id })).collect::<Vec<_>>(),
+                },
+            },
+            "results": sarif_results,
+        }],
+    })
+}
+
+fn code(diagnostic: &Diagnostic) -> Option<String> {
+    match diagnostic.code.as_ref()? {
+        NumberOrString::String(code) => Some(code.clone()),
+        NumberOrString::Number(code) => Some(code.to_string()),
+    }
+}
+
+fn severity_name(severity: Option<DiagnosticSeverity>) -> &'static str {
+    match severity {
+        Some(DiagnosticSeverity::ERROR) => "error",
+        Some(DiagnosticSeverity::WARNING) => "warning",
+        Some(DiagnosticSeverity::HINT) => "hint",
+        _ => "info",
+    }
+}
+
+fn sarif_level(severity: Option<DiagnosticSeverity>) -> &'static str {
+    match severity {
+        Some(DiagnosticSeverity::ERROR) => "error",
+        Some(DiagnosticSeverity::WARNING) => "warning",
+        _ => "note",
+    }
+}
+
+fn relative(path: &Path, root: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
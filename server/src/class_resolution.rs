@@ -0,0 +1,203 @@
+//! Class resolution: the fully qualified name a simple type name in a file stands
+//! for.
+//!
+//! A name resolves through the file's single-type imports first, aliases
+//! included, then its wildcard imports, then its own package.  What a language
+//! imports without saying so comes in through
+//! [`LanguageSupport::get_implicit_imports`](lsp_core::language_support::LanguageSupport::get_implicit_imports),
+//! usually gathered by [`FileContext`](crate::context::FileContext).
+
+use std::{
+    collections::{HashMap, HashSet},
+    pin::pin,
+};
+
+use futures::{Stream, StreamExt, stream};
+use lsp_core::util::{imported_name, split_import_alias};
+
+use crate::{constants::WILDCARD_LOOKUP_CONCURRENCY, enums::ResolvedSymbol, server::Backend};
+
+impl Backend {
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn resolve_fqn(
+        &self,
+        name: &str,
+        imports: Vec<String>,
+        package_name: Option<String>,
+    ) -> Option<String> {
+        if name.contains('.') {
+            return Some(name.to_string());
+        }
+
+        // Direct import match, by alias for `import com.foo.Bar as Baz`
+        if let Some(import) = imports.iter().find(|i| imported_name(i) == name) {
+            return Some(split_import_alias(import).0.to_string());
+        }
+
+        // Wildcard import match
+        if let Some((fqn, _)) = pin!(self.wildcard_matches(name, &imports)).next().await {
+            return Some(fqn);
+        }
+
+        // Package + name fallback
+        let fallback_fqn = package_name
+            .map(|pkg| {
+                if !name.contains(&pkg) {
+                    format!("{}.{}", pkg, name)
+                } else {
+                    name.to_string()
+                }
+            })
+            .unwrap_or_else(|| name.to_string());
+
+        if let Ok(Some(_)) = self
+            .repo
+            .get()
+            .ok_or_else(tower_lsp::jsonrpc::Error::internal_error)
+            .ok()?
+            .find_external_symbol_by_fqn(&fallback_fqn)
+            .await
+        {
+            return Some(fallback_fqn);
+        }
+
+        Some(fallback_fqn)
+    }
+
+    /// Like `resolve_fqn` but returns `None` when the FQN is only a guess.
+    ///
+    /// Used exclusively for `unresolved_symbol` diagnostics where a false positive
+    /// (flagging a valid type as unresolved) is worse than a false negative.
+    ///
+    /// Rules:
+    /// - Already-qualified name (`foo.Bar`) → `Some` always (we trust it).
+    /// - Direct explicit import (`import foo.Bar`) → `Some` always (we trust it).
+    /// - Wildcard import (`import foo.*`) → `Some(foo.Bar)` only when verified in DB.
+    /// - Same-package fallback → `Some(pkg.Bar)` only when verified in project DB.
+    /// - Everything else → `None` (no emit, rather than false positive).
+    pub(crate) async fn resolve_fqn_strict(
+        &self,
+        name: &str,
+        imports: &[String],
+        package_name: Option<String>,
+    ) -> Option<String> {
+        if name.contains('.') {
+            return Some(name.to_string());
+        }
+
+        // Direct non-wildcard import — trust it; outer check will emit if absent from DB.
+        if let Some(import) = imports
+            .iter()
+            .find(|i| !i.ends_with(".*") && imported_name(i) == name)
+        {
+            return Some(split_import_alias(import).0.to_string());
+        }
+
+        let repo = self.repo.get()?;
+
+        // Wildcard import match — only return when DB-verified.
+        if let Some((fqn, _)) = pin!(self.wildcard_matches(name, imports)).next().await {
+            return Some(fqn);
+        }
+
+        // Same-package fallback — only return when found in project DB.
+        if let Some(pkg) = package_name {
+            let fallback = format!("{}.{}", pkg, name);
+            if repo
+                .find_symbol_by_fqn(&fallback)
+                .await
+                .ok()
+                .flatten()
+                .is_some()
+            {
+                return Some(fallback);
+            }
+        }
+
+        // Could not verify — suppress to avoid false positives.
+        None
+    }
+
+    /// Every type `name` may stand for through the file's wildcard imports, when
+    /// neither a single-type import nor a type of the file's own package decides it.
+    /// Only the imports written in the file count: an explicit `import com.foo.*`
+    /// shadows the language's implicit ones.  More than one entry means the reference
+    /// is ambiguous; entries are ordered by the configured
+    /// [`ImportPreferences`](crate::import_preferences::ImportPreferences).
+    pub(crate) async fn wildcard_candidates(
+        &self,
+        name: &str,
+        imports: &[String],
+        package_name: Option<&str>,
+    ) -> Vec<String> {
+        let Some(repo) = self.repo.get() else {
+            return vec![];
+        };
+        if name.contains('.')
+            || imports
+                .iter()
+                .any(|i| !i.ends_with(".*") && imported_name(i) == name)
+        {
+            return vec![];
+        }
+        if let Some(pkg) = package_name
+            && let Ok(Some(_)) = repo.find_symbol_by_fqn(&format!("{pkg}.{name}")).await
+        {
+            return vec![];
+        }
+
+        // (fqn, is_external)
+        let mut candidates: Vec<(String, bool)> =
+            self.wildcard_matches(name, imports).collect().await;
+        let mut seen = HashSet::new();
+        candidates.retain(|(fqn, _)| seen.insert(fqn.clone()));
+        let preferences = self.import_preferences.read().await;
+        let counts = HashMap::new();
+        candidates.sort_by(|a, b| preferences.compare((&a.0, a.1), (&b.0, b.1), &counts));
+        candidates.into_iter().map(|(fqn, _)| fqn).collect()
+    }
+
+    /// The types `name` stands for through each of the wildcard `imports` that has it
+    /// indexed, in import order, with whether the type is external.  The lookups run
+    /// [`WILDCARD_LOOKUP_CONCURRENCY`] at a time: Kotlin and Groovy files carry a
+    /// dozen implicit wildcard imports, and most of them miss.
+    fn wildcard_matches<'a>(
+        &'a self,
+        name: &'a str,
+        imports: &'a [String],
+    ) -> impl Stream<Item = (String, bool)> + 'a {
+        stream::iter(imports.iter().filter(|i| i.ends_with(".*")))
+            .map(move |import| async move {
+                let repo = self.repo.get()?;
+                let fqn = import.replace("*", name);
+                if let Ok(Some(_)) = repo.find_symbol_by_fqn(&fqn).await {
+                    return Some((fqn, false));
+                }
+                if let Ok(Some(_)) = repo.find_external_symbol_by_fqn(&fqn).await {
+                    return Some((fqn, true));
+                }
+                None
+            })
+            .buffered(WILDCARD_LOOKUP_CONCURRENCY)
+            .filter_map(|found| async move { found })
+    }
+
+    /// The declarations of every candidate when `name` is ambiguous between wildcard
+    /// imports, so go-to-definition lets the user pick.
+    pub(crate) async fn resolve_ambiguous_type(
+        &self,
+        name: &str,
+        imports: &[String],
+        package_name: Option<&str>,
+    ) -> Option<Vec<ResolvedSymbol>> {
+        let candidates = self.wildcard_candidates(name, imports, package_name).await;
+        if candidates.len() < 2 {
+            return None;
+        }
+        let mut symbols = Vec::new();
+        for fqn in candidates {
+            symbols.extend(self.fqn_to_symbols(fqn).await.unwrap_or_default());
+        }
+        Some(symbols)
+    }
+}
@@ -0,0 +1,73 @@
+//! Library symbols from the version a sub-project compiles against.
+//!
+//! Sub-projects of one build may depend on different versions of a library, and
+//! the index holds the classes of every version.  A name resolves to whichever
+//! the index returns first; [`Backend::on_file_classpath`] then swaps a library
+//! declaration that is not on the requesting file's classpath for the same
+//! declaration in the JAR that is.  Without a sub-project classpath, or when no
+//! JAR on it declares the name, the declaration found is kept.
+
+use std::path::Path;
+
+use crate::{enums::ResolvedSymbol, models::external_symbol::ExternalSymbol, server::Backend};
+
+/// The declaration among `candidates`, all named as `symbol` but from other JARs,
+/// standing for `symbol`: the overload with its parameters, else one with as many,
+/// else the first.  `symbol` itself when there are none.
+pub fn closest_version(symbol: ExternalSymbol, candidates: Vec<ExternalSymbol>) -> ExternalSymbol {
+    let parameters = symbol.metadata.parameters.clone();
+    let arity = parameters.as_ref().map_or(0, Vec::len);
+    let same_arity =
+        |c: &&ExternalSymbol| c.metadata.parameters.as_ref().map_or(0, Vec::len) == arity;
+    candidates
+        .iter()
+        .find(|c| c.metadata.parameters == parameters)
+        .or_else(|| candidates.iter().find(same_arity))
+        .or_else(|| candidates.first())
+        .cloned()
+        .unwrap_or(symbol)
+}
+
+impl Backend {
+    /// `symbols` with each library declaration taken from the JARs on `file`'s
+    /// classpath.
+    pub(crate) async fn on_file_classpath(
+        &self,
+        file: &Path,
+        symbols: Vec<ResolvedSymbol>,
+    ) -> Vec<ResolvedSymbol> {
+        let Some(repo) = self.repo.get() else {
+            return symbols;
+        };
+        let jar_paths = self.jar_paths_for_file(file).await;
+        if jar_paths.is_empty() {
+            return symbols;
+        }
+
+        let mut resolved = Vec::with_capacity(symbols.len());
+        for symbol in symbols {
+            let symbol = match symbol {
+                ResolvedSymbol::External(external) if !jar_paths.contains(&external.jar_path) => {
+                    let candidates = repo
+                        .find_external_symbols_by_fqn_and_jars(
+                            &external.fully_qualified_name,
+                            &jar_paths,
+                        )
+                        .await
+                        .unwrap_or_default();
+                    let external = closest_version(external, candidates);
+                    let duplicate = resolved
+                        .iter()
+                        .any(|r| matches!(r, ResolvedSymbol::External(e) if e.id == external.id));
+                    if duplicate {
+                        continue;
+                    }
+                    ResolvedSymbol::External(external)
+                }
+                other => other,
+            };
+            resolved.push(symbol);
+        }
+        resolved
+    }
+}
@@ -0,0 +1,533 @@
+//! `textDocument/codeAction` implementation.
+//!
+//! Quick fixes are computed together with the diagnostics they repair: a
+//! diagnostic that can be fixed mechanically carries a [`QuickFix`] in its
+//! `data` field, and the code action handler turns it back into a
+//! `WorkspaceEdit`.  The fix therefore always matches the tree the diagnostic
+//! pass saw, and semantic checks are not re-run per code action request.
+//!
+//! Add-import fixes for `unresolved_symbol` and `ambiguous_import` are the
+//! exception: their candidates depend on the file's classpath and on
+//! project-wide import statistics, so they are looked up only when the client
+//! asks for actions.  They are also offered on a capitalized name under the
+//! cursor that does not resolve, when an `unresolved_*` diagnostic covers the
+//! cursor but names something else.  So is moving a file to
+//! match its `package`, which is a file operation rather than an edit, and so are
+//! the stubs for `unimplemented_abstract_methods`, which may read a library's
+//! sources.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use lsp_core::util::get_import_text_edit;
+use serde::{Deserialize, Serialize};
+use tower_lsp::{
+    jsonrpc::Result,
+    lsp_types::{
+        CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionParams, CodeActionResponse,
+        Diagnostic, NumberOrString, Position, Range, TextDocumentIdentifier,
+        TextDocumentPositionParams, TextEdit, Url, WorkspaceEdit,
+    },
+};
+
+use crate::{
+    accessors::GENERATE_ACCESSORS_KIND, context::import_lines,
+    implement_members::OVERRIDE_METHODS_KIND, package_mismatch::PACKAGE_MISMATCH_CODE,
+    server::Backend, source_set::is_source_set_visible, type_alias::word_at,
+    unused::UNUSED_IMPORT_CODE,
+};
+
+/// A mechanical fix attached to an lspintar diagnostic's `data` field.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuickFix {
+    /// Title shown in the client's code action menu.
+    pub title: String,
+    /// Edits to apply to the document the diagnostic belongs to.
+    pub edits: Vec<TextEdit>,
+}
+
+impl QuickFix {
+    pub fn new<S: Into<String>>(title: S, edits: Vec<TextEdit>) -> Self {
+        Self {
+            title: title.into(),
+            edits,
+        }
+    }
+
+    /// Serializes the fix for `Diagnostic::data`.
+    pub fn to_data(&self) -> Option<serde_json::Value> {
+        serde_json::to_value(self).ok()
+    }
+
+    pub(crate) fn from_diagnostic(diagnostic: &Diagnostic) -> Option<Self> {
+        if diagnostic.source.as_deref() != Some("lspintar") {
+            return None;
+        }
+        serde_json::from_value(diagnostic.data.clone()?).ok()
+    }
+}
+
+impl Backend {
+    /// Entry point for `textDocument/codeAction`.  Offers one quick fix per
+    /// diagnostic in the request context that carries a [`QuickFix`], the
+    /// "Override" actions of the class around the cursor, the accessor
+    /// generation actions of the field under it and the "Extract constant"
+    /// actions of the selected literal.
+    ///
+    /// The "Override" actions walk every supertype and may read library sources,
+    /// so they are computed only when the client asks for `source` actions, not
+    /// for each lightbulb request.
+    pub async fn code_action_impl(
+        &self,
+        params: CodeActionParams,
+    ) -> Result<Option<CodeActionResponse>> {
+        let asks = |kind: &str| {
+            params
+                .context
+                .only
+                .as_ref()
+                .map(|only| only.iter().any(|k| kind.starts_with(k.as_str())))
+        };
+        let wants = |kind: &str| asks(kind).unwrap_or(true);
+
+        let uri = &params.text_document.uri;
+        let mut actions = CodeActionResponse::new();
+        if wants(CodeActionKind::QUICKFIX.as_str()) {
+            actions.extend(self.quick_fixes(uri, &params).await);
+        }
+        if asks(OVERRIDE_METHODS_KIND) == Some(true) {
+            actions.extend(self.override_actions(uri, params.range.start).await);
+        }
+        if wants(GENERATE_ACCESSORS_KIND) {
+            actions.extend(self.accessor_actions(uri, params.range.start).await);
+        }
+        if wants(CodeActionKind::REFACTOR_EXTRACT.as_str()) {
+            actions.extend(self.extract_constant_actions(uri, params.range));
+        }
+        if wants(CodeActionKind::SOURCE_ORGANIZE_IMPORTS.as_str()) {
+            actions.extend(self.organize_imports_action(uri));
+        }
+
+        if actions.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(actions))
+        }
+    }
+
+    /// The quick fixes for the diagnostics in the request context, the imports of
+    /// an unresolved name under the cursor inside an `unresolved_*` diagnostic and
+    /// the explicit type of a Groovy `def` declaration under it.
+    async fn quick_fixes(&self, uri: &Url, params: &CodeActionParams) -> CodeActionResponse {
+        let mut actions: CodeActionResponse = params
+            .context
+            .diagnostics
+            .iter()
+            .filter_map(|diagnostic| {
+                let fix = QuickFix::from_diagnostic(diagnostic)?;
+                Some(CodeActionOrCommand::CodeAction(CodeAction {
+                    title: fix.title,
+                    kind: Some(CodeActionKind::QUICKFIX),
+                    diagnostics: Some(vec![diagnostic.clone()]),
+                    edit: Some(WorkspaceEdit {
+                        changes: Some(HashMap::from([(uri.clone(), fix.edits)])),
+                        ..Default::default()
+                    }),
+                    is_preferred: Some(true),
+                    ..Default::default()
+                }))
+            })
+            .collect();
+
+        actions.extend(
+            self.implement_actions(uri, &params.context.diagnostics)
+                .await,
+        );
+
+        let mut imported_names: HashSet<String> = HashSet::new();
+        for diagnostic in &params.context.diagnostics {
+            if diagnostic.source.as_deref() == Some("lspintar")
+                && diagnostic.code == Some(NumberOrString::String("unresolved_symbol".to_string()))
+            {
+                // A qualified reference such as `Map.Entry` is fixed by importing its
+                // outer type.
+                if let Some(name) = self
+                    .document_text(uri)
+                    .as_deref()
+                    .and_then(|content| text_in_range(content, diagnostic.range))
+                    .and_then(|text| text.split('.').next())
+                {
+                    imported_names.insert(name.to_string());
+                    actions.extend(self.import_actions(uri, name, Some(diagnostic)).await);
+                }
+            }
+            if diagnostic.source.as_deref() == Some("lspintar")
+                && diagnostic.code == Some(NumberOrString::String("ambiguous_import".to_string()))
+            {
+                actions.extend(self.disambiguation_actions(uri, diagnostic).await);
+            }
+            if diagnostic.source.as_deref() == Some("lspintar")
+                && diagnostic.code
+                    == Some(NumberOrString::String(PACKAGE_MISMATCH_CODE.to_string()))
+            {
+                actions.extend(self.move_to_package_action(uri, diagnostic));
+            }
+        }
+
+        let cursor = params.range.start;
+        let unresolved_at_cursor = params
+            .context
+            .diagnostics
+            .iter()
+            .any(|d| is_unresolved(d) && d.range.start <= cursor && cursor <= d.range.end);
+        if unresolved_at_cursor
+            && let Some(name) = self.unresolved_name_at(uri, cursor).await
+            && !imported_names.contains(&name)
+        {
+            actions.extend(self.import_actions(uri, &name, None).await);
+        }
+        actions.extend(self.explicit_type_action(uri, params.range.start).await);
+
+        let unused_import = NumberOrString::String(UNUSED_IMPORT_CODE.to_string());
+        if params.context.diagnostics.iter().any(|d| {
+            d.source.as_deref() == Some("lspintar") && d.code.as_ref() == Some(&unused_import)
+        }) {
+            actions.extend(self.remove_unused_imports_action(uri));
+        }
+
+        for diagnostic in &params.context.diagnostics {
+            actions.extend(self.suppression_actions(uri, diagnostic));
+        }
+        actions
+    }
+
+    /// Offers one "Import" action per type named `name`, drawn from the project and
+    /// from the JARs on the file's classpath, ordered by the configured
+    /// [`ImportPreferences`](crate::import_preferences::ImportPreferences) and then by
+    /// how often the project already imports each.  Types declared in a source set
+    /// the file cannot see (test code, from main) are not offered.
+    async fn import_actions(
+        &self,
+        uri: &Url,
+        name: &str,
+        diagnostic: Option<&Diagnostic>,
+    ) -> Vec<CodeActionOrCommand> {
+        let path = PathBuf::from(uri.path());
+        let Some(lang) = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .and_then(|ext| self.languages.get(ext))
+        else {
+            return vec![];
+        };
+        let Some(repo) = self.repo.get() else {
+            return vec![];
+        };
+        let Some(content) = self.document_text(uri) else {
+            return vec![];
+        };
+        // (fqn, package, is_external)
+        let mut candidates: Vec<(String, String, bool)> = Vec::new();
+        for sym in repo
+            .find_types_by_short_name(name)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|sym| is_source_set_visible(&path, Path::new(&sym.file_path)))
+        {
+            candidates.push((sym.fully_qualified_name, sym.package_name, false));
+        }
+        let jar_paths = self.jar_paths_for_file(&path).await;
+        for sym in repo
+            .find_external_types_by_short_name_and_jars(name, &jar_paths)
+            .await
+            .unwrap_or_default()
+        {
+            // Nested classes from bytecode keep their binary `Outer$Inner` name.
+            if !sym.fully_qualified_name.contains('$') {
+                candidates.push((sym.fully_qualified_name, sym.package_name, true));
+            }
+        }
+        let mut seen = HashSet::new();
+        candidates.retain(|(fqn, _, _)| seen.insert(fqn.clone()));
+
+        let fqns: Vec<String> = candidates.iter().map(|(fqn, _, _)| fqn.clone()).collect();
+        let counts = self.import_counts(&fqns).await;
+        let preferences = self.import_preferences.read().await;
+        candidates.sort_by(|a, b| preferences.compare((&a.0, a.2), (&b.0, b.2), &counts));
+
+        candidates
+            .into_iter()
+            .enumerate()
+            .map(|(index, (fqn, package, _))| {
+                let edit =
+                    get_import_text_edit(&content, &fqn, &package, &package, lang.get_language());
+                CodeActionOrCommand::CodeAction(CodeAction {
+                    title: format!("Import '{fqn}'"),
+                    kind: Some(CodeActionKind::QUICKFIX),
+                    diagnostics: diagnostic.map(|d| vec![d.clone()]),
+                    edit: Some(WorkspaceEdit {
+                        changes: Some(HashMap::from([(uri.clone(), vec![edit])])),
+                        ..Default::default()
+                    }),
+                    is_preferred: Some(index == 0),
+                    ..Default::default()
+                })
+            })
+            .collect()
+    }
+
+    /// The capitalized name around `position` in `uri`, when it resolves to nothing.
+    async fn unresolved_name_at(&self, uri: &Url, position: Position) -> Option<String> {
+        let content = self.document_text(uri)?;
+        let name = word_at(&content, &position)?;
+        if !name.starts_with(|c: char| c.is_uppercase()) {
+            return None;
+        }
+        let params = TextDocumentPositionParams {
+            text_document: TextDocumentIdentifier { uri: uri.clone() },
+            position,
+        };
+        match self.resolve_symbol_at_position(&params).await {
+            Ok(symbols) if !symbols.is_empty() => None,
+            _ => Some(name),
+        }
+    }
+
+    /// Offers one "Import" action per type an `ambiguous_import` reference may mean
+    /// through the file's wildcard imports.  The explicit import decides it.
+    async fn disambiguation_actions(
+        &self,
+        uri: &Url,
+        diagnostic: &Diagnostic,
+    ) -> Vec<CodeActionOrCommand> {
+        let path = PathBuf::from(uri.path());
+        let Some(lang) = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .and_then(|ext| self.languages.get(ext))
+        else {
+            return vec![];
+        };
+        let Some(content) = self.document_text(uri) else {
+            return vec![];
+        };
+        let Some((tree, _)) = lang.parse_str(&content) else {
+            return vec![];
+        };
+        let Some(name) = text_in_range(&content, diagnostic.range) else {
+            return vec![];
+        };
+        let Some(repo) = self.repo.get() else {
+            return vec![];
+        };
+        let imports = lang.get_imports(&tree, &content);
+        let package = lang.get_package_name(&tree, &content);
+        let fqns = self
+            .wildcard_candidates(name, &imports, package.as_deref())
+            .await;
+
+        // (fqn, is_external)
+        let mut candidates = Vec::new();
+        for fqn in &fqns {
+            let external = !matches!(repo.find_symbol_by_fqn(fqn).await, Ok(Some(_)));
+            candidates.push((fqn.clone(), external));
+        }
+        let counts = self.import_counts(&fqns).await;
+        let preferences = self.import_preferences.read().await;
+        candidates.sort_by(|a, b| preferences.compare((&a.0, a.1), (&b.0, b.1), &counts));
+
+        candidates
+            .into_iter()
+            .map(|(fqn, _)| {
+                let package = fqn.rsplit_once('.').map_or("", |(pkg, _)| pkg);
+                let edit =
+                    get_import_text_edit(&content, &fqn, package, package, lang.get_language());
+                CodeActionOrCommand::CodeAction(CodeAction {
+                    title: format!("Import '{fqn}'"),
+                    kind: Some(CodeActionKind::QUICKFIX),
+                    diagnostics: Some(vec![diagnostic.clone()]),
+                    edit: Some(WorkspaceEdit {
+                        changes: Some(HashMap::from([(uri.clone(), vec![edit])])),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                })
+            })
+            .collect()
+    }
+
+    /// Counts, for each FQN, the project source files importing it either directly or
+    /// through a wildcard import of its package.  Skipped when there is nothing to rank;
+    /// the files not read yet, or re-indexed since, are read off the async runtime.
+    async fn import_counts(&self, fqns: &[String]) -> HashMap<String, usize> {
+        if fqns.len() < 2 {
+            return HashMap::new();
+        }
+        let Some(repo) = self.repo.get() else {
+            return HashMap::new();
+        };
+        let (files, all) = match self.project_imports.stale_files() {
+            Some(stale) => (stale, false),
+            None => (
+                repo.find_all_source_file_paths().await.unwrap_or_default(),
+                true,
+            ),
+        };
+        if !files.is_empty() {
+            let read = tokio::task::spawn_blocking(move || {
+                files
+                    .into_iter()
+                    .map(|file| {
+                        let imports = std::fs::read_to_string(&file)
+                            .map(|text| imported_types(&text))
+                            .unwrap_or_default();
+                        (file, imports)
+                    })
+                    .collect()
+            })
+            .await
+            .unwrap_or_default();
+            self.project_imports.store(read, all);
+        }
+        self.project_imports.counts(fqns)
+    }
+}
+
+/// The types and packages `content` imports, e.g. `com.acme.Foo` or `com.acme.*`.
+/// A static import counts as importing the type its member belongs to.
+fn imported_types(content: &str) -> HashSet<String> {
+    import_lines(content)
+        .into_iter()
+        .filter_map(|import| {
+            if import.is_static {
+                import.path.rsplit_once('.').map(|(ty, _)| ty.to_string())
+            } else {
+                Some(import.path.to_string())
+            }
+        })
+        .collect()
+}
+
+/// The imports of each project source file, for ranking "Import" candidates by how
+/// often the project already imports each.  Read on first use, then re-read per
+/// file as files are re-indexed.
+#[derive(Default)]
+pub(crate) struct ProjectImports {
+    state: Mutex<ImportsState>,
+}
+
+#[derive(Default)]
+struct ImportsState {
+    /// `None` until every file has been read.
+    files: Option<HashMap<String, HashSet<String>>>,
+    /// Files re-indexed since they were read.
+    stale: HashSet<String>,
+}
+
+impl ProjectImports {
+    fn state(&self) -> std::sync::MutexGuard<'_, ImportsState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// The files to read before counting, `None` when every file is.
+    fn stale_files(&self) -> Option<Vec<String>> {
+        let state = self.state();
+        state.files.as_ref()?;
+        Some(state.stale.iter().cloned().collect())
+    }
+
+    /// Records the imports `read` from each file, all of the project's when `all`.
+    fn store(&self, read: Vec<(String, HashSet<String>)>, all: bool) {
+        let mut state = self.state();
+        if all {
+            state.files = Some(HashMap::new());
+        }
+        for (file, imports) in read {
+            state.stale.remove(&file);
+            if let Some(files) = state.files.as_mut() {
+                files.insert(file, imports);
+            }
+        }
+    }
+
+    fn counts(&self, fqns: &[String]) -> HashMap<String, usize> {
+        let state = self.state();
+        let mut counts = HashMap::new();
+        for imports in state.files.iter().flat_map(|files| files.values()) {
+            for fqn in fqns {
+                let wildcard = fqn.rsplit_once('.').map(|(pkg, _)| format!("{pkg}.*"));
+                if imports.contains(fqn.as_str())
+                    || wildcard.is_some_and(|w| imports.contains(w.as_str()))
+                {
+                    *counts.entry(fqn.clone()).or_insert(0) += 1;
+                }
+            }
+        }
+        counts
+    }
+
+    /// Marks `path` to be read again, as it was re-indexed.
+    pub(crate) fn file_reindexed(&self, path: &Path) {
+        let mut state = self.state();
+        if state.files.is_some() {
+            state.stale.insert(path.to_string_lossy().to_string());
+        }
+    }
+
+    /// Forgets the imports of `path`, which is gone.
+    pub(crate) fn file_removed(&self, path: &Path) {
+        let path = path.to_string_lossy();
+        let mut state = self.state();
+        state.stale.remove(path.as_ref());
+        if let Some(files) = state.files.as_mut() {
+            files.remove(path.as_ref());
+        }
+    }
+
+    /// Forgets every file, for when the project is indexed anew.
+    pub(crate) fn clear(&self) {
+        *self.state() = ImportsState::default();
+    }
+}
+
+/// Whether `diagnostic` is one of ours reporting something that does not resolve
+/// (`unresolved_symbol`, `unresolved_import`, ...).
+fn is_unresolved(diagnostic: &Diagnostic) -> bool {
+    let Some(NumberOrString::String(code)) = &diagnostic.code else {
+        return false;
+    };
+    diagnostic.source.as_deref() == Some("lspintar") && code.starts_with("unresolved_")
+}
+
+/// Returns the text covered by a single-line `range`.
+fn text_in_range(content: &str, range: Range) -> Option<&str> {
+    if range.start.line != range.end.line {
+        return None;
+    }
+    content
+        .lines()
+        .nth(range.start.line as usize)?
+        .get(range.start.character as usize..range.end.character as usize)
+}
+
+/// Widens `range` to the whole lines it spans, including the trailing newline, so
+/// removing a declaration leaves no blank line behind.  Returns `None` when other
+/// code shares those lines, since deleting just the range could leave dangling
+/// separators.
+pub(crate) fn removal_range(content: &str, range: Range) -> Option<Range> {
+    let first = content.lines().nth(range.start.line as usize)?;
+    let last = content.lines().nth(range.end.line as usize)?;
+    let before = first.get(..range.start.character as usize)?;
+    let after = last.get(range.end.character as usize..)?;
+    if !before.trim().is_empty() || !after.trim().is_empty() {
+        return None;
+    }
+    Some(Range::new(
+        Position::new(range.start.line, 0),
+        Position::new(range.end.line + 1, 0),
+    ))
+}
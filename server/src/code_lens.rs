@@ -0,0 +1,210 @@
+//! `textDocument/codeLens` — how often a type is implemented or a method overridden.
+//!
+//! Interfaces, abstract and `open` classes get a lens reading "N implementations";
+//! abstract, `open` and interface methods one reading "N overrides".  An interface
+//! method with a body tells its overrides from the classes inheriting it unchanged:
+//! "1 override, 2 classes inherit the default".  Counting walks the inheritance
+//! index, so the lenses are listed without a title and counted in
+//! `codeLens/resolve`, only for those the editor shows.
+
+use std::path::PathBuf;
+
+use serde_json::{Value, json};
+use tower_lsp::{
+    jsonrpc::Result,
+    lsp_types::{CodeLens, CodeLensParams, Command, Position, Range, Url},
+};
+
+use crate::{
+    models::symbol::Symbol,
+    repo::Repository,
+    server::{Backend, declares_method_with_body},
+    supertypes::{descendant_tree, descendants, is_overridable, method_overrides},
+};
+
+const TYPE_LENS: &str = "type";
+const METHOD_LENS: &str = "method";
+
+fn has_modifier(symbol: &Symbol, modifier: &str) -> bool {
+    symbol.modifiers.0.iter().any(|m| m == modifier)
+}
+
+fn is_extensible_type(symbol: &Symbol) -> bool {
+    match symbol.symbol_type.as_str() {
+        "Interface" => true,
+        "Class" => has_modifier(symbol, "abstract") || has_modifier(symbol, "open"),
+        _ => false,
+    }
+}
+
+/// Whether `symbol` gets an overrides lens: an overridable method that is abstract,
+/// `open` or declared in an interface.
+fn is_overridable_method(symbol: &Symbol, in_interface: bool) -> bool {
+    is_overridable(symbol)
+        && (has_modifier(symbol, "abstract") || has_modifier(symbol, "open") || in_interface)
+}
+
+fn arity(symbol: &Symbol) -> usize {
+    symbol.metadata.parameters.as_ref().map_or(0, Vec::len)
+}
+
+fn ident_range(symbol: &Symbol) -> Range {
+    Range::new(
+        Position::new(
+            symbol.ident_line_start as u32,
+            symbol.ident_char_start as u32,
+        ),
+        Position::new(symbol.ident_line_end as u32, symbol.ident_char_end as u32),
+    )
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("1 {noun}")
+    } else {
+        format!("{count} {noun}s")
+    }
+}
+
+fn inheritors(count: usize) -> String {
+    if count == 1 {
+        "1 class inherits the default".to_string()
+    } else {
+        format!("{count} classes inherit the default")
+    }
+}
+
+/// "N overrides" of the method `parent#name` taking `expected` parameters.
+async fn count_overrides(repo: &Repository, parent: &str, name: &str, expected: usize) -> String {
+    let tree = descendant_tree(repo, parent).await;
+    let overrides = method_overrides(repo, &tree, name, expected).await;
+    plural(overrides.overriding.len(), "override")
+}
+
+/// The lenses of a file's declarations, unresolved.
+pub(crate) fn unresolved_lenses(symbols: &[Symbol]) -> Vec<CodeLens> {
+    let interfaces: Vec<&str> = symbols
+        .iter()
+        .filter(|s| s.symbol_type == "Interface")
+        .map(|s| s.fully_qualified_name.as_str())
+        .collect();
+
+    symbols
+        .iter()
+        .filter_map(|symbol| {
+            let data = if is_extensible_type(symbol) {
+                json!({ "kind": TYPE_LENS, "fqn": symbol.fully_qualified_name })
+            } else {
+                let in_interface = symbol
+                    .parent_name
+                    .as_deref()
+                    .is_some_and(|parent| interfaces.contains(&parent));
+                if !is_overridable_method(symbol, in_interface) {
+                    return None;
+                }
+                json!({
+                    "kind": METHOD_LENS,
+                    "parent": symbol.parent_name,
+                    "name": symbol.short_name,
+                    "arity": arity(symbol),
+                })
+            };
+            Some(CodeLens {
+                range: ident_range(symbol),
+                command: None,
+                data: Some(data),
+            })
+        })
+        .collect()
+}
+
+impl Backend {
+    /// Entry point for `textDocument/codeLens`.
+    pub async fn code_lens_impl(&self, params: CodeLensParams) -> Result<Option<Vec<CodeLens>>> {
+        let Some(repo) = self.repo.get() else {
+            return Ok(None);
+        };
+        let Ok(path) = params.text_document.uri.to_file_path() else {
+            return Ok(None);
+        };
+        let symbols = repo
+            .find_symbols_by_file_path(&path.to_string_lossy())
+            .await
+            .unwrap_or_default();
+        Ok(Some(unresolved_lenses(&symbols)))
+    }
+
+    /// Entry point for `codeLens/resolve`: counts the lens's implementations or
+    /// overrides.
+    pub async fn code_lens_resolve_impl(&self, mut lens: CodeLens) -> Result<CodeLens> {
+        let Some(repo) = self.repo.get() else {
+            return Ok(lens);
+        };
+        let data = lens.data.clone().unwrap_or(Value::Null);
+        let title = match data["kind"].as_str() {
+            Some(TYPE_LENS) => {
+                let Some(fqn) = data["fqn"].as_str() else {
+                    return Ok(lens);
+                };
+                plural(descendants(repo, fqn).await.len(), "implementation")
+            }
+            Some(METHOD_LENS) => {
+                let (Some(parent), Some(name)) = (data["parent"].as_str(), data["name"].as_str())
+                else {
+                    return Ok(lens);
+                };
+                let expected = data["arity"].as_u64().unwrap_or(0) as usize;
+                let declarations = repo
+                    .find_symbols_by_fqn(&format!("{parent}#{name}"))
+                    .await
+                    .unwrap_or_default();
+                let with_body = declarations
+                    .iter()
+                    .any(|d| arity(d) == expected && self.has_body(d));
+                if with_body
+                    && let Some(implementations) = self
+                        .default_method_implementations(parent, name, expected)
+                        .await
+                {
+                    format!(
+                        "{}, {}",
+                        plural(implementations.overriding.len(), "override"),
+                        inheritors(implementations.inheriting.len())
+                    )
+                } else {
+                    count_overrides(repo, parent, name, expected).await
+                }
+            }
+            _ => return Ok(lens),
+        };
+        lens.command = Some(Command {
+            title,
+            command: String::new(),
+            arguments: None,
+        });
+        Ok(lens)
+    }
+
+    /// True when the method `symbol` is declared with a body.
+    pub(crate) fn has_body(&self, symbol: &Symbol) -> bool {
+        let path = PathBuf::from(&symbol.file_path);
+        let Some(lang) = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .and_then(|ext| self.languages.get(ext))
+        else {
+            return false;
+        };
+        let Some(content) = Url::from_file_path(&path)
+            .ok()
+            .and_then(|uri| self.document_text(&uri))
+        else {
+            return false;
+        };
+        let Some((tree, _)) = lang.parse_str(&content) else {
+            return false;
+        };
+        let name = ident_range(symbol).start;
+        declares_method_with_body(&tree, &content, &name, &symbol.short_name, lang.as_ref())
+    }
+}
@@ -4,6 +4,20 @@ pub static CACHE_DIR: OnceLock<PathBuf> = OnceLock::new();
 const CFR_JAR: &[u8] = include_bytes!("../../vendor/cfr.jar");
 pub const MAX_LINE_COUNT: usize = 10_000;
 pub const FILE_CACHE_TTL_SECS: u64 = 30;
+/// Longest a request is held back waiting for its project to finish indexing (see
+/// [`crate::index_status::WhileIndexing::Wait`]) before it is answered anyway.
+pub const INDEXING_WAIT_TIMEOUT_SECS: u64 = 60;
+/// How often an instance reading an index another instance writes checks whether it
+/// can take over (see [`crate::db_lock`]).
+pub const INDEX_TAKEOVER_INTERVAL_SECS: u64 = 5;
+/// How often the Gradle cache is checked for new versions of the indexed
+/// dependencies (see [`crate::dependency_watch`]).
+pub const DEPENDENCY_WATCH_INTERVAL_SECS: u64 = 30;
+/// How many wildcard imports are looked up at once when resolving a simple name.
+pub const WILDCARD_LOOKUP_CONCURRENCY: usize = 8;
+/// How many files have their workspace diagnostics computed at once (see
+/// [`crate::workspace_diagnostics`]).
+pub const WORKSPACE_DIAGNOSTICS_CONCURRENCY: usize = 4;
 
 pub fn get_cache_dir() -> &'static PathBuf {
     CACHE_DIR.get_or_init(|| {
@@ -26,8 +40,33 @@ pub const CLASSPATH_MANIFEST_PATH_FRAGMENT: &str = ".lspintar/classpath.manifest
 pub const INDEX_PATH_FRAGMENT: &str = ".lspintar/index.version";
 pub const DB_PATH_FRAGMENT: &str = ".lspintar/index.db";
 pub const VCS_REVISION_PATH_FRAGMENT: &str = ".lspintar/vcs.revision";
+/// Path root the workspace's files are indexed relative to (see
+/// [`crate::repo::Repository::adopt_path_root`]).
+pub const WORKSPACE_PATH_ROOT: &str = "ROOT";
+/// Path root for the home directory, where the Gradle and Maven caches live.
+pub const HOME_PATH_ROOT: &str = "HOME";
+/// Index used in single-file mode, kept under [`get_cache_dir`].
+pub const SINGLE_FILE_DB_FILE: &str = "single-file.db";
 
 pub const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// `workspace/executeCommand` that re-resolves dependencies after build changes the
+/// file watcher missed.
+pub const REFRESH_DEPENDENCIES_COMMAND: &str = "lspintar.refreshDependencies";
+
+/// `workspace/executeCommand` returning the canonical reference of the symbol at a
+/// position, e.g. `com.foo.Bar#baz(String,int)`.
+pub const COPY_REFERENCE_COMMAND: &str = "lspintar.copyReference";
+
+/// `workspace/executeCommand` explaining how the definition at a position was
+/// resolved, or toggling trace mode without arguments (see
+/// [`crate::explain_resolution`]).
+pub const EXPLAIN_RESOLUTION_COMMAND: &str = "lspintar.explainResolution";
+
+/// `workspace/executeCommand` returning the `WorkspaceEdit` that organizes the
+/// imports of a file, as the `source.organizeImports` code action does (see
+/// [`crate::organize_imports`]).
+pub const ORGANIZE_IMPORTS_COMMAND: &str = "lspintar.organizeImports";
+
 pub const HOVER_MODIFIER_FILTER: [&str; 1] = ["override"];
 pub const HOVER_ANNOTATION_FILTER: [&str; 1] = ["Override"];
@@ -0,0 +1,83 @@
+//! Navigation from `this(...)` and `super(...)` constructor delegation.
+//!
+//! Constructors are not indexed, so the class delegated to is looked up first —
+//! the enclosing class for `this`, its superclass for `super` — and its file is
+//! then searched for the constructors it declares.  The ones taking as many
+//! parameters as the call passes arguments win; when none does, all are offered,
+//! and a class with only an implicit constructor resolves to the class itself.
+
+use std::{path::Path, sync::Arc};
+
+use lsp_core::language_support::{ConstructorData, ConstructorDelegationData, LanguageSupport};
+use tower_lsp::lsp_types::{Position, Url};
+use tree_sitter::Tree;
+
+use crate::{enums::ResolvedSymbol, models::symbol::Symbol, server::Backend};
+
+impl Backend {
+    /// The constructors `delegation` may call.  `None` when the class delegated to
+    /// is unknown.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn resolve_constructor_delegation(
+        &self,
+        delegation: &ConstructorDelegationData,
+        lang: &Arc<dyn LanguageSupport + Send + Sync>,
+        tree: &Tree,
+        content: &str,
+        position: &Position,
+        imports: &[String],
+        package_name: Option<String>,
+    ) -> Option<Vec<ResolvedSymbol>> {
+        let this_type = lang.find_variable_type(tree, content, "this", position)?;
+        let this_fqn = self
+            .resolve_fqn(&this_type, imports.to_vec(), package_name)
+            .await?;
+        let class_fqn = if delegation.is_super {
+            self.superclass_fqn(&this_fqn).await?
+        } else {
+            this_fqn
+        };
+
+        let classes = self.fqn_to_symbols(class_fqn).await.ok()?;
+        let Some(ResolvedSymbol::Project(class)) = classes.first() else {
+            return (!classes.is_empty()).then_some(classes);
+        };
+        let Some((uri, mut constructors)) = self.declared_constructors(class) else {
+            return Some(classes);
+        };
+        if constructors.is_empty() {
+            return Some(classes);
+        }
+        if constructors
+            .iter()
+            .any(|c| c.param_count == delegation.arg_count)
+        {
+            constructors.retain(|c| c.param_count == delegation.arg_count);
+        }
+        Some(
+            constructors
+                .into_iter()
+                .map(|c| ResolvedSymbol::Local {
+                    uri: uri.clone(),
+                    position: c.range.start,
+                    name: format!("{}{}", class.short_name, c.parameters),
+                    var_type: None,
+                })
+                .collect(),
+        )
+    }
+
+    /// The constructors `class` declares, read from its file (the open document when
+    /// there is one), with the file's URI.
+    fn declared_constructors(&self, class: &Symbol) -> Option<(Url, Vec<ConstructorData>)> {
+        let path = Path::new(&class.file_path);
+        let lang = self
+            .languages
+            .get(path.extension().and_then(|e| e.to_str())?)?;
+        let uri = Url::from_file_path(path).ok()?;
+        let content = self.document_text(&uri)?;
+        let (tree, _) = lang.parse_str(&content)?;
+        let constructors = lang.get_constructors(&tree, &content, &class.short_name);
+        Some((uri, constructors))
+    }
+}
@@ -0,0 +1,68 @@
+//! The context the names of a source file resolve in.
+
+use lsp_core::language_support::LanguageSupport;
+use tree_sitter::Tree;
+
+/// The imports and package of a source file.
+pub(crate) struct FileContext {
+    /// The imports the file declares, then the implicit imports of its language it
+    /// does not repeat.
+    pub imports: Vec<String>,
+    pub package_name: Option<String>,
+    declared: usize,
+}
+
+impl FileContext {
+    pub(crate) fn new(lang: &dyn LanguageSupport, tree: &Tree, content: &str) -> Self {
+        let mut imports = lang.get_imports(tree, content);
+        let declared = imports.len();
+        for import in lang.get_implicit_imports() {
+            if !imports.contains(&import) {
+                imports.push(import);
+            }
+        }
+        Self {
+            imports,
+            package_name: lang.get_package_name(tree, content),
+            declared,
+        }
+    }
+
+    /// The imports the file declares.
+    pub(crate) fn declared_imports(&self) -> &[String] {
+        &self.imports[..self.declared]
+    }
+}
+
+/// An import statement: its line, the imported path and the byte column it starts
+/// at, and whether it is static.  The path is e.g. `com.acme.Foo`,
+/// `com.acme.Foo.bar` or `com.acme.*`.
+pub(crate) struct ImportLine<'a> {
+    pub line: usize,
+    pub column: usize,
+    pub path: &'a str,
+    pub is_static: bool,
+}
+
+/// The import statements of `content`, read line by line.
+pub(crate) fn import_lines(content: &str) -> Vec<ImportLine<'_>> {
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(line, text)| {
+            let statement = text.strip_prefix("import ")?.trim_start();
+            let is_static = statement.starts_with("static ");
+            let statement = statement
+                .strip_prefix("static ")
+                .unwrap_or(statement)
+                .trim_start();
+            let path = statement.split([';', ' ', '/']).next()?;
+            (!path.is_empty()).then_some(ImportLine {
+                line,
+                column: text.len() - statement.len(),
+                path,
+                is_static,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,37 @@
+//! `lspintar.copyReference` — the canonical reference string of the symbol at a
+//! position, for pasting into docs, commit messages or test filters.
+//!
+//! Types give their FQN, members `Owner#member`, and methods their parameter
+//! types as well: `com.foo.Bar#baz(String,int)` (see
+//! [`build_reference`](crate::models::util::build_reference)).
+
+use serde_json::Value;
+use tower_lsp::{
+    jsonrpc::{Error, Result},
+    lsp_types::TextDocumentPositionParams,
+};
+
+use crate::server::Backend;
+
+impl Backend {
+    /// Handles `lspintar.copyReference`.  The single argument is the
+    /// `TextDocumentPositionParams` of the symbol; the result is its reference as a
+    /// JSON string, or `null` for locals and unresolved positions.
+    pub(crate) async fn copy_reference(&self, arguments: Vec<Value>) -> Result<Option<Value>> {
+        let params: TextDocumentPositionParams = arguments
+            .into_iter()
+            .next()
+            .and_then(|arg| serde_json::from_value(arg).ok())
+            .ok_or_else(|| Error::invalid_params("Expected a text document position"))?;
+
+        let Some(symbol) = self
+            .resolve_symbol_at_position(&params)
+            .await
+            .ok()
+            .and_then(|symbols| symbols.into_iter().next())
+        else {
+            return Ok(None);
+        };
+        Ok(symbol.signature().map(Value::String))
+    }
+}
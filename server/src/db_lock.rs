@@ -0,0 +1,134 @@
+//! Which server instance writes a shared index.
+//!
+//! Two editor windows on one workspace start two servers on the same
+//! `.lspintar/index.db`, and every single-file session shares one index.  The first
+//! instance takes an exclusive lock on the `.lock` file next to the database and
+//! becomes its writer.  Later ones are readers: they answer from the index the writer
+//! maintains but never index or write themselves, and keep trying the lock so one of
+//! them takes over when the writer exits.  What a reader saw change in the meantime
+//! is caught up on then, the same way files edited while no server ran are.  A
+//! reader does not migrate the database either; the instance taking over does.
+//!
+//! The OS releases the lock with the process holding it, so a writer that crashed
+//! never leaves it stale.  The PID written into the file only names the holder.
+
+use std::{
+    fs::{File, OpenOptions, TryLockError},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::atomic::Ordering,
+    time::Duration,
+};
+
+use lsp_core::lsp_info;
+use tracing::warn;
+
+use crate::{constants::INDEX_TAKEOVER_INTERVAL_SECS, repo::Repository, server::Backend};
+
+/// Exclusive write access to an index, held until dropped.
+#[derive(Debug)]
+pub struct IndexLock {
+    _file: File,
+}
+
+/// The lock file guarding the database at `db_path`.
+pub fn lock_path(db_path: &Path) -> PathBuf {
+    db_path.with_extension("lock")
+}
+
+impl IndexLock {
+    /// Takes the lock at `path`, or `None` while another instance holds it.
+    pub fn try_acquire(path: &Path) -> std::io::Result<Option<Self>> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        match file.try_lock() {
+            Ok(()) => {
+                file.set_len(0)?;
+                write!(file, "{}", std::process::id())?;
+                Ok(Some(Self { _file: file }))
+            }
+            Err(TryLockError::WouldBlock) => Ok(None),
+            Err(TryLockError::Error(e)) => Err(e),
+        }
+    }
+
+    /// The PID of the instance holding the lock at `path`, as it recorded it.
+    pub fn holder(path: &Path) -> Option<u32> {
+        std::fs::read_to_string(path).ok()?.trim().parse().ok()
+    }
+}
+
+impl Backend {
+    /// Whether another instance writes the index and this one must not.
+    pub fn is_index_reader(&self) -> bool {
+        self.index_reader.load(Ordering::Acquire)
+    }
+
+    /// Takes the index lock for the database at `db_path`, or becomes a reader when
+    /// another instance holds it.  An unusable lock file leaves this instance the
+    /// writer, as it was before locking existed.
+    pub(crate) fn claim_index(&self, db_path: &Path) {
+        let path = lock_path(db_path);
+        match IndexLock::try_acquire(&path) {
+            Ok(Some(lock)) => {
+                *self.index_lock.lock().unwrap_or_else(|e| e.into_inner()) = Some(lock);
+            }
+            Ok(None) => {
+                let holder = IndexLock::holder(&path)
+                    .map_or_else(|| "another process".to_string(), |pid| format!("process {pid}"));
+                warn!("{} is written by {holder}; opening it as a reader", db_path.display());
+                self.index_reader.store(true, Ordering::Release);
+            }
+            Err(e) => warn!("cannot lock {}: {e}", path.display()),
+        }
+    }
+
+    /// Opens the database at `db_path` as [`Backend::claim_index`] left this
+    /// instance: migrated as its writer, untouched as a reader.
+    pub(crate) async fn open_index(&self, db_path: &str) -> Result<Repository, sqlx::Error> {
+        if self.is_index_reader() {
+            Repository::new_reader(db_path).await
+        } else {
+            Repository::new(db_path).await
+        }
+    }
+
+    /// While a reader, retries the index lock every [`INDEX_TAKEOVER_INTERVAL_SECS`];
+    /// once it is free, becomes the writer and catches up on what changed meanwhile.
+    pub(crate) fn spawn_index_takeover(&self, db_path: PathBuf) {
+        if !self.is_index_reader() {
+            return;
+        }
+        let backend = self.clone();
+        tokio::spawn(async move {
+            let path = lock_path(&db_path);
+            loop {
+                tokio::time::sleep(Duration::from_secs(INDEX_TAKEOVER_INTERVAL_SECS)).await;
+                match IndexLock::try_acquire(&path) {
+                    Ok(Some(lock)) => {
+                        *backend.index_lock.lock().unwrap_or_else(|e| e.into_inner()) =
+                            Some(lock);
+                        backend.index_reader.store(false, Ordering::Release);
+                        lsp_info!("Took over writing {}", db_path.display());
+                        if let Some(repo) = backend.repo.get()
+                            && let Err(e) = repo.prepare_for_writing().await
+                        {
+                            warn!("cannot migrate {}: {e}", db_path.display());
+                        }
+                        backend.catch_up_index().await;
+                        return;
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        warn!("cannot lock {}: {e}", path.display());
+                        return;
+                    }
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,244 @@
+//! `dependency_conflict`: an artifact the sub-projects of a build resolve to
+//! different versions of, e.g. `:app` compiling against `slf4j-api` 2.0.9 and
+//! `:legacy` against 1.7.36.
+//!
+//! The versions come from each sub-project's resolved classpath, transitive
+//! dependencies included, as the JARs' places in the Gradle cache tell them.  The
+//! warning is reported in a build file on each string declaring the artifact, and
+//! hovering one lists which sub-projects resolve which version.  Sub-projects are
+//! named by their directory under the workspace root, as Gradle paths.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::Path,
+};
+
+use lsp_core::build_tools::gradle_cache::cached_coordinates;
+use tower_lsp::lsp_types::{
+    Diagnostic, DiagnosticSeverity, Hover, HoverContents, MarkupContent, MarkupKind,
+    NumberOrString, Position, Range, TextDocumentPositionParams, Url,
+};
+
+use crate::server::Backend;
+
+pub(crate) const DEPENDENCY_CONFLICT_CODE: &str = "dependency_conflict";
+
+/// An artifact resolved to more than one version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionConflict {
+    /// `group:name`.
+    pub artifact: String,
+    /// The sub-projects resolving each version, by version.
+    pub versions: BTreeMap<String, Vec<String>>,
+}
+
+/// The artifacts resolved to more than one version among `projects`, each a
+/// sub-project's name with the `(group, name, version)` of its dependencies.
+pub fn version_conflicts(
+    projects: &[(String, Vec<(String, String, String)>)],
+) -> Vec<VersionConflict> {
+    let mut by_artifact: BTreeMap<String, BTreeMap<String, Vec<String>>> = BTreeMap::new();
+    for (project, coordinates) in projects {
+        for (group, name, version) in coordinates {
+            let users = by_artifact
+                .entry(format!("{group}:{name}"))
+                .or_default()
+                .entry(version.clone())
+                .or_default();
+            if !users.contains(project) {
+                users.push(project.clone());
+            }
+        }
+    }
+    by_artifact
+        .into_iter()
+        .filter(|(_, versions)| versions.len() > 1)
+        .map(|(artifact, versions)| VersionConflict { artifact, versions })
+        .collect()
+}
+
+/// The ranges of the strings in a build file declaring `artifact`, e.g.
+/// `'org.slf4j:slf4j-api:2.0.9'`, without their quotes.
+pub fn declaration_ranges(content: &str, artifact: &str) -> Vec<Range> {
+    let mut ranges = Vec::new();
+    for (line_no, line) in content.lines().enumerate() {
+        let column = |byte: usize| line[..byte].encode_utf16().count() as u32;
+        for (start, _) in line.match_indices(artifact) {
+            let Some(quote) = line[..start]
+                .chars()
+                .last()
+                .filter(|c| matches!(c, '"' | '\''))
+            else {
+                continue;
+            };
+            let rest = &line[start + artifact.len()..];
+            if !rest.starts_with([':', quote]) {
+                continue;
+            }
+            let end = start + artifact.len() + rest.find(quote).unwrap_or(rest.len());
+            ranges.push(Range::new(
+                Position::new(line_no as u32, column(start)),
+                Position::new(line_no as u32, column(end)),
+            ));
+        }
+    }
+    ranges
+}
+
+/// The Gradle path of the sub-project in `dir`, e.g. `:libs:core`, or `:` for the
+/// root project.
+pub(crate) fn project_path(root: &Path, dir: &Path) -> String {
+    let segments: Vec<&str> = dir
+        .strip_prefix(root)
+        .unwrap_or(dir)
+        .components()
+        .filter_map(|c| c.as_os_str().to_str())
+        .collect();
+    format!(":{}", segments.join(":"))
+}
+
+/// The sub-project directory of a source directory: the one holding its `src`.
+fn project_dir(source_dir: &Path) -> Option<&Path> {
+    source_dir
+        .ancestors()
+        .find(|dir| dir.file_name().is_some_and(|name| name == "src"))?
+        .parent()
+}
+
+fn version_list(versions: &[(&String, &Vec<String>)]) -> String {
+    versions
+        .iter()
+        .map(|(version, projects)| format!("{version} in {}", projects.join(", ")))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+fn conflict_message(conflict: &VersionConflict, project: &str) -> String {
+    let (own, others): (Vec<_>, Vec<_>) = conflict
+        .versions
+        .iter()
+        .partition(|(_, projects)| projects.iter().any(|p| p == project));
+    if own.is_empty() {
+        return format!(
+            "{} resolves to different versions across projects: {}",
+            conflict.artifact,
+            version_list(&others)
+        );
+    }
+    let own: Vec<&str> = own.iter().map(|(version, _)| version.as_str()).collect();
+    format!(
+        "{} resolves to {} here but to {}",
+        conflict.artifact,
+        own.join(", "),
+        version_list(&others)
+    )
+}
+
+impl Backend {
+    async fn is_build_file(&self, path: &Path) -> bool {
+        self.build_tool
+            .read()
+            .await
+            .as_ref()
+            .is_some_and(|build_tool| build_tool.is_build_file(path))
+    }
+
+    /// The conflicting versions among the sub-projects' classpaths.
+    async fn version_conflicts(&self) -> Vec<VersionConflict> {
+        let Some(root) = self.workspace_root.read().await.clone() else {
+            return vec![];
+        };
+        let mut projects: BTreeMap<String, BTreeSet<(String, String, String)>> = BTreeMap::new();
+        for entry in self.subproject_classpath.read().await.iter() {
+            let Some(dir) = entry.source_dirs.iter().find_map(|d| project_dir(d)) else {
+                continue;
+            };
+            projects
+                .entry(project_path(&root, dir))
+                .or_default()
+                .extend(
+                    entry
+                        .jar_paths
+                        .iter()
+                        .filter_map(|jar| cached_coordinates(jar)),
+                );
+        }
+        let projects: Vec<(String, Vec<(String, String, String)>)> = projects
+            .into_iter()
+            .map(|(project, coordinates)| (project, coordinates.into_iter().collect()))
+            .collect();
+        version_conflicts(&projects)
+    }
+
+    /// The `dependency_conflict` warnings of the build file at `path`, `None` when it
+    /// is not one.
+    pub(crate) async fn dependency_conflict_diagnostics(
+        &self,
+        uri: &Url,
+        path: &Path,
+    ) -> Option<Vec<Diagnostic>> {
+        if !self.is_build_file(path).await {
+            return None;
+        }
+        let content = self.document_text(uri)?;
+        let root = self.workspace_root.read().await.clone()?;
+        let project = project_path(&root, path.parent()?);
+
+        let mut diagnostics = Vec::new();
+        for conflict in self.version_conflicts().await {
+            for range in declaration_ranges(&content, &conflict.artifact) {
+                diagnostics.push(Diagnostic {
+                    range,
+                    severity: Some(DiagnosticSeverity::WARNING),
+                    code: Some(NumberOrString::String(DEPENDENCY_CONFLICT_CODE.to_string())),
+                    source: Some("lspintar".to_string()),
+                    message: conflict_message(&conflict, &project),
+                    ..Default::default()
+                });
+            }
+        }
+        Some(diagnostics)
+    }
+
+    /// Hover on a build file's declaration of a conflicting artifact: which
+    /// sub-projects resolve which version.
+    pub(crate) async fn dependency_conflict_hover(
+        &self,
+        params: &TextDocumentPositionParams,
+    ) -> Option<Hover> {
+        let uri = &params.text_document.uri;
+        if !self.is_build_file(&uri.to_file_path().ok()?).await {
+            return None;
+        }
+        let content = self.document_text(uri)?;
+        let position = params.position;
+        for conflict in self.version_conflicts().await {
+            let Some(range) = declaration_ranges(&content, &conflict.artifact)
+                .into_iter()
+                .find(|r| r.start <= position && position <= r.end)
+            else {
+                continue;
+            };
+            let lines: Vec<String> = conflict
+                .versions
+                .iter()
+                .map(|(version, projects)| {
+                    let projects: Vec<String> = projects.iter().map(|p| format!("`{p}`")).collect();
+                    format!("- `{version}`: {}", projects.join(", "))
+                })
+                .collect();
+            return Some(Hover {
+                contents: HoverContents::Markup(MarkupContent {
+                    kind: MarkupKind::Markdown,
+                    value: format!(
+                        "**{}** resolves to different versions across projects:\n\n{}",
+                        conflict.artifact,
+                        lines.join("\n")
+                    ),
+                }),
+                range: Some(range),
+            });
+        }
+        None
+    }
+}
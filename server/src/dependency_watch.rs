@@ -0,0 +1,99 @@
+//! Re-indexing dependencies Gradle re-resolved.
+//!
+//! Bumping a version in a build file the client does not watch, or running Gradle
+//! from the terminal, downloads new JARs into the Gradle cache without the server
+//! hearing of it.  The writer instance checks the cache directories of the indexed
+//! dependencies every [`DEPENDENCY_WATCH_INTERVAL_SECS`]: when one of them gains a
+//! version, the dependencies are refreshed as on a build file change, so the new
+//! JARs are indexed and the symbols of those no longer resolved are dropped.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use lsp_core::{
+    build_tools::gradle_cache::{cached_module_dir, cached_versions},
+    lsp_info,
+};
+
+use crate::{
+    constants::{DEPENDENCY_WATCH_INTERVAL_SECS, MANIFEST_PATH_FRAGMENT},
+    server::Backend,
+};
+
+/// The cached versions of each indexed dependency, by its Gradle cache directory.
+pub type DependencySnapshot = HashMap<PathBuf, HashSet<String>>;
+
+/// The cached versions of the dependencies listed in the deps manifest of `root`.
+/// Dependencies outside the Gradle cache are left out.
+pub fn dependency_snapshot(root: &Path) -> DependencySnapshot {
+    let jars: Vec<(Option<PathBuf>, Option<PathBuf>)> =
+        std::fs::read(root.join(MANIFEST_PATH_FRAGMENT))
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default();
+    jars.iter()
+        .filter_map(|(jar, sources)| cached_module_dir(jar.as_ref().or(sources.as_ref())?))
+        .map(|dir| {
+            let versions = cached_versions(&dir);
+            (dir, versions)
+        })
+        .collect()
+}
+
+/// The dependencies with a version in `current` they had not in `previous`.
+/// Dependencies new to `current` are not counted: they are indexed already.
+pub fn modules_with_new_versions(
+    previous: &DependencySnapshot,
+    current: &DependencySnapshot,
+) -> Vec<PathBuf> {
+    current
+        .iter()
+        .filter(|(dir, versions)| {
+            previous
+                .get(*dir)
+                .is_some_and(|before| !versions.is_subset(before))
+        })
+        .map(|(dir, _)| dir.clone())
+        .collect()
+}
+
+impl Backend {
+    /// Checks the Gradle cache for new versions of `root`'s dependencies until the
+    /// server exits.  A reader skips the checks until it takes over writing.
+    pub(crate) fn spawn_dependency_watch(&self, root: PathBuf) {
+        let backend = self.clone();
+        tokio::spawn(async move {
+            let mut previous = snapshot(&root).await;
+            loop {
+                tokio::time::sleep(Duration::from_secs(DEPENDENCY_WATCH_INTERVAL_SECS)).await;
+                if backend.is_index_reader() {
+                    continue;
+                }
+                let current = snapshot(&root).await;
+                let changed = modules_with_new_versions(&previous, &current);
+                if changed.is_empty() {
+                    previous = current;
+                    continue;
+                }
+                lsp_info!(
+                    "New versions of {} cached dependencies, refreshing",
+                    changed.len()
+                );
+                if let Some((added, removed)) = backend.handle_build_file_changed(&root).await {
+                    lsp_info!("Dependencies refreshed: {added} added, {removed} removed");
+                }
+                previous = snapshot(&root).await;
+            }
+        });
+    }
+}
+
+async fn snapshot(root: &Path) -> DependencySnapshot {
+    let root = root.to_path_buf();
+    tokio::task::spawn_blocking(move || dependency_snapshot(&root))
+        .await
+        .unwrap_or_default()
+}
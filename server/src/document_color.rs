@@ -0,0 +1,154 @@
+//! `textDocument/documentColor` and `textDocument/colorPresentation` for color
+//! literals.
+//!
+//! Two spellings are recognised in Java and Kotlin sources: Compose's
+//! `Color(0xAARRGGBB)` and Android's `"#RRGGBB"` / `"#AARRGGBB"` strings.  Both
+//! put alpha first.  A picked color is written back in the spelling it replaces,
+//! upper-case, with the alpha byte dropped from a string when it is opaque.
+
+use lsp_core::ts_helper::{byte_offset_to_position, position_to_byte_offset};
+use tower_lsp::{
+    jsonrpc::Result,
+    lsp_types::{
+        Color, ColorInformation, ColorPresentation, ColorPresentationParams, DocumentColorParams,
+        Range, TextEdit,
+    },
+};
+
+use crate::server::Backend;
+
+const COLOR_CALL: &str = "Color(0x";
+
+fn is_color_file(path: &str) -> bool {
+    path.ends_with(".kt") || path.ends_with(".java")
+}
+
+/// The color of 8 hex digits in ARGB order, or 6 in RGB order (opaque).
+fn parse_hex(digits: &str) -> Option<Color> {
+    if !matches!(digits.len(), 6 | 8) || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let value = u32::from_str_radix(digits, 16).ok()?;
+    let byte = |shift: u32| ((value >> shift) & 0xFF) as f32 / 255.0;
+    Some(Color {
+        red: byte(16),
+        green: byte(8),
+        blue: byte(0),
+        alpha: if digits.len() == 8 { byte(24) } else { 1.0 },
+    })
+}
+
+fn to_byte(channel: f32) -> u8 {
+    (channel.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// Every color literal in `content`, with its byte range.
+fn color_literals(content: &str) -> Vec<(usize, usize, Color)> {
+    let mut literals = Vec::new();
+
+    let mut from = 0;
+    while let Some(found) = content[from..].find(COLOR_CALL) {
+        let start = from + found;
+        let digits_start = start + COLOR_CALL.len();
+        from = digits_start;
+        // `Color(` must not be the tail of a longer name such as `parseColor(`.
+        if content[..start]
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_')
+        {
+            continue;
+        }
+        let digits_len = content[digits_start..]
+            .find(|c: char| !c.is_ascii_hexdigit())
+            .unwrap_or(content.len() - digits_start);
+        let digits = &content[digits_start..digits_start + digits_len];
+        let rest = content[digits_start + digits_len..].trim_start_matches(['L', 'u', 'U']);
+        if !rest.starts_with(')') || digits.len() != 8 {
+            continue;
+        }
+        if let Some(color) = parse_hex(digits) {
+            let end = content.len() - rest.len() + 1;
+            literals.push((start, end, color));
+        }
+    }
+
+    let mut from = 0;
+    while let Some(found) = content[from..].find("\"#") {
+        let start = from + found;
+        from = start + 2;
+        let Some(close) = content[start + 2..].find('"') else {
+            break;
+        };
+        if let Some(color) = parse_hex(&content[start + 2..start + 2 + close]) {
+            literals.push((start, start + close + 3, color));
+        }
+    }
+
+    literals.sort_by_key(|(start, _, _)| *start);
+    literals
+}
+
+/// `color` spelled like `original`.
+fn present(color: &Color, original: &str) -> String {
+    let [a, r, g, b] = [color.alpha, color.red, color.green, color.blue].map(to_byte);
+    if original.starts_with('"') {
+        if a == 0xFF {
+            format!("\"#{r:02X}{g:02X}{b:02X}\"")
+        } else {
+            format!("\"#{a:02X}{r:02X}{g:02X}{b:02X}\"")
+        }
+    } else {
+        format!("Color(0x{a:02X}{r:02X}{g:02X}{b:02X})")
+    }
+}
+
+impl Backend {
+    /// Entry point for `textDocument/documentColor`.
+    pub async fn document_color_impl(
+        &self,
+        params: DocumentColorParams,
+    ) -> Result<Vec<ColorInformation>> {
+        let uri = &params.text_document.uri;
+        if !is_color_file(uri.path()) {
+            return Ok(vec![]);
+        }
+        let Some(content) = self.document_text(uri) else {
+            return Ok(vec![]);
+        };
+        Ok(color_literals(&content)
+            .into_iter()
+            .map(|(start, end, color)| ColorInformation {
+                range: Range::new(
+                    byte_offset_to_position(&content, start),
+                    byte_offset_to_position(&content, end),
+                ),
+                color,
+            })
+            .collect())
+    }
+
+    /// Entry point for `textDocument/colorPresentation`.
+    pub async fn color_presentation_impl(
+        &self,
+        params: ColorPresentationParams,
+    ) -> Result<Vec<ColorPresentation>> {
+        let original = self
+            .document_text(&params.text_document.uri)
+            .and_then(|content| {
+                let start = position_to_byte_offset(&content, &params.range.start);
+                let end = position_to_byte_offset(&content, &params.range.end);
+                content.get(start..end).map(str::to_string)
+            })
+            .unwrap_or_default();
+        let text = present(&params.color, &original);
+        Ok(vec![ColorPresentation {
+            label: text.clone(),
+            text_edit: Some(TextEdit {
+                range: params.range,
+                new_text: text,
+            }),
+            additional_text_edits: None,
+        }])
+    }
+}
@@ -0,0 +1,161 @@
+//! `textDocument/documentLink` — imports as links to what they import.
+//!
+//! The name in each single-type import links to the file declaring it: the
+//! project source, or the library source or decompiled class extracted to the
+//! cache.  Extracting is slow, so a library import's target is only filled in by
+//! `documentLink/resolve`, when the link is followed.  Static imports link to
+//! their class, nested classes to the file of their outermost one.  Wildcard and
+//! unresolved imports are not links.
+
+use serde_json::json;
+use tower_lsp::{
+    jsonrpc::Result,
+    lsp_types::{DocumentLink, DocumentLinkParams, Position, Range, Url},
+};
+use tree_sitter::{Node, Tree};
+
+use crate::{lsp_convert::AsLspLocation, repo::Repository, server::Backend};
+
+/// The import declarations of a file: top-level, or grouped in Kotlin's
+/// `import_list`.
+fn imports<'a>(tree: &'a Tree) -> Vec<Node<'a>> {
+    let root = tree.root_node();
+    let mut cursor = root.walk();
+    let mut imports = Vec::new();
+    for child in root.children(&mut cursor) {
+        match child.kind() {
+            "import_declaration" | "import_header" => imports.push(child),
+            "import_list" => {
+                let mut cursor = child.walk();
+                imports.extend(
+                    child
+                        .children(&mut cursor)
+                        .filter(|n| n.kind() == "import_header"),
+                );
+            }
+            _ => {}
+        }
+    }
+    imports
+}
+
+fn node_range(node: &Node) -> Range {
+    let (start, end) = (node.start_position(), node.end_position());
+    Range::new(
+        Position::new(start.row as u32, start.column as u32),
+        Position::new(end.row as u32, end.column as u32),
+    )
+}
+
+/// The imported names of the single-type imports in `tree`, with their ranges.
+fn imported_names(tree: &Tree, content: &str) -> Vec<(String, Range)> {
+    imports(tree)
+        .into_iter()
+        .filter_map(|import| {
+            let text = import.utf8_text(content.as_bytes()).ok()?;
+            if text.trim_end_matches(';').trim_end().ends_with('*') {
+                return None;
+            }
+            let mut cursor = import.walk();
+            let name = import
+                .named_children(&mut cursor)
+                .find(|n| matches!(n.kind(), "scoped_identifier" | "identifier"))?;
+            let fqn: String = name
+                .utf8_text(content.as_bytes())
+                .ok()?
+                .split_whitespace()
+                .collect();
+            Some((fqn, node_range(&name)))
+        })
+        .collect()
+}
+
+/// `fqn`, then each enclosing name down to the package, for imports of members and
+/// nested classes.
+fn candidate_fqns(fqn: &str) -> impl Iterator<Item = &str> {
+    std::iter::successors(Some(fqn), |name| {
+        name.rsplit_once('.').map(|(outer, _)| outer)
+    })
+    .filter(|name| name.contains('.'))
+}
+
+impl Backend {
+    /// Entry point for `textDocument/documentLink`.
+    pub async fn document_link_impl(
+        &self,
+        params: DocumentLinkParams,
+    ) -> Result<Option<Vec<DocumentLink>>> {
+        let uri = &params.text_document.uri;
+        let Some(ext) = uri.path().rsplit_once('.').map(|(_, ext)| ext) else {
+            return Ok(None);
+        };
+        let Some(lang) = self.languages.get(ext) else {
+            return Ok(None);
+        };
+        let Some(repo) = self.repo.get() else {
+            return Ok(None);
+        };
+        let parsed = match self.document_text(uri) {
+            Some(content) => lang.parse_str(&content),
+            None => uri.to_file_path().ok().and_then(|path| lang.parse(&path)),
+        };
+        let Some((tree, content)) = parsed else {
+            return Ok(None);
+        };
+
+        let mut links = Vec::new();
+        for (fqn, range) in imported_names(&tree, &content) {
+            if let Some(link) = import_link(repo, &fqn, range).await {
+                links.push(link);
+            }
+        }
+        Ok(Some(links))
+    }
+
+    /// Entry point for `documentLink/resolve`: extracts the library class a link
+    /// points to.
+    pub async fn document_link_resolve_impl(&self, mut link: DocumentLink) -> Result<DocumentLink> {
+        let Some(repo) = self.repo.get() else {
+            return Ok(link);
+        };
+        let Some(fqn) = link
+            .data
+            .as_ref()
+            .and_then(|data| data["fqn"].as_str().map(str::to_string))
+        else {
+            return Ok(link);
+        };
+        if let Ok(Some(symbol)) = repo.find_external_symbol_by_fqn(&fqn).await {
+            link.target = tokio::task::spawn_blocking(move || symbol.as_lsp_location())
+                .await
+                .ok()
+                .flatten()
+                .map(|location| location.uri);
+        }
+        Ok(link)
+    }
+}
+
+/// The link for an import of `fqn`: resolved for project types, left to
+/// `documentLink/resolve` for library ones.
+async fn import_link(repo: &Repository, fqn: &str, range: Range) -> Option<DocumentLink> {
+    for candidate in candidate_fqns(fqn) {
+        if let Ok(Some(symbol)) = repo.find_symbol_by_fqn(candidate).await {
+            return Some(DocumentLink {
+                range,
+                target: Url::from_file_path(&symbol.file_path).ok(),
+                tooltip: Some(candidate.to_string()),
+                data: None,
+            });
+        }
+        if let Ok(Some(_)) = repo.find_external_symbol_by_fqn(candidate).await {
+            return Some(DocumentLink {
+                range,
+                target: None,
+                tooltip: Some(candidate.to_string()),
+                data: Some(json!({ "fqn": candidate })),
+            });
+        }
+    }
+    None
+}
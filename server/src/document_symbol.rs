@@ -0,0 +1,300 @@
+//! `textDocument/documentSymbol` — the outline of a source file: its types, with
+//! their methods, fields and nested types beneath them.
+//!
+//! Built from the document's own tree with the declaration queries the indexer uses,
+//! so it follows unsaved edits and does not wait for the index.
+//!
+//! Groovy files are mostly DSL: a Gradle script is `plugins { }`, `dependencies { }`
+//! and `tasks.register("x") { }`, a Jenkinsfile is nested `stage("Build") { }`, a
+//! Spock feature is `given:`/`when:`/`then:` blocks.  Those are outlined too, each
+//! named by its label, or by the method taking the closure when it has none.
+
+use std::path::Path;
+
+use lsp_core::{
+    language_support::LanguageSupport,
+    languages::Language,
+    node_kind::{NodeKind, Placement},
+};
+use tower_lsp::lsp_types::{DocumentSymbol, Position, Range, SymbolKind, Url};
+use tree_sitter::Node;
+
+use crate::server::Backend;
+
+/// The labels splitting a Spock feature method into blocks.
+const SPOCK_BLOCK_LABELS: &[&str] = &[
+    "setup", "given", "when", "then", "expect", "cleanup", "where", "and",
+];
+
+impl Backend {
+    /// The declarations of `uri`, nested by enclosing type.
+    pub async fn document_symbols(&self, uri: &Url) -> Option<Vec<DocumentSymbol>> {
+        let path = uri.to_file_path().ok()?;
+        let lang = self.languages.get(outline_language(&path)?)?;
+        let content = self.document_text(uri)?;
+        let (tree, _) = lang.parse_str(&content)?;
+        Some(outline(
+            tree.root_node(),
+            lang.as_ref(),
+            &content,
+            Placement::default(),
+        ))
+    }
+}
+
+/// The key in `languages` to outline `path` with.  Gradle scripts and Jenkinsfiles
+/// are outlined as Groovy, though build scripts are not indexed as sources.
+fn outline_language(path: &Path) -> Option<&str> {
+    if path.file_name()? == "Jenkinsfile" {
+        return Some("groovy");
+    }
+    match path.extension()?.to_str()? {
+        "gradle" => Some("groovy"),
+        ext => Some(ext),
+    }
+}
+
+/// The declarations below `node`, each holding those declared inside it.  `enclosing`
+/// is the innermost type around `node`, to tell constructors and enum constants apart.
+fn outline(
+    node: Node,
+    lang: &dyn LanguageSupport,
+    content: &str,
+    enclosing: Placement,
+) -> Vec<DocumentSymbol> {
+    let is_groovy = lang.get_language() == Language::Groovy;
+    let mut symbols = Vec::new();
+    let mut cursor = node.walk();
+    let children: Vec<Node> = node.named_children(&mut cursor).collect();
+    let mut i = 0;
+    while i < children.len() {
+        let child = children[i];
+        i += 1;
+        if is_groovy && let Some(label) = spock_label(&child, content) {
+            // A block runs until the next label, its statements following it as siblings.
+            let end = children[i..]
+                .iter()
+                .position(|c| spock_label(c, content).is_some())
+                .map_or(children.len(), |n| i + n);
+            let mut block = label;
+            let mut inner = outline(child, lang, content, enclosing);
+            for sibling in &children[i..end] {
+                inner.extend(outline(*sibling, lang, content, enclosing));
+            }
+            if let Some(last) = children[i..end].last() {
+                block.range.end = node_range(last).end;
+            }
+            block.children = (!inner.is_empty()).then_some(inner);
+            symbols.push(block);
+            i = end;
+            continue;
+        }
+        if is_groovy && let Some((mut block, closure)) = dsl_block(&child, content) {
+            let inner = outline(closure, lang, content, enclosing);
+            block.children = (!inner.is_empty()).then_some(inner);
+            symbols.push(block);
+            continue;
+        }
+        let Some(mut symbol) = declaration(&child, lang, content, enclosing) else {
+            symbols.extend(outline(child, lang, content, enclosing));
+            continue;
+        };
+        let inner_kind = match symbol.kind {
+            SymbolKind::CLASS | SymbolKind::OBJECT => Some(NodeKind::Class),
+            SymbolKind::INTERFACE => Some(NodeKind::Interface),
+            SymbolKind::ENUM => Some(NodeKind::Enum),
+            _ => None,
+        };
+        let inner = match &inner_kind {
+            Some(kind) => Placement {
+                parent: Some(symbol.name.as_str()),
+                parent_kind: Some(kind),
+            },
+            None => enclosing,
+        };
+        let children = outline(child, lang, content, inner);
+        symbol.children = (!children.is_empty()).then_some(children);
+        symbols.push(symbol);
+    }
+    symbols
+}
+
+fn declaration(
+    node: &Node,
+    lang: &dyn LanguageSupport,
+    content: &str,
+    enclosing: Placement,
+) -> Option<DocumentSymbol> {
+    if let Some(symbol) = enum_constant(node, content).or_else(|| kotlin_object(node, content)) {
+        return Some(symbol);
+    }
+    if !lang.should_index(node, content) {
+        return None;
+    }
+    let kind = lang.get_kind(node)?;
+    let name = lang.get_short_name(node, content)?;
+    let range = lang.get_range(node)?;
+    let selection_range = lang.get_ident_range(node)?;
+
+    // Enum constants are outlined from their own nodes, so no field is taken for one.
+    let symbol_kind = kind.symbol_kind(&name, None, enclosing);
+    let detail = match kind {
+        NodeKind::Function | NodeKind::Field => lang.get_return(node, content),
+        _ => None,
+    };
+
+    #[allow(deprecated)]
+    Some(DocumentSymbol {
+        name,
+        detail,
+        kind: symbol_kind,
+        tags: None,
+        deprecated: None,
+        range,
+        selection_range,
+        children: None,
+    })
+}
+
+/// An enum constant, which the indexer leaves to its enum.
+fn enum_constant(node: &Node, content: &str) -> Option<DocumentSymbol> {
+    let name = match node.kind() {
+        "enum_constant" => node.child_by_field_name("name")?,
+        "enum_entry" => {
+            let mut cursor = node.walk();
+            node.named_children(&mut cursor)
+                .find(|c| matches!(c.kind(), "identifier" | "simple_identifier"))?
+        }
+        _ => return None,
+    };
+    Some(plain_symbol(
+        name.utf8_text(content.as_bytes()).ok()?.to_string(),
+        SymbolKind::ENUM_MEMBER,
+        node,
+        &name,
+    ))
+}
+
+/// A Kotlin `object` or companion object, which the indexer does not list as a type.
+/// An unnamed companion is named `Companion`, as Kotlin names it.
+fn kotlin_object(node: &Node, content: &str) -> Option<DocumentSymbol> {
+    if !matches!(node.kind(), "object_declaration" | "companion_object") {
+        return None;
+    }
+    let (name, selection) = match node.child_by_field_name("name") {
+        Some(name) => (name.utf8_text(content.as_bytes()).ok()?.to_string(), name),
+        None if node.kind() == "companion_object" => ("Companion".to_string(), *node),
+        None => return None,
+    };
+    Some(plain_symbol(name, SymbolKind::OBJECT, node, &selection))
+}
+
+fn plain_symbol(name: String, kind: SymbolKind, node: &Node, selection: &Node) -> DocumentSymbol {
+    #[allow(deprecated)]
+    DocumentSymbol {
+        name,
+        detail: None,
+        kind,
+        tags: None,
+        deprecated: None,
+        range: node_range(node),
+        selection_range: node_range(selection),
+        children: None,
+    }
+}
+
+/// A call configured by a trailing closure, e.g. `dependencies { }`,
+/// `stage("Build") { }` or `tasks.register("jar") { }`, with the closure.  A call on
+/// a receiver counts only when a string names it, so `items.each { }` does not.
+fn dsl_block<'a>(node: &Node<'a>, content: &str) -> Option<(DocumentSymbol, Node<'a>)> {
+    if node.kind() != "method_invocation" {
+        return None;
+    }
+    let name = node.child_by_field_name("name")?;
+    let arguments = node.child_by_field_name("arguments");
+    let closure = node.child_by_field_name("closure").or_else(|| {
+        let arguments = arguments?;
+        let mut cursor = arguments.walk();
+        arguments
+            .named_children(&mut cursor)
+            .find(|n| n.kind() == "closure")
+    })?;
+    let label = arguments.and_then(|arguments| {
+        let mut cursor = arguments.walk();
+        arguments
+            .named_children(&mut cursor)
+            .next()
+            .filter(|n| matches!(n.kind(), "string_literal" | "gstring"))
+    });
+    if label.is_none() && node.child_by_field_name("object").is_some() {
+        return None;
+    }
+
+    let callee = content.get(node.start_byte()..name.end_byte())?;
+    let (symbol_name, detail, selection) = match label {
+        Some(label) => (
+            unquote(label.utf8_text(content.as_bytes()).ok()?),
+            Some(callee.to_string()),
+            label,
+        ),
+        None => (callee.to_string(), None, name),
+    };
+
+    #[allow(deprecated)]
+    let symbol = DocumentSymbol {
+        name: symbol_name,
+        detail,
+        kind: SymbolKind::NAMESPACE,
+        tags: None,
+        deprecated: None,
+        range: node_range(node),
+        selection_range: node_range(&selection),
+        children: None,
+    };
+    Some((symbol, closure))
+}
+
+/// A Spock block label such as `when:` or `given: "a signed-in user"`, its
+/// description as detail.
+fn spock_label(node: &Node, content: &str) -> Option<DocumentSymbol> {
+    if node.kind() != "labeled_statement" {
+        return None;
+    }
+    let mut cursor = node.walk();
+    let mut children = node.named_children(&mut cursor);
+    let label = children.next().filter(|n| n.kind() == "identifier")?;
+    let name = label.utf8_text(content.as_bytes()).ok()?;
+    if !SPOCK_BLOCK_LABELS.contains(&name) {
+        return None;
+    }
+    let detail = children
+        .next()
+        .map(|statement| statement.named_child(0).unwrap_or(statement))
+        .filter(|n| matches!(n.kind(), "string_literal" | "gstring"))
+        .and_then(|n| n.utf8_text(content.as_bytes()).ok())
+        .map(unquote);
+
+    #[allow(deprecated)]
+    Some(DocumentSymbol {
+        name: name.to_string(),
+        detail,
+        kind: SymbolKind::KEY,
+        tags: None,
+        deprecated: None,
+        range: node_range(node),
+        selection_range: node_range(&label),
+        children: None,
+    })
+}
+
+fn unquote(literal: &str) -> String {
+    literal.trim_matches(|c| c == '"' || c == '\'').to_string()
+}
+
+fn node_range(node: &Node) -> Range {
+    let (start, end) = (node.start_position(), node.end_position());
+    Range::new(
+        Position::new(start.row as u32, start.column as u32),
+        Position::new(end.row as u32, end.column as u32),
+    )
+}
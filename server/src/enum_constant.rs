@@ -0,0 +1,134 @@
+//! Hover for enum constants.
+//!
+//! Enum constants are not indexed, so a hover on one is answered from the file of
+//! the enum declaring it: `Level.FULL` names its enum, an unqualified `FULL` is
+//! looked for among the enums of the current file and then among static imports.
+//! The hover shows the enum, the constant's ordinal — its position among the
+//! enum's constants — and the arguments it passes to the enum's constructor.
+
+use std::path::Path;
+
+use lsp_core::{language_support::EnumConstantData, node_kind::NodeKind, qualifier::SegmentKind};
+use tower_lsp::lsp_types::{
+    Hover, HoverContents, MarkupContent, MarkupKind, TextDocumentPositionParams, Url,
+};
+
+use crate::{models::symbol::Symbol, server::Backend};
+
+pub(crate) fn enum_constant_hover(
+    declaring: &Symbol,
+    constant: &EnumConstantData,
+    ordinal: usize,
+) -> Hover {
+    let keyword = NodeKind::Enum
+        .keyword(&declaring.file_type)
+        .unwrap_or("enum");
+    let arguments = if constant.arguments.is_empty() {
+        String::new()
+    } else {
+        format!("({})", constant.arguments.join(", "))
+    };
+    let mut parts = vec![format!("```{}", declaring.file_type)];
+    if !declaring.package_name.is_empty() {
+        parts.push(format!("package {}", declaring.package_name));
+        parts.push(String::new());
+    }
+    parts.push(format!("{keyword} {}", declaring.short_name));
+    parts.push(format!("    {}{arguments}", constant.name));
+    parts.push("```".to_string());
+    parts.push(format!("Ordinal: {ordinal}"));
+    Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: parts.join("\n"),
+        }),
+        range: None,
+    }
+}
+
+impl Backend {
+    /// Hover for the enum constant at the request position.  `None` anywhere else,
+    /// so regular hover applies.
+    pub(crate) async fn enum_constant_hover(
+        &self,
+        params: &TextDocumentPositionParams,
+    ) -> Option<Hover> {
+        let uri = &params.text_document.uri;
+        let path = uri.to_file_path().ok()?;
+        let lang = self.languages.get(path.extension()?.to_str()?)?;
+        let content = self.document_text(uri)?;
+        let (tree, _) = lang.parse_str(&content)?;
+        let (ident, qualifier) = lang.find_ident_at_position(&tree, &content, &params.position)?;
+
+        let mut imports = lang.get_imports(&tree, &content);
+        imports.extend(lang.get_implicit_imports());
+        let package_name = lang.get_package_name(&tree, &content);
+        let repo = self.repo.get()?;
+
+        let candidates: Vec<Symbol> = match qualifier {
+            Some(qualifier) => {
+                if qualifier
+                    .segments
+                    .iter()
+                    .any(|s| !matches!(s.kind, SegmentKind::Variable | SegmentKind::Type))
+                {
+                    return None;
+                }
+                let name = qualifier
+                    .segments
+                    .iter()
+                    .map(|s| s.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(".");
+                let fqn = self.resolve_fqn(&name, imports, package_name).await?;
+                repo.find_symbol_by_fqn(&fqn)
+                    .await
+                    .ok()?
+                    .into_iter()
+                    .collect()
+            }
+            None => {
+                let mut enums = repo
+                    .find_symbols_by_file_path(&path.to_string_lossy())
+                    .await
+                    .ok()?;
+                // `import static com.foo.Level.FULL` / `import com.foo.Level.FULL`
+                let imported = self.resolve_fqn(&ident, imports, package_name).await;
+                if let Some((enum_fqn, _)) = imported.as_deref().and_then(|f| f.rsplit_once('.'))
+                    && let Ok(Some(symbol)) = repo.find_symbol_by_fqn(enum_fqn).await
+                {
+                    enums.push(symbol);
+                }
+                enums
+            }
+        };
+
+        for declaring in candidates {
+            if NodeKind::from_string(&declaring.symbol_type) != Some(NodeKind::Enum) {
+                continue;
+            }
+            let Some(constants) = self.declared_enum_constants(&declaring) else {
+                continue;
+            };
+            if let Some((ordinal, constant)) =
+                constants.iter().enumerate().find(|(_, c)| c.name == ident)
+            {
+                return Some(enum_constant_hover(&declaring, constant, ordinal));
+            }
+        }
+        None
+    }
+
+    /// The constants `declaring` declares, read from its file (the open document when
+    /// there is one).
+    fn declared_enum_constants(&self, declaring: &Symbol) -> Option<Vec<EnumConstantData>> {
+        let path = Path::new(&declaring.file_path);
+        let lang = self
+            .languages
+            .get(path.extension().and_then(|e| e.to_str())?)?;
+        let uri = Url::from_file_path(path).ok()?;
+        let content = self.document_text(&uri)?;
+        let (tree, _) = lang.parse_str(&content)?;
+        Some(lang.get_enum_constants(&tree, &content, &declaring.short_name))
+    }
+}
@@ -1,6 +1,7 @@
-use lsp_core::node_kind::NodeKind;
+use lsp_core::node_kind::{NodeKind, Placement};
 use tower_lsp::lsp_types::{
-    Hover, HoverContents, Location, MarkupContent, MarkupKind, Position, Range, Url,
+    CompletionItemKind, Hover, HoverContents, Location, MarkupContent, MarkupKind, Position, Range,
+    Url,
 };
 
 use crate::{
@@ -8,9 +9,15 @@ use crate::{
     models::{
         external_symbol::ExternalSymbol,
         symbol::{Symbol, SymbolMetadata},
+        util::build_reference,
     },
 };
 
+/// What a name resolves to.  Besides its location ([`AsLspLocation`]), it carries
+/// what features show of it without going back to the declaring file: its kind
+/// ([`ResolvedSymbol::node_kind`]), the container declaring it
+/// ([`ResolvedSymbol::container_fqn`]) and its signature
+/// ([`ResolvedSymbol::signature`]).
 #[derive(Clone, Debug)]
 pub enum ResolvedSymbol {
     Project(Symbol),
@@ -56,6 +63,51 @@ impl ResolvedSymbol {
         }
     }
 
+    /// The type declaring this member; `None` for top-level types and locals.
+    pub fn parent_name(&self) -> Option<&str> {
+        match self {
+            ResolvedSymbol::Project(s) => s.parent_name.as_deref(),
+            ResolvedSymbol::External(s) => s.parent_name.as_deref(),
+            ResolvedSymbol::Local { .. } => None,
+        }
+    }
+
+    /// What declares this symbol: the type declaring a member, the package of a
+    /// top-level type.  `None` for locals and types in the default package.
+    pub fn container_fqn(&self) -> Option<&str> {
+        self.parent_name()
+            .or(self.package_name())
+            .filter(|container| !container.is_empty())
+    }
+
+    /// The language of the declaring file, as in [`Symbol::file_type`]; `None` for
+    /// locals.
+    pub fn file_type(&self) -> Option<&str> {
+        match self {
+            ResolvedSymbol::Project(s) => Some(&s.file_type),
+            ResolvedSymbol::External(s) => Some(&s.file_type),
+            ResolvedSymbol::Local { .. } => None,
+        }
+    }
+
+    /// The canonical reference to this symbol, parameter types included for a
+    /// method (see [`build_reference`]); `None` for locals.
+    pub fn signature(&self) -> Option<String> {
+        match self {
+            ResolvedSymbol::Project(s) => Some(build_reference(
+                &s.fully_qualified_name,
+                &s.symbol_type,
+                &s.metadata,
+            )),
+            ResolvedSymbol::External(s) => Some(build_reference(
+                &s.fully_qualified_name,
+                &s.symbol_type,
+                &s.metadata,
+            )),
+            ResolvedSymbol::Local { .. } => None,
+        }
+    }
+
     pub fn node_kind(&self) -> NodeKind {
         match self {
             ResolvedSymbol::Project(s) => {
@@ -70,6 +122,18 @@ impl ResolvedSymbol {
                 .unwrap_or(NodeKind::Class),
         }
     }
+
+    /// The completion kind of this symbol, declared in a type of `parent_kind` when a
+    /// member: constructors, methods and enum constants apart from functions and fields.
+    pub fn completion_kind(&self, parent_kind: Option<&NodeKind>) -> CompletionItemKind {
+        let placement = Placement {
+            parent: self.parent_name().map(Placement::short_name),
+            parent_kind,
+        };
+        let type_name = self.metadata().and_then(|m| m.return_type.as_deref());
+        self.node_kind()
+            .completion_kind(self.name(), type_name, placement)
+    }
 }
 
 impl AsLspLocation for ResolvedSymbol {
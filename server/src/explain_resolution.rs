@@ -0,0 +1,374 @@
+//! `lspintar.explainResolution` — why go-to-definition landed where it did.
+//!
+//! Definition tries its resolvers in a fixed order (see [`Resolver::ORDER`]) and
+//! answers with the first one finding anything.  The explanation of a position
+//! names that resolver and those tried before it, the targets, and the fallbacks
+//! taken on the way: a string literal or Spring bean name resolved for want of a
+//! symbol, a member answered by its declaring type, several candidates left, and
+//! a library declaration without sources shown in its decompiled class.  Run
+//! without arguments, the command toggles trace mode instead, in which every
+//! definition request taking a fallback reports it with `window/showMessage`.
+
+use std::{path::PathBuf, sync::atomic::Ordering, time::Instant};
+
+use futures::{StreamExt, stream};
+use lsp_core::{lsp_info, node_kind::NodeKind};
+use serde_json::{Value, json};
+use tower_lsp::{
+    jsonrpc::{Error, Result},
+    lsp_types::TextDocumentPositionParams,
+};
+
+use crate::{
+    enums::ResolvedSymbol,
+    lsp_convert::AsLspLocation,
+    resolve_trace::{TraceSink, TraceStep, record},
+    server::Backend,
+};
+
+/// A source of definitions, tried in [`Resolver::ORDER`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Resolver {
+    Jpql,
+    MappedBy,
+    GradleTask,
+    GradlePlugin,
+    Resource,
+    ActualDeclaration,
+    Symbol,
+    StringReference,
+    SpringBean,
+}
+
+impl Resolver {
+    const ORDER: [Resolver; 9] = [
+        Resolver::Jpql,
+        Resolver::MappedBy,
+        Resolver::GradleTask,
+        Resolver::GradlePlugin,
+        Resolver::Resource,
+        Resolver::ActualDeclaration,
+        Resolver::Symbol,
+        Resolver::StringReference,
+        Resolver::SpringBean,
+    ];
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Resolver::Jpql => "jpql",
+            Resolver::MappedBy => "mappedBy",
+            Resolver::GradleTask => "gradleTask",
+            Resolver::GradlePlugin => "gradlePlugin",
+            Resolver::Resource => "resource",
+            Resolver::ActualDeclaration => "actualDeclaration",
+            Resolver::Symbol => "symbol",
+            Resolver::StringReference => "stringReference",
+            Resolver::SpringBean => "springBean",
+        }
+    }
+
+    /// The resolvers tried before this one, in order.
+    fn tried_before(self) -> Vec<&'static str> {
+        Resolver::ORDER
+            .iter()
+            .take_while(|r| **r != self)
+            .map(|r| r.name())
+            .collect()
+    }
+
+    /// The resolvers not tried once this one answered, in order.
+    pub(crate) fn not_tried_after(self) -> Vec<&'static str> {
+        Resolver::ORDER
+            .iter()
+            .skip_while(|r| **r != self)
+            .skip(1)
+            .map(|r| r.name())
+            .collect()
+    }
+}
+
+/// A fallback a definition took, by its code and the reason shown to the user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Fallback {
+    pub code: &'static str,
+    pub reason: String,
+}
+
+impl Fallback {
+    fn new(code: &'static str, reason: String) -> Self {
+        Self { code, reason }
+    }
+}
+
+fn is_type(symbol: &ResolvedSymbol) -> bool {
+    !matches!(symbol, ResolvedSymbol::Local { .. })
+        && !matches!(symbol.node_kind(), NodeKind::Function | NodeKind::Field)
+}
+
+impl Backend {
+    /// The declarations the symbol at `params` resolves to for go-to-definition, and
+    /// the resolver that found them.  Library declarations are not yet moved to
+    /// their sources (see [`Backend::definition_targets`]).
+    pub(crate) async fn definition_symbols(
+        &self,
+        params: &TextDocumentPositionParams,
+    ) -> Result<(Vec<ResolvedSymbol>, Resolver)> {
+        self.traced_definition_symbols(params, None).await
+    }
+
+    /// [`Backend::definition_symbols`], recording each resolver it tries in `trace`.
+    pub(crate) async fn traced_definition_symbols(
+        &self,
+        params: &TextDocumentPositionParams,
+        trace: Option<&TraceSink>,
+    ) -> Result<(Vec<ResolvedSymbol>, Resolver)> {
+        let tried = |resolver: Resolver, started: Instant, found: &Option<Vec<ResolvedSymbol>>| {
+            let found = found.as_deref().unwrap_or_default();
+            record(trace, || TraceStep::found(resolver.name(), started, found));
+        };
+        let t = Instant::now();
+        let found = self.resolve_jpql_reference(params).await;
+        tried(Resolver::Jpql, t, &found);
+        if let Some(symbols) = found {
+            return Ok((symbols, Resolver::Jpql));
+        }
+        let t = Instant::now();
+        let found = self.resolve_mapped_by_reference(params).await;
+        tried(Resolver::MappedBy, t, &found);
+        if let Some(symbols) = found {
+            return Ok((symbols, Resolver::MappedBy));
+        }
+        let t = Instant::now();
+        let found = self.resolve_gradle_task_reference(params).await;
+        tried(Resolver::GradleTask, t, &found);
+        if let Some(symbols) = found {
+            return Ok((symbols, Resolver::GradleTask));
+        }
+        let t = Instant::now();
+        let found = self.resolve_plugin_reference(params).await;
+        tried(Resolver::GradlePlugin, t, &found);
+        if let Some(symbols) = found {
+            return Ok((symbols, Resolver::GradlePlugin));
+        }
+        let t = Instant::now();
+        let found = self.resolve_resource_reference(params).await;
+        tried(Resolver::Resource, t, &found);
+        if let Some(symbols) = found {
+            return Ok((symbols, Resolver::Resource));
+        }
+        let t = Instant::now();
+        let found = self.resolve_actual_declarations(params).await;
+        tried(Resolver::ActualDeclaration, t, &found);
+        if let Some(symbols) = found {
+            return Ok((symbols, Resolver::ActualDeclaration));
+        }
+
+        let t = Instant::now();
+        let resolved = self.traced_symbol_at_position(params, trace).await;
+        record(trace, || match &resolved {
+            Ok(symbols) => TraceStep::found(Resolver::Symbol.name(), t, symbols),
+            Err(e) => TraceStep::failed(Resolver::Symbol.name(), t, e.message.to_string()),
+        });
+        let (symbols, resolver) = match resolved {
+            Ok(symbols) if !symbols.is_empty() => (symbols, Resolver::Symbol),
+            result => {
+                let t = Instant::now();
+                let symbols = self.resolve_string_reference(params).await;
+                record(trace, || {
+                    TraceStep::found(Resolver::StringReference.name(), t, &symbols)
+                });
+                if !symbols.is_empty() {
+                    (symbols, Resolver::StringReference)
+                } else {
+                    let t = Instant::now();
+                    let symbols = self.resolve_spring_bean_reference(params).await;
+                    record(trace, || {
+                        TraceStep::found(Resolver::SpringBean.name(), t, &symbols)
+                    });
+                    if symbols.is_empty() {
+                        (result?, Resolver::Symbol)
+                    } else {
+                        (symbols, Resolver::SpringBean)
+                    }
+                }
+            }
+        };
+        let symbols = self.through_type_aliases(symbols).await;
+        let symbols = match params.text_document.uri.to_file_path() {
+            Ok(from) => self.prefer_source_set(symbols, &from).await,
+            Err(_) => symbols,
+        };
+        Ok((symbols, resolver))
+    }
+
+    /// `symbols` with library declarations moved to their sources JAR, or to their
+    /// decompiled class when there is none.
+    pub(crate) async fn definition_targets(
+        &self,
+        symbols: Vec<ResolvedSymbol>,
+    ) -> Vec<ResolvedSymbol> {
+        let indexer_guard = self.indexer.read().await;
+        let indexer = indexer_guard.as_ref();
+
+        stream::iter(symbols)
+            .then(|s| async move {
+                match s {
+                    ResolvedSymbol::External(sym) => {
+                        ResolvedSymbol::External(sym.with_sources(indexer).await)
+                    }
+                    other => other,
+                }
+            })
+            .collect()
+            .await
+    }
+
+    /// The fallbacks taken resolving `params` to `targets` with `resolver`.
+    pub(crate) fn definition_fallbacks(
+        &self,
+        params: &TextDocumentPositionParams,
+        resolver: Resolver,
+        targets: &[ResolvedSymbol],
+    ) -> Vec<Fallback> {
+        let mut fallbacks = Vec::new();
+        match resolver {
+            Resolver::StringReference => fallbacks.push(Fallback::new(
+                "stringReference",
+                "No symbol at the position; the string literal was resolved as a reference"
+                    .to_string(),
+            )),
+            Resolver::SpringBean => fallbacks.push(Fallback::new(
+                "springBean",
+                "No symbol at the position; it was resolved as a Spring bean name".to_string(),
+            )),
+            _ => {}
+        }
+
+        let path = PathBuf::from(params.text_document.uri.path());
+        let ident = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .and_then(|ext| self.languages.get(ext))
+            .zip(self.document_text(&params.text_document.uri))
+            .and_then(|(lang, content)| {
+                let (tree, _) = lang.parse_str(&content)?;
+                lang.find_ident_at_position(&tree, &content, &params.position)
+            })
+            .map(|(ident, _)| ident);
+        if resolver == Resolver::Symbol
+            && let Some(ident) = ident
+            && !targets.is_empty()
+            && targets.iter().all(is_type)
+            && targets.iter().all(|t| t.name() != ident)
+        {
+            fallbacks.push(Fallback::new(
+                "declaringType",
+                format!(
+                    "Member '{ident}' was not found; its declaring type {} is shown instead",
+                    targets[0].fully_qualified_name()
+                ),
+            ));
+        }
+
+        if targets.len() > 1 {
+            fallbacks.push(Fallback::new(
+                "ambiguous",
+                format!(
+                    "{} candidates could not be narrowed down to one",
+                    targets.len()
+                ),
+            ));
+        }
+
+        for target in targets {
+            if let ResolvedSymbol::External(symbol) = target
+                && symbol.needs_decompilation
+            {
+                fallbacks.push(Fallback::new(
+                    "decompiled",
+                    format!(
+                        "No sources for {}; its decompiled class is shown instead",
+                        symbol.fully_qualified_name
+                    ),
+                ));
+            }
+        }
+        fallbacks
+    }
+
+    /// Reports the fallbacks of a definition request with `window/showMessage` when
+    /// trace mode is on.
+    pub(crate) fn trace_definition(
+        &self,
+        params: &TextDocumentPositionParams,
+        resolver: Resolver,
+        targets: &[ResolvedSymbol],
+    ) {
+        if !self.explain_resolution.load(Ordering::Relaxed) {
+            return;
+        }
+        let fallbacks = self.definition_fallbacks(params, resolver, targets);
+        if fallbacks.is_empty() {
+            return;
+        }
+        let reasons: Vec<String> = fallbacks.into_iter().map(|f| f.reason).collect();
+        lsp_info!(
+            "Definition at {}:{} ({}): {}",
+            params.position.line + 1,
+            params.position.character + 1,
+            resolver.name(),
+            reasons.join("; ")
+        );
+    }
+
+    /// Handles `lspintar.explainResolution`.  With the `TextDocumentPositionParams`
+    /// of a symbol as its argument, the result explains its definition; without
+    /// arguments, trace mode is toggled and the result is whether it is now on.
+    pub(crate) async fn explain_resolution(&self, arguments: Vec<Value>) -> Result<Option<Value>> {
+        let Some(argument) = arguments.into_iter().next() else {
+            let enabled = !self.explain_resolution.fetch_xor(true, Ordering::Relaxed);
+            lsp_info!(
+                "Definition resolution trace {}",
+                if enabled { "enabled" } else { "disabled" }
+            );
+            return Ok(Some(json!({ "trace": enabled })));
+        };
+        let params: TextDocumentPositionParams = serde_json::from_value(argument)
+            .map_err(|_| Error::invalid_params("Expected a text document position"))?;
+
+        let (symbols, resolver) = match self.definition_symbols(&params).await {
+            Ok(resolved) => resolved,
+            Err(e) => {
+                let tried: Vec<&str> = Resolver::ORDER.iter().map(|r| r.name()).collect();
+                return Ok(Some(json!({
+                    "resolver": null,
+                    "tried": tried,
+                    "error": e.message,
+                })));
+            }
+        };
+        let targets = self.definition_targets(symbols).await;
+        let fallbacks: Vec<Value> = self
+            .definition_fallbacks(&params, resolver, &targets)
+            .into_iter()
+            .map(|f| json!({ "code": f.code, "reason": f.reason }))
+            .collect();
+        let targets: Vec<Value> = targets
+            .iter()
+            .map(|t| {
+                json!({
+                    "symbol": t.signature().unwrap_or_else(|| t.name().to_string()),
+                    "kind": t.node_kind().to_string(),
+                    "container": t.container_fqn(),
+                    "location": t.as_lsp_location(),
+                })
+            })
+            .collect();
+        Ok(Some(json!({
+            "resolver": resolver.name(),
+            "tried": resolver.tried_before(),
+            "targets": targets,
+            "fallbacks": fallbacks,
+        })))
+    }
+}
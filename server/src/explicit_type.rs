@@ -0,0 +1,128 @@
+//! "Declare with explicit type" quick fix on Groovy `def` declarations.
+//!
+//! On `def x = ...`, the type Groovy inference gives the initializer replaces
+//! `def`, so a code base can move to static typing one declaration at a time.  A
+//! type outside the file's package and imports is imported.  Declarations of
+//! several variables, without an initializer, or whose initializer only infers to
+//! `Object` are left alone.
+
+use std::collections::HashMap;
+
+use lsp_core::{ts_helper::get_node_at_position, util::get_import_text_edit};
+use tower_lsp::lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOrCommand, Position, Range, TextEdit, Url, WorkspaceEdit,
+};
+use tree_sitter::Node;
+
+use crate::{generic_resolution::parse_type_ref, server::Backend, type_at::is_deferred_type};
+
+fn node_range(node: &Node) -> Range {
+    let (start, end) = (node.start_position(), node.end_position());
+    Range::new(
+        Position::new(start.row as u32, start.column as u32),
+        Position::new(end.row as u32, end.column as u32),
+    )
+}
+
+/// `type_name` as the file can write it, with the import it needs if any: a
+/// qualified name is shortened when its package is the file's or is imported.
+fn written_type(
+    type_name: &str,
+    imports: &[String],
+    package: Option<&str>,
+) -> (String, Option<String>) {
+    let (outer, _) = parse_type_ref(type_name);
+    let Some((type_package, short)) = outer.rsplit_once('.') else {
+        return (type_name.to_string(), None);
+    };
+    let written = format!("{short}{}", &type_name.trim()[outer.len()..]);
+    let visible = package == Some(type_package)
+        || imports
+            .iter()
+            .any(|i| *i == outer || *i == format!("{type_package}.*"));
+    (written, (!visible).then_some(outer))
+}
+
+impl Backend {
+    /// The quick fix replacing `def` with the inferred type in the Groovy
+    /// declaration at `position`.
+    pub(crate) async fn explicit_type_action(
+        &self,
+        uri: &Url,
+        position: Position,
+    ) -> Option<CodeActionOrCommand> {
+        if !uri.path().ends_with(".groovy") {
+            return None;
+        }
+        let lang = self.languages.get("groovy")?;
+        let content = self.document_text(uri)?;
+        let (tree, _) = lang.parse_str(&content)?;
+        let bytes = content.as_bytes();
+        let node = get_node_at_position(&tree, &content, &position)?;
+        let declaration = std::iter::successors(Some(node), |n| n.parent()).find(|n| {
+            n.child_by_field_name("type")
+                .and_then(|t| t.utf8_text(bytes).ok())
+                == Some("def")
+        })?;
+        let def = declaration.child_by_field_name("type")?;
+
+        let mut cursor = declaration.walk();
+        let declarators: Vec<Node> = declaration
+            .children_by_field_name("declarator", &mut cursor)
+            .collect();
+        let [declarator] = declarators.as_slice() else {
+            return None;
+        };
+        declarator.child_by_field_name("value")?;
+        let name = declarator
+            .child_by_field_name("name")?
+            .utf8_text(bytes)
+            .ok()?;
+
+        let end = declaration.end_position();
+        let after = Position::new(end.row as u32, end.column as u32);
+        let mut imports = lang.get_imports(&tree, &content);
+        imports.extend(lang.get_implicit_imports());
+        let package = lang.get_package_name(&tree, &content);
+        let inferred = lang.find_variable_type(&tree, &content, name, &after)?;
+        let inferred = if is_deferred_type(&inferred) {
+            self.walk_member_chain(
+                name,
+                lang,
+                &tree,
+                &content,
+                imports.clone(),
+                &after,
+                package.clone(),
+            )
+            .await?
+        } else {
+            inferred
+        };
+        if matches!(inferred.as_str(), "def" | "Object" | "java.lang.Object") {
+            return None;
+        }
+
+        let (type_name, import) = written_type(&inferred, &imports, package.as_deref());
+        let mut edits = vec![TextEdit::new(node_range(&def), type_name.clone())];
+        if let Some(fqn) = import {
+            let type_package = fqn.rsplit_once('.').map_or("", |(p, _)| p);
+            edits.push(get_import_text_edit(
+                &content,
+                &fqn,
+                type_package,
+                type_package,
+                lang.get_language(),
+            ));
+        }
+        Some(CodeActionOrCommand::CodeAction(CodeAction {
+            title: format!("Declare '{name}' as '{type_name}'"),
+            kind: Some(CodeActionKind::QUICKFIX),
+            edit: Some(WorkspaceEdit {
+                changes: Some(HashMap::from([(uri.clone(), edits)])),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }))
+    }
+}
@@ -0,0 +1,345 @@
+//! "Extract constant" refactoring on literals.
+//!
+//! A string, number, character or boolean literal under the selection becomes a
+//! `private static final` field at the top of the enclosing class in Java and
+//! Groovy.  In Kotlin it becomes a `const val` in the class's companion object
+//! when it has one, else a private top-level `const val` before the declaration.
+//! One action replaces the selected literal only, another every identical literal
+//! the constant is visible to.  The name comes from a string's words, e.g.
+//! `"max retries"` gives `MAX_RETRIES`, and is `CONSTANT` otherwise; a number is
+//! appended when the file already uses it.  Interpolated strings and literals
+//! outside any class are not extracted.
+
+use std::{collections::HashMap, path::PathBuf};
+
+use lsp_core::{languages::Language, node_kind::NodeKind};
+use tower_lsp::lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOrCommand, Position, Range, TextEdit, Url, WorkspaceEdit,
+};
+use tree_sitter::{Node, Point, Tree};
+
+use crate::{rename::word_boundary_occurrences, server::Backend};
+
+/// Literal node kinds of the three grammars that can initialize a constant.
+const SCALAR_LITERALS: &[&str] = &[
+    "decimal_integer_literal",
+    "hex_integer_literal",
+    "octal_integer_literal",
+    "binary_integer_literal",
+    "decimal_floating_point_literal",
+    "hex_floating_point_literal",
+    "hex_literal",
+    "bin_literal",
+    "long_literal",
+    "unsigned_literal",
+    "real_literal",
+    "true",
+    "false",
+    "boolean_literal",
+    "character_literal",
+    "string_literal",
+    "text_block",
+    "gstring",
+];
+
+/// Words of a string literal a constant name is made of, at most.
+const NAME_WORDS: usize = 4;
+
+fn node_range(node: &Node) -> Range {
+    let (start, end) = (node.start_position(), node.end_position());
+    Range::new(
+        Position::new(start.row as u32, start.column as u32),
+        Position::new(end.row as u32, end.column as u32),
+    )
+}
+
+fn point(position: Position) -> Point {
+    Point::new(position.line as usize, position.character as usize)
+}
+
+/// The outermost literal at `range`: `5L` rather than its digits, a string rather
+/// than its content.
+fn literal_at<'t>(tree: &'t Tree, range: Range) -> Option<Node<'t>> {
+    let node = tree
+        .root_node()
+        .descendant_for_point_range(point(range.start), point(range.end))?;
+    let is_literal = |n: &Node| SCALAR_LITERALS.contains(&n.kind());
+    std::iter::successors(Some(node), |n| n.parent())
+        .skip_while(|n| !is_literal(n))
+        .take_while(is_literal)
+        .last()
+}
+
+/// The Java and Groovy field type of `literal`.
+fn field_type(literal: &Node, text: &str, lang: &Language) -> &'static str {
+    let lower = text.to_lowercase();
+    match literal.kind() {
+        "decimal_integer_literal"
+        | "hex_integer_literal"
+        | "octal_integer_literal"
+        | "binary_integer_literal" => {
+            if lower.ends_with('l') {
+                "long"
+            } else {
+                "int"
+            }
+        }
+        "decimal_floating_point_literal" | "hex_floating_point_literal" => {
+            if lower.ends_with('f') {
+                "float"
+            } else if *lang == Language::Groovy && !lower.ends_with('d') {
+                "BigDecimal"
+            } else {
+                "double"
+            }
+        }
+        "true" | "false" => "boolean",
+        "character_literal" => "char",
+        _ => "String",
+    }
+}
+
+/// `MAX_RETRIES` for the string literal `"max retries"`, `CONSTANT` for anything
+/// else.
+fn constant_name(literal: &Node, text: &str) -> String {
+    if !matches!(literal.kind(), "string_literal" | "text_block" | "gstring") {
+        return "CONSTANT".to_string();
+    }
+    let words: Vec<String> = text
+        .trim_matches(['"', '\''])
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .take(NAME_WORDS)
+        .map(str::to_uppercase)
+        .collect();
+    match words.first() {
+        Some(first) if !first.starts_with(|c: char| c.is_ascii_digit()) => words.join("_"),
+        _ => "CONSTANT".to_string(),
+    }
+}
+
+/// `name`, numbered when `content` already has a word by it.
+fn unused_name(content: &str, name: String) -> String {
+    let taken = |n: &str| !word_boundary_occurrences(content, n).is_empty();
+    if !taken(&name) {
+        return name;
+    }
+    (2..)
+        .map(|i| format!("{name}_{i}"))
+        .find(|n| !taken(n))
+        .unwrap_or(name)
+}
+
+/// Whether a string literal may interpolate, which a constant cannot.  Groovy and
+/// Kotlin strings with a `$` are taken to.
+fn interpolates(literal: &Node, text: &str, lang: &Language) -> bool {
+    let mut cursor = literal.walk();
+    let has_interpolation = literal
+        .children(&mut cursor)
+        .any(|c| c.kind().contains("interpolat"));
+    has_interpolation
+        || (*lang != Language::Java
+            && matches!(literal.kind(), "string_literal" | "gstring")
+            && text.contains('$'))
+}
+
+/// The indentation of the members of `body`: that of its first member, else one
+/// level below `class`.
+fn member_indent(content: &str, class: &Node, body: &Node) -> String {
+    let indent_of = |row: usize| -> String {
+        content
+            .lines()
+            .nth(row)
+            .unwrap_or_default()
+            .chars()
+            .take_while(|c| c.is_whitespace())
+            .collect()
+    };
+    let mut cursor = body.walk();
+    let first_member = body
+        .named_children(&mut cursor)
+        .find(|n| n.start_position().row > body.start_position().row);
+    match first_member {
+        Some(member) => indent_of(member.start_position().row),
+        None => format!("{}    ", indent_of(class.start_position().row)),
+    }
+}
+
+/// The insertion of `declaration` as the first member of `body`, whose brace opens
+/// a line of its own.
+fn first_member_edit(
+    content: &str,
+    class: &Node,
+    body: &Node,
+    declaration: &str,
+) -> Option<TextEdit> {
+    if body.start_position().row == body.end_position().row {
+        return None;
+    }
+    let indent = member_indent(content, class, body);
+    let at = Position::new(body.start_position().row as u32 + 1, 0);
+    Some(TextEdit::new(
+        Range::new(at, at),
+        format!("{indent}{declaration}\n\n"),
+    ))
+}
+
+impl Backend {
+    /// The "Extract constant" actions for the literal in `range` of `uri`.
+    pub(crate) fn extract_constant_actions(
+        &self,
+        uri: &Url,
+        range: Range,
+    ) -> Vec<CodeActionOrCommand> {
+        let path = PathBuf::from(uri.path());
+        let Some(lang) = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .and_then(|ext| self.languages.get(ext))
+        else {
+            return vec![];
+        };
+        let Some(content) = self.document_text(uri) else {
+            return vec![];
+        };
+        let Some((tree, _)) = lang.parse_str(&content) else {
+            return vec![];
+        };
+        let Some(literal) = literal_at(&tree, range) else {
+            return vec![];
+        };
+        let bytes = content.as_bytes();
+        let Ok(text) = literal.utf8_text(bytes) else {
+            return vec![];
+        };
+        let language = lang.get_language();
+        if interpolates(&literal, text, &language) {
+            return vec![];
+        }
+
+        // Java and Groovy interface fields cannot be private.
+        let is_class = |n: &Node| {
+            let kind = lang.get_kind(n);
+            (kind == Some(NodeKind::Class)
+                || (language == Language::Kotlin && kind == Some(NodeKind::Interface)))
+                && n.child_by_field_name("body").is_some()
+        };
+        let Some(class) = std::iter::successors(literal.parent(), |n| n.parent()).find(is_class)
+        else {
+            return vec![];
+        };
+        let name = unused_name(&content, constant_name(&literal, text));
+
+        // The declaration, and the node the constant is visible throughout.
+        let (declaration, scope) = match language {
+            Language::Kotlin => {
+                let mut cursor = class.walk();
+                let companion = class
+                    .child_by_field_name("body")
+                    .and_then(|body| {
+                        body.named_children(&mut cursor)
+                            .find(|n| n.kind() == "companion_object")
+                    })
+                    .and_then(|companion| {
+                        let mut cursor = companion.walk();
+                        let body = companion
+                            .named_children(&mut cursor)
+                            .find(|n| n.kind() == "class_body")?;
+                        Some((companion, body))
+                    });
+                match companion {
+                    Some((companion, body)) => (
+                        first_member_edit(
+                            &content,
+                            &companion,
+                            &body,
+                            &format!("private const val {name} = {text}"),
+                        ),
+                        class,
+                    ),
+                    None => {
+                        let top = std::iter::successors(Some(class), |n| n.parent())
+                            .take_while(|n| n.parent().is_some())
+                            .last()
+                            .unwrap_or(class);
+                        // Documentation stays with the declaration it documents.
+                        let start = std::iter::successors(Some(top), |n| n.prev_sibling())
+                            .take_while(|n| *n == top || n.kind().contains("comment"))
+                            .last()
+                            .unwrap_or(top);
+                        let at = Position::new(start.start_position().row as u32, 0);
+                        let root = tree.root_node();
+                        (
+                            Some(TextEdit::new(
+                                Range::new(at, at),
+                                format!("private const val {name} = {text}\n\n"),
+                            )),
+                            root,
+                        )
+                    }
+                }
+            }
+            Language::Java | Language::Groovy => {
+                let Some(body) = class.child_by_field_name("body") else {
+                    return vec![];
+                };
+                let field_type = field_type(&literal, text, &language);
+                (
+                    first_member_edit(
+                        &content,
+                        &class,
+                        &body,
+                        &format!(
+                            "private static final {field_type} {name} = {text}{}",
+                            if language == Language::Java { ";" } else { "" }
+                        ),
+                    ),
+                    class,
+                )
+            }
+        };
+        let Some(declaration) = declaration else {
+            return vec![];
+        };
+
+        let mut occurrences = Vec::new();
+        let mut stack = vec![scope];
+        while let Some(node) = stack.pop() {
+            if node.kind() == literal.kind() && node.utf8_text(bytes).ok() == Some(text) {
+                occurrences.push(node_range(&node));
+                continue;
+            }
+            let mut cursor = node.walk();
+            stack.extend(node.children(&mut cursor));
+        }
+        occurrences.sort_by_key(|r| r.start);
+
+        let action = |title: String, ranges: &[Range]| {
+            let mut edits = vec![declaration.clone()];
+            edits.extend(ranges.iter().map(|r| TextEdit::new(*r, name.clone())));
+            CodeActionOrCommand::CodeAction(CodeAction {
+                title,
+                kind: Some(CodeActionKind::REFACTOR_EXTRACT),
+                edit: Some(WorkspaceEdit {
+                    changes: Some(HashMap::from([(uri.clone(), edits)])),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+        };
+        let mut actions = vec![action(
+            format!("Extract constant '{name}'"),
+            &[node_range(&literal)],
+        )];
+        if occurrences.len() > 1 {
+            actions.push(action(
+                format!(
+                    "Extract constant '{name}' replacing {} occurrences",
+                    occurrences.len()
+                ),
+                &occurrences,
+            ));
+        }
+        actions
+    }
+}
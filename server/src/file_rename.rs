@@ -0,0 +1,439 @@
+//! `workspace/willRenameFiles` — moving source files to another package.
+//!
+//! Before a `.java`, `.kt` or `.groovy` file, or a directory of them, is moved,
+//! the client asks for the edits keeping the workspace compiling.  The moved
+//! file's `package` declaration follows its new directory, and imports of its
+//! top-level declarations are rewritten to the new package.  Files that reached
+//! them without an import, through their own package or a wildcard import of it,
+//! get one, and the moved file in turn imports what it uses from the package it
+//! left.  When the file name changes too, the type named after it is renamed the
+//! way `textDocument/rename` renames it.
+//!
+//! The new package comes from the source root in Java and Groovy layouts (see
+//! [`source_root_and_package`]).  Elsewhere the directories standing for the last
+//! segments of the package are replaced, so Kotlin files leaving out the common
+//! root package keep leaving it out.
+
+use std::{
+    collections::HashMap,
+    ffi::OsStr,
+    path::{Component, Path, PathBuf},
+};
+
+use lsp_core::util::get_import_text_edit;
+use tower_lsp::{
+    jsonrpc::Result,
+    lsp_types::{Position, Range, RenameFilesParams, TextEdit, Url, WorkspaceEdit},
+};
+use tree_sitter::Tree;
+use walkdir::WalkDir;
+
+use crate::{
+    code_action::removal_range,
+    context::{ImportLine, import_lines},
+    models::symbol::Symbol,
+    package_mismatch::{package_edit, source_root_and_package},
+    rename::{EditsPerFile, position_in_comment_or_string, word_boundary_occurrences},
+    server::Backend,
+};
+
+/// The package a file declaring `package` in `old` declares once moved to `new`.
+/// `None` when the file stays in its directory, or leaves the directories its
+/// package can be told from.
+pub fn moved_package(old: &Path, new: &Path, package: &str) -> Option<String> {
+    let (old_dir, new_dir) = (old.parent()?, new.parent()?);
+    if old_dir == new_dir {
+        return None;
+    }
+    if let Some((_, package)) = source_root_and_package(new) {
+        return Some(package);
+    }
+
+    let segments: Vec<&str> = package.split('.').filter(|s| !s.is_empty()).collect();
+    let dirs: Vec<Component> = old_dir.components().collect();
+    // The most segments the directory ends with, the package's last ones.
+    let depth = (0..=segments.len().min(dirs.len())).rev().find(|&depth| {
+        segments[segments.len() - depth..]
+            .iter()
+            .zip(&dirs[dirs.len() - depth..])
+            .all(|(segment, dir)| dir.as_os_str() == OsStr::new(segment))
+    })?;
+    let root: PathBuf = dirs[..dirs.len() - depth].iter().collect();
+    let mut moved = segments[..segments.len() - depth].to_vec();
+    for dir in new_dir.strip_prefix(&root).ok()?.components() {
+        moved.push(dir.as_os_str().to_str()?);
+    }
+    Some(moved.join("."))
+}
+
+/// The package `content` declares, from its `package` line; empty for the
+/// default package.
+fn declared_package(content: &str) -> String {
+    content
+        .lines()
+        .find_map(|line| {
+            let rest = line.trim_start().strip_prefix("package ")?;
+            rest.split([';', ' ', '/']).find(|s| !s.is_empty())
+        })
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Whether `content` names `name` outside comments, strings and its `package`
+/// and `import` lines.
+fn mentions(content: &str, tree: &Tree, name: &str) -> bool {
+    let lines: Vec<&str> = content.lines().collect();
+    word_boundary_occurrences(content, name)
+        .into_iter()
+        .any(|(line, column, _)| {
+            let text = lines[line].trim_start();
+            !text.starts_with("import ")
+                && !text.starts_with("package ")
+                && !position_in_comment_or_string(tree, line, column)
+        })
+}
+
+fn qualified(package: &str, name: &str) -> String {
+    if package.is_empty() {
+        name.to_string()
+    } else {
+        format!("{package}.{name}")
+    }
+}
+
+/// Whether `symbol` is declared at the top level of `package` rather than in a
+/// type.
+fn is_top_level(symbol: &Symbol, package: &str) -> bool {
+    symbol
+        .fully_qualified_name
+        .strip_prefix(package)
+        .and_then(|rest| rest.strip_prefix('.'))
+        == Some(symbol.short_name.as_str())
+}
+
+fn file_stem(path: &Path) -> &str {
+    path.file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default()
+}
+
+/// A top-level declaration of a moved file.
+struct MovedDeclaration {
+    file: PathBuf,
+    old_package: String,
+    new_package: String,
+    old_name: String,
+    new_name: String,
+}
+
+impl MovedDeclaration {
+    fn old_fqn(&self) -> String {
+        qualified(&self.old_package, &self.old_name)
+    }
+
+    fn new_fqn(&self) -> String {
+        qualified(&self.new_package, &self.new_name)
+    }
+}
+
+/// A moved file, with the content and package it has before the move.
+struct MovedFile {
+    uri: Url,
+    content: String,
+    old_package: String,
+    new_package: String,
+}
+
+fn push_edit(edits_per_file: &mut EditsPerFile, uri: &Url, edit: TextEdit) {
+    edits_per_file
+        .entry(uri.clone())
+        .or_default()
+        .push((edit, None));
+}
+
+/// Rewrites `import` of `declaration`, or of a member or nested type of it, to
+/// where it moves: the package, and the name when the move renames it.  The
+/// import is removed when it moves to the default package.
+fn rewrite_import(
+    edits_per_file: &mut EditsPerFile,
+    uri: &Url,
+    content: &str,
+    import: &ImportLine,
+    declaration: &MovedDeclaration,
+) {
+    let line = import.line as u32;
+    let at = |column: usize| Position::new(line, column as u32);
+    if declaration.new_package.is_empty() {
+        let line_len = content.lines().nth(import.line).map_or(0, str::len);
+        let whole_line = Range::new(at(0), at(line_len));
+        if let Some(range) = removal_range(content, whole_line) {
+            push_edit(edits_per_file, uri, TextEdit::new(range, String::new()));
+        }
+        return;
+    }
+
+    if declaration.new_package != declaration.old_package {
+        let end = import.column + declaration.old_package.len();
+        let range = Range::new(at(import.column), at(end));
+        push_edit(
+            edits_per_file,
+            uri,
+            TextEdit::new(range, declaration.new_package.clone()),
+        );
+    }
+    if declaration.new_name != declaration.old_name {
+        let start = import.column + declaration.old_package.len() + 1;
+        let range = Range::new(at(start), at(start + declaration.old_name.len()));
+        // The rename of the type may have got here first.
+        let renamed = edits_per_file
+            .get(uri)
+            .is_some_and(|edits| edits.iter().any(|(e, _)| e.range == range));
+        if !renamed {
+            push_edit(
+                edits_per_file,
+                uri,
+                TextEdit::new(range, declaration.new_name.clone()),
+            );
+        }
+    }
+}
+
+impl Backend {
+    /// The source files `params` moves, by their old and new paths.  A directory
+    /// moves the source files under it.
+    fn source_file_moves(&self, params: &RenameFilesParams) -> Vec<(PathBuf, PathBuf)> {
+        let file_path = |uri: &str| Url::parse(uri).ok()?.to_file_path().ok();
+        let is_source = |path: &Path| {
+            path.extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|ext| self.languages.contains_key(ext))
+        };
+
+        let mut moves = Vec::new();
+        for rename in &params.files {
+            let (Some(old), Some(new)) = (file_path(&rename.old_uri), file_path(&rename.new_uri))
+            else {
+                continue;
+            };
+            if !old.is_dir() {
+                if is_source(&old) {
+                    moves.push((old, new));
+                }
+                continue;
+            }
+            for entry in WalkDir::new(&old).into_iter().filter_map(|e| e.ok()) {
+                let path = entry.path();
+                if entry.file_type().is_file()
+                    && is_source(path)
+                    && let Ok(relative) = path.strip_prefix(&old)
+                {
+                    moves.push((path.to_path_buf(), new.join(relative)));
+                }
+            }
+        }
+        moves
+    }
+
+    pub(crate) async fn will_rename_files_impl(
+        &self,
+        params: RenameFilesParams,
+    ) -> Result<Option<WorkspaceEdit>> {
+        let Some(repo) = self.repo.get() else {
+            return Ok(None);
+        };
+        let mut edits_per_file: EditsPerFile = HashMap::new();
+        let mut moved_files: HashMap<PathBuf, MovedFile> = HashMap::new();
+        let mut moved: Vec<MovedDeclaration> = Vec::new();
+
+        for (old, new) in self.source_file_moves(&params) {
+            let Ok(uri) = Url::from_file_path(&old) else {
+                continue;
+            };
+            let Some(content) = self.document_text(&uri) else {
+                continue;
+            };
+            let old_package = declared_package(&content);
+            let new_package =
+                moved_package(&old, &new, &old_package).unwrap_or_else(|| old_package.clone());
+            let renamed = file_stem(&old) != file_stem(&new);
+            if new_package == old_package && !renamed {
+                continue;
+            }
+
+            if new_package != old_package {
+                let terminator = if old.extension() == Some(OsStr::new("java")) {
+                    ";"
+                } else {
+                    ""
+                };
+                if let Some(edit) = package_edit(&content, &old_package, &new_package, terminator) {
+                    push_edit(&mut edits_per_file, &uri, edit);
+                }
+            }
+
+            let symbols = repo
+                .find_symbols_by_file_path(&old.to_string_lossy())
+                .await
+                .unwrap_or_default();
+            for symbol in symbols {
+                if !is_top_level(&symbol, &old_package) {
+                    continue;
+                }
+                let mut new_name = symbol.short_name.clone();
+                if renamed
+                    && symbol.short_name == file_stem(&old)
+                    && matches!(
+                        symbol.symbol_type.as_str(),
+                        "Class" | "Interface" | "Enum" | "Annotation"
+                    )
+                {
+                    new_name = file_stem(&new).to_string();
+                    let type_edits = self.type_rename_edits(symbol.clone(), &new_name).await?;
+                    for (uri, edits) in type_edits {
+                        edits_per_file.entry(uri).or_default().extend(edits);
+                    }
+                }
+                moved.push(MovedDeclaration {
+                    file: old.clone(),
+                    old_package: old_package.clone(),
+                    new_package: new_package.clone(),
+                    old_name: symbol.short_name,
+                    new_name,
+                });
+            }
+            moved_files.insert(
+                old,
+                MovedFile {
+                    uri,
+                    content,
+                    old_package,
+                    new_package,
+                },
+            );
+        }
+        if moved_files.is_empty() {
+            return Ok(None);
+        }
+
+        // The top-level declarations staying in the packages moved files leave.
+        let mut staying: Vec<(String, String)> = Vec::new();
+        for file_path in repo.find_all_source_file_paths().await.unwrap_or_default() {
+            let path = PathBuf::from(&file_path);
+            let Some(lang) = path
+                .extension()
+                .and_then(|e| e.to_str())
+                .and_then(|ext| self.languages.get(ext))
+            else {
+                continue;
+            };
+            let Ok(uri) = Url::from_file_path(&path) else {
+                continue;
+            };
+            let Some(content) = self.document_text(&uri) else {
+                continue;
+            };
+            let package = declared_package(&content);
+            let own_package = moved_files
+                .get(&path)
+                .map_or(package.as_str(), |f| f.new_package.as_str());
+            let imports = import_lines(&content);
+            let imports_all_of =
+                |package: &str| imports.iter().any(|i| i.path == format!("{package}.*"));
+
+            let mut unimported: Vec<&MovedDeclaration> = Vec::new();
+            for declaration in &moved {
+                let old_fqn = declaration.old_fqn();
+                let mut imported = false;
+                for import in &imports {
+                    let nested = import
+                        .path
+                        .strip_prefix(old_fqn.as_str())
+                        .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'));
+                    if !nested || declaration.old_package.is_empty() {
+                        continue;
+                    }
+                    imported = true;
+                    rewrite_import(&mut edits_per_file, &uri, &content, import, declaration);
+                }
+
+                if !imported
+                    && declaration.file != path
+                    && !declaration.new_package.is_empty()
+                    && own_package != declaration.new_package
+                    && !imports_all_of(&declaration.new_package)
+                    && (package == declaration.old_package
+                        || imports_all_of(&declaration.old_package))
+                {
+                    unimported.push(declaration);
+                }
+            }
+            if !unimported.is_empty()
+                && let Some((tree, _)) = lang.parse_str(&content)
+            {
+                for declaration in unimported {
+                    if mentions(&content, &tree, &declaration.old_name) {
+                        let fqn = declaration.new_fqn();
+                        let new_package = &declaration.new_package;
+                        let edit = get_import_text_edit(
+                            &content,
+                            &fqn,
+                            new_package,
+                            new_package,
+                            lang.get_language(),
+                        );
+                        push_edit(&mut edits_per_file, &uri, edit);
+                    }
+                }
+            }
+
+            let left = moved_files
+                .values()
+                .any(|f| f.old_package == package && f.new_package != package);
+            if left && own_package == package {
+                for symbol in repo
+                    .find_symbols_by_file_path(&file_path)
+                    .await
+                    .unwrap_or_default()
+                {
+                    if is_top_level(&symbol, &package) {
+                        staying.push((package.clone(), symbol.short_name));
+                    }
+                }
+            }
+        }
+
+        for (path, file) in &moved_files {
+            if file.old_package == file.new_package || file.new_package.is_empty() {
+                continue;
+            }
+            let Some(lang) = path
+                .extension()
+                .and_then(|e| e.to_str())
+                .and_then(|ext| self.languages.get(ext))
+            else {
+                continue;
+            };
+            let Some((tree, _)) = lang.parse_str(&file.content) else {
+                continue;
+            };
+            for (package, name) in &staying {
+                if *package == file.old_package && mentions(&file.content, &tree, name) {
+                    let fqn = qualified(package, name);
+                    let edit = get_import_text_edit(
+                        &file.content,
+                        &fqn,
+                        package,
+                        package,
+                        lang.get_language(),
+                    );
+                    push_edit(&mut edits_per_file, &file.uri, edit);
+                }
+            }
+        }
+
+        if edits_per_file.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(self.workspace_edit_from(edits_per_file)))
+    }
+}
@@ -0,0 +1,499 @@
+//! Plugin ids in `plugins {}` blocks.
+//!
+//! `id("com.github.johnrengelman.shadow") version "8.1.1"` applies the plugin its
+//! marker artifact, `<id>:<id>.gradle.plugin`, depends on; `kotlin("jvm")` stands
+//! for `id("org.jetbrains.kotlin.jvm")`.  A plugin is either built in the project,
+//! as a precompiled script plugin `<id>.gradle(.kts)` or a `gradlePlugin {}`
+//! registration in `buildSrc` or an included build, or was resolved into the
+//! Gradle cache.  Go-to-definition on an id lands on the local declaration, else
+//! on the plugin's implementation class as its JAR's
+//! `META-INF/gradle-plugins/<id>.properties` names it.  Hover shows that class.
+//! Ids found in neither place are warned about once the cache holds a plugin of
+//! the script, so a build Gradle never resolved is not flagged.  Core plugins
+//! (`java`, `application`) are not looked up.
+//!
+//! The scan is textual, like that of task names, so it reads Groovy and Kotlin
+//! DSL scripts alike.
+
+use std::{
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use lsp_core::{
+    build_tools::gradle_cache::{cached_plugin_jar, cached_plugin_versions, compare_versions},
+    ts_helper::byte_offset_to_position,
+};
+use tower_lsp::lsp_types::{
+    Diagnostic, DiagnosticSeverity, Hover, HoverContents, MarkupContent, MarkupKind,
+    NumberOrString, Position, Range, TextDocumentPositionParams, Url,
+};
+use walkdir::WalkDir;
+use zip::ZipArchive;
+
+use crate::{
+    enums::ResolvedSymbol, gradle_tasks::is_gradle_script, indexer::is_excluded, server::Backend,
+};
+
+pub(crate) const UNKNOWN_PLUGIN_CODE: &str = "unknown_plugin";
+
+/// The id prefix of the plugins `kotlin("...")` applies.
+const KOTLIN_PLUGIN_PREFIX: &str = "org.jetbrains.kotlin.";
+
+/// A plugin requested in a `plugins {}` block.  `range` covers the id, or the
+/// argument of `kotlin(...)`, without quotes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginRequest {
+    pub id: String,
+    pub version: Option<String>,
+    pub range: Range,
+}
+
+/// A plugin declared in `buildSrc` or an included build.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalPlugin {
+    pub id: String,
+    /// The precompiled script plugin, or the build file registering the plugin.
+    pub file: PathBuf,
+    pub position: Position,
+    pub implementation_class: Option<String>,
+}
+
+/// Where a requested plugin comes from.
+enum PluginSource {
+    Local(LocalPlugin),
+    Cached {
+        version: String,
+        implementation_class: Option<String>,
+    },
+}
+
+/// Plugins Gradle ships: unqualified ids such as `java`, and `org.gradle.*`.
+pub fn is_core_plugin(id: &str) -> bool {
+    !id.contains('.') || id.starts_with("org.gradle.")
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '.' | '$')
+}
+
+/// The byte range of the quoted string `text` starts with, after blanks and an
+/// optional `(`, without the quotes.
+fn string_argument(text: &str) -> Option<(usize, usize)> {
+    let trimmed = text.trim_start_matches([' ', '\t']);
+    let trimmed = trimmed.strip_prefix('(').unwrap_or(trimmed);
+    let trimmed = trimmed.trim_start_matches([' ', '\t']);
+    let quote = trimmed.chars().next().filter(|c| matches!(c, '"' | '\''))?;
+    let start = text.len() - trimmed.len() + 1;
+    let end = start + text[start..].find([quote, '\n'])?;
+    text[end..].starts_with(quote).then_some((start, end))
+}
+
+/// The calls of `name` in `text` with a string argument: the byte range of each
+/// argument, without quotes.
+fn string_calls(text: &str, name: &str) -> Vec<(usize, usize)> {
+    text.match_indices(name)
+        .filter(|(at, _)| {
+            !text[..*at]
+                .chars()
+                .next_back()
+                .is_some_and(is_identifier_char)
+                && text[at + name.len()..].starts_with([' ', '\t', '('])
+        })
+        .filter_map(|(at, _)| {
+            let after = at + name.len();
+            let (start, end) = string_argument(&text[after..])?;
+            Some((after + start, after + end))
+        })
+        .collect()
+}
+
+/// The byte ranges of the bodies of the `<name> { ... }` blocks in `content`.
+fn blocks<'c>(content: &'c str, name: &str) -> Vec<(usize, &'c str)> {
+    let mut bodies = Vec::new();
+    for (at, _) in content.match_indices(name) {
+        if content[..at]
+            .chars()
+            .next_back()
+            .is_some_and(is_identifier_char)
+        {
+            continue;
+        }
+        let rest = &content[at + name.len()..];
+        let Some(body) = rest.trim_start().strip_prefix('{') else {
+            continue;
+        };
+        let start = content.len() - body.len();
+        let mut depth = 1;
+        let end = body
+            .char_indices()
+            .find(|(_, c)| {
+                match c {
+                    '{' => depth += 1,
+                    '}' => depth -= 1,
+                    _ => {}
+                }
+                depth == 0
+            })
+            .map_or(body.len(), |(i, _)| i);
+        bodies.push((start, &body[..end]));
+    }
+    bodies
+}
+
+/// The plugins requested in the `plugins {}` blocks of a build or settings script.
+pub fn plugin_requests(content: &str) -> Vec<PluginRequest> {
+    let mut requests = Vec::new();
+    for (offset, body) in blocks(content, "plugins") {
+        for (name, prefix) in [("id", ""), ("kotlin", KOTLIN_PLUGIN_PREFIX)] {
+            for (start, end) in string_calls(body, name) {
+                let line_rest = &body[end + 1..];
+                let line_rest = &line_rest[..line_rest.find('\n').unwrap_or(line_rest.len())];
+                let version = string_calls(line_rest, "version")
+                    .first()
+                    .map(|(s, e)| line_rest[*s..*e].to_string());
+                requests.push(PluginRequest {
+                    id: format!("{prefix}{}", &body[start..end]),
+                    version,
+                    range: Range::new(
+                        byte_offset_to_position(content, offset + start),
+                        byte_offset_to_position(content, offset + end),
+                    ),
+                });
+            }
+        }
+    }
+    requests.sort_by_key(|r| r.range.start);
+    requests
+}
+
+/// The directories a settings script includes builds from with `includeBuild`,
+/// as written.
+pub fn included_builds(settings: &str) -> Vec<String> {
+    string_calls(settings, "includeBuild")
+        .into_iter()
+        .map(|(start, end)| settings[start..end].to_string())
+        .collect()
+}
+
+/// The plugins a build script registers in `gradlePlugin {}`: each id with its
+/// range and the `implementationClass` following it.
+pub fn plugin_registrations(content: &str) -> Vec<(String, Range, Option<String>)> {
+    let assigned = |text: &str, name: &str| -> Vec<(usize, usize)> {
+        text.match_indices(name)
+            .filter(|(at, _)| {
+                !text[..*at]
+                    .chars()
+                    .next_back()
+                    .is_some_and(is_identifier_char)
+            })
+            .filter_map(|(at, _)| {
+                let after = at + name.len();
+                let rest = &text[after..];
+                let value = rest.trim_start_matches([' ', '\t']);
+                let value = value.strip_prefix('=').unwrap_or(value);
+                let skipped = rest.len() - value.len();
+                if skipped == 0 {
+                    return None;
+                }
+                let (start, end) = string_argument(value)?;
+                Some((after + skipped + start, after + skipped + end))
+            })
+            .collect()
+    };
+
+    let mut registrations = Vec::new();
+    for (offset, body) in blocks(content, "gradlePlugin") {
+        let ids = assigned(body, "id");
+        let classes = assigned(body, "implementationClass");
+        for (i, (start, end)) in ids.iter().enumerate() {
+            let next_id = ids.get(i + 1).map_or(body.len(), |(s, _)| *s);
+            let class = classes
+                .iter()
+                .find(|(s, _)| s > end && *s < next_id)
+                .map(|(s, e)| body[*s..*e].to_string());
+            registrations.push((
+                body[*start..*end].to_string(),
+                Range::new(
+                    byte_offset_to_position(content, offset + start),
+                    byte_offset_to_position(content, offset + end),
+                ),
+                class,
+            ));
+        }
+    }
+    registrations
+}
+
+/// The root of the build `script` belongs to: the nearest directory with a
+/// settings script, else the script's own.
+fn build_root(script: &Path) -> Option<PathBuf> {
+    let dir = script.parent()?;
+    dir.ancestors()
+        .find(|d| d.join("settings.gradle").is_file() || d.join("settings.gradle.kts").is_file())
+        .or(Some(dir))
+        .map(Path::to_path_buf)
+}
+
+/// The plugins declared in `buildSrc` and the builds included by the build at `root`.
+pub fn local_plugins(root: &Path) -> Vec<LocalPlugin> {
+    let mut dirs = vec![root.join("buildSrc")];
+    for settings in ["settings.gradle", "settings.gradle.kts"] {
+        if let Ok(content) = std::fs::read_to_string(root.join(settings)) {
+            dirs.extend(included_builds(&content).iter().map(|d| root.join(d)));
+        }
+    }
+
+    let mut plugins = Vec::new();
+    for dir in dirs.iter().filter(|d| d.is_dir()) {
+        for entry in WalkDir::new(dir)
+            .into_iter()
+            .filter_entry(|e| !is_excluded(e))
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file() && is_gradle_script(e.path()))
+        {
+            let path = entry.path();
+            let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            let Ok(content) = std::fs::read_to_string(path) else {
+                continue;
+            };
+            let in_sources = path
+                .strip_prefix(dir)
+                .is_ok_and(|relative| relative.components().any(|c| c.as_os_str() == "src"));
+            if in_sources {
+                let stem = name
+                    .strip_suffix(".gradle.kts")
+                    .or_else(|| name.strip_suffix(".gradle"))
+                    .unwrap_or(name);
+                let package = content
+                    .lines()
+                    .find_map(|line| line.trim().strip_prefix("package "))
+                    .filter(|_| name.ends_with(".kts"))
+                    .map(|p| format!("{}.", p.trim()));
+                plugins.push(LocalPlugin {
+                    id: format!("{}{stem}", package.unwrap_or_default()),
+                    file: path.to_path_buf(),
+                    position: Position::new(0, 0),
+                    implementation_class: None,
+                });
+                continue;
+            }
+            for (id, range, implementation_class) in plugin_registrations(&content) {
+                plugins.push(LocalPlugin {
+                    id,
+                    file: path.to_path_buf(),
+                    position: range.start,
+                    implementation_class,
+                });
+            }
+        }
+    }
+    plugins
+}
+
+/// The implementation class of plugin `id` a plugin JAR declares.
+fn implementation_class(jar: &Path, id: &str) -> Option<String> {
+    let mut archive = ZipArchive::new(std::fs::File::open(jar).ok()?).ok()?;
+    let mut properties = String::new();
+    archive
+        .by_name(&format!("META-INF/gradle-plugins/{id}.properties"))
+        .ok()?
+        .read_to_string(&mut properties)
+        .ok()?;
+    properties.lines().find_map(|line| {
+        let (key, value) = line.split_once('=')?;
+        (key.trim() == "implementation-class").then(|| value.trim().to_string())
+    })
+}
+
+/// Where `request` comes from, among `local` plugins and the Gradle cache.  A
+/// request without a version takes the latest cached one.  Core plugins come from
+/// neither, unless the build declares one by that id.
+fn locate(request: &PluginRequest, local: &[LocalPlugin]) -> Option<PluginSource> {
+    if let Some(plugin) = local.iter().find(|p| p.id == request.id) {
+        return Some(PluginSource::Local(plugin.clone()));
+    }
+    if is_core_plugin(&request.id) {
+        return None;
+    }
+    let versions = cached_plugin_versions(&request.id)?;
+    let version = match &request.version {
+        Some(version) => versions.get(version).cloned(),
+        None => versions.into_iter().max_by(|a, b| compare_versions(a, b)),
+    }?;
+    let implementation_class = cached_plugin_jar(&request.id, &version)
+        .and_then(|jar| implementation_class(&jar, &request.id));
+    Some(PluginSource::Cached {
+        version,
+        implementation_class,
+    })
+}
+
+impl Backend {
+    /// The script at `params`, the plugin request under the cursor and the
+    /// plugins the script's build declares.
+    async fn plugin_request_at(
+        &self,
+        params: &TextDocumentPositionParams,
+    ) -> Option<(PluginRequest, Vec<LocalPlugin>)> {
+        let uri = &params.text_document.uri;
+        let path = uri.to_file_path().ok()?;
+        if !is_gradle_script(&path) {
+            return None;
+        }
+        let content = self.document_text(uri)?;
+        let position = params.position;
+        let request = plugin_requests(&content)
+            .into_iter()
+            .find(|r| r.range.start <= position && position <= r.range.end)?;
+        let root = build_root(&path)?;
+        let local = tokio::task::spawn_blocking(move || local_plugins(&root))
+            .await
+            .unwrap_or_default();
+        Some((request, local))
+    }
+
+    /// Go-to-definition on a plugin id: its local declaration, else its
+    /// implementation class.
+    pub(crate) async fn resolve_plugin_reference(
+        &self,
+        params: &TextDocumentPositionParams,
+    ) -> Option<Vec<ResolvedSymbol>> {
+        let (request, local) = self.plugin_request_at(params).await?;
+        let class = match locate(&request, &local) {
+            Some(PluginSource::Local(plugin)) => match plugin.implementation_class {
+                Some(class) => class,
+                None => {
+                    return Some(vec![ResolvedSymbol::Local {
+                        uri: Url::from_file_path(&plugin.file).ok()?,
+                        position: plugin.position,
+                        name: plugin.id,
+                        var_type: None,
+                    }]);
+                }
+            },
+            Some(PluginSource::Cached {
+                implementation_class: Some(class),
+                ..
+            }) => class,
+            _ => return Some(vec![]),
+        };
+        let repo = self.repo.get()?;
+        if let Ok(Some(symbol)) = repo.find_symbol_by_fqn(&class).await {
+            return Some(vec![ResolvedSymbol::Project(symbol)]);
+        }
+        if let Ok(Some(symbol)) = repo.find_external_symbol_by_fqn(&class).await {
+            return Some(vec![ResolvedSymbol::External(symbol)]);
+        }
+        // A registration whose class is not indexed yet is still a declaration to go to.
+        let Some(plugin) = local.into_iter().find(|p| p.id == request.id) else {
+            return Some(vec![]);
+        };
+        Some(
+            Url::from_file_path(&plugin.file)
+                .map(|uri| ResolvedSymbol::Local {
+                    uri,
+                    position: plugin.position,
+                    name: plugin.id,
+                    var_type: None,
+                })
+                .into_iter()
+                .collect(),
+        )
+    }
+
+    /// Hover on a plugin id: where it comes from and its implementation class.
+    pub(crate) async fn plugin_hover(&self, params: &TextDocumentPositionParams) -> Option<Hover> {
+        let (request, local) = self.plugin_request_at(params).await?;
+        let mut lines = vec![format!("**{}**", request.id)];
+        match locate(&request, &local) {
+            None if is_core_plugin(&request.id) => lines.push("Core Gradle plugin".to_string()),
+            None => return None,
+            Some(PluginSource::Local(plugin)) => match plugin.implementation_class {
+                Some(class) => lines.push(format!("Implementation class: `{class}`")),
+                None => lines.push(format!(
+                    "Precompiled script plugin `{}`",
+                    plugin.file.file_name()?.to_string_lossy()
+                )),
+            },
+            Some(PluginSource::Cached {
+                version,
+                implementation_class,
+            }) => {
+                lines[0] = format!("**{}** {version}", request.id);
+                lines.push(match implementation_class {
+                    Some(class) => format!("Implementation class: `{class}`"),
+                    None => format!(
+                        "Marker artifact `{0}:{0}.gradle.plugin:{version}`",
+                        request.id
+                    ),
+                });
+            }
+        }
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: lines.join("\n\n"),
+            }),
+            range: Some(request.range),
+        })
+    }
+
+    /// The `unknown_plugin` warnings of the build script at `path`: plugin ids
+    /// neither declared in the build nor in the Gradle cache.  Nothing until
+    /// Gradle resolved a plugin the script requests from outside the build, as
+    /// before the first sync the cache cannot tell a missing plugin.
+    pub(crate) async fn unknown_plugin_diagnostics(
+        &self,
+        uri: &Url,
+        path: &Path,
+    ) -> Vec<Diagnostic> {
+        let Some(content) = self.document_text(uri) else {
+            return vec![];
+        };
+        let requests: Vec<PluginRequest> = plugin_requests(&content)
+            .into_iter()
+            .filter(|r| !is_core_plugin(&r.id))
+            .collect();
+        if requests.is_empty() {
+            return vec![];
+        }
+        let Some(root) = build_root(path) else {
+            return vec![];
+        };
+        tokio::task::spawn_blocking(move || {
+            let local = local_plugins(&root);
+            let populated = requests
+                .iter()
+                .filter(|r| !local.iter().any(|p| p.id == r.id))
+                .any(|r| cached_plugin_versions(&r.id).is_some_and(|v| !v.is_empty()));
+            if !populated {
+                return vec![];
+            }
+            requests
+                .into_iter()
+                .filter(|r| locate(r, &local).is_none())
+                .map(|r| {
+                    let requested = match &r.version {
+                        Some(version) => format!("Plugin '{}' version {version}", r.id),
+                        None => format!("Plugin '{}'", r.id),
+                    };
+                    Diagnostic {
+                        range: r.range,
+                        severity: Some(DiagnosticSeverity::WARNING),
+                        code: Some(NumberOrString::String(UNKNOWN_PLUGIN_CODE.to_string())),
+                        source: Some("lspintar".to_string()),
+                        message: format!(
+                            "{requested} is neither in the Gradle cache nor declared in buildSrc or an included build"
+                        ),
+                        ..Default::default()
+                    }
+                })
+                .collect()
+        })
+        .await
+        .unwrap_or_default()
+    }
+}
@@ -0,0 +1,325 @@
+//! Gradle task names in build scripts.
+//!
+//! A task is registered under a name — `tasks.register("docs")`, `task docs`,
+//! `val docs by tasks.registering` — and referred to by that name in strings:
+//! `tasks.named("docs")`, `dependsOn("docs")`, `finalizedBy 'docs'`.  Go-to-definition
+//! on such a string lands on the registrations of that name in the build, which is
+//! the script itself and every script connected to it by `apply from:`, in either
+//! direction.  Tasks that plugins add (`jar`, `compileJava`) have no registration
+//! in any script and resolve to nothing.
+//!
+//! The scan is textual, like that of Spring XML files, so it reads Groovy and
+//! Kotlin DSL scripts alike.
+
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use lsp_core::{
+    build_tools::script_lexer::{ScriptToken, is_identifier_char, script_tokens, string_end},
+    ts_helper::byte_offset_to_position,
+};
+use tower_lsp::lsp_types::{Range, TextDocumentPositionParams, Url};
+use walkdir::WalkDir;
+
+use crate::{enums::ResolvedSymbol, indexer::is_excluded, server::Backend};
+
+/// `tasks.` methods whose first string argument registers a task.
+const REGISTERING_METHODS: &[&str] = &["register", "create"];
+
+/// `tasks.` methods whose first string argument names an existing task.
+const LOOKUP_METHODS: &[&str] = &[
+    "named",
+    "getByName",
+    "findByName",
+    "getByPath",
+    "findByPath",
+];
+
+/// Task methods every string argument of which names a task.
+const DEPENDENCY_METHODS: &[&str] = &["dependsOn", "finalizedBy", "mustRunAfter", "shouldRunAfter"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TaskMentionKind {
+    Registration,
+    Reference,
+}
+
+/// A task name found in a build script.  `range` covers the name only.
+#[derive(Debug, Clone)]
+pub(crate) struct TaskMention {
+    pub kind: TaskMentionKind,
+    pub name: String,
+    pub range: Range,
+}
+
+/// True for Groovy and Kotlin DSL build scripts.
+pub(crate) fn is_gradle_script(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| n.ends_with(".gradle") || n.ends_with(".gradle.kts"))
+}
+
+/// The task registrations and references of a build script.
+pub(crate) fn task_mentions(content: &str) -> Vec<TaskMention> {
+    let mut mentions = Vec::new();
+    let mut push = |kind, (name, start): (&str, usize)| {
+        mentions.push(TaskMention {
+            kind,
+            name: name.to_string(),
+            range: Range::new(
+                byte_offset_to_position(content, start),
+                byte_offset_to_position(content, start + name.len()),
+            ),
+        });
+    };
+
+    for (word, end) in words(content) {
+        let after = &content[end..];
+        let receiver_is_tasks = content[..end - word.len()].ends_with("tasks.");
+        if receiver_is_tasks && REGISTERING_METHODS.contains(&word) {
+            if let Some(first) = string_arguments(content, end).into_iter().next() {
+                push(TaskMentionKind::Registration, first);
+            }
+        } else if receiver_is_tasks && LOOKUP_METHODS.contains(&word) {
+            if let Some(first) = string_arguments(content, end).into_iter().next() {
+                push(TaskMentionKind::Reference, first);
+            }
+        } else if DEPENDENCY_METHODS.contains(&word) {
+            for argument in string_arguments(content, end) {
+                push(TaskMentionKind::Reference, argument);
+            }
+        } else if word == "task" && !content[..end - word.len()].ends_with('.') {
+            // `task("docs")`, `task docs`, `task docs(type: Copy)`, `task docs { }`.
+            let registered = string_arguments(content, end)
+                .into_iter()
+                .next()
+                .or_else(|| leading_identifier(content, end));
+            if let Some(name) = registered {
+                push(TaskMentionKind::Registration, name);
+            }
+        } else if word == "by" && after.trim_start().starts_with("tasks.register") {
+            // `val docs by tasks.registering`
+            let before = content[..end - word.len()].trim_end();
+            let name_start = before
+                .rfind(|c: char| !is_identifier_char(c))
+                .map_or(0, |i| i + 1);
+            if content[..name_start].trim_end().ends_with("val") {
+                push(
+                    TaskMentionKind::Registration,
+                    (&before[name_start..], name_start),
+                );
+            }
+        }
+    }
+    mentions
+}
+
+/// Every identifier in `content` outside comments and strings, with the byte offset
+/// just past it.
+pub(crate) fn words(content: &str) -> Vec<(&str, usize)> {
+    script_tokens(content)
+        .into_iter()
+        .filter_map(|token| match token {
+            ScriptToken::Word(word, end) => Some((word, end)),
+            ScriptToken::Literal(..) => None,
+        })
+        .collect()
+}
+
+/// The string literals passed to the call whose name ends at `end`, with the byte
+/// offset of each body: the arguments in parentheses, or up to the end of the line
+/// or an opening brace for a call without them.  Named arguments are skipped, and so
+/// are type arguments, as in `register<Copy>("docs")`.
+fn string_arguments(content: &str, end: usize) -> Vec<(&str, usize)> {
+    let mut rest = &content[end..];
+    if let Some(generic) = rest.strip_prefix('<')
+        && let Some(close) = generic.find('>')
+    {
+        rest = &generic[close + 1..];
+    }
+    let end = content.len() - rest.len();
+    let trimmed = rest.trim_start_matches([' ', '\t']);
+    let parenthesized = trimmed.starts_with('(');
+    let mut offset = end + (rest.len() - trimmed.len()) + usize::from(parenthesized);
+
+    let mut arguments = Vec::new();
+    let mut depth = 0usize;
+    let mut named = false;
+    while let Some(c) = content[offset..].chars().next() {
+        match c {
+            '"' | '\'' => {
+                let Some(close) = string_end(content, offset) else {
+                    break;
+                };
+                let body = &content[offset + 1..close - 1];
+                if depth == 0 && !named && !body.contains('$') {
+                    arguments.push((body, offset + 1));
+                }
+                offset = close;
+                continue;
+            }
+            '(' | '[' => depth += 1,
+            ')' | ']' if depth > 0 => depth -= 1,
+            ')' => break,
+            ',' if depth == 0 => named = false,
+            ':' | '=' if depth == 0 => named = true,
+            '{' | '\n' if !parenthesized => break,
+            _ => {}
+        }
+        offset += c.len_utf8();
+    }
+    arguments
+}
+
+/// The identifier right after `end` on the same line, for `task docs`.
+fn leading_identifier(content: &str, end: usize) -> Option<(&str, usize)> {
+    let rest = &content[end..];
+    let trimmed = rest.trim_start_matches([' ', '\t']);
+    if trimmed.len() == rest.len() {
+        return None;
+    }
+    let len = trimmed
+        .find(|c: char| !is_identifier_char(c))
+        .unwrap_or(trimmed.len());
+    (len > 0).then(|| (&trimmed[..len], end + rest.len() - trimmed.len()))
+}
+
+/// The scripts `content`, found at `script`, applies with `apply from:`, resolved
+/// against its directory or, for `$rootDir/...`, against `root`.
+fn applied_scripts(script: &Path, content: &str, root: &Path) -> Vec<PathBuf> {
+    let dir = script.parent().unwrap_or(root);
+    words(content)
+        .into_iter()
+        .filter(|(word, _)| *word == "apply")
+        .filter_map(|(_, end)| {
+            let rest = content[end..]
+                .trim_start()
+                .trim_start_matches('(')
+                .trim_start();
+            let rest = rest.strip_prefix("from")?.trim_start();
+            let rest = rest.strip_prefix(':').or_else(|| rest.strip_prefix('='))?;
+            let rest = rest.trim_start();
+            let quote = rest.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+            let target = &rest[1..1 + rest[1..].find(quote)?];
+            let target = match target
+                .strip_prefix("${rootDir}/")
+                .or_else(|| target.strip_prefix("$rootDir/"))
+                .or_else(|| target.strip_prefix("${rootProject.projectDir}/"))
+            {
+                Some(relative) => root.join(relative),
+                None if target.contains('$') || target.contains("://") => return None,
+                None => dir.join(target),
+            };
+            Some(target.canonicalize().unwrap_or(target))
+        })
+        .collect()
+}
+
+impl Backend {
+    /// Resolves a task name at the request position in a build script to the places
+    /// the build registers that task.  Returns `None` anywhere else, so regular
+    /// resolution applies.
+    pub(crate) async fn resolve_gradle_task_reference(
+        &self,
+        params: &TextDocumentPositionParams,
+    ) -> Option<Vec<ResolvedSymbol>> {
+        let uri = &params.text_document.uri;
+        let path = uri.to_file_path().ok()?;
+        if !is_gradle_script(&path) {
+            return None;
+        }
+        let path = path.canonicalize().unwrap_or(path);
+        let content = self.document_text(uri)?;
+        let position = params.position;
+        let mention = task_mentions(&content).into_iter().find(|m| {
+            m.range.start.line == position.line
+                && m.range.start.character <= position.character
+                && position.character <= m.range.end.character
+        })?;
+
+        let root = self
+            .workspace_root
+            .read()
+            .await
+            .clone()
+            .unwrap_or_else(|| path.parent().unwrap_or(&path).to_path_buf());
+        let mut registrations = Vec::new();
+        for script in self.connected_scripts(&path, &content, &root).await {
+            let text = match Url::from_file_path(&script) {
+                Ok(script_uri) if script == path => Some(content.clone()).zip(Some(script_uri)),
+                Ok(script_uri) => self.document_text(&script_uri).zip(Some(script_uri)),
+                Err(_) => None,
+            };
+            let Some((text, script_uri)) = text else {
+                continue;
+            };
+            registrations.extend(
+                task_mentions(&text)
+                    .into_iter()
+                    .filter(|m| m.kind == TaskMentionKind::Registration && m.name == mention.name)
+                    .map(|m| ResolvedSymbol::Local {
+                        uri: script_uri.clone(),
+                        position: m.range.start,
+                        name: m.name,
+                        var_type: None,
+                    }),
+            );
+        }
+        Some(registrations)
+    }
+
+    /// `script` with every build script under `root` linked to it through `apply from:`,
+    /// in either direction and transitively.
+    async fn connected_scripts(&self, script: &Path, content: &str, root: &Path) -> Vec<PathBuf> {
+        let script = script.to_path_buf();
+        let root_dir = root.to_path_buf();
+        let edges: Vec<(PathBuf, Vec<PathBuf>)> = tokio::task::spawn_blocking(move || {
+            WalkDir::new(&root_dir)
+                .follow_links(true)
+                .into_iter()
+                .filter_entry(|e| !is_excluded(e))
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_type().is_file() && is_gradle_script(e.path()))
+                .filter_map(|e| {
+                    let path = e.path().canonicalize().ok()?;
+                    let text = std::fs::read_to_string(&path).ok()?;
+                    let applied = applied_scripts(&path, &text, &root_dir);
+                    Some((path, applied))
+                })
+                .collect()
+        })
+        .await
+        .unwrap_or_default();
+
+        // The open buffer may apply scripts its saved copy does not.
+        let own = applied_scripts(&script, content, root);
+        let mut connected = vec![script.clone()];
+        let mut seen: HashSet<PathBuf> = HashSet::from([script.clone()]);
+        let mut i = 0;
+        while i < connected.len() {
+            let current = connected[i].clone();
+            i += 1;
+            let outgoing = if current == script {
+                own.clone()
+            } else {
+                edges
+                    .iter()
+                    .find(|(path, _)| *path == current)
+                    .map(|(_, applied)| applied.clone())
+                    .unwrap_or_default()
+            };
+            let incoming = edges
+                .iter()
+                .filter(|(_, applied)| applied.contains(&current))
+                .map(|(path, _)| path.clone());
+            for next in outgoing.into_iter().chain(incoming) {
+                if next.is_file() && seen.insert(next.clone()) {
+                    connected.push(next);
+                }
+            }
+        }
+        connected
+    }
+}
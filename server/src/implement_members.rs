@@ -0,0 +1,620 @@
+//! "Implement" quick fixes for `unimplemented_abstract_methods`, and "Override"
+//! actions for the methods a class inherits.
+//!
+//! Each diagnostic names the missing member in a [`MissingMember`]; the code action
+//! handler turns it into a fix inserting a stub override at the end of the class
+//! body: the supertype's declaration with its parameter names and types and its
+//! return type, and a body that throws (Java, Groovy) or calls `TODO()` (Kotlin).
+//! The declaration is read from the project's index, or for a library supertype
+//! from its sources JAR or decompiled class, so it is looked up only when the
+//! client asks for actions.  When a class misses several members, the handler also
+//! offers to implement them all at once.
+//!
+//! Inside a class body, each method of its supertypes that it may override and does
+//! not is offered as a `source.overrideMethods` action, nearest supertype first.
+//! The stub of a method with a body calls the super implementation, through the
+//! direct superinterface that inherits it when there is one (`Greeter.super.greet`,
+//! `super<Greeter>.greet`).
+
+use lsp_core::{language_support::MethodSig, languages::Language, ts_helper::get_node_at_position};
+use std::{collections::HashMap, path::PathBuf};
+
+use tower_lsp::lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOrCommand, Diagnostic, NumberOrString, Position, Range,
+    TextEdit, Url, WorkspaceEdit,
+};
+use tree_sitter::{Node, Tree};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{code_action::QuickFix, models::symbol::SymbolMetadata, server::Backend};
+
+pub(crate) const UNIMPLEMENTED_ABSTRACT_METHODS_CODE: &str = "unimplemented_abstract_methods";
+
+/// The kind of the "Override" actions.
+pub(crate) const OVERRIDE_METHODS_KIND: &str = "source.overrideMethods";
+
+const CLASS_BODY_KINDS: &[&str] = &[
+    "class_body",
+    "interface_body",
+    "enum_body",
+    "enum_class_body",
+];
+
+/// The member an `unimplemented_abstract_methods` diagnostic reports, in its `data`
+/// field.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct MissingMember {
+    /// The supertype declaring the member abstract.
+    pub parent_fqn: String,
+    pub name: String,
+    pub param_types: Vec<String>,
+}
+
+impl MissingMember {
+    pub(crate) fn new(parent_fqn: &str, required: &MethodSig) -> Self {
+        Self {
+            parent_fqn: parent_fqn.to_string(),
+            name: required.name.clone(),
+            param_types: required.param_types.clone(),
+        }
+    }
+
+    pub(crate) fn to_data(&self) -> Option<serde_json::Value> {
+        serde_json::to_value(self).ok()
+    }
+
+    fn from_diagnostic(diagnostic: &Diagnostic) -> Option<Self> {
+        let code = NumberOrString::String(UNIMPLEMENTED_ABSTRACT_METHODS_CODE.to_string());
+        if diagnostic.source.as_deref() != Some("lspintar") || diagnostic.code != Some(code) {
+            return None;
+        }
+        serde_json::from_value(diagnostic.data.clone()?).ok()
+    }
+}
+
+/// Where stubs go in a class body, and the class declaration's indentation.
+pub(crate) struct BodyEnd {
+    position: Position,
+    /// Whether the closing brace shares its line with other code, e.g. `class A {}`.
+    inline: bool,
+    indent: String,
+}
+
+/// The declaration of the class named at `ident_range`, with its body.
+fn class_and_body<'t>(
+    tree: &'t Tree,
+    content: &str,
+    ident_range: Range,
+) -> Option<(Node<'t>, Node<'t>)> {
+    let ident = get_node_at_position(tree, content, &ident_range.start)?;
+    let class = std::iter::successors(ident.parent(), |n| n.parent())
+        .find(|n| n.kind().contains("declaration") || n.kind().contains("definition"))?;
+    let mut cursor = class.walk();
+    let body = class
+        .children(&mut cursor)
+        .find(|n| CLASS_BODY_KINDS.contains(&n.kind()))?;
+    Some((class, body))
+}
+
+/// The end of the body of the class named at `ident_range`, `None` when it has none.
+pub(crate) fn class_body_end(tree: &Tree, content: &str, ident_range: Range) -> Option<BodyEnd> {
+    let (class, body) = class_and_body(tree, content, ident_range)?;
+    if !body.utf8_text(content.as_bytes()).ok()?.ends_with('}') {
+        return None;
+    }
+
+    let brace = body.end_position();
+    let brace_line = content.lines().nth(brace.row)?;
+    let before_brace = brace_line.get(..brace.column.saturating_sub(1))?;
+    let class_line = content.lines().nth(class.start_position().row)?;
+    let indent: String = class_line
+        .chars()
+        .take_while(|c| c.is_whitespace())
+        .collect();
+    let inline = !before_brace.trim().is_empty();
+    let position = if inline {
+        Position::new(brace.row as u32, brace.column.saturating_sub(1) as u32)
+    } else {
+        Position::new(brace.row as u32, 0)
+    };
+    Some(BodyEnd {
+        position,
+        inline,
+        indent,
+    })
+}
+
+/// The Kotlin name of a Java type.
+fn kotlin_type(java_type: &str) -> String {
+    match java_type {
+        "int" | "Integer" => "Int".to_string(),
+        "long" => "Long".to_string(),
+        "short" => "Short".to_string(),
+        "byte" => "Byte".to_string(),
+        "char" | "Character" => "Char".to_string(),
+        "float" => "Float".to_string(),
+        "double" => "Double".to_string(),
+        "boolean" => "Boolean".to_string(),
+        "Object" | "java.lang.Object" => "Any".to_string(),
+        "void" => "Unit".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// What a stub's body does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StubBody<'a> {
+    /// Throws (Java, Groovy) or calls `TODO()` (Kotlin).
+    Unimplemented,
+    /// Calls the overridden implementation, qualified with the named direct
+    /// superinterface when it is inherited from one.
+    CallSuper(Option<&'a str>),
+}
+
+/// The stub overriding a method declared with `metadata`, indented one level below
+/// `indent`.
+fn stub(
+    language: Language,
+    name: &str,
+    metadata: &SymbolMetadata,
+    modifiers: &[String],
+    indent: &str,
+    stub_body: StubBody,
+) -> String {
+    let member = format!("{indent}    ");
+    let body = format!("{indent}        ");
+    let parameters: Vec<(String, String)> = metadata
+        .parameters
+        .iter()
+        .flatten()
+        .enumerate()
+        .map(|(i, p)| {
+            let name = if p.name.is_empty() {
+                format!("arg{i}")
+            } else {
+                p.name.clone()
+            };
+            (name, p.type_name.clone().unwrap_or_default())
+        })
+        .collect();
+    let return_type = metadata.return_type.as_deref().filter(|t| !t.is_empty());
+    let arguments: Vec<&str> = parameters.iter().map(|(name, _)| name.as_str()).collect();
+    let arguments = arguments.join(", ");
+    let super_call = match (stub_body, language) {
+        (StubBody::CallSuper(Some(interface)), Language::Kotlin) => {
+            format!("super<{interface}>.{name}({arguments})")
+        }
+        (StubBody::CallSuper(Some(interface)), _) => {
+            format!("{interface}.super.{name}({arguments})")
+        }
+        _ => format!("super.{name}({arguments})"),
+    };
+
+    match language {
+        Language::Kotlin => {
+            let parameters: Vec<String> = parameters
+                .iter()
+                .map(|(name, ty)| {
+                    let ty = if ty.is_empty() {
+                        "Any".to_string()
+                    } else {
+                        kotlin_type(ty)
+                    };
+                    format!("{name}: {ty}")
+                })
+                .collect();
+            let returns = match return_type.map(kotlin_type) {
+                Some(ty) if ty != "Unit" => format!(": {ty}"),
+                _ => String::new(),
+            };
+            let statement = match stub_body {
+                StubBody::Unimplemented => "TODO(\"Not yet implemented\")".to_string(),
+                StubBody::CallSuper(_) if returns.is_empty() => super_call,
+                StubBody::CallSuper(_) => format!("return {super_call}"),
+            };
+            format!(
+                "{member}override fun {name}({}){returns} {{\n\
+                 {body}{statement}\n\
+                 {member}}}\n",
+                parameters.join(", ")
+            )
+        }
+        Language::Java | Language::Groovy => {
+            let parameters: Vec<String> = parameters
+                .iter()
+                .map(|(name, ty)| {
+                    let ty = if ty.is_empty() { "Object" } else { ty.as_str() };
+                    format!("{ty} {name}")
+                })
+                .collect();
+            let visibility = if modifiers.iter().any(|m| m == "protected") {
+                "protected"
+            } else {
+                "public"
+            };
+            let returns = return_type.unwrap_or("void");
+            let statement = match stub_body {
+                StubBody::Unimplemented if returns == "void" => String::new(),
+                StubBody::Unimplemented => {
+                    format!("{body}throw new UnsupportedOperationException(\"Not implemented\");\n")
+                }
+                StubBody::CallSuper(_) if returns == "void" => format!("{body}{super_call};\n"),
+                StubBody::CallSuper(_) => format!("{body}return {super_call};\n"),
+            };
+            format!(
+                "{member}@Override\n\
+                 {member}{visibility} {returns} {name}({}) {{\n\
+                 {statement}\
+                 {member}}}\n",
+                parameters.join(", ")
+            )
+        }
+    }
+}
+
+/// The edit inserting `stub` at `body_end`.
+pub(crate) fn insertion(body_end: &BodyEnd, stub: &str) -> TextEdit {
+    let text = if body_end.inline {
+        format!("\n{stub}{}", body_end.indent)
+    } else {
+        format!("\n{stub}")
+    };
+    TextEdit::new(Range::new(body_end.position, body_end.position), text)
+}
+
+/// An "Implement" quick fix for `diagnostic`.
+fn implement_action(uri: &Url, diagnostic: &Diagnostic, fix: QuickFix) -> CodeActionOrCommand {
+    CodeActionOrCommand::CodeAction(CodeAction {
+        title: fix.title,
+        kind: Some(CodeActionKind::QUICKFIX),
+        diagnostics: Some(vec![diagnostic.clone()]),
+        edit: Some(WorkspaceEdit {
+            changes: Some(HashMap::from([(uri.clone(), fix.edits)])),
+            ..Default::default()
+        }),
+        is_preferred: Some(true),
+        ..Default::default()
+    })
+}
+
+/// "Implement all missing members" for the first class in `fixes` that misses more
+/// than one, merging their fixes.
+fn implement_all_action(
+    uri: &Url,
+    fixes: &[(&Diagnostic, QuickFix)],
+) -> Option<CodeActionOrCommand> {
+    let class = fixes.first()?.0.range;
+    let (class_diagnostics, fixes): (Vec<Diagnostic>, Vec<QuickFix>) = fixes
+        .iter()
+        .filter(|(d, _)| d.range == class)
+        .map(|(d, fix)| ((*d).clone(), fix.clone()))
+        .unzip();
+    if fixes.len() < 2 {
+        return None;
+    }
+    Some(CodeActionOrCommand::CodeAction(CodeAction {
+        title: format!("Implement all {} missing members", fixes.len()),
+        kind: Some(CodeActionKind::QUICKFIX),
+        diagnostics: Some(class_diagnostics),
+        edit: Some(WorkspaceEdit {
+            changes: Some(HashMap::from([(
+                uri.clone(),
+                fixes.into_iter().flat_map(|fix| fix.edits).collect(),
+            )])),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }))
+}
+
+impl Backend {
+    /// The "Implement" quick fixes for the `unimplemented_abstract_methods`
+    /// diagnostics among `diagnostics`, and "Implement all" for a class missing
+    /// several members.
+    pub(crate) async fn implement_actions(
+        &self,
+        uri: &Url,
+        diagnostics: &[Diagnostic],
+    ) -> Vec<CodeActionOrCommand> {
+        let missing: Vec<(&Diagnostic, MissingMember)> = diagnostics
+            .iter()
+            .filter_map(|d| Some((d, MissingMember::from_diagnostic(d)?)))
+            .collect();
+        if missing.is_empty() {
+            return vec![];
+        }
+        let path = PathBuf::from(uri.path());
+        let Some(lang) = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .and_then(|ext| self.languages.get(ext))
+        else {
+            return vec![];
+        };
+        let Some(content) = self.document_text(uri) else {
+            return vec![];
+        };
+        let Some((tree, _)) = lang.parse_str(&content) else {
+            return vec![];
+        };
+
+        let mut fixes = Vec::new();
+        for (diagnostic, member) in missing {
+            let Some(body_end) = class_body_end(&tree, &content, diagnostic.range) else {
+                continue;
+            };
+            let required = MethodSig::new(member.name, member.param_types);
+            if let Some(fix) = self
+                .implement_member_fix(
+                    &member.parent_fqn,
+                    &required,
+                    lang.get_language(),
+                    &body_end,
+                )
+                .await
+            {
+                fixes.push((diagnostic, fix));
+            }
+        }
+        let mut actions: Vec<CodeActionOrCommand> = fixes
+            .iter()
+            .map(|(diagnostic, fix)| implement_action(uri, diagnostic, fix.clone()))
+            .collect();
+        actions.extend(implement_all_action(uri, &fixes));
+        actions
+    }
+
+    /// The fix implementing `required`, declared abstract in `parent_fqn`, at the end
+    /// of a class body.
+    async fn implement_member_fix(
+        &self,
+        parent_fqn: &str,
+        required: &MethodSig,
+        language: Language,
+        body_end: &BodyEnd,
+    ) -> Option<QuickFix> {
+        let repo = self.repo.get()?;
+        let declares = |name: &str, metadata: &SymbolMetadata| {
+            let param_types = metadata
+                .parameters
+                .iter()
+                .flatten()
+                .map(|p| p.type_name.clone().unwrap_or_default())
+                .collect();
+            MethodSig::new(name, param_types) == *required
+        };
+
+        let project = repo
+            .find_symbols_by_parent_name(parent_fqn)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .find(|s| s.symbol_type == "Function" && declares(&s.short_name, &s.metadata));
+        let (metadata, modifiers) = match project {
+            Some(symbol) => (symbol.metadata.0, symbol.modifiers.0),
+            None => {
+                let external = repo
+                    .find_external_symbols_by_parent_name(parent_fqn)
+                    .await
+                    .unwrap_or_default()
+                    .into_iter()
+                    .find(|s| {
+                        s.symbol_type == "Function" && declares(&s.short_name, &s.metadata)
+                    })?;
+                let indexer = self.indexer.read().await.as_ref().cloned();
+                let external = external.with_sources(indexer.as_ref()).await;
+                (external.metadata.0, external.modifiers.0)
+            }
+        };
+
+        let title = format!(
+            "Implement '{}({})'",
+            required.name,
+            required.param_types.join(", ")
+        );
+        let stub = stub(
+            language,
+            &required.name,
+            &metadata,
+            &modifiers,
+            &body_end.indent,
+            StubBody::Unimplemented,
+        );
+        Some(QuickFix::new(title, vec![insertion(body_end, &stub)]))
+    }
+
+    /// True when `fqn` names a project or library interface.
+    async fn is_interface(&self, fqn: &str) -> bool {
+        let Some(repo) = self.repo.get() else {
+            return false;
+        };
+        if let Some(symbol) = repo.find_symbol_by_fqn(fqn).await.ok().flatten() {
+            return symbol.symbol_type == "Interface";
+        }
+        repo.find_external_symbol_by_fqn(fqn)
+            .await
+            .ok()
+            .flatten()
+            .is_some_and(|s| s.symbol_type == "Interface")
+    }
+
+    /// The methods declared in `type_fqn` that a subclass may override, each with its
+    /// metadata and modifiers and whether it is abstract.
+    async fn overridable_methods(
+        &self,
+        type_fqn: &str,
+    ) -> Vec<(String, SymbolMetadata, Vec<String>, bool)> {
+        let Some(repo) = self.repo.get() else {
+            return vec![];
+        };
+        let type_name = type_fqn.rsplit(['.', '$']).next().unwrap_or(type_fqn);
+        let declared = |name: &str, modifiers: &[String]| {
+            name != type_name
+                && !name.starts_with('<')
+                && !modifiers
+                    .iter()
+                    .any(|m| matches!(m.as_str(), "private" | "static" | "final"))
+        };
+
+        let project_type = repo.find_symbol_by_fqn(type_fqn).await.ok().flatten();
+        let mut methods = Vec::new();
+        if let Some(project_type) = project_type {
+            let is_interface = project_type.symbol_type == "Interface";
+            for symbol in repo
+                .find_symbols_by_parent_name(type_fqn)
+                .await
+                .unwrap_or_default()
+            {
+                let modifiers = symbol.modifiers.0;
+                let has = |m: &str| modifiers.iter().any(|x| x == m);
+                // Kotlin members are final unless declared open.
+                let open = symbol.file_type != "kotlin"
+                    || is_interface
+                    || has("open")
+                    || has("abstract")
+                    || has("override");
+                if symbol.symbol_type != "Function"
+                    || !open
+                    || !declared(&symbol.short_name, &modifiers)
+                {
+                    continue;
+                }
+                // Kotlin interface functions carry no `default`; what makes them
+                // abstract is the missing body.
+                let is_abstract =
+                    has("abstract") || (is_interface && !has("static") && !self.has_body(&symbol));
+                methods.push((symbol.short_name, symbol.metadata.0, modifiers, is_abstract));
+            }
+            return methods;
+        }
+
+        let is_interface = repo
+            .find_external_symbol_by_fqn(type_fqn)
+            .await
+            .ok()
+            .flatten()
+            .is_some_and(|s| s.symbol_type == "Interface");
+        let indexer = self.indexer.read().await.as_ref().cloned();
+        for symbol in repo
+            .find_external_symbols_by_parent_name(type_fqn)
+            .await
+            .unwrap_or_default()
+        {
+            if symbol.symbol_type != "Function"
+                || !declared(&symbol.short_name, &symbol.modifiers.0)
+            {
+                continue;
+            }
+            // Bytecode flags every bodiless method abstract, interface or not.
+            let from_bytecode = symbol.needs_decompilation;
+            let symbol = symbol.with_sources(indexer.as_ref()).await;
+            let modifiers = symbol.modifiers.0;
+            let has = |m: &str| modifiers.iter().any(|x| x == m);
+            let is_abstract = has("abstract")
+                || (!from_bytecode && is_interface && !has("default") && !has("static"));
+            methods.push((symbol.short_name, symbol.metadata.0, modifiers, is_abstract));
+        }
+        methods
+    }
+
+    /// "Override" actions for the class whose body holds `position` in `uri`: one per
+    /// inherited method it may override and does not, nearest supertype first.
+    pub(crate) async fn override_actions(
+        &self,
+        uri: &Url,
+        position: Position,
+    ) -> Vec<CodeActionOrCommand> {
+        let path = PathBuf::from(uri.path());
+        let Some(lang) = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .and_then(|ext| self.languages.get(ext))
+        else {
+            return vec![];
+        };
+        let Some(content) = self.document_text(uri) else {
+            return vec![];
+        };
+        let Some((tree, _)) = lang.parse_str(&content) else {
+            return vec![];
+        };
+        let point = tree_sitter::Point::new(position.line as usize, position.character as usize);
+        let Some((class, body_end)) = lang
+            .get_class_declarations(&tree, &content)
+            .into_iter()
+            .filter_map(|class| {
+                let (declaration, body) = class_and_body(&tree, &content, class.ident_range)?;
+                let inside = body.start_position() < point && point < body.end_position();
+                let body_end = class_body_end(&tree, &content, class.ident_range)?;
+                inside.then_some((declaration.start_byte(), class, body_end))
+            })
+            .max_by_key(|(start, _, _)| *start)
+            .map(|(_, class, body_end)| (class, body_end))
+        else {
+            return vec![];
+        };
+
+        let imports = lang.get_imports(&tree, &content);
+        let package = lang.get_package_name(&tree, &content);
+        let mut covered = class.defined_methods.clone();
+        let mut actions = Vec::new();
+        for parent_name in &class.parents {
+            let Some(parent_fqn) = self
+                .resolve_fqn(parent_name, imports.clone(), package.clone())
+                .await
+            else {
+                continue;
+            };
+            // `super.m()` does not reach an interface's method; the call names the
+            // direct superinterface it is inherited through.
+            let qualifier = self
+                .is_interface(&parent_fqn)
+                .await
+                .then(|| parent_name.split('<').next().unwrap_or(parent_name).trim());
+            for type_fqn in self.type_and_supertypes(&parent_fqn).await {
+                for (name, metadata, modifiers, is_abstract) in
+                    self.overridable_methods(&type_fqn).await
+                {
+                    let param_types = metadata
+                        .parameters
+                        .iter()
+                        .flatten()
+                        .map(|p| p.type_name.clone().unwrap_or_default())
+                        .collect();
+                    let signature = MethodSig::new(name.as_str(), param_types);
+                    if covered.iter().any(|d| d.implements(&signature)) {
+                        continue;
+                    }
+                    let stub_body = if is_abstract {
+                        StubBody::Unimplemented
+                    } else {
+                        StubBody::CallSuper(qualifier)
+                    };
+                    let stub = stub(
+                        lang.get_language(),
+                        &name,
+                        &metadata,
+                        &modifiers,
+                        &body_end.indent,
+                        stub_body,
+                    );
+                    actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+                        title: format!("Override '{}({})'", name, signature.param_types.join(", ")),
+                        kind: Some(CodeActionKind::new(OVERRIDE_METHODS_KIND)),
+                        edit: Some(WorkspaceEdit {
+                            changes: Some(HashMap::from([(
+                                uri.clone(),
+                                vec![insertion(&body_end, &stub)],
+                            )])),
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    }));
+                    covered.push(signature);
+                }
+            }
+        }
+        actions
+    }
+}
@@ -0,0 +1,128 @@
+//! `lspintar/implementableSymbols` — gutter information for open documents.
+//!
+//! Each time a document is opened or saved, and once the index is built, the server
+//! pushes the types of the document that have subtypes and the methods that are
+//! overridden in them, with the count and locations of each.  Clients draw
+//! IntelliJ-style "implemented"/"overridden" gutter icons from it instead of
+//! polling code lenses.
+
+use std::sync::atomic::Ordering;
+
+use serde::{Deserialize, Serialize};
+use tower_lsp::lsp_types::{Location, Range, Url, notification::Notification};
+
+use crate::{
+    lsp_convert::AsLspLocation,
+    models::symbol::Symbol,
+    server::Backend,
+    supertypes::{descendant_tree, is_overridable, method_overrides},
+};
+
+pub enum ImplementableSymbols {}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImplementableSymbolsParams {
+    pub uri: Url,
+    pub symbols: Vec<ImplementableSymbol>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImplementableSymbol {
+    pub name: String,
+    /// The symbol's name in the document, where the gutter icon goes.
+    pub range: Range,
+    pub kind: ImplementableKind,
+    pub count: usize,
+    pub targets: Vec<Location>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ImplementableKind {
+    /// A class or interface with subtypes.
+    Type,
+    /// A method overridden in a subtype.
+    Method,
+}
+
+impl Notification for ImplementableSymbols {
+    type Params = ImplementableSymbolsParams;
+    const METHOD: &'static str = "lspintar/implementableSymbols";
+}
+
+impl Backend {
+    /// The types declared in `uri` with known subtypes, and their methods with known
+    /// overrides.
+    pub async fn implementable_symbols(&self, uri: &Url) -> Vec<ImplementableSymbol> {
+        let Some(repo) = self.repo.get() else {
+            return vec![];
+        };
+        let Ok(path) = uri.to_file_path() else {
+            return vec![];
+        };
+        let declared = repo
+            .find_symbols_by_file_path(&path.to_string_lossy())
+            .await
+            .unwrap_or_default();
+
+        let mut result = Vec::new();
+        for ty in declared
+            .iter()
+            .filter(|s| !s.fully_qualified_name.contains('#'))
+        {
+            let tree = descendant_tree(repo, &ty.fully_qualified_name).await;
+            if tree.is_empty() {
+                continue;
+            }
+
+            for method in declared.iter().filter(|s| {
+                s.parent_name.as_deref() == Some(ty.fully_qualified_name.as_str())
+                    && is_overridable(s)
+            }) {
+                let arity = method.metadata.parameters.as_ref().map_or(0, Vec::len);
+                let overrides = method_overrides(repo, &tree, &method.short_name, arity).await;
+                if let Some(entry) =
+                    implementable(method, ImplementableKind::Method, &overrides.overriding)
+                {
+                    result.push(entry);
+                }
+            }
+
+            let subtypes: Vec<Symbol> = tree.into_iter().map(|(symbol, _)| symbol).collect();
+            if let Some(entry) = implementable(ty, ImplementableKind::Type, &subtypes) {
+                result.push(entry);
+            }
+        }
+        result.sort_by_key(|s| (s.range.start.line, s.range.start.character));
+        result
+    }
+
+    /// Sends [`ImplementableSymbols`] for `uri`, once the initial index is built.
+    pub(crate) async fn publish_implementable_symbols(&self, uri: Url) {
+        if !self.index_ready.load(Ordering::Acquire) {
+            return;
+        }
+        let symbols = self.implementable_symbols(&uri).await;
+        self.client
+            .send_notification::<ImplementableSymbols>(ImplementableSymbolsParams { uri, symbols })
+            .await;
+    }
+}
+
+fn implementable(
+    symbol: &Symbol,
+    kind: ImplementableKind,
+    targets: &[Symbol],
+) -> Option<ImplementableSymbol> {
+    let range = symbol.as_lsp_location()?.range;
+    let targets: Vec<Location> = targets.iter().filter_map(|t| t.as_lsp_location()).collect();
+    (!targets.is_empty()).then(|| ImplementableSymbol {
+        name: symbol.short_name.clone(),
+        range,
+        kind,
+        count: targets.len(),
+        targets,
+    })
+}
@@ -0,0 +1,76 @@
+//! Package preferences for types sharing a simple name.
+//!
+//! When `List` may be `java.util.List` or `java.awt.List`, add-import quick fixes,
+//! completion and go-to-definition on a wildcard-ambiguous name all list the
+//! candidates in the order [`ImportPreferences`] gives them.
+
+use std::{cmp::Ordering, collections::HashMap};
+
+use serde::Deserialize;
+
+/// Read from the client's initialization options, e.g.
+/// `{"preferred_packages": ["java.util"], "demoted_packages": ["java.awt"]}`.
+/// A package matches its subpackages too.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct ImportPreferences {
+    /// Packages ranked first, earlier entries before later ones.
+    pub preferred_packages: Vec<String>,
+    /// Packages ranked last.
+    pub demoted_packages: Vec<String>,
+    /// Ranks project types before library types among otherwise equal candidates.
+    pub prefer_project_types: bool,
+}
+
+impl Default for ImportPreferences {
+    fn default() -> Self {
+        Self {
+            preferred_packages: vec![],
+            demoted_packages: vec![],
+            prefer_project_types: true,
+        }
+    }
+}
+
+fn in_package(fqn: &str, package: &str) -> bool {
+    fqn.strip_prefix(package)
+        .is_some_and(|rest| rest.starts_with('.'))
+}
+
+impl ImportPreferences {
+    /// Lower is better: the index of the first preferred package `fqn` is in, then
+    /// packages with no preference, then demoted ones.
+    pub fn package_rank(&self, fqn: &str) -> usize {
+        if let Some(index) = self
+            .preferred_packages
+            .iter()
+            .position(|p| in_package(fqn, p))
+        {
+            return index;
+        }
+        let neutral = self.preferred_packages.len();
+        if self.demoted_packages.iter().any(|p| in_package(fqn, p)) {
+            neutral + 1
+        } else {
+            neutral
+        }
+    }
+
+    /// Orders two `(fqn, is_external)` candidates: by package rank, then by how many
+    /// project files already import each (`counts`, when known), then project before
+    /// library when so configured, then by name.
+    pub fn compare(
+        &self,
+        a: (&str, bool),
+        b: (&str, bool),
+        counts: &HashMap<String, usize>,
+    ) -> Ordering {
+        let count = |fqn: &str| counts.get(fqn).copied().unwrap_or(0);
+        let origin = |external: bool| self.prefer_project_types && external;
+        self.package_rank(a.0)
+            .cmp(&self.package_rank(b.0))
+            .then(count(b.0).cmp(&count(a.0)))
+            .then(origin(a.1).cmp(&origin(b.1)))
+            .then(a.0.cmp(b.0))
+    }
+}
@@ -0,0 +1,204 @@
+//! Command-line access to the symbol index.
+//!
+//! ```text
+//! lspintar index [--output index.json] [<path>]    index the workspace and dump it
+//! lspintar query --fqn com.foo.Bar [--root <path>]  look a symbol up
+//! lspintar who-implements com.foo.Service [--root <path>]
+//! ```
+//!
+//! `index` builds the index as the server does.  `query` and `who-implements` read
+//! the index the server or `index` left under `<path>/.lspintar`, without starting
+//! anything; the path defaults to the working directory.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use serde_json::{Value, json};
+
+use crate::{
+    analyzer::Analyzer,
+    constants::DB_PATH_FRAGMENT,
+    models::{external_symbol::ExternalSymbol, symbol::Symbol},
+    repo::Repository,
+    supertypes::descendants,
+};
+
+pub const INDEX_COMMAND: &str = "index";
+pub const QUERY_COMMAND: &str = "query";
+pub const WHO_IMPLEMENTS_COMMAND: &str = "who-implements";
+
+/// A project symbol as the commands print it.  Lines and columns are 1-based.
+pub fn symbol_json(symbol: &Symbol) -> Value {
+    json!({
+        "fqn": symbol.fully_qualified_name,
+        "kind": symbol.symbol_type,
+        "file": symbol.file_path,
+        "line": symbol.ident_line_start + 1,
+        "column": symbol.ident_char_start + 1,
+    })
+}
+
+/// A library symbol as the commands print it: where its class file is.
+pub fn external_symbol_json(symbol: &ExternalSymbol) -> Value {
+    json!({
+        "fqn": symbol.fully_qualified_name,
+        "kind": symbol.symbol_type,
+        "jar": symbol.jar_path,
+        "entry": symbol.source_file_path,
+    })
+}
+
+/// Runs `lspintar index` with the arguments following it.
+pub async fn run_index(args: &[String]) -> ExitCode {
+    let mut output = None;
+    let mut root = PathBuf::from(".");
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--output" => match args.next() {
+                Some(path) => output = Some(PathBuf::from(path)),
+                None => return usage("missing --output file"),
+            },
+            path if !path.starts_with("--") => root = PathBuf::from(path),
+            other => return usage(&format!("unknown option {other}")),
+        }
+    }
+
+    let analyzer = match Analyzer::open_workspace(&root).await {
+        Ok(analyzer) => analyzer,
+        Err(e) => return failure(&format!("cannot open {}: {e}", root.display())),
+    };
+    let Some(output) = output else {
+        return ExitCode::SUCCESS;
+    };
+    let Some(repo) = analyzer.backend().repo.get() else {
+        return failure("the index is not available");
+    };
+    let symbols = match repo.find_all_symbols().await {
+        Ok(symbols) => symbols,
+        Err(e) => return failure(&format!("cannot read the index: {e}")),
+    };
+    let dump = json!({
+        "symbols": symbols.iter().map(symbol_json).collect::<Vec<_>>(),
+    });
+    let text = serde_json::to_string_pretty(&dump).unwrap_or_default();
+    let written = if output == Path::new("-") {
+        println!("{text}");
+        Ok(())
+    } else {
+        fs::write(&output, text)
+    };
+    match written {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => failure(&format!("cannot write {}: {e}", output.display())),
+    }
+}
+
+/// Runs `lspintar query` with the arguments following it.
+pub async fn run_query(args: &[String]) -> ExitCode {
+    let mut fqn = None;
+    let mut root = PathBuf::from(".");
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match (arg.as_str(), args.next()) {
+            ("--fqn", Some(value)) => fqn = Some(value.clone()),
+            ("--root", Some(value)) => root = PathBuf::from(value),
+            (other, _) => return usage(&format!("unexpected argument {other}")),
+        }
+    }
+    let Some(fqn) = fqn else {
+        return usage("missing --fqn");
+    };
+    let repo = match open_index(&root).await {
+        Ok(repo) => repo,
+        Err(e) => return failure(&e),
+    };
+
+    let mut found: Vec<Value> = repo
+        .find_symbols_by_fqn(&fqn)
+        .await
+        .unwrap_or_default()
+        .iter()
+        .map(symbol_json)
+        .collect();
+    if found.is_empty()
+        && let Ok(Some(external)) = repo.find_external_symbol_by_fqn(&fqn).await
+    {
+        found.push(external_symbol_json(&external));
+    }
+    if found.is_empty() {
+        eprintln!("{fqn} is not indexed");
+        return ExitCode::FAILURE;
+    }
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&Value::Array(found)).unwrap_or_default()
+    );
+    ExitCode::SUCCESS
+}
+
+/// Runs `lspintar who-implements` with the arguments following it.
+pub async fn run_who_implements(args: &[String]) -> ExitCode {
+    let mut fqn = None;
+    let mut root = PathBuf::from(".");
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--root" => match args.next() {
+                Some(value) => root = PathBuf::from(value),
+                None => return usage("missing --root path"),
+            },
+            value if fqn.is_none() && !value.starts_with("--") => fqn = Some(value.to_string()),
+            other => return usage(&format!("unexpected argument {other}")),
+        }
+    }
+    let Some(fqn) = fqn else {
+        return usage("missing type name");
+    };
+    let repo = match open_index(&root).await {
+        Ok(repo) => repo,
+        Err(e) => return failure(&e),
+    };
+
+    for symbol in descendants(&repo, &fqn).await {
+        println!(
+            "{}\t{}:{}",
+            symbol.fully_qualified_name,
+            symbol.file_path,
+            symbol.ident_line_start + 1
+        );
+    }
+    ExitCode::SUCCESS
+}
+
+/// The index left under `root`, without creating or migrating one: a running
+/// server may be writing it.
+async fn open_index(root: &Path) -> Result<Repository, String> {
+    let db_path = root.join(DB_PATH_FRAGMENT);
+    if !db_path.exists() {
+        return Err(format!(
+            "no index at {}; run `lspintar index` first",
+            db_path.display()
+        ));
+    }
+    Repository::new_reader(&db_path.to_string_lossy())
+        .await
+        .map_err(|e| format!("cannot open {}: {e}", db_path.display()))
+}
+
+fn usage(message: &str) -> ExitCode {
+    eprintln!(
+        "{message}\nusage: lspintar index [--output <file>] [<path>]\n       \
+         lspintar query --fqn <name> [--root <path>]\n       \
+         lspintar who-implements <name> [--root <path>]"
+    );
+    ExitCode::from(2)
+}
+
+fn failure(message: &str) -> ExitCode {
+    eprintln!("{message}");
+    ExitCode::from(2)
+}
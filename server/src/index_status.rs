@@ -0,0 +1,209 @@
+//! `lspintar/indexStatus` — per-project indexing progress.
+//!
+//! Every directory holding a build file is a project; in a multi-project build
+//! each one is indexed on its own, a few at a time, so this request tells which
+//! module is still warming up.  A project moves through
+//! [`IndexingStatus::Pending`] → `Parsing` (its sources are being indexed) →
+//! `External` (waiting for the workspace's dependency JARs) → `Done`.
+//!
+//! Definition and references requests made in a project that is not `Done` yet
+//! would come back with "not found" for whatever is still missing from the index.
+//! The [`WhileIndexing`] policy decides what happens instead: the request waits for
+//! the project to be indexed and is answered then, or it is answered at once and
+//! the client is told the answer may be partial with [`PartialResults`].
+
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use lsp_core::build_tools::BuildToolHandler;
+use serde::{Deserialize, Serialize};
+use tower_lsp::{
+    jsonrpc::Result,
+    lsp_types::{Url, notification::Notification},
+};
+use walkdir::WalkDir;
+
+use crate::{constants::INDEXING_WAIT_TIMEOUT_SECS, indexer::is_excluded, server::Backend};
+
+pub const INDEX_STATUS_METHOD: &str = "lspintar/indexStatus";
+
+/// How many projects are indexed at the same time.
+pub(crate) const MAX_CONCURRENT_PROJECTS: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IndexingStatus {
+    Pending,
+    Parsing,
+    External,
+    Done,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectMetadata {
+    /// The project directory name; the workspace root for the root project.
+    pub name: String,
+    pub root: Url,
+    pub indexing_status: IndexingStatus,
+}
+
+/// What definition and references requests do while the project they are made in is
+/// still being indexed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WhileIndexing {
+    /// Hold the request until the project is indexed, then answer it.  A held request
+    /// keeps its slot among the few the server handles at once, so enough of them
+    /// stall hover and completion too; clients opt in.
+    Wait,
+    /// Answer at once from what is indexed so far, and send [`PartialResults`].
+    #[default]
+    Partial,
+}
+
+/// Indexing options, read from the client's initialization options, e.g.
+/// `{"while_indexing": "wait"}`.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct IndexingSettings {
+    pub while_indexing: WhileIndexing,
+}
+
+/// `lspintar/partialResults`: a request was answered while its project was still
+/// being indexed, so the answer may miss what is not indexed yet.
+pub enum PartialResults {}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PartialResultsParams {
+    pub uri: Url,
+    /// The request answered, e.g. `textDocument/definition`.
+    pub method: String,
+    /// The project still being indexed.
+    pub project: ProjectMetadata,
+}
+
+impl Notification for PartialResults {
+    type Params = PartialResultsParams;
+    const METHOD: &'static str = "lspintar/partialResults";
+}
+
+/// The workspace root followed by every directory below it that holds a build file.
+pub(crate) fn discover_project_roots(
+    root: &Path,
+    build_tool: &(dyn BuildToolHandler + Send + Sync),
+) -> Vec<PathBuf> {
+    let mut roots = vec![root.to_path_buf()];
+    roots.extend(
+        WalkDir::new(root)
+            .min_depth(1)
+            .into_iter()
+            .filter_entry(|e| !is_excluded(e))
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file() && build_tool.is_build_file(e.path()))
+            .filter_map(|e| e.path().parent().map(Path::to_path_buf))
+            .filter(|dir| dir != root),
+    );
+    roots.sort();
+    roots.dedup();
+    roots
+}
+
+impl Backend {
+    /// Entry point for `lspintar/indexStatus`.
+    pub async fn index_status(&self) -> Result<Vec<ProjectMetadata>> {
+        Ok(self.project_status.read().await.clone())
+    }
+
+    /// Resets the tracked projects to `roots`, all pending.
+    pub(crate) async fn track_projects(&self, roots: &[PathBuf]) {
+        *self.project_status.write().await = roots
+            .iter()
+            .filter_map(|root| {
+                Some(ProjectMetadata {
+                    name: root.file_name()?.to_string_lossy().to_string(),
+                    root: Url::from_file_path(root).ok()?,
+                    indexing_status: IndexingStatus::Pending,
+                })
+            })
+            .collect();
+    }
+
+    pub(crate) async fn set_project_status(&self, root: &Path, status: IndexingStatus) {
+        let Ok(uri) = Url::from_file_path(root) else {
+            return;
+        };
+        let mut projects = self.project_status.write().await;
+        for project in projects.iter_mut().filter(|p| p.root == uri) {
+            project.indexing_status = status;
+        }
+        // Closures walked against a partly built index may be missing links.
+        self.supertypes.clear();
+        self.workspace_symbols.invalidate();
+        self.project_imports.clear();
+        self.index_progress.notify_waiters();
+    }
+
+    /// Moves every tracked project to `status`.
+    pub(crate) async fn set_all_project_status(&self, status: IndexingStatus) {
+        for project in self.project_status.write().await.iter_mut() {
+            project.indexing_status = status;
+        }
+        self.supertypes.clear();
+        self.workspace_symbols.invalidate();
+        self.project_imports.clear();
+        self.index_progress.notify_waiters();
+    }
+
+    /// The innermost tracked project holding `uri`, while it is not indexed yet.
+    async fn indexing_project(&self, uri: &Url) -> Option<ProjectMetadata> {
+        let path = uri.to_file_path().ok()?;
+        let projects = self.project_status.read().await;
+        projects
+            .iter()
+            .filter_map(|p| Some((p, p.root.to_file_path().ok()?)))
+            .filter(|(_, root)| path.starts_with(root))
+            .max_by_key(|(_, root)| root.components().count())
+            .map(|(p, _)| p)
+            .filter(|p| p.indexing_status != IndexingStatus::Done)
+            .cloned()
+    }
+
+    /// Applies the [`WhileIndexing`] policy to a `method` request on `uri`.  Returns
+    /// at once when the project holding `uri` is indexed, or is not tracked at all.
+    /// A request that waits longer than [`INDEXING_WAIT_TIMEOUT_SECS`] is let through
+    /// as partial.
+    pub(crate) async fn gate_on_indexing(&self, uri: &Url, method: &str) {
+        if self.indexing_project(uri).await.is_none() {
+            return;
+        }
+        if self.indexing_settings.read().await.while_indexing == WhileIndexing::Wait {
+            let indexed = async {
+                loop {
+                    // Registered before the check so no status change is missed.
+                    let progress = self.index_progress.notified();
+                    if self.indexing_project(uri).await.is_none() {
+                        return;
+                    }
+                    progress.await;
+                }
+            };
+            let timeout = Duration::from_secs(INDEXING_WAIT_TIMEOUT_SECS);
+            if tokio::time::timeout(timeout, indexed).await.is_ok() {
+                return;
+            }
+        }
+        if let Some(project) = self.indexing_project(uri).await {
+            self.client
+                .send_notification::<PartialResults>(PartialResultsParams {
+                    uri: uri.clone(),
+                    method: method.to_string(),
+                    project,
+                })
+                .await;
+        }
+    }
+}
@@ -1,26 +1,33 @@
 use classfile_parser::{
-    ClassAccessFlags, class_parser, constant_info::ConstantInfo, field_info::FieldAccessFlags,
-    method_info::MethodAccessFlags,
+    ClassAccessFlags, attribute_info::AttributeInfo, class_parser, constant_info::ConstantInfo,
+    field_info::FieldAccessFlags, method_info::MethodAccessFlags,
 };
 use crate::generic_resolution::{parse_class_type_params, parse_method_generic_params, parse_method_generic_return, parse_method_type_params, read_signature_attr};
+use dashmap::DashMap;
 use futures::{StreamExt, stream};
 use java::JAVA_IMPLICIT_IMPORTS;
-use lsp_core::{language_support::LanguageSupport, node_kind::NodeKind, util::naive_resolve_fqn};
+use lsp_core::{
+    constant_value::ConstantValue, language_support::LanguageSupport, languages::Language,
+    node_kind::NodeKind, util::naive_resolve_fqn,
+};
+use sha2::{Digest, Sha256};
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fs::File,
     io::Read,
     panic,
     path::{Path, PathBuf},
     sync::{
         Arc,
-        atomic::{AtomicI32, Ordering},
+        atomic::{AtomicBool, AtomicI32, Ordering},
     },
 };
 use zip::ZipArchive;
 
 use crate::{
     constants::MAX_LINE_COUNT,
+    jpql::declared_entity_name,
+    kotlin_facade::facade_symbols,
     models::{
         external_symbol::ExternalSymbol,
         symbol::{Symbol, SymbolMetadata, SymbolParameter},
@@ -40,6 +47,14 @@ use std::time::{SystemTime, UNIX_EPOCH};
 pub struct Indexer {
     languages: HashMap<String, Arc<dyn LanguageSupport>>,
     pub repo: Arc<Repository>,
+    /// Serializes the insert phase of projects indexed concurrently; parsing runs in
+    /// parallel, but concurrent bulk writes to SQLite only contend for the lock.
+    insert_lock: Arc<tokio::sync::Mutex<()>>,
+    /// Set while another instance writes the index.
+    reader: Arc<AtomicBool>,
+    /// The class sources parsed while a reader, by sources JAR and class, which
+    /// cannot be stored in the index.
+    pub(crate) parsed_sources: Arc<DashMap<(String, String), Vec<ExternalSymbol>>>,
 }
 
 impl Indexer {
@@ -47,9 +62,22 @@ impl Indexer {
         Self {
             languages: HashMap::new(),
             repo,
+            insert_lock: Arc::new(tokio::sync::Mutex::new(())),
+            reader: Arc::new(AtomicBool::new(false)),
+            parsed_sources: Arc::new(DashMap::new()),
         }
     }
 
+    /// Follows `reader`, the flag telling whether another instance writes the index.
+    pub fn share_reader_flag(&mut self, reader: Arc<AtomicBool>) {
+        self.reader = reader;
+    }
+
+    /// Whether another instance writes the index and this one must not.
+    pub fn is_reader(&self) -> bool {
+        self.reader.load(Ordering::Acquire)
+    }
+
     pub fn register_language(&mut self, ext: &str, lang: Arc<dyn LanguageSupport>) {
         self.languages.insert(ext.to_string(), lang.clone());
     }
@@ -60,6 +88,23 @@ impl Indexer {
         on_extract_progress: F,
         on_insert_progress: G,
     ) -> Result<()>
+    where
+        F: FnMut(i32, i32) + Send + 'static,
+        G: FnMut(i32, i32) + Send + 'static,
+    {
+        self.index_project(path, &[], on_extract_progress, on_insert_progress)
+            .await
+    }
+
+    /// Like [`Indexer::index_workspace`], but skips the sub-projects rooted at
+    /// `nested_roots`, so each project of a multi-project build is indexed on its own.
+    pub async fn index_project<F, G>(
+        &self,
+        path: &Path,
+        nested_roots: &[PathBuf],
+        on_extract_progress: F,
+        on_insert_progress: G,
+    ) -> Result<()>
     where
         F: FnMut(i32, i32) + Send + 'static,
         G: FnMut(i32, i32) + Send + 'static,
@@ -67,7 +112,7 @@ impl Indexer {
         let files: Vec<_> = WalkDir::new(path)
             .follow_links(true)
             .into_iter()
-            .filter_entry(|e| !is_excluded(e))
+            .filter_entry(|e| !is_excluded(e) && !nested_roots.iter().any(|r| r == e.path()))
             .filter_map(|e| e.ok())
             .filter(|e| e.file_type().is_file())
             .collect();
@@ -84,23 +129,35 @@ impl Indexer {
                 let progress_count = Arc::clone(&progress_count);
                 let on_progress = Arc::clone(&on_progress);
                 async move {
-                    let result =
-                        tokio::task::spawn_blocking(move || indexer.index_file(entry.path())).await;
+                    let result = tokio::task::spawn_blocking(move || {
+                        let indexed = indexer.index_file(entry.path())?;
+                        // Hash only files that were indexed, so unsupported files never
+                        // show up as stale on the next startup.
+                        let hash = indexed
+                            .as_ref()
+                            .and_then(|_| std::fs::read(entry.path()).ok())
+                            .map(|bytes| {
+                                (entry.path().to_string_lossy().to_string(), content_hash(&bytes))
+                            });
+                        Ok::<_, anyhow::Error>(indexed.map(|(symbols, supers)| (symbols, supers, hash)))
+                    })
+                    .await;
                     let done = progress_count.fetch_add(1, Ordering::Relaxed) + 1;
                     on_progress.lock().unwrap()(done, total);
-                    let result = result??;
-                    Ok::<Option<(Vec<Symbol>, Vec<SymbolSuperMapping>)>, anyhow::Error>(result)
+                    result?
                 }
             })
             .buffer_unordered(num_cpus::get() - 1)
             .collect()
             .await;
 
+        let mut all_hashes = vec![];
         for result in results {
             match result {
-                Ok(Some((symbols, supers))) => {
+                Ok(Some((symbols, supers, hash))) => {
                     all_symbols.extend(symbols);
                     all_supers.extend(supers);
+                    all_hashes.extend(hash);
                 }
                 Err(e) => tracing::warn!("Failed to index file: {e}"),
                 _ => {}
@@ -108,6 +165,7 @@ impl Indexer {
         }
 
         let on_insert_progress = Arc::new(std::sync::Mutex::new(on_insert_progress));
+        let _insert_guard = self.insert_lock.lock().await;
 
         let mut insert_count = 0i32;
         let insert_total = (all_symbols.len() + all_supers.len()) as i32;
@@ -132,9 +190,70 @@ impl Indexer {
             on_insert_progress.lock().unwrap()(insert_count, insert_total);
         }
 
+        for hashes in all_hashes.chunks(1000) {
+            if let Err(e) = self.repo.upsert_file_hashes(hashes).await {
+                tracing::warn!("Failed to insert file hashes: {e}");
+            }
+        }
+
         Ok(())
     }
 
+    /// Compares every project source file under `path` with the content hash it was last
+    /// indexed with.  Returns the files that are new or changed, and the previously indexed
+    /// files that no longer exist.  Works regardless of VCS state, so uncommitted edits and
+    /// non-VCS projects are detected too.
+    pub async fn find_stale_files(&self, path: &Path) -> Result<(Vec<PathBuf>, Vec<String>)> {
+        let stored: Arc<HashMap<String, String>> =
+            Arc::new(self.repo.find_all_file_hashes().await?.into_iter().collect());
+
+        let files: Vec<PathBuf> = WalkDir::new(path)
+            .follow_links(true)
+            .into_iter()
+            .filter_entry(|e| !is_excluded(e))
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .filter(|e| {
+                e.path()
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| self.languages.contains_key(ext))
+            })
+            .map(|e| e.into_path())
+            .collect();
+        let on_disk: HashSet<String> = files
+            .iter()
+            .map(|f| f.to_string_lossy().to_string())
+            .collect();
+
+        let changed: Vec<PathBuf> = stream::iter(files)
+            .map(|file| {
+                let stored = Arc::clone(&stored);
+                async move {
+                    tokio::task::spawn_blocking(move || {
+                        let hash = std::fs::read(&file).ok().map(|bytes| content_hash(&bytes));
+                        let key = file.to_string_lossy().to_string();
+                        (stored.get(&key) != hash.as_ref()).then_some(file)
+                    })
+                    .await
+                    .ok()
+                    .flatten()
+                }
+            })
+            .buffer_unordered(num_cpus::get())
+            .filter_map(|file| async move { file })
+            .collect()
+            .await;
+
+        let removed = stored
+            .keys()
+            .filter(|f| !on_disk.contains(*f))
+            .cloned()
+            .collect();
+
+        Ok((changed, removed))
+    }
+
     pub fn index_file(
         &self,
         path: &Path,
@@ -216,6 +335,12 @@ impl Indexer {
             is_external,
         )?;
 
+        // Library facades come with the JAR's class files.
+        if lang.get_language() == Language::Kotlin && !is_external {
+            let facade = facade_symbols(&symbols, path, content, &package_name);
+            symbols.extend(facade);
+        }
+
         Ok((symbols, symbol_super_mappings))
     }
 
@@ -294,6 +419,9 @@ impl Indexer {
                         type_params: None,
                         generic_param_types: None,
                         method_type_params: None,
+                        constant_value: None,
+                        entity_name: None,
+                        synthetic_accessor: None,
                     };
 
                     match node_kind {
@@ -309,6 +437,15 @@ impl Indexer {
                                         })
                                         .collect()
                                 });
+                            let is_entity =
+                                metadata.annotations.iter().flatten().any(|a| a == "Entity");
+                            if is_entity {
+                                metadata.entity_name = declared_entity_name(
+                                    content,
+                                    range.start.line,
+                                    ident_range.start.line,
+                                );
+                            }
                         }
                         Some(NodeKind::Function) => {
                             let symbol_params = lang
@@ -329,6 +466,12 @@ impl Indexer {
                         }
                         Some(NodeKind::Field) => {
                             metadata.return_type = lang.get_return(&node, content);
+                            metadata.constant_value = lang
+                                .get_constant_value(&node, content)
+                                .map(|v| v.to_string());
+                        }
+                        Some(NodeKind::TypeAlias) => {
+                            metadata.return_type = lang.get_return(&node, content);
                         }
                         _ => (),
                     };
@@ -444,6 +587,36 @@ impl Indexer {
         Ok((all_symbols, all_mappings))
     }
 
+    /// Parses the single source file for the class compiled to `class_entry` (e.g.
+    /// `com/foo/Bar$Inner.class`) out of the sources JAR `src_jar_path`, instead of
+    /// the whole JAR.  Yields nothing when the JAR holds no such source.
+    pub fn extract_class_source_symbols(
+        &self,
+        src_jar_path: &Path,
+        class_entry: &str,
+    ) -> Result<(Vec<ExternalSymbol>, Vec<SymbolSuperMapping>)> {
+        let stem = class_entry
+            .trim_end_matches(".class")
+            .split('$')
+            .next()
+            .unwrap_or(class_entry);
+        let mut archive = ZipArchive::new(File::open(src_jar_path)?)?;
+        let Some(entry_name) = ["java", "groovy", "kt"]
+            .iter()
+            .map(|ext| format!("{stem}.{ext}"))
+            .find(|name| archive.by_name(name).is_ok())
+        else {
+            return Ok((vec![], vec![]));
+        };
+        let mut buffer = Vec::new();
+        archive.by_name(&entry_name)?.read_to_end(&mut buffer)?;
+
+        panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            self.extract_source_symbols(buffer, &entry_name, src_jar_path)
+        }))
+        .unwrap_or_else(|_| Err(anyhow!("Panic in {entry_name}")))
+    }
+
     fn extract_source_symbols(
         &self,
         buffer: Vec<u8>,
@@ -633,6 +806,9 @@ impl Indexer {
                 type_params: class_type_params,
                 generic_param_types: None,
                 method_type_params: None,
+                constant_value: None,
+                entity_name: None,
+                synthetic_accessor: None,
             }),
             last_modified: now,
             file_type: "java".to_string(),
@@ -685,7 +861,8 @@ impl Indexer {
                 ident_char_end: 0,
                 needs_decompilation: true,
                 metadata: Json::from(SymbolMetadata {
-                    annotations: None,
+                    annotations: Some(read_annotation_names(&method.attributes, &class.const_pool))
+                        .filter(|a| !a.is_empty()),
                     parameters: Some(params),
                     documentation: None,
                     return_type: Some(return_type),
@@ -693,6 +870,9 @@ impl Indexer {
                     type_params: None,
                     generic_param_types,
                     method_type_params,
+                    constant_value: None,
+                    entity_name: None,
+                    synthetic_accessor: None,
                 }),
                 last_modified: now,
                 file_type: "java".to_string(),
@@ -708,6 +888,8 @@ impl Indexer {
             let field_name = get_utf8(&class.const_pool, field.name_index)?;
             let descriptor = get_utf8(&class.const_pool, field.descriptor_index)?;
             let field_type = parse_field_descriptor(&descriptor);
+            let constant_value =
+                read_constant_value_attr(&field.attributes, &class.const_pool, &descriptor);
 
             symbols.push(ExternalSymbol {
                 id: None,
@@ -730,7 +912,8 @@ impl Indexer {
                 ident_char_end: 0,
                 needs_decompilation: true,
                 metadata: Json::from(SymbolMetadata {
-                    annotations: None,
+                    annotations: Some(read_annotation_names(&field.attributes, &class.const_pool))
+                        .filter(|a| !a.is_empty()),
                     parameters: None,
                     documentation: None,
                     return_type: Some(field_type),
@@ -738,6 +921,9 @@ impl Indexer {
                     type_params: None,
                     generic_param_types: None,
                     method_type_params: None,
+                    constant_value,
+                    entity_name: None,
+                    synthetic_accessor: None,
                 }),
                 last_modified: now,
                 file_type: "java".to_string(),
@@ -838,6 +1024,159 @@ fn get_utf8(pool: &[ConstantInfo], index: u16) -> Result<String> {
     }
 }
 
+/// Returns the simple names of the annotations recorded on a method or field, e.g.
+/// `["Nullable", "Deprecated"]`.  Reads both the visible and invisible declaration
+/// annotation attributes (`@org.jetbrains.annotations.Nullable` has CLASS retention)
+/// plus type annotations on the return/field type itself (JSpecify, Checker Framework).
+fn read_annotation_names(attributes: &[AttributeInfo], pool: &[ConstantInfo]) -> Vec<String> {
+    let in_pool = |index: u16| index != 0 && index as usize <= pool.len();
+    let mut names = Vec::new();
+    for attr in attributes {
+        if !in_pool(attr.attribute_name_index) {
+            continue;
+        }
+        let Ok(attr_name) = get_utf8(pool, attr.attribute_name_index) else {
+            continue;
+        };
+        let type_annotations = match attr_name.as_str() {
+            "RuntimeVisibleAnnotations" | "RuntimeInvisibleAnnotations" => false,
+            "RuntimeVisibleTypeAnnotations" | "RuntimeInvisibleTypeAnnotations" => true,
+            _ => continue,
+        };
+        let mut reader = AnnotationReader { bytes: &attr.info, pos: 0 };
+        let Some(count) = reader.u16() else { continue };
+        for _ in 0..count {
+            let annotation = if type_annotations {
+                reader.type_annotation()
+            } else {
+                reader.annotation().map(Some)
+            };
+            match annotation {
+                Some(Some(type_index)) if in_pool(type_index) => {
+                    if let Some(name) = get_utf8(pool, type_index)
+                        .ok()
+                        .map(|d| parse_field_descriptor(&d))
+                        .and_then(|fqn| fqn.rsplit('.').next().map(str::to_string))
+                        && !names.contains(&name)
+                    {
+                        names.push(name);
+                    }
+                }
+                Some(_) => {}
+                None => break,
+            }
+        }
+    }
+    names
+}
+
+/// The value of a constant field's `ConstantValue` attribute (JVMS §4.7.2), spelled as
+/// a Java literal.  Booleans and chars are stored as ints, so the field descriptor
+/// says which they are.
+fn read_constant_value_attr(
+    attributes: &[AttributeInfo],
+    pool: &[ConstantInfo],
+    descriptor: &str,
+) -> Option<String> {
+    let in_pool = |index: u16| index != 0 && index as usize <= pool.len();
+    let attr = attributes.iter().find(|a| {
+        in_pool(a.attribute_name_index)
+            && get_utf8(pool, a.attribute_name_index).is_ok_and(|name| name == "ConstantValue")
+    })?;
+    let index = u16::from_be_bytes([*attr.info.first()?, *attr.info.get(1)?]);
+    if !in_pool(index) {
+        return None;
+    }
+    let value = match (&pool[(index - 1) as usize], descriptor) {
+        (ConstantInfo::Integer(i), "Z") => (i.value != 0).to_string(),
+        (ConstantInfo::Integer(i), "C") => {
+            let c = char::from_u32(i.value as u32)?;
+            format!("'{}'", c.escape_default())
+        }
+        (ConstantInfo::Integer(i), _) => i.value.to_string(),
+        (ConstantInfo::Long(l), _) => format!("{}L", l.value),
+        (ConstantInfo::Float(f), _) => format!("{:?}f", f.value),
+        (ConstantInfo::Double(d), _) => format!("{:?}", d.value),
+        (ConstantInfo::String(s), _) => {
+            ConstantValue::String(get_utf8(pool, s.string_index).ok()?).to_string()
+        }
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// Cursor over the raw bytes of an annotation attribute (JVMS §4.7.16–4.7.20).
+struct AnnotationReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl AnnotationReader<'_> {
+    fn u8(&mut self) -> Option<u8> {
+        let b = *self.bytes.get(self.pos)?;
+        self.pos += 1;
+        Some(b)
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        let hi = self.u8()?;
+        let lo = self.u8()?;
+        Some(u16::from_be_bytes([hi, lo]))
+    }
+
+    fn skip(&mut self, n: usize) -> Option<()> {
+        (self.pos + n <= self.bytes.len()).then(|| self.pos += n)
+    }
+
+    /// Reads one `annotation` structure and returns its type descriptor index.
+    fn annotation(&mut self) -> Option<u16> {
+        let type_index = self.u16()?;
+        let pairs = self.u16()?;
+        for _ in 0..pairs {
+            self.u16()?;
+            self.element_value()?;
+        }
+        Some(type_index)
+    }
+
+    fn element_value(&mut self) -> Option<()> {
+        match self.u8()? {
+            b'B' | b'C' | b'D' | b'F' | b'I' | b'J' | b'S' | b'Z' | b's' | b'c' => self.skip(2),
+            b'e' => self.skip(4),
+            b'@' => self.annotation().map(|_| ()),
+            b'[' => {
+                let n = self.u16()?;
+                for _ in 0..n {
+                    self.element_value()?;
+                }
+                Some(())
+            }
+            _ => None,
+        }
+    }
+
+    /// Reads one `type_annotation` structure.  Returns `Some(Some(type_index))` only when the
+    /// annotation applies to the member's own type (field type or method return type, with an
+    /// empty type path); annotations on parameters, type arguments or array components yield
+    /// `Some(None)`.
+    fn type_annotation(&mut self) -> Option<Option<u16>> {
+        let target_type = self.u8()?;
+        let target_info_len = match target_type {
+            0x00 | 0x01 | 0x16 => 1,
+            0x10 | 0x11 | 0x12 | 0x17 => 2,
+            0x13..=0x15 => 0,
+            // Targets inside method bodies never appear on fields or methods.
+            _ => return None,
+        };
+        self.skip(target_info_len)?;
+        let path_len = self.u8()?;
+        self.skip(path_len as usize * 2)?;
+        let type_index = self.annotation()?;
+        let own_type = matches!(target_type, 0x13 | 0x14) && path_len == 0;
+        Some(own_type.then_some(type_index))
+    }
+}
+
 fn get_class_name(pool: &[ConstantInfo], index: u16) -> Result<String> {
     match &pool[(index - 1) as usize] {
         ConstantInfo::Class(c) => get_utf8(pool, c.name_index),
@@ -902,14 +1241,46 @@ fn field_access_to_modifiers(flags: FieldAccessFlags) -> Vec<String> {
     mods
 }
 
-fn is_excluded(entry: &walkdir::DirEntry) -> bool {
+/// Hex-encoded SHA-256 of a source file's bytes, stored per file to detect stale entries.
+pub fn content_hash(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+pub(crate) fn is_excluded(entry: &walkdir::DirEntry) -> bool {
     entry
         .file_name()
         .to_str()
-        .map(|s| matches!(s, "build" | "target" | ".gradle" | ".git" | "out" | "bin"))
+        .map(|s| matches!(
+                s,
+                "build" | "target" | ".gradle" | ".git" | ".hg" | ".jj" | "out" | "bin"
+            ))
         .unwrap_or(false)
 }
 
+/// Drops dependencies that are build outputs of the workspace itself, such as a
+/// sibling module's `build/libs/*.jar`.  Their classes are indexed from source, and
+/// a jar copy would go stale as soon as those sources change.
+pub fn without_workspace_outputs(
+    deps: Vec<(Option<PathBuf>, Option<PathBuf>)>,
+    root: &Path,
+) -> Vec<(Option<PathBuf>, Option<PathBuf>)> {
+    let is_output = |path: &Option<PathBuf>| {
+        path.as_ref().is_some_and(|p| {
+            p.strip_prefix(root).is_ok_and(|relative| {
+                relative
+                    .components()
+                    .any(|c| matches!(c.as_os_str().to_str(), Some("build" | "target" | "out")))
+            })
+        })
+    };
+    deps.into_iter()
+        .filter(|(jar, sources)| !is_output(jar) && !is_output(sources))
+        .collect()
+}
+
 fn parse_field_descriptor(descriptor: &str) -> String {
     match descriptor {
         "I" => "int".to_string(),
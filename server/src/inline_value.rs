@@ -0,0 +1,142 @@
+//! `textDocument/inlineValue` — which variables a debugger can show inline.
+//!
+//! When a debug session stops, the client asks for the values worth showing in the
+//! visible range.  Every identifier between the start of that range and the stopped
+//! location that names a variable in scope at the stop is reported as a variable
+//! lookup; the debug adapter fills in the value.  An occurrence counts only when it
+//! resolves to the same declaration as the name does at the stop, so a shadowed
+//! outer variable is not shown with the inner one's value.  Each variable is
+//! reported once per line.
+
+use std::collections::HashSet;
+
+use lsp_core::{language_support::LanguageSupport, ts_helper::position_to_byte_offset};
+use tower_lsp::{
+    jsonrpc::Result,
+    lsp_types::{InlineValue, InlineValueParams, InlineValueVariableLookup, Position, Range},
+};
+use tree_sitter::{Node, Tree};
+
+use crate::server::Backend;
+
+fn is_identifier(node: &Node) -> bool {
+    matches!(node.kind(), "identifier" | "simple_identifier")
+}
+
+/// Identifiers in `start..end` that are not the member part of `a.b`.
+fn identifiers_between<'a>(
+    tree: &'a Tree,
+    content: &str,
+    start: usize,
+    end: usize,
+) -> Vec<Node<'a>> {
+    let mut found = Vec::new();
+    let mut stack = vec![tree.root_node()];
+    while let Some(node) = stack.pop() {
+        if node.end_byte() <= start || node.start_byte() >= end {
+            continue;
+        }
+        if is_identifier(&node) {
+            let after_dot = content[..node.start_byte()].trim_end().ends_with('.');
+            if !after_dot {
+                found.push(node);
+            }
+            continue;
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor));
+    }
+    found.sort_by_key(|n| n.start_byte());
+    found
+}
+
+fn node_range(node: &Node) -> Range {
+    let (start, end) = (node.start_position(), node.end_position());
+    Range::new(
+        Position::new(start.row as u32, start.column as u32),
+        Position::new(end.row as u32, end.column as u32),
+    )
+}
+
+/// The variable lookups for `range`, stopped at `stopped`.
+fn inline_values(
+    lang: &dyn LanguageSupport,
+    tree: &Tree,
+    content: &str,
+    range: &Range,
+    stopped: &Range,
+) -> Vec<InlineValue> {
+    let in_scope: HashSet<String> = lang
+        .find_declarations_in_scope(tree, content, &stopped.start)
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    if in_scope.is_empty() {
+        return vec![];
+    }
+
+    let start = position_to_byte_offset(content, &range.start);
+    let end = position_to_byte_offset(content, &stopped.end.min(range.end));
+    let mut seen = HashSet::new();
+    let mut values = Vec::new();
+    for node in identifiers_between(tree, content, start, end) {
+        let Ok(name) = node.utf8_text(content.as_bytes()) else {
+            continue;
+        };
+        if !in_scope.contains(name) {
+            continue;
+        }
+        let range = node_range(&node);
+        if seen.contains(&(range.start.line, name)) {
+            continue;
+        }
+        let Some((_, declaration)) =
+            lang.find_variable_declaration(tree, content, name, &stopped.start)
+        else {
+            continue;
+        };
+        let is_declaration = range.start == declaration;
+        let resolves_there = lang
+            .find_variable_declaration(tree, content, name, &range.start)
+            .is_some_and(|(_, p)| p == declaration);
+        if !is_declaration && !resolves_there {
+            continue;
+        }
+        seen.insert((range.start.line, name));
+        values.push(InlineValue::VariableLookup(InlineValueVariableLookup {
+            range,
+            variable_name: Some(name.to_string()),
+            case_sensitive_lookup: true,
+        }));
+    }
+    values
+}
+
+impl Backend {
+    /// Entry point for `textDocument/inlineValue`.
+    pub async fn inline_value_impl(
+        &self,
+        params: InlineValueParams,
+    ) -> Result<Option<Vec<InlineValue>>> {
+        let uri = &params.text_document.uri;
+        let Some(ext) = uri.path().rsplit_once('.').map(|(_, ext)| ext) else {
+            return Ok(None);
+        };
+        let Some(lang) = self.languages.get(ext) else {
+            return Ok(None);
+        };
+        let Some(content) = self.document_text(uri) else {
+            return Ok(None);
+        };
+        let Some((tree, _)) = lang.parse_str(&content) else {
+            return Ok(None);
+        };
+        Ok(Some(inline_values(
+            lang.as_ref(),
+            &tree,
+            &content,
+            &params.range,
+            &params.context.stopped_location,
+        )))
+    }
+}
@@ -0,0 +1,215 @@
+//! `mappedBy` navigation and checks for JPA relationship annotations.
+//!
+//! The inverse side of a bidirectional relationship names the owning field of
+//! the other entity in a string: `@OneToMany(mappedBy = "owner") List<Pet> pets`.
+//! That entity is the `targetEntity` argument when given, otherwise the type of
+//! the annotated field or getter, looking through collections.  The string
+//! resolves to the named field, declared on the entity or inherited from its
+//! supertypes; names that match no field of a project entity are reported as
+//! `unresolved_mapped_by`.
+
+use lsp_core::{
+    language_support::LanguageSupport, node_kind::NodeKind, ts_helper::byte_offset_to_position,
+};
+use tower_lsp::lsp_types::{
+    Diagnostic, DiagnosticSeverity, NumberOrString, Range, TextDocumentPositionParams,
+};
+use tree_sitter::{Node, Tree};
+
+use crate::{
+    enums::ResolvedSymbol,
+    jpql::{annotation_name, annotation_string_arguments, element_type_name, literal_body},
+    models::symbol::Symbol,
+    server::Backend,
+};
+
+/// Relationship annotations that accept `mappedBy`.
+const RELATIONSHIP_ANNOTATIONS: &[&str] = &["OneToMany", "OneToOne", "ManyToMany"];
+
+/// A `mappedBy` argument: the field it names, where, and the simple name of the
+/// entity that should declare it.
+struct MappedBy {
+    field: String,
+    range: Range,
+    target_type: String,
+}
+
+fn mapped_by_arguments(tree: &Tree, content: &str, lang: &dyn LanguageSupport) -> Vec<MappedBy> {
+    let mut arguments = Vec::new();
+    let mut stack = vec![tree.root_node()];
+    while let Some(node) = stack.pop() {
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor));
+
+        let is_relationship = node.kind().contains("annotation")
+            && annotation_name(node, content)
+                .is_some_and(|n| RELATIONSHIP_ANNOTATIONS.contains(&n));
+        if !is_relationship {
+            continue;
+        }
+        let Some((_, literal)) = annotation_string_arguments(node, content)
+            .into_iter()
+            .find(|(argument, _)| *argument == "mappedBy")
+        else {
+            continue;
+        };
+        let Some((field, start)) = literal_body(literal, content) else {
+            continue;
+        };
+        let Some(target_type) =
+            target_entity(node, content).or_else(|| annotated_type(node, content, lang))
+        else {
+            continue;
+        };
+        arguments.push(MappedBy {
+            field: field.to_string(),
+            range: Range::new(
+                byte_offset_to_position(content, start),
+                byte_offset_to_position(content, start + field.len()),
+            ),
+            target_type,
+        });
+    }
+    arguments
+}
+
+/// The simple name of `targetEntity = Pet.class` (or `Pet::class`).
+fn target_entity(annotation: Node, content: &str) -> Option<String> {
+    let text = annotation.utf8_text(content.as_bytes()).ok()?;
+    let rest = &text[text.find("targetEntity")? + "targetEntity".len()..];
+    let rest = rest.trim_start().strip_prefix('=')?.trim_start();
+    let end = rest
+        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.' || c == ':'))
+        .unwrap_or(rest.len());
+    let name = rest[..end]
+        .trim_end_matches(".class")
+        .trim_end_matches("::class");
+    name.rsplit('.').next().map(str::to_string)
+}
+
+/// The element type of the field or getter `annotation` is attached to.
+fn annotated_type(annotation: Node, content: &str, lang: &dyn LanguageSupport) -> Option<String> {
+    let mut current = annotation.parent();
+    while let Some(node) = current {
+        if matches!(
+            lang.get_kind(&node),
+            Some(NodeKind::Field | NodeKind::Function)
+        ) {
+            let declared = lang.get_return(&node, content)?;
+            return element_type_name(&declared).map(str::to_string);
+        }
+        current = node.parent();
+    }
+    None
+}
+
+impl Backend {
+    /// Resolves a `mappedBy` string at the request position to the field it names.
+    /// Returns `None` outside `mappedBy` arguments, so regular resolution applies.
+    pub(crate) async fn resolve_mapped_by_reference(
+        &self,
+        params: &TextDocumentPositionParams,
+    ) -> Option<Vec<ResolvedSymbol>> {
+        let uri = &params.text_document.uri;
+        let content = self.document_text(uri)?;
+        if !content.contains("mappedBy") {
+            return None;
+        }
+        let ext = uri.path().rsplit('.').next()?;
+        let lang = self.languages.get(ext)?;
+        let (tree, _) = lang.parse_str(&content)?;
+
+        let position = params.position;
+        let argument = mapped_by_arguments(&tree, &content, lang.as_ref())
+            .into_iter()
+            .find(|a| {
+                a.range.start.line == position.line
+                    && a.range.start.character <= position.character
+                    && position.character <= a.range.end.character
+            })?;
+        let symbols = match self
+            .relationship_target(&argument.target_type, &tree, &content, lang.as_ref())
+            .await
+        {
+            Some(target) => self
+                .entity_field(&target.fully_qualified_name, &argument.field)
+                .await
+                .map(ResolvedSymbol::Project)
+                .into_iter()
+                .collect(),
+            None => vec![],
+        };
+        Some(symbols)
+    }
+
+    /// Reports `mappedBy` arguments naming no field of their target entity.
+    pub(crate) async fn mapped_by_diagnostics(
+        &self,
+        tree: &Tree,
+        content: &str,
+        lang: &dyn LanguageSupport,
+    ) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        for argument in mapped_by_arguments(tree, content, lang) {
+            let Some(target) = self
+                .relationship_target(&argument.target_type, tree, content, lang)
+                .await
+            else {
+                continue;
+            };
+            if self
+                .entity_field(&target.fully_qualified_name, &argument.field)
+                .await
+                .is_some()
+            {
+                continue;
+            }
+            diagnostics.push(Diagnostic {
+                range: argument.range,
+                severity: Some(DiagnosticSeverity::ERROR),
+                code: Some(NumberOrString::String("unresolved_mapped_by".to_string())),
+                source: Some("lspintar".to_string()),
+                message: format!(
+                    "Cannot resolve field '{}' in '{}'",
+                    argument.field, target.short_name
+                ),
+                ..Default::default()
+            });
+        }
+        diagnostics
+    }
+
+    /// The project type named `type_name` as seen from the annotated file.  A
+    /// name the file's imports do not resolve stands for the one `@Entity` class
+    /// of that simple name, and for none when several share it.
+    async fn relationship_target(
+        &self,
+        type_name: &str,
+        tree: &Tree,
+        content: &str,
+        lang: &dyn LanguageSupport,
+    ) -> Option<Symbol> {
+        let repo = self.repo.get()?;
+        let imports = lang.get_imports(tree, content);
+        let package_name = lang.get_package_name(tree, content);
+        if let Some(fqn) = self.resolve_fqn(type_name, imports, package_name).await
+            && let Ok(Some(symbol)) = repo.find_symbol_by_fqn(&fqn).await
+        {
+            return Some(symbol);
+        }
+        let mut entities = repo
+            .find_types_by_short_name(type_name)
+            .await
+            .ok()?
+            .into_iter()
+            .filter(|s| {
+                s.metadata
+                    .annotations
+                    .iter()
+                    .flatten()
+                    .any(|a| a == "Entity")
+            });
+        let entity = entities.next()?;
+        entities.next().is_none().then_some(entity)
+    }
+}
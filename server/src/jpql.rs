@@ -0,0 +1,512 @@
+//! JPQL assistance inside `@Query` and `@NamedQuery` strings.
+//!
+//! The string literals of a query annotation are joined into one JPQL text
+//! (so `"SELECT u " + "FROM User u"` reads as a single query) and scanned for
+//! entity names — the identifiers after `FROM`, `JOIN`, `UPDATE` and between
+//! commas of a `FROM` list — together with the aliases they introduce.  Entity
+//! names resolve to the project classes annotated `@Entity` under that name (the
+//! annotation's `name`, or else the simple class name); `alias.field` paths
+//! resolve field by field through the declared field types.  The entity name is
+//! read when the class is indexed.  Native queries (`nativeQuery = true`) are SQL
+//! and left alone.
+//!
+//! Entity names that match no `@Entity` class, nor any library type of that
+//! name (library classes are indexed without their annotations), are reported
+//! as `unresolved_entity`.
+
+use std::collections::HashMap;
+
+use lsp_core::ts_helper::{byte_offset_to_position, position_to_byte_offset};
+use tower_lsp::lsp_types::{
+    Diagnostic, DiagnosticSeverity, NumberOrString, Range, TextDocumentPositionParams,
+};
+use tree_sitter::{Node, Tree};
+
+use crate::{enums::ResolvedSymbol, models::symbol::Symbol, server::Backend};
+
+/// Annotations whose string arguments hold JPQL.
+const QUERY_ANNOTATIONS: &[&str] = &["Query", "NamedQuery"];
+
+/// Reserved words that end an entity declaration, so they are never taken as aliases.
+const JPQL_KEYWORDS: &[&str] = &[
+    "AS", "BY", "FETCH", "FROM", "GROUP", "HAVING", "IN", "INNER", "JOIN", "LEFT", "ON", "ORDER",
+    "OUTER", "SELECT", "SET", "UNION", "WHERE",
+];
+
+/// A JPQL query assembled from the string literals of one annotation.
+struct JpqlQuery {
+    text: String,
+    /// `(offset in text, byte offset in source, length)` of each literal's content.
+    segments: Vec<(usize, usize, usize)>,
+}
+
+impl JpqlQuery {
+    fn source_offset(&self, offset: usize) -> usize {
+        let (start, source, _) = self
+            .segments
+            .iter()
+            .rev()
+            .find(|(start, _, _)| *start <= offset)
+            .copied()
+            .unwrap_or_default();
+        source + (offset - start)
+    }
+
+    fn query_offset(&self, source_offset: usize) -> Option<usize> {
+        self.segments.iter().find_map(|(start, source, len)| {
+            (*source..=source + len)
+                .contains(&source_offset)
+                .then(|| start + (source_offset - source))
+        })
+    }
+}
+
+/// What an alias stands for: an entity name followed by the fields joined through.
+#[derive(Debug, Clone)]
+struct AliasTarget {
+    entity: String,
+    fields: Vec<String>,
+    offset: usize,
+}
+
+/// The entity names, aliases and paths of one query, with offsets into its text.
+#[derive(Debug, Default)]
+struct JpqlAnalysis {
+    entities: Vec<(String, usize)>,
+    aliases: HashMap<String, AliasTarget>,
+    paths: Vec<Vec<(String, usize)>>,
+}
+
+/// What a word under the cursor refers to.
+enum JpqlTarget {
+    Entity(String),
+    Alias { name: String, offset: usize },
+    Field { entity: String, fields: Vec<String> },
+}
+
+/// Splits JPQL into identifier paths (`u.address.city`) and commas, skipping string
+/// literals and named or positional parameters.
+fn tokenize(text: &str) -> Vec<(&str, usize)> {
+    let mut tokens = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        if c == '\'' {
+            while chars.next().is_some_and(|(_, c)| c != '\'') {}
+        } else if c == ',' {
+            tokens.push((",", start));
+        } else if c.is_alphabetic() || c == '_' || c == '$' {
+            let mut end = start + c.len_utf8();
+            while let Some(&(i, c)) = chars.peek() {
+                if !(c.is_alphanumeric() || c == '_' || c == '$' || c == '.') {
+                    break;
+                }
+                end = i + c.len_utf8();
+                chars.next();
+            }
+            let is_parameter = text[..start].ends_with(':') || text[..start].ends_with('?');
+            if !is_parameter {
+                tokens.push((text[start..end].trim_end_matches('.'), start));
+            }
+        }
+    }
+    tokens
+}
+
+fn is_keyword(word: &str) -> bool {
+    JPQL_KEYWORDS.iter().any(|k| k.eq_ignore_ascii_case(word))
+}
+
+fn analyze(text: &str) -> JpqlAnalysis {
+    let tokens = tokenize(text);
+    let mut analysis = JpqlAnalysis::default();
+    let mut i = 0;
+    while i < tokens.len() {
+        let word = tokens[i].0;
+        i += 1;
+        if !["FROM", "JOIN", "UPDATE"]
+            .iter()
+            .any(|k| k.eq_ignore_ascii_case(word))
+        {
+            continue;
+        }
+        if word.eq_ignore_ascii_case("JOIN")
+            && tokens.get(i).is_some_and(|t| t.0.eq_ignore_ascii_case("FETCH"))
+        {
+            i += 1;
+        }
+        // One declaration, or a comma-separated list of them after FROM.
+        while let Some(&(name, offset)) = tokens.get(i) {
+            if name == "," || is_keyword(name) {
+                break;
+            }
+            i += 1;
+            let target = match name.split_once('.') {
+                None => {
+                    analysis.entities.push((name.to_string(), offset));
+                    Some((name.to_string(), vec![]))
+                }
+                Some((alias, fields)) => analysis.aliases.get(alias).map(|a| {
+                    let mut joined = a.fields.clone();
+                    joined.extend(fields.split('.').map(str::to_string));
+                    (a.entity.clone(), joined)
+                }),
+            };
+            if tokens.get(i).is_some_and(|t| t.0.eq_ignore_ascii_case("AS")) {
+                i += 1;
+            }
+            if let Some(&(alias, alias_offset)) = tokens.get(i)
+                && alias != ","
+                && !is_keyword(alias)
+                && !alias.contains('.')
+            {
+                i += 1;
+                if let Some((entity, fields)) = target {
+                    analysis.aliases.insert(
+                        alias.to_string(),
+                        AliasTarget {
+                            entity,
+                            fields,
+                            offset: alias_offset,
+                        },
+                    );
+                }
+            }
+            if !word.eq_ignore_ascii_case("FROM") || tokens.get(i).is_none_or(|t| t.0 != ",") {
+                break;
+            }
+            i += 1;
+        }
+    }
+
+    for &(word, offset) in &tokens {
+        if word.contains('.') {
+            let mut segment_offset = offset;
+            let segments = word
+                .split('.')
+                .map(|segment| {
+                    let entry = (segment.to_string(), segment_offset);
+                    segment_offset += segment.len() + 1;
+                    entry
+                })
+                .collect();
+            analysis.paths.push(segments);
+        } else if analysis.aliases.contains_key(word) {
+            analysis.paths.push(vec![(word.to_string(), offset)]);
+        }
+    }
+    analysis
+}
+
+impl JpqlAnalysis {
+    fn target_at(&self, offset: usize) -> Option<JpqlTarget> {
+        let covers = |word: &str, start: usize| (start..=start + word.len()).contains(&offset);
+        if let Some((name, _)) = self.entities.iter().find(|(n, s)| covers(n, *s)) {
+            return Some(JpqlTarget::Entity(name.clone()));
+        }
+        if let Some((name, alias)) = self.aliases.iter().find(|(n, a)| covers(n, a.offset)) {
+            return Some(JpqlTarget::Alias {
+                name: name.clone(),
+                offset: alias.offset,
+            });
+        }
+        for path in &self.paths {
+            let Some(index) = path.iter().position(|(s, start)| covers(s, *start)) else {
+                continue;
+            };
+            let Some(alias) = self.aliases.get(&path[0].0) else {
+                continue;
+            };
+            if index == 0 {
+                return Some(JpqlTarget::Alias {
+                    name: path[0].0.clone(),
+                    offset: alias.offset,
+                });
+            }
+            let mut fields = alias.fields.clone();
+            fields.extend(path[1..=index].iter().map(|(s, _)| s.clone()));
+            return Some(JpqlTarget::Field {
+                entity: alias.entity.clone(),
+                fields,
+            });
+        }
+        None
+    }
+}
+
+/// The JPQL queries of the query annotations in `tree`.
+fn jpql_queries(tree: &Tree, content: &str) -> Vec<JpqlQuery> {
+    let mut queries = Vec::new();
+    let mut stack = vec![tree.root_node()];
+    while let Some(node) = stack.pop() {
+        if node.kind().contains("annotation")
+            && let Some(query) = annotation_query(node, content)
+        {
+            queries.push(query);
+            continue;
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor));
+    }
+    queries
+}
+
+fn annotation_query(node: Node, content: &str) -> Option<JpqlQuery> {
+    if !QUERY_ANNOTATIONS.contains(&annotation_name(node, content)?) {
+        return None;
+    }
+    let text = node.utf8_text(content.as_bytes()).ok()?;
+    if text.replace(' ', "").contains("nativeQuery=true") {
+        return None;
+    }
+
+    // Only the `value`/`query` argument is JPQL; `@NamedQuery(name = "User.findAll")`
+    // names the query.
+    let mut query = JpqlQuery {
+        text: String::new(),
+        segments: vec![],
+    };
+    for (argument, literal) in annotation_string_arguments(node, content) {
+        if !matches!(argument, "value" | "query") {
+            continue;
+        }
+        let (body, start) = literal_body(literal, content)?;
+        if !query.text.is_empty() {
+            query.text.push(' ');
+        }
+        query.segments.push((query.text.len(), start, body.len()));
+        query.text.push_str(body);
+    }
+    (!query.text.is_empty()).then_some(query)
+}
+
+/// The simple name of an annotation node, e.g. `Query` for `@org.example.Query("...")`.
+pub(crate) fn annotation_name<'a>(node: Node, content: &'a str) -> Option<&'a str> {
+    let name = node
+        .utf8_text(content.as_bytes())
+        .ok()?
+        .strip_prefix('@')?
+        .split(|c: char| c == '(' || c.is_whitespace())
+        .next()?;
+    Some(name.rsplit('.').next().unwrap_or(name))
+}
+
+/// The string literals among an annotation's arguments, in source order, each with
+/// the name of the argument it belongs to (`value` for a positional argument).
+/// Concatenated literals belong to the argument of the literal before them.
+pub(crate) fn annotation_string_arguments<'t, 'a>(
+    node: Node<'t>,
+    content: &'a str,
+) -> Vec<(&'a str, Node<'t>)> {
+    let mut stack = vec![node];
+    let mut literals = vec![];
+    while let Some(n) = stack.pop() {
+        if matches!(
+            n.kind(),
+            "string_literal" | "text_block" | "gstring" | "multiline_string_literal"
+        ) {
+            literals.push(n);
+            continue;
+        }
+        let mut cursor = n.walk();
+        stack.extend(n.children(&mut cursor));
+    }
+    literals.sort_by_key(|n| n.start_byte());
+
+    let mut argument = "value";
+    literals
+        .into_iter()
+        .map(|literal| {
+            let before = content[node.start_byte()..literal.start_byte()].trim_end();
+            if let Some(name) = before.strip_suffix('=') {
+                let name = name.trim_end();
+                argument = name
+                    .rsplit(|c: char| !(c.is_alphanumeric() || c == '_'))
+                    .next()
+                    .unwrap_or(name);
+            } else if !before.ends_with('+') {
+                argument = "value";
+            }
+            (argument, literal)
+        })
+        .collect()
+}
+
+/// The text between a string literal's quotes, with the byte offset it starts at.
+pub(crate) fn literal_body<'a>(literal: Node, content: &'a str) -> Option<(&'a str, usize)> {
+    let raw = literal.utf8_text(content.as_bytes()).ok()?;
+    let body = raw.trim_start_matches(['"', '\'']);
+    let start = literal.start_byte() + (raw.len() - body.len());
+    Some((body.trim_end_matches(['"', '\'']), start))
+}
+
+/// The simple name of the type a declared type refers to, looking through collection
+/// and nullability wrappers: `Order` for `List<Order>`, `Address?` or `com.x.Address`.
+pub(crate) fn element_type_name(declared: &str) -> Option<&str> {
+    let name = declared
+        .rsplit('<')
+        .next()
+        .unwrap_or(declared)
+        .trim_end_matches(['>', '?', ' '])
+        .rsplit(['.', ',', ' '])
+        .next()?
+        .trim();
+    (!name.is_empty()).then_some(name)
+}
+
+/// The `name` given in the `@Entity(name = "...")` annotation of a class declared
+/// from `line_start` of `content`, read up to the line of its name.
+pub(crate) fn declared_entity_name(
+    content: &str,
+    line_start: u32,
+    ident_line: u32,
+) -> Option<String> {
+    let header = content
+        .lines()
+        .skip(line_start as usize)
+        .take(ident_line.saturating_sub(line_start) as usize + 1)
+        .collect::<Vec<_>>()
+        .join("\n");
+    let (_, after) = header
+        .match_indices("@Entity")
+        .map(|(i, m)| header.split_at(i + m.len()))
+        .find(|(_, rest)| !rest.starts_with(|c: char| c.is_alphanumeric() || c == '_'))?;
+    let arguments = after.trim_start().strip_prefix('(')?.split(')').next()?;
+    arguments.split(',').find_map(|argument| {
+        let (key, value) = argument.split_once('=')?;
+        (key.trim() == "name").then(|| value.trim().trim_matches('"').to_string())
+    })
+}
+
+impl Backend {
+    /// Resolves the JPQL word at the request position, when it sits inside a query
+    /// annotation.  Returns `None` outside JPQL, so regular resolution applies.
+    pub(crate) async fn resolve_jpql_reference(
+        &self,
+        params: &TextDocumentPositionParams,
+    ) -> Option<Vec<ResolvedSymbol>> {
+        let uri = &params.text_document.uri;
+        let content = self.document_text(uri)?;
+        if !content.contains("Query") {
+            return None;
+        }
+        let ext = uri.path().rsplit('.').next()?;
+        let lang = self.languages.get(ext)?;
+        let (tree, _) = lang.parse_str(&content)?;
+        let offset = position_to_byte_offset(&content, &params.position);
+
+        let query = jpql_queries(&tree, &content)
+            .into_iter()
+            .find(|q| q.query_offset(offset).is_some())?;
+        let analysis = analyze(&query.text);
+        let symbols = match analysis.target_at(query.query_offset(offset)?)? {
+            JpqlTarget::Entity(name) => self
+                .find_entities(&name)
+                .await
+                .into_iter()
+                .map(ResolvedSymbol::Project)
+                .collect(),
+            JpqlTarget::Alias { name, offset: alias_offset } => vec![ResolvedSymbol::Local {
+                uri: uri.clone(),
+                position: byte_offset_to_position(&content, query.source_offset(alias_offset)),
+                name,
+                var_type: None,
+            }],
+            JpqlTarget::Field { entity, fields } => self
+                .resolve_entity_path(&entity, &fields)
+                .await
+                .map(ResolvedSymbol::Project)
+                .into_iter()
+                .collect(),
+        };
+        Some(symbols)
+    }
+
+    /// Reports entity names in query annotations that match no entity class.
+    pub(crate) async fn jpql_diagnostics(&self, tree: &Tree, content: &str) -> Vec<Diagnostic> {
+        let Some(repo) = self.repo.get() else {
+            return vec![];
+        };
+        let mut diagnostics = Vec::new();
+        for query in jpql_queries(tree, content) {
+            for (name, offset) in analyze(&query.text).entities {
+                if !self.find_entities(&name).await.is_empty()
+                    || !repo
+                        .find_external_types_by_short_name_and_jars(&name, &[])
+                        .await
+                        .unwrap_or_default()
+                        .is_empty()
+                {
+                    continue;
+                }
+                let start = query.source_offset(offset);
+                diagnostics.push(Diagnostic {
+                    range: Range::new(
+                        byte_offset_to_position(content, start),
+                        byte_offset_to_position(content, start + name.len()),
+                    ),
+                    severity: Some(DiagnosticSeverity::ERROR),
+                    code: Some(NumberOrString::String("unresolved_entity".to_string())),
+                    source: Some("lspintar".to_string()),
+                    message: format!("Cannot resolve entity '{name}'"),
+                    ..Default::default()
+                });
+            }
+        }
+        diagnostics
+    }
+
+    /// The project classes annotated `@Entity` under the entity name `name`: the
+    /// `name` attribute of the annotation, or else the simple class name.
+    async fn find_entities(&self, name: &str) -> Vec<Symbol> {
+        let Some(repo) = self.repo.get() else {
+            return vec![];
+        };
+        repo.find_entities_by_name(name).await.unwrap_or_default()
+    }
+
+    /// Walks `fields` from `entity`, each through the declared type of the previous
+    /// field; collections are followed into their element type.
+    async fn resolve_entity_path(&self, entity: &str, fields: &[String]) -> Option<Symbol> {
+        let mut owner = self.find_entities(entity).await.into_iter().next()?;
+        let mut field = None;
+        for name in fields {
+            if let Some(previous) = field.take() {
+                owner = self.field_type(&previous).await?;
+            }
+            field = Some(self.entity_field(&owner.fully_qualified_name, name).await?);
+        }
+        field
+    }
+
+    /// The field `name` of the type `owner_fqn`, declared on it or inherited from a
+    /// `@MappedSuperclass` or parent entity.
+    pub(crate) async fn entity_field(&self, owner_fqn: &str, name: &str) -> Option<Symbol> {
+        let repo = self.repo.get()?;
+        let ancestors = self.supertype_closure(owner_fqn).await;
+        let owners = std::iter::once(owner_fqn)
+            .chain(ancestors.iter().map(|s| s.fully_qualified_name.as_str()));
+        for owner in owners {
+            if let Some(field) = repo
+                .find_symbols_by_parent_name(owner)
+                .await
+                .unwrap_or_default()
+                .into_iter()
+                .find(|s| s.symbol_type == "Field" && s.short_name == name)
+            {
+                return Some(field);
+            }
+        }
+        None
+    }
+
+    async fn field_type(&self, field: &Symbol) -> Option<Symbol> {
+        let declared = field.metadata.0.return_type.as_deref()?;
+        self.repo
+            .get()?
+            .find_types_by_short_name(element_type_name(declared)?)
+            .await
+            .ok()?
+            .into_iter()
+            .next()
+    }
+}
@@ -0,0 +1,190 @@
+//! Kotlin file facades: the classes Java sees top-level declarations through.
+//!
+//! Kotlin compiles the top-level functions and properties of `utils.kt` into
+//! static members of a class `UtilsKt` in the file's package, or of the class a
+//! `@file:JvmName("Utils")` annotation names.  The index keeps each top-level
+//! declaration under its package, as Kotlin callers name it, and adds the facade
+//! as a class with the static members Java calls: the functions themselves, a
+//! getter and, for a `var`, a setter per property, and the property itself when
+//! it is `const` or `@JvmField`.  Private declarations are not part of it.  The
+//! getters and setters are marked as synthetic accessors, having no declaration
+//! of their own.
+
+use std::path::Path;
+
+use lsp_core::node_kind::NodeKind;
+use sqlx::types::Json;
+
+use crate::models::symbol::{Symbol, SymbolMetadata, SymbolParameter};
+
+/// The name of the facade class of the Kotlin file at `path` with `content`.
+pub fn facade_name(path: &Path, content: &str) -> Option<String> {
+    if let Some(name) = jvm_name(content) {
+        return Some(name);
+    }
+    let stem = path.file_stem()?.to_str()?;
+    let mut chars = stem.chars();
+    let first = chars.next()?;
+    let name: String = first
+        .to_uppercase()
+        .chain(chars)
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect();
+    Some(format!("{name}Kt"))
+}
+
+/// The class name a `@file:JvmName("...")` annotation in `content` gives.
+fn jvm_name(content: &str) -> Option<String> {
+    let (_, rest) = content.split_once("@file:JvmName(")?;
+    let arguments = &rest[..rest.find(')')?];
+    let name = arguments.split('"').nth(1)?.trim();
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// Whether Kotlin names the accessors of property `name` after it, as it does for
+/// `isActive`: getter `isActive`, setter `setActive`.
+fn is_boolean_style(name: &str) -> bool {
+    name.strip_prefix("is")
+        .and_then(|rest| rest.chars().next())
+        .is_some_and(char::is_uppercase)
+}
+
+fn capitalized(name: &str) -> String {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map(|c| c.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+/// `symbol` moved into the facade `facade_fqn` as a static member named `name`.
+fn facade_member(symbol: &Symbol, facade_fqn: &str, name: &str, kind: NodeKind) -> Symbol {
+    let mut modifiers: Vec<String> = symbol
+        .modifiers
+        .iter()
+        .filter(|m| !matches!(m.as_str(), "val" | "var" | "const"))
+        .cloned()
+        .collect();
+    if modifiers.iter().all(|m| m != "static") {
+        modifiers.push("static".to_string());
+    }
+    if kind == NodeKind::Field && modifiers.iter().all(|m| m != "final") {
+        modifiers.push("final".to_string());
+    }
+    Symbol {
+        id: None,
+        short_name: name.to_string(),
+        fully_qualified_name: format!("{facade_fqn}#{name}"),
+        parent_name: Some(facade_fqn.to_string()),
+        symbol_type: kind.to_string(),
+        modifiers: Json(modifiers),
+        ..symbol.clone()
+    }
+}
+
+/// The facade class of a Kotlin file and its members, given the file's `symbols`.
+/// The class is located at the first top-level declaration.  Empty when the file
+/// declares no public top-level function or property.
+pub fn facade_symbols(
+    symbols: &[Symbol],
+    path: &Path,
+    content: &str,
+    package_name: &str,
+) -> Vec<Symbol> {
+    let top_level: Vec<&Symbol> = symbols
+        .iter()
+        .filter(|s| s.parent_name.as_deref() == Some(package_name))
+        .filter(|s| matches!(s.symbol_type.as_str(), "Function" | "Field"))
+        .filter(|s| !s.modifiers.iter().any(|m| m == "private"))
+        .collect();
+    let Some(first) = top_level.first() else {
+        return vec![];
+    };
+    let Some(name) = facade_name(path, content) else {
+        return vec![];
+    };
+    let facade_fqn = if package_name.is_empty() {
+        name.clone()
+    } else {
+        format!("{package_name}.{name}")
+    };
+
+    let mut facade = vec![Symbol {
+        id: None,
+        short_name: name.clone(),
+        fully_qualified_name: facade_fqn.clone(),
+        parent_name: Some(package_name.to_string()),
+        symbol_type: NodeKind::Class.to_string(),
+        modifiers: Json(vec!["public".to_string(), "final".to_string()]),
+        metadata: Json(SymbolMetadata {
+            parameters: None,
+            return_type: None,
+            generic_return_type: None,
+            documentation: None,
+            annotations: None,
+            type_params: None,
+            generic_param_types: None,
+            method_type_params: None,
+            constant_value: None,
+            entity_name: None,
+            synthetic_accessor: None,
+        }),
+        ..(*first).clone()
+    }];
+
+    for symbol in top_level {
+        if symbol.symbol_type == "Function" {
+            facade.push(facade_member(
+                symbol,
+                &facade_fqn,
+                &symbol.short_name,
+                NodeKind::Function,
+            ));
+            continue;
+        }
+        let annotations = symbol.metadata.annotations.as_deref().unwrap_or_default();
+        if symbol.modifiers.iter().any(|m| m == "const")
+            || annotations.iter().any(|a| a == "JvmField")
+        {
+            facade.push(facade_member(
+                symbol,
+                &facade_fqn,
+                &symbol.short_name,
+                NodeKind::Field,
+            ));
+            continue;
+        }
+
+        let property_type = symbol.metadata.return_type.clone();
+        let getter_name = if is_boolean_style(&symbol.short_name) {
+            symbol.short_name.clone()
+        } else {
+            format!("get{}", capitalized(&symbol.short_name))
+        };
+        let mut getter = facade_member(symbol, &facade_fqn, &getter_name, NodeKind::Function);
+        getter.metadata.parameters = Some(vec![]);
+        getter.metadata.constant_value = None;
+        getter.metadata.synthetic_accessor = Some(true);
+        facade.push(getter);
+
+        if symbol.modifiers.iter().any(|m| m == "var") {
+            let base = if is_boolean_style(&symbol.short_name) {
+                &symbol.short_name[2..]
+            } else {
+                &symbol.short_name
+            };
+            let setter_name = format!("set{}", capitalized(base));
+            let mut setter = facade_member(symbol, &facade_fqn, &setter_name, NodeKind::Function);
+            setter.metadata.parameters = Some(vec![SymbolParameter {
+                name: "value".to_string(),
+                type_name: property_type,
+                default_value: None,
+            }]);
+            setter.metadata.return_type = Some("void".to_string());
+            setter.metadata.constant_value = None;
+            setter.metadata.synthetic_accessor = Some(true);
+            facade.push(setter);
+        }
+    }
+    facade
+}
@@ -1,12 +1,73 @@
+pub mod accessors;
+pub mod analyzer;
+pub mod annotation_attributes;
+pub mod build_scripts;
+pub mod check;
+pub mod class_resolution;
+pub mod classpath_version;
+pub mod code_action;
+pub mod code_lens;
 pub mod constants;
+pub mod constructor_delegation;
+pub mod context;
+pub mod copy_reference;
+pub mod db_lock;
+pub mod dependency_conflict;
+pub mod dependency_watch;
+pub mod document_color;
+pub mod document_link;
+pub mod document_symbol;
+pub mod enum_constant;
 pub mod enums;
+pub mod explain_resolution;
+pub mod explicit_type;
+pub mod extract_constant;
+pub mod file_rename;
 pub mod generic_resolution;
+pub mod gradle_plugins;
+pub mod gradle_tasks;
+pub mod implement_members;
+pub mod implementable;
+pub mod import_preferences;
+pub mod index_cli;
+pub mod index_status;
 pub mod indexer;
+pub mod inline_value;
+pub mod jpa_relationship;
+pub mod jpql;
+pub mod kotlin_facade;
 pub mod lsp_convert;
+pub mod member_search;
 pub mod models;
+pub mod moved_files;
+pub mod nullability;
+pub mod on_type_formatting;
+pub mod organize_imports;
+pub mod package_mismatch;
+pub mod project_cycles;
+pub mod project_root;
 pub mod rename;
+pub mod resource_bundle;
 pub mod repo;
+pub mod resolve_trace;
+pub mod search_limits;
+pub mod semantic_tokens;
 pub mod server;
+pub mod source_set;
+pub mod spring_xml;
+pub mod string_reference;
+pub mod supertypes;
+pub mod suppression;
+pub mod synthetic_members;
+pub mod test_discovery;
+pub mod type_alias;
+pub mod type_at;
+pub mod unreachable;
+pub mod unused;
+pub mod visibility;
+pub mod warm_cache;
+pub mod workspace_diagnostics;
+pub mod workspace_symbol;
 
 pub use indexer::Indexer;
 pub use repo::Repository;
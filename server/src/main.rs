@@ -1,22 +1,115 @@
+use std::process::ExitCode;
+
+use lspintar_server::{check, index_cli, warm_cache};
 use tokio::io::{stdin, stdout};
 use tower_lsp::{LspService, Server};
 
+mod accessors;
+mod annotation_attributes;
+mod build_scripts;
+mod class_resolution;
+mod classpath_version;
+mod code_action;
+mod code_lens;
 mod constants;
+mod constructor_delegation;
+mod context;
+mod copy_reference;
+mod db_lock;
+mod dependency_conflict;
+mod dependency_watch;
+mod document_color;
+mod document_link;
+mod document_symbol;
+mod enum_constant;
 mod enums;
+mod explain_resolution;
+mod explicit_type;
+mod extract_constant;
+mod file_rename;
 mod generic_resolution;
+mod gradle_plugins;
+mod gradle_tasks;
+mod implement_members;
+mod implementable;
+mod import_preferences;
+mod index_status;
 mod indexer;
+mod inline_value;
+mod jpa_relationship;
+mod jpql;
+mod kotlin_facade;
 mod lsp_convert;
+mod member_search;
 mod models;
+mod moved_files;
+mod nullability;
+mod on_type_formatting;
+mod organize_imports;
+mod package_mismatch;
+mod project_cycles;
+mod project_root;
 mod rename;
+mod resource_bundle;
 mod repo;
+mod resolve_trace;
+mod search_limits;
+mod semantic_tokens;
 mod server;
+mod source_set;
+mod spring_xml;
+mod string_reference;
+mod supertypes;
+mod suppression;
+mod synthetic_members;
+mod test_discovery;
+mod type_alias;
+mod type_at;
+mod unreachable;
+mod unused;
+mod visibility;
+mod workspace_diagnostics;
+mod workspace_symbol;
 
+use index_status::INDEX_STATUS_METHOD;
 use indexer::Indexer;
 use repo::Repository;
+use resolve_trace::RESOLVE_TRACE_METHOD;
 use server::Backend;
+use test_discovery::{TEST_LOCATION_METHOD, TESTS_METHOD};
+use type_at::TYPE_AT_METHOD;
+
+/// Subcommands run without starting the language server.
+const CLI_COMMANDS: &[&str] = &[
+    check::CHECK_COMMAND,
+    index_cli::INDEX_COMMAND,
+    index_cli::QUERY_COMMAND,
+    index_cli::WHO_IMPLEMENTS_COMMAND,
+    warm_cache::WARM_CACHE_COMMAND,
+];
 
 #[tokio::main]
-async fn main() {
+async fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some((command, rest)) = args.split_first()
+        && CLI_COMMANDS.contains(&command.as_str())
+    {
+        tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::WARN)
+            .with_env_filter("warn,sqlx=error")
+            .with_writer(std::io::stderr)
+            .without_time()
+            .with_target(false)
+            .init();
+        return match command.as_str() {
+            check::CHECK_COMMAND => check::run(rest).await,
+            index_cli::INDEX_COMMAND => index_cli::run_index(rest).await,
+            index_cli::QUERY_COMMAND => index_cli::run_query(rest).await,
+            warm_cache::WARM_CACHE_COMMAND => warm_cache::run(rest).await,
+            _ => index_cli::run_who_implements(rest).await,
+        };
+    }
+
     tracing_subscriber::fmt()
         .with_max_level(tracing::Level::DEBUG)
         .with_env_filter("debug,sqlx=warn,rusqlite=warn")
@@ -26,7 +119,14 @@ async fn main() {
         .with_target(false)
         .init();
 
-    let (service, socket) = LspService::new(Backend::new);
+    let (service, socket) = LspService::build(Backend::new)
+        .custom_method(TESTS_METHOD, Backend::tests)
+        .custom_method(TEST_LOCATION_METHOD, Backend::test_location)
+        .custom_method(INDEX_STATUS_METHOD, Backend::index_status)
+        .custom_method(TYPE_AT_METHOD, Backend::type_at)
+        .custom_method(RESOLVE_TRACE_METHOD, Backend::resolve_trace)
+        .finish();
 
     Server::new(stdin(), stdout(), socket).serve(service).await;
+    ExitCode::SUCCESS
 }
@@ -0,0 +1,174 @@
+//! Member search: the members a name reached through a type stands for.
+//!
+//! A member is looked up on the type itself, then among the members it has
+//! without declaring them under that name (see [`crate::synthetic_members`]), then
+//! through its supertypes, depth first, and last among the library declarations
+//! of the type.
+
+use std::collections::HashSet;
+
+use crate::{enums::ResolvedSymbol, server::Backend};
+
+impl Backend {
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn try_type_member(
+        &self,
+        qualifier: &str,
+        member: &str,
+        imports: &[String],
+        package_name: Option<String>,
+    ) -> Vec<ResolvedSymbol> {
+        let class_fqn = match self
+            .resolve_fqn(qualifier, imports.to_vec(), package_name.clone())
+            .await
+        {
+            Some(fqn) => fqn,
+            None => return vec![],
+        };
+
+        let mut visited = HashSet::new();
+        self.try_members_with_inheritance(
+            &class_fqn,
+            member,
+            &mut visited,
+            imports.to_vec(),
+            package_name,
+        )
+        .await
+    }
+
+    async fn try_parent_member(
+        &self,
+        type_fqn: &str,
+        member: &str,
+        visited: &mut HashSet<String>,
+        imports: Vec<String>,
+        package_name: Option<String>,
+    ) -> Vec<ResolvedSymbol> {
+        let type_symbol = match self.repo.get() {
+            None => return vec![],
+            Some(repo) => match repo.find_symbol_by_fqn(type_fqn).await {
+                Ok(symbols) => symbols.into_iter().next(),
+                Err(_) => None,
+            },
+        };
+
+        let type_symbol = match type_symbol {
+            Some(s) => s,
+            None => return vec![],
+        };
+
+        let supers = match self.repo.get() {
+            None => return vec![],
+            Some(repo) => match repo
+                .find_supers_by_symbol_fqn(&type_symbol.fully_qualified_name)
+                .await
+            {
+                Ok(symbols) => symbols,
+                Err(_) => return vec![],
+            },
+        };
+
+        for super_name in supers.iter().map(|symbol| &symbol.fully_qualified_name) {
+            let results = self
+                .recurse_try_members_with_inheritance(
+                    super_name,
+                    member,
+                    visited,
+                    imports.clone(),
+                    package_name.clone(),
+                )
+                .await;
+            if !results.is_empty() {
+                return results;
+            }
+        }
+
+        vec![]
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn try_members_with_inheritance(
+        &self,
+        type_fqn: &str,
+        member: &str,
+        visited: &mut HashSet<String>,
+        imports: Vec<String>,
+        package_name: Option<String>,
+    ) -> Vec<ResolvedSymbol> {
+        if !visited.insert(type_fqn.to_string()) {
+            return vec![];
+        }
+
+        let member_fqn = format!("{}#{}", type_fqn, member);
+
+        // Try direct member
+        if let Some(repo) = self.repo.get()
+            && let Ok(found) = repo.find_symbols_by_fqn(&member_fqn).await
+            && !found.is_empty()
+        {
+            return found.into_iter().map(ResolvedSymbol::Project).collect();
+        }
+
+        if let Some(found) = self.synthetic_member(type_fqn, member).await {
+            return vec![ResolvedSymbol::Project(found)];
+        }
+
+        let result = self
+            .try_parent_member(type_fqn, member, visited, imports, package_name)
+            .await;
+        if !result.is_empty() {
+            return result;
+        }
+
+        if let Some(repo) = self.repo.get()
+            && let Ok(Some(found)) = repo.find_external_symbol_by_fqn(&member_fqn).await
+        {
+            tracing::info!("found: {:?}", found);
+            return vec![ResolvedSymbol::External(found)];
+        }
+
+        vec![]
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn recurse_try_members_with_inheritance(
+        &self,
+        parent_short_name: &str,
+        member: &str,
+        visited: &mut HashSet<String>,
+        imports: Vec<String>,
+        package_name: Option<String>,
+    ) -> Vec<ResolvedSymbol> {
+        tracing::info!("recurse_try_members_with_inheritance");
+        let fqn = match self
+            .resolve_fqn(parent_short_name, imports.clone(), package_name.clone())
+            .await
+        {
+            Some(fqn) => fqn,
+            None => return vec![],
+        };
+
+        let resolved_fqn = match self.repo.get() {
+            None => return vec![],
+            Some(repo) => {
+                if let Ok(Some(s)) = repo.find_symbol_by_fqn(&fqn).await {
+                    s.fully_qualified_name
+                } else if let Ok(Some(s)) = repo.find_external_symbol_by_fqn(&fqn).await {
+                    s.fully_qualified_name
+                } else {
+                    return vec![];
+                }
+            }
+        };
+
+        Box::pin(self.try_members_with_inheritance(
+            &resolved_fqn,
+            member,
+            visited,
+            imports,
+            package_name,
+        ))
+        .await
+    }
+}
@@ -16,7 +16,7 @@ use crate::Indexer;
 use crate::constants::{get_cache_dir, get_cfr_jar_path};
 use crate::lsp_convert::{AsLspHover, AsLspLocation};
 use crate::models::symbol::SymbolMetadata;
-use crate::models::util::build_hover_parts;
+use crate::models::util::{build_hover_parts, symbol_key};
 
 #[derive(Debug, Clone, FromRow, PartialEq, Eq)]
 pub struct ExternalSymbol {
@@ -49,18 +49,13 @@ pub struct ExternalSymbol {
 impl AsLspLocation for ExternalSymbol {
     fn as_lsp_location(&self) -> Option<Location> {
         let cached_path = self.extract_to_cache().ok()?;
-        let from_sources = self.needs_decompilation
-            && cached_path.extension().and_then(|e| e.to_str()) != Some("class");
         let uri = Url::from_file_path(cached_path).ok()?;
-        let range = if from_sources {
-            // Precise location unknown from bytecode indexing; open at top of file
-            Range::new(Position::new(0, 0), Position::new(0, 0))
-        } else {
-            Range::new(
-                Position::new(self.ident_line_start as u32, self.ident_char_start as u32),
-                Position::new(self.ident_line_end as u32, self.ident_char_end as u32),
-            )
-        };
+        // Bytecode indexing records no positions, so a decompiled symbol that
+        // `with_sources` could not locate opens at the top of the file.
+        let range = Range::new(
+            Position::new(self.ident_line_start as u32, self.ident_char_start as u32),
+            Position::new(self.ident_line_end as u32, self.ident_char_end as u32),
+        );
         Some(Location { uri, range })
     }
 }
@@ -86,6 +81,15 @@ impl AsLspHover for ExternalSymbol {
 }
 
 impl ExternalSymbol {
+    /// The stable key of this symbol (see [`symbol_key`]).
+    pub fn key(&self) -> String {
+        symbol_key(
+            &self.fully_qualified_name,
+            &self.symbol_type,
+            &self.metadata,
+        )
+    }
+
     pub fn extract_to_cache(&self) -> Result<PathBuf, Box<dyn Error>> {
         let mut hasher = DefaultHasher::new();
         self.jar_path.hash(&mut hasher);
@@ -180,6 +184,15 @@ impl ExternalSymbol {
         Ok(outpath)
     }
 
+    /// This symbol as declared in its sources JAR, when it was indexed from bytecode
+    /// and one is known: located in the source file, and carrying the source's
+    /// signature (parameter names, documentation) when the overload can be matched.
+    ///
+    /// The class's source is parsed on first access only; its member signatures are
+    /// stored alongside the bytecode ones and looked up from then on.  While another
+    /// instance writes the index, they are kept in memory instead.
+    ///
+    /// Without sources, the symbol is located in its decompiled class instead.
     pub async fn with_sources(&self, indexer: Option<&Indexer>) -> Self {
         let Some(indexer) = indexer else {
             return self.clone();
@@ -188,26 +201,63 @@ impl ExternalSymbol {
             return self.clone();
         }
         let Some(alt_jar) = &self.alt_jar_path else {
-            return self.clone();
+            return self.located_in_decompiled(indexer).await;
         };
-        let alt_jar = PathBuf::from(alt_jar);
-        let fqn = self.fully_qualified_name.clone();
-        let Ok(Ok((src_symbols, _))) = tokio::task::spawn_blocking({
-            let indexer = indexer.clone();
-            move || indexer.extract_jar_symbols(&alt_jar, None)
-        })
-        .await
-        else {
-            return self.clone();
-        };
-        for chunk in src_symbols.chunks(1000) {
-            match indexer.repo.insert_external_symbols(chunk).await {
-                Ok(_) => tracing::info!("inserted {} src symbols", chunk.len()),
+        let class_fqn = self
+            .fully_qualified_name
+            .split_once('#')
+            .map(|(class, _)| class)
+            .unwrap_or(&self.fully_qualified_name)
+            .to_string();
+
+        let parsed = indexer
+            .repo
+            .is_external_class_source_parsed(alt_jar, &class_fqn)
+            .await
+            .unwrap_or(false);
+        let src_symbols = if parsed {
+            self.stored_source_symbols(indexer, alt_jar).await
+        } else if indexer.is_reader() {
+            let key = (alt_jar.clone(), class_fqn);
+            let cached = indexer.parsed_sources.get(&key).map(|s| s.clone());
+            let class_symbols = match cached {
+                Some(symbols) => symbols,
+                None => {
+                    let Some(symbols) = self.parse_class_source(indexer, alt_jar).await else {
+                        return self.clone();
+                    };
+                    indexer.parsed_sources.insert(key, symbols.clone());
+                    symbols
+                }
+            };
+            class_symbols
+                .into_iter()
+                .filter(|s| s.fully_qualified_name == self.fully_qualified_name)
+                .collect()
+        } else {
+            let Some(symbols) = self.parse_class_source(indexer, alt_jar).await else {
+                return self.clone();
+            };
+            match indexer
+                .repo
+                .insert_external_class_source(alt_jar, &class_fqn, &symbols)
+                .await
+            {
+                Ok(_) => tracing::info!("inserted {} src symbols", symbols.len()),
                 Err(e) => tracing::warn!("failed to insert src symbols: {e}"),
             }
-        }
-        let Some(src_sym) = src_symbols.iter().find(|s| s.fully_qualified_name == fqn) else {
-            return self.clone();
+            self.stored_source_symbols(indexer, alt_jar).await
+        };
+
+        let Some((src_sym, same_signature)) = self.best_declaration(&src_symbols, |s| {
+            (
+                s.symbol_type.as_str(),
+                s.parent_name.as_deref(),
+                s.short_name.as_str(),
+                &s.metadata.0,
+            )
+        }) else {
+            return self.located_in_decompiled(indexer).await;
         };
         let mut enriched = self.clone();
         enriched.jar_path = src_sym.jar_path.clone();
@@ -217,6 +267,140 @@ impl ExternalSymbol {
         enriched.ident_line_end = src_sym.ident_line_end;
         enriched.ident_char_start = src_sym.ident_char_start;
         enriched.ident_char_end = src_sym.ident_char_end;
+        if same_signature {
+            enriched.metadata = src_sym.metadata.clone();
+        }
         enriched
     }
+
+    /// The member signatures parsed from this symbol's class in the sources JAR
+    /// `src_jar`.
+    async fn parse_class_source(
+        &self,
+        indexer: &Indexer,
+        src_jar: &str,
+    ) -> Option<Vec<ExternalSymbol>> {
+        let src_jar = PathBuf::from(src_jar);
+        let class_entry = self.source_file_path.clone();
+        let Ok(Ok((src_symbols, _))) = tokio::task::spawn_blocking({
+            let indexer = indexer.clone();
+            move || indexer.extract_class_source_symbols(&src_jar, &class_entry)
+        })
+        .await
+        else {
+            return None;
+        };
+        Some(src_symbols)
+    }
+
+    /// The declarations of this symbol stored from the sources JAR `src_jar`.
+    async fn stored_source_symbols(&self, indexer: &Indexer, src_jar: &str) -> Vec<Self> {
+        indexer
+            .repo
+            .find_external_symbols_by_fqn_and_jar(&self.fully_qualified_name, src_jar)
+            .await
+            .unwrap_or_default()
+    }
+
+    /// This symbol with the position of its declaration in the decompiled class,
+    /// or unchanged when the decompiler's output does not declare it.
+    async fn located_in_decompiled(&self, indexer: &Indexer) -> Self {
+        let located = tokio::task::spawn_blocking({
+            let symbol = self.clone();
+            let indexer = indexer.clone();
+            move || {
+                let path = symbol.extract_to_cache().ok()?;
+                let (declarations, _) = indexer.index_file(&path).ok()??;
+                let (declaration, _) = symbol.best_declaration(&declarations, |s| {
+                    (
+                        s.symbol_type.as_str(),
+                        s.parent_name.as_deref(),
+                        s.short_name.as_str(),
+                        &s.metadata.0,
+                    )
+                })?;
+                Some((
+                    declaration.ident_line_start,
+                    declaration.ident_line_end,
+                    declaration.ident_char_start,
+                    declaration.ident_char_end,
+                ))
+            }
+        })
+        .await;
+        let mut located_symbol = self.clone();
+        if let Ok(Some((line_start, line_end, char_start, char_end))) = located {
+            located_symbol.ident_line_start = line_start;
+            located_symbol.ident_line_end = line_end;
+            located_symbol.ident_char_start = char_start;
+            located_symbol.ident_char_end = char_end;
+        }
+        located_symbol
+    }
+
+    /// The declaration among `candidates` that is this symbol: the same kind of
+    /// member, of the same name, declared by the same class.  Among overloads, the
+    /// one taking the same parameter types, then the one taking as many; the flag
+    /// tells whether the signature matched exactly.
+    ///
+    /// A same-named member of another kind or class is never taken: a field
+    /// shadowing a getter, or a nested class's method of the same name.
+    fn best_declaration<'a, T>(
+        &self,
+        candidates: &'a [T],
+        key: impl Fn(&T) -> (&str, Option<&str>, &str, &SymbolMetadata),
+    ) -> Option<(&'a T, bool)> {
+        let owner = self.parent_name.as_deref().map(source_name);
+        let same_member: Vec<&T> = candidates
+            .iter()
+            .filter(|c| {
+                let (kind, parent, name, _) = key(c);
+                kind == self.symbol_type
+                    && name == self.short_name
+                    && parent.map(source_name) == owner
+            })
+            .collect();
+        if let Some(exact) = same_member
+            .iter()
+            .find(|c| same_parameters(key(c).3, &self.metadata))
+        {
+            return Some((*exact, true));
+        }
+        let arity = |metadata: &SymbolMetadata| metadata.parameters.as_ref().map(Vec::len);
+        let candidate = same_member
+            .iter()
+            .find(|c| arity(key(c).3) == arity(&self.metadata))
+            .or(same_member.first())?;
+        Some((*candidate, false))
+    }
+}
+
+/// A class name as sources spell it: bytecode separates nested classes with `$`.
+fn source_name(name: &str) -> String {
+    name.replace('$', ".")
+}
+
+/// Whether two declarations take the same parameter types, comparing simple names
+/// only: bytecode records `java.lang.String` where the source says `String`.
+fn same_parameters(a: &SymbolMetadata, b: &SymbolMetadata) -> bool {
+    fn simple_types(metadata: &SymbolMetadata) -> Option<Vec<String>> {
+        let params = metadata.parameters.as_ref()?;
+        Some(
+            params
+                .iter()
+                .map(|p| {
+                    let type_name = p.type_name.as_deref().unwrap_or_default();
+                    let erased = type_name.split('<').next().unwrap_or(type_name);
+                    // Varargs compile to arrays: `Object...` is `java.lang.Object[]`.
+                    let (erased, varargs) = match erased.strip_suffix("...") {
+                        Some(element) => (element, "[]"),
+                        None => (erased, ""),
+                    };
+                    let simple = erased.rsplit('.').next().unwrap_or(erased);
+                    format!("{simple}{varargs}")
+                })
+                .collect(),
+        )
+    }
+    simple_types(a) == simple_types(b)
 }
@@ -6,7 +6,7 @@ use tower_lsp::lsp_types::{
 
 use crate::{
     lsp_convert::{AsLspHover, AsLspLocation},
-    models::util::build_hover_parts,
+    models::util::{build_hover_parts, symbol_key},
 };
 
 #[derive(Debug, Clone, FromRow, PartialEq, Eq)]
@@ -83,6 +83,41 @@ pub struct SymbolMetadata {
     /// Used to build call-site bindings when explicit type args appear at the call site.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub method_type_params: Option<Vec<String>>,
+
+    /// Compile-time value of a constant field, spelled as a literal, e.g. `"v1.2"` or
+    /// `42L`.  Folded from the initializer in source, or read from the field's
+    /// ConstantValue attribute in bytecode; absent for anything that is not a
+    /// constant.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub constant_value: Option<String>,
+
+    /// The name a class annotated `@Entity(name = "...")` goes by in JPQL; absent
+    /// when the annotation gives none and the simple class name applies.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entity_name: Option<String>,
+
+    /// Set on the getter and setter a Kotlin file facade exposes for a top-level
+    /// property.  They share the property's position but have no declaration of
+    /// their own, so position lookups and workspace symbols skip them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub synthetic_accessor: Option<bool>,
+}
+
+impl Symbol {
+    /// Whether this is a Kotlin facade accessor with no declaration of its own (see
+    /// [`SymbolMetadata::synthetic_accessor`]).
+    pub fn is_synthetic_accessor(&self) -> bool {
+        self.metadata.synthetic_accessor == Some(true)
+    }
+
+    /// The stable key of this symbol (see [`symbol_key`]).
+    pub fn key(&self) -> String {
+        symbol_key(
+            &self.fully_qualified_name,
+            &self.symbol_type,
+            &self.metadata,
+        )
+    }
 }
 
 impl AsLspLocation for Symbol {
@@ -2,6 +2,7 @@ use lsp_core::{node_kind::NodeKind, util::strip_comment_signifiers};
 
 use crate::{
     constants::{HOVER_ANNOTATION_FILTER, HOVER_MODIFIER_FILTER},
+    indexer::content_hash,
     models::symbol::{SymbolMetadata, SymbolParameter},
 };
 
@@ -28,6 +29,7 @@ pub fn build_hover_parts(
     }
 
     let node_kind = NodeKind::from_string(symbol_type);
+    let is_type_alias = node_kind == Some(NodeKind::TypeAlias);
     let modifiers_str = modifiers
         .iter()
         .cloned()
@@ -108,12 +110,18 @@ pub fn build_hover_parts(
 
     if file_type == "kotlin".to_string() {
         if let Some(ret) = &metadata.return_type {
-            signature_line.push_str(": ");
+            signature_line.push_str(if is_type_alias { " = " } else { ": " });
             signature_line.push_str(ret);
             signature_line.push(' ');
         }
     }
 
+    if let Some(value) = &metadata.constant_value {
+        signature_line.truncate(signature_line.trim_end().len());
+        signature_line.push_str(" = ");
+        signature_line.push_str(value);
+    }
+
     parts.push(signature_line);
 
     if metadata.documentation.is_some() {
@@ -129,3 +137,70 @@ pub fn build_hover_parts(
 
     parts
 }
+
+/// The canonical reference to a symbol: its FQN, with the parameter types of a
+/// method appended, e.g. `com.foo.Bar#baz(String,int)`.  Parameter types are the
+/// declared simple names without generic arguments.
+pub fn build_reference(
+    fully_qualified_name: &str,
+    symbol_type: &str,
+    metadata: &SymbolMetadata,
+) -> String {
+    if NodeKind::from_string(symbol_type) != Some(NodeKind::Function) {
+        return fully_qualified_name.to_string();
+    }
+    let params = metadata
+        .parameters
+        .iter()
+        .flatten()
+        .map(|p| {
+            p.type_name
+                .as_deref()
+                .map(reference_type)
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    format!("{fully_qualified_name}({params})")
+}
+
+/// Hex digits of a symbol key.
+const SYMBOL_KEY_LENGTH: usize = 16;
+
+/// The stable key of a symbol, stored with it in the index: a hash of its
+/// canonical reference (see [`build_reference`]), so overloads sharing an FQN get
+/// different keys, and a symbol keeps its key across re-indexing as long as its
+/// name and parameters do not change.
+pub fn symbol_key(
+    fully_qualified_name: &str,
+    symbol_type: &str,
+    metadata: &SymbolMetadata,
+) -> String {
+    let mut key =
+        content_hash(build_reference(fully_qualified_name, symbol_type, metadata).as_bytes());
+    key.truncate(SYMBOL_KEY_LENGTH);
+    key
+}
+
+/// `java.util.List<String>` → `List`, `String...` stays as written.
+fn reference_type(type_name: &str) -> String {
+    let mut base = String::with_capacity(type_name.len());
+    let mut depth = 0;
+    for ch in type_name.chars() {
+        match ch {
+            '<' => depth += 1,
+            '>' if depth > 0 => depth -= 1,
+            c if depth == 0 && !c.is_whitespace() => base.push(c),
+            _ => {}
+        }
+    }
+    match base.rfind('.') {
+        // Keep varargs' `...`, drop the package.
+        Some(_) if base.ends_with("...") => {
+            let stem = &base[..base.len() - 3];
+            format!("{}...", stem.rsplit('.').next().unwrap_or(stem))
+        }
+        Some(i) => base[i + 1..].to_string(),
+        None => base,
+    }
+}
@@ -0,0 +1,234 @@
+//! Source files moved behind the server's back.
+//!
+//! A file moved outside `workspace/willRenameFiles` — by a shell, a VCS checkout
+//! or a client without file operations — reaches `workspace/didChangeWatchedFiles`
+//! as its deletion and the creation of a file of the same name elsewhere.  Such a
+//! pair is taken for a move: the index entries of the old path are re-keyed to the
+//! new one, so definitions stop pointing at a file that is gone, and the new path
+//! is re-indexed for whatever the move changed in it.
+//!
+//! Declarations a file stops declaring break the imports naming them.  The open
+//! documents importing them have their diagnostics re-published, and an import
+//! of a type the project no longer declares there but declares elsewhere is
+//! reported as `unresolved_import`, with a [`QuickFix`] to the new location when
+//! there is only one.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use lsp_core::lsp_error;
+use tower_lsp::lsp_types::{
+    Diagnostic, DiagnosticSeverity, FileChangeType, FileEvent, NumberOrString, Position, Range,
+    TextEdit, Url,
+};
+
+use crate::{
+    code_action::QuickFix, context::import_lines, models::symbol::Symbol, server::Backend,
+};
+
+pub(crate) const UNRESOLVED_IMPORT_CODE: &str = "unresolved_import";
+
+/// The moves among `deleted` and `created` paths: a deleted file paired with the
+/// created one of the same name.  Names deleted or created more than once are left
+/// alone, there being no telling which went where.
+pub fn paired_moves(deleted: &[PathBuf], created: &[PathBuf]) -> Vec<(PathBuf, PathBuf)> {
+    fn by_name(paths: &[PathBuf]) -> HashMap<&std::ffi::OsStr, Vec<&PathBuf>> {
+        let mut names: HashMap<_, Vec<_>> = HashMap::new();
+        for path in paths {
+            if let Some(name) = path.file_name() {
+                names.entry(name).or_default().push(path);
+            }
+        }
+        names
+    }
+
+    let created = by_name(created);
+    by_name(deleted)
+        .into_iter()
+        .filter_map(
+            |(name, from)| match (from.as_slice(), created.get(name)?.as_slice()) {
+                ([from], [to]) if from != to => Some(((*from).clone(), (*to).clone())),
+                _ => None,
+            },
+        )
+        .collect()
+}
+
+/// The fully qualified names of `before` missing from `after`.
+pub(crate) fn removed_fqns(before: &[Symbol], after: &[Symbol]) -> Vec<String> {
+    let after: HashSet<&str> = after
+        .iter()
+        .map(|s| s.fully_qualified_name.as_str())
+        .collect();
+    before
+        .iter()
+        .map(|s| s.fully_qualified_name.as_str())
+        .filter(|fqn| !after.contains(fqn))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Whether the import of `path` names `fqn` or something declared in it.
+fn imports(path: &str, fqn: &str) -> bool {
+    path.strip_prefix(fqn)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+}
+
+/// The first two segments of `fqn`, e.g. `com.acme`: a type moving within the
+/// project keeps them, while a library import the index knows nothing of seldom
+/// shares them with a project type.
+fn root_package(fqn: &str) -> Option<&str> {
+    let second_dot = fqn.match_indices('.').nth(1)?.0;
+    Some(&fqn[..second_dot])
+}
+
+impl Backend {
+    fn is_source_file(&self, path: &Path) -> bool {
+        path.extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|ext| self.languages.contains_key(ext))
+    }
+
+    /// Re-keys the index entries of the source files `changes` report moved (see
+    /// [`paired_moves`]) and queues their new paths for re-indexing.  Returns the
+    /// paths of the events handled.
+    pub(crate) async fn move_source_files(&self, changes: &[FileEvent]) -> HashSet<PathBuf> {
+        let mut handled = HashSet::new();
+        if self.is_index_reader() {
+            return handled;
+        }
+        let Some(repo) = self.repo.get() else {
+            return handled;
+        };
+        let paths = |typ: FileChangeType| -> Vec<PathBuf> {
+            changes
+                .iter()
+                .filter(|c| c.typ == typ)
+                .filter_map(|c| c.uri.to_file_path().ok())
+                .filter(|p| self.is_source_file(p))
+                .collect()
+        };
+
+        let moves = paired_moves(
+            &paths(FileChangeType::DELETED),
+            &paths(FileChangeType::CREATED),
+        );
+        for (from, to) in moves {
+            if let Err(e) = repo
+                .move_file(&from.to_string_lossy(), &to.to_string_lossy())
+                .await
+            {
+                lsp_error!(
+                    "Failed to move the index entries of {} to {}: {e}",
+                    from.display(),
+                    to.display()
+                );
+                continue;
+            }
+            if let Ok(uri) = Url::from_file_path(&from) {
+                self.documents.remove(&uri.to_string());
+            }
+            self.workspace_diagnostics.file_removed(&from);
+            let _ = self.debounce_tx.send(to.clone()).await;
+            handled.insert(from);
+            handled.insert(to);
+        }
+        handled
+    }
+
+    /// Queues diagnostics for the open documents importing one of `fqns`, which are
+    /// no longer declared where they were.
+    pub(crate) fn republish_importers(&self, fqns: &[String]) {
+        if fqns.is_empty() {
+            return;
+        }
+        for entry in self.documents.iter() {
+            let broken = import_lines(&entry.value().0)
+                .iter()
+                .any(|import| fqns.iter().any(|fqn| imports(import.path, fqn)));
+            if broken && let Ok(uri) = Url::parse(entry.key()) {
+                let _ = self.diag_debounce_tx.try_send(uri);
+            }
+        }
+    }
+
+    async fn is_declared(&self, fqn: &str) -> bool {
+        let Some(repo) = self.repo.get() else {
+            return false;
+        };
+        matches!(repo.find_symbol_by_fqn(fqn).await, Ok(Some(_)))
+            || matches!(repo.find_external_symbol_by_fqn(fqn).await, Ok(Some(_)))
+    }
+
+    /// `unresolved_import` diagnostics for the imports of `content` naming a type
+    /// that is declared nowhere under that name, but elsewhere in the project under
+    /// the same root package.
+    pub(crate) async fn unresolved_import_diagnostics(&self, content: &str) -> Vec<Diagnostic> {
+        let Some(repo) = self.repo.get() else {
+            return vec![];
+        };
+        let mut diagnostics = Vec::new();
+        for import in import_lines(content) {
+            if import.path.ends_with(".*") {
+                continue;
+            }
+            // A static import names a member of the type before it.
+            let type_path = if import.is_static {
+                match import.path.rsplit_once('.') {
+                    Some((owner, _)) => owner,
+                    None => continue,
+                }
+            } else {
+                import.path
+            };
+            let Some((owner, name)) = type_path.rsplit_once('.') else {
+                continue;
+            };
+            // An owner that is declared is a type, `type_path` being nested in it.
+            if self.is_declared(type_path).await || self.is_declared(owner).await {
+                continue;
+            }
+            let mut candidates: Vec<String> = repo
+                .find_types_by_short_name(name)
+                .await
+                .unwrap_or_default()
+                .into_iter()
+                .map(|s| s.fully_qualified_name.replace('#', "."))
+                .filter(|fqn| root_package(fqn) == root_package(type_path))
+                .collect();
+            candidates.sort();
+            candidates.dedup();
+            if candidates.is_empty() {
+                continue;
+            }
+
+            let range = Range::new(
+                Position::new(import.line as u32, import.column as u32),
+                Position::new(import.line as u32, (import.column + type_path.len()) as u32),
+            );
+            let data = match candidates.as_slice() {
+                [fqn] => QuickFix::new(
+                    format!("Import '{fqn}'"),
+                    vec![TextEdit::new(range, fqn.clone())],
+                )
+                .to_data(),
+                _ => None,
+            };
+            diagnostics.push(Diagnostic {
+                range,
+                severity: Some(DiagnosticSeverity::ERROR),
+                code: Some(NumberOrString::String(UNRESOLVED_IMPORT_CODE.to_string())),
+                source: Some("lspintar".to_string()),
+                message: format!(
+                    "Cannot resolve import '{type_path}': '{name}' is declared as {}",
+                    candidates.join(", ")
+                ),
+                data,
+                ..Default::default()
+            });
+        }
+        diagnostics
+    }
+}
@@ -0,0 +1,105 @@
+//! Nullability of Java declarations, from their annotations.
+//!
+//! Java has no nullable types, so libraries annotate what may be null: JetBrains'
+//! `@Nullable`/`@NotNull`, JSR-305's `@CheckForNull`/`@Nonnull`, the Checker
+//! Framework's `@NullableDecl`/`@NonNullDecl`, and the `@Nullable`/`@NonNull` of
+//! JSpecify, Android and Lombok.  Only simple names are indexed, so all vendors
+//! read alike.  A method or field carrying neither kind is a platform type: Kotlin
+//! sees it as `String!` and trusts the caller to know.
+//!
+//! Hover on such a Java declaration says which it is and, when asked from a Kotlin
+//! file, shows the type as Kotlin sees it.
+
+use lsp_core::node_kind::NodeKind;
+use tower_lsp::lsp_types::{Hover, HoverContents, TextDocumentPositionParams};
+
+use crate::{enums::ResolvedSymbol, lsp_convert::AsLspHover, models::symbol::SymbolMetadata};
+
+/// Simple names of the annotations that mark a Java return value as possibly null
+/// (JetBrains, JSR-305, JSpecify, Checker Framework, Android, FindBugs).
+pub(crate) const NULLABLE_ANNOTATIONS: &[&str] = &["Nullable", "CheckForNull", "NullableDecl"];
+
+/// Simple names of the annotations that mark a Java return value as never null.
+pub(crate) const NON_NULL_ANNOTATIONS: &[&str] = &["NotNull", "NonNull", "Nonnull", "NonNullDecl"];
+
+/// Java types that cannot hold null.
+const PRIMITIVE_TYPES: &[&str] = &[
+    "boolean", "byte", "char", "short", "int", "long", "float", "double", "void",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Nullability {
+    /// Annotated as possibly null; the annotation's simple name.
+    Nullable(String),
+    /// Annotated as never null; the annotation's simple name.
+    NonNull(String),
+    /// Not annotated either way.
+    Platform,
+}
+
+impl Nullability {
+    /// How Kotlin sees a Java value of type `java_type` with this nullability.
+    pub(crate) fn kotlin_type(&self, java_type: &str) -> String {
+        match self {
+            Nullability::Nullable(_) => format!("{java_type}?"),
+            Nullability::NonNull(_) => java_type.to_string(),
+            Nullability::Platform => format!("{java_type}!"),
+        }
+    }
+}
+
+/// The nullability the annotations in `metadata` declare.  A declaration carrying
+/// both kinds is taken as nullable, the safer reading.
+pub(crate) fn nullability(metadata: &SymbolMetadata) -> Nullability {
+    let annotations = metadata.annotations.iter().flatten();
+    let mut non_null = None;
+    for annotation in annotations {
+        if NULLABLE_ANNOTATIONS.contains(&annotation.as_str()) {
+            return Nullability::Nullable(annotation.clone());
+        }
+        if NON_NULL_ANNOTATIONS.contains(&annotation.as_str()) {
+            non_null.get_or_insert_with(|| annotation.clone());
+        }
+    }
+    non_null.map_or(Nullability::Platform, Nullability::NonNull)
+}
+
+/// Hover for a Java method or non-constant field of a reference type: the declaration, then its
+/// nullability, and the Kotlin view of its type when hovered from Kotlin.  `None`
+/// for anything else.
+pub(crate) fn nullability_hover(
+    params: &TextDocumentPositionParams,
+    symbol: &ResolvedSymbol,
+) -> Option<Hover> {
+    if symbol.file_type()? != "java"
+        || !matches!(symbol.node_kind(), NodeKind::Function | NodeKind::Field)
+    {
+        return None;
+    }
+    let metadata = symbol.metadata()?;
+    let java_type = metadata.return_type.as_deref()?;
+    // A constant's value is right there in the hover.
+    if PRIMITIVE_TYPES.contains(&java_type) || metadata.constant_value.is_some() {
+        return None;
+    }
+
+    let nullability = nullability(metadata);
+    let summary = match &nullability {
+        Nullability::Nullable(annotation) => format!("**Nullable** (`@{annotation}`)"),
+        Nullability::NonNull(annotation) => format!("**Not null** (`@{annotation}`)"),
+        Nullability::Platform => "**Platform type**: no nullability annotation".to_string(),
+    };
+    let mut hover = symbol.as_lsp_hover()?;
+    let HoverContents::Markup(markup) = &mut hover.contents else {
+        return None;
+    };
+    markup.value.push_str("\n\n");
+    markup.value.push_str(&summary);
+    if params.text_document.uri.path().ends_with(".kt") {
+        markup.value.push_str(&format!(
+            "\n\n```kotlin\n{}\n```",
+            nullability.kotlin_type(java_type)
+        ));
+    }
+    Some(hover)
+}
@@ -0,0 +1,325 @@
+//! `textDocument/onTypeFormatting` on `}` and newline.
+//!
+//! Typing the `}` closing a block re-indents the block: each line one level below
+//! the line opening the innermost bracket it is in, and the `}` at the level of
+//! the line with its `{`.  Following the opening line rather than counting
+//! brackets keeps `list.each { item ->` or `foo(bar {` bodies one level deep.  A
+//! newline indents the new line the same way, and re-indents the block a `}`
+//! ending the previous line closed.
+//!
+//! Statements under a `case x:` label, lines leading with `.`, `?.`, `?:`, `&&`
+//! or `||`, and lines continuing one ending with `=`, `->`, `&&`, `||` or `?:` go
+//! one level further.  Multi-line strings are left as they are, and the level
+//! is a tab or `tabSize` spaces as the client's formatting options say.
+
+use std::{collections::HashMap, ops::RangeInclusive, path::PathBuf};
+
+use tower_lsp::{
+    jsonrpc::Result,
+    lsp_types::{DocumentOnTypeFormattingParams, FormattingOptions, Position, Range, TextEdit},
+};
+
+use crate::server::Backend;
+
+/// Leading tokens continuing the statement of the line before.
+const LEADING_CONTINUATIONS: &[&str] = &["?.", "?:", "&&", "||", "."];
+
+/// Trailing tokens continuing a statement on the line after.
+const TRAILING_CONTINUATIONS: &[&str] = &["=", "->", "&&", "||", "?:"];
+
+/// Where a line starts relative to the brackets, strings and comments before it.
+#[derive(Debug, Default, Clone)]
+struct LineState {
+    /// The lines of the brackets open at the start of the line, innermost last.
+    open: Vec<usize>,
+    /// Whether the line starts inside a multi-line string.
+    in_string: bool,
+    /// Whether the line starts inside a block comment.
+    in_comment: bool,
+}
+
+struct Scan {
+    lines: Vec<LineState>,
+    /// The line of the `{` each `}` closes, by the line and byte column of the `}`.
+    closers: HashMap<(usize, usize), usize>,
+}
+
+/// The length of `text` up to and including the first unescaped `delimiter`.
+fn find_closing(text: &str, delimiter: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (i, c) in text.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if text[i..].starts_with(delimiter) {
+            return Some(i + delimiter.len());
+        }
+    }
+    None
+}
+
+/// Tracks brackets through `content`, skipping comments and string and character
+/// literals.  Mismatched brackets close the innermost open one.
+fn scan(content: &str) -> Scan {
+    let mut lines = Vec::new();
+    let mut closers = HashMap::new();
+    let mut open: Vec<usize> = Vec::new();
+    let mut string: Option<&'static str> = None;
+    let mut in_comment = false;
+    for (row, line) in content.split('\n').enumerate() {
+        lines.push(LineState {
+            open: open.clone(),
+            in_string: string.is_some(),
+            in_comment,
+        });
+        let mut i = 0;
+        while let Some(c) = line[i..].chars().next() {
+            let rest = &line[i..];
+            if in_comment {
+                let Some(end) = rest.find("*/") else { break };
+                in_comment = false;
+                i += end + 2;
+                continue;
+            }
+            if let Some(delimiter) = string {
+                let Some(end) = find_closing(rest, delimiter) else {
+                    break;
+                };
+                string = None;
+                i += end;
+                continue;
+            }
+            if rest.starts_with("//") {
+                break;
+            }
+            if rest.starts_with("/*") {
+                in_comment = true;
+                i += 2;
+                continue;
+            }
+            if let Some(delimiter) = ["\"\"\"", "'''"].into_iter().find(|d| rest.starts_with(d)) {
+                string = Some(delimiter);
+                i += delimiter.len();
+                continue;
+            }
+            match c {
+                '"' | '\'' => {
+                    let delimiter = if c == '"' { "\"" } else { "'" };
+                    let Some(end) = find_closing(&rest[1..], delimiter) else {
+                        break;
+                    };
+                    i += 1 + end;
+                    continue;
+                }
+                '{' | '(' | '[' => open.push(row),
+                '}' | ')' | ']' => {
+                    if let Some(opener) = open.pop()
+                        && c == '}'
+                    {
+                        closers.insert((row, i), opener);
+                    }
+                }
+                _ => {}
+            }
+            i += c.len_utf8();
+        }
+    }
+    Scan { lines, closers }
+}
+
+fn leading_whitespace(line: &str) -> &str {
+    &line[..line.len() - line.trim_start().len()]
+}
+
+/// `line` without a trailing `//` comment or whitespace.  Approximate: a `//` in a
+/// string cuts it short.
+fn code_of(line: &str) -> &str {
+    line.split("//").next().unwrap_or(line).trim()
+}
+
+fn is_case_label(code: &str) -> bool {
+    (code.starts_with("case ") || code.starts_with("default")) && code.ends_with(':')
+}
+
+/// The edits re-indenting `rows` of `content` one `unit` per level.  Blank lines
+/// are left alone unless `blank_row` is one of them.
+fn reindent(
+    content: &str,
+    scan: &Scan,
+    rows: RangeInclusive<usize>,
+    unit: &str,
+    blank_row: Option<usize>,
+) -> Vec<TextEdit> {
+    let lines: Vec<&str> = content.split('\n').collect();
+    let mut indents: HashMap<usize, String> = HashMap::new();
+    // Per bracket line, whether a `case x:` label came last in its block.
+    let mut under_label: HashMap<usize, bool> = HashMap::new();
+    let mut previous: Option<usize> = None;
+    let mut edits = Vec::new();
+
+    for (row, line) in lines.iter().enumerate().take(rows.end() + 1) {
+        let state = &scan.lines[row];
+        let code = code_of(line);
+        if state.in_string || (line.trim().is_empty() && blank_row != Some(row)) {
+            continue;
+        }
+        let indent_of = |row: usize| {
+            indents
+                .get(&row)
+                .cloned()
+                .unwrap_or_else(|| leading_whitespace(lines[row]).to_string())
+        };
+
+        let label = !state.in_comment && is_case_label(code);
+        let indent = match state.open.last() {
+            _ if state.in_comment => {
+                let base = state
+                    .open
+                    .last()
+                    .map(|opener| indent_of(*opener) + unit)
+                    .unwrap_or_default();
+                if code.starts_with('*') {
+                    base + " "
+                } else {
+                    base
+                }
+            }
+            Some(opener) if code.starts_with(['}', ')', ']']) => indent_of(*opener),
+            Some(opener) => {
+                let mut indent = indent_of(*opener) + unit;
+                let labelled = under_label.get(opener).copied().unwrap_or(false);
+                let continues = LEADING_CONTINUATIONS.iter().any(|t| code.starts_with(t))
+                    || previous.is_some_and(|p| {
+                        scan.lines[p].open == state.open
+                            && !scan.lines[p].in_comment
+                            && TRAILING_CONTINUATIONS
+                                .iter()
+                                .any(|t| code_of(lines[p]).ends_with(t))
+                    });
+                if labelled && !label {
+                    indent.push_str(unit);
+                }
+                if continues {
+                    indent.push_str(unit);
+                }
+                indent
+            }
+            None => String::new(),
+        };
+        if let Some(opener) = state.open.last()
+            && !state.in_comment
+        {
+            under_label.insert(*opener, label || under_label.get(opener) == Some(&true));
+        }
+        if !code.is_empty() {
+            previous = Some(row);
+        }
+
+        let current = leading_whitespace(line);
+        if rows.contains(&row) && indent != current {
+            edits.push(TextEdit::new(
+                Range::new(
+                    Position::new(row as u32, 0),
+                    Position::new(row as u32, current.chars().count() as u32),
+                ),
+                indent.clone(),
+            ));
+        }
+        if rows.contains(&row) {
+            indents.insert(row, indent);
+        }
+    }
+    edits
+}
+
+/// The byte offset in `line` of the last `}` before `character` (UTF-16).
+fn closing_brace(line: &str, character: u32) -> Option<usize> {
+    let mut units = 0;
+    let end = line
+        .char_indices()
+        .find(|(_, c)| {
+            let past = units >= character as usize;
+            units += c.len_utf16();
+            past
+        })
+        .map_or(line.len(), |(i, _)| i);
+    line[..end].rfind('}')
+}
+
+/// The edits fixing indentation after `ch` was typed at `position` of `content`:
+/// the block a `}` closed, or the line a newline opened.
+pub fn on_type_edits(
+    content: &str,
+    position: Position,
+    ch: &str,
+    options: &FormattingOptions,
+) -> Vec<TextEdit> {
+    let unit = if options.insert_spaces {
+        " ".repeat(options.tab_size as usize)
+    } else {
+        "\t".to_string()
+    };
+    let scan = scan(content);
+    let lines: Vec<&str> = content.split('\n').collect();
+    let row = position.line as usize;
+    let Some(line) = lines.get(row) else {
+        return vec![];
+    };
+
+    // The block closed by the `}` at `column` of `row`, re-indented.
+    let closed_block = |row: usize, column: usize| -> Vec<TextEdit> {
+        match scan.closers.get(&(row, column)) {
+            Some(opener) if *opener < row => {
+                reindent(content, &scan, opener + 1..=row, &unit, None)
+            }
+            _ => vec![],
+        }
+    };
+    match ch {
+        "}" => closing_brace(line, position.character)
+            .map(|column| closed_block(row, column))
+            .unwrap_or_default(),
+        "\n" => {
+            let mut edits = match row.checked_sub(1) {
+                Some(above) if code_of(lines[above]).ends_with('}') => {
+                    let column = closing_brace(lines[above], u32::MAX);
+                    column
+                        .map(|column| closed_block(above, column))
+                        .unwrap_or_default()
+                }
+                _ => vec![],
+            };
+            edits.extend(reindent(content, &scan, row..=row, &unit, Some(row)));
+            edits
+        }
+        _ => vec![],
+    }
+}
+
+impl Backend {
+    pub(crate) async fn on_type_formatting_impl(
+        &self,
+        params: DocumentOnTypeFormattingParams,
+    ) -> Result<Option<Vec<TextEdit>>> {
+        let uri = &params.text_document_position.text_document.uri;
+        let path = PathBuf::from(uri.path());
+        let supported = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|ext| self.languages.contains_key(ext));
+        if !supported {
+            return Ok(None);
+        }
+        let Some(content) = self.document_text(uri) else {
+            return Ok(None);
+        };
+        let edits = on_type_edits(
+            &content,
+            params.text_document_position.position,
+            &params.ch,
+            &params.options,
+        );
+        Ok((!edits.is_empty()).then_some(edits))
+    }
+}
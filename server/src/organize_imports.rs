@@ -0,0 +1,126 @@
+//! Organize imports: drops the unused imports of a file and sorts the rest.
+//!
+//! The same edit is offered as the `source.organizeImports` code action and
+//! returned by the `lspintar.organizeImports` command, which clients can run from
+//! a save hook without going through code action selection.  Imports are unused
+//! as the `unused_import` warning has them (see [`crate::unused`]), suppressions
+//! included.  The kept ones are sorted by path, static imports in a group of their
+//! own after the others, and duplicates are dropped.  A file with anything but
+//! blank lines between its imports, such as a commented-out import, is left alone
+//! so that nothing is lost.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
+
+use serde_json::Value;
+use tower_lsp::{
+    jsonrpc::{Error, Result},
+    lsp_types::{
+        CodeAction, CodeActionKind, CodeActionOrCommand, Position, Range, TextDocumentIdentifier,
+        TextEdit, Url, WorkspaceEdit,
+    },
+};
+
+use crate::{
+    context::import_lines, server::Backend, suppression::Suppressions, unused::unused_imports,
+};
+
+/// The edit rewriting the import block of `content` with the imports on
+/// `unused_lines` dropped and the others sorted.  `None` when that changes nothing.
+fn organized_imports(content: &str, unused_lines: &HashSet<u32>) -> Option<TextEdit> {
+    let imports = import_lines(content);
+    let first = imports.first()?.line;
+    let last = imports.last()?.line;
+    let lines: Vec<&str> = content.lines().collect();
+    let import_at = |line: usize| imports.iter().find(|i| i.line == line);
+    if (first..=last).any(|line| import_at(line).is_none() && !lines[line].trim().is_empty()) {
+        return None;
+    }
+
+    let mut kept: Vec<(bool, &str, &str)> = imports
+        .iter()
+        .filter(|i| !unused_lines.contains(&(i.line as u32)))
+        .map(|i| (i.is_static, i.path, lines[i.line].trim_end()))
+        .collect();
+    kept.sort();
+    kept.dedup_by(|a, b| a.2 == b.2);
+
+    let (statics, others): (Vec<_>, Vec<_>) = kept.into_iter().partition(|(s, _, _)| *s);
+    let groups: Vec<String> = [others, statics]
+        .into_iter()
+        .filter(|group| !group.is_empty())
+        .map(|group| {
+            group
+                .iter()
+                .map(|(_, _, text)| *text)
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .collect();
+    let new_text = groups.join("\n\n");
+
+    let current = lines[first..=last].join("\n");
+    if new_text == current {
+        return None;
+    }
+    // With no import left, the line break after the block goes too.
+    let end = if new_text.is_empty() {
+        Position::new(last as u32 + 1, 0)
+    } else {
+        Position::new(last as u32, lines[last].len() as u32)
+    };
+    Some(TextEdit {
+        range: Range::new(Position::new(first as u32, 0), end),
+        new_text,
+    })
+}
+
+impl Backend {
+    /// The edit organizing the imports of `uri`, or `None` when they are organized
+    /// already.
+    pub(crate) fn organize_imports_edit(&self, uri: &Url) -> Option<WorkspaceEdit> {
+        let path = PathBuf::from(uri.path());
+        let lang = self.languages.get(path.extension()?.to_str()?)?;
+        let content = self.document_text(uri)?;
+        let (tree, _) = lang.parse_str(&content)?;
+        let suppressions = Suppressions::parse(&tree, &content, lang.as_ref());
+        let unused_lines: HashSet<u32> = unused_imports(&content, lang.as_ref())
+            .into_iter()
+            .filter(|d| !suppressions.suppresses(d))
+            .map(|d| d.range.start.line)
+            .collect();
+        let edit = organized_imports(&content, &unused_lines)?;
+        Some(WorkspaceEdit {
+            changes: Some(HashMap::from([(uri.clone(), vec![edit])])),
+            ..Default::default()
+        })
+    }
+
+    /// "Organize imports" for `uri`.  `None` when the imports are organized already.
+    pub(crate) fn organize_imports_action(&self, uri: &Url) -> Option<CodeActionOrCommand> {
+        Some(CodeActionOrCommand::CodeAction(CodeAction {
+            title: "Organize imports".to_string(),
+            kind: Some(CodeActionKind::SOURCE_ORGANIZE_IMPORTS),
+            edit: Some(self.organize_imports_edit(uri)?),
+            ..Default::default()
+        }))
+    }
+
+    /// Handles `lspintar.organizeImports`.  The single argument is the
+    /// `TextDocumentIdentifier` of the file; the result is the `WorkspaceEdit` of
+    /// the code action, or `null` when there is nothing to change.
+    pub(crate) async fn organize_imports(&self, arguments: Vec<Value>) -> Result<Option<Value>> {
+        let document: TextDocumentIdentifier = arguments
+            .into_iter()
+            .next()
+            .and_then(|arg| serde_json::from_value(arg).ok())
+            .ok_or_else(|| Error::invalid_params("Expected a text document"))?;
+
+        self.organize_imports_edit(&document.uri)
+            .map(serde_json::to_value)
+            .transpose()
+            .map_err(|_| Error::internal_error())
+    }
+}
@@ -0,0 +1,187 @@
+//! `package_mismatch`: a Java or Groovy file whose `package` declaration does not
+//! match its directory under the source root, e.g. `package com.acme.api` in
+//! `src/main/java/com/acme/web/Api.java`.
+//!
+//! The diagnostic carries a [`QuickFix`] rewriting the declaration to match the
+//! directory.  The other way round — moving the file to the directory its package
+//! names — is a file operation, offered by [`Backend::move_to_package_action`] when
+//! the client can rename files in a workspace edit.  Kotlin is left alone: its
+//! convention lets directories omit the common root package.
+
+use std::{
+    path::{Component, Path, PathBuf},
+    sync::atomic::Ordering,
+};
+
+use lsp_core::{language_support::LanguageSupport, languages::Language};
+use tower_lsp::lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOrCommand, Diagnostic, DiagnosticSeverity,
+    DocumentChangeOperation, DocumentChanges, NumberOrString, Position, Range, RenameFile,
+    ResourceOp, TextEdit, Url, WorkspaceEdit,
+};
+use tree_sitter::Tree;
+
+use crate::{
+    code_action::{QuickFix, removal_range},
+    server::Backend,
+};
+
+pub(crate) const PACKAGE_MISMATCH_CODE: &str = "package_mismatch";
+
+/// Language directories directly under a source set, e.g. `src/main/java`.
+const SOURCE_ROOT_DIRS: &[&str] = &["java", "groovy"];
+
+/// The source root `path` is under and the package its directory stands for, e.g.
+/// `(".../src/main/java", "com.acme.web")`.  `None` outside a Java or Groovy source
+/// root.
+pub(crate) fn source_root_and_package(path: &Path) -> Option<(PathBuf, String)> {
+    let components: Vec<Component> = path.components().collect();
+    let src = components.iter().rposition(|c| c.as_os_str() == "src")?;
+    let lang_dir = components.get(src + 2)?.as_os_str().to_str()?;
+    if !SOURCE_ROOT_DIRS.contains(&lang_dir) || components.len() < src + 4 {
+        return None;
+    }
+    let root: PathBuf = components[..src + 3].iter().collect();
+    let package = components[src + 3..components.len() - 1]
+        .iter()
+        .filter_map(|c| c.as_os_str().to_str())
+        .collect::<Vec<_>>()
+        .join(".");
+    Some((root, package))
+}
+
+/// The range of the package name in the file's `package` line.
+fn package_name_range(content: &str, package: &str) -> Option<Range> {
+    content.lines().enumerate().find_map(|(line, text)| {
+        let rest = text.trim_start().strip_prefix("package")?;
+        let start = text.len() - rest.len() + rest.find(package)?;
+        Some(Range::new(
+            Position::new(line as u32, start as u32),
+            Position::new(line as u32, (start + package.len()) as u32),
+        ))
+    })
+}
+
+/// The edit changing the `package` declaration of `content` from `declared` to
+/// `expected`: inserting one ending with `terminator` where there is none, and
+/// removing it for the default package.
+pub(crate) fn package_edit(
+    content: &str,
+    declared: &str,
+    expected: &str,
+    terminator: &str,
+) -> Option<TextEdit> {
+    if declared.is_empty() {
+        let at = Range::new(Position::new(0, 0), Position::new(0, 0));
+        return (!expected.is_empty())
+            .then(|| TextEdit::new(at, format!("package {expected}{terminator}\n\n")));
+    }
+    let range = package_name_range(content, declared)?;
+    if expected.is_empty() {
+        let line_len = content.lines().nth(range.start.line as usize)?.len() as u32;
+        let line = Range::new(
+            Position::new(range.start.line, 0),
+            Position::new(range.start.line, line_len),
+        );
+        Some(TextEdit::new(removal_range(content, line)?, String::new()))
+    } else {
+        Some(TextEdit::new(range, expected.to_string()))
+    }
+}
+
+/// Reports `path` when its declared package differs from its directory's.
+pub(crate) fn package_mismatch_diagnostic(
+    path: &Path,
+    tree: &Tree,
+    content: &str,
+    lang: &dyn LanguageSupport,
+) -> Option<Diagnostic> {
+    let semicolon = match lang.get_language() {
+        Language::Java => ";",
+        Language::Groovy => "",
+        Language::Kotlin => return None,
+    };
+    let (_, expected) = source_root_and_package(path)?;
+    let declared = lang.get_package_name(tree, content).unwrap_or_default();
+    if declared == expected {
+        return None;
+    }
+
+    let edit = package_edit(content, &declared, &expected, semicolon)?;
+    let (range, message) = if declared.is_empty() {
+        let message = format!("Missing package declaration: expected '{expected}'");
+        (edit.range, message)
+    } else {
+        let range = package_name_range(content, &declared)?;
+        let message = if expected.is_empty() {
+            format!("Package '{declared}' does not match the source root, which has no package")
+        } else {
+            format!("Package '{declared}' does not match the directory: expected '{expected}'")
+        };
+        (range, message)
+    };
+
+    let title = if expected.is_empty() {
+        "Remove package declaration".to_string()
+    } else {
+        format!("Set package to '{expected}'")
+    };
+    Some(Diagnostic {
+        range,
+        severity: Some(DiagnosticSeverity::WARNING),
+        code: Some(NumberOrString::String(PACKAGE_MISMATCH_CODE.to_string())),
+        source: Some("lspintar".to_string()),
+        message,
+        data: QuickFix::new(title, vec![edit]).to_data(),
+        ..Default::default()
+    })
+}
+
+impl Backend {
+    /// "Move file to package" for a `package_mismatch` diagnostic on `uri`: renames
+    /// the file into the directory its declared package stands for.  `None` when the
+    /// client cannot rename files, or the file declares no package.
+    pub(crate) fn move_to_package_action(
+        &self,
+        uri: &Url,
+        diagnostic: &Diagnostic,
+    ) -> Option<CodeActionOrCommand> {
+        if !self.resource_rename_support.load(Ordering::Acquire) {
+            return None;
+        }
+        let path = uri.to_file_path().ok()?;
+        let lang = self.languages.get(path.extension()?.to_str()?)?;
+        let content = self.document_text(uri)?;
+        let (tree, _) = lang.parse_str(&content)?;
+        let declared = lang.get_package_name(&tree, &content)?;
+        let (root, _) = source_root_and_package(&path)?;
+
+        let target = declared
+            .split('.')
+            .fold(root, |dir, segment| dir.join(segment))
+            .join(path.file_name()?);
+        if target.exists() {
+            return None;
+        }
+        let new_uri = Url::from_file_path(&target).ok()?;
+
+        Some(CodeActionOrCommand::CodeAction(CodeAction {
+            title: format!("Move file to package '{declared}'"),
+            kind: Some(CodeActionKind::QUICKFIX),
+            diagnostics: Some(vec![diagnostic.clone()]),
+            edit: Some(WorkspaceEdit {
+                document_changes: Some(DocumentChanges::Operations(vec![
+                    DocumentChangeOperation::Op(ResourceOp::Rename(RenameFile {
+                        old_uri: uri.clone(),
+                        new_uri,
+                        options: None,
+                        annotation_id: None,
+                    })),
+                ])),
+                ..Default::default()
+            }),
+            is_preferred: Some(false),
+            ..Default::default()
+        }))
+    }
+}
@@ -0,0 +1,335 @@
+//! `project_dependency_cycle`: Gradle sub-projects depending on each other in a
+//! loop, e.g. `:app` on `:core` through `implementation project(':core')` and
+//! `:core` back on `:app`.
+//!
+//! The dependencies between sub-projects are read from the `project(...)` calls
+//! of every build file in the workspace, whose sub-project is named by its
+//! directory as in `dependency_conflict`.  Gradle cannot order the builds of such
+//! projects, and neither can anything here that walks projects in dependency
+//! order, so each `project(...)` dependency closing a loop is reported as an
+//! error in its build file, with the loop it closes.  The reports are also part
+//! of `workspace/diagnostic`, which lists every build file so that a loop, once
+//! broken, is cleared from the files it was reported in.
+//!
+//! The dependencies are kept in a [`ProjectGraphCache`] until a build file is
+//! saved, created or deleted; edits to an open one update its entry.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, VecDeque},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use lsp_core::ts_helper::byte_offset_to_position;
+use tokio::sync::RwLock;
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Range, Url};
+use walkdir::WalkDir;
+
+use crate::{dependency_conflict::project_path, indexer::is_excluded, server::Backend};
+
+pub(crate) const PROJECT_CYCLE_CODE: &str = "project_dependency_cycle";
+
+const BUILD_FILES: &[&str] = &["build.gradle", "build.gradle.kts"];
+
+/// A `project(':path')` dependency in a build file.  `range` covers the path,
+/// without its quotes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectReference {
+    pub path: String,
+    pub range: Range,
+}
+
+/// Whether `configuration` only serves a project's tests, as `testImplementation`,
+/// `testFixturesApi` or `integrationTestRuntimeOnly` do.  Gradle builds the main
+/// code of both projects before either's tests, so such a dependency closes no loop.
+fn is_test_configuration(configuration: &str) -> bool {
+    configuration.starts_with("test") || configuration.contains("Test")
+}
+
+/// The sub-projects the build file `content` depends on: `project(':core')`,
+/// `project(":core")` and `project(path: ':core')` following a configuration
+/// name, as in `implementation project(':core')` or `api(project(":core"))`.  A
+/// `project(':core') { ... }` block configuring a sub-project is no dependency,
+/// and neither is one in a test configuration.
+pub fn project_references(content: &str) -> Vec<ProjectReference> {
+    let mut references = Vec::new();
+    for (call, _) in content.match_indices("project(") {
+        let line_start = content[..call].rfind('\n').map_or(0, |i| i + 1);
+        let before = content[line_start..call].trim_end();
+        let before = before.strip_suffix('(').unwrap_or(before).trim_end();
+        let after_configuration = before
+            .chars()
+            .last()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_');
+        let is_word = content[..call]
+            .chars()
+            .last()
+            .is_none_or(|c| !(c.is_alphanumeric() || c == '_' || c == '.'));
+        if !after_configuration || !is_word {
+            continue;
+        }
+        let configuration_start = before
+            .rfind(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .map_or(0, |i| i + 1);
+        if is_test_configuration(&before[configuration_start..]) {
+            continue;
+        }
+
+        let arguments = &content[call + "project(".len()..];
+        let arguments = arguments.trim_start();
+        let arguments = arguments
+            .strip_prefix("path")
+            .map(str::trim_start)
+            .and_then(|rest| rest.strip_prefix(':').or_else(|| rest.strip_prefix('=')))
+            .unwrap_or(arguments)
+            .trim_start();
+        let Some(quote) = arguments.chars().next().filter(|c| matches!(c, '"' | '\'')) else {
+            continue;
+        };
+        let Some(length) = arguments[1..].find(quote) else {
+            continue;
+        };
+        let path = &arguments[1..1 + length];
+        if !path.starts_with(':') {
+            continue;
+        }
+        let start = content.len() - arguments.len() + 1;
+        references.push(ProjectReference {
+            path: path.to_string(),
+            range: Range::new(
+                byte_offset_to_position(content, start),
+                byte_offset_to_position(content, start + path.len()),
+            ),
+        });
+    }
+    references
+}
+
+/// The loops of `graph`, which maps each project to those it depends on: its
+/// strongly connected components of more than one project, and projects depending
+/// on themselves.  Each loop is sorted.
+pub fn dependency_cycles(graph: &BTreeMap<String, BTreeSet<String>>) -> Vec<Vec<String>> {
+    let reaches = |from: &str| -> BTreeSet<String> {
+        let mut seen = BTreeSet::new();
+        let mut queue: VecDeque<&str> = VecDeque::from([from]);
+        while let Some(project) = queue.pop_front() {
+            for next in graph.get(project).into_iter().flatten() {
+                if seen.insert(next.clone()) {
+                    queue.push_back(next.as_str());
+                }
+            }
+        }
+        seen
+    };
+    let reachable: BTreeMap<&str, BTreeSet<String>> =
+        graph.keys().map(|p| (p.as_str(), reaches(p))).collect();
+
+    let mut cycles: Vec<Vec<String>> = Vec::new();
+    for (project, from_project) in &reachable {
+        let known = cycles.iter().flatten().any(|p| p.as_str() == *project);
+        if known || !from_project.contains(*project) {
+            continue;
+        }
+        let cycle: Vec<String> = from_project
+            .iter()
+            .filter(|other| {
+                reachable
+                    .get(other.as_str())
+                    .is_some_and(|back| back.contains(*project))
+            })
+            .cloned()
+            .collect();
+        cycles.push(cycle);
+    }
+    cycles
+}
+
+/// The shortest path of dependencies from `from` to `to` in `graph`, both included.
+fn dependency_path(
+    graph: &BTreeMap<String, BTreeSet<String>>,
+    from: &str,
+    to: &str,
+) -> Option<Vec<String>> {
+    let mut previous: BTreeMap<&str, &str> = BTreeMap::new();
+    let mut queue: VecDeque<&str> = VecDeque::from([from]);
+    while let Some(project) = queue.pop_front() {
+        if project == to {
+            let mut path = vec![to.to_string()];
+            let mut current = to;
+            while current != from {
+                current = *previous.get(current)?;
+                path.push(current.to_string());
+            }
+            path.reverse();
+            return Some(path);
+        }
+        for next in graph.get(project).into_iter().flatten() {
+            if next != from && !previous.contains_key(next.as_str()) {
+                previous.insert(next.as_str(), project);
+                queue.push_back(next.as_str());
+            }
+        }
+    }
+    None
+}
+
+/// A build file and the sub-projects it depends on.
+#[derive(Debug, Clone)]
+struct BuildFile {
+    /// The path of the file's sub-project.
+    project: String,
+    path: PathBuf,
+    references: Vec<ProjectReference>,
+}
+
+/// Every build file under `root`, read from `open` when it holds the file's text
+/// and from disk otherwise.
+fn read_build_files(root: &Path, open: &HashMap<PathBuf, String>) -> Vec<BuildFile> {
+    WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|e| !is_excluded(e))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && is_build_file(e.path()))
+        .filter_map(|e| {
+            let path = e.into_path();
+            let project = project_path(root, path.parent()?);
+            let content = match open.get(&path) {
+                Some(text) => text.clone(),
+                None => std::fs::read_to_string(&path).unwrap_or_default(),
+            };
+            let references = project_references(&content);
+            Some(BuildFile {
+                project,
+                path,
+                references,
+            })
+        })
+        .collect()
+}
+
+fn is_build_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| BUILD_FILES.contains(&name))
+}
+
+/// The build files of the workspace, read on first use.
+#[derive(Default)]
+pub(crate) struct ProjectGraphCache {
+    files: RwLock<Option<Arc<Vec<BuildFile>>>>,
+}
+
+impl ProjectGraphCache {
+    /// Forgets every build file, for when one is created, deleted or saved.
+    pub(crate) async fn clear(&self) {
+        *self.files.write().await = None;
+    }
+
+    /// Replaces the dependencies of the build file at `path` with those `content`
+    /// declares.
+    async fn update(&self, path: &Path, content: &str) {
+        let mut files = self.files.write().await;
+        if let Some(file) = files
+            .as_mut()
+            .and_then(|files| Arc::make_mut(files).iter_mut().find(|f| f.path == path))
+        {
+            file.references = project_references(content);
+        }
+    }
+}
+
+impl Backend {
+    /// Updates the cached dependencies of `uri` when it is a build file whose text
+    /// changed in the editor.
+    pub(crate) async fn build_file_edited(&self, uri: &Url) {
+        let Ok(path) = uri.to_file_path() else {
+            return;
+        };
+        if !is_build_file(&path) {
+            return;
+        }
+        if let Some(content) = self.document_text(uri) {
+            self.project_graph.update(&path, &content).await;
+        }
+    }
+
+    /// The build files of the workspace under `root`, walked and read off the async
+    /// runtime when not cached.
+    async fn build_files(&self, root: &Path) -> Arc<Vec<BuildFile>> {
+        if let Some(files) = self.project_graph.files.read().await.clone() {
+            return files;
+        }
+        let open: HashMap<PathBuf, String> = self
+            .documents
+            .iter()
+            .filter_map(|entry| {
+                let path = Url::parse(entry.key()).ok()?.to_file_path().ok()?;
+                is_build_file(&path).then(|| (path, entry.value().0.clone()))
+            })
+            .collect();
+        let root = root.to_path_buf();
+        let files = tokio::task::spawn_blocking(move || read_build_files(&root, &open))
+            .await
+            .unwrap_or_default();
+        let files = Arc::new(files);
+        *self.project_graph.files.write().await = Some(Arc::clone(&files));
+        files
+    }
+
+    /// The `project_dependency_cycle` errors of every build file of the workspace,
+    /// empty for those closing no loop.
+    pub(crate) async fn project_cycle_reports(&self) -> Vec<(PathBuf, Vec<Diagnostic>)> {
+        let Some(root) = self.workspace_root.read().await.clone() else {
+            return vec![];
+        };
+        let files = self.build_files(&root).await;
+        let graph: BTreeMap<String, BTreeSet<String>> = files
+            .iter()
+            .map(|file| {
+                let dependencies = file.references.iter().map(|r| r.path.clone()).collect();
+                (file.project.clone(), dependencies)
+            })
+            .collect();
+        let cycles = dependency_cycles(&graph);
+
+        files
+            .iter()
+            .map(|file| {
+                let Some(cycle) = cycles.iter().find(|c| c.contains(&file.project)) else {
+                    return (file.path.clone(), vec![]);
+                };
+                let diagnostics = file
+                    .references
+                    .iter()
+                    .filter(|r| cycle.contains(&r.path))
+                    .filter_map(|r| {
+                        let mut cycle_path = dependency_path(&graph, &r.path, &file.project)?;
+                        cycle_path.insert(0, file.project.clone());
+                        Some(Diagnostic {
+                            range: r.range,
+                            severity: Some(DiagnosticSeverity::ERROR),
+                            code: Some(NumberOrString::String(PROJECT_CYCLE_CODE.to_string())),
+                            source: Some("lspintar".to_string()),
+                            message: format!(
+                                "Project dependency cycle: {}",
+                                cycle_path.join(" -> ")
+                            ),
+                            ..Default::default()
+                        })
+                    })
+                    .collect();
+                (file.path.clone(), diagnostics)
+            })
+            .collect()
+    }
+
+    /// The `project_dependency_cycle` errors of the build file at `path`.
+    pub(crate) async fn project_cycle_diagnostics(&self, path: &Path) -> Vec<Diagnostic> {
+        self.project_cycle_reports()
+            .await
+            .into_iter()
+            .find(|(file, _)| file == path)
+            .map(|(_, diagnostics)| diagnostics)
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,66 @@
+//! Which directory is the project root.
+//!
+//! By default it is the root the client opens.  In a monorepo that is often the
+//! wrong level: the client opens `services/billing` while the Gradle build lives at
+//! the repository root, or the other way round.  [`RootSettings`] pins the root
+//! explicitly, or names marker files to look for from the client's root upwards.
+
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use serde_json::Value;
+use tracing::warn;
+
+/// Read from the `lspintar` section of the client's initialization options, e.g.
+/// `{"lspintar": {"projectRoot": "services/billing"}}` or
+/// `{"lspintar": {"rootMarkers": ["settings.gradle"]}}`.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RootSettings {
+    /// The project root, absolute or relative to the client's root.  Takes precedence
+    /// over `rootMarkers`.
+    pub project_root: Option<PathBuf>,
+    /// File or directory names marking a project root.  The nearest directory holding
+    /// one, starting at the client's root and going up, is the project root.
+    pub root_markers: Vec<String>,
+}
+
+impl RootSettings {
+    /// The settings in the `lspintar` section of `options`; the defaults when the
+    /// section is absent.
+    pub fn from_initialization_options(options: &Value) -> serde_json::Result<Self> {
+        match options.get("lspintar") {
+            Some(section) => serde_json::from_value(section.clone()),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// The project root for a client opened at `client_root`.  Falls back to
+    /// `client_root` when the configured root does not exist or no marker is found.
+    pub fn resolve(&self, client_root: &Path) -> PathBuf {
+        if let Some(root) = &self.project_root {
+            let root = client_root.join(root);
+            if root.is_dir() {
+                return root;
+            }
+            warn!(
+                "projectRoot {} is not a directory; ignoring it",
+                root.display()
+            );
+        }
+        if !self.root_markers.is_empty() {
+            let marked = client_root
+                .ancestors()
+                .find(|dir| self.root_markers.iter().any(|m| dir.join(m).exists()));
+            match marked {
+                Some(dir) => return dir.to_path_buf(),
+                None => warn!(
+                    "no root marker ({}) found above {}",
+                    self.root_markers.join(", "),
+                    client_root.display()
+                ),
+            }
+        }
+        client_root.to_path_buf()
+    }
+}
@@ -1,23 +1,84 @@
-//! `textDocument/rename` implementation.
+//! `textDocument/prepareRename` and `textDocument/rename` implementation.
 //!
-//! Rename reuses the same resolver as goto-definition, in reverse: given a
-//! declaration resolved at the cursor, every reference whose
-//! `resolve_symbol_at_position` lands on that declaration is renamed.  Scope
-//! and shadowing are therefore respected by construction.
-
-use std::{collections::HashMap, path::PathBuf, str::FromStr, sync::Arc};
+//! Rename runs the goto-definition resolver in reverse, and edits beyond the
+//! chosen symbol are annotated for the client to confirm (see [`RiskyChange`]).
+
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    str::FromStr,
+    sync::{Arc, atomic::Ordering},
+};
 
 use lsp_core::language_support::LanguageSupport;
 use tower_lsp::{
     jsonrpc::{Error, Result},
     lsp_types::{
-        OneOf, Position, Range, RenameParams, TextDocumentIdentifier, TextDocumentPositionParams,
+        AnnotatedTextEdit, ChangeAnnotation, DocumentChanges, OneOf,
+        OptionalVersionedTextDocumentIdentifier, Position, PrepareRenameResponse, Range,
+        RenameParams, TextDocumentEdit, TextDocumentIdentifier, TextDocumentPositionParams,
         TextEdit, Url, WorkspaceEdit,
     },
 };
 
 use crate::{enums::ResolvedSymbol, models::symbol::Symbol, server::Backend};
 
+/// Pending edits per file, each tagged with the risky change it belongs to (if any).
+pub(crate) type EditsPerFile = HashMap<Url, Vec<(TextEdit, Option<RiskyChange>)>>;
+
+/// Edits that go beyond the symbol the user picked and deserve a preview.  Clients
+/// that support change annotations receive them as annotated edits needing
+/// confirmation, so the rename can be previewed before it is applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RiskyChange {
+    /// Overriding or overridden declarations (and their call sites) renamed
+    /// because they share the target's signature.
+    Overrides,
+    /// Getters and setters renamed along with their field.
+    Accessors,
+    /// Class names in Spring XML bean definitions.
+    SpringXml,
+    /// Class names in Gradle build scripts.
+    BuildScript,
+}
+
+impl RiskyChange {
+    fn id(self) -> &'static str {
+        match self {
+            RiskyChange::Overrides => "rename.overrides",
+            RiskyChange::Accessors => "rename.accessors",
+            RiskyChange::SpringXml => "rename.springXml",
+            RiskyChange::BuildScript => "rename.buildScript",
+        }
+    }
+
+    fn annotation(self) -> ChangeAnnotation {
+        let (label, description) = match self {
+            RiskyChange::Overrides => (
+                "Rename methods in the type hierarchy",
+                "Declarations with the same signature in supertypes or subtypes, and their call sites",
+            ),
+            RiskyChange::Accessors => (
+                "Rename accessors",
+                "Getters and setters derived from the renamed field, and their call sites",
+            ),
+            RiskyChange::SpringXml => (
+                "Rename Spring XML references",
+                "Bean definitions in XML context files that name the class",
+            ),
+            RiskyChange::BuildScript => (
+                "Rename build script references",
+                "Gradle build scripts that name the class",
+            ),
+        };
+        ChangeAnnotation {
+            label: label.to_string(),
+            needs_confirmation: Some(true),
+            description: Some(description.to_string()),
+        }
+    }
+}
+
 impl Backend {
     /// Entry point for `textDocument/rename`.  Returns `Ok(None)` when the
     /// rename is a silent no-op (e.g. target resolves into an external JAR).
@@ -30,24 +91,7 @@ impl Backend {
         };
         let new_name = params.new_name;
 
-        let primary = match self.resolve_symbol_at_position(&tdpp).await {
-            Ok(mut syms) if !syms.is_empty() => syms.remove(0),
-            _ => {
-                // Fall back: the cursor may be on the symbol's own declaration
-                // ident range, where goto-definition has nothing to resolve.
-                // Try indexed-symbol lookup first (class/field/function
-                // declarations), then local-declaration lookup (parameters,
-                // for-each bindings, catch clauses) where the current
-                // file's tree can answer directly.
-                if let Some(sym) = self.find_declaration_at(&tdpp).await? {
-                    ResolvedSymbol::Project(sym)
-                } else if let Some(local) = self.local_at(&tdpp).await {
-                    local
-                } else {
-                    return Err(Error::invalid_params("nothing to rename at cursor"));
-                }
-            }
-        };
+        let primary = self.rename_target(&tdpp).await?;
 
         // Validate the new name against the *language of the target symbol*,
         // falling back to the source file's language.
@@ -67,6 +111,70 @@ impl Backend {
         }
     }
 
+    /// Entry point for `textDocument/prepareRename`: the identifier under the
+    /// cursor, with its current name as placeholder.  Fails up front when the
+    /// cursor is not on a renameable symbol or the symbol comes from a JAR, so
+    /// the client does not prompt for a name that `rename` would ignore.
+    pub async fn prepare_rename_impl(
+        &self,
+        params: TextDocumentPositionParams,
+    ) -> Result<Option<PrepareRenameResponse>> {
+        let Some((range, placeholder)) = self.identifier_at(&params) else {
+            return Err(Error::invalid_params("nothing to rename at cursor"));
+        };
+        match self.rename_target(&params).await? {
+            ResolvedSymbol::External(_) => Err(Error::invalid_params(
+                "symbols declared in libraries cannot be renamed",
+            )),
+            _ => Ok(Some(PrepareRenameResponse::RangeWithPlaceholder {
+                range,
+                placeholder,
+            })),
+        }
+    }
+
+    /// The declaration a rename at `tdpp` applies to.
+    async fn rename_target(&self, tdpp: &TextDocumentPositionParams) -> Result<ResolvedSymbol> {
+        match self.resolve_symbol_at_position(tdpp).await {
+            Ok(mut syms) if !syms.is_empty() => Ok(syms.remove(0)),
+            _ => {
+                // Fall back: the cursor may be on the symbol's own declaration
+                // ident range, where goto-definition has nothing to resolve.
+                // Try indexed-symbol lookup first (class/field/function
+                // declarations), then local-declaration lookup (parameters,
+                // for-each bindings, catch clauses) where the current
+                // file's tree can answer directly.
+                if let Some(sym) = self.find_declaration_at(tdpp).await? {
+                    Ok(ResolvedSymbol::Project(sym))
+                } else if let Some(local) = self.local_at(tdpp).await {
+                    Ok(local)
+                } else {
+                    Err(Error::invalid_params("nothing to rename at cursor"))
+                }
+            }
+        }
+    }
+
+    /// The range and text of the identifier under the cursor.
+    fn identifier_at(&self, tdpp: &TextDocumentPositionParams) -> Option<(Range, String)> {
+        let uri = &tdpp.text_document.uri;
+        let path = uri.to_file_path().ok()?;
+        let lang = self.languages.get(path.extension()?.to_str()?)?;
+        let content = self.document_text(uri)?;
+        let (tree, _) = lang.parse_str(&content)?;
+        let node =
+            lsp_core::ts_helper::get_node_at_position(&tree, &content, &tdpp.position)?;
+        if !node.kind().ends_with("identifier") {
+            return None;
+        }
+        let (start, end) = (node.start_position(), node.end_position());
+        let range = Range::new(
+            Position::new(start.row as u32, start.column as u32),
+            Position::new(end.row as u32, end.column as u32),
+        );
+        Some((range, node.utf8_text(content.as_bytes()).ok()?.to_string()))
+    }
+
     /// When the cursor sits on a local/parameter/catch/for-each binding's
     /// declaration identifier, treat it as a local rename seeded at that
     /// position.  Uses per-language `find_local_references` — if the language
@@ -116,7 +224,8 @@ impl Backend {
             .await
             .unwrap_or_default();
         let pos = tdpp.position;
-        for s in symbols {
+        // A facade accessor shares its property's position.
+        for s in symbols.into_iter().filter(|s| !s.is_synthetic_accessor()) {
             if (s.ident_line_start as u32) <= pos.line
                 && pos.line <= (s.ident_line_end as u32)
                 && (s.ident_char_start as u32) <= pos.character
@@ -155,6 +264,9 @@ impl Backend {
     // Project symbol dispatch
     // ----------------------------------------------------------------------
 
+    /// Renames an indexed declaration: every reference whose
+    /// `resolve_symbol_at_position` lands on it is renamed, so scope and shadowing
+    /// are respected by construction.
     async fn rename_project_symbol(
         &self,
         target: Symbol,
@@ -182,13 +294,34 @@ impl Backend {
         target: Symbol,
         new_name: &str,
     ) -> Result<Option<WorkspaceEdit>> {
+        let edits_per_file = self.type_rename_edits(target, new_name).await?;
+        Ok(Some(self.workspace_edit_from(edits_per_file)))
+    }
+
+    /// The edits renaming the type `target` to `new_name`: its declarations, the
+    /// references resolving to it or its constructors, and the class names in Spring
+    /// XML and build scripts.
+    pub(crate) async fn type_rename_edits(
+        &self,
+        target: Symbol,
+        new_name: &str,
+    ) -> Result<EditsPerFile> {
         let short_name = target.short_name.clone();
-        let target_fqns: Vec<String> = vec![target.fully_qualified_name.clone()];
+        let mut keys: Vec<String> = vec![target.key()];
+
+        // Every file declaring the type under the same key, e.g. the copies of
+        // a class in several source sets, has its declaration renamed.
+        let mut declarations = match self.repo.get() {
+            Some(repo) => repo.find_symbols_by_key(&keys[0]).await.unwrap_or_default(),
+            None => vec![],
+        };
+        if !declarations.iter().any(|d| d.file_path == target.file_path) {
+            declarations.insert(0, target.clone());
+        }
 
-        // Include the constructor FQNs (members under this class whose short
+        // Include the constructors (members under this class whose short
         // name equals the class short_name) so that `new Foo(..)` call sites
         // that happen to resolve to a constructor symbol still count.
-        let mut extra_fqns: Vec<String> = Vec::new();
         if let Some(repo) = self.repo.get() {
             if let Ok(children) = repo
                 .find_symbols_by_parent_name(&target.fully_qualified_name)
@@ -196,29 +329,56 @@ impl Backend {
             {
                 for c in children {
                     if c.short_name == short_name {
-                        extra_fqns.push(c.fully_qualified_name);
+                        keys.push(c.key());
                     }
                 }
             }
         }
 
-        let mut all_fqns = target_fqns;
-        all_fqns.extend(extra_fqns);
-
-        // Always edit the declaration itself first.
-        let mut edits_per_file: HashMap<Url, Vec<TextEdit>> = HashMap::new();
-        push_decl_edit(&mut edits_per_file, &target, new_name)?;
+        // Always edit the declarations themselves first.
+        let mut edits_per_file: EditsPerFile = HashMap::new();
+        for declaration in &declarations {
+            push_decl_edit(&mut edits_per_file, declaration, new_name, None)?;
+        }
+        let decl_file_paths: Vec<&str> =
+            declarations.iter().map(|d| d.file_path.as_str()).collect();
 
         self.collect_identity_aware_refs(
             &short_name,
-            &all_fqns,
+            &keys,
             new_name,
-            &[target.file_path.as_str()],
+            &decl_file_paths,
             &mut edits_per_file,
+            |_| None,
         )
         .await?;
 
-        Ok(Some(workspace_edit_from(edits_per_file)))
+        for (uri, range) in self
+            .spring_xml_class_usages(&target.fully_qualified_name)
+            .await
+        {
+            edits_per_file.entry(uri).or_default().push((
+                TextEdit {
+                    range,
+                    new_text: new_name.to_string(),
+                },
+                Some(RiskyChange::SpringXml),
+            ));
+        }
+        for (uri, range) in self
+            .build_script_class_usages(&target.fully_qualified_name)
+            .await
+        {
+            edits_per_file.entry(uri).or_default().push((
+                TextEdit {
+                    range,
+                    new_text: new_name.to_string(),
+                },
+                Some(RiskyChange::BuildScript),
+            ));
+        }
+
+        Ok(edits_per_file)
     }
 
     // ----------------------------------------------------------------------
@@ -232,27 +392,27 @@ impl Backend {
     ) -> Result<Option<WorkspaceEdit>> {
         let peers = self.signature_matched_hierarchy(&target).await;
         let short_name = target.short_name.clone();
-        let peer_fqns: Vec<String> = peers
-            .iter()
-            .map(|s| s.fully_qualified_name.clone())
-            .collect();
+        let peer_keys: Vec<String> = peers.iter().map(Symbol::key).collect();
         let decl_file_paths: Vec<&str> = peers.iter().map(|s| s.file_path.as_str()).collect();
 
-        let mut edits_per_file: HashMap<Url, Vec<TextEdit>> = HashMap::new();
+        let mut edits_per_file: EditsPerFile = HashMap::new();
+        let target_key = target.key();
+        let risk_of = |key: &str| (key != target_key).then_some(RiskyChange::Overrides);
         for peer in &peers {
-            push_decl_edit(&mut edits_per_file, peer, new_name)?;
+            push_decl_edit(&mut edits_per_file, peer, new_name, risk_of(&peer.key()))?;
         }
 
         self.collect_identity_aware_refs(
             &short_name,
-            &peer_fqns,
+            &peer_keys,
             new_name,
             &decl_file_paths,
             &mut edits_per_file,
+            risk_of,
         )
         .await?;
 
-        Ok(Some(workspace_edit_from(edits_per_file)))
+        Ok(Some(self.workspace_edit_from(edits_per_file)))
     }
 
     /// Walk the inheritance graph around `seed`, collecting functions that
@@ -338,7 +498,7 @@ impl Backend {
         new_name: &str,
     ) -> Result<Option<WorkspaceEdit>> {
         let short_name = target.short_name.clone();
-        let mut fqns: Vec<String> = vec![target.fully_qualified_name.clone()];
+        let mut keys: Vec<String> = vec![target.key()];
 
         // Locate accessor siblings (Groovy `getFoo`/`setFoo`/`isFoo`, or
         // Kotlin JVM-visible accessors).  Only collected for JVM languages
@@ -360,14 +520,19 @@ impl Backend {
         }
 
         for (s, _) in &accessor_syms {
-            fqns.push(s.fully_qualified_name.clone());
+            keys.push(s.key());
         }
 
-        let mut edits_per_file: HashMap<Url, Vec<TextEdit>> = HashMap::new();
-        push_decl_edit(&mut edits_per_file, &target, new_name)?;
+        let mut edits_per_file: EditsPerFile = HashMap::new();
+        push_decl_edit(&mut edits_per_file, &target, new_name, None)?;
         for (s, kind) in &accessor_syms {
             let new_accessor = rename_accessor_text(kind, new_name);
-            push_decl_edit(&mut edits_per_file, s, &new_accessor)?;
+            push_decl_edit(
+                &mut edits_per_file,
+                s,
+                &new_accessor,
+                Some(RiskyChange::Accessors),
+            )?;
         }
 
         // Identity-aware reference sweep, using the field's short name as
@@ -379,10 +544,11 @@ impl Backend {
 
         self.collect_identity_aware_refs(
             &short_name,
-            &fqns,
+            &keys,
             new_name,
             &decl_paths,
             &mut edits_per_file,
+            |_| None,
         )
         .await?;
 
@@ -392,15 +558,16 @@ impl Backend {
             let new_accessor = rename_accessor_text(kind, new_name);
             self.collect_identity_aware_refs(
                 &s.short_name,
-                std::slice::from_ref(&s.fully_qualified_name),
+                &[s.key()],
                 &new_accessor,
                 &decl_paths,
                 &mut edits_per_file,
+                |_| Some(RiskyChange::Accessors),
             )
             .await?;
         }
 
-        Ok(Some(workspace_edit_from(edits_per_file)))
+        Ok(Some(self.workspace_edit_from(edits_per_file)))
     }
 
     // ----------------------------------------------------------------------
@@ -435,17 +602,20 @@ impl Backend {
             return Ok(None);
         }
 
-        let edits: Vec<TextEdit> = ranges
+        let edits: Vec<(TextEdit, Option<RiskyChange>)> = ranges
             .into_iter()
-            .map(|range| TextEdit {
-                range,
-                new_text: new_name.to_string(),
+            .map(|range| {
+                let edit = TextEdit {
+                    range,
+                    new_text: new_name.to_string(),
+                };
+                (edit, None)
             })
             .collect();
 
-        let mut edits_per_file: HashMap<Url, Vec<TextEdit>> = HashMap::new();
+        let mut edits_per_file: EditsPerFile = HashMap::new();
         edits_per_file.insert(uri, edits);
-        Ok(Some(workspace_edit_from(edits_per_file)))
+        Ok(Some(self.workspace_edit_from(edits_per_file)))
     }
 
     // ----------------------------------------------------------------------
@@ -453,19 +623,24 @@ impl Backend {
     // ----------------------------------------------------------------------
 
     /// For every project source file, find text occurrences of `short_name`,
-    /// verify each resolves to one of `target_fqns`, and append an edit
-    /// replacing the occurrence with `new_text`.
+    /// verify each resolves to one of the symbols keyed `target_keys` (see
+    /// [`Symbol::key`]), and append an edit replacing the occurrence with
+    /// `new_text`.  Matching keys rather than FQNs leaves the references to other
+    /// overloads of a renamed method alone.
     ///
     /// `decl_file_paths` lists source files whose declaration identifier
     /// ranges are *already* in `edits_per_file`; occurrences at those ranges
-    /// are suppressed to avoid duplicates.
+    /// are suppressed to avoid duplicates.  `risk_of` tags each edit by the
+    /// key it resolved to; an occurrence matching several keys is only tagged
+    /// when all of them are risky.
     async fn collect_identity_aware_refs(
         &self,
         short_name: &str,
-        target_fqns: &[String],
+        target_keys: &[String],
         new_text: &str,
         decl_file_paths: &[&str],
-        edits_per_file: &mut HashMap<Url, Vec<TextEdit>>,
+        edits_per_file: &mut EditsPerFile,
+        risk_of: impl Fn(&str) -> Option<RiskyChange>,
     ) -> Result<()> {
         let Some(repo) = self.repo.get() else {
             return Ok(());
@@ -519,7 +694,7 @@ impl Backend {
                 }
 
                 // Identity check: does this occurrence resolve to any of the
-                // target FQNs?
+                // target symbols?
                 let tdpp = TextDocumentPositionParams {
                     text_document: TextDocumentIdentifier { uri: uri.clone() },
                     position,
@@ -528,15 +703,22 @@ impl Backend {
                     Ok(v) => v,
                     Err(_) => continue,
                 };
-                let matches = resolved.iter().any(|r| match r {
-                    ResolvedSymbol::Project(s) => {
-                        target_fqns.iter().any(|f| f == &s.fully_qualified_name)
-                    }
-                    _ => false,
-                });
-                if !matches {
+                let matched: Vec<String> = resolved
+                    .iter()
+                    .filter_map(|r| match r {
+                        ResolvedSymbol::Project(s) => Some(s.key()),
+                        _ => None,
+                    })
+                    .filter(|key| target_keys.contains(key))
+                    .collect();
+                let Some(first) = matched.first() else {
                     continue;
-                }
+                };
+                let risk = if matched.iter().any(|f| risk_of(f).is_none()) {
+                    None
+                } else {
+                    risk_of(first)
+                };
 
                 let range = Range {
                     start: position,
@@ -545,10 +727,11 @@ impl Backend {
                         character: end_column as u32,
                     },
                 };
-                edits_per_file.entry(uri.clone()).or_default().push(TextEdit {
+                let edit = TextEdit {
                     range,
                     new_text: new_text.to_string(),
-                });
+                };
+                edits_per_file.entry(uri.clone()).or_default().push((edit, risk));
             }
         }
         Ok(())
@@ -605,7 +788,7 @@ fn capitalize_first(s: &str) -> String {
 /// Returns (line_idx, start_col, end_col) for each ASCII-word-boundary
 /// occurrence of `needle` in `content`.  Columns are 0-based UTF-8 byte
 /// offsets within the line.
-fn word_boundary_occurrences(content: &str, needle: &str) -> Vec<(usize, usize, usize)> {
+pub(crate) fn word_boundary_occurrences(content: &str, needle: &str) -> Vec<(usize, usize, usize)> {
     let mut out = Vec::new();
     if needle.is_empty() {
         return out;
@@ -633,7 +816,7 @@ fn word_boundary_occurrences(content: &str, needle: &str) -> Vec<(usize, usize,
 
 /// Returns true when the byte offset `(line, col)` falls inside a comment or
 /// string literal node in `tree`.
-fn position_in_comment_or_string(tree: &tree_sitter::Tree, line: usize, col: usize) -> bool {
+pub(crate) fn position_in_comment_or_string(tree: &tree_sitter::Tree, line: usize, col: usize) -> bool {
     let pt = tree_sitter::Point { row: line, column: col };
     let Some(mut node) = tree.root_node().descendant_for_point_range(pt, pt) else {
         return false;
@@ -657,12 +840,12 @@ fn position_in_comment_or_string(tree: &tree_sitter::Tree, line: usize, col: usi
 }
 
 fn declaration_already_covered(
-    edits: Option<&Vec<TextEdit>>,
+    edits: Option<&Vec<(TextEdit, Option<RiskyChange>)>>,
     start: Position,
     end_char: u32,
 ) -> bool {
     let Some(edits) = edits else { return false };
-    edits.iter().any(|e| {
+    edits.iter().any(|(e, _)| {
         e.range.start.line == start.line
             && e.range.start.character == start.character
             && e.range.end.line == start.line
@@ -710,9 +893,10 @@ fn normalise_type(t: Option<&str>) -> Option<String> {
 // --------------------------------------------------------------------------
 
 fn push_decl_edit(
-    edits_per_file: &mut HashMap<Url, Vec<TextEdit>>,
+    edits_per_file: &mut EditsPerFile,
     sym: &Symbol,
     new_name: &str,
+    risk: Option<RiskyChange>,
 ) -> Result<()> {
     let uri = Url::from_file_path(&sym.file_path)
         .map_err(|_| Error::invalid_params(format!("bad file path: {}", sym.file_path)))?;
@@ -726,10 +910,11 @@ fn push_decl_edit(
             character: sym.ident_char_end as u32,
         },
     };
-    edits_per_file.entry(uri).or_default().push(TextEdit {
+    let edit = TextEdit {
         range,
         new_text: new_name.to_string(),
-    });
+    };
+    edits_per_file.entry(uri).or_default().push((edit, risk));
     Ok(())
 }
 
@@ -742,26 +927,80 @@ fn file_type_to_extension(file_type: &str) -> String {
     }
 }
 
-fn workspace_edit_from(edits_per_file: HashMap<Url, Vec<TextEdit>>) -> WorkspaceEdit {
-    // Dedupe edits per file: same (start, end) multiple times.
-    let mut changes: HashMap<Url, Vec<TextEdit>> = HashMap::new();
-    for (uri, mut list) in edits_per_file {
-        list.sort_by(|a, b| {
-            a.range
-                .start
-                .line
-                .cmp(&b.range.start.line)
-                .then(a.range.start.character.cmp(&b.range.start.character))
-                .then(a.range.end.line.cmp(&b.range.end.line))
-                .then(a.range.end.character.cmp(&b.range.end.character))
-        });
-        list.dedup_by(|a, b| a.range == b.range && a.new_text == b.new_text);
-        changes.insert(uri, list);
-    }
-    WorkspaceEdit {
-        changes: Some(changes),
-        document_changes: None,
-        change_annotations: None,
+/// Sorts and dedupes each file's edits.  When the same range is edited both as
+/// a plain and as a risky change, the plain edit wins.
+fn sorted_edits(
+    edits_per_file: EditsPerFile,
+) -> Vec<(Url, Vec<(TextEdit, Option<RiskyChange>)>)> {
+    let mut files: Vec<_> = edits_per_file
+        .into_iter()
+        .map(|(uri, mut list)| {
+            list.sort_by(|(a, a_risk), (b, b_risk)| {
+                a.range
+                    .start
+                    .line
+                    .cmp(&b.range.start.line)
+                    .then(a.range.start.character.cmp(&b.range.start.character))
+                    .then(a.range.end.line.cmp(&b.range.end.line))
+                    .then(a.range.end.character.cmp(&b.range.end.character))
+                    .then(a_risk.is_some().cmp(&b_risk.is_some()))
+            });
+            list.dedup_by(|(a, _), (b, _)| a.range == b.range && a.new_text == b.new_text);
+            (uri, list)
+        })
+        .collect();
+    files.sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+    files
+}
+
+impl Backend {
+    /// Builds the rename result.  Risky edits are only annotated when the client
+    /// supports change annotations; otherwise every edit goes into `changes`.
+    pub(crate) fn workspace_edit_from(&self, edits_per_file: EditsPerFile) -> WorkspaceEdit {
+        let files = sorted_edits(edits_per_file);
+        let has_risky = files
+            .iter()
+            .any(|(_, list)| list.iter().any(|(_, risk)| risk.is_some()));
+
+        if !has_risky || !self.change_annotation_support.load(Ordering::Acquire) {
+            let changes = files
+                .into_iter()
+                .map(|(uri, list)| (uri, list.into_iter().map(|(edit, _)| edit).collect()))
+                .collect();
+            return WorkspaceEdit {
+                changes: Some(changes),
+                document_changes: None,
+                change_annotations: None,
+            };
+        }
+
+        let mut annotations = HashMap::new();
+        let document_edits = files
+            .into_iter()
+            .map(|(uri, list)| TextDocumentEdit {
+                text_document: OptionalVersionedTextDocumentIdentifier { uri, version: None },
+                edits: list
+                    .into_iter()
+                    .map(|(edit, risk)| match risk {
+                        None => OneOf::Left(edit),
+                        Some(risk) => {
+                            annotations
+                                .entry(risk.id().to_string())
+                                .or_insert_with(|| risk.annotation());
+                            OneOf::Right(AnnotatedTextEdit {
+                                text_edit: edit,
+                                annotation_id: risk.id().to_string(),
+                            })
+                        }
+                    })
+                    .collect(),
+            })
+            .collect();
+        WorkspaceEdit {
+            changes: None,
+            document_changes: Some(DocumentChanges::Edits(document_edits)),
+            change_annotations: Some(annotations),
+        }
     }
 }
 
@@ -1,6 +1,17 @@
-use sqlx::{SqlitePool, sqlite::SqlitePoolOptions};
+use std::{
+    collections::HashSet,
+    path::{MAIN_SEPARATOR, Path},
+    sync::RwLock,
+};
 
-use crate::models::{external_symbol::ExternalSymbol, symbol::Symbol};
+use sqlx::{SqliteConnection, SqlitePool, sqlite::SqlitePoolOptions, types::Json};
+
+use crate::models::{
+    external_symbol::ExternalSymbol,
+    symbol::{Symbol, SymbolMetadata},
+    symbol_super_mapping::SymbolSuperMapping,
+    util::symbol_key,
+};
 
 fn capitalize_prefix(prefix: &str) -> String {
     let lower = prefix.to_lowercase();
@@ -11,13 +22,158 @@ fn capitalize_prefix(prefix: &str) -> String {
     }
 }
 
+/// `dir` as a prefix of the paths under it.
+pub fn dir_prefix(dir: &Path) -> String {
+    let dir = dir.to_string_lossy();
+    format!("{}{MAIN_SEPARATOR}", dir.trim_end_matches(MAIN_SEPARATOR))
+}
+
+/// The prefix paths under the path root `name` are stored with, e.g. `${ROOT}/`.
+pub fn path_root_prefix(name: &str) -> String {
+    format!("${{{name}}}{MAIN_SEPARATOR}")
+}
+
+/// The path roots in use, as `(stored prefix, absolute prefix)`, innermost first.
+#[derive(Debug, Default)]
+struct PathRoots(Vec<(String, String)>);
+
+impl PathRoots {
+    fn stored(&self, path: &str) -> String {
+        self.0
+            .iter()
+            .find_map(|(stored, absolute)| {
+                path.strip_prefix(absolute.as_str())
+                    .map(|rest| format!("{stored}{rest}"))
+            })
+            .unwrap_or_else(|| path.to_string())
+    }
+
+    fn absolute(&self, path: String) -> String {
+        if !path.starts_with("${") {
+            return path;
+        }
+        self.0
+            .iter()
+            .find_map(|(stored, absolute)| {
+                path.strip_prefix(stored.as_str())
+                    .map(|rest| format!("{absolute}{rest}"))
+            })
+            .unwrap_or(path)
+    }
+}
+
+/// Inserts `s` as declared in `file_path`, as stored, or updates the row for the same
+/// declaration in the same file.
+async fn insert_symbol(
+    conn: &mut SqliteConnection,
+    s: &Symbol,
+    file_path: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO symbols (short_name, package_name, fully_qualified_name, parent_name, 
+        file_path, file_type, symbol_type, modifiers, line_start, line_end, 
+        char_start, char_end, ident_line_start, ident_line_end, ident_char_start,
+        ident_char_end, metadata, last_modified, symbol_key)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        ON CONFLICT(file_path, fully_qualified_name, metadata) DO UPDATE SET
+            short_name = excluded.short_name,
+            package_name = excluded.package_name,
+            fully_qualified_name = excluded.fully_qualified_name,
+            parent_name = excluded.parent_name,
+            file_type = excluded.file_type,
+            symbol_type = excluded.symbol_type,
+            modifiers = excluded.modifiers,
+            line_end = excluded.line_end,
+            char_end = excluded.char_end,
+            ident_line_start = excluded.ident_line_start,
+            ident_line_end = excluded.ident_line_end,
+            ident_char_start = excluded.ident_char_start,
+            ident_char_end = excluded.ident_char_end,
+            metadata = excluded.metadata,
+            last_modified = excluded.last_modified,
+            symbol_key = excluded.symbol_key",
+    )
+    .bind(&s.short_name)
+    .bind(&s.package_name)
+    .bind(&s.fully_qualified_name)
+    .bind(&s.parent_name)
+    .bind(file_path)
+    .bind(&s.file_type)
+    .bind(&s.symbol_type)
+    .bind(&s.modifiers)
+    .bind(s.line_start)
+    .bind(s.line_end)
+    .bind(s.char_start)
+    .bind(s.char_end)
+    .bind(s.ident_line_start)
+    .bind(s.ident_line_end)
+    .bind(s.ident_char_start)
+    .bind(s.ident_char_end)
+    .bind(&s.metadata)
+    .bind(s.last_modified)
+    .bind(s.key())
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+/// Replaces the supertypes recorded for each symbol in `mappings` with those given.
+/// Repeated entries are recorded once.
+async fn insert_super_mappings(
+    conn: &mut SqliteConnection,
+    mappings: &[(&str, &str, Option<&str>)],
+) -> Result<(), sqlx::Error> {
+    let mut seen = HashSet::new();
+    for (symbol_fqn, _, _) in mappings {
+        if seen.insert(*symbol_fqn) {
+            sqlx::query("DELETE FROM symbol_super_mapping WHERE symbol_fqn = ?")
+                .bind(symbol_fqn)
+                .execute(&mut *conn)
+                .await?;
+        }
+    }
+
+    let mut seen = HashSet::new();
+    for mapping in mappings {
+        if !seen.insert(mapping) {
+            continue;
+        }
+        let (symbol_fqn, super_short_name, super_fqn) = mapping;
+        sqlx::query(
+            "INSERT INTO symbol_super_mapping (symbol_fqn, super_short_name, super_fqn) 
+             VALUES (?, ?, ?)",
+        )
+        .bind(symbol_fqn)
+        .bind(super_short_name)
+        .bind(super_fqn)
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
+
+/// The index.  Paths under a path root (see [`Repository::adopt_path_root`]) are
+/// stored relative to it and returned absolute.
 #[derive(Debug)]
 pub struct Repository {
     pool: SqlitePool,
+    roots: RwLock<PathRoots>,
 }
 
 impl Repository {
     pub async fn new(path: &str) -> Result<Self, sqlx::Error> {
+        let repo = Self::connect(path).await?;
+        repo.prepare_for_writing().await?;
+        Ok(repo)
+    }
+
+    /// Opens an index another instance writes.  The schema is left as the writer
+    /// migrated it, and nothing is written until [`Repository::prepare_for_writing`].
+    pub async fn new_reader(path: &str) -> Result<Self, sqlx::Error> {
+        Self::connect(path).await
+    }
+
+    async fn connect(path: &str) -> Result<Self, sqlx::Error> {
         let url = if path.starts_with("file:") || path == ":memory:" {
             format!("sqlite:{}", path)
         } else {
@@ -29,19 +185,124 @@ impl Repository {
             .connect(&url)
             .await?;
 
-        // WAL mode: readers never block on writers, so autocomplete queries
-        // run concurrently with the initial indexing writes instead of queuing behind them.
         // synchronous=NORMAL is safe with WAL and avoids the per-commit fsync overhead.
         // busy_timeout gives queries a grace period before returning SQLITE_BUSY.
-        sqlx::query("PRAGMA journal_mode=WAL").execute(&pool).await?;
         sqlx::query("PRAGMA synchronous=NORMAL").execute(&pool).await?;
         sqlx::query("PRAGMA busy_timeout=5000").execute(&pool).await?;
         // Enables index use for LIKE prefix queries. Queries use lower(prefix) for FQNs
         // and capitalize(prefix) for short names to preserve case-insensitive matching.
         sqlx::query("PRAGMA case_sensitive_like=ON").execute(&pool).await?;
 
-        sqlx::migrate!("../migrations").run(&pool).await?;
-        Ok(Self { pool })
+        Ok(Self {
+            pool,
+            roots: RwLock::default(),
+        })
+    }
+
+    /// Readies the index for this instance to write it: switches it to WAL, migrates
+    /// the schema and fills in what older versions left out.
+    pub async fn prepare_for_writing(&self) -> Result<(), sqlx::Error> {
+        // WAL mode: readers never block on writers, so autocomplete queries
+        // run concurrently with the initial indexing writes instead of queuing behind them.
+        sqlx::query("PRAGMA journal_mode=WAL")
+            .execute(&self.pool)
+            .await?;
+        sqlx::migrate!("../migrations").run(&self.pool).await?;
+        if let Err(e) = self.backfill_symbol_keys().await {
+            tracing::warn!("failed to fill in symbol keys: {e}");
+        }
+        Ok(())
+    }
+
+    /// Fills in the keys of the symbols indexed before keys were stored with them.
+    async fn backfill_symbol_keys(&self) -> Result<(), sqlx::Error> {
+        for table in ["symbols", "external_symbols"] {
+            let rows = sqlx::query_as::<_, (i64, String, String, Json<SymbolMetadata>)>(&format!(
+                "SELECT id, fully_qualified_name, symbol_type, metadata FROM {table}
+                WHERE symbol_key IS NULL"
+            ))
+            .fetch_all(&self.pool)
+            .await?;
+            if rows.is_empty() {
+                continue;
+            }
+
+            let mut tx = self.pool.begin().await?;
+            let update = format!("UPDATE {table} SET symbol_key = ? WHERE id = ?");
+            for (id, fqn, symbol_type, metadata) in rows {
+                sqlx::query(&update)
+                    .bind(symbol_key(&fqn, &symbol_type, &metadata))
+                    .bind(id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            tx.commit().await?;
+        }
+        Ok(())
+    }
+
+    /// From now on, stores the paths under `dir` relative to it, as `${name}/...`,
+    /// and returns those stored so as under `dir`.  Does not write the index; see
+    /// [`Repository::adopt_path_root`].
+    pub fn add_path_root(&self, name: &str, dir: &Path) {
+        let stored = path_root_prefix(name);
+        let mut roots = self.roots.write().unwrap();
+        roots.0.retain(|(s, _)| *s != stored);
+        roots.0.push((stored, dir_prefix(dir)));
+        roots
+            .0
+            .sort_by_key(|(_, absolute)| std::cmp::Reverse(absolute.len()));
+    }
+
+    /// Like [`Repository::add_path_root`], and records `dir` as the root `name` in
+    /// the index.  The first time `name` is recorded, paths already stored under
+    /// `dir` are made relative to it.  A root recorded before at another directory
+    /// just moves there: its paths are stored relative.
+    pub async fn adopt_path_root(&self, name: &str, dir: &Path) -> Result<(), sqlx::Error> {
+        self.add_path_root(name, dir);
+        let recorded: Option<(String,)> =
+            sqlx::query_as("SELECT path FROM path_roots WHERE name = ?")
+                .bind(name)
+                .fetch_optional(&self.pool)
+                .await?;
+        if recorded.is_none() {
+            self.relocate_paths(&[(dir_prefix(dir), path_root_prefix(name))])
+                .await?;
+        }
+        sqlx::query(
+            "INSERT INTO path_roots (name, path) VALUES (?, ?)
+             ON CONFLICT(name) DO UPDATE SET path = excluded.path",
+        )
+        .bind(name)
+        .bind(dir.to_string_lossy())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    fn stored_path(&self, path: &str) -> String {
+        self.roots.read().unwrap().stored(path)
+    }
+
+    fn absolute_path(&self, path: String) -> String {
+        self.roots.read().unwrap().absolute(path)
+    }
+
+    fn absolute_symbols(&self, mut symbols: Vec<Symbol>) -> Vec<Symbol> {
+        let roots = self.roots.read().unwrap();
+        for s in &mut symbols {
+            s.file_path = roots.absolute(std::mem::take(&mut s.file_path));
+        }
+        symbols
+    }
+
+    fn absolute_external_symbols(&self, mut symbols: Vec<ExternalSymbol>) -> Vec<ExternalSymbol> {
+        let roots = self.roots.read().unwrap();
+        for s in &mut symbols {
+            s.jar_path = roots.absolute(std::mem::take(&mut s.jar_path));
+            s.alt_jar_path = s.alt_jar_path.take().map(|p| roots.absolute(p));
+        }
+        symbols
     }
 
     pub async fn insert_symbols(&self, symbols: &[Symbol]) -> Result<(), sqlx::Error> {
@@ -51,56 +312,14 @@ impl Repository {
 
         let mut tx = self.pool.begin().await?;
 
-        let file_path = &symbols[0].file_path;
+        let file_path = self.stored_path(&symbols[0].file_path);
         sqlx::query("DELETE FROM symbols WHERE file_path = ?")
-            .bind(file_path)
+            .bind(&file_path)
             .execute(&mut *tx)
             .await?;
 
         for s in symbols {
-            sqlx::query(
-                "INSERT INTO symbols (short_name, package_name, fully_qualified_name, parent_name, 
-                file_path, file_type, symbol_type, modifiers, line_start, line_end, 
-                char_start, char_end, ident_line_start, ident_line_end, ident_char_start,
-                ident_char_end, metadata, last_modified)
-                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-                ON CONFLICT(file_path, fully_qualified_name, metadata) DO UPDATE SET
-                    short_name = excluded.short_name,
-                    package_name = excluded.package_name,
-                    fully_qualified_name = excluded.fully_qualified_name,
-                    parent_name = excluded.parent_name,
-                    file_type = excluded.file_type,
-                    symbol_type = excluded.symbol_type,
-                    modifiers = excluded.modifiers,
-                    line_end = excluded.line_end,
-                    char_end = excluded.char_end,
-                    ident_line_start = excluded.ident_line_start,
-                    ident_line_end = excluded.ident_line_end,
-                    ident_char_start = excluded.ident_char_start,
-                    ident_char_end = excluded.ident_char_end,
-                    metadata = excluded.metadata,
-                    last_modified = excluded.last_modified",
-            )
-            .bind(&s.short_name)
-            .bind(&s.package_name)
-            .bind(&s.fully_qualified_name)
-            .bind(&s.parent_name)
-            .bind(&s.file_path)
-            .bind(&s.file_type)
-            .bind(&s.symbol_type)
-            .bind(&s.modifiers)
-            .bind(s.line_start)
-            .bind(s.line_end)
-            .bind(s.char_start)
-            .bind(s.char_end)
-            .bind(s.ident_line_start)
-            .bind(s.ident_line_end)
-            .bind(s.ident_char_start)
-            .bind(s.ident_char_end)
-            .bind(&s.metadata)
-            .bind(s.last_modified)
-            .execute(&mut *tx)
-            .await?;
+            insert_symbol(&mut tx, s, &file_path).await?;
         }
         tx.commit().await?;
         Ok(())
@@ -109,10 +328,12 @@ impl Repository {
     #[tracing::instrument(skip(self))]
     pub async fn find_symbol_by_fqn(&self, fqn: &str) -> Result<Option<Symbol>, sqlx::Error> {
         tracing::info!("find_symbol_by_fqn");
-        sqlx::query_as::<_, Symbol>("SELECT * FROM symbols WHERE fully_qualified_name = ?")
-            .bind(fqn)
-            .fetch_optional(&self.pool)
-            .await
+        let symbol =
+            sqlx::query_as::<_, Symbol>("SELECT * FROM symbols WHERE fully_qualified_name = ?")
+                .bind(fqn)
+                .fetch_optional(&self.pool)
+                .await?;
+        Ok(self.absolute_symbols(symbol.into_iter().collect()).pop())
     }
 
     #[tracing::instrument(skip(self))]
@@ -121,10 +342,11 @@ impl Repository {
         parent_fqn: &str,
     ) -> Result<Vec<Symbol>, sqlx::Error> {
         tracing::info!("find_symbols_by_parent_name");
-        sqlx::query_as::<_, Symbol>("SELECT * FROM symbols WHERE parent_name = ?")
+        let symbols = sqlx::query_as::<_, Symbol>("SELECT * FROM symbols WHERE parent_name = ?")
             .bind(parent_fqn)
             .fetch_all(&self.pool)
-            .await
+            .await?;
+        Ok(self.absolute_symbols(symbols))
     }
 
     #[tracing::instrument(skip(self))]
@@ -151,16 +373,78 @@ impl Repository {
 
         by_fqn.extend(by_short.into_iter().filter(|s| !seen.contains(&s.fully_qualified_name)));
         by_fqn.truncate(200);
-        Ok(by_fqn)
+        Ok(self.absolute_symbols(by_fqn))
+    }
+
+    /// Returns the project types (classes, interfaces, enums, ...) named exactly `short_name`.
+    #[tracing::instrument(skip(self))]
+    pub async fn find_types_by_short_name(
+        &self,
+        short_name: &str,
+    ) -> Result<Vec<Symbol>, sqlx::Error> {
+        tracing::info!("find_types_by_short_name");
+        let symbols = sqlx::query_as::<_, Symbol>(
+            "SELECT * FROM symbols WHERE short_name = ? AND symbol_type NOT IN ('Function', 'Field')",
+        )
+        .bind(short_name)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(self.absolute_symbols(symbols))
+    }
+
+    /// Returns the project types annotated with `annotation` (its simple name).
+    #[tracing::instrument(skip(self))]
+    pub async fn find_types_by_annotation(
+        &self,
+        annotation: &str,
+    ) -> Result<Vec<Symbol>, sqlx::Error> {
+        tracing::info!("find_types_by_annotation");
+        let symbols = sqlx::query_as::<_, Symbol>(
+            "SELECT * FROM symbols WHERE symbol_type NOT IN ('Function', 'Field') \
+             AND EXISTS (SELECT 1 FROM json_each(metadata, '$.annotations') WHERE value = ?)",
+        )
+        .bind(annotation)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(self.absolute_symbols(symbols))
+    }
+
+    /// Returns the project types annotated `@Entity` whose entity name is `name`:
+    /// the one the annotation gives, else their simple name.
+    #[tracing::instrument(skip(self))]
+    pub async fn find_entities_by_name(&self, name: &str) -> Result<Vec<Symbol>, sqlx::Error> {
+        tracing::info!("find_entities_by_name");
+        let symbols = sqlx::query_as::<_, Symbol>(
+            "SELECT * FROM symbols WHERE symbol_type NOT IN ('Function', 'Field') \
+             AND EXISTS (SELECT 1 FROM json_each(metadata, '$.annotations') WHERE value = 'Entity') \
+             AND COALESCE(json_extract(metadata, '$.entity_name'), short_name) = ?",
+        )
+        .bind(name)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(self.absolute_symbols(symbols))
+    }
+
+    /// Returns the project symbols with the key `key` (see [`Symbol::key`]): one per
+    /// file declaring the symbol.
+    #[tracing::instrument(skip(self))]
+    pub async fn find_symbols_by_key(&self, key: &str) -> Result<Vec<Symbol>, sqlx::Error> {
+        let symbols = sqlx::query_as::<_, Symbol>("SELECT * FROM symbols WHERE symbol_key = ?")
+            .bind(key)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(self.absolute_symbols(symbols))
     }
 
     #[tracing::instrument(skip(self))]
     pub async fn find_symbols_by_fqn(&self, fqn: &str) -> Result<Vec<Symbol>, sqlx::Error> {
         tracing::info!("find_symbols_by_fqn");
-        sqlx::query_as::<_, Symbol>("SELECT * FROM symbols WHERE fully_qualified_name = ?")
-            .bind(fqn)
-            .fetch_all(&self.pool)
-            .await
+        let symbols =
+            sqlx::query_as::<_, Symbol>("SELECT * FROM symbols WHERE fully_qualified_name = ?")
+                .bind(fqn)
+                .fetch_all(&self.pool)
+                .await?;
+        Ok(self.absolute_symbols(symbols))
     }
 
     pub async fn insert_symbol_super_mappings(
@@ -168,26 +452,49 @@ impl Repository {
         mappings: Vec<(&str, &str, Option<&str>)>,
     ) -> Result<(), sqlx::Error> {
         let mut tx = self.pool.begin().await?;
+        insert_super_mappings(&mut tx, &mappings).await?;
+        tx.commit().await?;
+        Ok(())
+    }
 
-        for (symbol_fqn, _, _) in &mappings {
-            sqlx::query("DELETE FROM symbol_super_mapping WHERE symbol_fqn = ?")
-                .bind(symbol_fqn)
-                .execute(&mut *tx)
-                .await?;
-        }
+    /// Replaces everything recorded for the source file `file_path` with `symbols`
+    /// and their supertypes `mappings`, at once.  The supertypes of declarations the
+    /// file no longer has are dropped too, unless another file declares them.
+    pub async fn replace_file_symbols(
+        &self,
+        file_path: &str,
+        symbols: &[Symbol],
+        mappings: &[SymbolSuperMapping],
+    ) -> Result<(), sqlx::Error> {
+        let file_path = self.stored_path(file_path);
+        let mut tx = self.pool.begin().await?;
 
-        for (symbol_fqn, super_short_name, super_fqn) in mappings {
-            sqlx::query(
-                "INSERT INTO symbol_super_mapping (symbol_fqn, super_short_name, super_fqn) 
-             VALUES (?, ?, ?)",
-            )
-            .bind(symbol_fqn)
-            .bind(super_short_name)
-            .bind(super_fqn)
+        sqlx::query(
+            "DELETE FROM symbol_super_mapping WHERE symbol_fqn IN
+                (SELECT fully_qualified_name FROM symbols WHERE file_path = ?)
+            AND symbol_fqn NOT IN
+                (SELECT fully_qualified_name FROM symbols WHERE file_path != ?)",
+        )
+        .bind(&file_path)
+        .bind(&file_path)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("DELETE FROM symbols WHERE file_path = ?")
+            .bind(&file_path)
             .execute(&mut *tx)
             .await?;
+
+        for s in symbols {
+            insert_symbol(&mut tx, s, &file_path).await?;
         }
 
+        let mappings: Vec<_> = mappings
+            .iter()
+            .map(|m| (&*m.symbol_fqn, &*m.super_short_name, m.super_fqn.as_deref()))
+            .collect();
+        insert_super_mappings(&mut tx, &mappings).await?;
+
         tx.commit().await?;
         Ok(())
     }
@@ -212,7 +519,7 @@ impl Repository {
         .fetch_all(&self.pool)
         .await?;
 
-        Ok(symbols)
+        Ok(self.absolute_symbols(symbols))
     }
 
     pub async fn find_super_impls_by_short_name(
@@ -235,7 +542,7 @@ impl Repository {
         .fetch_all(&self.pool)
         .await?;
 
-        Ok(symbols)
+        Ok(self.absolute_symbols(symbols))
     }
 
     pub async fn find_supers_by_symbol_fqn(
@@ -258,7 +565,7 @@ impl Repository {
         .fetch_all(&self.pool)
         .await?;
 
-        Ok(symbols)
+        Ok(self.absolute_symbols(symbols))
     }
 
     pub async fn insert_external_symbols(
@@ -276,8 +583,8 @@ impl Repository {
             "INSERT INTO external_symbols (jar_path, source_file_path, alt_jar_path, short_name, package_name, 
             fully_qualified_name, parent_name, symbol_type, modifiers, line_start, line_end, 
             char_start, char_end, ident_line_start, ident_line_end, ident_char_start,
-            ident_char_end, needs_decompilation, metadata, last_modified, file_type)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ident_char_end, needs_decompilation, metadata, last_modified, file_type, symbol_key)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             ON CONFLICT(jar_path, source_file_path, fully_qualified_name, metadata) DO UPDATE SET
                 alt_jar_path = excluded.alt_jar_path,
                 short_name = excluded.short_name,
@@ -296,11 +603,12 @@ impl Repository {
                 needs_decompilation = excluded.needs_decompilation,
                 metadata = excluded.metadata,
                 last_modified = excluded.last_modified,
-                file_type = excluded.file_type",
+                file_type = excluded.file_type,
+                symbol_key = excluded.symbol_key",
         )
-        .bind(&s.jar_path)
+        .bind(self.stored_path(&s.jar_path))
         .bind(&s.source_file_path)
-        .bind(&s.alt_jar_path)
+        .bind(s.alt_jar_path.as_deref().map(|p| self.stored_path(p)))
         .bind(&s.short_name)
         .bind(&s.package_name)
         .bind(&s.fully_qualified_name)
@@ -319,6 +627,7 @@ impl Repository {
         .bind(&s.metadata)
         .bind(s.last_modified)
         .bind(&s.file_type)
+        .bind(s.key())
         .execute(&mut *tx)
         .await?;
         }
@@ -337,22 +646,99 @@ impl Repository {
         )
         .bind(fqn)
         .fetch_optional(&self.pool)
-        .await;
+        .await
+        .map(|s| self.absolute_external_symbols(s.into_iter().collect()).pop());
 
         tracing::info!("find_external_symbol_by_fqn result: {:?}", result);
         result
     }
 
+    /// Returns the symbols named `fqn` that came from `jar_path`, one per overload.
+    pub async fn find_external_symbols_by_fqn_and_jar(
+        &self,
+        fqn: &str,
+        jar_path: &str,
+    ) -> Result<Vec<ExternalSymbol>, sqlx::Error> {
+        let symbols = sqlx::query_as::<_, ExternalSymbol>(
+            "SELECT * FROM external_symbols WHERE fully_qualified_name = ? AND jar_path = ?",
+        )
+        .bind(fqn)
+        .bind(self.stored_path(jar_path))
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(self.absolute_external_symbols(symbols))
+    }
+
+    /// Returns the symbols named `fqn` that came from any of `jar_paths`, one per
+    /// overload and JAR.
+    #[tracing::instrument(skip(self, jar_paths))]
+    pub async fn find_external_symbols_by_fqn_and_jars(
+        &self,
+        fqn: &str,
+        jar_paths: &[String],
+    ) -> Result<Vec<ExternalSymbol>, sqlx::Error> {
+        let all = sqlx::query_as::<_, ExternalSymbol>(
+            "SELECT * FROM external_symbols WHERE fully_qualified_name = ?",
+        )
+        .bind(fqn)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(self
+            .absolute_external_symbols(all)
+            .into_iter()
+            .filter(|s| jar_paths.contains(&s.jar_path))
+            .collect())
+    }
+
+    /// Whether the sources of `class_fqn` in `jar_path` have already been parsed into
+    /// `external_symbols`.
+    pub async fn is_external_class_source_parsed(
+        &self,
+        jar_path: &str,
+        class_fqn: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let row: Option<(i64,)> = sqlx::query_as(
+            "SELECT 1 FROM external_class_sources WHERE jar_path = ? AND class_fqn = ?",
+        )
+        .bind(self.stored_path(jar_path))
+        .bind(class_fqn)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.is_some())
+    }
+
+    /// Stores the member signatures parsed from the sources of `class_fqn` in
+    /// `jar_path`, then records the class as parsed.
+    pub async fn insert_external_class_source(
+        &self,
+        jar_path: &str,
+        class_fqn: &str,
+        symbols: &[ExternalSymbol],
+    ) -> Result<(), sqlx::Error> {
+        self.insert_external_symbols(symbols).await?;
+        sqlx::query(
+            "INSERT OR IGNORE INTO external_class_sources (jar_path, class_fqn) VALUES (?, ?)",
+        )
+        .bind(self.stored_path(jar_path))
+        .bind(class_fqn)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
     #[tracing::instrument(skip(self))]
     pub async fn find_external_symbols_by_parent_name(
         &self,
         parent_fqn: &str,
     ) -> Result<Vec<ExternalSymbol>, sqlx::Error> {
         tracing::info!("find_external_symbols_by_parent_name");
-        sqlx::query_as::<_, ExternalSymbol>("SELECT * FROM external_symbols WHERE parent_name = ?")
-            .bind(parent_fqn)
-            .fetch_all(&self.pool)
-            .await
+        let symbols = sqlx::query_as::<_, ExternalSymbol>(
+            "SELECT * FROM external_symbols WHERE parent_name = ?",
+        )
+        .bind(parent_fqn)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(self.absolute_external_symbols(symbols))
     }
 
     /// Like `find_external_symbols_by_parent_name` but restricted to symbols from the given JARs.
@@ -400,7 +786,7 @@ impl Repository {
 
         by_fqn.extend(by_short.into_iter().filter(|s| !seen.contains(&s.fully_qualified_name)));
         by_fqn.truncate(200);
-        Ok(by_fqn)
+        Ok(self.absolute_external_symbols(by_fqn))
     }
 
     /// Like `find_external_symbols_by_prefix` but restricted to symbols from the given JARs.
@@ -421,19 +807,45 @@ impl Repository {
             .collect())
     }
 
+    /// Returns the external types named exactly `short_name`, restricted to the given JARs.
+    /// Falls back to every indexed JAR when `jar_paths` is empty.
+    #[tracing::instrument(skip(self, jar_paths))]
+    pub async fn find_external_types_by_short_name_and_jars(
+        &self,
+        short_name: &str,
+        jar_paths: &[String],
+    ) -> Result<Vec<ExternalSymbol>, sqlx::Error> {
+        tracing::info!("find_external_types_by_short_name_and_jars");
+        let all = sqlx::query_as::<_, ExternalSymbol>(
+            "SELECT * FROM external_symbols WHERE short_name = ? AND symbol_type NOT IN ('Function', 'Field')",
+        )
+        .bind(short_name)
+        .fetch_all(&self.pool)
+        .await?;
+        let all = self.absolute_external_symbols(all);
+        if jar_paths.is_empty() {
+            return Ok(all);
+        }
+        Ok(all
+            .into_iter()
+            .filter(|s| jar_paths.contains(&s.jar_path))
+            .collect())
+    }
+
     pub async fn delete_symbols_for_file(&self, file_path: &str) -> Result<(), sqlx::Error> {
+        let file_path = self.stored_path(file_path);
         let mut tx = self.pool.begin().await?;
 
         sqlx::query(
             "DELETE FROM symbol_super_mapping WHERE symbol_fqn IN 
         (SELECT fully_qualified_name FROM symbols WHERE file_path = ?)",
         )
-        .bind(file_path)
+        .bind(&file_path)
         .execute(&mut *tx)
         .await?;
 
         sqlx::query("DELETE FROM symbols WHERE file_path = ?")
-            .bind(file_path)
+            .bind(&file_path)
             .execute(&mut *tx)
             .await?;
 
@@ -442,8 +854,13 @@ impl Repository {
     }
 
     pub async fn delete_external_symbols_for_jar(&self, jar_path: &str) -> Result<(), sqlx::Error> {
+        let jar_path = self.stored_path(jar_path);
         sqlx::query("DELETE FROM external_symbols WHERE jar_path = ?")
-            .bind(jar_path)
+            .bind(&jar_path)
+            .execute(&self.pool)
+            .await?;
+        sqlx::query("DELETE FROM external_class_sources WHERE jar_path = ?")
+            .bind(&jar_path)
             .execute(&self.pool)
             .await?;
         Ok(())
@@ -456,10 +873,28 @@ impl Repository {
         &self,
         file_path: &str,
     ) -> Result<Vec<Symbol>, sqlx::Error> {
-        sqlx::query_as::<_, Symbol>("SELECT * FROM symbols WHERE file_path = ?")
-            .bind(file_path)
+        let symbols = sqlx::query_as::<_, Symbol>("SELECT * FROM symbols WHERE file_path = ?")
+            .bind(self.stored_path(file_path))
             .fetch_all(&self.pool)
-            .await
+            .await?;
+        Ok(self.absolute_symbols(symbols))
+    }
+
+    /// Returns every indexed project type and method, for workspace symbol search.
+    pub async fn find_all_symbols(&self) -> Result<Vec<Symbol>, sqlx::Error> {
+        let symbols =
+            sqlx::query_as::<_, Symbol>("SELECT * FROM symbols ORDER BY file_path, line_start")
+                .fetch_all(&self.pool)
+                .await?;
+        Ok(self.absolute_symbols(symbols))
+    }
+
+    pub async fn find_all_types_and_functions(&self) -> Result<Vec<Symbol>, sqlx::Error> {
+        let symbols =
+            sqlx::query_as::<_, Symbol>("SELECT * FROM symbols WHERE symbol_type != 'Field'")
+                .fetch_all(&self.pool)
+                .await?;
+        Ok(self.absolute_symbols(symbols))
     }
 
     /// Returns the distinct file paths of all indexed project symbols.
@@ -469,11 +904,113 @@ impl Repository {
             sqlx::query_as("SELECT DISTINCT file_path FROM symbols ORDER BY file_path")
                 .fetch_all(&self.pool)
                 .await?;
-        Ok(rows.into_iter().map(|(p,)| p).collect())
+        Ok(rows.into_iter().map(|(p,)| self.absolute_path(p)).collect())
+    }
+
+    /// Records the content hash each file was indexed with.
+    pub async fn upsert_file_hashes(&self, hashes: &[(String, String)]) -> Result<(), sqlx::Error> {
+        if hashes.is_empty() {
+            return Ok(());
+        }
+        let mut tx = self.pool.begin().await?;
+        for (file_path, content_hash) in hashes {
+            sqlx::query(
+                "INSERT INTO file_hashes (file_path, content_hash) VALUES (?, ?)
+                 ON CONFLICT(file_path) DO UPDATE SET content_hash = excluded.content_hash",
+            )
+            .bind(self.stored_path(file_path))
+            .bind(content_hash)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Returns every `(file_path, content_hash)` pair recorded by the last indexing pass.
+    pub async fn find_all_file_hashes(&self) -> Result<Vec<(String, String)>, sqlx::Error> {
+        let hashes: Vec<(String, String)> =
+            sqlx::query_as("SELECT file_path, content_hash FROM file_hashes")
+                .fetch_all(&self.pool)
+                .await?;
+        Ok(hashes
+            .into_iter()
+            .map(|(path, hash)| (self.absolute_path(path), hash))
+            .collect())
+    }
+
+    pub async fn delete_file_hash(&self, file_path: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM file_hashes WHERE file_path = ?")
+            .bind(self.stored_path(file_path))
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Re-keys what the index holds for the source file `from` to `to`, where it was
+    /// moved, replacing anything recorded for `to`.
+    pub async fn move_file(&self, from: &str, to: &str) -> Result<(), sqlx::Error> {
+        let (from, to) = (self.stored_path(from), self.stored_path(to));
+        let mut tx = self.pool.begin().await?;
+        for table in ["symbols", "file_hashes"] {
+            sqlx::query(&format!("DELETE FROM {table} WHERE file_path = ?"))
+                .bind(&to)
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query(&format!(
+                "UPDATE {table} SET file_path = ? WHERE file_path = ?"
+            ))
+            .bind(&to)
+            .bind(&from)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Writes a compacted copy of the whole index to `dest`, which must not exist.
+    pub async fn export_to(&self, dest: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("VACUUM INTO ?")
+            .bind(dest)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Rewrites every recorded source file and JAR path starting with `from` to start
+    /// with `to` instead, for each `(from, to)` in turn.
+    async fn relocate_paths(&self, prefixes: &[(String, String)]) -> Result<(), sqlx::Error> {
+        const PATH_COLUMNS: &[(&str, &str)] = &[
+            ("symbols", "file_path"),
+            ("file_hashes", "file_path"),
+            ("external_symbols", "jar_path"),
+            ("external_symbols", "alt_jar_path"),
+            ("external_class_sources", "jar_path"),
+        ];
+        let mut tx = self.pool.begin().await?;
+        for (from, to) in prefixes {
+            let from_len = from.chars().count() as i64;
+            for (table, column) in PATH_COLUMNS {
+                sqlx::query(&format!(
+                    "UPDATE {table} SET {column} = ? || substr({column}, ?)
+                     WHERE substr({column}, 1, ?) = ?"
+                ))
+                .bind(to)
+                .bind(from_len + 1)
+                .bind(from_len)
+                .bind(from)
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+        tx.commit().await?;
+        Ok(())
     }
 
     pub async fn clear_all(&self) -> Result<(), sqlx::Error> {
         let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM file_hashes").execute(&mut *tx).await?;
         sqlx::query("DELETE FROM symbol_super_mapping")
             .execute(&mut *tx)
             .await?;
@@ -481,6 +1018,9 @@ impl Repository {
         sqlx::query("DELETE FROM external_symbols")
             .execute(&mut *tx)
             .await?;
+        sqlx::query("DELETE FROM external_class_sources")
+            .execute(&mut *tx)
+            .await?;
         tx.commit().await?;
         Ok(())
     }
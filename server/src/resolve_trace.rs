@@ -0,0 +1,174 @@
+//! `lspintar/resolveTrace` — every strategy go-to-definition tries for a position,
+//! with what each finds and how long it takes.
+//!
+//! Definition records its steps in a [`TraceSink`] as it runs: the reference
+//! resolvers it tries first, then the lookups of the symbol resolution (a local
+//! variable, a member through its qualifier, an overload, the name through the
+//! imports, the project and library types), then the string and Spring bean
+//! fallbacks.  The resolvers after the one answering are listed as skipped, and
+//! its targets close the trace, so a wrong definition can be told apart from a
+//! lookup that never ran.
+
+use std::{sync::Mutex, time::Instant};
+
+use serde::{Deserialize, Serialize};
+use tower_lsp::{
+    jsonrpc::Result,
+    lsp_types::{Location, TextDocumentPositionParams},
+};
+
+use crate::{enums::ResolvedSymbol, lsp_convert::AsLspLocation, server::Backend};
+
+pub const RESOLVE_TRACE_METHOD: &str = "lspintar/resolveTrace";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Outcome {
+    Found,
+    NotFound,
+    Skipped,
+    Failed,
+}
+
+/// One strategy of the trace.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceStep {
+    pub strategy: String,
+    pub outcome: Outcome,
+    /// The references of what the strategy found, e.g. `com.foo.Bar#baz(String)`.
+    pub results: Vec<String>,
+    /// Why the strategy was skipped or failed, or what it looked up.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    pub elapsed_micros: u64,
+}
+
+impl TraceStep {
+    fn new(strategy: &str, started: Instant, outcome: Outcome, results: Vec<String>) -> Self {
+        Self {
+            strategy: strategy.to_string(),
+            outcome,
+            results,
+            detail: None,
+            elapsed_micros: started.elapsed().as_micros() as u64,
+        }
+    }
+
+    fn skipped(strategy: &str, detail: impl Into<String>) -> Self {
+        Self {
+            strategy: strategy.to_string(),
+            outcome: Outcome::Skipped,
+            results: vec![],
+            detail: Some(detail.into()),
+            elapsed_micros: 0,
+        }
+    }
+
+    pub(crate) fn failed(strategy: &str, started: Instant, detail: impl Into<String>) -> Self {
+        Self {
+            detail: Some(detail.into()),
+            ..Self::new(strategy, started, Outcome::Failed, vec![])
+        }
+    }
+
+    /// The step of a strategy answering with `symbols`, found when there are any.
+    pub(crate) fn found(strategy: &str, started: Instant, symbols: &[ResolvedSymbol]) -> Self {
+        let outcome = if symbols.is_empty() {
+            Outcome::NotFound
+        } else {
+            Outcome::Found
+        };
+        Self::new(
+            strategy,
+            started,
+            outcome,
+            symbols.iter().map(reference).collect(),
+        )
+    }
+
+    /// The step of a strategy resolving a name to `fqn`.
+    pub(crate) fn resolved(strategy: &str, started: Instant, fqn: Option<&str>) -> Self {
+        match fqn {
+            Some(fqn) => Self::new(strategy, started, Outcome::Found, vec![fqn.to_string()]),
+            None => Self::new(strategy, started, Outcome::NotFound, vec![]),
+        }
+    }
+
+    pub(crate) fn with_detail(self, detail: impl Into<String>) -> Self {
+        Self {
+            detail: Some(detail.into()),
+            ..self
+        }
+    }
+}
+
+/// The trace of a position.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveTrace {
+    pub steps: Vec<TraceStep>,
+    /// The strategy go-to-definition answers with; `None` when it fails.
+    pub resolver: Option<String>,
+    pub targets: Vec<Location>,
+    pub elapsed_micros: u64,
+}
+
+fn reference(symbol: &ResolvedSymbol) -> String {
+    symbol
+        .signature()
+        .unwrap_or_else(|| format!("local {}", symbol.name()))
+}
+
+/// Where a traced resolution records its steps, in the order it takes them.
+#[derive(Default)]
+pub(crate) struct TraceSink {
+    steps: Mutex<Vec<TraceStep>>,
+}
+
+impl TraceSink {
+    fn into_steps(self) -> Vec<TraceStep> {
+        self.steps.into_inner().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Records the step `step` builds in `trace`, when resolution is traced.
+pub(crate) fn record(trace: Option<&TraceSink>, step: impl FnOnce() -> TraceStep) {
+    if let Some(trace) = trace {
+        trace
+            .steps
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(step());
+    }
+}
+
+impl Backend {
+    /// Entry point for `lspintar/resolveTrace`.
+    pub async fn resolve_trace(&self, params: TextDocumentPositionParams) -> Result<ResolveTrace> {
+        let started = Instant::now();
+        let sink = TraceSink::default();
+        let resolved = self.traced_definition_symbols(&params, Some(&sink)).await;
+        let mut steps = sink.into_steps();
+
+        let (resolver, targets) = match resolved {
+            Ok((symbols, resolver)) => {
+                steps.extend(resolver.not_tried_after().into_iter().map(|strategy| {
+                    TraceStep::skipped(strategy, format!("{} answered first", resolver.name()))
+                }));
+                let targets = self.definition_targets(symbols).await;
+                (
+                    Some(resolver.name().to_string()),
+                    targets.iter().filter_map(|s| s.as_lsp_location()).collect(),
+                )
+            }
+            Err(_) => (None, vec![]),
+        };
+        Ok(ResolveTrace {
+            steps,
+            resolver,
+            targets,
+            elapsed_micros: started.elapsed().as_micros() as u64,
+        })
+    }
+}
@@ -0,0 +1,425 @@
+//! Resource bundles and classpath resources.
+//!
+//! Every file under a `src/<set>/resources` directory is a classpath resource; the
+//! keys of its `.properties` files are read too.  The index is kept in memory per
+//! file, built once the workspace index is ready and refreshed when a resource is
+//! saved or changes on disk, like that of Spring XML files.
+//!
+//! Source files name them in string arguments, found textually so every language
+//! reads alike:
+//!
+//! - `ResourceBundle.getBundle("messages")` resolves to the bundle's files,
+//!   `messages.properties` first, then its locale variants such as
+//!   `messages_fr.properties`;
+//! - `getString("error.notFound")`, `getMessage("error.notFound", ...)` and the like
+//!   resolve to the key in each file defining it, only in the bundles the file loads
+//!   when it loads any;
+//! - `getResource("/logback.xml")` and `"classpath:logback.xml"` resolve to the file.
+//!
+//! Keys no bundle defines are reported as `unknown_resource_key` when the client
+//! opts in with `{"check_resource_keys": true}`.
+
+use std::path::{Component, Path, PathBuf};
+
+use lsp_core::ts_helper::byte_offset_to_position;
+use serde::Deserialize;
+use tower_lsp::lsp_types::{
+    Diagnostic, DiagnosticSeverity, NumberOrString, Position, Range, TextDocumentPositionParams,
+    Url,
+};
+use walkdir::WalkDir;
+
+use crate::{enums::ResolvedSymbol, indexer::is_excluded, server::Backend};
+
+/// Methods whose first string argument is a resource bundle key.
+const KEY_METHODS: &[&str] = &[
+    "getString",
+    "getStringArray",
+    "getObject",
+    "getMessage",
+    "containsKey",
+];
+
+/// Methods whose first string argument is a bundle base name.
+const BUNDLE_METHODS: &[&str] = &["getBundle"];
+
+/// Methods whose first string argument is a classpath resource path.
+const RESOURCE_METHODS: &[&str] = &["getResource", "getResourceAsStream"];
+
+/// Resource options, read from the client's initialization options.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct ResourceSettings {
+    /// Report bundle keys that no `.properties` file defines.
+    pub check_resource_keys: bool,
+}
+
+/// A key defined in a `.properties` file.  `range` covers the key only.
+#[derive(Debug, Clone)]
+pub(crate) struct PropertyKey {
+    pub key: String,
+    pub range: Range,
+}
+
+/// A file under a resources directory.
+#[derive(Debug, Clone)]
+pub(crate) struct ResourceFile {
+    /// The path below the resources directory, e.g. `com/foo/messages_fr.properties`.
+    pub relative: String,
+    /// The keys it defines; empty for files other than `.properties`.
+    pub keys: Vec<PropertyKey>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReferenceKind {
+    Key,
+    Bundle,
+    Resource,
+}
+
+/// A string in source naming a key, bundle or resource.  `range` covers the text
+/// between the quotes.
+#[derive(Debug, Clone)]
+struct ResourceReference {
+    kind: ReferenceKind,
+    value: String,
+    range: Range,
+}
+
+/// The path of `path` below the `resources` directory of a source set, with `/`
+/// separators, or `None` outside one.
+pub(crate) fn resources_relative(path: &Path) -> Option<String> {
+    let components: Vec<Component> = path.components().collect();
+    let at = components
+        .windows(3)
+        .position(|w| w[0].as_os_str() == "src" && w[2].as_os_str() == "resources")?;
+    let rest: Vec<String> = components[at + 3..]
+        .iter()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    (!rest.is_empty()).then(|| rest.join("/"))
+}
+
+/// The base name of the bundle a `.properties` resource belongs to, e.g.
+/// `com.foo.messages` for `com/foo/messages_fr_CA.properties`.
+fn bundle_name(relative: &str) -> Option<String> {
+    let stem = relative.strip_suffix(".properties")?;
+    let (dir, file) = stem.rsplit_once('/').unwrap_or(("", stem));
+    // A locale suffix is a two-letter lowercase language, optionally followed by a
+    // two-letter uppercase country: `_fr` or `_fr_CA`.
+    let is_code = |s: &str, upper: bool| {
+        s.len() == 2
+            && s.chars().all(|c| {
+                if upper {
+                    c.is_ascii_uppercase()
+                } else {
+                    c.is_ascii_lowercase()
+                }
+            })
+    };
+    let mut base = file;
+    if let Some((head, country)) = base.rsplit_once('_')
+        && is_code(country, true)
+        && let Some((head, language)) = head.rsplit_once('_')
+        && is_code(language, false)
+        && !head.is_empty()
+    {
+        base = head;
+    } else if let Some((head, language)) = base.rsplit_once('_')
+        && is_code(language, false)
+        && !head.is_empty()
+    {
+        base = head;
+    }
+    let name = if dir.is_empty() {
+        base.to_string()
+    } else {
+        format!("{dir}/{base}")
+    };
+    Some(name.replace('/', "."))
+}
+
+/// The keys defined by a `.properties` file.  Continuation lines and comments are
+/// skipped; a key ends at the first unescaped `=`, `:` or whitespace.
+pub(crate) fn parse_property_keys(content: &str) -> Vec<PropertyKey> {
+    let mut keys = Vec::new();
+    let mut continued = false;
+    for (line_no, line) in content.lines().enumerate() {
+        let is_continuation = continued;
+        let trailing = line.len() - line.trim_end_matches('\\').len();
+        continued = trailing % 2 == 1;
+        if is_continuation {
+            continue;
+        }
+        let body = line.trim_start();
+        if body.is_empty() || body.starts_with('#') || body.starts_with('!') {
+            continue;
+        }
+        let indent = line.len() - body.len();
+        let mut escaped = false;
+        let mut len = body.len();
+        for (i, c) in body.char_indices() {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '=' | ':' => {
+                    len = i;
+                    break;
+                }
+                c if c.is_whitespace() => {
+                    len = i;
+                    break;
+                }
+                _ => {}
+            }
+        }
+        if len == 0 {
+            continue;
+        }
+        let start = line[..indent].chars().count() as u32;
+        let end = start + body[..len].chars().count() as u32;
+        keys.push(PropertyKey {
+            key: body[..len].replace('\\', ""),
+            range: Range::new(
+                Position::new(line_no as u32, start),
+                Position::new(line_no as u32, end),
+            ),
+        });
+    }
+    keys
+}
+
+/// The bundle keys, bundle names and resource paths named in `content`.
+fn resource_references(content: &str) -> Vec<ResourceReference> {
+    let mut references = Vec::new();
+    let methods = KEY_METHODS
+        .iter()
+        .map(|m| (*m, ReferenceKind::Key))
+        .chain(BUNDLE_METHODS.iter().map(|m| (*m, ReferenceKind::Bundle)))
+        .chain(
+            RESOURCE_METHODS
+                .iter()
+                .map(|m| (*m, ReferenceKind::Resource)),
+        );
+    for (method, kind) in methods {
+        for (at, _) in content.match_indices(method) {
+            let follows_identifier = content[..at]
+                .chars()
+                .next_back()
+                .is_some_and(|c| c.is_alphanumeric() || c == '_');
+            if follows_identifier {
+                continue;
+            }
+            let Some(argument) = content[at + method.len()..].strip_prefix('(') else {
+                continue;
+            };
+            let start = content.len() - argument.trim_start().len();
+            if let Some((value, offset)) = string_literal(content, start) {
+                references.push(reference(content, kind, value, offset));
+            }
+        }
+    }
+    for (at, _) in content.match_indices("classpath:") {
+        let quoted = content[..at].ends_with(['"', '\'']);
+        if let Some((value, offset)) = quoted.then(|| string_literal(content, at - 1)).flatten() {
+            references.push(reference(content, ReferenceKind::Resource, value, offset));
+        }
+    }
+    references
+}
+
+fn reference(content: &str, kind: ReferenceKind, value: &str, offset: usize) -> ResourceReference {
+    ResourceReference {
+        kind,
+        value: value.to_string(),
+        range: Range::new(
+            byte_offset_to_position(content, offset),
+            byte_offset_to_position(content, offset + value.len()),
+        ),
+    }
+}
+
+/// The body of the string literal opening at `start`, with its byte offset, when it
+/// is closed on the same line and not interpolated.
+fn string_literal(content: &str, start: usize) -> Option<(&str, usize)> {
+    let quote = content[start..]
+        .chars()
+        .next()
+        .filter(|c| *c == '"' || *c == '\'')?;
+    let body_start = start + 1;
+    let len = content[body_start..].find(|c| c == quote || c == '\n')?;
+    let body = &content[body_start..body_start + len];
+    (content[body_start + len..].starts_with(quote) && !body.contains(['$', '\\']))
+        .then_some((body, body_start))
+}
+
+/// Orders bundle files base file first, then by locale.
+fn base_first(a: &str, b: &str) -> std::cmp::Ordering {
+    a.len().cmp(&b.len()).then_with(|| a.cmp(b))
+}
+
+impl Backend {
+    /// Scans every resources directory under `root`.
+    pub(crate) async fn index_resources(&self, root: &Path) {
+        let root = root.to_path_buf();
+        let files = tokio::task::spawn_blocking(move || {
+            WalkDir::new(root)
+                .follow_links(true)
+                .into_iter()
+                .filter_entry(|e| !is_excluded(e))
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_type().is_file())
+                .filter_map(|e| {
+                    let file = read_resource(e.path())?;
+                    Some((e.into_path(), file))
+                })
+                .collect::<Vec<_>>()
+        })
+        .await
+        .unwrap_or_default();
+
+        let mut index = self.resources.write().await;
+        index.clear();
+        index.extend(files);
+    }
+
+    /// Re-reads one resource, dropping it from the index when it is gone.
+    pub(crate) async fn reindex_resource_file(&self, path: &Path) {
+        let mut index = self.resources.write().await;
+        match read_resource(path) {
+            Some(file) => index.insert(path.to_path_buf(), file),
+            None => index.remove(path),
+        };
+    }
+
+    /// Resolves a bundle key, bundle name or resource path at the request position.
+    /// Returns `None` outside such strings, so regular resolution applies.
+    pub(crate) async fn resolve_resource_reference(
+        &self,
+        params: &TextDocumentPositionParams,
+    ) -> Option<Vec<ResolvedSymbol>> {
+        let content = self.document_text(&params.text_document.uri)?;
+        let references = resource_references(&content);
+        let position = params.position;
+        let reference = references.iter().find(|r| {
+            r.range.start.line == position.line
+                && r.range.start.character <= position.character
+                && position.character <= r.range.end.character
+        })?;
+
+        let index = self.resources.read().await;
+        let mut targets: Vec<(&PathBuf, &ResourceFile, Position)> = match reference.kind {
+            ReferenceKind::Key => {
+                let bundles = loaded_bundles(&references);
+                index
+                    .iter()
+                    .filter(|(_, file)| in_bundles(file, &bundles))
+                    .flat_map(|(path, file)| {
+                        file.keys
+                            .iter()
+                            .filter(|k| k.key == reference.value)
+                            .map(move |k| (path, file, k.range.start))
+                    })
+                    .collect()
+            }
+            ReferenceKind::Bundle => {
+                let name = reference.value.replace('/', ".");
+                index
+                    .iter()
+                    .filter(|(_, file)| bundle_name(&file.relative).as_deref() == Some(&name))
+                    .map(|(path, file)| (path, file, Position::new(0, 0)))
+                    .collect()
+            }
+            ReferenceKind::Resource => {
+                let wanted = reference
+                    .value
+                    .trim_start_matches("classpath:")
+                    .trim_start_matches('/');
+                index
+                    .iter()
+                    .filter(|(_, file)| file.relative == wanted)
+                    .map(|(path, file)| (path, file, Position::new(0, 0)))
+                    .collect()
+            }
+        };
+        targets.sort_by(|a, b| base_first(&a.1.relative, &b.1.relative).then_with(|| a.0.cmp(b.0)));
+        Some(
+            targets
+                .into_iter()
+                .filter_map(|(path, _, position)| {
+                    Some(ResolvedSymbol::Local {
+                        uri: Url::from_file_path(path).ok()?,
+                        position,
+                        name: reference.value.clone(),
+                        var_type: None,
+                    })
+                })
+                .collect(),
+        )
+    }
+
+    /// `unknown_resource_key` diagnostics for the bundle keys `content` names, when
+    /// the client asked for them and the workspace has any bundle at all.
+    pub(crate) async fn resource_key_diagnostics(&self, content: &str) -> Vec<Diagnostic> {
+        if !self.resource_settings.read().await.check_resource_keys {
+            return vec![];
+        }
+        let references = resource_references(content);
+        if !references.iter().any(|r| r.kind == ReferenceKind::Key) {
+            return vec![];
+        }
+        let index = self.resources.read().await;
+        let bundles = loaded_bundles(&references);
+        let defined: Vec<&ResourceFile> = index
+            .values()
+            .filter(|file| in_bundles(file, &bundles))
+            .collect();
+        if defined.is_empty() {
+            return vec![];
+        }
+        references
+            .into_iter()
+            .filter(|r| r.kind == ReferenceKind::Key)
+            .filter(|r| {
+                !defined
+                    .iter()
+                    .any(|file| file.keys.iter().any(|k| k.key == r.value))
+            })
+            .map(|r| Diagnostic {
+                range: r.range,
+                severity: Some(DiagnosticSeverity::WARNING),
+                code: Some(NumberOrString::String("unknown_resource_key".to_string())),
+                source: Some("lspintar".to_string()),
+                message: format!("No resource bundle defines '{}'", r.value),
+                ..Default::default()
+            })
+            .collect()
+    }
+}
+
+fn read_resource(path: &Path) -> Option<ResourceFile> {
+    let relative = resources_relative(path)?;
+    let keys = if relative.ends_with(".properties") {
+        parse_property_keys(&std::fs::read_to_string(path).ok()?)
+    } else {
+        vec![]
+    };
+    Some(ResourceFile { relative, keys })
+}
+
+/// The bundle base names a source file loads with `getBundle`.
+fn loaded_bundles(references: &[ResourceReference]) -> Vec<String> {
+    references
+        .iter()
+        .filter(|r| r.kind == ReferenceKind::Bundle)
+        .map(|r| r.value.replace('/', "."))
+        .collect()
+}
+
+/// Whether `file` belongs to one of `bundles`, or to any bundle when none is named.
+fn in_bundles(file: &ResourceFile, bundles: &[String]) -> bool {
+    match bundle_name(&file.relative) {
+        Some(name) => bundles.is_empty() || bundles.contains(&name),
+        None => false,
+    }
+}
@@ -0,0 +1,43 @@
+//! Time limits on resolving `receiver.member`.
+//!
+//! Resolution has two phases: walking the receiver's chain to a type, then
+//! searching that type and its supertypes for the member.  Either can take seconds
+//! in a large monorepo or a deep hierarchy of decompiled classes, and the editor
+//! waits on definition and hover.  Each phase gets a budget; when the member
+//! search runs out, the type itself is the answer, which is where the member is
+//! declared more often than not.
+
+use std::{future::Future, time::Duration};
+
+use serde::Deserialize;
+
+/// Read from the client's initialization options, e.g.
+/// `{"type_search_timeout_ms": 5000, "member_search_timeout_ms": 0}`.  A limit of
+/// `0` means none.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct SearchLimits {
+    /// Budget for resolving the receiver of a member access to its type.
+    pub type_search_timeout_ms: u64,
+    /// Budget for finding the member in that type and its supertypes.
+    pub member_search_timeout_ms: u64,
+}
+
+impl Default for SearchLimits {
+    fn default() -> Self {
+        Self {
+            type_search_timeout_ms: 2000,
+            member_search_timeout_ms: 1000,
+        }
+    }
+}
+
+/// The output of `future`, or `None` when it takes longer than `timeout_ms`.
+pub(crate) async fn within<F: Future>(timeout_ms: u64, future: F) -> Option<F::Output> {
+    if timeout_ms == 0 {
+        return Some(future.await);
+    }
+    tokio::time::timeout(Duration::from_millis(timeout_ms), future)
+        .await
+        .ok()
+}
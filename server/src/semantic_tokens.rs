@@ -0,0 +1,597 @@
+//! `textDocument/semanticTokens` — full, delta and range requests.
+//!
+//! Tokens come from the syntax tree alone: declarations of types, methods, fields
+//! and enum constants with their `static`, `final`/`val`, `abstract` and
+//! `@Deprecated` modifiers, locals and parameters with every reference to them, and
+//! the type names, method calls, field accesses and annotations of the file.
+//! Names resolving elsewhere are not looked up, so a field of another class is a
+//! property only where it is accessed through `.`.
+//!
+//! The tokens of each document are kept with the hash of the text they were
+//! computed from and a result id.  A full request on unchanged text is answered
+//! from the cache, a delta request replaces the changed run of tokens only, and a
+//! range request filters the tokens of the current text, computing them once.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use dashmap::DashMap;
+use lsp_core::{language_support::LanguageSupport, node_kind::NodeKind};
+use tower_lsp::{
+    jsonrpc::Result,
+    lsp_types::{
+        Position, Range, SemanticToken, SemanticTokenModifier, SemanticTokenType, SemanticTokens,
+        SemanticTokensDelta, SemanticTokensDeltaParams, SemanticTokensEdit,
+        SemanticTokensFullDeltaResult, SemanticTokensLegend, SemanticTokensParams,
+        SemanticTokensRangeParams, SemanticTokensRangeResult, SemanticTokensResult, Url,
+    },
+};
+use tree_sitter::{Node, Tree};
+
+use crate::{indexer::content_hash, server::Backend};
+
+const TOKEN_TYPES: &[SemanticTokenType] = &[
+    SemanticTokenType::CLASS,
+    SemanticTokenType::INTERFACE,
+    SemanticTokenType::ENUM,
+    SemanticTokenType::TYPE,
+    SemanticTokenType::TYPE_PARAMETER,
+    SemanticTokenType::ENUM_MEMBER,
+    SemanticTokenType::METHOD,
+    SemanticTokenType::PROPERTY,
+    SemanticTokenType::PARAMETER,
+    SemanticTokenType::VARIABLE,
+    SemanticTokenType::DECORATOR,
+];
+
+const TOKEN_MODIFIERS: &[SemanticTokenModifier] = &[
+    SemanticTokenModifier::DECLARATION,
+    SemanticTokenModifier::STATIC,
+    SemanticTokenModifier::READONLY,
+    SemanticTokenModifier::ABSTRACT,
+    SemanticTokenModifier::DEPRECATED,
+];
+
+const DECLARATION: u32 = 1;
+const STATIC: u32 = 1 << 1;
+const READONLY: u32 = 1 << 2;
+const ABSTRACT: u32 = 1 << 3;
+const DEPRECATED: u32 = 1 << 4;
+
+/// The legend the server announces: token types and modifiers by index.
+pub fn legend() -> SemanticTokensLegend {
+    SemanticTokensLegend {
+        token_types: TOKEN_TYPES.to_vec(),
+        token_modifiers: TOKEN_MODIFIERS.to_vec(),
+    }
+}
+
+fn token_type_index(token_type: &SemanticTokenType) -> u32 {
+    TOKEN_TYPES
+        .iter()
+        .position(|t| t == token_type)
+        .unwrap_or_default() as u32
+}
+
+/// Parameter and local variable bindings, by the kind of the node naming them.
+const PARAMETER_BINDINGS: &[&str] = &[
+    "formal_parameter",
+    "catch_formal_parameter",
+    "spread_parameter",
+    "parameter",
+    "closure_parameter",
+    "class_parameter",
+    "lambda_parameters",
+];
+const VARIABLE_BINDINGS: &[&str] = &[
+    "variable_declarator",
+    "variable_declaration",
+    "enhanced_for_statement",
+    "resource",
+];
+
+/// Parents of a Kotlin property that make it a member rather than a local.
+const MEMBER_SCOPES: &[&str] = &["class_body", "enum_class_body", "source_file"];
+
+/// A token with an absolute position, as cached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Token {
+    line: u32,
+    start: u32,
+    length: u32,
+    token_type: u32,
+    modifiers: u32,
+}
+
+/// Tokens by position; the first classification of a name wins.
+#[derive(Default)]
+struct Tokens(BTreeMap<(u32, u32), Token>);
+
+impl Tokens {
+    fn add(&mut self, node: &Node, token_type: SemanticTokenType, modifiers: u32) {
+        self.add_range(node_range(node), token_type, modifiers);
+    }
+
+    fn add_range(&mut self, range: Range, token_type: SemanticTokenType, modifiers: u32) {
+        if range.start.line != range.end.line || range.end.character <= range.start.character {
+            return;
+        }
+        self.0
+            .entry((range.start.line, range.start.character))
+            .or_insert(Token {
+                line: range.start.line,
+                start: range.start.character,
+                length: range.end.character - range.start.character,
+                token_type: token_type_index(&token_type),
+                modifiers,
+            });
+    }
+
+    fn contains(&self, node: &Node) -> bool {
+        let start = node.start_position();
+        self.0
+            .contains_key(&(start.row as u32, start.column as u32))
+    }
+}
+
+fn node_range(node: &Node) -> Range {
+    let (start, end) = (node.start_position(), node.end_position());
+    Range::new(
+        Position::new(start.row as u32, start.column as u32),
+        Position::new(end.row as u32, end.column as u32),
+    )
+}
+
+fn is_identifier(node: &Node) -> bool {
+    matches!(
+        node.kind(),
+        "identifier" | "simple_identifier" | "type_identifier"
+    )
+}
+
+/// Whether `node` is the name `parent` declares: its `name` field, or its first
+/// identifier when it has none.
+fn is_name_of(node: &Node, parent: &Node) -> bool {
+    match parent.child_by_field_name("name") {
+        Some(name) => name == *node,
+        None => {
+            let mut cursor = parent.walk();
+            parent
+                .named_children(&mut cursor)
+                .find(|c| is_identifier(c))
+                .is_some_and(|c| c == *node)
+        }
+    }
+}
+
+fn declaration_modifiers(
+    lang: &(dyn LanguageSupport + Send + Sync),
+    node: &Node,
+    content: &str,
+) -> u32 {
+    let mut modifiers = DECLARATION;
+    for modifier in lang.get_modifiers(node, content) {
+        modifiers |= match modifier.as_str() {
+            "static" => STATIC,
+            "final" | "val" | "const" => READONLY,
+            "abstract" => ABSTRACT,
+            _ => 0,
+        };
+    }
+    let deprecated = lang.get_annotations(node, content).iter().any(|a| {
+        let name = a
+            .trim_start_matches('@')
+            .split('(')
+            .next()
+            .unwrap_or_default();
+        name.rsplit('.').next() == Some("Deprecated")
+    });
+    if deprecated {
+        modifiers |= DEPRECATED;
+    }
+    modifiers
+}
+
+/// The range of the name of a type, method or field declaration.
+fn declaration_name(lang: &(dyn LanguageSupport + Send + Sync), node: &Node) -> Option<Range> {
+    if node.kind() == "property_declaration" {
+        let mut cursor = node.walk();
+        let variable = node
+            .named_children(&mut cursor)
+            .find(|c| c.kind() == "variable_declaration")?;
+        let mut cursor = variable.walk();
+        return variable
+            .named_children(&mut cursor)
+            .find(|c| is_identifier(c))
+            .map(|name| node_range(&name));
+    }
+    lang.get_ident_range(node)
+}
+
+/// The semantic tokens of a parsed file, in document order.
+fn compute_tokens(
+    lang: &(dyn LanguageSupport + Send + Sync),
+    tree: &Tree,
+    content: &str,
+) -> Vec<Token> {
+    let bytes = content.as_bytes();
+    let mut tokens = Tokens::default();
+    let mut nodes = Vec::new();
+    let mut stack = vec![tree.root_node()];
+    while let Some(node) = stack.pop() {
+        nodes.push(node);
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor));
+    }
+    nodes.sort_by_key(|n| n.start_byte());
+
+    // Declarations of types, members and enum constants.
+    let mut fields: HashMap<&str, u32> = HashMap::new();
+    for node in &nodes {
+        if matches!(node.kind(), "enum_constant" | "enum_entry") {
+            let mut cursor = node.walk();
+            let name = node
+                .child_by_field_name("name")
+                .or_else(|| node.named_children(&mut cursor).find(|c| is_identifier(c)));
+            if let Some(name) = name {
+                let modifiers = DECLARATION | STATIC | READONLY;
+                tokens.add(&name, SemanticTokenType::ENUM_MEMBER, modifiers);
+            }
+            continue;
+        }
+        let Some(kind) = lang.get_kind(node) else {
+            continue;
+        };
+        let is_local_property = node.kind() == "property_declaration"
+            && node
+                .parent()
+                .is_some_and(|p| !MEMBER_SCOPES.contains(&p.kind()));
+        if is_local_property {
+            continue;
+        }
+        let Some(range) = declaration_name(lang, node) else {
+            continue;
+        };
+        let token_type = match kind {
+            NodeKind::Class => SemanticTokenType::CLASS,
+            NodeKind::Interface | NodeKind::Annotation => SemanticTokenType::INTERFACE,
+            NodeKind::Enum => SemanticTokenType::ENUM,
+            NodeKind::TypeAlias => SemanticTokenType::TYPE,
+            NodeKind::Function => SemanticTokenType::METHOD,
+            NodeKind::Field => SemanticTokenType::PROPERTY,
+        };
+        let modifiers = declaration_modifiers(lang, node, content);
+        if kind == NodeKind::Field {
+            let line = content
+                .lines()
+                .nth(range.start.line as usize)
+                .unwrap_or_default();
+            if let Some(name) =
+                line.get(range.start.character as usize..range.end.character as usize)
+            {
+                fields.insert(name, modifiers & !DECLARATION);
+            }
+        }
+        tokens.add_range(range, token_type, modifiers);
+    }
+
+    // Locals and parameters, with every reference resolving to them.
+    let mut bindings = Vec::new();
+    for node in nodes.iter().filter(|n| is_identifier(n)) {
+        let Some(parent) = node.parent() else {
+            continue;
+        };
+        if !is_name_of(node, &parent) || tokens.contains(node) {
+            continue;
+        }
+        let token_type = if parent.kind() == "variable_declaration"
+            && parent
+                .parent()
+                .is_some_and(|p| p.kind() == "lambda_parameters")
+        {
+            SemanticTokenType::PARAMETER
+        } else if PARAMETER_BINDINGS.contains(&parent.kind()) && lang.get_kind(&parent).is_none() {
+            SemanticTokenType::PARAMETER
+        } else if VARIABLE_BINDINGS.contains(&parent.kind()) {
+            let owner = parent.parent();
+            let is_member = owner.is_some_and(|o| {
+                lang.get_kind(&o).is_some()
+                    && (o.kind() != "property_declaration"
+                        || o.parent()
+                            .is_some_and(|p| MEMBER_SCOPES.contains(&p.kind())))
+            });
+            if is_member {
+                continue;
+            }
+            if owner.is_some_and(|o| o.kind() == "spread_parameter") {
+                SemanticTokenType::PARAMETER
+            } else {
+                SemanticTokenType::VARIABLE
+            }
+        } else {
+            continue;
+        };
+        let range = node_range(node);
+        tokens.add_range(range, token_type.clone(), DECLARATION);
+        bindings.push((range.start, token_type));
+    }
+    let positions: Vec<Position> = bindings.iter().map(|(start, _)| *start).collect();
+    let references = lang.find_all_local_references(tree, content, &positions);
+    for ((_, token_type), references) in bindings.into_iter().zip(references) {
+        for reference in references {
+            tokens.add_range(reference, token_type.clone(), 0);
+        }
+    }
+
+    // Type parameters are named by declarations anywhere in the file.
+    let type_parameters: Vec<&str> = nodes
+        .iter()
+        .filter(|n| n.kind() == "type_parameter")
+        .filter_map(|n| {
+            let mut cursor = n.walk();
+            let name = n.named_children(&mut cursor).find(|c| is_identifier(c))?;
+            name.utf8_text(bytes).ok()
+        })
+        .collect();
+
+    // Everything else named in the file.
+    for node in nodes.iter().filter(|n| is_identifier(n)) {
+        if tokens.contains(node) {
+            continue;
+        }
+        let Some(parent) = node.parent() else {
+            continue;
+        };
+        let Ok(text) = node.utf8_text(bytes) else {
+            continue;
+        };
+        let annotation = std::iter::successors(Some(parent), |n| n.parent())
+            .take_while(|n| {
+                matches!(
+                    n.kind(),
+                    "annotation"
+                        | "marker_annotation"
+                        | "user_type"
+                        | "constructor_invocation"
+                        | "scoped_identifier"
+                )
+            })
+            .any(|n| matches!(n.kind(), "annotation" | "marker_annotation"));
+        let first_named = |n: &Node| n.named_child(0).is_some_and(|c| c == *node);
+        let is_callee = match parent.kind() {
+            "method_invocation" => parent.child_by_field_name("name") == Some(*node),
+            "call_expression" => first_named(&parent),
+            "navigation_suffix" => parent
+                .parent()
+                .and_then(|nav| Some((nav, nav.parent()?)))
+                .is_some_and(|(nav, call)| {
+                    call.kind() == "call_expression" && call.named_child(0) == Some(nav)
+                }),
+            _ => false,
+        };
+        let is_member_access = match parent.kind() {
+            "field_access" => parent.child_by_field_name("field") == Some(*node),
+            "navigation_suffix" => true,
+            _ => false,
+        };
+
+        if annotation {
+            tokens.add(node, SemanticTokenType::DECORATOR, 0);
+        } else if parent.kind() == "type_parameter" {
+            tokens.add(node, SemanticTokenType::TYPE_PARAMETER, DECLARATION);
+        } else if node.kind() == "type_identifier" {
+            let token_type = if type_parameters.contains(&text) {
+                SemanticTokenType::TYPE_PARAMETER
+            } else {
+                SemanticTokenType::TYPE
+            };
+            tokens.add(node, token_type, 0);
+        } else if is_callee {
+            let constructor =
+                parent.kind() == "call_expression" && text.starts_with(|c: char| c.is_uppercase());
+            let token_type = if constructor {
+                SemanticTokenType::CLASS
+            } else {
+                SemanticTokenType::METHOD
+            };
+            tokens.add(node, token_type, 0);
+        } else if let Some(modifiers) = fields.get(text) {
+            tokens.add(node, SemanticTokenType::PROPERTY, *modifiers);
+        } else if is_member_access {
+            tokens.add(node, SemanticTokenType::PROPERTY, 0);
+        }
+    }
+
+    tokens.0.into_values().collect()
+}
+
+/// `tokens` in the relative encoding of the protocol.
+fn encode(tokens: &[Token]) -> Vec<SemanticToken> {
+    let mut previous = (0, 0);
+    tokens
+        .iter()
+        .map(|token| {
+            let delta_line = token.line - previous.0;
+            let delta_start = if delta_line == 0 {
+                token.start - previous.1
+            } else {
+                token.start
+            };
+            previous = (token.line, token.start);
+            SemanticToken {
+                delta_line,
+                delta_start,
+                length: token.length,
+                token_type: token.token_type,
+                token_modifiers_bitset: token.modifiers,
+            }
+        })
+        .collect()
+}
+
+/// The single edit turning `old` into `new`: what lies between their common prefix
+/// and common suffix.  Offsets count integers, five per token.
+fn delta_edits(old: &[SemanticToken], new: &[SemanticToken]) -> Vec<SemanticTokensEdit> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (old_end, new_end) = (old.len() - suffix, new.len() - suffix);
+    if prefix == old_end && prefix == new_end {
+        return vec![];
+    }
+    vec![SemanticTokensEdit {
+        start: (prefix * 5) as u32,
+        delete_count: ((old_end - prefix) * 5) as u32,
+        data: Some(new[prefix..new_end].to_vec()),
+    }]
+}
+
+struct CachedTokens {
+    content_hash: String,
+    result_id: String,
+    tokens: Vec<Token>,
+    data: Vec<SemanticToken>,
+}
+
+/// The tokens of each open document: the last sent in full, which delta requests
+/// refer to, and the last computed, which range requests filter.
+#[derive(Default)]
+pub(crate) struct SemanticTokensCache {
+    documents: DashMap<Url, Arc<CachedTokens>>,
+    computed: DashMap<Url, Arc<CachedTokens>>,
+    next_result_id: AtomicU64,
+}
+
+impl SemanticTokensCache {
+    pub(crate) fn remove(&self, uri: &Url) {
+        self.documents.remove(uri);
+        self.computed.remove(uri);
+    }
+}
+
+impl Backend {
+    /// The tokens of `uri` as they are now, computed again only when its text
+    /// changed since they were last computed.  `sent` records them as the tokens
+    /// the client has, which later delta requests refer to.
+    fn document_tokens(&self, uri: &Url, sent: bool) -> Option<Arc<CachedTokens>> {
+        let content = self.document_text(uri)?;
+        let hash = content_hash(content.as_bytes());
+        let cache = &self.semantic_tokens;
+        if let Some(cached) = cache.documents.get(uri)
+            && cached.content_hash == hash
+        {
+            return Some(cached.clone());
+        }
+        let computed = cache
+            .computed
+            .get(uri)
+            .map(|cached| cached.clone())
+            .filter(|cached| cached.content_hash == hash)
+            .or_else(|| self.compute_document_tokens(uri, &content, hash))?;
+        cache.computed.insert(uri.clone(), computed.clone());
+        if sent {
+            cache.documents.insert(uri.clone(), computed.clone());
+        }
+        Some(computed)
+    }
+
+    fn compute_document_tokens(
+        &self,
+        uri: &Url,
+        content: &str,
+        hash: String,
+    ) -> Option<Arc<CachedTokens>> {
+        let cache = &self.semantic_tokens;
+        let extension = uri.path().rsplit_once('.')?.1;
+        let lang = self.languages.get(extension)?;
+        let (tree, _) = lang.parse_str(content)?;
+        let tokens = compute_tokens(lang.as_ref(), &tree, content);
+        let id = cache.next_result_id.fetch_add(1, Ordering::Relaxed);
+        Some(Arc::new(CachedTokens {
+            content_hash: hash,
+            result_id: id.to_string(),
+            data: encode(&tokens),
+            tokens,
+        }))
+    }
+
+    pub async fn semantic_tokens_full_impl(
+        &self,
+        params: SemanticTokensParams,
+    ) -> Result<Option<SemanticTokensResult>> {
+        let Some(current) = self.document_tokens(&params.text_document.uri, true) else {
+            return Ok(None);
+        };
+        Ok(Some(SemanticTokensResult::Tokens(SemanticTokens {
+            result_id: Some(current.result_id.clone()),
+            data: current.data.clone(),
+        })))
+    }
+
+    /// Answers with the edits from the tokens of `previous_result_id` when they are
+    /// still cached, else with all tokens.
+    pub async fn semantic_tokens_full_delta_impl(
+        &self,
+        params: SemanticTokensDeltaParams,
+    ) -> Result<Option<SemanticTokensFullDeltaResult>> {
+        let uri = &params.text_document.uri;
+        let previous = self
+            .semantic_tokens
+            .documents
+            .get(uri)
+            .map(|cached| cached.clone())
+            .filter(|cached| cached.result_id == params.previous_result_id);
+        let Some(current) = self.document_tokens(uri, true) else {
+            return Ok(None);
+        };
+        let Some(previous) = previous else {
+            return Ok(Some(SemanticTokensFullDeltaResult::Tokens(
+                SemanticTokens {
+                    result_id: Some(current.result_id.clone()),
+                    data: current.data.clone(),
+                },
+            )));
+        };
+        Ok(Some(SemanticTokensFullDeltaResult::TokensDelta(
+            SemanticTokensDelta {
+                result_id: Some(current.result_id.clone()),
+                edits: delta_edits(&previous.data, &current.data),
+            },
+        )))
+    }
+
+    /// Answers from the tokens of the current text, computed once per change.
+    /// They are not recorded as sent, so they do not replace the ones a later
+    /// delta request refers to.
+    pub async fn semantic_tokens_range_impl(
+        &self,
+        params: SemanticTokensRangeParams,
+    ) -> Result<Option<SemanticTokensRangeResult>> {
+        let Some(current) = self.document_tokens(&params.text_document.uri, false) else {
+            return Ok(None);
+        };
+        let range = params.range;
+        let in_range: Vec<Token> = current
+            .tokens
+            .iter()
+            .filter(|t| {
+                (t.line, t.start) >= (range.start.line, range.start.character)
+                    && (t.line, t.start) < (range.end.line, range.end.character)
+            })
+            .copied()
+            .collect();
+        Ok(Some(SemanticTokensRangeResult::Tokens(SemanticTokens {
+            result_id: None,
+            data: encode(&in_range),
+        })))
+    }
+}
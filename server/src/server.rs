@@ -5,11 +5,16 @@ use groovy::GroovySupport;
 use java::JavaSupport;
 use kotlin::KotlinSupport;
 use lsp_core::{
-    build_tools::{BuildToolHandler, SubprojectClasspath, get_build_tool},
+    build_tools::{
+        ArtifactSettings, BuildToolHandler, SubprojectClasspath, find_java_home_sources,
+        get_build_tool, set_artifact_settings,
+    },
     language_support::LanguageSupport,
     languages::Language,
     lsp_error, lsp_info, lsp_logging, lsp_progress, lsp_progress_begin, lsp_progress_end,
-    util::{capitalize, extract_prefix, extract_receiver, get_import_text_edit},
+    node_kind::NodeKind,
+    qualifier::{QualifierChain, SegmentKind},
+    util::{extract_prefix, extract_receiver, get_import_text_edit},
     vcs::{VcsHandler, get_vcs_handler},
 };
 use std::{
@@ -23,7 +28,7 @@ use std::{
     },
     time::{Duration, Instant},
 };
-use tokio::sync::{OnceCell, RwLock};
+use tokio::sync::{Notify, OnceCell, RwLock};
 use tower_lsp::lsp_types::*;
 use tower_lsp::{LanguageServer, lsp_types::request::GotoImplementationParams};
 use tower_lsp::{jsonrpc::Result, lsp_types::request::GotoImplementationResponse};
@@ -32,14 +37,47 @@ use tree_sitter::Tree;
 
 use crate::{
     Indexer, Repository,
+    accessors::GENERATE_ACCESSORS_KIND,
+    build_scripts::ScriptReferences,
+    code_action::{ProjectImports, QuickFix},
+    db_lock::IndexLock,
     constants::{
-        APP_VERSION, CLASSPATH_MANIFEST_PATH_FRAGMENT, DB_PATH_FRAGMENT, FILE_CACHE_TTL_SECS,
-        INDEX_PATH_FRAGMENT, MANIFEST_PATH_FRAGMENT, VCS_REVISION_PATH_FRAGMENT,
+        APP_VERSION, CLASSPATH_MANIFEST_PATH_FRAGMENT, COPY_REFERENCE_COMMAND, DB_PATH_FRAGMENT,
+        EXPLAIN_RESOLUTION_COMMAND, FILE_CACHE_TTL_SECS, HOME_PATH_ROOT, INDEX_PATH_FRAGMENT,
+        MANIFEST_PATH_FRAGMENT, ORGANIZE_IMPORTS_COMMAND, REFRESH_DEPENDENCIES_COMMAND,
+        SINGLE_FILE_DB_FILE, VCS_REVISION_PATH_FRAGMENT, WORKSPACE_PATH_ROOT, get_cache_dir,
     },
+    context::FileContext,
     enums::ResolvedSymbol,
     generic_resolution::{build_type_bindings, parse_type_ref, substitute_type_vars},
+    gradle_tasks::is_gradle_script,
+    implement_members::{MissingMember, OVERRIDE_METHODS_KIND, UNIMPLEMENTED_ABSTRACT_METHODS_CODE},
+    index_status::{
+        IndexingSettings, IndexingStatus, MAX_CONCURRENT_PROJECTS, ProjectMetadata,
+        discover_project_roots,
+    },
+    import_preferences::ImportPreferences,
+    indexer::{content_hash, without_workspace_outputs},
     lsp_convert::{AsLspHover, AsLspLocation},
-    models::symbol::Symbol,
+    models::symbol::{Symbol, SymbolMetadata},
+    moved_files::removed_fqns,
+    nullability::{NULLABLE_ANNOTATIONS, nullability_hover},
+    package_mismatch::package_mismatch_diagnostic,
+    project_cycles::ProjectGraphCache,
+    project_root::RootSettings,
+    resolve_trace::{TraceSink, TraceStep, record},
+    resource_bundle::{ResourceFile, ResourceSettings, resources_relative},
+    search_limits::{SearchLimits, within},
+    semantic_tokens::SemanticTokensCache,
+    source_set::is_source_set_visible,
+    spring_xml::{BeanAttribute, is_spring_xml_candidate},
+    supertypes::{SupertypeCache, descendant_tree, method_overrides, type_fqns},
+    suppression::Suppressions,
+    unreachable::unreachable_code,
+    unused::collect_unused_declarations,
+    visibility::AccessContext,
+    workspace_diagnostics::WorkspaceDiagnostics,
+    workspace_symbol::WorkspaceSymbols,
 };
 
 #[derive(Clone)]
@@ -50,7 +88,7 @@ pub struct Backend {
     pub repo: OnceCell<Arc<Repository>>,
 
     indexer: Arc<RwLock<Option<Indexer>>>,
-    workspace_root: Arc<RwLock<Option<PathBuf>>>,
+    pub(crate) workspace_root: Arc<RwLock<Option<PathBuf>>>,
     pub(crate) languages: HashMap<String, Arc<dyn LanguageSupport + Send + Sync>>,
     vcs_handler: Arc<RwLock<Option<Arc<dyn VcsHandler + Send + Sync>>>>,
     last_known_revision: Arc<RwLock<Option<String>>>,
@@ -60,18 +98,69 @@ pub struct Backend {
     /// Caches open document contents to avoid excessive I/O reads.
     pub documents: DashMap<String, (String, Instant)>,
     /// Debounces `didChangeWatchedFiles` to avoid redundant reindexing.
-    debounce_tx: tokio::sync::mpsc::Sender<PathBuf>,
+    pub(crate) debounce_tx: tokio::sync::mpsc::Sender<PathBuf>,
     /// Debounces `textDocument/didChange` to trigger diagnostics after 300 ms of idle.
-    diag_debounce_tx: tokio::sync::mpsc::Sender<Url>,
+    pub(crate) diag_debounce_tx: tokio::sync::mpsc::Sender<Url>,
 
     /// Per-sub-project source-root → classpath JAR mapping.
     /// Empty when the workspace is a single-project build.
-    subproject_classpath: Arc<RwLock<Vec<SubprojectClasspath>>>,
+    pub(crate) subproject_classpath: Arc<RwLock<Vec<SubprojectClasspath>>>,
 
     /// Set to true once the initial indexing pass completes. Diagnostics that rely on
     /// cross-file symbol lookups are suppressed while this is false to avoid bogus errors
     /// from a half-populated index.
-    index_ready: Arc<AtomicBool>,
+    pub(crate) index_ready: Arc<AtomicBool>,
+
+    /// Set when the client gave no workspace root, e.g. for a standalone file.  Files
+    /// are then served from their own parse plus the JDK, without workspace indexing.
+    single_file_mode: Arc<AtomicBool>,
+    /// Write access to the index, when this instance holds it.
+    pub(crate) index_lock: Arc<std::sync::Mutex<Option<IndexLock>>>,
+    /// Set while another instance writes the index: this one neither indexes nor writes.
+    pub(crate) index_reader: Arc<AtomicBool>,
+
+    /// Set when the client accepts `documentChanges` with change annotations, so
+    /// workspace edits can mark risky changes as needing confirmation.
+    pub(crate) change_annotation_support: Arc<AtomicBool>,
+    /// Whether the client accepts file renames in workspace edits.
+    pub(crate) resource_rename_support: Arc<AtomicBool>,
+
+    /// Class and bean attributes of Spring XML context files, per file.
+    pub(crate) spring_beans: Arc<RwLock<HashMap<PathBuf, Vec<BeanAttribute>>>>,
+    /// The types Gradle build scripts mention, per script.
+    pub(crate) build_scripts: Arc<RwLock<HashMap<PathBuf, ScriptReferences>>>,
+    /// Files under the resources directories, with the keys of `.properties` files.
+    pub(crate) resources: Arc<RwLock<HashMap<PathBuf, ResourceFile>>>,
+    /// Whether unknown resource bundle keys are reported.
+    pub(crate) resource_settings: Arc<RwLock<ResourceSettings>>,
+    /// How long member resolution may search before answering what it has.
+    pub(crate) search_limits: Arc<RwLock<SearchLimits>>,
+
+    /// Indexing progress of each project in the workspace, for `lspintar/indexStatus`.
+    pub(crate) project_status: Arc<RwLock<Vec<ProjectMetadata>>>,
+    /// Woken whenever a project's indexing status changes, for requests waiting on it.
+    pub(crate) index_progress: Arc<Notify>,
+    /// What requests do while their project is being indexed.
+    pub(crate) indexing_settings: Arc<RwLock<IndexingSettings>>,
+    /// How candidate types sharing a simple name are ordered.
+    pub(crate) import_preferences: Arc<RwLock<ImportPreferences>>,
+
+    /// Ancestors of each type walked so far, dropped as the files behind them change.
+    pub(crate) supertypes: Arc<SupertypeCache>,
+
+    /// Diagnostics of every indexed file, for `workspace/diagnostic`.
+    pub(crate) workspace_diagnostics: Arc<WorkspaceDiagnostics>,
+    /// Name search over project types and methods, rebuilt after files are re-indexed.
+    pub(crate) workspace_symbols: Arc<WorkspaceSymbols>,
+    /// Semantic tokens last sent for each open document, for delta and range requests.
+    pub(crate) semantic_tokens: Arc<SemanticTokensCache>,
+    /// Sub-project dependencies of the workspace's build files.
+    pub(crate) project_graph: Arc<ProjectGraphCache>,
+    /// What each project source file imports, for ranking import candidates.
+    pub(crate) project_imports: Arc<ProjectImports>,
+    /// Set while `lspintar.explainResolution` trace mode is on: definition requests
+    /// taking a fallback then report it with `window/showMessage`.
+    pub(crate) explain_resolution: Arc<AtomicBool>,
 }
 
 /// Java primitive types and keywords that are never unresolved.
@@ -79,6 +168,16 @@ const TYPE_REF_SKIP_LIST: &[&str] = &[
     "boolean", "byte", "char", "double", "float", "int", "long", "short", "void", "var",
 ];
 
+/// The functional interfaces of the implicitly imported `java.lang`, with their abstract
+/// method, for when the JDK is not indexed.
+const JAVA_LANG_FUNCTIONAL_INTERFACES: &[(&str, &str)] = &[
+    ("Runnable", "run"),
+    ("Comparable", "compareTo"),
+    ("Iterable", "iterator"),
+    ("AutoCloseable", "close"),
+    ("Readable", "read"),
+];
+
 /// Methods universally available on every Java/Kotlin/Groovy object (java.lang.Object).
 /// Included in the reachable-method set to avoid false-positive method_not_found diagnostics.
 const JAVA_OBJECT_METHODS: &[&str] = &[
@@ -131,7 +230,7 @@ fn is_type_ref_skippable(name: &str, local_types: &[String]) -> bool {
 
 /// Returns true if `(line, col)` is inside a comment node in the parse tree.
 /// Works for any language because all tree-sitter comment node kinds contain "comment".
-fn position_in_comment(tree: &tree_sitter::Tree, line: usize, col: usize) -> bool {
+pub(crate) fn position_in_comment(tree: &tree_sitter::Tree, line: usize, col: usize) -> bool {
     let point = tree_sitter::Point::new(line, col);
     let Some(mut node) = tree.root_node().descendant_for_point_range(point, point) else {
         return false;
@@ -202,26 +301,110 @@ fn is_arg_compatible_with_param(arg_base: &str, param_type: &str) -> bool {
     }
 }
 
+/// True when the cursor is on the name of a method declaration `name` that has a body.
+pub(crate) fn declares_method_with_body(
+    tree: &Tree,
+    content: &str,
+    position: &Position,
+    name: &str,
+    lang: &dyn LanguageSupport,
+) -> bool {
+    let Some(mut node) = lsp_core::ts_helper::get_node_at_position(tree, content, position) else {
+        return false;
+    };
+    while lang.get_kind(&node) != Some(lsp_core::node_kind::NodeKind::Function) {
+        let Some(parent) = node.parent() else {
+            return false;
+        };
+        node = parent;
+    }
+    let on_name = lang.get_ident_range(&node).is_some_and(|r| {
+        r.start.line == position.line
+            && r.start.character <= position.character
+            && position.character <= r.end.character
+    });
+    let mut cursor = node.walk();
+    let has_body = node.child_by_field_name("body").is_some()
+        || node
+            .children(&mut cursor)
+            .any(|c| matches!(c.kind(), "function_body" | "block"));
+    on_name && has_body && lang.get_short_name(&node, content).as_deref() == Some(name)
+}
+
+/// What [`Backend::default_method_implementations`] finds for an interface method
+/// with a body.  Go-to-implementation lists them in this order: locations carry no
+/// label, so the target tells them apart, the overrides pointing at the overriding
+/// method and the inheriting classes at the class name.  The code lens of the method
+/// counts each group under its own label.
+pub(crate) struct DefaultMethodImplementations {
+    pub declaration: Vec<ResolvedSymbol>,
+    /// Methods overriding the default.
+    pub overriding: Vec<ResolvedSymbol>,
+    /// Classes inheriting the default unchanged.
+    pub inheriting: Vec<ResolvedSymbol>,
+}
+
+impl DefaultMethodImplementations {
+    fn into_symbols(self) -> Vec<ResolvedSymbol> {
+        let mut symbols = self.declaration;
+        symbols.extend(self.overriding);
+        symbols.extend(self.inheriting);
+        symbols
+    }
+}
+
+/// `symbols` with those matching what the segment is first: methods for a call,
+/// fields and properties for a variable.  A field and a getter-less method can
+/// share a name; the syntax says which one a chain step means.
+fn prefer_segment_kind(mut symbols: Vec<ResolvedSymbol>, kind: SegmentKind) -> Vec<ResolvedSymbol> {
+    match kind {
+        SegmentKind::Call => symbols.sort_by_key(|s| s.node_kind() != NodeKind::Function),
+        SegmentKind::Variable => symbols.sort_by_key(|s| s.node_kind() == NodeKind::Function),
+        _ => {}
+    }
+    symbols
+}
+
 /// Returns a sort key for completion suggestions.
 /// Lower values appear first:
 ///   0 – local variables / method parameters (most relevant)
 ///   1 – project symbols in the same package as the current file
 ///   2 – project symbols in a different package
 ///   3 – external (JAR) symbols
-fn completion_rank(symbol: &ResolvedSymbol, current_package: Option<&str>) -> u8 {
+fn completion_rank(
+    symbol: &ResolvedSymbol,
+    current_package: Option<&str>,
+    preferences: &ImportPreferences,
+    enclosing_types: &[String],
+) -> (u8, usize, u8) {
+    if is_enclosing_member(symbol, enclosing_types) {
+        return (1, 0, 0);
+    }
     match symbol {
-        ResolvedSymbol::Local { .. } => 0,
+        ResolvedSymbol::Local { .. } => (0, 0, 0),
         ResolvedSymbol::Project(s) => {
             if current_package.is_some_and(|pkg| pkg == s.package_name) {
-                1
+                (1, 0, 0)
             } else {
-                2
+                (2, preferences.package_rank(&s.fully_qualified_name), 0)
             }
         }
-        ResolvedSymbol::External(_) => 3,
+        ResolvedSymbol::External(s) => (
+            2,
+            preferences.package_rank(&s.fully_qualified_name),
+            u8::from(preferences.prefer_project_types),
+        ),
     }
 }
 
+/// Whether `symbol` is declared on the class around the cursor or one of its
+/// supertypes, and so is in scope without a qualifier or an import.
+fn is_enclosing_member(symbol: &ResolvedSymbol, enclosing_types: &[String]) -> bool {
+    symbol
+        .parent_name()
+        .is_some_and(|parent| enclosing_types.iter().any(|t| t == parent))
+}
+
 impl Backend {
     pub fn new(client: tower_lsp::Client) -> Self {
         lsp_logging::init_logging_service(client.clone());
@@ -247,6 +430,27 @@ impl Backend {
             diag_debounce_tx,
             subproject_classpath: Arc::new(RwLock::new(vec![])),
             index_ready: Arc::new(AtomicBool::new(false)),
+            single_file_mode: Arc::new(AtomicBool::new(false)),
+            index_lock: Arc::new(std::sync::Mutex::new(None)),
+            index_reader: Arc::new(AtomicBool::new(false)),
+            change_annotation_support: Arc::new(AtomicBool::new(false)),
+            resource_rename_support: Arc::new(AtomicBool::new(false)),
+            spring_beans: Arc::new(RwLock::new(HashMap::new())),
+            build_scripts: Arc::new(RwLock::new(HashMap::new())),
+            resources: Arc::new(RwLock::new(HashMap::new())),
+            resource_settings: Arc::new(RwLock::new(ResourceSettings::default())),
+            search_limits: Arc::new(RwLock::new(SearchLimits::default())),
+            project_status: Arc::new(RwLock::new(vec![])),
+            index_progress: Arc::new(Notify::new()),
+            indexing_settings: Arc::new(RwLock::new(IndexingSettings::default())),
+            import_preferences: Arc::new(RwLock::new(ImportPreferences::default())),
+            supertypes: Arc::new(SupertypeCache::default()),
+            workspace_diagnostics: Arc::new(WorkspaceDiagnostics::default()),
+            workspace_symbols: Arc::new(WorkspaceSymbols::default()),
+            semantic_tokens: Arc::new(SemanticTokensCache::default()),
+            project_graph: Arc::new(ProjectGraphCache::default()),
+            project_imports: Arc::new(ProjectImports::default()),
+            explain_resolution: Arc::new(AtomicBool::new(false)),
         };
 
         backend.spawn_debounce_task(debounce_rx);
@@ -271,6 +475,10 @@ impl Backend {
                     }
                     _ = tokio::time::sleep(Duration::from_millis(300)), if !pending.is_empty() => {
                         let batch = std::mem::take(&mut pending);
+                        // Caught up on by hash once this instance takes over the index.
+                        if backend.is_index_reader() {
+                            continue;
+                        }
                         let indexer_guard = indexer.read().await;
                         let Some(indexer) = indexer_guard.as_ref().cloned() else { continue };
                         let Some(repo) = repo.get().cloned() else { continue };
@@ -281,24 +489,35 @@ impl Backend {
                             let buffered = Url::from_file_path(&path)
                                 .ok()
                                 .and_then(|uri| backend.documents.get(&uri.to_string()).map(|e| e.0.clone()));
-                            let result = tokio::task::spawn_blocking(move || match buffered {
-                                Some(content) => indexer.index_content(&path_clone, &content),
-                                None => indexer.index_file(&path_clone),
+                            let result = tokio::task::spawn_blocking(move || {
+                                let (indexed, hash) = match buffered {
+                                    Some(content) => (
+                                        indexer.index_content(&path_clone, &content),
+                                        Some(content_hash(content.as_bytes())),
+                                    ),
+                                    None => (
+                                        indexer.index_file(&path_clone),
+                                        std::fs::read(&path_clone).ok().map(|b| content_hash(&b)),
+                                    ),
+                                };
+                                indexed.map(|r| r.map(|(symbols, supers)| (symbols, supers, hash)))
                             }).await;
 
                             match result {
-                                Ok(Ok(Some((symbols, supers)))) => {
-                                    for chunk in symbols.chunks(1000) {
-                                        if let Err(e) = repo.insert_symbols(chunk).await {
-                                            warn!("Failed to insert symbols: {e}");
-                                        }
+                                Ok(Ok(Some((symbols, supers, hash)))) => {
+                                    let before = backend.indexed_symbols(&path.to_string_lossy()).await;
+                                    if let Err(e) = repo
+                                        .replace_file_symbols(&path.to_string_lossy(), &symbols, &supers)
+                                        .await
+                                    {
+                                        warn!("Failed to replace symbols: {e}");
                                     }
-                                    for chunk in supers.chunks(1000) {
-                                        let mappings = chunk.iter()
-                                            .map(|m| (&*m.symbol_fqn, &*m.super_short_name, m.super_fqn.as_deref()))
-                                            .collect::<Vec<_>>();
-                                        if let Err(e) = repo.insert_symbol_super_mappings(mappings).await {
-                                            warn!("Failed to insert mappings: {e}");
+                                    backend.file_reindexed(&path, &before, &symbols);
+
+                                    if let Some(hash) = hash {
+                                        let entry = [(path.to_string_lossy().to_string(), hash)];
+                                        if let Err(e) = repo.upsert_file_hashes(&entry).await {
+                                            warn!("Failed to update file hash: {e}");
                                         }
                                     }
 
@@ -340,318 +559,40 @@ impl Backend {
         });
     }
 
-    #[tracing::instrument(skip_all)]
-    async fn resolve_fqn(
+    /// Implementations of an interface method that has a body (a Java `default` method or
+    /// a Kotlin interface function with a body), across the whole subtype hierarchy:
+    /// the overrides, and the classes that inherit the default unchanged.  Subtypes
+    /// below an override inherit that override, not the default, and are not listed.
+    /// Returns `None` when `interface_fqn` is not an indexed interface.
+    pub(crate) async fn default_method_implementations(
         &self,
-        name: &str,
-        imports: Vec<String>,
-        package_name: Option<String>,
-    ) -> Option<String> {
-        if name.contains('.') {
-            return Some(name.to_string());
-        }
-
-        // Direct import match
-        if let Some(import) = imports
-            .iter()
-            .find(|i| i.split('.').next_back() == Some(name))
-        {
-            return Some(import.clone());
-        }
-
-        // Wildcard import match
-        for import in imports.iter().filter(|i| i.ends_with(".*")) {
-            let tmp_fqn = import.replace("*", name);
-            if (self
-                .repo
-                .get()
-                .ok_or_else(tower_lsp::jsonrpc::Error::internal_error)
-                .ok()?
-                .find_symbol_by_fqn(&tmp_fqn)
-                .await
-                .ok()?)
-            .is_some()
-            {
-                return Some(tmp_fqn);
-            }
-            if let Ok(Some(_)) = self
-                .repo
-                .get()
-                .ok_or_else(tower_lsp::jsonrpc::Error::internal_error)
-                .ok()?
-                .find_external_symbol_by_fqn(&tmp_fqn)
-                .await
-            {
-                return Some(tmp_fqn);
-            }
-        }
-
-        // Package + name fallback
-        let fallback_fqn = package_name
-            .map(|pkg| {
-                if !name.contains(&pkg) {
-                    format!("{}.{}", pkg, name)
-                } else {
-                    name.to_string()
-                }
-            })
-            .unwrap_or_else(|| name.to_string());
-
-        if let Ok(Some(_)) = self
-            .repo
-            .get()
-            .ok_or_else(tower_lsp::jsonrpc::Error::internal_error)
-            .ok()?
-            .find_external_symbol_by_fqn(&fallback_fqn)
-            .await
-        {
-            return Some(fallback_fqn);
-        }
-
-        Some(fallback_fqn)
-    }
-
-    /// Like `resolve_fqn` but returns `None` when the FQN is only a guess.
-    ///
-    /// Used exclusively for `unresolved_symbol` diagnostics where a false positive
-    /// (flagging a valid type as unresolved) is worse than a false negative.
-    ///
-    /// Rules:
-    /// - Already-qualified name (`foo.Bar`) → `Some` always (we trust it).
-    /// - Direct explicit import (`import foo.Bar`) → `Some` always (we trust it).
-    /// - Wildcard import (`import foo.*`) → `Some(foo.Bar)` only when verified in DB.
-    /// - Same-package fallback → `Some(pkg.Bar)` only when verified in project DB.
-    /// - Everything else → `None` (no emit, rather than false positive).
-    async fn resolve_fqn_strict(
-        &self,
-        name: &str,
-        imports: &[String],
-        package_name: Option<String>,
-    ) -> Option<String> {
-        if name.contains('.') {
-            return Some(name.to_string());
-        }
-
-        // Direct non-wildcard import — trust it; outer check will emit if absent from DB.
-        if let Some(import) = imports
-            .iter()
-            .find(|i| !i.ends_with(".*") && i.split('.').next_back() == Some(name))
-        {
-            return Some(import.clone());
-        }
-
+        interface_fqn: &str,
+        method_name: &str,
+        arity: usize,
+    ) -> Option<DefaultMethodImplementations> {
         let repo = self.repo.get()?;
-
-        // Wildcard import match — only return when DB-verified.
-        for import in imports.iter().filter(|i| i.ends_with(".*")) {
-            let tmp_fqn = import.replace("*", name);
-            if repo.find_symbol_by_fqn(&tmp_fqn).await.ok().flatten().is_some() {
-                return Some(tmp_fqn);
-            }
-            if let Ok(Some(_)) = repo.find_external_symbol_by_fqn(&tmp_fqn).await {
-                return Some(tmp_fqn);
-            }
-        }
-
-        // Same-package fallback — only return when found in project DB.
-        if let Some(pkg) = package_name {
-            let fallback = format!("{}.{}", pkg, name);
-            if repo.find_symbol_by_fqn(&fallback).await.ok().flatten().is_some() {
-                return Some(fallback);
-            }
-        }
-
-        // Could not verify — suppress to avoid false positives.
-        None
-    }
-
-    #[tracing::instrument(skip_all)]
-    async fn try_type_member(
-        &self,
-        qualifier: &str,
-        member: &str,
-        imports: &[String],
-        package_name: Option<String>,
-    ) -> Vec<ResolvedSymbol> {
-        let class_fqn = match self
-            .resolve_fqn(qualifier, imports.to_vec(), package_name.clone())
-            .await
-        {
-            Some(fqn) => fqn,
-            None => return vec![],
-        };
-
-        let mut visited = HashSet::new();
-        self.try_members_with_inheritance(
-            &class_fqn,
-            member,
-            &mut visited,
-            imports.to_vec(),
-            package_name,
-        )
-        .await
-    }
-
-    #[tracing::instrument(skip_all)]
-    async fn try_property_access(&self, class_fqn: &str, ident: &str) -> Option<Symbol> {
-        // Try getter
-        let getter_fqn = format!("{}#get{}", class_fqn, capitalize(ident));
-        if let Ok(Some(found)) = self
-            .repo
-            .get()
-            .ok_or_else(tower_lsp::jsonrpc::Error::internal_error)
-            .ok()?
-            .find_symbol_by_fqn(&getter_fqn)
-            .await
-        {
-            return Some(found);
-        }
-
-        // Try boolean getter (isX for boolean properties)
-        let is_getter_fqn = format!("{}#is{}", class_fqn, capitalize(ident));
-        self.repo
-            .get()
-            .ok_or_else(tower_lsp::jsonrpc::Error::internal_error)
-            .ok()?
-            .find_symbol_by_fqn(&is_getter_fqn)
-            .await
-            .ok()
-            .flatten()
-    }
-
-    async fn try_parent_member(
-        &self,
-        type_fqn: &str,
-        member: &str,
-        visited: &mut HashSet<String>,
-        imports: Vec<String>,
-        package_name: Option<String>,
-    ) -> Vec<ResolvedSymbol> {
-        let type_symbol = match self.repo.get() {
-            None => return vec![],
-            Some(repo) => match repo.find_symbol_by_fqn(type_fqn).await {
-                Ok(symbols) => symbols.into_iter().next(),
-                Err(_) => None,
-            },
-        };
-
-        let type_symbol = match type_symbol {
-            Some(s) => s,
-            None => return vec![],
-        };
-
-        let supers = match self.repo.get() {
-            None => return vec![],
-            Some(repo) => match repo
-                .find_supers_by_symbol_fqn(&type_symbol.fully_qualified_name)
-                .await
-            {
-                Ok(symbols) => symbols,
-                Err(_) => return vec![],
-            },
-        };
-
-        for super_name in supers.iter().map(|symbol| &symbol.fully_qualified_name) {
-            let results = self
-                .recurse_try_members_with_inheritance(
-                    super_name,
-                    member,
-                    visited,
-                    imports.clone(),
-                    package_name.clone(),
-                )
-                .await;
-            if !results.is_empty() {
-                return results;
-            }
-        }
-
-        vec![]
-    }
-
-    #[tracing::instrument(skip(self))]
-    async fn try_members_with_inheritance(
-        &self,
-        type_fqn: &str,
-        member: &str,
-        visited: &mut HashSet<String>,
-        imports: Vec<String>,
-        package_name: Option<String>,
-    ) -> Vec<ResolvedSymbol> {
-        if !visited.insert(type_fqn.to_string()) {
-            return vec![];
-        }
-
-        let member_fqn = format!("{}#{}", type_fqn, member);
-
-        // Try direct member
-        if let Some(repo) = self.repo.get()
-            && let Ok(found) = repo.find_symbols_by_fqn(&member_fqn).await
-            && !found.is_empty()
-        {
-            return found.into_iter().map(ResolvedSymbol::Project).collect();
-        }
-
-        if let Some(found) = self.try_property_access(type_fqn, member).await {
-            return vec![ResolvedSymbol::Project(found)];
-        }
-
-        let result = self
-            .try_parent_member(type_fqn, member, visited, imports, package_name)
-            .await;
-        if !result.is_empty() {
-            return result;
-        }
-
-        if let Some(repo) = self.repo.get()
-            && let Ok(Some(found)) = repo.find_external_symbol_by_fqn(&member_fqn).await
-        {
-            tracing::info!("found: {:?}", found);
-            return vec![ResolvedSymbol::External(found)];
+        let interface = repo.find_symbol_by_fqn(interface_fqn).await.ok()??;
+        if interface.symbol_type != "Interface" {
+            return None;
         }
 
-        vec![]
-    }
-
-    #[tracing::instrument(skip(self))]
-    async fn recurse_try_members_with_inheritance(
-        &self,
-        parent_short_name: &str,
-        member: &str,
-        visited: &mut HashSet<String>,
-        imports: Vec<String>,
-        package_name: Option<String>,
-    ) -> Vec<ResolvedSymbol> {
-        tracing::info!("recurse_try_members_with_inheritance");
-        let fqn = match self
-            .resolve_fqn(parent_short_name, imports.clone(), package_name.clone())
+        let declared = repo
+            .find_symbols_by_fqn(&format!("{interface_fqn}#{method_name}"))
             .await
-        {
-            Some(fqn) => fqn,
-            None => return vec![],
-        };
-
-        let resolved_fqn = match self.repo.get() {
-            None => return vec![],
-            Some(repo) => {
-                if let Ok(Some(s)) = repo.find_symbol_by_fqn(&fqn).await {
-                    s.fully_qualified_name
-                } else if let Ok(Some(s)) = repo.find_external_symbol_by_fqn(&fqn).await {
-                    s.fully_qualified_name
-                } else {
-                    return vec![];
-                }
-            }
-        };
-
-        Box::pin(self.try_members_with_inheritance(
-            &resolved_fqn,
-            member,
-            visited,
-            imports,
-            package_name,
-        ))
-        .await
+            .unwrap_or_default();
+        let declaration = self.filter_by_arity(
+            declared.into_iter().map(ResolvedSymbol::Project).collect(),
+            arity,
+        );
+        let tree = descendant_tree(repo, interface_fqn).await;
+        let overrides = method_overrides(repo, &tree, method_name, arity).await;
+        let project = |symbols: Vec<Symbol>| symbols.into_iter().map(ResolvedSymbol::Project);
+
+        Some(DefaultMethodImplementations {
+            declaration,
+            overriding: project(overrides.overriding).collect(),
+            inheriting: project(overrides.inheriting).collect(),
+        })
     }
 
     fn resolved_symbols_to_impl_response(
@@ -676,7 +617,7 @@ impl Backend {
     #[tracing::instrument(skip_all)]
     async fn resolve_type_member_chain(
         &self,
-        qualifier: &str,
+        qualifier: &QualifierChain,
         member: &str,
         lang: &Arc<dyn LanguageSupport + Send + Sync>,
         tree: &Tree,
@@ -685,28 +626,47 @@ impl Backend {
         position: &Position,
         package_name: Option<String>,
     ) -> Vec<ResolvedSymbol> {
-        if let Some(current_type_fqn) = self
-            .walk_member_chain(
-                qualifier,
-                lang,
-                tree,
-                content,
-                imports.clone(),
-                position,
-                package_name,
-            )
-            .await
-        {
-            // Returns all overloads
-            self.try_type_member(&current_type_fqn, member, &imports, None)
-                .await
-        } else {
-            vec![]
+        let limits = self.search_limits.read().await.clone();
+        let walk = Box::pin(self.walk_chain_segments(
+            qualifier,
+            None,
+            lang,
+            tree,
+            content,
+            imports.clone(),
+            position,
+            package_name,
+            &HashMap::new(),
+        ));
+        let current_type_fqn = match within(limits.type_search_timeout_ms, walk).await {
+            Some(Some(fqn)) => fqn,
+            Some(None) => return vec![],
+            None => {
+                warn!(
+                    "resolving the type of {qualifier} took over {} ms; giving up",
+                    limits.type_search_timeout_ms
+                );
+                return vec![];
+            }
+        };
+        // Returns all overloads
+        let members = self.try_type_member(&current_type_fqn, member, &imports, None);
+        match within(limits.member_search_timeout_ms, members).await {
+            Some(symbols) => symbols,
+            None => {
+                warn!(
+                    "searching {current_type_fqn} for {member} took over {} ms; answering the type",
+                    limits.member_search_timeout_ms
+                );
+                self.fqn_to_symbols(current_type_fqn)
+                    .await
+                    .unwrap_or_default()
+            }
         }
     }
 
     #[allow(clippy::too_many_arguments)]
-    async fn walk_member_chain(
+    pub(crate) async fn walk_member_chain(
         &self,
         qualifier: &str,
         lang: &Arc<dyn LanguageSupport + Send + Sync>,
@@ -752,22 +712,82 @@ impl Backend {
             (qualifier, None)
         };
 
-        let parts: Vec<&str> = chain_part.split('#').collect();
-        if parts.is_empty() {
-            return None;
-        }
+        Box::pin(self.walk_chain_segments(
+            &QualifierChain::parse(chain_part),
+            lambda_body_info,
+            lang,
+            tree,
+            content,
+            imports,
+            position,
+            package_name,
+            scope_overrides,
+        ))
+        .await
+    }
+
+    /// Walks `chain` segment by segment, returning the FQN of the type the last
+    /// segment evaluates to.  `lambda_body_info` is the `__lb__` suffix of a
+    /// string-encoded chain, applied to the last step.
+    #[allow(clippy::too_many_arguments)]
+    async fn walk_chain_segments(
+        &self,
+        chain: &QualifierChain,
+        lambda_body_info: Option<&str>,
+        lang: &Arc<dyn LanguageSupport + Send + Sync>,
+        tree: &Tree,
+        content: &str,
+        imports: Vec<String>,
+        position: &Position,
+        package_name: Option<String>,
+        scope_overrides: &HashMap<String, String>,
+    ) -> Option<String> {
+        let first = chain.segments.first()?;
 
         // Resolve the base variable's type (may carry generic args like "List<String>").
         // find_variable_type may return two kinds of special strings:
         //   "Foo#bar"           — chain expression; resolve recursively.
         //   "__cp__:..."        — closure/lambda param; resolve from method signature.
         let base_type_str = {
-            let raw = if let Some(overridden) = scope_overrides.get(parts[0]) {
-                overridden.clone()
-            } else {
-                let vtype = lang.find_variable_type(tree, content, parts[0], position);
-                tracing::debug!("[LSPINTAR_COMPLETION] find_variable_type({:?}) = {:?}", parts[0], vtype);
-                vtype.unwrap_or_else(|| parts[0].to_string())
+            let raw = match first.kind {
+                SegmentKind::Type => first.name.clone(),
+                // Kotlin's `super<Interface>` names the supertype meant.
+                SegmentKind::Super if first.name.contains('<') => {
+                    let (_, args) = parse_type_ref(&first.name);
+                    args.into_iter().next()?
+                }
+                SegmentKind::Super => {
+                    self.super_type_of_this(
+                        lang,
+                        tree,
+                        content,
+                        &imports,
+                        position,
+                        package_name.clone(),
+                    )
+                    .await?
+                }
+                SegmentKind::Call => {
+                    self.bare_call_type(
+                        &first.name,
+                        lang,
+                        tree,
+                        content,
+                        &imports,
+                        position,
+                        package_name.clone(),
+                    )
+                    .await
+                }
+                SegmentKind::Variable | SegmentKind::This => {
+                    if let Some(overridden) = scope_overrides.get(&first.name) {
+                        overridden.clone()
+                    } else {
+                        let vtype = lang.find_variable_type(tree, content, &first.name, position);
+                        tracing::debug!("[LSPINTAR_COMPLETION] find_variable_type({:?}) = {:?}", first.name, vtype);
+                        vtype.unwrap_or_else(|| first.name.clone())
+                    }
+                }
             };
             if raw.starts_with("__cp__:") {
                 Box::pin(self.resolve_closure_param_type(
@@ -802,13 +822,15 @@ impl Backend {
         // Split into name + receiver type args: "List<String>" → ("List", ["String"])
         let (base_name, mut current_type_args) = parse_type_ref(&base_type_str);
 
-        let mut current_type_fqn = self
+        let current_type_fqn = self
             .resolve_fqn(&base_name, imports.clone(), package_name.clone())
             .await?;
+        let mut current_type_fqn = self.unalias_fqn(current_type_fqn).await;
 
-        let parts_len = parts.len();
-        for (step_idx, part) in parts[1..].iter().enumerate() {
-            let is_last_step = step_idx == parts_len - 2;
+        let steps = &chain.segments[1..];
+        for (step_idx, segment) in steps.iter().enumerate() {
+            let is_last_step = step_idx + 1 == steps.len();
+            let part = segment.name.as_str();
 
             // Parse optional call-site type args encoded in the step.
             // Format: "method__ca__TypeArg1,TypeArg2" encodes explicit call-site type arguments
@@ -832,7 +854,10 @@ impl Backend {
             let symbols = self
                 .try_type_member(&current_type_fqn, method_name, &imports, None)
                 .await;
-            let resolved = match symbols.into_iter().next() {
+            let resolved = match prefer_segment_kind(symbols, segment.kind)
+                .into_iter()
+                .next()
+            {
                 Some(s) => s,
                 None => return Some("java.lang.Object".to_string()),
             };
@@ -911,6 +936,70 @@ impl Backend {
         Some(current_type_fqn)
     }
 
+    /// The type `super` stands for in the class enclosing `position`: its superclass
+    /// when it is indexed, the class itself otherwise, so inherited members still
+    /// resolve.
+    pub(crate) async fn super_type_of_this(
+        &self,
+        lang: &Arc<dyn LanguageSupport + Send + Sync>,
+        tree: &Tree,
+        content: &str,
+        imports: &[String],
+        position: &Position,
+        package_name: Option<String>,
+    ) -> Option<String> {
+        let this_type = lang.find_variable_type(tree, content, "this", position)?;
+        let this_fqn = self
+            .resolve_fqn(&this_type, imports.to_vec(), package_name)
+            .await?;
+        Some(self.superclass_fqn(&this_fqn).await.unwrap_or(this_fqn))
+    }
+
+    /// The indexed superclass of `fqn`, or its first indexed supertype when it only
+    /// implements interfaces.
+    pub(crate) async fn superclass_fqn(&self, fqn: &str) -> Option<String> {
+        let supers = self
+            .repo
+            .get()?
+            .find_supers_by_symbol_fqn(fqn)
+            .await
+            .unwrap_or_default();
+        let superclass = supers
+            .iter()
+            .find(|s| s.symbol_type == "Class")
+            .or(supers.first())?;
+        Some(superclass.fully_qualified_name.clone())
+    }
+
+    /// The type of an unqualified call at the head of a chain: the return type of
+    /// the enclosing class's method of that name, or — for `Foo()` in Kotlin, with
+    /// no such method — the constructed type.
+    #[allow(clippy::too_many_arguments)]
+    async fn bare_call_type(
+        &self,
+        name: &str,
+        lang: &Arc<dyn LanguageSupport + Send + Sync>,
+        tree: &Tree,
+        content: &str,
+        imports: &[String],
+        position: &Position,
+        package_name: Option<String>,
+    ) -> String {
+        if let Some(this_type) = lang.find_variable_type(tree, content, "this", position) {
+            let methods = self
+                .try_type_member(&this_type, name, imports, package_name)
+                .await;
+            let return_type = prefer_segment_kind(methods, SegmentKind::Call)
+                .into_iter()
+                .find(|s| s.node_kind() == NodeKind::Function)
+                .and_then(|s| s.metadata().and_then(|m| m.return_type.clone()));
+            if let Some(return_type) = return_type {
+                return return_type;
+            }
+        }
+        name.to_string()
+    }
+
     /// Applies InferLambdaReturnType: given a partially-substituted return type that
     /// may still have unbound type variables, tries to bind the output type variable
     /// of the functional parameter using the lambda body's return type.
@@ -1081,17 +1170,17 @@ impl Backend {
     }
 
     #[allow(clippy::too_many_arguments)]
-    /// Returns the JAR paths that are on the classpath of the sub-project owning `file`.
-    /// Returns an empty vec for single-project workspaces or when the file cannot be matched.
-    async fn jar_paths_for_file(&self, file: &Path) -> Vec<String> {
+    /// Returns the JAR paths that are on the classpath of the sub-project owning `file`,
+    /// including the test-only JARs for test sources.  Returns an empty vec for
+    /// single-project workspaces or when the file cannot be matched.
+    pub(crate) async fn jar_paths_for_file(&self, file: &Path) -> Vec<String> {
         let classpath = self.subproject_classpath.read().await;
         classpath
             .iter()
             .find(|entry| entry.contains_file(file))
             .map(|entry| {
                 entry
-                    .jar_paths
-                    .iter()
+                    .jar_paths_for(file)
                     .map(|p| p.to_string_lossy().into_owned())
                     .collect()
             })
@@ -1149,22 +1238,72 @@ impl Backend {
         }
     }
 
-    async fn complete_by_prefix(&self, prefix: &str, jar_paths: &[String]) -> Vec<ResolvedSymbol> {
+    /// Members starting with `prefix` declared on any of `enclosing_types`, the class
+    /// around the cursor followed by its supertypes.
+    async fn complete_enclosing_members(
+        &self,
+        prefix: &str,
+        enclosing_types: &[String],
+        jar_paths: &[String],
+    ) -> Vec<ResolvedSymbol> {
+        let Some(repo) = self.repo.get() else {
+            return vec![];
+        };
+        let mut symbols: Vec<ResolvedSymbol> = vec![];
+        for fqn in enclosing_types {
+            symbols.extend(
+                repo.find_symbols_by_parent_name(fqn)
+                    .await
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|s| s.short_name.starts_with(prefix))
+                    .map(ResolvedSymbol::Project),
+            );
+            symbols.extend(
+                repo.find_external_symbols_by_parent_name_and_jars(fqn, jar_paths)
+                    .await
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|s| s.short_name.starts_with(prefix))
+                    .map(ResolvedSymbol::External),
+            );
+        }
+        symbols
+    }
+
+    /// Project and classpath symbols starting with `prefix`, as seen from the file at
+    /// `from`: declarations in source sets it cannot see (tests, from main code) are
+    /// left out, so they neither show up nor shadow the JAR copy of a class.
+    async fn complete_by_prefix(
+        &self,
+        prefix: &str,
+        jar_paths: &[String],
+        from: &Path,
+    ) -> Vec<ResolvedSymbol> {
         let Some(repo) = self.repo.get() else {
             return vec![];
         };
 
         let mut symbols: Vec<ResolvedSymbol> = vec![];
 
-        if let Ok(project_syms) = repo.find_symbols_by_prefix(prefix).await {
+        let mut project_fqns: HashSet<String> = HashSet::new();
+        if let Ok(mut project_syms) = repo.find_symbols_by_prefix(prefix).await {
+            project_syms.retain(|s| is_source_set_visible(from, Path::new(&s.file_path)));
+            project_fqns.extend(project_syms.iter().map(|s| s.fully_qualified_name.clone()));
             symbols.extend(project_syms.into_iter().map(ResolvedSymbol::Project));
         }
 
+        // A JAR copy of a workspace class is shadowed by its sources.
         if let Ok(ext_syms) = repo
             .find_external_symbols_by_prefix_and_jars(prefix, jar_paths)
             .await
         {
-            symbols.extend(ext_syms.into_iter().map(ResolvedSymbol::External));
+            symbols.extend(
+                ext_syms
+                    .into_iter()
+                    .filter(|s| !project_fqns.contains(&s.fully_qualified_name))
+                    .map(ResolvedSymbol::External),
+            );
         }
 
         symbols
@@ -1204,7 +1343,8 @@ impl Backend {
         for (arg, position) in &call_args {
             let arg_type =
                 if let Some(literal_type) = lang.get_literal_type(tree, content, position) {
-                    literal_type
+                    // Parameter types are compared without their type arguments.
+                    strip_type_args(&literal_type).to_string()
                 } else {
                     lang.find_variable_type(tree, content, arg, position)
                         .unwrap_or_else(|| arg.clone())
@@ -1280,9 +1420,31 @@ impl Backend {
             .collect()
     }
 
+    /// The declarations the symbol at `params` resolves to, library ones taken from
+    /// the JARs on the classpath of the file's sub-project.
     pub(crate) async fn resolve_symbol_at_position(
         &self,
         params: &TextDocumentPositionParams,
+    ) -> Result<Vec<ResolvedSymbol>> {
+        self.traced_symbol_at_position(params, None).await
+    }
+
+    /// [`Backend::resolve_symbol_at_position`], recording the lookups it makes in
+    /// `trace`.
+    pub(crate) async fn traced_symbol_at_position(
+        &self,
+        params: &TextDocumentPositionParams,
+        trace: Option<&TraceSink>,
+    ) -> Result<Vec<ResolvedSymbol>> {
+        let symbols = self.resolve_symbol_in_any_version(params, trace).await?;
+        let path = PathBuf::from_str(params.text_document.uri.path()).unwrap();
+        Ok(self.on_file_classpath(&path, symbols).await)
+    }
+
+    async fn resolve_symbol_in_any_version(
+        &self,
+        params: &TextDocumentPositionParams,
+        trace: Option<&TraceSink>,
     ) -> Result<Vec<ResolvedSymbol>> {
         let path = PathBuf::from_str(params.text_document.uri.path()).unwrap();
 
@@ -1299,29 +1461,88 @@ impl Backend {
             .parse(&path)
             .ok_or_else(|| tower_lsp::jsonrpc::Error::invalid_params("Failed to parse file"))?;
 
-        let mut imports = lang.get_imports(&tree, &content);
-        for imp in lang.get_implicit_imports() {
-            if !imports.contains(&imp) {
-                imports.push(imp);
+        let context = FileContext::new(lang.as_ref(), &tree, &content);
+        let declared_imports = context.declared_imports().to_vec();
+        let FileContext {
+            imports,
+            package_name,
+            ..
+        } = context;
+        let position = params.position;
+
+        if let Some(delegation) = lang.find_constructor_delegation(&tree, &content, &position) {
+            let t = Instant::now();
+            let constructors = self
+                .resolve_constructor_delegation(
+                    &delegation,
+                    lang,
+                    &tree,
+                    &content,
+                    &position,
+                    &imports,
+                    package_name.clone(),
+                )
+                .await;
+            record(trace, || {
+                TraceStep::found(
+                    "constructorDelegation",
+                    t,
+                    constructors.as_deref().unwrap_or_default(),
+                )
+            });
+            if let Some(constructors) = constructors {
+                return Ok(constructors);
             }
         }
-        let package_name = lang.get_package_name(&tree, &content);
-        let position = params.position;
 
         if let Some(type_name) = lang.get_type_at_position(tree.root_node(), &content, &position) {
+            if let Some(symbols) = self
+                .traced_ambiguous_type(
+                    &type_name,
+                    &declared_imports,
+                    package_name.as_deref(),
+                    trace,
+                )
+                .await
+            {
+                return Ok(symbols);
+            }
             let fqn = self
-                .resolve_fqn(&type_name, imports, package_name)
+                .traced_fqn(&type_name, imports, package_name, trace)
                 .await
                 .ok_or_else(|| {
                     tower_lsp::jsonrpc::Error::invalid_params("Failed to find FQN by location")
                 })?;
 
-            return self.fqn_to_symbols(fqn).await;
+            return self.traced_fqn_to_symbols(fqn, trace).await;
         }
 
         if let Some((ident, qualifier)) = lang.find_ident_at_position(&tree, &content, &position) {
             match qualifier {
                 Some(q) => {
+                    let t = Instant::now();
+                    let lambda = self
+                        .resolve_lambda_invocation(
+                            &q.to_string(),
+                            &ident,
+                            lang.as_ref(),
+                            &tree,
+                            &content,
+                            &position,
+                            &imports,
+                            package_name.clone(),
+                            &params.text_document.uri,
+                        )
+                        .await;
+                    record(trace, || {
+                        TraceStep::found("lambda", t, lambda.as_slice())
+                            .with_detail(format!("on {q}"))
+                    });
+                    if let Some(lambda) = lambda {
+                        return Ok(vec![lambda]);
+                    }
+
+                    let t = Instant::now();
                     let symbols = self
                         .resolve_type_member_chain(
                             &q,
@@ -1334,6 +1555,9 @@ impl Backend {
                             package_name.clone(),
                         )
                         .await;
+                    record(trace, || {
+                        TraceStep::found("member", t, &symbols).with_detail(format!("on {q}"))
+                    });
 
                     if symbols.is_empty() {
                         return Err(tower_lsp::jsonrpc::Error::invalid_params(format!(
@@ -1341,12 +1565,20 @@ impl Backend {
                         )));
                     }
 
+                    let file_path = path.to_string_lossy();
+                    let access = AccessContext {
+                        file_path: &file_path,
+                        package: package_name.as_deref().unwrap_or_default(),
+                    };
+                    let symbols = self.prefer_accessible(symbols, &access).await;
+
                     if symbols.len() == 1 {
                         return Ok(symbols);
                     }
 
-                    if let Some(args) = lang.extract_call_arguments(&tree, &content, &position)
-                        && let Some(symbol) = self
+                    if let Some(args) = lang.extract_call_arguments(&tree, &content, &position) {
+                        let t = Instant::now();
+                        let symbol = self
                             .select_best_overload(
                                 symbols.clone(),
                                 args,
@@ -1356,27 +1588,44 @@ impl Backend {
                                 &imports,
                                 package_name,
                             )
-                            .await
-                    {
-                        return Ok(vec![symbol]);
+                            .await;
+                        record(trace, || TraceStep::found("overload", t, symbol.as_slice()));
+                        if let Some(symbol) = symbol {
+                            return Ok(vec![symbol]);
+                        }
                     }
 
                     Ok(symbols)
                 }
                 None => {
-                    if let Some((var_type, var_pos)) =
-                        lang.find_variable_declaration(&tree, &content, &ident, &position)
-                    {
-                        return Ok(vec![ResolvedSymbol::Local {
+                    let t = Instant::now();
+                    let local = lang
+                        .find_variable_declaration(&tree, &content, &ident, &position)
+                        .map(|(var_type, var_pos)| ResolvedSymbol::Local {
                             name: ident.clone(),
                             var_type,
                             uri: params.text_document.uri.clone(),
                             position: var_pos,
-                        }]);
+                        });
+                    record(trace, || TraceStep::found("local", t, local.as_slice()));
+                    if let Some(local) = local {
+                        return Ok(vec![local]);
+                    }
+
+                    if let Some(symbols) = self
+                        .traced_ambiguous_type(
+                            &ident,
+                            &declared_imports,
+                            package_name.as_deref(),
+                            trace,
+                        )
+                        .await
+                    {
+                        return Ok(symbols);
                     }
 
                     let fqn = self
-                        .resolve_fqn(&ident, imports, package_name)
+                        .traced_fqn(&ident, imports, package_name, trace)
                         .await
                         .ok_or_else(|| {
                             tower_lsp::jsonrpc::Error::invalid_params(
@@ -1384,7 +1633,7 @@ impl Backend {
                             )
                         })?;
 
-                    self.fqn_to_symbols(fqn).await
+                    self.traced_fqn_to_symbols(fqn, trace).await
                 }
             }
         } else {
@@ -1394,19 +1643,108 @@ impl Backend {
         }
     }
 
+    /// Resolves `receiver.method` to the lambda (or method reference) `receiver` was
+    /// initialised with, when `receiver` is a local of functional-interface type and
+    /// `method` is that interface's single abstract method.
+    #[allow(clippy::too_many_arguments)]
+    async fn resolve_lambda_invocation(
+        &self,
+        receiver: &str,
+        method: &str,
+        lang: &dyn LanguageSupport,
+        tree: &Tree,
+        content: &str,
+        position: &Position,
+        imports: &[String],
+        package_name: Option<String>,
+        uri: &Url,
+    ) -> Option<ResolvedSymbol> {
+        if JAVA_OBJECT_METHODS.contains(&method) {
+            return None;
+        }
+        let (var_type, decl_position) =
+            lang.find_variable_declaration(tree, content, receiver, position)?;
+        let (lambda_range, sam_type) = lang.find_lambda_initializer(tree, content, &decl_position)?;
+        let type_name = var_type.or(sam_type);
+        if let Some(type_name) = &type_name
+            && !self
+                .is_single_abstract_method(type_name, method, imports, package_name)
+                .await
+        {
+            return None;
+        }
+        Some(ResolvedSymbol::Local {
+            uri: uri.clone(),
+            position: lambda_range.start,
+            name: receiver.to_string(),
+            var_type: type_name,
+        })
+    }
+
+    /// True when `method` is the abstract method of the functional interface `type_name`.
+    /// A type that resolves to nothing is not taken for one.  Resolved types without
+    /// indexed abstract methods (e.g. imported JDK interfaces without sources) are
+    /// given the benefit of the doubt: a lambda implements exactly one method, and calls
+    /// of `java.lang.Object` methods are excluded by the caller.
+    async fn is_single_abstract_method(
+        &self,
+        type_name: &str,
+        method: &str,
+        imports: &[String],
+        package_name: Option<String>,
+    ) -> bool {
+        let base = type_name.split('<').next().unwrap_or(type_name).trim();
+        let Some(fqn) = self.resolve_fqn_strict(base, imports, package_name).await else {
+            return JAVA_LANG_FUNCTIONAL_INTERFACES.contains(&(base, method));
+        };
+        let abstract_methods = self.abstract_methods(&fqn).await;
+        abstract_methods.is_empty() || abstract_methods.iter().any(|sig| sig.name == method)
+    }
+
     #[tracing::instrument(skip_all)]
-    async fn fqn_to_symbols(&self, fqn: String) -> Result<Vec<ResolvedSymbol>> {
+    pub(crate) async fn fqn_to_symbols(&self, fqn: String) -> Result<Vec<ResolvedSymbol>> {
+        self.traced_fqn_to_symbols(fqn, None).await
+    }
+
+    /// [`Backend::fqn_to_symbols`], recording the project and library lookups in
+    /// `trace`.
+    async fn traced_fqn_to_symbols(
+        &self,
+        fqn: String,
+        trace: Option<&TraceSink>,
+    ) -> Result<Vec<ResolvedSymbol>> {
         let repo = self
             .repo
             .get()
             .ok_or_else(tower_lsp::jsonrpc::Error::internal_error)?;
 
-        if let Ok(Some(symbol)) = repo.find_symbol_by_fqn(&fqn).await {
-            return Ok(vec![ResolvedSymbol::Project(symbol)]);
-        }
-        let external_symbol = repo
-            .find_external_symbol_by_fqn(&fqn)
+        let t = Instant::now();
+        let project = repo
+            .find_symbol_by_fqn(&fqn)
             .await
+            .ok()
+            .flatten()
+            .map(ResolvedSymbol::Project);
+        record(trace, || {
+            TraceStep::found("project", t, project.as_slice()).with_detail(fqn.clone())
+        });
+        if let Some(symbol) = project {
+            return Ok(vec![symbol]);
+        }
+        let t = Instant::now();
+        let external_symbol = repo.find_external_symbol_by_fqn(&fqn).await;
+        record(trace, || match &external_symbol {
+            Ok(symbol) => {
+                let found: Vec<ResolvedSymbol> = symbol
+                    .iter()
+                    .cloned()
+                    .map(ResolvedSymbol::External)
+                    .collect();
+                TraceStep::found("external", t, &found).with_detail(fqn.clone())
+            }
+            Err(e) => TraceStep::failed("external", t, e.to_string()),
+        });
+        let external_symbol = external_symbol
             .map_err(|e| {
                 tower_lsp::jsonrpc::Error::invalid_params(format!("Failed to find symbol: {}", e))
             })?
@@ -1416,6 +1754,40 @@ impl Backend {
         Ok(vec![ResolvedSymbol::External(external_symbol)])
     }
 
+    /// [`Backend::resolve_ambiguous_type`], recorded in `trace` when it answers.
+    async fn traced_ambiguous_type(
+        &self,
+        name: &str,
+        declared_imports: &[String],
+        package_name: Option<&str>,
+        trace: Option<&TraceSink>,
+    ) -> Option<Vec<ResolvedSymbol>> {
+        let t = Instant::now();
+        let symbols = self
+            .resolve_ambiguous_type(name, declared_imports, package_name)
+            .await?;
+        record(trace, || {
+            TraceStep::found("ambiguousImport", t, &symbols).with_detail(name.to_string())
+        });
+        Some(symbols)
+    }
+
+    /// [`Backend::resolve_fqn`], recorded in `trace`.
+    async fn traced_fqn(
+        &self,
+        name: &str,
+        imports: Vec<String>,
+        package_name: Option<String>,
+        trace: Option<&TraceSink>,
+    ) -> Option<String> {
+        let t = Instant::now();
+        let fqn = self.resolve_fqn(name, imports, package_name).await;
+        record(trace, || {
+            TraceStep::resolved("imports", t, fqn.as_deref()).with_detail(name.to_string())
+        });
+        fqn
+    }
+
     fn is_cache_dir(&self, path: Option<&Path>) -> bool {
         path.map(|p| {
             p.components()
@@ -1425,6 +1797,69 @@ impl Backend {
         false
     }
 
+    /// Single-file mode: indexes the JDK sources under `JAVA_HOME` so builtin types
+    /// resolve.  The index is shared by every standalone session, so this only runs
+    /// when it has no JDK yet.  `index_ready` stays false: without the rest of the
+    /// project, cross-file diagnostics would only report false positives.
+    async fn index_standalone_jdk(&self) {
+        if self.is_index_reader() {
+            return;
+        }
+        let Some(repo) = self.repo.get() else {
+            lsp_error!("Failed to initialize index repository");
+            return;
+        };
+        if let Ok(Some(_)) = repo.find_external_symbol_by_fqn("java.lang.Object").await {
+            return;
+        }
+        let Some(src_zip) = find_java_home_sources() else {
+            lsp_info!("JAVA_HOME has no JDK sources; JDK types will not resolve");
+            return;
+        };
+
+        let mut indexer = Indexer::new(Arc::clone(repo));
+        self.languages.iter().for_each(|(k, v)| {
+            indexer.register_language(k, v.clone());
+        });
+
+        let token = format!("idx-jdk-{}", uuid::Uuid::new_v4());
+        let token_end = token.clone();
+        lsp_progress_begin!(&token, "Indexing JDK...");
+        indexer
+            .index_external_deps(
+                vec![(None, Some(src_zip))],
+                move |completed, total| {
+                    lsp_progress!(
+                        &token,
+                        &format!("Indexing JDK ({}/{})", completed, total),
+                        (completed as f32 / total as f32) * 100.0
+                    );
+                },
+                |_, _| {},
+            )
+            .await;
+        lsp_progress_end!(&token_end);
+    }
+
+    /// Resolves `name` as written in the file at `path`, against that file's imports
+    /// and package.
+    pub(crate) async fn resolve_fqn_in_file(&self, path: &Path, name: &str) -> Option<String> {
+        let content = self.document_text(&Url::from_file_path(path).ok()?)?;
+        let lang = self.languages.get(path.extension()?.to_str()?)?;
+        let (tree, _) = lang.parse_str(&content)?;
+        let context = FileContext::new(lang.as_ref(), &tree, &content);
+        self.resolve_fqn(name, context.imports, context.package_name)
+            .await
+    }
+
+    /// The text of `uri`: the open document when there is one, otherwise the file on disk.
+    pub(crate) fn document_text(&self, uri: &Url) -> Option<String> {
+        match self.documents.get(&uri.to_string()) {
+            Some(entry) => Some(entry.0.clone()),
+            None => std::fs::read_to_string(uri.to_file_path().ok()?).ok(),
+        }
+    }
+
     fn get_line_at(&self, pos: &TextDocumentPositionParams) -> Option<String> {
         let uri = pos.text_document.uri.to_string();
         let ttl = Duration::from_secs(FILE_CACHE_TTL_SECS);
@@ -1449,7 +1884,14 @@ impl Backend {
         line
     }
 
-    async fn handle_build_file_changed(&self, root: &Path) {
+    /// Re-resolves the build's dependencies and diffs them against the deps manifest:
+    /// newly added JARs are indexed and removed ones pruned from the index and the
+    /// manifest.  Returns the number of added and removed JARs, or `None` when the
+    /// dependencies could not be resolved.
+    pub(crate) async fn handle_build_file_changed(&self, root: &Path) -> Option<(usize, usize)> {
+        if self.is_index_reader() {
+            return None;
+        }
         let manifest_path = root.join(MANIFEST_PATH_FRAGMENT);
 
         let previous: Vec<(Option<PathBuf>, Option<PathBuf>)> = tokio::fs::read(&manifest_path)
@@ -1460,7 +1902,7 @@ impl Backend {
 
         let build_tool_guard = self.build_tool.read().await;
         let Some(build_tool) = build_tool_guard.as_ref().cloned() else {
-            return;
+            return None;
         };
         drop(build_tool_guard);
 
@@ -1469,8 +1911,9 @@ impl Backend {
             tokio::task::spawn_blocking(move || build_tool.get_dependency_paths(&root_clone)).await
         else {
             lsp_error!("Failed to resolve dependencies");
-            return;
+            return None;
         };
+        let current = without_workspace_outputs(current, root);
 
         let previous_jars: HashSet<PathBuf> =
             previous.iter().filter_map(|(b, _)| b.clone()).collect();
@@ -1485,7 +1928,7 @@ impl Backend {
             .collect();
 
         let Some(repo) = self.repo.get().cloned() else {
-            return;
+            return None;
         };
 
         for jar in &removed {
@@ -1497,11 +1940,10 @@ impl Backend {
             }
         }
 
+        let added_count = added.len();
         if !added.is_empty() {
             let indexer_guard = self.indexer.read().await;
-            let Some(indexer) = indexer_guard.as_ref().cloned() else {
-                return;
-            };
+            let indexer = indexer_guard.as_ref().cloned()?;
             drop(indexer_guard);
             indexer
                 .index_external_deps(added, |_, _| {}, |_, _| {})
@@ -1519,6 +1961,7 @@ impl Backend {
             drop(build_tool_guard);
             self.write_classpath_manifest(root, &bt).await;
         }
+        Some((added_count, removed.len()))
     }
 
     async fn write_classpath_manifest(
@@ -1558,6 +2001,61 @@ impl Backend {
         }
     }
 
+    /// Re-indexes only source files whose content no longer matches the hash they
+    /// were indexed with.  Unlike a VCS diff this also catches uncommitted edits and
+    /// works in projects without version control.
+    async fn reindex_stale_files(&self, indexer: &Indexer, root: &Path) {
+        let Some(repo) = self.repo.get() else {
+            return;
+        };
+        match indexer.find_stale_files(root).await {
+            Ok((changed, removed)) => {
+                for file in &removed {
+                    let before = self.indexed_symbols(file).await;
+                    if let Err(e) = repo.delete_symbols_for_file(file).await {
+                        lsp_error!("Failed to remove symbols for {file}: {e}");
+                    }
+                    self.file_removed(Path::new(file), &before);
+                    if let Err(e) = repo.delete_file_hash(file).await {
+                        lsp_error!("Failed to remove file hash for {file}: {e}");
+                    }
+                }
+                if !changed.is_empty() {
+                    lsp_info!(
+                        "IncrementalOpen: re-indexing {} changed file(s)",
+                        changed.len()
+                    );
+                    for path in changed {
+                        let _ = self.debounce_tx.send(path).await;
+                    }
+                }
+            }
+            Err(e) => {
+                lsp_error!("Failed to compare file hashes for incremental open: {e}");
+            }
+        }
+    }
+
+    /// Brings the index up to date after taking it over from another instance: what
+    /// that instance did not index before exiting, and what this one skipped while
+    /// it was a reader.
+    pub(crate) async fn catch_up_index(&self) {
+        if self.single_file_mode.load(Ordering::Acquire) {
+            self.index_standalone_jdk().await;
+            return;
+        }
+        let Some(root) = self.workspace_root.read().await.clone() else {
+            return;
+        };
+        let Some(indexer) = self.indexer.read().await.as_ref().cloned() else {
+            return;
+        };
+        self.reindex_stale_files(&indexer, &root).await;
+        if let Err(e) = tokio::fs::write(root.join(INDEX_PATH_FRAGMENT), APP_VERSION).await {
+            lsp_error!("Failed to write {INDEX_PATH_FRAGMENT}: {e}");
+        }
+    }
+
     #[allow(unused)]
     fn needs_full_reindex(&self, root: &Path) -> bool {
         #[cfg(feature = "integration-test")]
@@ -1696,33 +2194,32 @@ impl Backend {
     }
 
     /// Returns the set of all method names reachable on a type (direct + inherited via supers).
-    /// Follows the project super-mapping chain one level; also includes direct external methods.
+    /// Follows the project supertype closure; also includes direct external methods.
     async fn reachable_method_names(&self, type_fqn: &str) -> HashSet<String> {
         let Some(repo) = self.repo.get() else {
             return HashSet::new();
         };
-        let mut visited: HashSet<String> = HashSet::new();
-        let mut queue = vec![type_fqn.to_string()];
         let mut names: HashSet<String> = JAVA_OBJECT_METHODS.iter().map(|s| s.to_string()).collect();
 
-        while let Some(fqn) = queue.pop() {
-            if !visited.insert(fqn.clone()) {
-                continue;
-            }
+        for fqn in self.type_and_supertypes(type_fqn).await {
             for sym in repo.find_symbols_by_parent_name(&fqn).await.unwrap_or_default() {
                 names.insert(sym.short_name);
             }
             for sym in repo.find_external_symbols_by_parent_name(&fqn).await.unwrap_or_default() {
                 names.insert(sym.short_name);
             }
-            // Follow project supers one step
-            for s in repo.find_supers_by_symbol_fqn(&fqn).await.unwrap_or_default() {
-                queue.push(s.fully_qualified_name);
-            }
         }
         names
     }
 
+    /// `type_fqn` followed by its indexed ancestors, nearest first.
+    pub(crate) async fn type_and_supertypes(&self, type_fqn: &str) -> Vec<String> {
+        let ancestors = self.supertype_closure(type_fqn).await;
+        std::iter::once(type_fqn.to_string())
+            .chain(ancestors.iter().map(|s| s.fully_qualified_name.clone()))
+            .collect()
+    }
+
     /// Returns the list of (modifiers, declaring_type_fqn) for all members named `member_name`
     /// that are directly declared on `type_fqn` (no inheritance).
     async fn direct_member_symbols(
@@ -1755,13 +2252,8 @@ impl Backend {
         method_name: &str,
     ) -> Option<String> {
         let repo = self.repo.get()?;
-        let mut visited: HashSet<String> = HashSet::new();
-        let mut queue = vec![class_fqn.to_string()];
 
-        while let Some(fqn) = queue.pop() {
-            if !visited.insert(fqn.clone()) {
-                continue;
-            }
+        for fqn in self.type_and_supertypes(class_fqn).await {
             for sym in repo.find_symbols_by_parent_name(&fqn).await.unwrap_or_default() {
                 if sym.short_name == method_name && sym.symbol_type == "Function" {
                     return sym
@@ -1786,8 +2278,115 @@ impl Backend {
                         .or_else(|| sym.metadata.0.return_type.clone());
                 }
             }
-            for s in repo.find_supers_by_symbol_fqn(&fqn).await.unwrap_or_default() {
-                queue.push(s.fully_qualified_name);
+        }
+        None
+    }
+
+    /// Walks `parent_fqns` and their supertypes, returning the FQN of the first type that
+    /// declares an overridable (non-static, non-private) method matching `sig`.
+    async fn overridden_method_owner(
+        &self,
+        parent_fqns: Vec<String>,
+        sig: &lsp_core::language_support::MethodSig,
+    ) -> Option<String> {
+        let repo = self.repo.get()?;
+        let mut visited: HashSet<String> = HashSet::new();
+
+        fn overrides(
+            sig: &lsp_core::language_support::MethodSig,
+            short_name: &str,
+            symbol_type: &str,
+            modifiers: &[String],
+            metadata: &SymbolMetadata,
+        ) -> bool {
+            if symbol_type != "Function"
+                || modifiers.iter().any(|m| m == "static" || m == "private")
+            {
+                return false;
+            }
+            let params = metadata
+                .parameters
+                .as_ref()
+                .map(|ps| {
+                    ps.iter()
+                        .map(|p| p.type_name.clone().unwrap_or_default())
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            sig.implements(&lsp_core::language_support::MethodSig::new(short_name, params))
+        }
+
+        let mut candidates: Vec<String> = Vec::new();
+        for parent_fqn in &parent_fqns {
+            candidates.extend(self.type_and_supertypes(parent_fqn).await);
+        }
+
+        for fqn in candidates {
+            if !visited.insert(fqn.clone()) {
+                continue;
+            }
+            for sym in repo.find_symbols_by_parent_name(&fqn).await.unwrap_or_default() {
+                if overrides(sig, &sym.short_name, &sym.symbol_type, &sym.modifiers.0, &sym.metadata.0) {
+                    return Some(fqn);
+                }
+            }
+            for sym in repo
+                .find_external_symbols_by_parent_name(&fqn)
+                .await
+                .unwrap_or_default()
+            {
+                if overrides(sig, &sym.short_name, &sym.symbol_type, &sym.modifiers.0, &sym.metadata.0) {
+                    return Some(fqn);
+                }
+            }
+        }
+        None
+    }
+
+    /// Finds the nearest declaration of `method_name` on `type_fqn` or its supertypes and, when
+    /// it comes from Java and every overload carries a nullability annotation such as
+    /// `@Nullable`, returns that annotation's name.  Kotlin declarations are skipped since their
+    /// nullability is part of the type system.
+    async fn nullable_java_method(&self, type_fqn: &str, method_name: &str) -> Option<String> {
+        let repo = self.repo.get()?;
+
+        fn nullable_annotation(metadata: &SymbolMetadata) -> Option<String> {
+            metadata
+                .annotations
+                .as_ref()?
+                .iter()
+                .find(|a| NULLABLE_ANNOTATIONS.contains(&a.as_str()))
+                .cloned()
+        }
+
+        for fqn in self.type_and_supertypes(type_fqn).await {
+            let mut overloads: Vec<(String, Option<String>)> = repo
+                .find_symbols_by_parent_name(&fqn)
+                .await
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|sym| sym.short_name == method_name && sym.symbol_type == "Function")
+                .map(|sym| (sym.file_type.clone(), nullable_annotation(&sym.metadata.0)))
+                .collect();
+            overloads.extend(
+                repo.find_external_symbols_by_parent_name(&fqn)
+                    .await
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|sym| sym.short_name == method_name && sym.symbol_type == "Function")
+                    .map(|sym| (sym.file_type.clone(), nullable_annotation(&sym.metadata.0))),
+            );
+            if !overloads.is_empty() {
+                if overloads.iter().any(|(file_type, _)| file_type != "java") {
+                    return None;
+                }
+                let annotations: Vec<Option<String>> =
+                    overloads.into_iter().map(|(_, a)| a).collect();
+                return annotations
+                    .iter()
+                    .all(Option::is_some)
+                    .then(|| annotations.into_iter().flatten().next())
+                    .flatten();
             }
         }
         None
@@ -1800,6 +2399,11 @@ impl Backend {
             return Some(vec![]);
         }
         let path = PathBuf::from_str(uri.path()).unwrap();
+        if let Some(mut diagnostics) = self.dependency_conflict_diagnostics(uri, &path).await {
+            diagnostics.extend(self.unknown_plugin_diagnostics(uri, &path).await);
+            diagnostics.extend(self.project_cycle_diagnostics(&path).await);
+            return Some(diagnostics);
+        }
         let ext = path.extension().and_then(|e| e.to_str())?;
         let lang = self.languages.get(ext)?;
         let parse_result = if let Some(entry) = self.documents.get(&uri.to_string()) {
@@ -1808,11 +2412,17 @@ impl Backend {
             lang.parse(&path)
         };
         let (tree, content) = parse_result?;
-        Some(self.compute_diagnostics_from_tree(&tree, &content, lang.as_ref()).await)
+        let mut diagnostics = self
+            .compute_diagnostics_from_tree(&path, &tree, &content, lang.as_ref())
+            .await;
+        let suppressions = Suppressions::parse(&tree, &content, lang.as_ref());
+        diagnostics.retain(|d| !suppressions.suppresses(d));
+        Some(diagnostics)
     }
 
     async fn compute_diagnostics_from_tree(
         &self,
+        path: &Path,
         tree: &Tree,
         content: &str,
         lang: &dyn lsp_core::language_support::LanguageSupport,
@@ -1832,18 +2442,37 @@ impl Backend {
                     if is_type_ref_skippable(&name, &local_types) {
                         continue;
                     }
+                    let candidates = self
+                        .wildcard_candidates(&name, &imports, package.as_deref())
+                        .await;
+                    if candidates.len() > 1 {
+                        diagnostics.push(Diagnostic {
+                            range,
+                            severity: Some(DiagnosticSeverity::WARNING),
+                            code: Some(NumberOrString::String("ambiguous_import".to_string())),
+                            source: Some("lspintar".to_string()),
+                            message: format!(
+                                "'{name}' is ambiguous between wildcard imports: {}",
+                                candidates.join(", ")
+                            ),
+                            ..Default::default()
+                        });
+                        continue;
+                    }
                     let resolved = self
                         .resolve_fqn_strict(&name, &imports, package.clone())
                         .await;
                     let Some(fqn) = resolved else {
                         continue;
                     };
-                    let in_project = repo
-                        .find_symbol_by_fqn(&fqn)
-                        .await
-                        .ok()
-                        .flatten()
-                        .is_some();
+                    let project_symbol = repo.find_symbol_by_fqn(&fqn).await.ok().flatten();
+                    if let Some(sym) = &project_symbol
+                        && let Some(diagnostic) =
+                            self.internal_access_diagnostic(path, sym, &name, range).await
+                    {
+                        diagnostics.push(diagnostic);
+                    }
+                    let in_project = project_symbol.is_some();
                     let in_external = !in_project
                         && repo
                             .find_external_symbol_by_fqn(&fqn)
@@ -1920,13 +2549,14 @@ impl Backend {
                             range: class_data.ident_range,
                             severity: Some(DiagnosticSeverity::ERROR),
                             code: Some(NumberOrString::String(
-                                "unimplemented_abstract_methods".to_string(),
+                                UNIMPLEMENTED_ABSTRACT_METHODS_CODE.to_string(),
                             )),
                             source: Some("lspintar".to_string()),
                             message: format!(
                                 "'{}' must implement '{}'",
                                 class_data.name, pretty
                             ),
+                            data: MissingMember::new(&parent_fqn, &required_sig).to_data(),
                             ..Default::default()
                         });
                     }
@@ -2023,6 +2653,18 @@ impl Backend {
                         continue;
                     }
 
+                    if let Some(diagnostic) = self
+                        .internal_member_access_diagnostic(
+                            path,
+                            &type_fqn,
+                            &access.member_name,
+                            access.member_range,
+                        )
+                        .await
+                    {
+                        diagnostics.push(diagnostic);
+                    }
+
                     // Check direct-member modifiers (first overload that exists)
                     let direct = self
                         .direct_member_symbols(&type_fqn, &access.member_name)
@@ -2075,6 +2717,67 @@ impl Backend {
             }
         }
 
+        // Semantic check: platform_null_dereference (Kotlin dereferencing a Java @Nullable result)
+        let dereferences = lang.get_platform_dereferences(&tree, &content);
+        if !dereferences.is_empty() {
+            let imports = lang.get_imports(&tree, &content);
+            let package = lang.get_package_name(&tree, &content);
+
+            for deref in dereferences {
+                // The receiver is either a variable or, for static calls, a type name.
+                let raw_type = lang
+                    .find_variable_type(
+                        &tree,
+                        &content,
+                        &deref.receiver_name,
+                        &deref.receiver_range.start,
+                    )
+                    .or_else(|| {
+                        deref
+                            .receiver_name
+                            .starts_with(char::is_uppercase)
+                            .then(|| deref.receiver_name.clone())
+                    });
+                let Some(raw_type) = raw_type else {
+                    continue;
+                };
+                let base_type = raw_type
+                    .split('<')
+                    .next()
+                    .unwrap_or(&raw_type)
+                    .trim()
+                    .trim_end_matches('?')
+                    .to_string();
+                if is_type_ref_skippable(&base_type, &[]) {
+                    continue;
+                }
+                let Some(type_fqn) = self
+                    .resolve_fqn(&base_type, imports.clone(), package.clone())
+                    .await
+                else {
+                    continue;
+                };
+                let Some(annotation) =
+                    self.nullable_java_method(&type_fqn, &deref.method_name).await
+                else {
+                    continue;
+                };
+                diagnostics.push(Diagnostic {
+                    range: deref.range,
+                    severity: Some(DiagnosticSeverity::WARNING),
+                    code: Some(NumberOrString::String(
+                        "platform_null_dereference".to_string(),
+                    )),
+                    source: Some("lspintar".to_string()),
+                    message: format!(
+                        "'{}.{}' is annotated @{} in Java and may return null; use '?.' or check for null",
+                        base_type, deref.method_name, annotation
+                    ),
+                    ..Default::default()
+                });
+            }
+        }
+
         // Semantic check: wrong_type_argument_count
         let generic_usages = lang.get_generic_type_usages(&tree, &content);
         if !generic_usages.is_empty() {
@@ -2191,6 +2894,89 @@ impl Backend {
             }
         }
 
+        // Semantic check: missing_override (Java/Groovy)
+        let method_decls = lang.get_method_declarations(&tree, &content);
+        if !method_decls.is_empty() && self.repo.get().is_some() {
+            let imports = lang.get_imports(&tree, &content);
+            let package = lang.get_package_name(&tree, &content);
+            let class_parents: HashMap<String, Vec<String>> = lang
+                .get_class_declarations(&tree, &content)
+                .into_iter()
+                .map(|c| (c.name, c.parents))
+                .collect();
+
+            for method in method_decls {
+                if method.annotations.iter().any(|a| a == "Override")
+                    || method.modifiers.iter().any(|m| m == "static" || m == "private")
+                {
+                    continue;
+                }
+                let Some(parents) = class_parents.get(&method.containing_class) else {
+                    continue;
+                };
+                // java.lang.Object is an implicit supertype of every class.
+                let mut parent_fqns = vec!["java.lang.Object".to_string()];
+                for parent in parents {
+                    if let Some(fqn) = self
+                        .resolve_fqn(parent, imports.clone(), package.clone())
+                        .await
+                    {
+                        parent_fqns.push(fqn);
+                    }
+                }
+                let Some(owner_fqn) = self
+                    .overridden_method_owner(parent_fqns, &method.signature)
+                    .await
+                else {
+                    continue;
+                };
+
+                let indent: String = content
+                    .lines()
+                    .nth(method.decl_start.line as usize)
+                    .unwrap_or_default()
+                    .chars()
+                    .take_while(|c| c.is_whitespace())
+                    .collect();
+                let fix = QuickFix::new(
+                    "Add @Override annotation",
+                    vec![TextEdit {
+                        range: Range::new(method.decl_start, method.decl_start),
+                        new_text: format!("@Override\n{indent}"),
+                    }],
+                );
+                let owner = owner_fqn.rsplit('.').next().unwrap_or(&owner_fqn);
+                diagnostics.push(Diagnostic {
+                    range: method.name_range,
+                    severity: Some(DiagnosticSeverity::INFORMATION),
+                    code: Some(NumberOrString::String("missing_override".to_string())),
+                    source: Some("lspintar".to_string()),
+                    message: format!(
+                        "'{}' overrides a method from '{}' but is not annotated with @Override",
+                        method.signature.name, owner
+                    ),
+                    data: fix.to_data(),
+                    ..Default::default()
+                });
+            }
+        }
+
+        // File-local check: unused_private_member, unused_local_variable
+        diagnostics.extend(collect_unused_declarations(tree, content, lang));
+
+        // File-local check: unreachable_code
+        diagnostics.extend(unreachable_code(tree, content));
+
+        // Semantic check: unresolved_entity in JPQL query annotations
+        diagnostics.extend(self.jpql_diagnostics(tree, content).await);
+
+        // Semantic check: unresolved_mapped_by in JPA relationship annotations
+        diagnostics.extend(self.mapped_by_diagnostics(tree, content, lang).await);
+        diagnostics.extend(self.resource_key_diagnostics(content).await);
+
+        // Semantic check: annotation_attribute_type_mismatch
+        diagnostics.extend(self.annotation_attribute_diagnostics(tree, content, lang).await);
+
         // Semantic check: narrowing_conversion (Java/Groovy — Kotlin skip is justified)
         let narrowing_candidates = lang.get_narrowing_candidates(&tree, &content);
         for candidate in narrowing_candidates {
@@ -2365,7 +3151,43 @@ impl Backend {
             }
         }
 
-        diagnostics
+        diagnostics.extend(package_mismatch_diagnostic(path, tree, content, lang));
+        diagnostics.extend(self.unresolved_import_diagnostics(content).await);
+        diagnostics.extend(self.duplicate_class_diagnostics(path).await);
+
+        diagnostics
+    }
+
+    /// The symbols the index holds for `file_path`, to compare against what it holds
+    /// once the file is re-indexed.
+    pub(crate) async fn indexed_symbols(&self, file_path: &str) -> Vec<Symbol> {
+        let Some(repo) = self.repo.get() else {
+            return vec![];
+        };
+        repo.find_symbols_by_file_path(file_path)
+            .await
+            .unwrap_or_default()
+    }
+
+    /// Drops what was derived from `path` declaring `before`, now that it declares
+    /// `after`.
+    fn file_reindexed(&self, path: &Path, before: &[Symbol], after: &[Symbol]) {
+        self.supertypes.invalidate(&type_fqns(before), &type_fqns(after));
+        self.workspace_symbols.invalidate();
+        self.workspace_diagnostics.file_reindexed(path, before, after);
+        self.project_imports.file_reindexed(path);
+        self.spawn_workspace_diagnostics_refresh();
+        self.republish_importers(&removed_fqns(before, after));
+    }
+
+    /// Drops what was derived from `path`, which declared `before` and is gone.
+    fn file_removed(&self, path: &Path, before: &[Symbol]) {
+        self.supertypes.invalidate(&type_fqns(before), &[]);
+        self.workspace_symbols.invalidate();
+        self.workspace_diagnostics.file_removed(path);
+        self.project_imports.file_removed(path);
+        self.spawn_workspace_diagnostics_refresh();
+        self.republish_importers(&removed_fqns(before, &[]));
     }
 
     async fn publish_diagnostics(&self, uri: Url) {
@@ -2380,6 +3202,59 @@ impl Backend {
 #[tower_lsp::async_trait]
 impl LanguageServer for Backend {
     async fn initialize(&self, params: InitializeParams) -> Result<InitializeResult> {
+        let annotations_supported = params
+            .capabilities
+            .workspace
+            .as_ref()
+            .and_then(|w| w.workspace_edit.as_ref())
+            .is_some_and(|we| {
+                we.document_changes == Some(true) && we.change_annotation_support.is_some()
+            });
+        self.change_annotation_support
+            .store(annotations_supported, Ordering::Release);
+        let renames_supported = params
+            .capabilities
+            .workspace
+            .as_ref()
+            .and_then(|w| w.workspace_edit.as_ref())
+            .is_some_and(|we| {
+                we.document_changes == Some(true)
+                    && we
+                        .resource_operations
+                        .as_ref()
+                        .is_some_and(|ops| ops.contains(&ResourceOperationKind::Rename))
+            });
+        self.resource_rename_support
+            .store(renames_supported, Ordering::Release);
+
+        let mut root_settings = RootSettings::default();
+        if let Some(options) = params.initialization_options.as_ref() {
+            match serde_json::from_value::<ArtifactSettings>(options.clone()) {
+                Ok(settings) => set_artifact_settings(settings),
+                Err(e) => warn!("ignoring invalid artifact settings: {e}"),
+            }
+            match serde_json::from_value::<IndexingSettings>(options.clone()) {
+                Ok(settings) => *self.indexing_settings.write().await = settings,
+                Err(e) => warn!("ignoring invalid indexing settings: {e}"),
+            }
+            match serde_json::from_value::<ImportPreferences>(options.clone()) {
+                Ok(preferences) => *self.import_preferences.write().await = preferences,
+                Err(e) => warn!("ignoring invalid import preferences: {e}"),
+            }
+            match serde_json::from_value::<ResourceSettings>(options.clone()) {
+                Ok(settings) => *self.resource_settings.write().await = settings,
+                Err(e) => warn!("ignoring invalid resource settings: {e}"),
+            }
+            match serde_json::from_value::<SearchLimits>(options.clone()) {
+                Ok(limits) => *self.search_limits.write().await = limits,
+                Err(e) => warn!("ignoring invalid search limits: {e}"),
+            }
+            match RootSettings::from_initialization_options(options) {
+                Ok(settings) => root_settings = settings,
+                Err(e) => warn!("ignoring invalid project root settings: {e}"),
+            }
+        }
+
         let workspace_root = params
             .root_uri
             .and_then(|uri| uri.to_file_path().ok())
@@ -2388,7 +3263,8 @@ impl LanguageServer for Backend {
                     .workspace_folders
                     .and_then(|folders| folders.first().cloned())
                     .and_then(|folder| folder.uri.to_file_path().ok())
-            });
+            })
+            .map(|root| root_settings.resolve(&root));
 
         if let Some(root) = workspace_root {
             if self.is_cache_dir(Some(&root)) {
@@ -2413,7 +3289,9 @@ impl LanguageServer for Backend {
                     })?;
 
                 let db_path = lspintar_dir.join(file_name);
-                let repo = Repository::new(db_path.to_str().unwrap())
+                self.claim_index(&db_path);
+                let repo = self
+                    .open_index(db_path.to_str().unwrap())
                     .await
                     .map_err(|e| {
                         debug!("Failed to create {DB_PATH_FRAGMENT} in {:?}: {e}", root);
@@ -2423,10 +3301,43 @@ impl LanguageServer for Backend {
                 self.repo.set(Arc::new(repo)).ok();
             }
 
+            // Paths are indexed relative to the workspace and the home directory, so
+            // the index survives moving the checkout or copying it to another machine.
+            if let Some(repo) = self.repo.get() {
+                let mut roots = vec![(WORKSPACE_PATH_ROOT, root.clone())];
+                roots.extend(dirs::home_dir().map(|home| (HOME_PATH_ROOT, home)));
+                for (name, dir) in roots {
+                    if self.is_index_reader() {
+                        repo.add_path_root(name, &dir);
+                    } else if let Err(e) = repo.adopt_path_root(name, &dir).await {
+                        warn!("Failed to record path root {name}: {e}");
+                    }
+                }
+            }
+
             *self.workspace_root.write().await = Some(root);
         } else {
-            debug!("workspace root not found, shutting down");
-            std::process::exit(0);
+            debug!("workspace root not found, starting in single-file mode");
+            if self.repo.get().is_none() {
+                let cache_dir = get_cache_dir();
+                std::fs::create_dir_all(cache_dir).map_err(|e| {
+                    tracing::error!("failed to create cache dir {:?}: {}", cache_dir, e);
+                    tower_lsp::jsonrpc::Error::internal_error()
+                })?;
+
+                let db_path = cache_dir.join(SINGLE_FILE_DB_FILE);
+                self.claim_index(&db_path);
+                let repo = self
+                    .open_index(db_path.to_str().unwrap())
+                    .await
+                    .map_err(|e| {
+                        debug!("Failed to create {SINGLE_FILE_DB_FILE} in {:?}: {e}", cache_dir);
+                        tower_lsp::jsonrpc::Error::internal_error()
+                    })?;
+
+                self.repo.set(Arc::new(repo)).ok();
+            }
+            self.single_file_mode.store(true, Ordering::Release);
         }
 
         let documents = self.documents.clone();
@@ -2470,6 +3381,12 @@ impl LanguageServer for Backend {
                                 glob_pattern: GlobPattern::String("**/*.gradle.kts".to_string()),
                                 kind: Some(WatchKind::all()),
                             },
+                            FileSystemWatcher {
+                                glob_pattern: GlobPattern::String(
+                                    "**/src/*/resources/**".to_string(),
+                                ),
+                                kind: Some(WatchKind::all()),
+                            },
                         ],
                     })
                     .unwrap(),
@@ -2487,8 +3404,65 @@ impl LanguageServer for Backend {
                 implementation_provider: Some(ImplementationProviderCapability::Simple(true)),
                 type_definition_provider: Some(TypeDefinitionProviderCapability::Simple(true)),
                 references_provider: Some(OneOf::Left(true)),
-                rename_provider: Some(OneOf::Left(true)),
+                document_symbol_provider: Some(OneOf::Left(true)),
+                workspace_symbol_provider: Some(OneOf::Left(true)),
+                rename_provider: Some(OneOf::Right(RenameOptions {
+                    prepare_provider: Some(true),
+                    work_done_progress_options: Default::default(),
+                })),
+                code_action_provider: Some(CodeActionProviderCapability::Options(
+                    CodeActionOptions {
+                        code_action_kinds: Some(vec![
+                            CodeActionKind::QUICKFIX,
+                            CodeActionKind::REFACTOR_EXTRACT,
+                            CodeActionKind::SOURCE_ORGANIZE_IMPORTS,
+                            CodeActionKind::new(OVERRIDE_METHODS_KIND),
+                            CodeActionKind::new(GENERATE_ACCESSORS_KIND),
+                        ]),
+                        ..Default::default()
+                    },
+                )),
                 hover_provider: Some(HoverProviderCapability::Simple(true)),
+                semantic_tokens_provider: Some(
+                    SemanticTokensServerCapabilities::SemanticTokensOptions(
+                        SemanticTokensOptions {
+                            legend: crate::semantic_tokens::legend(),
+                            range: Some(true),
+                            full: Some(SemanticTokensFullOptions::Delta { delta: Some(true) }),
+                            ..Default::default()
+                        },
+                    ),
+                ),
+                color_provider: Some(ColorProviderCapability::Simple(true)),
+                document_on_type_formatting_provider: Some(DocumentOnTypeFormattingOptions {
+                    first_trigger_character: "}".to_string(),
+                    more_trigger_character: Some(vec!["\n".to_string()]),
+                }),
+                inline_value_provider: Some(OneOf::Left(true)),
+                code_lens_provider: Some(CodeLensOptions {
+                    resolve_provider: Some(true),
+                }),
+                document_link_provider: Some(DocumentLinkOptions {
+                    resolve_provider: Some(true),
+                    work_done_progress_options: Default::default(),
+                }),
+                diagnostic_provider: Some(DiagnosticServerCapabilities::Options(
+                    DiagnosticOptions {
+                        identifier: Some("lspintar".to_string()),
+                        inter_file_dependencies: true,
+                        workspace_diagnostics: true,
+                        ..Default::default()
+                    },
+                )),
+                execute_command_provider: Some(ExecuteCommandOptions {
+                    commands: vec![
+                        REFRESH_DEPENDENCIES_COMMAND.to_string(),
+                        COPY_REFERENCE_COMMAND.to_string(),
+                        EXPLAIN_RESOLUTION_COMMAND.to_string(),
+                        ORGANIZE_IMPORTS_COMMAND.to_string(),
+                    ],
+                    ..Default::default()
+                }),
                 completion_provider: Some(CompletionOptions {
                     trigger_characters: Some(
                         "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ."
@@ -2498,6 +3472,32 @@ impl LanguageServer for Backend {
                     ),
                     ..Default::default()
                 }),
+                workspace: Some(WorkspaceServerCapabilities {
+                    workspace_folders: None,
+                    file_operations: Some(WorkspaceFileOperationsServerCapabilities {
+                        will_rename: Some(FileOperationRegistrationOptions {
+                            filters: vec![
+                                FileOperationFilter {
+                                    scheme: Some("file".to_string()),
+                                    pattern: FileOperationPattern {
+                                        glob: "**/*.{java,kt,groovy}".to_string(),
+                                        matches: Some(FileOperationPatternKind::File),
+                                        options: None,
+                                    },
+                                },
+                                FileOperationFilter {
+                                    scheme: Some("file".to_string()),
+                                    pattern: FileOperationPattern {
+                                        glob: "**".to_string(),
+                                        matches: Some(FileOperationPatternKind::Folder),
+                                        options: None,
+                                    },
+                                },
+                            ],
+                        }),
+                        ..Default::default()
+                    }),
+                }),
                 ..Default::default()
             },
             server_info: Some(ServerInfo {
@@ -2530,11 +3530,12 @@ impl LanguageServer for Backend {
             *self.build_tool.write().await = Some(Arc::clone(&build_tool));
 
             let mut indexer = Indexer::new(Arc::clone(repo));
+            indexer.share_reader_flag(Arc::clone(&self.index_reader));
             languages.iter().for_each(|(k, v)| {
                 indexer.register_language(k, v.clone());
             });
 
-            if self.needs_full_reindex(&root) {
+            if !self.is_index_reader() && self.needs_full_reindex(&root) {
                 let indexing_start = Instant::now();
 
                 let token_ws = format!("idx-ws-{}", uuid::Uuid::new_v4());
@@ -2573,7 +3574,8 @@ impl LanguageServer for Backend {
                         panic!("{}", message);
                     }
                 };
-                let mut jars: Vec<(Option<PathBuf>, Option<PathBuf>)> = external_deps;
+                let mut jars: Vec<(Option<PathBuf>, Option<PathBuf>)> =
+                    without_workspace_outputs(external_deps, &root);
 
                 // exclude JDK
                 let jars_for_manifest = jars.clone();
@@ -2584,41 +3586,73 @@ impl LanguageServer for Backend {
 
                 lsp_progress!(&token_ws, "Indexing workspace...", 0.0);
 
-                let save_ws_begun = std::sync::Once::new();
+                let projects = discover_project_roots(&root, build_tool.as_ref());
+                self.track_projects(&projects).await;
 
-                let ws_result = indexer
-                    .index_workspace(
-                        &root,
-                        move |completed, total| {
-                            lsp_progress!(
-                                &token_ws,
-                                &format!("(1/2) Indexing workspace ({}/{})", completed, total),
-                                (completed as f32 / total as f32) * 100.0
-                            );
-                            if completed == total {
-                                lsp_progress_end!(&token_ws_end);
-                            }
-                        },
-                        move |completed, total| {
-                            save_ws_begun.call_once(|| {
-                                lsp_progress_begin!(&token_ws_save, "Saving data...")
-                            });
-                            lsp_progress!(
-                                &token_ws_save,
-                                &format!(
-                                    "(2/2) Saving project symbol indexes ({}/{})",
-                                    completed, total
-                                ),
-                                (completed as f32 / total as f32) * 100.0
-                            );
-                            if completed == total {
-                                lsp_progress_end!(&token_ws_save_end);
-                            }
-                        },
-                    )
+                let save_ws_begun = Arc::new(std::sync::Once::new());
+
+                let ws_results: Vec<_> = stream::iter(&projects)
+                    .map(|project| {
+                        let nested: Vec<PathBuf> = projects
+                            .iter()
+                            .filter(|p| *p != project && p.starts_with(project))
+                            .cloned()
+                            .collect();
+                        let name = project
+                            .file_name()
+                            .map(|n| n.to_string_lossy().to_string())
+                            .unwrap_or_default();
+                        let token_ws = token_ws.clone();
+                        let token_ws_save = token_ws_save.clone();
+                        let save_ws_begun = Arc::clone(&save_ws_begun);
+                        let indexer = &indexer;
+                        async move {
+                            self.set_project_status(project, IndexingStatus::Parsing)
+                                .await;
+                            let result = indexer
+                                .index_project(
+                                    project,
+                                    &nested,
+                                    move |completed, total| {
+                                        lsp_progress!(
+                                            &token_ws,
+                                            &format!(
+                                                "(1/2) Indexing {} ({}/{})",
+                                                name, completed, total
+                                            ),
+                                            (completed as f32 / total as f32) * 100.0
+                                        );
+                                    },
+                                    move |completed, total| {
+                                        save_ws_begun.call_once(|| {
+                                            lsp_progress_begin!(&token_ws_save, "Saving data...")
+                                        });
+                                        lsp_progress!(
+                                            &token_ws_save,
+                                            &format!(
+                                                "(2/2) Saving project symbol indexes ({}/{})",
+                                                completed, total
+                                            ),
+                                            (completed as f32 / total as f32) * 100.0
+                                        );
+                                    },
+                                )
+                                .await;
+                            self.set_project_status(project, IndexingStatus::External)
+                                .await;
+                            result
+                        }
+                    })
+                    .buffer_unordered(MAX_CONCURRENT_PROJECTS)
+                    .collect()
                     .await;
 
-                if let Err(e) = ws_result {
+                lsp_progress_end!(&token_ws_end);
+                if save_ws_begun.is_completed() {
+                    lsp_progress_end!(&token_ws_save_end);
+                }
+
+                if let Some(Err(e)) = ws_results.into_iter().find(|r| r.is_err()) {
                     let message = format!("Failed to index workspace: {e}");
                     lsp_error!("{}", message);
                     tokio::time::sleep(Duration::from_millis(500)).await;
@@ -2666,6 +3700,7 @@ impl LanguageServer for Backend {
                         },
                     )
                     .await;
+                self.set_all_project_status(IndexingStatus::Done).await;
 
                 let manifest_path = root.join(MANIFEST_PATH_FRAGMENT);
                 match serde_json::to_string(&jars_for_manifest) {
@@ -2694,6 +3729,10 @@ impl LanguageServer for Backend {
                     }
                 }
             } else {
+                self.track_projects(&discover_project_roots(&root, build_tool.as_ref()))
+                    .await;
+                self.set_all_project_status(IndexingStatus::Done).await;
+
                 // IncrementalOpen: load the persisted classpath manifest into memory.
                 let classpath_path = root.join(CLASSPATH_MANIFEST_PATH_FRAGMENT);
                 if let Ok(bytes) = tokio::fs::read(&classpath_path).await {
@@ -2702,55 +3741,15 @@ impl LanguageServer for Backend {
                     }
                 }
 
-                // Re-index only source files that changed since the last stored VCS revision.
-                let stored_rev = tokio::fs::read_to_string(root.join(VCS_REVISION_PATH_FRAGMENT))
-                    .await
-                    .ok()
-                    .map(|s| s.trim().to_string())
-                    .filter(|s| !s.is_empty());
-
-                if let Some(stored) = stored_rev {
-                    if let Ok(current) = vcs.get_current_revision() {
-                        if stored != current {
-                            match vcs.get_changed_files(&stored, &current, &root) {
-                                Ok(changed) => {
-                                    let supported_exts: std::collections::HashSet<&str> =
-                                        languages.iter().map(|(k, _)| k.as_str()).collect();
-                                    let source_changes: Vec<PathBuf> = changed
-                                        .into_iter()
-                                        .filter(|p| {
-                                            p.extension()
-                                                .and_then(|e| e.to_str())
-                                                .map(|e| supported_exts.contains(e))
-                                                .unwrap_or(false)
-                                        })
-                                        .collect();
-
-                                    if !source_changes.is_empty() {
-                                        lsp_info!(
-                                            "IncrementalOpen: re-indexing {} changed file(s) since {}",
-                                            source_changes.len(),
-                                            &stored[..stored.len().min(8)]
-                                        );
-                                        for path in source_changes {
-                                            let _ = self.debounce_tx.send(path).await;
-                                        }
-                                    }
-                                }
-                                Err(e) => {
-                                    lsp_error!("Failed to get changed files for incremental open: {e}");
-                                }
-                            }
+                // Another instance writes the index and keeps it current.
+                if !self.is_index_reader() {
+                    self.reindex_stale_files(&indexer, &root).await;
 
-                            if let Err(e) = tokio::fs::write(
-                                root.join(VCS_REVISION_PATH_FRAGMENT),
-                                &current,
-                            )
-                            .await
-                            {
-                                lsp_error!("Failed to update {VCS_REVISION_PATH_FRAGMENT}: {e}");
-                            }
-                        }
+                    if let Ok(current) = vcs.get_current_revision()
+                        && let Err(e) =
+                            tokio::fs::write(root.join(VCS_REVISION_PATH_FRAGMENT), &current).await
+                    {
+                        lsp_error!("Failed to update {VCS_REVISION_PATH_FRAGMENT}: {e}");
                     }
                 }
             }
@@ -2765,10 +3764,16 @@ impl LanguageServer for Backend {
                 }
             }
 
-            if let Err(e) = tokio::fs::write(root.join(INDEX_PATH_FRAGMENT), APP_VERSION).await {
+            if !self.is_index_reader()
+                && let Err(e) = tokio::fs::write(root.join(INDEX_PATH_FRAGMENT), APP_VERSION).await
+            {
                 lsp_error!("Failed to write {INDEX_PATH_FRAGMENT}: {e}");
             }
+            self.spawn_index_takeover(root.join(DB_PATH_FRAGMENT));
 
+            self.index_spring_xml(&root).await;
+            self.index_build_scripts(&root).await;
+            self.index_resources(&root).await;
             self.index_ready.store(true, Ordering::Release);
 
             // Publish diagnostics for any files already opened during indexing.
@@ -2778,8 +3783,14 @@ impl LanguageServer for Backend {
                 .filter_map(|entry| Url::parse(entry.key()).ok())
                 .collect();
             for uri in open_uris {
-                self.publish_diagnostics(uri).await;
+                self.publish_diagnostics(uri.clone()).await;
+                self.publish_implementable_symbols(uri).await;
             }
+            self.spawn_workspace_diagnostics_refresh();
+            self.spawn_dependency_watch(root.clone());
+        } else if self.single_file_mode.load(Ordering::Acquire) {
+            self.index_standalone_jdk().await;
+            self.spawn_index_takeover(get_cache_dir().join(SINGLE_FILE_DB_FILE));
         }
     }
 
@@ -2787,27 +3798,14 @@ impl LanguageServer for Backend {
         &self,
         params: GotoDefinitionParams,
     ) -> Result<Option<GotoDefinitionResponse>> {
-        let symbols = self
-            .resolve_symbol_at_position(&params.text_document_position_params)
-            .await?;
-
-        let indexer_guard = self.indexer.read().await;
-        let indexer = indexer_guard.as_ref();
-
-        let locations: Vec<Location> = stream::iter(symbols)
-            .then(|s| async move {
-                let indexer = indexer.clone();
-                match s {
-                    ResolvedSymbol::External(sym) => {
-                        let enriched = sym.with_sources(indexer).await;
-                        enriched.as_lsp_location()
-                    }
-                    other => other.as_lsp_location(),
-                }
-            })
-            .filter_map(|l| async move { l })
-            .collect()
+        let position_params = &params.text_document_position_params;
+        self.gate_on_indexing(&position_params.text_document.uri, "textDocument/definition")
             .await;
+        let (symbols, resolver) = self.definition_symbols(position_params).await?;
+        let targets = self.definition_targets(symbols).await;
+        self.trace_definition(position_params, resolver, &targets);
+
+        let locations: Vec<Location> = targets.iter().filter_map(|s| s.as_lsp_location()).collect();
 
         match locations.len() {
             0 => Ok(None),
@@ -2842,13 +3840,11 @@ impl LanguageServer for Backend {
                 tower_lsp::jsonrpc::Error::invalid_params("Failed to parse file".to_string())
             })?;
 
-            let mut imports = lang.get_imports(&tree, &content);
-            for imp in lang.get_implicit_imports() {
-                if !imports.contains(&imp) {
-                    imports.push(imp);
-                }
-            }
-            let package_name = lang.get_package_name(&tree, &content);
+            let FileContext {
+                imports,
+                package_name,
+                ..
+            } = FileContext::new(lang.as_ref(), &tree, &content);
 
             let position = params.text_document_position_params.position;
 
@@ -2911,6 +3907,15 @@ impl LanguageServer for Backend {
                             tower_lsp::jsonrpc::Error::invalid_params("Failed to resolve FQN")
                         })?;
 
+                    if declares_method_with_body(&tree, &content, &position, &ident, lang.as_ref())
+                        && let Some(implementations) = self
+                            .default_method_implementations(&parent_fqn, &ident, params.len())
+                            .await
+                    {
+                        let symbols = implementations.into_symbols();
+                        return Ok(self.resolved_symbols_to_impl_response(symbols));
+                    }
+
                     let implementations = self
                         .repo
                         .get()
@@ -2954,6 +3959,30 @@ impl LanguageServer for Backend {
 
     #[tracing::instrument(skip_all)]
     async fn hover(&self, params: HoverParams) -> Result<Option<Hover>> {
+        if let Some(hover) = self
+            .dependency_conflict_hover(&params.text_document_position_params)
+            .await
+        {
+            return Ok(Some(hover));
+        }
+        if let Some(hover) = self
+            .plugin_hover(&params.text_document_position_params)
+            .await
+        {
+            return Ok(Some(hover));
+        }
+        if let Some(hover) = self
+            .annotation_attribute_hover(&params.text_document_position_params)
+            .await
+        {
+            return Ok(Some(hover));
+        }
+        if let Some(hover) = self
+            .enum_constant_hover(&params.text_document_position_params)
+            .await
+        {
+            return Ok(Some(hover));
+        }
         let symbols = self
             .resolve_symbol_at_position(&params.text_document_position_params)
             .await;
@@ -2969,6 +3998,15 @@ impl LanguageServer for Backend {
             Some(other) => other,
             None => return Ok(None),
         };
+        if let Some(hover) = self
+            .alias_hover(&params.text_document_position_params, &symbol)
+            .await
+        {
+            return Ok(Some(hover));
+        }
+        if let Some(hover) = nullability_hover(&params.text_document_position_params, &symbol) {
+            return Ok(Some(hover));
+        }
         Ok(symbol.as_lsp_hover())
     }
 
@@ -2976,12 +4014,38 @@ impl LanguageServer for Backend {
         Ok(())
     }
 
+    async fn execute_command(
+        &self,
+        params: ExecuteCommandParams,
+    ) -> Result<Option<serde_json::Value>> {
+        match params.command.as_str() {
+            REFRESH_DEPENDENCIES_COMMAND => {
+                let Some(root) = self.workspace_root.read().await.clone() else {
+                    return Ok(None);
+                };
+                let Some((added, removed)) = self.handle_build_file_changed(&root).await else {
+                    return Err(tower_lsp::jsonrpc::Error::internal_error());
+                };
+                lsp_info!("Dependencies refreshed: {added} added, {removed} removed");
+                Ok(Some(serde_json::json!({ "added": added, "removed": removed })))
+            }
+            COPY_REFERENCE_COMMAND => self.copy_reference(params.arguments).await,
+            EXPLAIN_RESOLUTION_COMMAND => self.explain_resolution(params.arguments).await,
+            ORGANIZE_IMPORTS_COMMAND => self.organize_imports(params.arguments).await,
+            _ => Err(tower_lsp::jsonrpc::Error::invalid_params(format!(
+                "Unknown command: {}",
+                params.command
+            ))),
+        }
+    }
+
     async fn did_open(&self, params: DidOpenTextDocumentParams) {
         let uri = params.text_document.uri.clone();
         let text = params.text_document.text.clone();
         self.documents
             .insert(uri.to_string(), (text, Instant::now()));
-        self.publish_diagnostics(uri).await;
+        self.publish_diagnostics(uri.clone()).await;
+        self.publish_implementable_symbols(uri).await;
     }
 
     async fn did_save(&self, params: DidSaveTextDocumentParams) {
@@ -2989,6 +4053,22 @@ impl LanguageServer for Backend {
             Ok(p) => p,
             Err(_) => return,
         };
+        if is_gradle_script(&path) {
+            self.reindex_build_script(&path).await;
+        }
+        if resources_relative(&path).is_some() {
+            self.reindex_resource_file(&path).await;
+            if !is_spring_xml_candidate(&path) {
+                return;
+            }
+        }
+        if is_spring_xml_candidate(&path) {
+            self.reindex_spring_xml_file(&path).await;
+            return;
+        }
+        if self.is_index_reader() {
+            return;
+        }
         let Some(indexer) = self.indexer.read().await.as_ref().cloned() else {
             return;
         };
@@ -3001,20 +4081,14 @@ impl LanguageServer for Backend {
 
         match result {
             Ok(Ok(Some((symbols, supers)))) => {
-                for chunk in symbols.chunks(1000) {
-                    if let Err(e) = repo.insert_symbols(chunk).await {
-                        warn!("Failed to insert symbols on save: {e}");
-                    }
-                }
-                for chunk in supers.chunks(1000) {
-                    let mappings = chunk
-                        .iter()
-                        .map(|m| (&*m.symbol_fqn, &*m.super_short_name, m.super_fqn.as_deref()))
-                        .collect::<Vec<_>>();
-                    if let Err(e) = repo.insert_symbol_super_mappings(mappings).await {
-                        warn!("Failed to insert mappings on save: {e}");
-                    }
+                let before = self.indexed_symbols(&path.to_string_lossy()).await;
+                if let Err(e) = repo
+                    .replace_file_symbols(&path.to_string_lossy(), &symbols, &supers)
+                    .await
+                {
+                    warn!("Failed to replace symbols on save: {e}");
                 }
+                self.file_reindexed(&path, &before, &symbols);
                 debug!("Re-indexed: {}", path.display());
             }
             Ok(Ok(None)) => warn!("Unsupported file type, ignore"),
@@ -3022,7 +4096,8 @@ impl LanguageServer for Backend {
             Err(e) => warn!("Failed to spawn index task: {e}"),
         }
 
-        self.publish_diagnostics(params.text_document.uri).await;
+        self.publish_diagnostics(params.text_document.uri.clone()).await;
+        self.publish_implementable_symbols(params.text_document.uri).await;
     }
 
     async fn completion(&self, params: CompletionParams) -> Result<Option<CompletionResponse>> {
@@ -3052,16 +4127,28 @@ impl LanguageServer for Backend {
             lang.parse(&path)
         }
         .ok_or_else(|| tower_lsp::jsonrpc::Error::invalid_params("Failed to parse file"))?;
-        let mut imports = lang.get_imports(&tree, &content);
-        for imp in lang.get_implicit_imports() {
-            if !imports.contains(&imp) {
-                imports.push(imp);
-            }
+        let FileContext {
+            imports,
+            package_name,
+            ..
+        } = FileContext::new(lang.as_ref(), &tree, &content);
+
+        if let Some(items) = self
+            .annotation_value_completions(
+                &content,
+                &pos.position,
+                lang.as_ref(),
+                &imports,
+                package_name.clone(),
+            )
+            .await
+        {
+            return Ok(Some(CompletionResponse::Array(items)));
         }
-        let package_name = lang.get_package_name(&tree, &content);
 
         let jar_paths = self.jar_paths_for_file(&path).await;
 
+        let mut enclosing_types: Vec<String> = vec![];
         let line_prefix = if line.is_empty() || char_pos == 0 {
             ""
         } else {
@@ -3098,11 +4185,27 @@ impl LanguageServer for Backend {
                 })
                 .collect();
 
-            symbols.extend(self.complete_by_prefix(prefix, &jar_paths).await);
+            if let Some(this_type) =
+                lang.find_variable_type(&tree, &content, "this", &pos.position)
+                && let Some(this_fqn) = self
+                    .resolve_fqn(&this_type, imports.clone(), package_name.clone())
+                    .await
+            {
+                enclosing_types = self.type_and_supertypes(&this_fqn).await;
+                symbols.extend(
+                    self.complete_enclosing_members(prefix, &enclosing_types, &jar_paths)
+                        .await,
+                );
+            }
+
+            symbols.extend(self.complete_by_prefix(prefix, &jar_paths, &path).await);
             symbols
         };
 
-        symbols.sort_by_key(|s| completion_rank(s, package_name.as_deref()));
+        let preferences = self.import_preferences.read().await.clone();
+        symbols.sort_by_key(|s| {
+            completion_rank(s, package_name.as_deref(), &preferences, &enclosing_types)
+        });
 
         // Deduplicate: keep the first occurrence of each fqn.
         // Multiple JARs can contain the same class; after sorting, the preferred
@@ -3110,16 +4213,45 @@ impl LanguageServer for Backend {
         let mut seen_fqns = std::collections::HashSet::new();
         symbols.retain(|s| seen_fqns.insert(s.fully_qualified_name().to_string()));
 
+        let file_path = path.to_string_lossy();
+        let access = AccessContext {
+            file_path: &file_path,
+            package: package_name.as_deref().unwrap_or_default(),
+        };
+        self.retain_accessible(&mut symbols, &access).await;
+
+        // The kinds of the types declaring fields, to tell enum constants apart.
+        let mut parent_kinds: HashMap<String, Option<NodeKind>> = HashMap::new();
+        for s in &symbols {
+            if s.node_kind() == NodeKind::Field
+                && let Some(parent) = s.parent_name()
+                && !parent_kinds.contains_key(parent)
+            {
+                let kind = self.symbol_type_of(parent).await;
+                parent_kinds.insert(
+                    parent.to_string(),
+                    kind.as_deref().and_then(NodeKind::from_string),
+                );
+            }
+        }
+
         let items: Vec<CompletionItem> =
             symbols
                 .into_iter()
                 .filter(|s| s.name() != "<init>")
-                .map(|s| match s {
+                .enumerate()
+                .map(|(index, s)| match s {
                     ResolvedSymbol::External(_) | ResolvedSymbol::Project(_) => {
                         let is_function = s.node_kind() == lsp_core::node_kind::NodeKind::Function;
                         CompletionItem {
                         label: s.name().to_string(),
-                        kind: s.node_kind().to_lsp_kind(),
+                        // Keeps the server's ranking among items the client scores alike.
+                        sort_text: Some(format!("{index:05}")),
+                        kind: Some(s.completion_kind(
+                            s.parent_name()
+                                .and_then(|p| parent_kinds.get(p))
+                                .and_then(Option::as_ref),
+                        )),
                         insert_text: if is_function {
                             Some(format!("{}($0)", s.name()))
                         } else {
@@ -3130,10 +4262,12 @@ impl LanguageServer for Backend {
                         } else {
                             None
                         },
-                        detail: Some(s.package_name().unwrap_or_default().to_string()),
-                        additional_text_edits: if lang.get_implicit_imports().iter().any(|i| {
-                            i.trim_end_matches(".*") == s.package_name().unwrap_or_default()
-                        }) {
+                        detail: Some(s.container_fqn().unwrap_or_default().to_string()),
+                        additional_text_edits: if is_enclosing_member(&s, &enclosing_types)
+                            || lang.get_implicit_imports().iter().any(|i| {
+                                i.trim_end_matches(".*") == s.package_name().unwrap_or_default()
+                            })
+                        {
                             None
                         } else {
                             match s {
@@ -3191,6 +4325,7 @@ impl LanguageServer for Backend {
                         label: name,
                         kind: Some(CompletionItemKind::VARIABLE),
                         detail: var_type,
+                        sort_text: Some(format!("{index:05}")),
                         ..Default::default()
                     },
                 })
@@ -3203,18 +4338,149 @@ impl LanguageServer for Backend {
         }
     }
 
+    async fn document_symbol(
+        &self,
+        params: DocumentSymbolParams,
+    ) -> Result<Option<DocumentSymbolResponse>> {
+        Ok(self
+            .document_symbols(&params.text_document.uri)
+            .await
+            .map(DocumentSymbolResponse::Nested))
+    }
+
+    async fn symbol(
+        &self,
+        params: WorkspaceSymbolParams,
+    ) -> Result<Option<Vec<SymbolInformation>>> {
+        Ok(Some(self.workspace_symbols(&params.query).await))
+    }
+
+    async fn prepare_rename(
+        &self,
+        params: TextDocumentPositionParams,
+    ) -> Result<Option<PrepareRenameResponse>> {
+        self.prepare_rename_impl(params).await
+    }
+
     async fn rename(&self, params: RenameParams) -> Result<Option<WorkspaceEdit>> {
         self.rename_impl(params).await
     }
 
+    async fn code_action(&self, params: CodeActionParams) -> Result<Option<CodeActionResponse>> {
+        self.code_action_impl(params).await
+    }
+
+    async fn document_color(&self, params: DocumentColorParams) -> Result<Vec<ColorInformation>> {
+        self.document_color_impl(params).await
+    }
+
+    async fn color_presentation(
+        &self,
+        params: ColorPresentationParams,
+    ) -> Result<Vec<ColorPresentation>> {
+        self.color_presentation_impl(params).await
+    }
+
+    async fn inline_value(&self, params: InlineValueParams) -> Result<Option<Vec<InlineValue>>> {
+        self.inline_value_impl(params).await
+    }
+
+    async fn semantic_tokens_full(
+        &self,
+        params: SemanticTokensParams,
+    ) -> Result<Option<SemanticTokensResult>> {
+        self.semantic_tokens_full_impl(params).await
+    }
+
+    async fn semantic_tokens_full_delta(
+        &self,
+        params: SemanticTokensDeltaParams,
+    ) -> Result<Option<SemanticTokensFullDeltaResult>> {
+        self.semantic_tokens_full_delta_impl(params).await
+    }
+
+    async fn semantic_tokens_range(
+        &self,
+        params: SemanticTokensRangeParams,
+    ) -> Result<Option<SemanticTokensRangeResult>> {
+        self.semantic_tokens_range_impl(params).await
+    }
+
+    async fn on_type_formatting(
+        &self,
+        params: DocumentOnTypeFormattingParams,
+    ) -> Result<Option<Vec<TextEdit>>> {
+        self.on_type_formatting_impl(params).await
+    }
+
+    async fn will_rename_files(&self, params: RenameFilesParams) -> Result<Option<WorkspaceEdit>> {
+        self.will_rename_files_impl(params).await
+    }
+
+    async fn code_lens(&self, params: CodeLensParams) -> Result<Option<Vec<CodeLens>>> {
+        self.code_lens_impl(params).await
+    }
+
+    async fn code_lens_resolve(&self, params: CodeLens) -> Result<CodeLens> {
+        self.code_lens_resolve_impl(params).await
+    }
+
+    async fn document_link(
+        &self,
+        params: DocumentLinkParams,
+    ) -> Result<Option<Vec<DocumentLink>>> {
+        self.document_link_impl(params).await
+    }
+
+    async fn document_link_resolve(&self, params: DocumentLink) -> Result<DocumentLink> {
+        self.document_link_resolve_impl(params).await
+    }
+
+    async fn diagnostic(
+        &self,
+        params: DocumentDiagnosticParams,
+    ) -> Result<DocumentDiagnosticReportResult> {
+        let items = self
+            .compute_diagnostics(&params.text_document.uri)
+            .await
+            .unwrap_or_default();
+        Ok(DocumentDiagnosticReportResult::Report(
+            DocumentDiagnosticReport::Full(RelatedFullDocumentDiagnosticReport {
+                related_documents: None,
+                full_document_diagnostic_report: FullDocumentDiagnosticReport {
+                    result_id: None,
+                    items,
+                },
+            }),
+        ))
+    }
+
+    async fn workspace_diagnostic(
+        &self,
+        params: WorkspaceDiagnosticParams,
+    ) -> Result<WorkspaceDiagnosticReportResult> {
+        Ok(WorkspaceDiagnosticReportResult::Report(
+            self.workspace_diagnostic_report(&params).await,
+        ))
+    }
+
     async fn references(
         &self,
         params: ReferenceParams,
     ) -> Result<Option<Vec<Location>>> {
         let text_doc_pos = params.text_document_position;
+        self.gate_on_indexing(&text_doc_pos.text_document.uri, "textDocument/references")
+            .await;
         let path = PathBuf::from_str(text_doc_pos.text_document.uri.path()).unwrap();
         let position = text_doc_pos.position;
 
+        if is_spring_xml_candidate(&path) {
+            return Ok(self
+                .spring_bean_references(&text_doc_pos, params.context.include_declaration)
+                .await
+                .filter(|locations| !locations.is_empty()));
+        }
+
         let ext = match path.extension().and_then(|e| e.to_str()) {
             Some(e) => e.to_string(),
             None => return Ok(None),
@@ -3313,6 +4579,19 @@ impl LanguageServer for Backend {
             }
         }
 
+        // Spring XML names classes by FQN, and build scripts are no sources: both are
+        // out of reach of the textual scan above.
+        let imports = lang.get_imports(&tree, &content);
+        let package_name = lang.get_package_name(&tree, &content);
+        if let Some(fqn) = self.resolve_fqn(&ident, imports, package_name).await {
+            for (uri, range) in self.spring_xml_class_usages(&fqn).await {
+                locations.push(Location { uri, range });
+            }
+            for (uri, range) in self.build_script_class_usages(&fqn).await {
+                locations.push(Location { uri, range });
+            }
+        }
+
         if locations.is_empty() {
             Ok(None)
         } else {
@@ -3326,6 +4605,7 @@ impl LanguageServer for Backend {
             self.documents
                 .insert(uri.to_string(), (change.text, Instant::now()));
         }
+        self.build_file_edited(&uri).await;
         // Only enqueue an in-memory reindex once the initial bulk index has
         // finished publishing.  Otherwise our 300 ms-debounced writes contend
         // with the bulk indexer's DELETE/INSERT batch on the same SQLite file
@@ -3341,6 +4621,9 @@ impl LanguageServer for Backend {
     async fn did_close(&self, params: DidCloseTextDocumentParams) {
         let uri = params.text_document.uri;
         self.documents.remove(&uri.to_string());
+        self.semantic_tokens.remove(&uri);
+        // The file's text reverts to what is on disk.
+        self.build_file_edited(&uri).await;
         self.client.publish_diagnostics(uri, vec![], None).await;
     }
 
@@ -3354,19 +4637,44 @@ impl LanguageServer for Backend {
             .as_ref()
             .and_then(|vcs| vcs.get_revision_file(&root));
 
+        let moved = self.move_source_files(&params.changes).await;
         for change in params.changes {
             let Ok(path) = change.uri.to_file_path() else {
                 continue;
             };
+            if moved.contains(&path) {
+                continue;
+            }
 
+            if is_gradle_script(&path) {
+                self.reindex_build_script(&path).await;
+            }
             if change.typ == FileChangeType::DELETED {
                 self.documents.remove(&change.uri.to_string());
+                if resources_relative(&path).is_some() {
+                    self.reindex_resource_file(&path).await;
+                    if !is_spring_xml_candidate(&path) {
+                        continue;
+                    }
+                }
+                if is_spring_xml_candidate(&path) {
+                    self.reindex_spring_xml_file(&path).await;
+                    continue;
+                }
+                if self.is_index_reader() {
+                    continue;
+                }
                 let Some(repo) = self.repo.get() else {
                     continue;
                 };
+                let before = self.indexed_symbols(&path.to_string_lossy()).await;
                 if let Err(e) = repo.delete_symbols_for_file(&path.to_string_lossy()).await {
                     lsp_error!("Failed to remove symbols for {}: {e}", path.display());
                 }
+                self.file_removed(&path, &before);
+                if let Err(e) = repo.delete_file_hash(&path.to_string_lossy()).await {
+                    lsp_error!("Failed to remove file hash for {}: {e}", path.display());
+                }
             } else if revision_file.as_deref() == Some(&path) {
                 let Some(vcs) = vcs_guard.as_ref() else {
                     continue;
@@ -3397,6 +4705,17 @@ impl LanguageServer for Backend {
                     }
                 }
 
+                if resources_relative(&path).is_some() {
+                    self.reindex_resource_file(&path).await;
+                    if !is_spring_xml_candidate(&path) {
+                        continue;
+                    }
+                }
+                if is_spring_xml_candidate(&path) {
+                    self.reindex_spring_xml_file(&path).await;
+                    continue;
+                }
+
                 // Skip files currently open in the editor — did_save already re-indexes them.
                 if !self.documents.contains_key(&change.uri.to_string()) {
                     let _ = self.debounce_tx.send(path).await;
@@ -0,0 +1,276 @@
+//! Choosing between declarations that share a fully-qualified name.
+//!
+//! The same FQN can be declared more than once in a workspace: a test fake
+//! shadowing a main class, generated sources next to handwritten ones, or the
+//! same class in two modules.  Every copy is indexed; when a lookup lands on one
+//! of them, the copies are ranked from the requesting file's point of view:
+//! the requester's own source set first, then `main`, then any other, with
+//! handwritten sources ahead of generated ones.  All copies of the best rank are
+//! kept, in path order, so real ambiguity still shows up as several locations.
+//!
+//! Source sets also limit what code sees: main sources do not see test sources
+//! (see [`is_source_set_visible`]).  Access checks, prefix completion and
+//! add-import fixes all leave such declarations out.
+//!
+//! Kotlin Multiplatform `expect` declarations are the other legitimate duplicate:
+//! go-to-definition on one lists the `actual` declarations of the platform
+//! source sets.
+//!
+//! Two copies within one source set of one module are never legitimate — the
+//! compiler rejects them — and are reported on both files (see
+//! [`Backend::duplicate_class_diagnostics`]).
+
+use std::path::{Path, PathBuf};
+
+use tower_lsp::lsp_types::{
+    Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, NumberOrString,
+    TextDocumentPositionParams,
+};
+
+use crate::{
+    enums::ResolvedSymbol, lsp_convert::AsLspLocation, models::symbol::Symbol, server::Backend,
+};
+
+/// The source set a file belongs to: the directory after the last `src` (`main`,
+/// `test`, `testFixtures`, ...), or for generated sources the set they were
+/// generated for.
+pub(crate) fn source_set_of(path: &Path) -> Option<String> {
+    let components: Vec<&str> = path
+        .components()
+        .filter_map(|c| c.as_os_str().to_str())
+        .collect();
+    if let Some(src) = components.iter().rposition(|c| *c == "src")
+        && let Some(set) = components.get(src + 1)
+    {
+        return Some(set.to_string());
+    }
+    // Maven's target/generated-(test-)sources, Gradle's build/generated/.../<set>.
+    if components.contains(&"generated-test-sources") {
+        return Some("test".to_string());
+    }
+    if components.contains(&"generated-sources") {
+        return Some("main".to_string());
+    }
+    components
+        .iter()
+        .rev()
+        .find(|c| matches!(**c, "main" | "test"))
+        .map(|c| c.to_string())
+}
+
+/// Whether code in `from` can see declarations in `declaration`.  Production source
+/// sets — `main`, or a Kotlin Multiplatform `<target>Main` — see themselves and
+/// `commonMain`; every other source set (tests, fixtures, ...) sees everything.
+/// Files outside any known source set see and are seen by all.
+pub(crate) fn is_source_set_visible(from: &Path, declaration: &Path) -> bool {
+    match (
+        source_set_of(from).as_deref(),
+        source_set_of(declaration).as_deref(),
+    ) {
+        (Some(from_set), Some(set)) if is_production_set(from_set) => {
+            set == from_set || set == "commonMain"
+        }
+        _ => true,
+    }
+}
+
+/// The root directory of the source set `path` is in, e.g. `core/src/main`, which
+/// tells the same set of two modules apart.  `None` outside a `src` directory.
+fn source_set_root(path: &Path) -> Option<PathBuf> {
+    let components: Vec<_> = path.components().collect();
+    let src = components.iter().rposition(|c| c.as_os_str() == "src")?;
+    components.get(src + 1)?;
+    Some(components[..src + 2].iter().collect())
+}
+
+fn is_production_set(set: &str) -> bool {
+    set == "main" || set.ends_with("Main")
+}
+
+fn is_generated(path: &Path) -> bool {
+    path.components()
+        .filter_map(|c| c.as_os_str().to_str())
+        .any(|c| c.starts_with("generated"))
+}
+
+/// Lower is better.
+fn rank(from_set: Option<&str>, declaration: &Symbol) -> (u8, bool) {
+    let path = Path::new(&declaration.file_path);
+    let set = source_set_of(path);
+    let set_rank = match set.as_deref() {
+        Some(s) if Some(s) == from_set => 0,
+        Some("main") => 1,
+        _ => 2,
+    };
+    (set_rank, is_generated(path))
+}
+
+fn parameter_types(symbol: &Symbol) -> Option<Vec<Option<&str>>> {
+    symbol
+        .metadata
+        .parameters
+        .as_ref()
+        .map(|params| params.iter().map(|p| p.type_name.as_deref()).collect())
+}
+
+impl Backend {
+    /// Replaces each project symbol declared more than once with the copies of its
+    /// FQN that rank best as seen from `from`.
+    pub(crate) async fn prefer_source_set(
+        &self,
+        symbols: Vec<ResolvedSymbol>,
+        from: &Path,
+    ) -> Vec<ResolvedSymbol> {
+        let Some(repo) = self.repo.get() else {
+            return symbols;
+        };
+        let from_set = source_set_of(from);
+
+        let mut preferred: Vec<ResolvedSymbol> = Vec::with_capacity(symbols.len());
+        for symbol in symbols {
+            let ResolvedSymbol::Project(declaration) = &symbol else {
+                preferred.push(symbol);
+                continue;
+            };
+            let mut copies: Vec<Symbol> = repo
+                .find_symbols_by_fqn(&declaration.fully_qualified_name)
+                .await
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|c| {
+                    c.symbol_type == declaration.symbol_type
+                        && parameter_types(c) == parameter_types(declaration)
+                })
+                .collect();
+            if copies.iter().all(|c| c.file_path == declaration.file_path) {
+                preferred.push(symbol);
+                continue;
+            }
+
+            let Some(best) = copies.iter().map(|c| rank(from_set.as_deref(), c)).min() else {
+                preferred.push(symbol);
+                continue;
+            };
+            copies.retain(|c| rank(from_set.as_deref(), c) == best);
+            copies.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+            for copy in copies {
+                let seen = preferred.iter().any(|p| {
+                    matches!(p, ResolvedSymbol::Project(s)
+                        if s.file_path == copy.file_path
+                            && s.ident_line_start == copy.ident_line_start)
+                });
+                if !seen {
+                    preferred.push(ResolvedSymbol::Project(copy));
+                }
+            }
+        }
+        preferred
+    }
+
+    /// One diagnostic per class declared in `path` that another file of the same
+    /// source set declares too, pointing at the other declarations.
+    pub(crate) async fn duplicate_class_diagnostics(&self, path: &Path) -> Vec<Diagnostic> {
+        let Some(repo) = self.repo.get() else {
+            return vec![];
+        };
+        let Some(root) = source_set_root(path) else {
+            return vec![];
+        };
+        let declared = repo
+            .find_symbols_by_file_path(&path.to_string_lossy())
+            .await
+            .unwrap_or_default();
+
+        let mut diagnostics = Vec::new();
+        for class in declared
+            .iter()
+            .filter(|s| !s.fully_qualified_name.contains('#'))
+            .filter(|s| !s.modifiers.0.iter().any(|m| m == "expect" || m == "actual"))
+        {
+            let mut others: Vec<Symbol> = repo
+                .find_symbols_by_fqn(&class.fully_qualified_name)
+                .await
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|s| {
+                    s.file_path != class.file_path
+                        && source_set_root(Path::new(&s.file_path)).as_ref() == Some(&root)
+                })
+                .collect();
+            if others.is_empty() {
+                continue;
+            }
+            others.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+            let Some(range) = class.as_lsp_location().map(|l| l.range) else {
+                continue;
+            };
+            let related: Vec<DiagnosticRelatedInformation> = others
+                .iter()
+                .filter_map(|other| {
+                    Some(DiagnosticRelatedInformation {
+                        location: other.as_lsp_location()?,
+                        message: format!("'{}' is also declared here", class.short_name),
+                    })
+                })
+                .collect();
+            let elsewhere: Vec<String> = others
+                .iter()
+                .filter_map(|o| Path::new(&o.file_path).file_name())
+                .map(|name| name.to_string_lossy().to_string())
+                .collect();
+            diagnostics.push(Diagnostic {
+                range,
+                severity: Some(DiagnosticSeverity::ERROR),
+                code: Some(NumberOrString::String("duplicate_class".to_string())),
+                source: Some("lspintar".to_string()),
+                message: format!(
+                    "Duplicate class '{}': also declared in {}",
+                    class.fully_qualified_name,
+                    elsewhere.join(", ")
+                ),
+                related_information: Some(related),
+                ..Default::default()
+            });
+        }
+        diagnostics
+    }
+
+    /// When the request position is on the name of an `expect` declaration, returns the
+    /// matching `actual` declarations.  `None` anywhere else, so regular resolution
+    /// applies.
+    pub(crate) async fn resolve_actual_declarations(
+        &self,
+        params: &TextDocumentPositionParams,
+    ) -> Option<Vec<ResolvedSymbol>> {
+        let path = params.text_document.uri.to_file_path().ok()?;
+        if path.extension().and_then(|e| e.to_str()) != Some("kt") {
+            return None;
+        }
+        let repo = self.repo.get()?;
+        let position = params.position;
+        let expect = repo
+            .find_symbols_by_file_path(&path.to_string_lossy())
+            .await
+            .ok()?
+            .into_iter()
+            .find(|s| {
+                s.modifiers.0.iter().any(|m| m == "expect")
+                    && s.ident_line_start == position.line as i64
+                    && s.ident_char_start <= position.character as i64
+                    && position.character as i64 <= s.ident_char_end
+            })?;
+
+        let mut actuals: Vec<Symbol> = repo
+            .find_symbols_by_fqn(&expect.fully_qualified_name)
+            .await
+            .ok()?
+            .into_iter()
+            .filter(|s| s.modifiers.0.iter().any(|m| m == "actual"))
+            .collect();
+        if actuals.is_empty() {
+            return None;
+        }
+        actuals.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        Some(actuals.into_iter().map(ResolvedSymbol::Project).collect())
+    }
+}
@@ -0,0 +1,283 @@
+//! Spring XML bean definitions.
+//!
+//! `*.xml` files with a `<beans>` element are scanned for the attributes that
+//! name classes (`class="com.foo.Bar"`) and beans (`id`, `ref`, `parent`,
+//! `factory-bean`, and `bean` on `<ref>`/`<idref>`).  The scan is textual, so
+//! malformed files still yield whatever attributes can be read.  The result is
+//! kept in memory per file: it is built once the workspace index is ready and
+//! refreshed when an XML file is saved or changes on disk.
+//!
+//! Bean references resolve to the `id` of the bean they name; class
+//! attributes resolve through [`Backend::resolve_string_reference`].  XML
+//! usages of a class are reported by find-references and renamed, pending
+//! confirmation, along with the class.
+
+use std::path::{Path, PathBuf};
+
+use tower_lsp::lsp_types::{Location, Position, Range, TextDocumentPositionParams, Url};
+use walkdir::WalkDir;
+
+use crate::{enums::ResolvedSymbol, indexer::is_excluded, server::Backend};
+
+/// What a bean definition attribute names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BeanAttributeKind {
+    /// The `id` of a `<bean>`.
+    Id,
+    /// A fully-qualified class name.
+    Class,
+    /// The id of another bean.
+    Ref,
+}
+
+/// An attribute value found in a Spring XML file.  `range` covers the value only,
+/// without its quotes.
+#[derive(Debug, Clone)]
+pub(crate) struct BeanAttribute {
+    pub kind: BeanAttributeKind,
+    pub value: String,
+    pub range: Range,
+}
+
+/// True for files that may hold bean definitions.
+pub(crate) fn is_spring_xml_candidate(path: &Path) -> bool {
+    path.extension().and_then(|e| e.to_str()) == Some("xml")
+}
+
+/// Collects the class and bean attributes of a Spring XML file.  Returns nothing
+/// for XML files without a `<beans>` element.
+pub(crate) fn parse_bean_attributes(content: &str) -> Vec<BeanAttribute> {
+    if !content.contains("<beans") && !content.contains(":beans") {
+        return vec![];
+    }
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(content.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    let position_of = |offset: usize| {
+        let line = line_starts.partition_point(|&start| start <= offset) - 1;
+        let character = content[line_starts[line]..offset].chars().count();
+        Position::new(line as u32, character as u32)
+    };
+
+    let mut attributes = Vec::new();
+    let mut cursor = 0;
+    while let Some(found) = content[cursor..].find('<') {
+        let start = cursor + found;
+        if content[start..].starts_with("<!--") {
+            cursor = content[start..]
+                .find("-->")
+                .map_or(content.len(), |end| start + end + 3);
+            continue;
+        }
+        let end = tag_end(content, start);
+        let tag = &content[start + 1..end];
+        let element = tag
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default();
+        let element = element.rsplit(':').next().unwrap_or(element);
+
+        for (name, value_offset, value) in tag_attributes(tag) {
+            let kind = match (element, name) {
+                ("bean", "id") => BeanAttributeKind::Id,
+                (_, "class") => BeanAttributeKind::Class,
+                (_, "ref" | "parent" | "factory-bean") => BeanAttributeKind::Ref,
+                ("ref" | "idref", "bean") => BeanAttributeKind::Ref,
+                _ => continue,
+            };
+            let value_start = start + 1 + value_offset;
+            attributes.push(BeanAttribute {
+                kind,
+                value: value.to_string(),
+                range: Range::new(
+                    position_of(value_start),
+                    position_of(value_start + value.len()),
+                ),
+            });
+        }
+        cursor = (end + 1).min(content.len());
+    }
+    attributes
+}
+
+/// Byte offset of the `>` closing the tag opened at `start`, ignoring `>` inside
+/// quoted attribute values.
+fn tag_end(content: &str, start: usize) -> usize {
+    let mut quote = None;
+    for (i, c) in content[start..].char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if q == c => quote = None,
+            (None, '>') => return start + i,
+            _ => {}
+        }
+    }
+    content.len()
+}
+
+/// The `name="value"` pairs of a tag (without its `<`), as
+/// `(name, byte offset of the value within tag, value)`.
+fn tag_attributes(tag: &str) -> Vec<(&str, usize, &str)> {
+    let mut attributes = Vec::new();
+    let mut rest = tag;
+    while let Some(eq) = rest.find('=') {
+        let name = rest[..eq].split_whitespace().last().unwrap_or_default();
+        let after = rest[eq + 1..].trim_start();
+        let Some(quote) = after.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            rest = after;
+            continue;
+        };
+        let Some(len) = after[1..].find(quote) else {
+            break;
+        };
+        let value_offset = tag.len() - after.len() + 1;
+        attributes.push((name, value_offset, &after[1..1 + len]));
+        rest = &after[1 + len + 1..];
+    }
+    attributes
+}
+
+impl Backend {
+    /// Scans every XML file under `root` for bean definitions.
+    pub(crate) async fn index_spring_xml(&self, root: &Path) {
+        let root = root.to_path_buf();
+        let files = tokio::task::spawn_blocking(move || {
+            WalkDir::new(root)
+                .follow_links(true)
+                .into_iter()
+                .filter_entry(|e| !is_excluded(e))
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_type().is_file() && is_spring_xml_candidate(e.path()))
+                .filter_map(|e| {
+                    let content = std::fs::read_to_string(e.path()).ok()?;
+                    let attributes = parse_bean_attributes(&content);
+                    (!attributes.is_empty()).then(|| (e.into_path(), attributes))
+                })
+                .collect::<Vec<_>>()
+        })
+        .await
+        .unwrap_or_default();
+
+        let mut index = self.spring_beans.write().await;
+        index.clear();
+        index.extend(files);
+    }
+
+    /// Re-reads one XML file, dropping it from the index when it is gone or no longer
+    /// defines beans.
+    pub(crate) async fn reindex_spring_xml_file(&self, path: &Path) {
+        let attributes = std::fs::read_to_string(path)
+            .map(|content| parse_bean_attributes(&content))
+            .unwrap_or_default();
+        let mut index = self.spring_beans.write().await;
+        if attributes.is_empty() {
+            index.remove(path);
+        } else {
+            index.insert(path.to_path_buf(), attributes);
+        }
+    }
+
+    /// Resolves a bean reference at the request position to the bean's `id`.
+    pub(crate) async fn resolve_spring_bean_reference(
+        &self,
+        params: &TextDocumentPositionParams,
+    ) -> Vec<ResolvedSymbol> {
+        let Some(path) = params.text_document.uri.to_file_path().ok() else {
+            return vec![];
+        };
+        let Some(attribute) = self.bean_attribute_at(&path, &params.position).await else {
+            return vec![];
+        };
+        if attribute.kind != BeanAttributeKind::Ref {
+            return vec![];
+        }
+        self.bean_attributes(|a| a.kind == BeanAttributeKind::Id && a.value == attribute.value)
+            .await
+            .into_iter()
+            .map(|(uri, a)| ResolvedSymbol::Local {
+                uri,
+                position: a.range.start,
+                name: a.value,
+                var_type: None,
+            })
+            .collect()
+    }
+
+    /// For a request made from an XML file: the references of the bean whose `id` or
+    /// reference is under the cursor.  `None` when the cursor is on neither.
+    pub(crate) async fn spring_bean_references(
+        &self,
+        params: &TextDocumentPositionParams,
+        include_declaration: bool,
+    ) -> Option<Vec<Location>> {
+        let path = params.text_document.uri.to_file_path().ok()?;
+        let attribute = self.bean_attribute_at(&path, &params.position).await?;
+        if attribute.kind == BeanAttributeKind::Class {
+            return None;
+        }
+        let locations = self
+            .bean_attributes(|a| {
+                a.value == attribute.value
+                    && match a.kind {
+                        BeanAttributeKind::Ref => true,
+                        BeanAttributeKind::Id => include_declaration,
+                        BeanAttributeKind::Class => false,
+                    }
+            })
+            .await
+            .into_iter()
+            .map(|(uri, a)| Location::new(uri, a.range))
+            .collect();
+        Some(locations)
+    }
+
+    /// The class attributes naming `fqn`, as the ranges of the simple class name within
+    /// each value.  Nested classes match both their source name (`Outer.Inner`) and
+    /// binary name (`Outer$Inner`).
+    pub(crate) async fn spring_xml_class_usages(&self, fqn: &str) -> Vec<(Url, Range)> {
+        self.bean_attributes(|a| {
+            a.kind == BeanAttributeKind::Class && a.value.replace('$', ".") == fqn
+        })
+        .await
+        .into_iter()
+        .map(|(uri, a)| {
+            let simple_name = a.value.rsplit(['.', '$']).next().unwrap_or_default();
+            let mut start = a.range.end;
+            start.character -= simple_name.chars().count() as u32;
+            (uri, Range::new(start, a.range.end))
+        })
+        .collect()
+    }
+
+    async fn bean_attribute_at(&self, path: &Path, position: &Position) -> Option<BeanAttribute> {
+        let index = self.spring_beans.read().await;
+        index
+            .get(path)?
+            .iter()
+            .find(|a| {
+                a.range.start.line == position.line
+                    && a.range.start.character <= position.character
+                    && position.character <= a.range.end.character
+            })
+            .cloned()
+    }
+
+    async fn bean_attributes(
+        &self,
+        filter: impl Fn(&BeanAttribute) -> bool,
+    ) -> Vec<(Url, BeanAttribute)> {
+        let index = self.spring_beans.read().await;
+        let mut files: Vec<(&PathBuf, &Vec<BeanAttribute>)> = index.iter().collect();
+        files.sort_by(|a, b| a.0.cmp(b.0));
+        files
+            .into_iter()
+            .filter_map(|(path, attributes)| Some((Url::from_file_path(path).ok()?, attributes)))
+            .flat_map(|(uri, attributes)| {
+                attributes
+                    .iter()
+                    .filter(|a| filter(a))
+                    .map(move |a| (uri.clone(), a.clone()))
+            })
+            .collect()
+    }
+}
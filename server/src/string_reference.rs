@@ -0,0 +1,84 @@
+//! Navigation from string literals naming classes or packages.
+//!
+//! Frameworks refer to types by name in strings — `Class.forName("com.foo.Bar")`,
+//! Spring XML `class="com.foo.Bar"`, `@ComponentScan(basePackages = "com.foo")` —
+//! which no grammar resolves.  When regular resolution finds nothing, the quoted
+//! text under the cursor is looked up in the symbol index: first as a type FQN
+//! (binary names such as `Outer$Inner` included), then as a package, whose
+//! top-level types are returned.  The lookup is purely textual so it also works
+//! in files without language support, such as XML.
+
+use tower_lsp::lsp_types::{Position, TextDocumentPositionParams};
+
+use crate::{enums::ResolvedSymbol, server::Backend};
+
+/// Returns the quoted text around `position` when it looks like a qualified name.
+pub(crate) fn qualified_name_literal_at(content: &str, position: &Position) -> Option<String> {
+    let line: Vec<char> = content.lines().nth(position.line as usize)?.chars().collect();
+    let cursor = (position.character as usize).min(line.len());
+    let is_quote = |c: char| c == '"' || c == '\'';
+
+    let open = line[..cursor].iter().rposition(|&c| is_quote(c))?;
+    let close = open + 1 + line[open + 1..].iter().position(|&c| c == line[open])?;
+    if close < cursor {
+        return None;
+    }
+    let literal: String = line[open + 1..close].iter().collect();
+    is_qualified_name(&literal).then_some(literal)
+}
+
+/// True for dotted names of at least two identifier segments, e.g. `com.foo.Bar`.
+fn is_qualified_name(text: &str) -> bool {
+    let mut segments = 0;
+    for segment in text.split('.') {
+        let mut chars = segment.chars();
+        let starts_ok = chars
+            .next()
+            .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$');
+        if !starts_ok || !chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$') {
+            return false;
+        }
+        segments += 1;
+    }
+    segments >= 2
+}
+
+impl Backend {
+    /// Resolves the string literal at the request position to the type or package
+    /// it names, or returns nothing when it names neither.
+    pub(crate) async fn resolve_string_reference(
+        &self,
+        params: &TextDocumentPositionParams,
+    ) -> Vec<ResolvedSymbol> {
+        let Some(content) = self.document_text(&params.text_document.uri) else {
+            return vec![];
+        };
+        let Some(literal) = qualified_name_literal_at(&content, &params.position) else {
+            return vec![];
+        };
+        let Some(repo) = self.repo.get() else {
+            return vec![];
+        };
+
+        let binary_name = literal.replace('$', ".");
+        for fqn in [literal.as_str(), binary_name.as_str()] {
+            if let Ok(Some(symbol)) = repo.find_symbol_by_fqn(fqn).await {
+                return vec![ResolvedSymbol::Project(symbol)];
+            }
+            if let Ok(Some(symbol)) = repo.find_external_symbol_by_fqn(fqn).await {
+                return vec![ResolvedSymbol::External(symbol)];
+            }
+        }
+
+        repo.find_symbols_by_parent_name(&literal)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|s| {
+                s.package_name == literal
+                    && !matches!(s.symbol_type.as_str(), "Function" | "Field")
+            })
+            .map(ResolvedSymbol::Project)
+            .collect()
+    }
+}
@@ -0,0 +1,181 @@
+//! Memoized supertype closures.
+//!
+//! Member resolution, override checks and the method-existence diagnostics all walk a
+//! type's `extends`/`implements` chain, one `symbol_super_mapping` query per level.
+//! [`SupertypeCache`] keeps the walk's result — every indexed ancestor of a type,
+//! nearest first — until a file declaring the type or one of its ancestors is
+//! re-indexed.
+
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::Arc,
+};
+
+use dashmap::DashMap;
+
+use crate::{models::symbol::Symbol, repo::Repository, server::Backend};
+
+#[derive(Default)]
+pub(crate) struct SupertypeCache {
+    closures: DashMap<String, Arc<Vec<Symbol>>>,
+}
+
+impl SupertypeCache {
+    /// Every indexed ancestor of `fqn`, breadth-first so direct supertypes come before
+    /// theirs.  `fqn` itself is not included.
+    pub(crate) async fn ancestors(&self, repo: &Repository, fqn: &str) -> Arc<Vec<Symbol>> {
+        if let Some(closure) = self.closures.get(fqn) {
+            return Arc::clone(&closure);
+        }
+
+        let mut visited: HashSet<String> = HashSet::from([fqn.to_string()]);
+        let mut queue = VecDeque::from([fqn.to_string()]);
+        let mut ancestors = Vec::new();
+        while let Some(current) = queue.pop_front() {
+            for s in repo
+                .find_supers_by_symbol_fqn(&current)
+                .await
+                .unwrap_or_default()
+            {
+                if visited.insert(s.fully_qualified_name.clone()) {
+                    queue.push_back(s.fully_qualified_name.clone());
+                    ancestors.push(s);
+                }
+            }
+        }
+
+        let closure = Arc::new(ancestors);
+        self.closures.insert(fqn.to_string(), Arc::clone(&closure));
+        closure
+    }
+
+    /// Drops what a file declaring `before` and now declaring `after` may have
+    /// changed: the closure of every type that is, or descends from, one of them.
+    /// A type new to the file may be the missing link of any cached chain, so then
+    /// everything goes.
+    pub(crate) fn invalidate(&self, before: &[String], after: &[String]) {
+        if after.iter().any(|fqn| !before.contains(fqn)) {
+            self.clear();
+            return;
+        }
+        self.closures.retain(|fqn, closure| {
+            !before.contains(fqn)
+                && !closure
+                    .iter()
+                    .any(|s| before.contains(&s.fully_qualified_name))
+        });
+    }
+
+    pub(crate) fn clear(&self) {
+        self.closures.clear();
+    }
+}
+
+/// The types among `symbols`, leaving out their members.
+pub(crate) fn type_fqns(symbols: &[Symbol]) -> Vec<String> {
+    symbols
+        .iter()
+        .filter(|s| !s.fully_qualified_name.contains('#'))
+        .map(|s| s.fully_qualified_name.clone())
+        .collect()
+}
+
+/// Every indexed project type extending or implementing `fqn`, directly or through
+/// another, breadth-first.  Not memoized: it depends on every file, not one chain.
+pub async fn descendants(repo: &Repository, fqn: &str) -> Vec<Symbol> {
+    descendant_tree(repo, fqn)
+        .await
+        .into_iter()
+        .map(|(symbol, _)| symbol)
+        .collect()
+}
+
+/// [`descendants`], each with the type it was reached from: `fqn` or a descendant
+/// listed before it.
+pub(crate) async fn descendant_tree(repo: &Repository, fqn: &str) -> Vec<(Symbol, String)> {
+    let mut visited: HashSet<String> = HashSet::from([fqn.to_string()]);
+    let mut queue = VecDeque::from([fqn.to_string()]);
+    let mut descendants = Vec::new();
+    while let Some(current) = queue.pop_front() {
+        for s in repo
+            .find_super_impls_by_fqn(&current)
+            .await
+            .unwrap_or_default()
+        {
+            if visited.insert(s.fully_qualified_name.clone()) {
+                queue.push_back(s.fully_qualified_name.clone());
+                descendants.push((s, current.clone()));
+            }
+        }
+    }
+    descendants
+}
+
+/// Whether `method` can be overridden: not a constructor, and neither private,
+/// static nor final.
+pub(crate) fn is_overridable(method: &Symbol) -> bool {
+    let owner = method
+        .parent_name
+        .as_deref()
+        .map(|parent| parent.rsplit('.').next().unwrap_or(parent));
+    method.symbol_type == "Function"
+        && owner != Some(method.short_name.as_str())
+        && !method
+            .modifiers
+            .iter()
+            .any(|m| matches!(m.as_str(), "private" | "static" | "final"))
+}
+
+/// The project declarations overriding a method, below the type declaring it.
+pub(crate) struct MethodOverrides {
+    /// Every override, at any depth.
+    pub overriding: Vec<Symbol>,
+    /// The classes inheriting the method unchanged: those with no override on the
+    /// way down to them.
+    pub inheriting: Vec<Symbol>,
+}
+
+/// The overrides of the method `name` taking `arity` parameters among the types of
+/// `tree`, the [`descendant_tree`] of the type declaring it.
+pub(crate) async fn method_overrides(
+    repo: &Repository,
+    tree: &[(Symbol, String)],
+    name: &str,
+    arity: usize,
+) -> MethodOverrides {
+    let mut overrides = MethodOverrides {
+        overriding: Vec::new(),
+        inheriting: Vec::new(),
+    };
+    // The types at or below an override, which inherit that override instead.
+    let mut overridden: HashSet<&str> = HashSet::new();
+    for (subtype, reached_from) in tree {
+        let fqn = subtype.fully_qualified_name.as_str();
+        let own: Vec<Symbol> = repo
+            .find_symbols_by_fqn(&format!("{fqn}#{name}"))
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|s| s.metadata.parameters.as_ref().map_or(0, Vec::len) == arity)
+            .collect();
+        if !own.is_empty() {
+            overrides.overriding.extend(own);
+            overridden.insert(fqn);
+        } else if overridden.contains(reached_from.as_str()) {
+            overridden.insert(fqn);
+        } else if subtype.symbol_type != "Interface" {
+            overrides.inheriting.push(subtype.clone());
+        }
+    }
+    overrides
+}
+
+impl Backend {
+    /// Every indexed ancestor of `fqn`, nearest first.
+    pub async fn supertype_closure(&self, fqn: &str) -> Arc<Vec<Symbol>> {
+        match self.repo.get() {
+            Some(repo) => self.supertypes.ancestors(repo, fqn).await,
+            None => Arc::new(vec![]),
+        }
+    }
+}
@@ -0,0 +1,281 @@
+//! Suppressing diagnostics from the source.
+//!
+//! `// lspintar:disable <rule>` disables a rule on the line it trails, on the next
+//! line when it stands on its own, and in the whole file when it stands before the
+//! first line of code.  `@SuppressWarnings("lspintar:<rule>")`, or `@Suppress` in
+//! Kotlin, disables it in the declaration it annotates, and `@file:Suppress` in the
+//! file.  A directive may list several rules, separated by commas or spaces; `all`
+//! stands for every rule.  A rule is a diagnostic's code, e.g. `unused_import`.
+//!
+//! Each diagnostic in a code action request is offered quick fixes inserting the
+//! directive for its line, its enclosing declaration and its file.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
+
+use lsp_core::{
+    language_support::LanguageSupport, languages::Language, ts_helper::get_node_at_position,
+};
+use tower_lsp::lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOrCommand, Diagnostic, NumberOrString, Position, Range,
+    TextEdit, Url, WorkspaceEdit,
+};
+use tree_sitter::{Node, Tree};
+
+use crate::server::Backend;
+
+const DIRECTIVE: &str = "lspintar:disable";
+const ANNOTATION_PREFIX: &str = "lspintar:";
+const ALL_RULES: &str = "all";
+
+/// The rules disabled in a file, by scope.
+#[derive(Debug, Default)]
+pub struct Suppressions {
+    file: HashSet<String>,
+    /// By zero-based line.
+    lines: HashMap<u32, HashSet<String>>,
+    declarations: Vec<(Range, HashSet<String>)>,
+}
+
+impl Suppressions {
+    /// The directives of `content`, whose declarations are read from `tree`.
+    pub fn parse(tree: &Tree, content: &str, lang: &dyn LanguageSupport) -> Self {
+        let mut suppressions = Self::default();
+        let mut seen_code = false;
+        for (line_no, line) in content.lines().enumerate() {
+            let trimmed = line.trim_start();
+            let is_comment = ["//", "/*", "*"].iter().any(|p| trimmed.starts_with(p));
+            if let Some((comment_start, rules)) = comment_directive(line) {
+                let standalone = line[..comment_start].trim().is_empty();
+                let line_no = line_no as u32;
+                if !standalone {
+                    suppressions.lines.entry(line_no).or_default().extend(rules);
+                } else if !seen_code {
+                    suppressions.file.extend(rules);
+                } else {
+                    suppressions
+                        .lines
+                        .entry(line_no + 1)
+                        .or_default()
+                        .extend(rules);
+                }
+            }
+            if !trimmed.is_empty() && !is_comment {
+                seen_code = true;
+            }
+        }
+
+        for (at, arguments) in suppress_annotations(content) {
+            let rules: HashSet<String> = quoted(arguments)
+                .filter_map(|s| s.strip_prefix(ANNOTATION_PREFIX))
+                .map(str::to_string)
+                .collect();
+            if rules.is_empty() {
+                continue;
+            }
+            if content[at..].starts_with("@file:") {
+                suppressions.file.extend(rules);
+                continue;
+            }
+            let Some(node) = tree.root_node().descendant_for_byte_range(at, at) else {
+                continue;
+            };
+            if let Some(declaration) = enclosing_declaration(node, lang) {
+                suppressions
+                    .declarations
+                    .push((node_range(&declaration), rules));
+            }
+        }
+        suppressions
+    }
+
+    /// Whether a directive disables `diagnostic`.
+    pub fn suppresses(&self, diagnostic: &Diagnostic) -> bool {
+        let Some(NumberOrString::String(code)) = &diagnostic.code else {
+            return false;
+        };
+        let names = |rules: &HashSet<String>| rules.contains(code) || rules.contains(ALL_RULES);
+        let start = diagnostic.range.start;
+        names(&self.file)
+            || self.lines.get(&start.line).is_some_and(names)
+            || self
+                .declarations
+                .iter()
+                .any(|(range, rules)| range.start <= start && start <= range.end && names(rules))
+    }
+}
+
+/// The byte offset of the comment holding a `lspintar:disable` directive on `line`,
+/// with the rules it lists.
+fn comment_directive(line: &str) -> Option<(usize, Vec<String>)> {
+    let comment_start = line.find("//")?;
+    let comment = &line[comment_start..];
+    let rules = &comment[comment.find(DIRECTIVE)? + DIRECTIVE.len()..];
+    if !rules.starts_with([' ', '\t']) {
+        return None;
+    }
+    let rules: Vec<String> = rules
+        .split([',', ' ', '\t'])
+        .filter(|r| !r.is_empty())
+        .map(str::to_string)
+        .collect();
+    (!rules.is_empty()).then_some((comment_start, rules))
+}
+
+/// The `@SuppressWarnings` and `@Suppress` annotations in `content`: the byte offset
+/// of each `@` with the text between its parentheses.
+fn suppress_annotations(content: &str) -> Vec<(usize, &str)> {
+    let mut annotations = Vec::new();
+    for (at, _) in content.match_indices('@') {
+        let rest = &content[at + 1..];
+        let rest = rest.strip_prefix("file:").unwrap_or(rest);
+        let Some(arguments) = rest
+            .strip_prefix("SuppressWarnings(")
+            .or_else(|| rest.strip_prefix("Suppress("))
+        else {
+            continue;
+        };
+        if let Some(end) = arguments.find(')') {
+            annotations.push((at, &arguments[..end]));
+        }
+    }
+    annotations
+}
+
+/// The contents of the string literals in `text`.
+fn quoted(text: &str) -> impl Iterator<Item = &str> {
+    text.split('"').skip(1).step_by(2)
+}
+
+/// The innermost class, method or field declaration around `node`.
+fn enclosing_declaration<'t>(node: Node<'t>, lang: &dyn LanguageSupport) -> Option<Node<'t>> {
+    std::iter::successors(Some(node), |n| n.parent()).find(|n| lang.get_kind(n).is_some())
+}
+
+/// Whether `declaration` already carries a `@SuppressWarnings` or `@Suppress`, which
+/// Java does not allow twice.
+fn is_annotated(
+    tree: &Tree,
+    content: &str,
+    lang: &dyn LanguageSupport,
+    declaration: &Node,
+) -> bool {
+    suppress_annotations(content).iter().any(|(at, _)| {
+        !content[*at..].starts_with("@file:")
+            && tree
+                .root_node()
+                .descendant_for_byte_range(*at, *at)
+                .and_then(|node| enclosing_declaration(node, lang))
+                .is_some_and(|d| d.id() == declaration.id())
+    })
+}
+
+fn node_range(node: &Node) -> Range {
+    let (start, end) = (node.start_position(), node.end_position());
+    Range::new(
+        Position::new(start.row as u32, start.column as u32),
+        Position::new(end.row as u32, end.column as u32),
+    )
+}
+
+fn suppress_action(
+    uri: &Url,
+    diagnostic: &Diagnostic,
+    title: String,
+    edit: TextEdit,
+) -> CodeActionOrCommand {
+    CodeActionOrCommand::CodeAction(CodeAction {
+        title,
+        kind: Some(CodeActionKind::QUICKFIX),
+        diagnostics: Some(vec![diagnostic.clone()]),
+        edit: Some(WorkspaceEdit {
+            changes: Some(HashMap::from([(uri.clone(), vec![edit])])),
+            ..Default::default()
+        }),
+        ..Default::default()
+    })
+}
+
+impl Backend {
+    /// Quick fixes disabling `diagnostic`'s rule on its line, in its enclosing
+    /// declaration and in its file.
+    pub(crate) fn suppression_actions(
+        &self,
+        uri: &Url,
+        diagnostic: &Diagnostic,
+    ) -> Vec<CodeActionOrCommand> {
+        let Some(NumberOrString::String(rule)) = &diagnostic.code else {
+            return vec![];
+        };
+        if diagnostic.source.as_deref() != Some("lspintar") {
+            return vec![];
+        }
+        let Some(content) = self.document_text(uri) else {
+            return vec![];
+        };
+        let line_no = diagnostic.range.start.line;
+        let Some(line) = content.lines().nth(line_no as usize) else {
+            return vec![];
+        };
+
+        let end_of_line = Position::new(line_no, line.encode_utf16().count() as u32);
+        let mut actions = vec![suppress_action(
+            uri,
+            diagnostic,
+            format!("Suppress '{rule}' on this line"),
+            TextEdit::new(
+                Range::new(end_of_line, end_of_line),
+                format!(" // {DIRECTIVE} {rule}"),
+            ),
+        )];
+
+        let path = PathBuf::from(uri.path());
+        let lang = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .and_then(|ext| self.languages.get(ext));
+        if let Some(lang) = lang
+            && let Some((tree, _)) = lang.parse_str(&content)
+            && let Some(node) = get_node_at_position(&tree, &content, &diagnostic.range.start)
+            && let Some(declaration) = enclosing_declaration(node, lang.as_ref())
+            && !is_annotated(&tree, &content, lang.as_ref(), &declaration)
+        {
+            let row = declaration.start_position().row;
+            let indent: String = content
+                .lines()
+                .nth(row)
+                .unwrap_or_default()
+                .chars()
+                .take_while(|c| c.is_whitespace())
+                .collect();
+            let annotation = match lang.get_language() {
+                Language::Kotlin => "Suppress",
+                Language::Java | Language::Groovy => "SuppressWarnings",
+            };
+            let name = lang
+                .get_short_name(&declaration, &content)
+                .unwrap_or_else(|| "this declaration".to_string());
+            let start = Position::new(row as u32, 0);
+            actions.push(suppress_action(
+                uri,
+                diagnostic,
+                format!("Suppress '{rule}' in '{name}'"),
+                TextEdit::new(
+                    Range::new(start, start),
+                    format!("{indent}@{annotation}(\"{ANNOTATION_PREFIX}{rule}\")\n"),
+                ),
+            ));
+        }
+
+        let top = Position::new(0, 0);
+        actions.push(suppress_action(
+            uri,
+            diagnostic,
+            format!("Suppress '{rule}' in this file"),
+            TextEdit::new(Range::new(top, top), format!("// {DIRECTIVE} {rule}\n")),
+        ));
+        actions
+    }
+}
@@ -0,0 +1,117 @@
+//! Members a type has without declaring them under the name they are used by.
+//!
+//! Which ones depend on the language the type is declared in.  Java types reach
+//! the JavaBean property `foo` through its `getFoo()` or `isFoo()` accessor, and
+//! those annotated with Lombok's `@Getter`, `@Setter`, `@Data` or `@Value` have
+//! the accessors of their fields generated.  Groovy properties go both ways: the
+//! property stands for its accessors and the accessors for the property.  Kotlin
+//! properties are indexed under their own names, so Kotlin types have none.
+//!
+//! Each kind of such member is a [`SyntheticMemberProvider`], and member search
+//! tries the ones [`providers`] lists for the type's language, so a new kind — e.g.
+//! record component accessors — plugs in by implementing the trait and being
+//! listed there.
+
+use lsp_core::util::capitalize;
+
+use crate::{models::symbol::Symbol, server::Backend};
+
+pub trait SyntheticMemberProvider: Send + Sync {
+    /// The names of the declared members of `class` that `member` may stand for, in
+    /// the order to try them.
+    fn declared_names(&self, class: &Symbol, member: &str) -> Vec<String>;
+}
+
+/// JavaBean getters: `foo` stands for `getFoo()`, or `isFoo()` for a boolean.
+pub struct BeanGetters;
+
+impl SyntheticMemberProvider for BeanGetters {
+    fn declared_names(&self, _class: &Symbol, member: &str) -> Vec<String> {
+        let property = capitalize(member);
+        vec![format!("get{property}"), format!("is{property}")]
+    }
+}
+
+/// The property an accessor name stands for and whether the accessor is a setter,
+/// e.g. `("foo", false)` for `getFoo` or `isFoo`.
+fn accessed_property(accessor: &str) -> Option<(String, bool)> {
+    let (rest, is_setter) = if let Some(rest) = accessor.strip_prefix("get") {
+        (rest, false)
+    } else if let Some(rest) = accessor.strip_prefix("is") {
+        (rest, false)
+    } else {
+        (accessor.strip_prefix("set")?, true)
+    };
+    let mut chars = rest.chars();
+    let first = chars.next().filter(|c| c.is_uppercase())?;
+    Some((first.to_lowercase().chain(chars).collect(), is_setter))
+}
+
+/// Properties reached through their accessors: `getFoo()`, `isFoo()` and
+/// `setFoo()` stand for `foo`.
+pub struct PropertyAccessors;
+
+impl SyntheticMemberProvider for PropertyAccessors {
+    fn declared_names(&self, _class: &Symbol, member: &str) -> Vec<String> {
+        accessed_property(member)
+            .map(|(property, _)| vec![property])
+            .unwrap_or_default()
+    }
+}
+
+/// Lombok accessors of a class annotated to generate them: `getFoo()` and `isFoo()`
+/// stand for the field `foo` under `@Getter`, `@Data` or `@Value`, `setFoo()` under
+/// `@Setter` or `@Data`.  Field-level annotations are not indexed on the class, so
+/// only class-level ones count.
+pub struct LombokAccessors;
+
+impl SyntheticMemberProvider for LombokAccessors {
+    fn declared_names(&self, class: &Symbol, member: &str) -> Vec<String> {
+        let Some((property, is_setter)) = accessed_property(member) else {
+            return vec![];
+        };
+        let generating: &[&str] = if is_setter {
+            &["Setter", "Data"]
+        } else {
+            &["Getter", "Data", "Value"]
+        };
+        let annotations = class.metadata.annotations.as_deref().unwrap_or_default();
+        if annotations.iter().any(|a| generating.contains(&a.as_str())) {
+            vec![property]
+        } else {
+            vec![]
+        }
+    }
+}
+
+/// The synthetic members of types declared in `language`, as in
+/// [`Symbol::file_type`].
+pub fn providers(language: &str) -> &'static [&'static dyn SyntheticMemberProvider] {
+    match language {
+        "java" => &[&BeanGetters, &LombokAccessors],
+        "groovy" => &[&BeanGetters, &PropertyAccessors],
+        _ => &[],
+    }
+}
+
+impl Backend {
+    /// The declared member of `class_fqn` that `member` stands for through one of
+    /// the [`providers`] of the class's language.
+    #[tracing::instrument(skip_all)]
+    pub(crate) async fn synthetic_member(&self, class_fqn: &str, member: &str) -> Option<Symbol> {
+        let repo = self.repo.get()?;
+        let class = repo.find_symbol_by_fqn(class_fqn).await.ok().flatten()?;
+        let names = providers(&class.file_type)
+            .iter()
+            .flat_map(|provider| provider.declared_names(&class, member));
+        for name in names {
+            if let Ok(Some(found)) = repo
+                .find_symbol_by_fqn(&format!("{class_fqn}#{name}"))
+                .await
+            {
+                return Some(found);
+            }
+        }
+        None
+    }
+}
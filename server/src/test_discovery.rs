@@ -0,0 +1,581 @@
+//! `lspintar/tests` — test discovery for test-explorer clients.
+//!
+//! Tests are found syntactically, per file, so discovery works before the
+//! project has been compiled:
+//!
+//! - JUnit 4/5 and TestNG: methods annotated `@Test` (or a JUnit 5 test
+//!   template annotation).  The framework is told apart by the file's imports.
+//! - Spock: feature methods of classes extending `Specification` — methods
+//!   named by a string literal or containing a Spock block label.
+//! - Kotest: string-named tests of classes extending a Kotest spec style,
+//!   nested under their containers.
+//!
+//! `lspintar/testLocation` goes the other way: from the class and test display
+//! name of a test report line back to the test's declaration.
+
+use std::{collections::HashSet, path::PathBuf, sync::Arc};
+
+use lsp_core::{
+    language_support::{LanguageSupport, StringNamedTestData},
+    node_kind::NodeKind,
+    util::{imported_name, split_import_alias},
+};
+use serde::{Deserialize, Serialize};
+use tower_lsp::{
+    jsonrpc::{Error, Result},
+    lsp_types::{Location, Range, TextDocumentIdentifier, Url},
+};
+use tree_sitter::Node;
+
+use crate::server::Backend;
+
+pub const TESTS_METHOD: &str = "lspintar/tests";
+pub const TEST_LOCATION_METHOD: &str = "lspintar/testLocation";
+
+/// Annotations marking a JUnit/TestNG test method.  Only `Test` exists in JUnit 4
+/// and TestNG; the rest are JUnit 5 test templates.
+const TEST_ANNOTATIONS: &[&str] = &[
+    "Test",
+    "ParameterizedTest",
+    "RepeatedTest",
+    "TestFactory",
+    "TestTemplate",
+];
+
+/// Kotest spec styles a test class extends.
+const KOTEST_SPECS: &[&str] = &[
+    "StringSpec",
+    "FunSpec",
+    "ShouldSpec",
+    "DescribeSpec",
+    "BehaviorSpec",
+    "WordSpec",
+    "FreeSpec",
+    "FeatureSpec",
+    "ExpectSpec",
+    "AnnotationSpec",
+];
+
+/// Spock block labels; a method containing one is a feature method.
+const SPOCK_BLOCKS: &[&str] = &["expect:", "when:", "then:", "where:"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestsParams {
+    /// Restricts discovery to one file; every project source file when absent.
+    #[serde(default)]
+    pub text_document: Option<TextDocumentIdentifier>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestLocationParams {
+    /// The test class as reported, e.g. `com.example.MathSpec` or `Outer$Inner`.
+    pub class_name: String,
+    /// The test display name as reported.  Kotest reports nested tests with their
+    /// containers joined by ` -- `; Spock reports unrolled iterations with their
+    /// placeholders filled in.
+    pub test_name: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TestFramework {
+    #[serde(rename = "junit4")]
+    JUnit4,
+    #[serde(rename = "junit5")]
+    JUnit5,
+    #[serde(rename = "testng")]
+    TestNg,
+    #[serde(rename = "spock")]
+    Spock,
+    #[serde(rename = "kotest")]
+    Kotest,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TestItemKind {
+    Class,
+    /// A string-named test that groups other tests, e.g. Kotest's `context("...")`.
+    Container,
+    Test,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestItem {
+    /// The class FQN, followed by `#` and the test name for tests.  Nested
+    /// string-named tests join their container names with ` -- `.
+    pub id: String,
+    pub label: String,
+    pub kind: TestItemKind,
+    pub framework: TestFramework,
+    pub location: Location,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<TestItem>,
+}
+
+impl Backend {
+    /// Entry point for `lspintar/tests`.  Returns one item per test class, with its
+    /// tests as children.
+    pub async fn tests(&self, params: TestsParams) -> Result<Vec<TestItem>> {
+        let paths: Vec<PathBuf> = match params.text_document {
+            Some(doc) => vec![
+                doc.uri
+                    .to_file_path()
+                    .map_err(|_| Error::invalid_params("bad uri"))?,
+            ],
+            None => match self.repo.get() {
+                Some(repo) => repo
+                    .find_all_source_file_paths()
+                    .await
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(PathBuf::from)
+                    .collect(),
+                None => vec![],
+            },
+        };
+
+        Ok(self.discover_tests(paths).await)
+    }
+
+    /// Entry point for `lspintar/testLocation`.  Falls back to the class declaration
+    /// when the class is found but no test in it matches the name.
+    pub async fn test_location(&self, params: TestLocationParams) -> Result<Option<Location>> {
+        let class_id = params.class_name.replace('$', ".");
+        let short_name = class_id.rsplit('.').next().unwrap_or(&class_id).to_string();
+
+        for path in self.test_class_candidates(&class_id, &short_name).await {
+            let classes = self.discover_tests(vec![path]).await;
+            let Some(class) = classes.into_iter().find(|item| {
+                item.id == class_id || (!class_id.contains('.') && item.label == short_name)
+            }) else {
+                continue;
+            };
+            let mut tests = Vec::new();
+            flatten_tests(&class.children, &mut tests);
+            let location = tests
+                .iter()
+                .find(|t| reported_name_matches(t, &class.id, &params.test_name))
+                .map(|t| t.location.clone())
+                .unwrap_or(class.location);
+            return Ok(Some(location));
+        }
+        Ok(None)
+    }
+
+    /// Files that may declare the class: open documents first, since they can hold
+    /// unsaved tests, then the indexed declarations of that name.
+    async fn test_class_candidates(&self, class_id: &str, short_name: &str) -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = self
+            .documents
+            .iter()
+            .filter_map(|entry| Url::parse(entry.key()).ok()?.to_file_path().ok())
+            .collect();
+        if let Some(repo) = self.repo.get() {
+            let symbols = match repo.find_symbol_by_fqn(class_id).await {
+                Ok(Some(symbol)) => vec![symbol],
+                _ => repo
+                    .find_types_by_short_name(short_name)
+                    .await
+                    .unwrap_or_default(),
+            };
+            paths.extend(symbols.into_iter().map(|s| PathBuf::from(s.file_path)));
+        }
+        let mut seen = HashSet::new();
+        paths.retain(|p| seen.insert(p.clone()));
+        paths
+    }
+
+    /// Finds the test classes declared in `paths`.  The files are parsed off the
+    /// async runtime.
+    pub(crate) async fn discover_tests(&self, paths: Vec<PathBuf>) -> Vec<TestItem> {
+        let sources: Vec<TestSource> = paths
+            .into_iter()
+            .filter_map(|path| self.test_source(path))
+            .collect();
+        tokio::task::spawn_blocking(move || sources.iter().flat_map(TestSource::tests).collect())
+            .await
+            .unwrap_or_default()
+    }
+
+    fn test_source(&self, path: PathBuf) -> Option<TestSource> {
+        let lang = self.languages.get(path.extension()?.to_str()?)?.clone();
+        let uri = Url::from_file_path(&path).ok()?;
+        let text = self
+            .documents
+            .get(&uri.to_string())
+            .map(|entry| entry.0.clone());
+        Some(TestSource {
+            lang,
+            path,
+            uri,
+            text,
+        })
+    }
+}
+
+/// A file to discover tests in, with the text of its open document, which can hold
+/// unsaved tests.
+struct TestSource {
+    lang: Arc<dyn LanguageSupport + Send + Sync>,
+    path: PathBuf,
+    uri: Url,
+    text: Option<String>,
+}
+
+impl TestSource {
+    /// The test classes declared in the file.
+    fn tests(&self) -> Vec<TestItem> {
+        let parsed = match &self.text {
+            Some(text) => self.lang.parse_str(text),
+            None => self.lang.parse(&self.path),
+        };
+        let Some((tree, content)) = parsed else {
+            return vec![];
+        };
+        let uri = &self.uri;
+        let lang = self.lang.as_ref();
+        let package = lang.get_package_name(&tree, &content).unwrap_or_default();
+        let imports = lang.get_imports(&tree, &content);
+        let string_named = lang.get_string_named_tests(&tree, &content);
+
+        let mut items = Vec::new();
+        let mut stack = vec![tree.root_node()];
+        while let Some(node) = stack.pop() {
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                stack.push(child);
+            }
+            if lang.get_kind(&node) != Some(NodeKind::Class) {
+                continue;
+            }
+            let Some(name) = lang.get_short_name(&node, &content) else {
+                continue;
+            };
+            let Some(ident_range) = lang.get_ident_range(&node) else {
+                continue;
+            };
+            let id = qualified_class_name(node, &name, &package, &content, lang);
+            let mut supers = lang.get_implements(&node, &content);
+            supers.extend(lang.get_extends(&node, &content));
+            let supers: Vec<&str> = supers.iter().map(|s| simple_type_name(s)).collect();
+
+            let (framework, children) = if supers.contains(&"Specification") {
+                let features = spock_features(node, &id, &content, uri, lang);
+                (TestFramework::Spock, features)
+            } else {
+                let mut children = annotated_tests(node, &id, &content, &imports, uri, lang);
+                if supers.iter().any(|s| KOTEST_SPECS.contains(s)) {
+                    let own: Vec<&StringNamedTestData> = string_named
+                        .iter()
+                        .filter(|t| t.containing_class == name)
+                        .collect();
+                    children.extend(nest_string_named(own, &id, uri));
+                    (TestFramework::Kotest, children)
+                } else {
+                    let Some(framework) = children.first().map(|c| c.framework) else {
+                        continue;
+                    };
+                    (framework, children)
+                }
+            };
+            if children.is_empty() {
+                continue;
+            }
+            items.push(TestItem {
+                id,
+                label: name,
+                kind: TestItemKind::Class,
+                framework,
+                location: Location {
+                    uri: uri.clone(),
+                    range: ident_range,
+                },
+                children,
+            });
+        }
+
+        items.sort_by_key(|item| item.location.range.start);
+        items
+    }
+}
+
+/// `package.Outer.Inner` for a class node.
+fn qualified_class_name(
+    node: Node,
+    name: &str,
+    package: &str,
+    content: &str,
+    lang: &dyn LanguageSupport,
+) -> String {
+    let mut names = vec![name.to_string()];
+    let mut current = node.parent();
+    while let Some(ancestor) = current {
+        if matches!(
+            lang.get_kind(&ancestor),
+            Some(NodeKind::Class | NodeKind::Interface | NodeKind::Enum)
+        ) && let Some(outer) = lang.get_short_name(&ancestor, content)
+        {
+            names.push(outer);
+        }
+        current = ancestor.parent();
+    }
+    if !package.is_empty() {
+        names.push(package.to_string());
+    }
+    names.reverse();
+    names.join(".")
+}
+
+/// `Specification` for `spock.lang.Specification` or `Spec<T>`-style supertypes.
+fn simple_type_name(type_name: &str) -> &str {
+    let base = type_name.split('<').next().unwrap_or(type_name).trim();
+    base.rsplit('.').next().unwrap_or(base)
+}
+
+/// The methods declared directly in `class` (not in nested classes).
+fn own_methods<'a>(class: Node<'a>, lang: &dyn LanguageSupport) -> Vec<Node<'a>> {
+    let mut methods = Vec::new();
+    let mut stack = Vec::new();
+    let mut cursor = class.walk();
+    stack.extend(class.children(&mut cursor));
+    while let Some(node) = stack.pop() {
+        match lang.get_kind(&node) {
+            Some(NodeKind::Function) => methods.push(node),
+            Some(_) => {}
+            None => {
+                let mut cursor = node.walk();
+                stack.extend(node.children(&mut cursor));
+            }
+        }
+    }
+    methods.sort_by_key(|n| n.start_byte());
+    methods
+}
+
+fn test_item(
+    class_id: &str,
+    name: String,
+    framework: TestFramework,
+    uri: &Url,
+    range: Range,
+) -> TestItem {
+    TestItem {
+        id: format!("{class_id}#{name}"),
+        label: name,
+        kind: TestItemKind::Test,
+        framework,
+        location: Location {
+            uri: uri.clone(),
+            range,
+        },
+        children: vec![],
+    }
+}
+
+fn annotated_tests(
+    class: Node,
+    class_id: &str,
+    content: &str,
+    imports: &[String],
+    uri: &Url,
+    lang: &dyn LanguageSupport,
+) -> Vec<TestItem> {
+    own_methods(class, lang)
+        .into_iter()
+        .filter_map(|method| {
+            let framework = lang
+                .get_annotations(&method, content)
+                .iter()
+                .find_map(|a| annotation_framework(a, imports))?;
+            let name = lang.get_short_name(&method, content)?;
+            let range = lang.get_ident_range(&method)?;
+            Some(test_item(class_id, name, framework, uri, range))
+        })
+        .collect()
+}
+
+/// The framework a test annotation belongs to, judged by which test library
+/// the file imports it from.
+fn annotation_framework(annotation: &str, imports: &[String]) -> Option<TestFramework> {
+    let name = annotation.trim_start_matches('@');
+    let name = name.split('(').next().unwrap_or(name).trim();
+    let short = name.rsplit('.').next().unwrap_or(name);
+    if !TEST_ANNOTATIONS.contains(&short) {
+        return None;
+    }
+    let origin = if name.contains('.') {
+        Some(name)
+    } else {
+        imports
+            .iter()
+            .map(String::as_str)
+            .find(|i| imported_name(i) == short)
+            .map(|i| split_import_alias(i).0)
+    };
+    let framework_of = |qualified: &str| {
+        if qualified.starts_with("org.junit.jupiter.") {
+            Some(TestFramework::JUnit5)
+        } else if qualified.starts_with("org.testng.") {
+            Some(TestFramework::TestNg)
+        } else if qualified.starts_with("org.junit.") {
+            Some(TestFramework::JUnit4)
+        } else if qualified.starts_with("io.kotest.") {
+            Some(TestFramework::Kotest)
+        } else {
+            None
+        }
+    };
+    if let Some(framework) = origin.and_then(framework_of) {
+        return Some(framework);
+    }
+    // Wildcard imports: take the first test library imported at all.
+    imports
+        .iter()
+        .find_map(|i| framework_of(i))
+        .or(Some(TestFramework::JUnit5))
+}
+
+fn spock_features(
+    class: Node,
+    class_id: &str,
+    content: &str,
+    uri: &Url,
+    lang: &dyn LanguageSupport,
+) -> Vec<TestItem> {
+    own_methods(class, lang)
+        .into_iter()
+        .filter_map(|method| {
+            let name = lang.get_short_name(&method, content)?;
+            let body = method.utf8_text(content.as_bytes()).ok()?;
+            let has_block = body
+                .lines()
+                .any(|line| SPOCK_BLOCKS.iter().any(|b| line.trim_start().starts_with(b)));
+            if lang.is_valid_identifier(&name) && !has_block {
+                return None;
+            }
+            let range = lang.get_ident_range(&method)?;
+            Some(test_item(class_id, name, TestFramework::Spock, uri, range))
+        })
+        .collect()
+}
+
+/// Builds the container tree for a class's string-named tests.  An entry whose
+/// container is not itself reported (e.g. an interpolated name) is attached to
+/// the deepest container that is.
+fn nest_string_named(
+    mut tests: Vec<&StringNamedTestData>,
+    class_id: &str,
+    uri: &Url,
+) -> Vec<TestItem> {
+    tests.sort_by_key(|t| (t.parents.len(), t.range.start));
+    let mut roots: Vec<TestItem> = Vec::new();
+    for test in tests {
+        let mut siblings = &mut roots;
+        let mut path: Vec<&str> = Vec::new();
+        for parent in &test.parents {
+            let Some(idx) = siblings.iter().position(|item| item.label == *parent) else {
+                break;
+            };
+            path.push(parent);
+            siblings = &mut siblings[idx].children;
+        }
+        path.push(&test.name);
+        let mut item = test_item(
+            class_id,
+            path.join(" -- "),
+            TestFramework::Kotest,
+            uri,
+            test.range,
+        );
+        item.label = test.name.clone();
+        siblings.push(item);
+    }
+    mark_containers(&mut roots);
+    roots
+}
+
+fn mark_containers(items: &mut [TestItem]) {
+    for item in items {
+        if !item.children.is_empty() {
+            item.kind = TestItemKind::Container;
+            mark_containers(&mut item.children);
+        }
+    }
+}
+
+fn flatten_tests<'a>(items: &'a [TestItem], out: &mut Vec<&'a TestItem>) {
+    for item in items {
+        out.push(item);
+        flatten_tests(&item.children, out);
+    }
+}
+
+/// True when `reported` (a test report display name) names `test`: its full
+/// ` -- `-joined path, its label, its method name followed by a parameter list or
+/// invocation index (JUnit), or its Spock template with `#placeholders` filled in.
+fn reported_name_matches(test: &TestItem, class_id: &str, reported: &str) -> bool {
+    let path = test
+        .id
+        .strip_prefix(class_id)
+        .and_then(|rest| rest.strip_prefix('#'))
+        .unwrap_or(&test.label);
+    if reported == path || reported == test.label {
+        return true;
+    }
+    // `adds()`, `subtracts(int)[1]`
+    if let Some(rest) = reported.strip_prefix(test.label.as_str())
+        && (rest.starts_with('(') || rest.starts_with('['))
+    {
+        return true;
+    }
+    // Spock 2 unrolls as `name [a: 1, b: 2, #0]` unless the name has placeholders.
+    let reported = match reported.rfind(" [") {
+        Some(idx) if reported.ends_with(']') => &reported[..idx],
+        _ => reported,
+    };
+    reported == test.label
+        || (test.framework == TestFramework::Spock && template_matches(&test.label, reported))
+}
+
+/// Matches a Spock unroll template such as `max of #a and #b is #c` against a
+/// filled-in name, treating each placeholder as a non-empty wildcard.
+fn template_matches(template: &str, name: &str) -> bool {
+    let mut literals = Vec::new();
+    let mut rest = template;
+    while let Some(idx) = rest.find('#') {
+        let after = &rest[idx + 1..];
+        let placeholder = after
+            .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+            .map_or(after, |end| &after[..end]);
+        // A sentence-ending `.` is not part of a `#a.b` property placeholder.
+        let placeholder_len = placeholder.trim_end_matches('.').len();
+        if placeholder_len == 0 {
+            break;
+        }
+        literals.push(&rest[..idx]);
+        rest = &rest[idx + 1 + placeholder_len..];
+    }
+    if literals.is_empty() {
+        return false;
+    }
+    literals.push(rest);
+
+    let (first, last) = (literals[0], literals[literals.len() - 1]);
+    let Some(mut remaining) = name.strip_prefix(first) else {
+        return false;
+    };
+    for literal in &literals[1..literals.len() - 1] {
+        // Skip at least one character for the placeholder before the literal.
+        let Some(skip) = remaining.chars().next().map(char::len_utf8) else {
+            return false;
+        };
+        let Some(idx) = remaining[skip..].find(literal) else {
+            return false;
+        };
+        remaining = &remaining[skip + idx + literal.len()..];
+    }
+    remaining.len() > last.len() && remaining.ends_with(last)
+}
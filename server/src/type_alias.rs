@@ -0,0 +1,156 @@
+//! Kotlin type aliases and import aliases.
+//!
+//! `typealias Handler = (Request) -> Response` is indexed as a `TypeAlias` symbol
+//! whose return type is the aliased type.  Usages of the alias resolve through to
+//! the aliased declaration when it names an indexed type, and to the alias itself
+//! otherwise (function types, for one).  Hover shows the alias above the aliased
+//! type.
+//!
+//! `import com.foo.Bar as Baz` needs no index: imports are matched by the name they
+//! bring into scope (see [`lsp_core::util::imported_name`]), so `Baz` resolves to
+//! `com.foo.Bar`, and hover names the import it was reached through.
+
+use std::path::Path;
+
+use lsp_core::util::split_import_alias;
+use tower_lsp::lsp_types::{
+    Hover, HoverContents, MarkupContent, MarkupKind, Position, TextDocumentPositionParams,
+};
+
+use crate::{
+    enums::ResolvedSymbol, generic_resolution::parse_type_ref, lsp_convert::AsLspHover,
+    models::symbol::Symbol, server::Backend,
+};
+
+/// Aliases of aliases are followed at most this deep.
+const MAX_ALIAS_DEPTH: usize = 8;
+
+/// The identifier around `position`, by text.
+pub(crate) fn word_at(content: &str, position: &Position) -> Option<String> {
+    let line: Vec<char> = content.lines().nth(position.line as usize)?.chars().collect();
+    let is_word = |c: &char| c.is_alphanumeric() || *c == '_';
+    let cursor = (position.character as usize).min(line.len());
+    let start = line[..cursor]
+        .iter()
+        .rposition(|c| !is_word(c))
+        .map_or(0, |i| i + 1);
+    let end = line[cursor..]
+        .iter()
+        .position(|c| !is_word(c))
+        .map_or(line.len(), |i| cursor + i);
+    (start < end).then(|| line[start..end].iter().collect())
+}
+
+fn markdown(hover: Option<Hover>) -> Option<String> {
+    match hover?.contents {
+        HoverContents::Markup(markup) => Some(markup.value),
+        _ => None,
+    }
+}
+
+fn joined_hover(parts: Vec<String>) -> Hover {
+    Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: parts.join("\n\n"),
+        }),
+        range: None,
+    }
+}
+
+impl Backend {
+    /// Replaces each type alias with the declaration it stands for, when there is one.
+    pub(crate) async fn through_type_aliases(
+        &self,
+        symbols: Vec<ResolvedSymbol>,
+    ) -> Vec<ResolvedSymbol> {
+        let mut resolved = Vec::with_capacity(symbols.len());
+        for symbol in symbols {
+            match &symbol {
+                ResolvedSymbol::Project(alias) if alias.symbol_type == "TypeAlias" => {
+                    resolved.push(self.aliased_declaration(alias).await.unwrap_or(symbol));
+                }
+                _ => resolved.push(symbol),
+            }
+        }
+        resolved
+    }
+
+    /// `fqn`, or the FQN of the declaration it stands for when it names a type alias.
+    pub(crate) async fn unalias_fqn(&self, fqn: String) -> String {
+        let Some(repo) = self.repo.get() else {
+            return fqn;
+        };
+        match repo.find_symbol_by_fqn(&fqn).await {
+            Ok(Some(alias)) if alias.symbol_type == "TypeAlias" => {
+                match self.aliased_declaration(&alias).await {
+                    Some(ResolvedSymbol::Project(s)) => s.fully_qualified_name,
+                    Some(ResolvedSymbol::External(s)) => s.fully_qualified_name,
+                    _ => fqn,
+                }
+            }
+            _ => fqn,
+        }
+    }
+
+    /// The declaration `alias` stands for, following aliases of aliases.  `None`
+    /// when the aliased type is not a named, indexed type.
+    pub(crate) async fn aliased_declaration(&self, alias: &Symbol) -> Option<ResolvedSymbol> {
+        let mut current = alias.clone();
+        for _ in 0..MAX_ALIAS_DEPTH {
+            let fqn = self.aliased_fqn(&current).await?;
+            let target = self.fqn_to_symbols(fqn).await.ok()?.into_iter().next()?;
+            match target {
+                ResolvedSymbol::Project(s) if s.symbol_type == "TypeAlias" => current = s,
+                other => return Some(other),
+            }
+        }
+        None
+    }
+
+    /// The FQN of the type `alias` names, resolved against the alias's own file.
+    async fn aliased_fqn(&self, alias: &Symbol) -> Option<String> {
+        let aliased = alias.metadata.return_type.as_deref()?;
+        // Function types have no declaration to go to.
+        if aliased.starts_with('(') || aliased.contains("->") {
+            return None;
+        }
+        let (base, _) = parse_type_ref(aliased.trim_end_matches('?'));
+        self.resolve_fqn_in_file(Path::new(&alias.file_path), &base).await
+    }
+
+    /// Hover for a symbol reached through an alias: the type alias or aliased import
+    /// first, then the declaration it stands for.  `None` when no alias is involved.
+    pub(crate) async fn alias_hover(
+        &self,
+        params: &TextDocumentPositionParams,
+        symbol: &ResolvedSymbol,
+    ) -> Option<Hover> {
+        if let ResolvedSymbol::Project(alias) = symbol
+            && alias.symbol_type == "TypeAlias"
+        {
+            let target = self.aliased_declaration(alias).await?;
+            return Some(joined_hover(vec![
+                markdown(symbol.as_lsp_hover())?,
+                markdown(target.as_lsp_hover())?,
+            ]));
+        }
+
+        let uri = &params.text_document.uri;
+        if !uri.path().ends_with(".kt") {
+            return None;
+        }
+        let content = self.document_text(uri)?;
+        let name = word_at(&content, &params.position)?;
+        let lang = self.languages.get("kt")?;
+        let (tree, _) = lang.parse_str(&content)?;
+        let import = lang
+            .get_imports(&tree, &content)
+            .into_iter()
+            .find(|i| split_import_alias(i).1 == Some(name.as_str()))?;
+        Some(joined_hover(vec![
+            format!("```kotlin\nimport {import}\n```"),
+            markdown(symbol.as_lsp_hover())?,
+        ]))
+    }
+}
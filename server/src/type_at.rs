@@ -0,0 +1,88 @@
+//! `lspintar/typeAt` — the static type of the expression at a position, as plain
+//! text.
+//!
+//! Meant for editor plugins that want a type without hover's markdown ("copy
+//! type", type under selection).  Literals give their boxed type, locals and
+//! parameters their declared or inferred type, fields and properties their type,
+//! method calls their return type, and a type name the type's FQN.  Types are
+//! returned as written at the declaration, generic arguments included.
+
+use std::{path::PathBuf, str::FromStr};
+
+use tower_lsp::{
+    jsonrpc::{Error, Result},
+    lsp_types::TextDocumentPositionParams,
+};
+
+use crate::{enums::ResolvedSymbol, server::Backend};
+
+pub const TYPE_AT_METHOD: &str = "lspintar/typeAt";
+
+/// Symbol types whose "type" is the declaration itself.
+const TYPE_DECLARATIONS: &[&str] = &["Class", "Interface", "Enum", "Annotation", "TypeAlias"];
+
+/// Set by `find_variable_type` when the type is only known once the chain or
+/// closure parameter it names has been walked.
+pub(crate) fn is_deferred_type(var_type: &str) -> bool {
+    var_type.starts_with("__cp__:") || var_type.contains('#')
+}
+
+impl Backend {
+    /// Entry point for `lspintar/typeAt`.  `None` when nothing typed is at the
+    /// position or its type cannot be resolved.
+    pub async fn type_at(&self, params: TextDocumentPositionParams) -> Result<Option<String>> {
+        let path = PathBuf::from_str(params.text_document.uri.path())
+            .map_err(|_| Error::invalid_params("bad uri"))?;
+        let Some(lang) = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .and_then(|ext| self.languages.get(ext))
+        else {
+            return Ok(None);
+        };
+        let Some((tree, content)) = lang.parse(&path) else {
+            return Ok(None);
+        };
+        if let Some(literal) = lang.get_literal_type(&tree, &content, &params.position) {
+            return Ok(Some(literal));
+        }
+
+        let Some(symbol) = self
+            .resolve_symbol_at_position(&params)
+            .await
+            .ok()
+            .and_then(|symbols| symbols.into_iter().next())
+        else {
+            return Ok(None);
+        };
+        let (symbol_type, fqn, metadata) = match symbol {
+            ResolvedSymbol::Local {
+                var_type: Some(var_type),
+                ..
+            } if !is_deferred_type(&var_type) => {
+                return Ok(Some(var_type));
+            }
+            ResolvedSymbol::Local { name, .. } => {
+                let mut imports = lang.get_imports(&tree, &content);
+                imports.extend(lang.get_implicit_imports());
+                return Ok(self
+                    .walk_member_chain(
+                        &name,
+                        lang,
+                        &tree,
+                        &content,
+                        imports,
+                        &params.position,
+                        lang.get_package_name(&tree, &content),
+                    )
+                    .await);
+            }
+            ResolvedSymbol::Project(s) => (s.symbol_type, s.fully_qualified_name, s.metadata),
+            ResolvedSymbol::External(s) => (s.symbol_type, s.fully_qualified_name, s.metadata),
+        };
+        if TYPE_DECLARATIONS.contains(&symbol_type.as_str()) {
+            return Ok(Some(fqn));
+        }
+        Ok(metadata.0.return_type)
+    }
+}
@@ -0,0 +1,187 @@
+//! Unreachable code detection.
+//!
+//! Two trivially dead shapes are reported, in all three languages: statements
+//! following a `return`, `throw`, `break` or `continue` in the same block, and the
+//! branch of an `if (false)`.  The diagnostic is a hint tagged unnecessary, so
+//! editors dim the code rather than underline it, and carries the quick fix
+//! deleting it.  An `if (false)` with an `else` is replaced by the statements of
+//! its `else` branch.
+//! Nothing is inferred from constants or conditions other than the literal
+//! `false`.
+
+use tower_lsp::lsp_types::{
+    Diagnostic, DiagnosticSeverity, DiagnosticTag, NumberOrString, Position, Range, TextEdit,
+};
+use tree_sitter::{Node, Tree};
+
+use crate::code_action::{QuickFix, removal_range};
+
+pub(crate) const UNREACHABLE_CODE: &str = "unreachable_code";
+
+/// Nodes whose children are statements executed in order.
+const BLOCKS: &[&str] = &[
+    "block",
+    "statements",
+    "constructor_body",
+    "switch_block_statement_group",
+];
+
+/// Statements control never passes over.
+const JUMPS: &[&str] = &[
+    "return_statement",
+    "throw_statement",
+    "break_statement",
+    "continue_statement",
+    "jump_expression",
+];
+
+fn node_range(node: &Node) -> Range {
+    let (start, end) = (node.start_position(), node.end_position());
+    Range::new(
+        Position::new(start.row as u32, start.column as u32),
+        Position::new(end.row as u32, end.column as u32),
+    )
+}
+
+fn is_comment(node: &Node) -> bool {
+    node.kind().contains("comment")
+}
+
+/// The condition, branch and `else` branch of an `if`, by field where the grammar
+/// names them and by position otherwise.
+fn if_parts<'t>(node: &Node<'t>) -> Option<(Node<'t>, Node<'t>, Option<Node<'t>>)> {
+    let mut cursor = node.walk();
+    let mut parts = node.named_children(&mut cursor).filter(|c| !is_comment(c));
+    let condition = node
+        .child_by_field_name("condition")
+        .or_else(|| parts.next())?;
+    let consequence = match node.child_by_field_name("consequence") {
+        Some(consequence) => consequence,
+        None => parts.find(|c| *c != condition)?,
+    };
+    let alternative = node
+        .child_by_field_name("alternative")
+        .or_else(|| parts.find(|c| *c != condition && *c != consequence));
+    Some((condition, consequence, alternative))
+}
+
+fn unreachable_diagnostic(range: Range, edit: TextEdit) -> Diagnostic {
+    Diagnostic {
+        range,
+        severity: Some(DiagnosticSeverity::HINT),
+        code: Some(NumberOrString::String(UNREACHABLE_CODE.to_string())),
+        source: Some("lspintar".to_string()),
+        message: "Unreachable code".to_string(),
+        tags: Some(vec![DiagnosticTag::UNNECESSARY]),
+        data: QuickFix::new("Remove unreachable code", vec![edit]).to_data(),
+        ..Default::default()
+    }
+}
+
+/// The edit deleting `range`, with the lines it spans when nothing else is on
+/// them.
+fn deletion(content: &str, range: Range) -> TextEdit {
+    TextEdit {
+        range: removal_range(content, range).unwrap_or(range),
+        new_text: String::new(),
+    }
+}
+
+/// What replaces an `if (false)` with the `else` branch `alternative`: the
+/// statements of a braced branch, re-indented to the `if`'s `indent`, or the branch
+/// statement itself.  The braces go, since a bare block is a lambda that never runs
+/// in Kotlin and an ambiguous closure in Groovy.
+fn else_branch(alternative: &str, indent: &str) -> String {
+    let Some(inner) = alternative
+        .strip_prefix('{')
+        .and_then(|rest| rest.strip_suffix('}'))
+    else {
+        return alternative.to_string();
+    };
+    let lines: Vec<&str> = inner.lines().collect();
+    let (Some(first), Some(last)) = (
+        lines.iter().position(|l| !l.trim().is_empty()),
+        lines.iter().rposition(|l| !l.trim().is_empty()),
+    ) else {
+        return String::new();
+    };
+    let lines = &lines[first..=last];
+    let common = lines
+        .iter()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.len() - l.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    let mut text = String::new();
+    for (i, line) in lines.iter().enumerate() {
+        if i > 0 {
+            text.push('\n');
+        }
+        let line = line.get(common..).unwrap_or(line.trim_start()).trim_end();
+        if i > 0 && !line.is_empty() {
+            text.push_str(indent);
+        }
+        text.push_str(line);
+    }
+    text
+}
+
+/// The `unreachable_code` hints of a parsed file.
+pub(crate) fn unreachable_code(tree: &Tree, content: &str) -> Vec<Diagnostic> {
+    let bytes = content.as_bytes();
+    let mut diagnostics = Vec::new();
+    let mut stack = vec![tree.root_node()];
+    while let Some(node) = stack.pop() {
+        let mut cursor = node.walk();
+        stack.extend(node.named_children(&mut cursor));
+
+        if BLOCKS.contains(&node.kind()) {
+            let mut cursor = node.walk();
+            let statements: Vec<Node> = node
+                .named_children(&mut cursor)
+                .filter(|c| !is_comment(c))
+                .collect();
+            let jump = statements.iter().position(|s| JUMPS.contains(&s.kind()));
+            if let Some(jump) = jump
+                && let (Some(first), Some(last)) = (statements.get(jump + 1), statements.last())
+                && first.kind() != "switch_label"
+            {
+                let range = Range::new(node_range(first).start, node_range(last).end);
+                diagnostics.push(unreachable_diagnostic(range, deletion(content, range)));
+            }
+            continue;
+        }
+
+        if !matches!(node.kind(), "if_statement" | "if_expression") {
+            continue;
+        }
+        let Some((condition, consequence, alternative)) = if_parts(&node) else {
+            continue;
+        };
+        let condition = condition.utf8_text(bytes).unwrap_or_default();
+        if condition.trim_matches(|c: char| c == '(' || c == ')' || c.is_whitespace()) != "false" {
+            continue;
+        }
+        let statement = node_range(&node);
+        let indent = content
+            .lines()
+            .nth(statement.start.line as usize)
+            .map(|line| &line[..line.len() - line.trim_start().len()])
+            .unwrap_or_default();
+        let replacement = alternative
+            .map(|alternative| {
+                else_branch(alternative.utf8_text(bytes).unwrap_or_default(), indent)
+            })
+            .filter(|text| !text.is_empty());
+        let edit = match replacement {
+            Some(new_text) => TextEdit {
+                range: statement,
+                new_text,
+            },
+            None => deletion(content, statement),
+        };
+        diagnostics.push(unreachable_diagnostic(node_range(&consequence), edit));
+    }
+    diagnostics.sort_by_key(|d| d.range.start);
+    diagnostics
+}
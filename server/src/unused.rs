@@ -0,0 +1,448 @@
+//! Unused import, private member and local variable detection.
+//!
+//! All checks are file-local: an import only serves its own file, a private
+//! member can only be referenced from its own file, and a local only from its
+//! own scope.  Private members are counted by word-boundary occurrences outside
+//! comments (string literals count as uses so Groovy/Kotlin templates and
+//! reflective lookups don't produce false positives); locals reuse the
+//! scope-aware `find_local_references` of the language.  Imports are counted the
+//! same way, comments included, since Javadoc `{@link}` references need them.
+//! Wildcard imports, and Kotlin imports of operator and delegate functions that
+//! syntax uses without naming them, are never reported.
+//!
+//! Each diagnostic carries the quick fix deleting its declaration, and unused
+//! imports also offer one removing every unused import of the file.  A local
+//! initialized by a call, creation, assignment or increment keeps it as an
+//! expression statement instead; one whose initializer has such an effect deeper
+//! inside (`compute() + 1`) gets no fix.
+
+use std::{collections::HashMap, path::PathBuf};
+
+use lsp_core::{language_support::LanguageSupport, languages::Language, node_kind::NodeKind};
+use tower_lsp::lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOrCommand, Diagnostic, DiagnosticSeverity, DiagnosticTag,
+    NumberOrString, Position, Range, TextEdit, Url, WorkspaceEdit,
+};
+use tree_sitter::{Node, Point, Tree};
+
+use crate::{
+    code_action::{QuickFix, removal_range},
+    rename::word_boundary_occurrences,
+    server::{Backend, position_in_comment},
+    suppression::Suppressions,
+};
+
+pub(crate) const UNUSED_IMPORT_CODE: &str = "unused_import";
+
+/// Kotlin operator and delegate conventions: `a + b` calls an imported `plus`, and
+/// `val x by lazy {}` an imported `getValue`.
+const KOTLIN_CONVENTIONS: &[&str] = &[
+    "getValue",
+    "setValue",
+    "provideDelegate",
+    "invoke",
+    "get",
+    "set",
+    "contains",
+    "iterator",
+    "next",
+    "hasNext",
+    "plus",
+    "minus",
+    "times",
+    "div",
+    "rem",
+    "rangeTo",
+    "rangeUntil",
+    "unaryPlus",
+    "unaryMinus",
+    "not",
+    "inc",
+    "dec",
+    "compareTo",
+    "plusAssign",
+    "minusAssign",
+    "timesAssign",
+    "divAssign",
+    "remAssign",
+];
+
+/// Members the Java serialization machinery looks up reflectively.
+const SERIALIZATION_MEMBERS: &[&str] = &[
+    "serialVersionUID",
+    "serialPersistentFields",
+    "writeObject",
+    "readObject",
+    "readObjectNoData",
+    "writeReplace",
+    "readResolve",
+];
+
+pub(crate) fn collect_unused_declarations(
+    tree: &Tree,
+    content: &str,
+    lang: &dyn LanguageSupport,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = unused_imports(content, lang);
+    collect_unused_private_members(tree, content, lang, &mut diagnostics);
+    collect_unused_locals(tree, content, lang, &mut diagnostics);
+    diagnostics
+}
+
+/// The `unused_import` warnings of `content`.
+pub(crate) fn unused_imports(content: &str, lang: &dyn LanguageSupport) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for (line_no, line) in content.lines().enumerate() {
+        let Some(statement) = line.strip_prefix("import ") else {
+            continue;
+        };
+        let statement = statement
+            .split(['/', ';'])
+            .next()
+            .unwrap_or_default()
+            .trim();
+        let statement = statement.strip_prefix("static ").unwrap_or(statement);
+        let (path, name) = match statement.split_once(" as ") {
+            Some((path, alias)) => (path.trim(), alias.trim()),
+            None => (statement, statement.rsplit('.').next().unwrap_or(statement)),
+        };
+        if path.ends_with('*') || name.is_empty() {
+            continue;
+        }
+        if lang.get_language() == Language::Kotlin
+            && (KOTLIN_CONVENTIONS.contains(&name) || name.starts_with("component"))
+        {
+            continue;
+        }
+        let used = word_boundary_occurrences(content, name)
+            .into_iter()
+            .any(|(line, _, _)| line != line_no);
+        if used {
+            continue;
+        }
+
+        let line_no = line_no as u32;
+        let start = line.find(path).unwrap_or_default() as u32;
+        diagnostics.push(unused_diagnostic(
+            content,
+            UNUSED_IMPORT_CODE,
+            format!("Unused import {path}"),
+            Range::new(
+                Position::new(line_no, start),
+                Position::new(line_no, start + path.len() as u32),
+            ),
+            Some(Range::new(
+                Position::new(line_no, 0),
+                Position::new(line_no, line.len() as u32),
+            )),
+            format!("Remove unused import '{path}'"),
+        ));
+    }
+    diagnostics
+}
+
+fn collect_unused_private_members(
+    tree: &Tree,
+    content: &str,
+    lang: &dyn LanguageSupport,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let bytes = content.as_bytes();
+    let mut stack = vec![tree.root_node()];
+
+    while let Some(node) = stack.pop() {
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            stack.push(child);
+        }
+
+        let kind = match lang.get_kind(&node) {
+            Some(NodeKind::Function) => "method",
+            Some(NodeKind::Field) => "field",
+            _ => continue,
+        };
+        if !lang.get_modifiers(&node, content).iter().any(|m| m == "private") {
+            continue;
+        }
+        // Annotated members are usually bound by a framework (DI, persistence,
+        // lifecycle callbacks) and used reflectively.
+        if !lang.get_annotations(&node, content).is_empty() {
+            continue;
+        }
+        let Some(name) = lang.get_short_name(&node, content) else {
+            continue;
+        };
+        let Some(ident_range) = lang.get_ident_range(&node) else {
+            continue;
+        };
+        if SERIALIZATION_MEMBERS.contains(&name.as_str()) || unused_suppressed(node, bytes, lang) {
+            continue;
+        }
+
+        let used = word_boundary_occurrences(content, &name)
+            .into_iter()
+            .any(|(line, start, _)| {
+                let is_decl = line as u32 == ident_range.start.line
+                    && start as u32 == ident_range.start.character;
+                !is_decl && !position_in_comment(tree, line, start)
+            });
+        if used {
+            continue;
+        }
+
+        let Some(decl_range) = lang.get_range(&node) else {
+            continue;
+        };
+        diagnostics.push(unused_diagnostic(
+            content,
+            "unused_private_member",
+            format!("Private {kind} '{name}' is never used"),
+            ident_range,
+            Some(decl_range),
+            format!("Remove unused '{name}'"),
+        ));
+    }
+}
+
+fn collect_unused_locals(
+    tree: &Tree,
+    content: &str,
+    lang: &dyn LanguageSupport,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let bytes = content.as_bytes();
+
+    for local in lang.get_local_variable_declarations(tree, content) {
+        if local.name == "_" || local.name.starts_with("ignored") {
+            continue;
+        }
+        let decl_range = local.statement_range.unwrap_or(local.ident_range);
+        let decl = tree
+            .root_node()
+            .descendant_for_point_range(to_point(decl_range.start), to_point(decl_range.end));
+        if decl.is_some_and(|node| unused_suppressed(node, bytes, lang)) {
+            continue;
+        }
+        let Some(refs) = lang.find_local_references(tree, content, &local.ident_range.start)
+        else {
+            continue;
+        };
+        // The declaration's own identifier is always part of the result.
+        if refs.iter().any(|r| *r != local.ident_range) {
+            continue;
+        }
+
+        // An initializer that may do something is kept as a statement of its own, or
+        // blocks the fix when it cannot stand alone.
+        let value = local
+            .statement_range
+            .and(decl)
+            .and_then(|statement| initializer(statement).map(|value| (statement, value)));
+        let fix = match value {
+            Some((_, value)) if !has_effect(value, bytes) => Fix::Delete,
+            Some((statement, value)) if is_effect(value, bytes) => {
+                match (value.utf8_text(bytes), statement.utf8_text(bytes)) {
+                    (Ok(value), Ok(statement)) if statement.ends_with(';') => {
+                        Fix::Keep(format!("{value};"))
+                    }
+                    (Ok(value), Ok(_)) => Fix::Keep(value.to_string()),
+                    _ => Fix::Withheld,
+                }
+            }
+            Some(_) => Fix::Withheld,
+            None => Fix::Delete,
+        };
+        let mut diagnostic = unused_diagnostic(
+            content,
+            "unused_local_variable",
+            format!("Local variable '{}' is never used", local.name),
+            local.ident_range,
+            local.statement_range.filter(|_| matches!(fix, Fix::Delete)),
+            format!("Remove unused '{}'", local.name),
+        );
+        if let (Fix::Keep(new_text), Some(range)) = (fix, local.statement_range) {
+            diagnostic.data = QuickFix::new(
+                format!("Remove unused '{}', keeping its initializer", local.name),
+                vec![TextEdit { range, new_text }],
+            )
+            .to_data();
+        }
+        diagnostics.push(diagnostic);
+    }
+}
+
+/// How an unused local's declaration is removed.
+enum Fix {
+    Delete,
+    /// Replace the declaration with its initializer, as an expression statement.
+    Keep(String),
+    /// No fix: the initializer has an effect but is no statement on its own.
+    Withheld,
+}
+
+/// Initializers that make a statement of their own: calls, object creations,
+/// assignments and increments, in the Java/Groovy and Kotlin grammars.
+const STATEMENT_EXPRESSIONS: &[&str] = &[
+    "method_invocation",
+    "object_creation_expression",
+    "assignment_expression",
+    "update_expression",
+    "call_expression",
+    "assignment",
+];
+
+/// Expressions whose body runs later, if at all, rather than where it is written.
+const DEFERRED_BODIES: &[&str] = &[
+    "lambda_expression",
+    "method_reference",
+    "closure",
+    "lambda_literal",
+    "anonymous_function",
+    "callable_reference",
+];
+
+/// The initializer of the single-variable declaration `statement`: the `value` of
+/// its declarator in Java and Groovy, of the property itself in Kotlin.
+fn initializer(statement: Node) -> Option<Node> {
+    if let Some(value) = statement.child_by_field_name("value") {
+        return Some(value);
+    }
+    let mut cursor = statement.walk();
+    let declarator = statement
+        .children(&mut cursor)
+        .find(|c| c.kind() == "variable_declarator")?;
+    declarator.child_by_field_name("value")
+}
+
+/// True when `node` is itself a call, creation, assignment or increment.
+fn is_effect(node: Node, bytes: &[u8]) -> bool {
+    if STATEMENT_EXPRESSIONS.contains(&node.kind()) {
+        return true;
+    }
+    let unary = matches!(
+        node.kind(),
+        "postfix_expression" | "prefix_expression" | "unary_expression"
+    );
+    unary
+        && node
+            .utf8_text(bytes)
+            .is_ok_and(|text| text.contains("++") || text.contains("--"))
+}
+
+/// True when evaluating `node` may do more than produce its value: it or one of its
+/// subexpressions is an effect, lambda and closure bodies aside.
+fn has_effect(node: Node, bytes: &[u8]) -> bool {
+    if DEFERRED_BODIES.contains(&node.kind()) {
+        return false;
+    }
+    if is_effect(node, bytes) {
+        return true;
+    }
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor)
+        .any(|child| has_effect(child, bytes))
+}
+
+fn unused_diagnostic(
+    content: &str,
+    code: &str,
+    message: String,
+    ident_range: Range,
+    decl_range: Option<Range>,
+    fix_title: String,
+) -> Diagnostic {
+    let data = decl_range
+        .and_then(|range| removal_range(content, range))
+        .and_then(|range| {
+            QuickFix::new(
+                fix_title,
+                vec![TextEdit {
+                    range,
+                    new_text: String::new(),
+                }],
+            )
+            .to_data()
+        });
+    Diagnostic {
+        range: ident_range,
+        severity: Some(DiagnosticSeverity::WARNING),
+        code: Some(NumberOrString::String(code.to_string())),
+        source: Some("lspintar".to_string()),
+        message,
+        tags: Some(vec![DiagnosticTag::UNNECESSARY]),
+        data,
+        ..Default::default()
+    }
+}
+
+fn to_point(position: Position) -> Point {
+    Point {
+        row: position.line as usize,
+        column: position.character as usize,
+    }
+}
+
+/// True when `node` or one of its enclosing declarations is annotated with
+/// `@SuppressWarnings("unused")` (Java/Groovy) or `@Suppress("unused")` (Kotlin).
+/// Only a declaration's header — the text before its body — is inspected, so a
+/// suppression on one member does not leak to its siblings through the class text.
+fn unused_suppressed(node: Node, bytes: &[u8], lang: &dyn LanguageSupport) -> bool {
+    let mut current = Some(node);
+    while let Some(n) = current {
+        current = n.parent();
+        if n.id() != node.id() && lang.get_kind(&n).is_none() {
+            continue;
+        }
+        let header_end = n
+            .child_by_field_name("body")
+            .map(|b| b.start_byte())
+            .unwrap_or_else(|| n.end_byte());
+        let header = std::str::from_utf8(&bytes[n.start_byte()..header_end]).unwrap_or_default();
+        if suppresses_unused(header) {
+            return true;
+        }
+    }
+    false
+}
+
+fn suppresses_unused(header: &str) -> bool {
+    header.match_indices("@Suppress").any(|(idx, _)| {
+        let rest = &header[idx..];
+        let args = &rest[..rest.find(')').unwrap_or(rest.len())];
+        args.contains("\"unused\"") || args.contains("\"all\"")
+    })
+}
+
+impl Backend {
+    /// "Remove all unused imports" for `uri`, leaving the imports whose warning is
+    /// suppressed.  `None` when the file has no unused import.
+    pub(crate) fn remove_unused_imports_action(&self, uri: &Url) -> Option<CodeActionOrCommand> {
+        let path = PathBuf::from(uri.path());
+        let lang = self.languages.get(path.extension()?.to_str()?)?;
+        let content = self.document_text(uri)?;
+        let (tree, _) = lang.parse_str(&content)?;
+        let suppressions = Suppressions::parse(&tree, &content, lang.as_ref());
+        let unused: Vec<Diagnostic> = unused_imports(&content, lang.as_ref())
+            .into_iter()
+            .filter(|d| !suppressions.suppresses(d))
+            .collect();
+        let edits: Vec<TextEdit> = unused
+            .iter()
+            .filter_map(QuickFix::from_diagnostic)
+            .flat_map(|fix| fix.edits)
+            .collect();
+        if edits.is_empty() {
+            return None;
+        }
+        Some(CodeActionOrCommand::CodeAction(CodeAction {
+            title: "Remove all unused imports".to_string(),
+            kind: Some(CodeActionKind::QUICKFIX),
+            diagnostics: Some(unused),
+            edit: Some(WorkspaceEdit {
+                changes: Some(HashMap::from([(uri.clone(), edits)])),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }))
+    }
+}
@@ -0,0 +1,223 @@
+//! Declaration visibility rules for completion and resolution.
+//!
+//! A symbol is accessible from a file when its declared visibility allows it:
+//! `private` members only from their own file, Java package-private members only
+//! from the same package, and Kotlin `internal` declarations only from the same
+//! module.  Each Gradle sub-project is one Kotlin module; single-project builds
+//! and files outside every known source root count as a single module.
+//! `protected` is treated as accessible, since subclass membership is not
+//! checked here.  Independently of modifiers, main sources cannot access
+//! declarations in test sources.
+
+use std::{collections::HashMap, path::Path};
+
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Range};
+
+use crate::{
+    enums::ResolvedSymbol, models::symbol::Symbol, server::Backend,
+    source_set::is_source_set_visible,
+};
+
+/// The file a completion or resolution request was made from.
+pub(crate) struct AccessContext<'a> {
+    pub file_path: &'a str,
+    pub package: &'a str,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Visibility {
+    Public,
+    Protected,
+    Internal,
+    PackagePrivate,
+    Private,
+}
+
+/// Reads the visibility from a modifier list.  Java declarations without an explicit
+/// modifier are package-private; Kotlin and Groovy default to public.
+fn declared_visibility(modifiers: &[String], file_type: &str) -> Visibility {
+    for modifier in modifiers {
+        match modifier.as_str() {
+            "private" => return Visibility::Private,
+            "protected" => return Visibility::Protected,
+            "internal" => return Visibility::Internal,
+            "public" => return Visibility::Public,
+            _ => {}
+        }
+    }
+    if file_type == "java" {
+        Visibility::PackagePrivate
+    } else {
+        Visibility::Public
+    }
+}
+
+impl Backend {
+    /// Keeps only the symbols accessible from `ctx`.
+    pub(crate) async fn retain_accessible(
+        &self,
+        symbols: &mut Vec<ResolvedSymbol>,
+        ctx: &AccessContext<'_>,
+    ) {
+        let mut parent_kinds: HashMap<String, Option<String>> = HashMap::new();
+        let mut keep = Vec::with_capacity(symbols.len());
+        for symbol in symbols.iter() {
+            keep.push(self.is_accessible(symbol, ctx, &mut parent_kinds).await);
+        }
+        let mut keep = keep.into_iter();
+        symbols.retain(|_| keep.next().unwrap_or(true));
+    }
+
+    /// Like [`Backend::retain_accessible`], but falls back to the full list when nothing
+    /// is accessible, so navigation still works from code that does not compile.
+    pub(crate) async fn prefer_accessible(
+        &self,
+        symbols: Vec<ResolvedSymbol>,
+        ctx: &AccessContext<'_>,
+    ) -> Vec<ResolvedSymbol> {
+        let mut accessible = symbols.clone();
+        self.retain_accessible(&mut accessible, ctx).await;
+        if accessible.is_empty() {
+            symbols
+        } else {
+            accessible
+        }
+    }
+
+    async fn is_accessible(
+        &self,
+        symbol: &ResolvedSymbol,
+        ctx: &AccessContext<'_>,
+        parent_kinds: &mut HashMap<String, Option<String>>,
+    ) -> bool {
+        let (modifiers, file_type, package, parent, file_path) = match symbol {
+            ResolvedSymbol::Local { .. } => return true,
+            ResolvedSymbol::Project(s) => (
+                &s.modifiers.0,
+                s.file_type.as_str(),
+                s.package_name.as_str(),
+                s.parent_name.as_deref(),
+                Some(s.file_path.as_str()),
+            ),
+            ResolvedSymbol::External(s) => (
+                &s.modifiers.0,
+                s.file_type.as_str(),
+                s.package_name.as_str(),
+                s.parent_name.as_deref(),
+                None,
+            ),
+        };
+
+        if let Some(decl_path) = file_path
+            && !is_source_set_visible(Path::new(ctx.file_path), Path::new(decl_path))
+        {
+            return false;
+        }
+
+        match declared_visibility(modifiers, file_type) {
+            Visibility::Public | Visibility::Protected => true,
+            Visibility::Internal => match file_path {
+                Some(decl_path) => {
+                    self.same_module(Path::new(ctx.file_path), Path::new(decl_path))
+                        .await
+                }
+                None => true,
+            },
+            Visibility::Private => file_path == Some(ctx.file_path),
+            Visibility::PackagePrivate => {
+                if package == ctx.package {
+                    return true;
+                }
+                // Interface and annotation members are implicitly public, as are enum
+                // constants (indexed as fields of the enum).
+                let Some(parent) = parent else {
+                    return false;
+                };
+                let parent_kind = match parent_kinds.get(parent) {
+                    Some(kind) => kind.clone(),
+                    None => {
+                        let kind = self.symbol_type_of(parent).await;
+                        parent_kinds.insert(parent.to_string(), kind.clone());
+                        kind
+                    }
+                };
+                match parent_kind.as_deref() {
+                    Some("Interface" | "Annotation") => true,
+                    Some("Enum") => symbol.node_kind() == lsp_core::node_kind::NodeKind::Field,
+                    _ => false,
+                }
+            }
+        }
+    }
+
+    /// True when `a` and `b` belong to the same Kotlin module.
+    pub(crate) async fn same_module(&self, a: &Path, b: &Path) -> bool {
+        let classpath = self.subproject_classpath.read().await;
+        let module_of = |file: &Path| classpath.iter().position(|e| e.contains_file(file));
+        match (module_of(a), module_of(b)) {
+            (Some(x), Some(y)) => x == y,
+            _ => true,
+        }
+    }
+
+    /// Reports a reference from `path` to a Kotlin `internal` declaration of another module.
+    pub(crate) async fn internal_access_diagnostic(
+        &self,
+        path: &Path,
+        symbol: &Symbol,
+        name: &str,
+        range: Range,
+    ) -> Option<Diagnostic> {
+        if symbol.file_type != "kotlin"
+            || !symbol.modifiers.0.iter().any(|m| m == "internal")
+            || self.same_module(path, Path::new(&symbol.file_path)).await
+        {
+            return None;
+        }
+        Some(Diagnostic {
+            range,
+            severity: Some(DiagnosticSeverity::ERROR),
+            code: Some(NumberOrString::String("internal_access".to_string())),
+            source: Some("lspintar".to_string()),
+            message: format!("Cannot access '{name}': it is internal in another module"),
+            ..Default::default()
+        })
+    }
+
+    /// Like [`Backend::internal_access_diagnostic`] for `receiver.member` accesses: reports
+    /// when every overload of `member` declared directly on `type_fqn` is `internal` to
+    /// another module.
+    pub(crate) async fn internal_member_access_diagnostic(
+        &self,
+        path: &Path,
+        type_fqn: &str,
+        member: &str,
+        range: Range,
+    ) -> Option<Diagnostic> {
+        let repo = self.repo.get()?;
+        let overloads: Vec<Symbol> = repo
+            .find_symbols_by_parent_name(type_fqn)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|sym| sym.short_name == member)
+            .collect();
+        let mut diagnostic = None;
+        for sym in &overloads {
+            diagnostic = Some(self.internal_access_diagnostic(path, sym, member, range).await?);
+        }
+        diagnostic
+    }
+
+    pub(crate) async fn symbol_type_of(&self, fqn: &str) -> Option<String> {
+        let repo = self.repo.get()?;
+        if let Ok(Some(sym)) = repo.find_symbol_by_fqn(fqn).await {
+            return Some(sym.symbol_type);
+        }
+        repo.find_external_symbol_by_fqn(fqn)
+            .await
+            .ok()
+            .flatten()
+            .map(|sym| sym.symbol_type)
+    }
+}
@@ -0,0 +1,208 @@
+//! `lspintar warm-cache`: the index built ahead of time, e.g. in CI.
+//!
+//! ```text
+//! lspintar warm-cache [--export <dir>] [<path>]   index the workspace, then export it
+//! lspintar warm-cache --import <dir> [<path>]      install an exported index
+//! ```
+//!
+//! Indexing a large workspace and its dependencies takes minutes on first open.
+//! `warm-cache` does it once, the workspace and every dependency JAR, and persists
+//! it under `<path>/.lspintar` as the server would.  `--export` copies the result
+//! to a directory a CI job can publish as an artifact; `--import` installs such a
+//! directory into a fresh checkout, where the server then only catches up on what
+//! changed since.  The directory holds a `.lspintar` laid out as the workspace's.
+//!
+//! The index records paths under the workspace root and the home directory (where
+//! the Gradle cache lives) relative to them.  The manifests beside it record
+//! absolute paths: an export replaces those roots with placeholders, and an import
+//! puts the local ones back, so a cache built in `/home/ci/work/app` serves a
+//! checkout in `/Users/dev/src/app`.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use anyhow::{Result, anyhow, bail};
+use serde_json::Value;
+
+use crate::{
+    analyzer::Analyzer,
+    constants::{
+        APP_VERSION, CLASSPATH_MANIFEST_PATH_FRAGMENT, DB_PATH_FRAGMENT, HOME_PATH_ROOT,
+        INDEX_PATH_FRAGMENT, MANIFEST_PATH_FRAGMENT, VCS_REVISION_PATH_FRAGMENT,
+        WORKSPACE_PATH_ROOT,
+    },
+    db_lock::{IndexLock, lock_path},
+    repo::{Repository, dir_prefix, path_root_prefix},
+};
+
+pub const WARM_CACHE_COMMAND: &str = "warm-cache";
+
+const USAGE: &str = "usage: lspintar warm-cache [--export <dir>] [<path>]\n       \
+                     lspintar warm-cache --import <dir> [<path>]";
+
+/// Manifests whose paths are rewritten on export and import.
+const MANIFESTS: &[&str] = &[MANIFEST_PATH_FRAGMENT, CLASSPATH_MANIFEST_PATH_FRAGMENT];
+
+/// Files copied as they are.
+const MARKERS: &[&str] = &[INDEX_PATH_FRAGMENT, VCS_REVISION_PATH_FRAGMENT];
+
+/// Runs `lspintar warm-cache` with the arguments following it.
+pub async fn run(args: &[String]) -> ExitCode {
+    let mut export = None;
+    let mut import = None;
+    let mut root = PathBuf::from(".");
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--export" | "--import" => {
+                let Some(dir) = args.next() else {
+                    return usage(&format!("missing {arg} directory"));
+                };
+                if arg == "--export" {
+                    export = Some(PathBuf::from(dir));
+                } else {
+                    import = Some(PathBuf::from(dir));
+                }
+            }
+            path if !path.starts_with("--") => root = PathBuf::from(path),
+            other => return usage(&format!("unknown option {other}")),
+        }
+    }
+
+    let result = match (export, import) {
+        (Some(_), Some(_)) => return usage("--export and --import are exclusive"),
+        (export, None) => warm(&root, export.as_deref()).await,
+        (None, Some(import)) => import_cache(&import, &root).await,
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Indexes the workspace at `root` and, with `export`, exports its index there.
+async fn warm(root: &Path, export: Option<&Path>) -> Result<()> {
+    let analyzer = Analyzer::open_workspace(root)
+        .await
+        .map_err(|e| anyhow!("cannot open {}: {e}", root.display()))?;
+    let Some(export) = export else {
+        return Ok(());
+    };
+    let root = analyzer.root().await.unwrap_or(root.to_path_buf());
+    let repo = analyzer
+        .backend()
+        .repo
+        .get()
+        .ok_or_else(|| anyhow!("the index is not available"))?;
+    export_cache(repo, &root, export).await
+}
+
+/// Writes the index of the workspace at `root`, read from `repo`, to the directory
+/// `dir` with the manifests' paths made relocatable.
+pub async fn export_cache(repo: &Repository, root: &Path, dir: &Path) -> Result<()> {
+    let db = dir.join(DB_PATH_FRAGMENT);
+    fs::create_dir_all(db.parent().unwrap_or(dir))?;
+    if db.exists() {
+        fs::remove_file(&db)?;
+    }
+    repo.export_to(&db.to_string_lossy()).await?;
+    copy_cache_files(root, dir, &export_prefixes(root))
+}
+
+/// Installs the index exported to `dir` as the index of the workspace at `root`,
+/// replacing any index there.  Fails while a server is using it.
+pub async fn import_cache(dir: &Path, root: &Path) -> Result<()> {
+    let root = root.canonicalize()?;
+    let version = fs::read_to_string(dir.join(INDEX_PATH_FRAGMENT)).unwrap_or_default();
+    if version.trim() != APP_VERSION {
+        bail!(
+            "{} was exported by lspintar {}, not {APP_VERSION}",
+            dir.display(),
+            if version.trim().is_empty() {
+                "(unknown)"
+            } else {
+                version.trim()
+            }
+        );
+    }
+
+    let db = root.join(DB_PATH_FRAGMENT);
+    fs::create_dir_all(db.parent().unwrap_or(&root))?;
+    let Some(_lock) = IndexLock::try_acquire(&lock_path(&db))? else {
+        bail!("{} is in use by a running server", db.display());
+    };
+    for stale in [
+        db.clone(),
+        db.with_extension("db-wal"),
+        db.with_extension("db-shm"),
+    ] {
+        if stale.exists() {
+            fs::remove_file(stale)?;
+        }
+    }
+    fs::copy(dir.join(DB_PATH_FRAGMENT), &db)?;
+    copy_cache_files(dir, &root, &import_prefixes(&root))
+}
+
+/// The workspace root first: it usually lies under the home directory.
+fn export_prefixes(root: &Path) -> Vec<(String, String)> {
+    let mut prefixes = vec![(dir_prefix(root), path_root_prefix(WORKSPACE_PATH_ROOT))];
+    if let Some(home) = dirs::home_dir() {
+        prefixes.push((dir_prefix(&home), path_root_prefix(HOME_PATH_ROOT)));
+    }
+    prefixes
+}
+
+fn import_prefixes(root: &Path) -> Vec<(String, String)> {
+    export_prefixes(root)
+        .into_iter()
+        .map(|(absolute, stored)| (stored, absolute))
+        .collect()
+}
+
+/// Copies the manifests and markers from `from` to `to`, both roots, rewriting the
+/// manifests' paths.  Those missing in `from` are skipped.
+fn copy_cache_files(from: &Path, to: &Path, prefixes: &[(String, String)]) -> Result<()> {
+    for fragment in MANIFESTS {
+        let Ok(text) = fs::read_to_string(from.join(fragment)) else {
+            continue;
+        };
+        let mut manifest: Value = serde_json::from_str(&text)?;
+        relocate_value(&mut manifest, prefixes);
+        fs::write(to.join(fragment), serde_json::to_string(&manifest)?)?;
+    }
+    for fragment in MARKERS {
+        if from.join(fragment).exists() {
+            fs::copy(from.join(fragment), to.join(fragment))?;
+        }
+    }
+    Ok(())
+}
+
+/// Rewrites every string in `value` starting with a `from` prefix, the first that
+/// matches.
+fn relocate_value(value: &mut Value, prefixes: &[(String, String)]) {
+    match value {
+        Value::String(s) => {
+            if let Some((from, to)) = prefixes.iter().find(|(from, _)| s.starts_with(from)) {
+                *s = format!("{to}{}", &s[from.len()..]);
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|v| relocate_value(v, prefixes)),
+        Value::Object(fields) => fields
+            .values_mut()
+            .for_each(|v| relocate_value(v, prefixes)),
+        _ => {}
+    }
+}
+
+fn usage(message: &str) -> ExitCode {
+    eprintln!("{message}\n{USAGE}");
+    ExitCode::from(2)
+}
@@ -0,0 +1,210 @@
+//! `workspace/diagnostic` — the diagnostics of every indexed source file, for a
+//! project-wide problems view.
+//!
+//! Reports are computed in the background once the index is built and kept per file.
+//! A file's report goes stale when the file is re-indexed; every report goes stale
+//! when a re-indexed file changes what it declares, since other files' unresolved
+//! symbol and missing method checks depend on it.  Only stale reports are computed
+//! again, a few files at a time, and a client holding a report's result id is told
+//! it is unchanged.
+//! Build files are reported too, with the project dependency cycles they close.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use dashmap::DashMap;
+use futures::{StreamExt, stream};
+use tower_lsp::lsp_types::{
+    Diagnostic, FullDocumentDiagnosticReport, UnchangedDocumentDiagnosticReport, Url,
+    WorkspaceDiagnosticParams, WorkspaceDiagnosticReport, WorkspaceDocumentDiagnosticReport,
+    WorkspaceFullDocumentDiagnosticReport, WorkspaceUnchangedDocumentDiagnosticReport,
+};
+
+use crate::{
+    constants::WORKSPACE_DIAGNOSTICS_CONCURRENCY, indexer::content_hash, models::symbol::Symbol,
+    server::Backend,
+};
+
+#[derive(Default)]
+pub(crate) struct WorkspaceDiagnostics {
+    /// Latest report of each indexed file, by path.
+    reports: DashMap<PathBuf, FileReport>,
+    /// Bumped whenever every report goes stale.
+    generation: AtomicU64,
+    /// Held while reports are being computed, so refreshes do not overlap.
+    refreshing: tokio::sync::Mutex<()>,
+}
+
+struct FileReport {
+    generation: u64,
+    content_hash: String,
+    items: Vec<Diagnostic>,
+}
+
+impl FileReport {
+    fn result_id(&self) -> String {
+        format!("{}:{}", self.generation, self.content_hash)
+    }
+}
+
+impl WorkspaceDiagnostics {
+    /// Marks what re-indexing `path` from `before` to `after` made stale.
+    pub(crate) fn file_reindexed(&self, path: &Path, before: &[Symbol], after: &[Symbol]) {
+        let declared = |symbols: &[Symbol]| {
+            let mut fqns: Vec<String> = symbols
+                .iter()
+                .map(|s| s.fully_qualified_name.clone())
+                .collect();
+            fqns.sort();
+            fqns
+        };
+        if declared(before) != declared(after) {
+            self.invalidate_all();
+        }
+        self.reports.remove(path);
+    }
+
+    pub(crate) fn file_removed(&self, path: &Path) {
+        self.reports.remove(path);
+        self.invalidate_all();
+    }
+
+    pub(crate) fn invalidate_all(&self) {
+        self.generation.fetch_add(1, Ordering::AcqRel);
+    }
+}
+
+impl Backend {
+    /// Computes the report of every indexed file that has none, or a stale one.
+    pub(crate) async fn refresh_workspace_diagnostics(&self) {
+        if !self.index_ready.load(Ordering::Acquire) {
+            return;
+        }
+        let Some(repo) = self.repo.get() else {
+            return;
+        };
+        let _refreshing = self.workspace_diagnostics.refreshing.lock().await;
+        let paths = repo.find_all_source_file_paths().await.unwrap_or_default();
+
+        let build_files = self.project_cycle_reports().await;
+
+        let reports = &self.workspace_diagnostics.reports;
+        let live: HashSet<PathBuf> = paths
+            .iter()
+            .map(PathBuf::from)
+            .chain(build_files.iter().map(|(file, _)| file.clone()))
+            .collect();
+        reports.retain(|path, _| live.contains(path));
+        stream::iter(paths)
+            .map(|path| async move {
+                let path = PathBuf::from(path);
+                let uri = Url::from_file_path(&path).ok()?;
+                let content = self.document_text(&uri)?;
+                let hash = content_hash(content.as_bytes());
+                let generation = self
+                    .workspace_diagnostics
+                    .generation
+                    .load(Ordering::Acquire);
+                let fresh = reports
+                    .get(&path)
+                    .is_some_and(|r| r.generation == generation && r.content_hash == hash);
+                if fresh {
+                    return None;
+                }
+                let items = self.compute_diagnostics(&uri).await?;
+                let report = FileReport {
+                    generation,
+                    content_hash: hash,
+                    items,
+                };
+                Some((path, report))
+            })
+            .buffer_unordered(WORKSPACE_DIAGNOSTICS_CONCURRENCY)
+            .for_each(|computed| async move {
+                if let Some((path, report)) = computed {
+                    reports.insert(path, report);
+                }
+            })
+            .await;
+
+        // A build file's report depends on every other build file, so it is told
+        // apart by what it reports rather than by its own text.
+        let generation = self
+            .workspace_diagnostics
+            .generation
+            .load(Ordering::Acquire);
+        for (path, items) in build_files {
+            let reported = serde_json::to_vec(&items).unwrap_or_default();
+            reports.insert(
+                path,
+                FileReport {
+                    generation,
+                    content_hash: content_hash(&reported),
+                    items,
+                },
+            );
+        }
+    }
+
+    /// Runs [`Backend::refresh_workspace_diagnostics`] without waiting for it.
+    pub(crate) fn spawn_workspace_diagnostics_refresh(&self) {
+        let backend = self.clone();
+        tokio::spawn(async move { backend.refresh_workspace_diagnostics().await });
+    }
+
+    /// Reports for every indexed file, `Unchanged` for those whose result id the
+    /// client already holds.  Open documents are left out: their diagnostics are
+    /// published as they are edited.
+    pub(crate) async fn workspace_diagnostic_report(
+        &self,
+        params: &WorkspaceDiagnosticParams,
+    ) -> WorkspaceDiagnosticReport {
+        self.refresh_workspace_diagnostics().await;
+
+        let previous: HashMap<&Url, &str> = params
+            .previous_result_ids
+            .iter()
+            .map(|previous| (&previous.uri, previous.value.as_str()))
+            .collect();
+        let mut items: Vec<WorkspaceDocumentDiagnosticReport> = Vec::new();
+        for entry in self.workspace_diagnostics.reports.iter() {
+            let Ok(uri) = Url::from_file_path(entry.key()) else {
+                continue;
+            };
+            if self.documents.contains_key(&uri.to_string()) {
+                continue;
+            }
+            let report = entry.value();
+            let result_id = report.result_id();
+            let unchanged = previous.get(&uri) == Some(&result_id.as_str());
+            items.push(if unchanged {
+                WorkspaceDocumentDiagnosticReport::Unchanged(
+                    WorkspaceUnchangedDocumentDiagnosticReport {
+                        uri,
+                        version: None,
+                        unchanged_document_diagnostic_report: UnchangedDocumentDiagnosticReport {
+                            result_id,
+                        },
+                    },
+                )
+            } else {
+                WorkspaceDocumentDiagnosticReport::Full(WorkspaceFullDocumentDiagnosticReport {
+                    uri,
+                    version: None,
+                    full_document_diagnostic_report: FullDocumentDiagnosticReport {
+                        result_id: Some(result_id),
+                        items: report.items.clone(),
+                    },
+                })
+            });
+        }
+        items.sort_by_key(|item| match item {
+            WorkspaceDocumentDiagnosticReport::Full(r) => r.uri.to_string(),
+            WorkspaceDocumentDiagnosticReport::Unchanged(r) => r.uri.to_string(),
+        });
+        WorkspaceDiagnosticReport { items }
+    }
+}
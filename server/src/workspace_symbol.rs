@@ -0,0 +1,264 @@
+//! `workspace/symbol` — project types and methods by partial or camel-hump name, so
+//! `UsrSvc` finds `UserService` and `fBI` finds `findById`.
+//!
+//! [`SymbolSearchIndex`] is built from the index on the first query and dropped
+//! whenever a file is re-indexed.  It lists, per letter, the symbols with a hump
+//! starting with that letter; a query is matched only against the symbols listed
+//! under each of its own hump initials.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use lsp_core::node_kind::{NodeKind, Placement};
+use tower_lsp::lsp_types::{SymbolInformation, SymbolKind};
+
+use crate::{lsp_convert::AsLspLocation, models::symbol::Symbol, server::Backend};
+
+/// Most symbols answered per query, best matches first.
+const MAX_WORKSPACE_SYMBOLS: usize = 200;
+
+#[derive(Default)]
+pub(crate) struct WorkspaceSymbols {
+    index: RwLock<Option<Arc<SymbolSearchIndex>>>,
+}
+
+impl WorkspaceSymbols {
+    pub(crate) fn invalidate(&self) {
+        *self.index.write().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+}
+
+pub(crate) struct SymbolSearchIndex {
+    symbols: Vec<Symbol>,
+    /// Positions in `symbols` of those with a hump starting with each lowercase letter.
+    by_initial: HashMap<char, Vec<usize>>,
+}
+
+impl SymbolSearchIndex {
+    pub(crate) fn new(symbols: Vec<Symbol>) -> Self {
+        let mut by_initial: HashMap<char, Vec<usize>> = HashMap::new();
+        for (i, symbol) in symbols.iter().enumerate() {
+            let name: Vec<char> = symbol.short_name.chars().collect();
+            let mut initials: Vec<char> = hump_starts(&name)
+                .iter()
+                .enumerate()
+                .filter(|(_, start)| **start)
+                .flat_map(|(n, _)| name[n].to_lowercase())
+                .collect();
+            initials.sort_unstable();
+            initials.dedup();
+            for initial in initials {
+                by_initial.entry(initial).or_default().push(i);
+            }
+        }
+        Self {
+            symbols,
+            by_initial,
+        }
+    }
+
+    /// The symbols whose name matches `query`: exact names first, then prefixes,
+    /// then hump matches skipping the fewest characters.
+    pub(crate) fn search(&self, query: &str) -> Vec<&Symbol> {
+        let query: Vec<char> = query.chars().filter(|c| !c.is_whitespace()).collect();
+        let candidates: Vec<usize> = match self.candidates(&query) {
+            Some(candidates) => candidates,
+            None => (0..self.symbols.len()).collect(),
+        };
+
+        let lowered: String = query.iter().flat_map(|c| c.to_lowercase()).collect();
+        let mut matches: Vec<((u8, usize, usize), &Symbol)> = candidates
+            .into_iter()
+            .filter_map(|i| {
+                let symbol = &self.symbols[i];
+                let skipped = hump_match(&query, &symbol.short_name)?;
+                let name = symbol.short_name.to_lowercase();
+                let tier = if name == lowered {
+                    0
+                } else if name.starts_with(&lowered) {
+                    1
+                } else {
+                    2
+                };
+                Some(((tier, skipped, symbol.short_name.len()), symbol))
+            })
+            .collect();
+        matches.sort_by(|a, b| {
+            a.0.cmp(&b.0)
+                .then_with(|| a.1.fully_qualified_name.cmp(&b.1.fully_qualified_name))
+        });
+        matches
+            .into_iter()
+            .take(MAX_WORKSPACE_SYMBOLS)
+            .map(|(_, s)| s)
+            .collect()
+    }
+
+    /// The symbols listed under every hump initial of `query`; `None` for an empty
+    /// query, which matches everything.
+    fn candidates(&self, query: &[char]) -> Option<Vec<usize>> {
+        let mut initials: Vec<char> = query
+            .iter()
+            .enumerate()
+            .filter(|(i, c)| *i == 0 || c.is_uppercase())
+            .flat_map(|(_, c)| c.to_lowercase())
+            .collect();
+        initials.sort_unstable();
+        initials.dedup();
+
+        let mut postings: Vec<&Vec<usize>> = Vec::new();
+        for initial in &initials {
+            let Some(posting) = self.by_initial.get(initial) else {
+                return Some(vec![]);
+            };
+            postings.push(posting);
+        }
+        postings.sort_by_key(|p| p.len());
+        let (shortest, rest) = postings.split_first()?;
+        Some(
+            shortest
+                .iter()
+                .copied()
+                .filter(|i| rest.iter().all(|p| p.binary_search(i).is_ok()))
+                .collect(),
+        )
+    }
+}
+
+/// Whether each character of `name` starts a hump: the first character, an
+/// uppercase letter after a lowercase one or a digit, the last capital of an
+/// acronym followed by a lowercase letter (`HTTPServer`), or a character after `_`.
+fn hump_starts(name: &[char]) -> Vec<bool> {
+    (0..name.len())
+        .map(|i| {
+            if i == 0 {
+                return true;
+            }
+            let (prev, c) = (name[i - 1], name[i]);
+            ((prev == '_' || prev == '$') && c.is_alphanumeric())
+                || (c.is_uppercase() && (prev.is_lowercase() || prev.is_ascii_digit()))
+                || (c.is_uppercase()
+                    && prev.is_uppercase()
+                    && name.get(i + 1).is_some_and(|next| next.is_lowercase()))
+        })
+        .collect()
+}
+
+/// Matches `query` against `name` hump by hump, case-insensitively: the first
+/// query character and every uppercase one start a hump of `name`, the others
+/// continue the current hump, possibly skipping characters, or start the next.
+/// Returns the fewest characters skipped, or `None` when `name` does not match.
+fn hump_match(query: &[char], name: &str) -> Option<usize> {
+    let name: Vec<char> = name.chars().collect();
+    let starts = hump_starts(&name);
+    let mut hump = Vec::with_capacity(name.len());
+    let mut current = 0;
+    for (i, start) in starts.iter().enumerate() {
+        if *start && i > 0 {
+            current += 1;
+        }
+        hump.push(current);
+    }
+
+    // Memoized by (q, n): fewest characters skipped matching query[q..] against
+    // name[n..], the previous query character having matched name[n - 1].
+    let mut memo: HashMap<(usize, usize), Option<usize>> = HashMap::new();
+    fn go(
+        q: usize,
+        n: usize,
+        query: &[char],
+        name: &[char],
+        starts: &[bool],
+        hump: &[usize],
+        memo: &mut HashMap<(usize, usize), Option<usize>>,
+    ) -> Option<usize> {
+        if q == query.len() {
+            return Some(0);
+        }
+        if let Some(cached) = memo.get(&(q, n)) {
+            return *cached;
+        }
+        let wanted = query[q];
+        let mut best: Option<usize> = None;
+        for m in n..name.len() {
+            let same_hump = q > 0 && n > 0 && hump[m] == hump[n - 1];
+            if !same_hump && !starts[m] {
+                continue;
+            }
+            if !starts[m] && wanted.is_uppercase() {
+                continue;
+            }
+            if !name[m].to_lowercase().eq(wanted.to_lowercase()) {
+                continue;
+            }
+            if let Some(rest) = go(q + 1, m + 1, query, name, starts, hump, memo) {
+                let cost = rest + (m - n);
+                best = Some(best.map_or(cost, |b| b.min(cost)));
+            }
+        }
+        memo.insert((q, n), best);
+        best
+    }
+    go(0, 0, query, &name, &starts, &hump, &mut memo)
+}
+
+impl Backend {
+    /// Project types and methods matching `query`, best first.
+    pub async fn workspace_symbols(&self, query: &str) -> Vec<SymbolInformation> {
+        let Some(index) = self.symbol_search_index().await else {
+            return vec![];
+        };
+        index
+            .search(query)
+            .into_iter()
+            .filter_map(|symbol| {
+                let location = symbol.as_lsp_location()?;
+                let placement = Placement {
+                    parent: symbol.parent_name.as_deref().map(Placement::short_name),
+                    parent_kind: None,
+                };
+                let kind = NodeKind::from_string(&symbol.symbol_type)
+                    .map_or(SymbolKind::CLASS, |k| {
+                        k.symbol_kind(&symbol.short_name, None, placement)
+                    });
+                let container_name = match &symbol.parent_name {
+                    Some(parent) => parent.clone(),
+                    None => symbol.package_name.clone(),
+                };
+                #[allow(deprecated)]
+                Some(SymbolInformation {
+                    name: symbol.short_name.clone(),
+                    kind,
+                    tags: None,
+                    deprecated: None,
+                    location,
+                    container_name: (!container_name.is_empty()).then_some(container_name),
+                })
+            })
+            .collect()
+    }
+
+    async fn symbol_search_index(&self) -> Option<Arc<SymbolSearchIndex>> {
+        if let Some(index) = self
+            .workspace_symbols
+            .index
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+        {
+            return Some(Arc::clone(index));
+        }
+        let mut symbols = self.repo.get()?.find_all_types_and_functions().await.ok()?;
+        // The property a facade accessor stands for is listed already.
+        symbols.retain(|s| !s.is_synthetic_accessor());
+        let index = Arc::new(SymbolSearchIndex::new(symbols));
+        *self
+            .workspace_symbols
+            .index
+            .write()
+            .unwrap_or_else(|e| e.into_inner()) = Some(Arc::clone(&index));
+        Some(index)
+    }
+}
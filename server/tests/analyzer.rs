@@ -0,0 +1,43 @@
+use std::fs;
+
+use lspintar_server::analyzer::Analyzer;
+use tower_lsp::lsp_types::{Position, Url};
+
+#[tokio::test]
+async fn analyzer_answers_without_a_client() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().canonicalize().unwrap();
+    let package = root.join("src/main/java/com/example");
+    fs::create_dir_all(&package).unwrap();
+    fs::write(
+        package.join("Greeter.java"),
+        "package com.example;\n\npublic class Greeter {\n    public String greet() { return \"hi\"; }\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        package.join("App.java"),
+        "package com.example;\n\npublic class App {\n    private Greeter greeter;\n}\n",
+    )
+    .unwrap();
+    let app = package.join("App.java");
+
+    let analyzer = Analyzer::open_workspace(&root).await.unwrap();
+
+    let locations = analyzer.resolve(&app, Position::new(3, 14)).await.unwrap();
+    assert_eq!(locations.len(), 1);
+    assert_eq!(
+        locations[0].uri,
+        Url::from_file_path(package.join("Greeter.java")).unwrap()
+    );
+    assert_eq!(locations[0].range.start, Position::new(2, 13));
+
+    let hover = analyzer
+        .hover(&app, Position::new(3, 14))
+        .await
+        .unwrap()
+        .expect("hover on a project type");
+    assert!(hover.contains("Greeter"), "{hover}");
+
+    let symbols = analyzer.symbols(&app).await.unwrap();
+    assert_eq!(symbols[0].name, "App");
+}
@@ -0,0 +1,51 @@
+use lspintar_server::build_scripts::ScriptReferences;
+use tower_lsp::lsp_types::{Position, Range};
+
+fn range(line: u32, start: u32, end: u32) -> Range {
+    Range::new(Position::new(line, start), Position::new(line, end))
+}
+
+#[test]
+fn imported_class_is_found_in_groovy_script() {
+    let script = ScriptReferences::parse(
+        "import com.acme.build.DocsTask\n\
+         \n\
+         // DocsTask renders the site\n\
+         tasks.register('docs', DocsTask) {\n\
+         \x20   title = 'DocsTask'\n\
+         }\n",
+    );
+    assert_eq!(
+        script.usages("com.acme.build.DocsTask"),
+        vec![range(0, 22, 30), range(3, 23, 31)]
+    );
+    assert!(script.usages("com.other.DocsTask").is_empty());
+}
+
+#[test]
+fn qualified_and_wildcard_mentions_are_found_in_kotlin_script() {
+    let script = ScriptReferences::parse(
+        "import com.acme.build.*\n\
+         \n\
+         tasks.register<DocsTask>(\"docs\")\n\
+         tasks.register<com.acme.release.PublishTask>(\"publish\")\n",
+    );
+    assert_eq!(
+        script.usages("com.acme.build.DocsTask"),
+        vec![range(2, 15, 23)]
+    );
+    assert_eq!(
+        script.usages("com.acme.release.PublishTask"),
+        vec![range(3, 32, 43)]
+    );
+}
+
+#[test]
+fn default_package_class_is_found_unless_imported_from_elsewhere() {
+    let unqualified = ScriptReferences::parse("tasks.register('docs', DocsTask)\n");
+    assert_eq!(unqualified.usages("DocsTask"), vec![range(0, 23, 31)]);
+
+    let imported =
+        ScriptReferences::parse("import org.docs.DocsTask\ntasks.register('docs', DocsTask)\n");
+    assert!(imported.usages("DocsTask").is_empty());
+}
@@ -0,0 +1,110 @@
+use std::path::{Path, PathBuf};
+
+use lspintar_server::check::{
+    CheckFormat, CheckOptions, FileDiagnostics, fails, render_human, render_sarif,
+};
+use pretty_assertions::assert_eq;
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Position, Range};
+
+fn diagnostic(line: u32, severity: DiagnosticSeverity, code: &str, message: &str) -> Diagnostic {
+    Diagnostic {
+        range: Range::new(Position::new(line, 7), Position::new(line, 12)),
+        severity: Some(severity),
+        code: Some(NumberOrString::String(code.to_string())),
+        source: Some("lspintar".to_string()),
+        message: message.to_string(),
+        ..Default::default()
+    }
+}
+
+fn results() -> Vec<FileDiagnostics> {
+    vec![FileDiagnostics {
+        path: PathBuf::from("/work/app/src/main/java/App.java"),
+        diagnostics: vec![
+            diagnostic(
+                2,
+                DiagnosticSeverity::ERROR,
+                "unresolved_import",
+                "Unresolved import com.foo.Bar",
+            ),
+            diagnostic(
+                3,
+                DiagnosticSeverity::WARNING,
+                "unused_import",
+                "Unused import java.util.List",
+            ),
+        ],
+    }]
+}
+
+#[test]
+fn check_arguments() {
+    let args = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+    assert_eq!(
+        CheckOptions::parse(&args(&["--format", "sarif", "app"])),
+        Ok(CheckOptions {
+            root: PathBuf::from("app"),
+            format: CheckFormat::Sarif,
+            warnings_as_errors: false,
+        })
+    );
+    assert_eq!(
+        CheckOptions::parse(&args(&["app", "--warnings-as-errors"])).map(|o| o.warnings_as_errors),
+        Ok(true)
+    );
+    assert!(CheckOptions::parse(&args(&[])).is_err());
+    assert!(CheckOptions::parse(&args(&["--format", "xml", "app"])).is_err());
+    assert!(CheckOptions::parse(&args(&["app", "other"])).is_err());
+}
+
+#[test]
+fn check_fails_on_errors_and_optionally_warnings() {
+    let results = results();
+    assert!(fails(&results, false));
+
+    let warnings_only = vec![FileDiagnostics {
+        path: results[0].path.clone(),
+        diagnostics: vec![results[0].diagnostics[1].clone()],
+    }];
+    assert!(!fails(&warnings_only, false));
+    assert!(fails(&warnings_only, true));
+    assert!(!fails(&[], true));
+}
+
+#[test]
+fn check_human_output() {
+    assert_eq!(
+        render_human(&results(), Path::new("/work/app")),
+        "src/main/java/App.java:3:8: error[unresolved_import]: Unresolved import com.foo.Bar\n\
+         src/main/java/App.java:4:8: warning[unused_import]: Unused import java.util.List\n\
+         1 error, 1 warning\n"
+    );
+}
+
+#[test]
+fn check_sarif_output() {
+    let sarif = render_sarif(&results(), Path::new("/work/app"));
+
+    assert_eq!(sarif["version"], "2.1.0");
+    let run = &sarif["runs"][0];
+    assert_eq!(run["tool"]["driver"]["name"], "lspintar");
+    assert_eq!(
+        run["tool"]["driver"]["rules"],
+        serde_json::json!([{ "id": "unresolved_import" }, { "id": "unused_import" }])
+    );
+    let result = &run["results"][0];
+    assert_eq!(result["ruleId"], "unresolved_import");
+    assert_eq!(result["level"], "error");
+    assert_eq!(result["message"]["text"], "Unresolved import com.foo.Bar");
+    let location = &result["locations"][0]["physicalLocation"];
+    assert_eq!(
+        location["artifactLocation"]["uri"],
+        "src/main/java/App.java"
+    );
+    assert_eq!(
+        location["region"],
+        serde_json::json!({ "startLine": 3, "startColumn": 8, "endLine": 3, "endColumn": 13 })
+    );
+    assert_eq!(run["results"][1]["level"], "warning");
+}
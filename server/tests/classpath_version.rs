@@ -0,0 +1,101 @@
+use std::sync::Arc;
+
+use lspintar_server::{
+    Repository,
+    classpath_version::closest_version,
+    models::{
+        external_symbol::ExternalSymbol,
+        symbol::{SymbolMetadata, SymbolParameter},
+    },
+};
+use sqlx::types::Json;
+use uuid::Uuid;
+
+fn method(jar_path: &str, parameter_types: &[&str]) -> ExternalSymbol {
+    ExternalSymbol {
+        id: None,
+        jar_path: jar_path.to_string(),
+        alt_jar_path: None,
+        source_file_path: "com/example/Client.class".to_string(),
+        short_name: "send".to_string(),
+        fully_qualified_name: "com.example.Client#send".to_string(),
+        package_name: "com.example".to_string(),
+        parent_name: Some("com.example.Client".to_string()),
+        symbol_type: "Function".to_string(),
+        file_type: "java".to_string(),
+        modifiers: Json(vec!["public".to_string()]),
+        line_start: 0,
+        line_end: 0,
+        char_start: 0,
+        char_end: 0,
+        ident_line_start: 0,
+        ident_line_end: 0,
+        ident_char_start: 0,
+        ident_char_end: 0,
+        needs_decompilation: true,
+        metadata: Json(SymbolMetadata {
+            parameters: Some(
+                parameter_types
+                    .iter()
+                    .enumerate()
+                    .map(|(i, t)| SymbolParameter {
+                        name: format!("arg{i}"),
+                        type_name: Some(t.to_string()),
+                        default_value: None,
+                    })
+                    .collect(),
+            ),
+            return_type: None,
+            documentation: None,
+            annotations: None,
+            generic_return_type: None,
+            type_params: None,
+            generic_param_types: None,
+            method_type_params: None,
+            constant_value: None,
+            entity_name: None,
+            synthetic_accessor: None,
+        }),
+        last_modified: 0,
+    }
+}
+
+#[tokio::test]
+async fn symbols_are_looked_up_in_the_given_jars_only() {
+    let db_dir = format!("file:{}?mode=memory", Uuid::new_v4());
+    let repo = Arc::new(Repository::new(&db_dir).await.unwrap());
+    repo.insert_external_symbols(&[
+        method("/jars/client-1.0.jar", &["String"]),
+        method("/jars/client-2.0.jar", &["String"]),
+    ])
+    .await
+    .unwrap();
+
+    let found = repo
+        .find_external_symbols_by_fqn_and_jars(
+            "com.example.Client#send",
+            &["/jars/client-2.0.jar".to_string()],
+        )
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].jar_path, "/jars/client-2.0.jar");
+}
+
+#[test]
+fn closest_version_prefers_the_same_overload() {
+    let resolved = method("/jars/client-1.0.jar", &["String", "int"]);
+    let candidates = vec![
+        method("/jars/client-2.0.jar", &["byte[]"]),
+        method("/jars/client-2.0.jar", &["String", "long"]),
+        method("/jars/client-2.0.jar", &["String", "int"]),
+    ];
+
+    let version = closest_version(resolved.clone(), candidates.clone());
+    assert_eq!(version, candidates[2]);
+
+    let version = closest_version(resolved.clone(), candidates[..2].to_vec());
+    assert_eq!(version, candidates[1]);
+
+    assert_eq!(closest_version(resolved.clone(), vec![]), resolved);
+}
@@ -0,0 +1,77 @@
+use std::env;
+
+use pretty_assertions::assert_eq;
+use tower_lsp::{
+    LanguageServer,
+    lsp_types::{
+        CodeLensParams, PartialResultParams, TextDocumentIdentifier, Url, WorkDoneProgressParams,
+    },
+};
+
+use crate::util::get_test_server;
+
+mod util;
+
+#[tokio::test]
+async fn code_lens_counts_implementations_and_overrides() {
+    let server = get_test_server("groovy-gradle-multi").await;
+    let root = env::current_dir().expect("cannot get current dir");
+
+    let params = CodeLensParams {
+        text_document: TextDocumentIdentifier {
+            uri: Url::from_file_path(root.join(
+                "tests/fixtures/groovy-gradle-multi/core/src/main/groovy/com/example/core/DataProcessor.groovy",
+            ))
+            .unwrap(),
+        },
+        work_done_progress_params: WorkDoneProgressParams::default(),
+        partial_result_params: PartialResultParams::default(),
+    };
+    let mut lenses = server.backend.code_lens(params).await.unwrap().unwrap();
+    lenses.sort_by_key(|l| l.range.start.line);
+    assert_eq!(
+        lenses
+            .iter()
+            .map(|l| l.range.start.line)
+            .collect::<Vec<_>>(),
+        vec![4, 7]
+    );
+    assert!(lenses.iter().all(|l| l.command.is_none()));
+
+    let mut titles = Vec::new();
+    for lens in lenses {
+        let resolved = server.backend.code_lens_resolve(lens).await.unwrap();
+        titles.push(resolved.command.unwrap().title);
+    }
+    assert_eq!(titles, vec!["1 implementation", "1 override"]);
+}
+
+/// The lens of a default method counts its overrides and the classes inheriting it
+/// apart.  `LoudGreeter` inherits `FormalGreeter`'s override, not the default.
+#[tokio::test]
+async fn code_lens_labels_default_method_inheritors() {
+    let server = get_test_server("polyglot-spring").await;
+    let root = env::current_dir().expect("cannot get current dir");
+
+    let params = CodeLensParams {
+        text_document: TextDocumentIdentifier {
+            uri: Url::from_file_path(root.join(
+                "tests/fixtures/polyglot-spring/src/main/java/com/example/demo/Greeter.java",
+            ))
+            .unwrap(),
+        },
+        work_done_progress_params: WorkDoneProgressParams::default(),
+        partial_result_params: PartialResultParams::default(),
+    };
+    let lenses = server.backend.code_lens(params).await.unwrap().unwrap();
+    let method = lenses
+        .into_iter()
+        .find(|l| l.range.start.line == 3)
+        .expect("expected a lens on greet");
+
+    let resolved = server.backend.code_lens_resolve(method).await.unwrap();
+    assert_eq!(
+        resolved.command.unwrap().title,
+        "1 override, 1 class inherits the default"
+    );
+}
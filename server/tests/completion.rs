@@ -1,6 +1,6 @@
 use std::env;
 
-use tower_lsp::lsp_types::{CompletionParams, CompletionResponse, DidChangeTextDocumentParams, TextDocumentContentChangeEvent, VersionedTextDocumentIdentifier};
+use tower_lsp::lsp_types::{CompletionParams, CompletionResponse, DidChangeTextDocumentParams, DidOpenTextDocumentParams, TextDocumentContentChangeEvent, TextDocumentItem, VersionedTextDocumentIdentifier};
 use tower_lsp::{
     LanguageServer,
     lsp_types::{
@@ -163,6 +163,41 @@ async fn completion_locals_before_globals() {
     }
 }
 
+// At prefix "testLoc", the enclosing class's own method completes without a qualifier
+// and without an import edit.
+#[tokio::test]
+async fn completion_prefix_includes_enclosing_class_members() {
+    let server = get_test_server("polyglot-spring").await;
+    let root = env::current_dir().expect("cannot get current dir");
+
+    let params = CompletionParams {
+        text_document_position: TextDocumentPositionParams {
+            text_document: TextDocumentIdentifier {
+                uri: Url::from_file_path(root.join(
+                    "tests/fixtures/polyglot-spring/src/main/groovy/com/example/demo/CompletionTest.groovy",
+                ))
+                .expect("cannot parse root URI"),
+            },
+            position: Position::new(26, 15),
+        },
+        context: None,
+        work_done_progress_params: WorkDoneProgressParams::default(),
+        partial_result_params: PartialResultParams::default(),
+    };
+
+    let result = server.backend.completion(params).await.unwrap();
+    match result.unwrap() {
+        CompletionResponse::Array(items) => {
+            let item = items
+                .iter()
+                .find(|i| i.label == "testLocalsBeforeGlobals")
+                .expect("enclosing class method must be in results");
+            assert!(item.additional_text_edits.is_none());
+        }
+        _ => panic!("Invalid completion response"),
+    }
+}
+
 // Closure is in groovy.lang.*, which is an implicit import for Groovy files.
 // Chain completion on a Closure variable must return results without an explicit import.
 #[tokio::test]
@@ -216,3 +251,168 @@ async fn completion_chain_via_implicit_import() {
         _ => panic!("Invalid completion response"),
     }
 }
+
+// UserDirectory#cachedEmail is package-private in com.example, so member completion
+// from another package must only offer the public methods.
+#[tokio::test]
+async fn completion_excludes_package_private_members_from_other_packages() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let uri = Url::parse("file:///tmp/DirectoryUser.java").unwrap();
+    let content = r#"package com.other;
+
+import com.example.UserDirectory;
+
+public class DirectoryUser {
+    public void run(UserDirectory directory) {
+        directory.
+    }
+}
+"#;
+    server
+        .backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "java".to_string(),
+                version: 1,
+                text: content.to_string(),
+            },
+        })
+        .await;
+
+    let params = CompletionParams {
+        text_document_position: TextDocumentPositionParams {
+            text_document: TextDocumentIdentifier { uri },
+            position: Position::new(6, 18),
+        },
+        context: None,
+        work_done_progress_params: WorkDoneProgressParams::default(),
+        partial_result_params: PartialResultParams::default(),
+    };
+
+    let result = server.backend.completion(params).await.unwrap();
+    match result.unwrap() {
+        CompletionResponse::Array(items) => {
+            let labels: Vec<&str> = items.iter().map(|i| i.label.as_str()).collect();
+            assert!(labels.contains(&"findEmail"), "got: {labels:?}");
+            assert!(!labels.contains(&"cachedEmail"), "got: {labels:?}");
+            // Members are detailed with the type declaring them.
+            let find_email = items.iter().find(|i| i.label == "findEmail").unwrap();
+            assert_eq!(find_email.detail.as_deref(), Some("com.example.UserDirectory"));
+        }
+        _ => panic!("Invalid completion response"),
+    }
+}
+
+// ClockFixtures lives in the test source set, so only test code is offered it.
+#[tokio::test]
+async fn completion_hides_test_classes_from_main_sources() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let content = r#"package com.example;
+
+public class FixtureUser {
+    public void run() {
+        ClockFix
+    }
+}
+"#;
+    for (source_set, offered) in [("main", false), ("test", true)] {
+        let uri = Url::parse(&format!(
+            "file:///tmp/app/src/{source_set}/java/com/example/FixtureUser.java"
+        ))
+        .unwrap();
+        server
+            .backend
+            .did_open(DidOpenTextDocumentParams {
+                text_document: TextDocumentItem {
+                    uri: uri.clone(),
+                    language_id: "java".to_string(),
+                    version: 1,
+                    text: content.to_string(),
+                },
+            })
+            .await;
+
+        let params = CompletionParams {
+            text_document_position: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier { uri },
+                position: Position::new(4, 16),
+            },
+            context: None,
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+        };
+
+        let labels: Vec<String> = match server.backend.completion(params).await.unwrap() {
+            Some(CompletionResponse::Array(items)) => items.into_iter().map(|i| i.label).collect(),
+            None => vec![],
+            _ => panic!("Invalid completion response"),
+        };
+        assert_eq!(
+            labels.iter().any(|l| l == "ClockFixtures"),
+            offered,
+            "{source_set}: {labels:?}"
+        );
+    }
+}
+
+// Audited#level is declared as com.example.AuditLevel, so its value completes to
+// the enum's constants, bare after the qualifier and qualified without one.
+#[tokio::test]
+async fn completion_offers_enum_constants_in_annotation_attribute_value() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let uri = Url::parse("file:///tmp/AuditedUsage.java").unwrap();
+    let content = r#"package com.other;
+
+import com.example.Audited;
+
+@Audited(level = AuditLevel.)
+public class AuditedUsage {
+    @Audited(category = "ops", level = )
+    public void run() {}
+}
+"#;
+    server
+        .backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "java".to_string(),
+                version: 1,
+                text: content.to_string(),
+            },
+        })
+        .await;
+
+    for (position, expected) in [
+        (Position::new(4, 28), vec!["BASIC", "FULL"]),
+        (
+            Position::new(6, 39),
+            vec!["AuditLevel.BASIC", "AuditLevel.FULL"],
+        ),
+    ] {
+        let params = CompletionParams {
+            text_document_position: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier { uri: uri.clone() },
+                position,
+            },
+            context: None,
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+        };
+
+        match server.backend.completion(params).await.unwrap() {
+            Some(CompletionResponse::Array(items)) => {
+                let labels: Vec<&str> = items.iter().map(|i| i.label.as_str()).collect();
+                assert_eq!(labels, expected);
+                // Unqualified constants bring the enum's import with them.
+                let imports = items[0].additional_text_edits.is_some();
+                assert_eq!(imports, position.line == 6, "{labels:?}");
+            }
+            _ => panic!("Invalid completion response"),
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use lspintar_server::{
+    db_lock::{IndexLock, lock_path},
+    repo::Repository,
+};
+
+#[test]
+fn index_lock_admits_one_writer_at_a_time() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = lock_path(&dir.path().join("index.db"));
+    assert_eq!(path, dir.path().join("index.lock"));
+
+    let writer = IndexLock::try_acquire(&path).unwrap();
+    assert!(writer.is_some());
+    assert_eq!(IndexLock::holder(&path), Some(std::process::id()));
+
+    // A second instance only gets to read.
+    assert!(IndexLock::try_acquire(&path).unwrap().is_none());
+
+    // Released with its holder, so a later instance takes over.
+    drop(writer);
+    assert!(IndexLock::try_acquire(&path).unwrap().is_some());
+}
+
+#[tokio::test]
+async fn reader_leaves_the_schema_to_the_writer() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("index.db");
+    let path = path.to_string_lossy();
+
+    // Opened before any writer, a reader finds no schema and creates none.
+    let reader = Repository::new_reader(&path).await.unwrap();
+    assert!(reader.find_symbol_by_fqn("com.example.User").await.is_err());
+
+    let writer = Repository::new(&path).await.unwrap();
+    assert_eq!(
+        writer.find_symbol_by_fqn("com.example.User").await.unwrap(),
+        None
+    );
+    assert!(reader.find_symbol_by_fqn("com.example.User").await.is_ok());
+
+    // The reader migrates the database once it takes over.
+    reader.prepare_for_writing().await.unwrap();
+}
@@ -0,0 +1,63 @@
+use std::collections::BTreeMap;
+
+use lspintar_server::dependency_conflict::{
+    VersionConflict, declaration_ranges, version_conflicts,
+};
+use tower_lsp::lsp_types::{Position, Range};
+
+fn coordinates(group: &str, name: &str, version: &str) -> (String, String, String) {
+    (group.to_string(), name.to_string(), version.to_string())
+}
+
+#[test]
+fn artifacts_resolved_to_several_versions_conflict() {
+    let projects = vec![
+        (
+            ":app".to_string(),
+            vec![
+                coordinates("org.slf4j", "slf4j-api", "2.0.9"),
+                coordinates("com.google.guava", "guava", "33.0.0-jre"),
+            ],
+        ),
+        (
+            ":core".to_string(),
+            vec![
+                coordinates("org.slf4j", "slf4j-api", "2.0.9"),
+                coordinates("com.google.guava", "guava", "33.0.0-jre"),
+            ],
+        ),
+        (
+            ":legacy".to_string(),
+            vec![coordinates("org.slf4j", "slf4j-api", "1.7.36")],
+        ),
+    ];
+
+    assert_eq!(
+        version_conflicts(&projects),
+        vec![VersionConflict {
+            artifact: "org.slf4j:slf4j-api".to_string(),
+            versions: BTreeMap::from([
+                ("1.7.36".to_string(), vec![":legacy".to_string()]),
+                (
+                    "2.0.9".to_string(),
+                    vec![":app".to_string(), ":core".to_string()]
+                ),
+            ]),
+        }]
+    );
+}
+
+#[test]
+fn declarations_are_the_quoted_coordinates() {
+    let content = "dependencies {\n    implementation 'org.slf4j:slf4j-api:2.0.9'\n    \
+                   implementation(\"org.slf4j:slf4j-api-extra:1.0\")\n    \
+                   testImplementation \"org.slf4j:slf4j-api\"\n}\n";
+
+    assert_eq!(
+        declaration_ranges(content, "org.slf4j:slf4j-api"),
+        vec![
+            Range::new(Position::new(1, 20), Position::new(1, 45)),
+            Range::new(Position::new(3, 24), Position::new(3, 43)),
+        ]
+    );
+}
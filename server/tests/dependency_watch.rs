@@ -0,0 +1,37 @@
+use std::path::PathBuf;
+
+use lspintar_server::dependency_watch::{DependencySnapshot, modules_with_new_versions};
+
+fn snapshot(modules: &[(&str, &[&str])]) -> DependencySnapshot {
+    modules
+        .iter()
+        .map(|(dir, versions)| {
+            (
+                PathBuf::from(dir),
+                versions.iter().map(|v| v.to_string()).collect(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn only_modules_gaining_a_version_are_reported() {
+    let previous = snapshot(&[
+        ("/cache/org.slf4j/slf4j-api", &["2.0.9"]),
+        (
+            "/cache/com.google.guava/guava",
+            &["32.1.3-jre", "33.0.0-jre"],
+        ),
+    ]);
+    let current = snapshot(&[
+        ("/cache/org.slf4j/slf4j-api", &["2.0.9", "2.0.13"]),
+        ("/cache/com.google.guava/guava", &["33.0.0-jre"]),
+        ("/cache/io.netty/netty-all", &["4.1.100.Final"]),
+    ]);
+
+    assert_eq!(
+        modules_with_new_versions(&previous, &current),
+        vec![PathBuf::from("/cache/org.slf4j/slf4j-api")]
+    );
+    assert!(modules_with_new_versions(&current, &current).is_empty());
+}
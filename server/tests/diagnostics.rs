@@ -11,10 +11,13 @@
 
 use tower_lsp::{
     LanguageServer,
-    lsp_types::{DidOpenTextDocumentParams, TextDocumentItem, Url},
+    lsp_types::{
+        CodeActionContext, CodeActionOrCommand, CodeActionParams, DidOpenTextDocumentParams,
+        Position, TextDocumentIdentifier, TextDocumentItem, Url,
+    },
 };
 
-use crate::util::get_test_server;
+use crate::util::{get_test_server, open_ambiguous_greeter_client};
 
 mod util;
 
@@ -230,3 +233,1807 @@ class FullNotifier : Notifier {
         "expected no unimplemented_abstract_methods when both overloads are implemented, got: {diags:?}"
     );
 }
+
+/// A Java method implementing an interface method without `@Override` gets a
+/// missing_override diagnostic whose quick fix inserts the annotation above the
+/// declaration.  The annotated overload must not be flagged.
+#[tokio::test]
+async fn missing_override_offers_quick_fix() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let uri = Url::parse("file:///tmp/ConsoleNotifier.java").unwrap();
+    let content = r#"package com.example;
+
+public class ConsoleNotifier implements Notifier {
+    public void notify(String message) {
+        System.out.println(message);
+    }
+
+    @Override
+    public void notify(String message, int priority) {
+        System.out.println(message);
+    }
+}
+"#;
+    server
+        .backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "java".to_string(),
+                version: 1,
+                text: content.to_string(),
+            },
+        })
+        .await;
+
+    let diags = server
+        .backend
+        .compute_diagnostics(&uri)
+        .await
+        .expect("compute_diagnostics returned None");
+
+    let missing: Vec<_> = diags
+        .iter()
+        .filter(|d| {
+            d.code
+                == Some(tower_lsp::lsp_types::NumberOrString::String(
+                    "missing_override".to_string(),
+                ))
+        })
+        .cloned()
+        .collect();
+    assert_eq!(missing.len(), 1, "expected one missing_override, got: {diags:?}");
+    assert_eq!(missing[0].range.start.line, 3);
+
+    let actions = server
+        .backend
+        .code_action_impl(CodeActionParams {
+            text_document: TextDocumentIdentifier { uri: uri.clone() },
+            range: missing[0].range,
+            context: CodeActionContext {
+                diagnostics: missing.clone(),
+                ..Default::default()
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+        .await
+        .unwrap()
+        .expect("expected a quick fix");
+
+    let Some(CodeActionOrCommand::CodeAction(action)) = actions.first() else {
+        panic!("expected a code action, got: {actions:?}");
+    };
+    let edits = &action.edit.as_ref().unwrap().changes.as_ref().unwrap()[&uri];
+    assert_eq!(edits.len(), 1);
+    assert_eq!(edits[0].range.start, Position::new(3, 4));
+    assert_eq!(edits[0].new_text, "@Override\n    ");
+}
+
+/// Private members and locals with no references are reported as unused, while
+/// serialization members and `@SuppressWarnings("unused")` declarations are not.
+#[tokio::test]
+async fn unused_private_members_and_locals_reported() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let uri = Url::parse("file:///tmp/UnusedMembers.java").unwrap();
+    let content = r#"package com.example;
+
+public class UnusedMembers implements java.io.Serializable {
+    private static final long serialVersionUID = 1L;
+    private int counter;
+    private String label;
+
+    @SuppressWarnings("unused")
+    private void keep() {}
+
+    private void helper() {}
+
+    public String describe() {
+        int unusedLocal = 42;
+        String result = label;
+        return result;
+    }
+}
+"#;
+    server
+        .backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "java".to_string(),
+                version: 1,
+                text: content.to_string(),
+            },
+        })
+        .await;
+
+    let diags = server
+        .backend
+        .compute_diagnostics(&uri)
+        .await
+        .expect("compute_diagnostics returned None");
+
+    let messages: Vec<String> = diags
+        .iter()
+        .filter(|d| {
+            matches!(
+                &d.code,
+                Some(tower_lsp::lsp_types::NumberOrString::String(c))
+                    if c == "unused_private_member" || c == "unused_local_variable"
+            )
+        })
+        .map(|d| d.message.clone())
+        .collect();
+
+    assert!(messages.iter().any(|m| m.contains("'counter'")), "got: {messages:?}");
+    assert!(messages.iter().any(|m| m.contains("'helper'")), "got: {messages:?}");
+    assert!(messages.iter().any(|m| m.contains("'unusedLocal'")), "got: {messages:?}");
+    for used in ["serialVersionUID", "label", "keep", "result"] {
+        assert!(
+            !messages.iter().any(|m| m.contains(&format!("'{used}'"))),
+            "'{used}' must not be reported, got: {messages:?}"
+        );
+    }
+}
+
+/// Removing an unused local drops a side-effect-free initializer with it, lambdas
+/// included, but keeps a call as an expression statement.  A call nested in a binary
+/// expression is not a statement of its own, so that local gets no fix.
+#[tokio::test]
+async fn unused_local_fix_keeps_side_effects() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let uri = Url::parse("file:///tmp/UnusedInitializers.java").unwrap();
+    let content = r#"package com.example;
+
+public class UnusedInitializers {
+    private String label = "x";
+
+    public void run() {
+        int plain = 42;
+        String called = label.trim();
+        int total = label.length() + 1;
+        Runnable task = () -> label.trim();
+    }
+}
+"#;
+    server
+        .backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "java".to_string(),
+                version: 1,
+                text: content.to_string(),
+            },
+        })
+        .await;
+
+    let diags = server
+        .backend
+        .compute_diagnostics(&uri)
+        .await
+        .expect("compute_diagnostics returned None");
+    let unused: Vec<_> = diags
+        .into_iter()
+        .filter(|d| {
+            d.code
+                == Some(tower_lsp::lsp_types::NumberOrString::String(
+                    "unused_local_variable".to_string(),
+                ))
+        })
+        .collect();
+    assert_eq!(unused.len(), 4, "got: {unused:?}");
+
+    let actions: Vec<tower_lsp::lsp_types::CodeAction> = server
+        .backend
+        .code_action_impl(CodeActionParams {
+            text_document: TextDocumentIdentifier { uri: uri.clone() },
+            range: tower_lsp::lsp_types::Range::new(Position::new(6, 0), Position::new(10, 0)),
+            context: CodeActionContext {
+                diagnostics: unused,
+                only: Some(vec![tower_lsp::lsp_types::CodeActionKind::QUICKFIX]),
+                ..Default::default()
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+        .await
+        .unwrap()
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|a| match a {
+            CodeActionOrCommand::CodeAction(a) => Some(a),
+            _ => None,
+        })
+        .collect();
+    let edits = |title: &str| {
+        let action = actions
+            .iter()
+            .find(|a| a.title == title)
+            .unwrap_or_else(|| panic!("expected '{title}', got: {actions:?}"));
+        action.edit.as_ref().unwrap().changes.as_ref().unwrap()[&uri].clone()
+    };
+
+    let removed = edits("Remove unused 'plain'");
+    assert_eq!(removed.len(), 1);
+    assert_eq!(removed[0].new_text, "");
+
+    let kept = edits("Remove unused 'called', keeping its initializer");
+    assert_eq!(kept.len(), 1);
+    assert_eq!(
+        kept[0].range,
+        tower_lsp::lsp_types::Range::new(Position::new(7, 8), Position::new(7, 37))
+    );
+    assert_eq!(kept[0].new_text, "label.trim();");
+
+    let lambda = edits("Remove unused 'task'");
+    assert_eq!(lambda.len(), 1);
+    assert_eq!(lambda[0].new_text, "");
+
+    assert!(
+        !actions.iter().any(|a| a.title.contains("'total'")),
+        "got: {actions:?}"
+    );
+}
+
+/// Dereferencing the result of a Java `@Nullable` method with `.` from Kotlin is
+/// reported, while safe calls, null-checked locals and unannotated methods are not.
+#[tokio::test]
+async fn platform_null_dereference_reported() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let uri = Url::parse("file:///tmp/EmailPrinter.kt").unwrap();
+    let content = r#"package com.example
+
+class EmailPrinter(private val directory: UserDirectory) {
+    fun print(name: String) {
+        val length = directory.findEmail(name).length
+        val email = directory.findEmail(name)
+        println(email.length + length)
+        val checked = directory.findEmail(name)
+        if (checked != null) {
+            println(checked.length)
+        }
+        println(directory.findEmail(name)?.length)
+        println(directory.displayName(name).length)
+    }
+}
+"#;
+    server
+        .backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "kotlin".to_string(),
+                version: 1,
+                text: content.to_string(),
+            },
+        })
+        .await;
+
+    let diags = server
+        .backend
+        .compute_diagnostics(&uri)
+        .await
+        .expect("compute_diagnostics returned None");
+
+    let mut lines: Vec<u32> = diags
+        .iter()
+        .filter(|d| {
+            d.code
+                == Some(tower_lsp::lsp_types::NumberOrString::String(
+                    "platform_null_dereference".to_string(),
+                ))
+        })
+        .map(|d| d.range.start.line)
+        .collect();
+    lines.sort();
+    assert_eq!(lines, vec![4, 6], "got: {diags:?}");
+}
+
+/// An unresolved type name gets an add-import quick fix for the indexed class
+/// with that short name.
+#[tokio::test]
+async fn unresolved_symbol_offers_import_quick_fix() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let uri = Url::parse("file:///tmp/DirectoryClient.java").unwrap();
+    let content = r#"package com.other;
+
+public class DirectoryClient {
+    private UserDirectory directory;
+}
+"#;
+    server
+        .backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "java".to_string(),
+                version: 1,
+                text: content.to_string(),
+            },
+        })
+        .await;
+
+    let diags = server
+        .backend
+        .compute_diagnostics(&uri)
+        .await
+        .expect("compute_diagnostics returned None");
+
+    let unresolved: Vec<_> = diags
+        .iter()
+        .filter(|d| {
+            d.code
+                == Some(tower_lsp::lsp_types::NumberOrString::String(
+                    "unresolved_symbol".to_string(),
+                ))
+        })
+        .cloned()
+        .collect();
+    assert_eq!(unresolved.len(), 1, "got: {diags:?}");
+
+    let actions = server
+        .backend
+        .code_action_impl(CodeActionParams {
+            text_document: TextDocumentIdentifier { uri: uri.clone() },
+            range: unresolved[0].range,
+            context: CodeActionContext {
+                diagnostics: unresolved.clone(),
+                ..Default::default()
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+        .await
+        .unwrap()
+        .expect("expected an import quick fix");
+
+    let Some(CodeActionOrCommand::CodeAction(action)) = actions.first() else {
+        panic!("expected a code action, got: {actions:?}");
+    };
+    assert_eq!(action.title, "Import 'com.example.UserDirectory'");
+    let edits = &action.edit.as_ref().unwrap().changes.as_ref().unwrap()[&uri];
+    assert_eq!(edits[0].range.start, Position::new(1, 0));
+    assert_eq!(edits[0].new_text, "import com.example.UserDirectory;\n");
+}
+
+/// ClockFixtures is declared in the test source set, so only test code is offered
+/// its import.
+#[tokio::test]
+async fn import_quick_fix_skips_test_classes_in_main_sources() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let content = r#"package com.other;
+
+public class ClockClient {
+    private ClockFixtures clocks;
+}
+"#;
+    for (source_set, offered) in [("main", false), ("test", true)] {
+        let uri = Url::parse(&format!(
+            "file:///tmp/app/src/{source_set}/java/com/other/ClockClient.java"
+        ))
+        .unwrap();
+        server
+            .backend
+            .did_open(DidOpenTextDocumentParams {
+                text_document: TextDocumentItem {
+                    uri: uri.clone(),
+                    language_id: "java".to_string(),
+                    version: 1,
+                    text: content.to_string(),
+                },
+            })
+            .await;
+
+        let diags = server
+            .backend
+            .compute_diagnostics(&uri)
+            .await
+            .expect("compute_diagnostics returned None");
+        let unresolved: Vec<_> = diags
+            .iter()
+            .filter(|d| has_code(std::slice::from_ref(*d), "unresolved_symbol"))
+            .cloned()
+            .collect();
+        assert_eq!(unresolved.len(), 1, "{source_set}: {diags:?}");
+
+        let titles: Vec<String> = server
+            .backend
+            .code_action_impl(CodeActionParams {
+                text_document: TextDocumentIdentifier { uri: uri.clone() },
+                range: unresolved[0].range,
+                context: CodeActionContext {
+                    diagnostics: unresolved.clone(),
+                    ..Default::default()
+                },
+                work_done_progress_params: Default::default(),
+                partial_result_params: Default::default(),
+            })
+            .await
+            .unwrap()
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|a| match a {
+                CodeActionOrCommand::CodeAction(action) => Some(action.title),
+                CodeActionOrCommand::Command(_) => None,
+            })
+            .collect();
+        let imports = titles.contains(&"Import 'com.example.ClockFixtures'".to_string());
+        assert_eq!(imports, offered, "{source_set}: {titles:?}");
+    }
+}
+
+/// Each unimplemented method carries a fix inserting a stub with the interface's
+/// signature, and the code action also offers to implement all of them.
+#[tokio::test]
+async fn unimplemented_methods_fixed_with_stubs() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let uri = Url::parse("file:///tmp/QuietNotifier.java").unwrap();
+    let content = r#"package com.example;
+
+public class QuietNotifier implements Notifier {
+}
+"#;
+    server
+        .backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "java".to_string(),
+                version: 1,
+                text: content.to_string(),
+            },
+        })
+        .await;
+
+    let diags = server
+        .backend
+        .compute_diagnostics(&uri)
+        .await
+        .expect("compute_diagnostics returned None");
+    let missing: Vec<_> = diags
+        .iter()
+        .filter(|d| {
+            d.code
+                == Some(tower_lsp::lsp_types::NumberOrString::String(
+                    "unimplemented_abstract_methods".to_string(),
+                ))
+        })
+        .cloned()
+        .collect();
+    assert_eq!(missing.len(), 2, "got: {diags:?}");
+
+    let actions = server
+        .backend
+        .code_action_impl(CodeActionParams {
+            text_document: TextDocumentIdentifier { uri: uri.clone() },
+            range: missing[0].range,
+            context: CodeActionContext {
+                diagnostics: missing.clone(),
+                ..Default::default()
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+        .await
+        .unwrap()
+        .expect("expected implement quick fixes");
+
+    let action = |title: &str| {
+        actions
+            .iter()
+            .find_map(|a| match a {
+                CodeActionOrCommand::CodeAction(a) if a.title == title => Some(a.clone()),
+                _ => None,
+            })
+            .unwrap_or_else(|| panic!("expected '{title}', got: {actions:?}"))
+    };
+    let single = action("Implement 'notify(String, int)'");
+    let edits = &single.edit.as_ref().unwrap().changes.as_ref().unwrap()[&uri];
+    assert_eq!(edits[0].range.start, Position::new(3, 0));
+    assert_eq!(
+        edits[0].new_text,
+        "\n    @Override\n    public void notify(String message, int priority) {\n    }\n"
+    );
+
+    let all = action("Implement all 2 missing members");
+    assert_eq!(
+        all.edit.as_ref().unwrap().changes.as_ref().unwrap()[&uri].len(),
+        2
+    );
+}
+
+/// Inside a class body, each inherited method the class does not override yet is
+/// offered once, from its nearest declaration, with a stub calling `super`.
+#[tokio::test]
+async fn override_actions_offered_inside_class_body() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let uri = Url::parse("file:///tmp/PoliteGreeter.java").unwrap();
+    let content = r#"package com.example;
+
+public class PoliteGreeter extends FormalGreeter {
+}
+"#;
+    server
+        .backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "java".to_string(),
+                version: 1,
+                text: content.to_string(),
+            },
+        })
+        .await;
+
+    let cursor = Position::new(3, 0);
+    let actions = server
+        .backend
+        .code_action_impl(CodeActionParams {
+            text_document: TextDocumentIdentifier { uri: uri.clone() },
+            range: tower_lsp::lsp_types::Range::new(cursor, cursor),
+            context: CodeActionContext {
+                only: Some(vec![tower_lsp::lsp_types::CodeActionKind::SOURCE]),
+                ..Default::default()
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+        .await
+        .unwrap()
+        .expect("expected override actions");
+
+    let greets: Vec<_> = actions
+        .iter()
+        .filter_map(|a| match a {
+            CodeActionOrCommand::CodeAction(a) if a.title == "Override 'greet(String)'" => Some(a),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(greets.len(), 1, "got: {actions:?}");
+    let edits = &greets[0].edit.as_ref().unwrap().changes.as_ref().unwrap()[&uri];
+    assert_eq!(edits[0].range.start, Position::new(3, 0));
+    assert_eq!(
+        edits[0].new_text,
+        "\n    @Override\n    public String greet(String name) {\n        return super.greet(name);\n    }\n"
+    );
+
+    // A lightbulb request, with no kinds asked for, leaves them out.
+    let lightbulb = server
+        .backend
+        .code_action_impl(CodeActionParams {
+            text_document: TextDocumentIdentifier { uri: uri.clone() },
+            range: tower_lsp::lsp_types::Range::new(cursor, cursor),
+            context: CodeActionContext::default(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+        .await
+        .unwrap()
+        .unwrap_or_default();
+    assert!(
+        lightbulb.iter().all(|a| !matches!(
+            a,
+            CodeActionOrCommand::CodeAction(a) if a.title.starts_with("Override")
+        )),
+        "got: {lightbulb:?}"
+    );
+}
+
+/// An interface's default method is called through the interface, since `super`
+/// alone does not reach it.
+#[tokio::test]
+async fn override_actions_qualify_interface_super_calls() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let uri = Url::parse("file:///tmp/WarmGreeter.java").unwrap();
+    let content = r#"package com.example;
+
+public class WarmGreeter implements Greeter {
+}
+"#;
+    server
+        .backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "java".to_string(),
+                version: 1,
+                text: content.to_string(),
+            },
+        })
+        .await;
+
+    let cursor = Position::new(3, 0);
+    let actions = server
+        .backend
+        .code_action_impl(CodeActionParams {
+            text_document: TextDocumentIdentifier { uri: uri.clone() },
+            range: tower_lsp::lsp_types::Range::new(cursor, cursor),
+            context: CodeActionContext {
+                only: Some(vec![tower_lsp::lsp_types::CodeActionKind::SOURCE]),
+                ..Default::default()
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+        .await
+        .unwrap()
+        .expect("expected override actions");
+
+    let greet = actions
+        .iter()
+        .find_map(|a| match a {
+            CodeActionOrCommand::CodeAction(a) if a.title == "Override 'greet(String)'" => Some(a),
+            _ => None,
+        })
+        .expect("expected an override of greet");
+    let edits = &greet.edit.as_ref().unwrap().changes.as_ref().unwrap()[&uri];
+    assert_eq!(
+        edits[0].new_text,
+        "\n    @Override\n    public String greet(String name) {\n        return Greeter.super.greet(name);\n    }\n"
+    );
+}
+
+/// The name under the cursor is only looked up for imports inside an
+/// `unresolved_*` diagnostic: none is offered without one, and one whose text is
+/// not the name (here the whole declaration) still gets the name's import.
+#[tokio::test]
+async fn import_quick_fix_for_name_under_cursor_needs_unresolved_diagnostic() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let uri = Url::parse("file:///tmp/DirectoryCaller.java").unwrap();
+    let content = r#"package com.other;
+
+public class DirectoryCaller {
+    private UserDirectory directory;
+}
+"#;
+    server
+        .backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "java".to_string(),
+                version: 1,
+                text: content.to_string(),
+            },
+        })
+        .await;
+
+    let cursor = Position::new(3, 14);
+    let declaration = tower_lsp::lsp_types::Diagnostic {
+        range: tower_lsp::lsp_types::Range::new(Position::new(3, 4), Position::new(3, 36)),
+        code: Some(tower_lsp::lsp_types::NumberOrString::String(
+            "unresolved_symbol".to_string(),
+        )),
+        source: Some("lspintar".to_string()),
+        message: "Cannot resolve symbol".to_string(),
+        ..Default::default()
+    };
+    for (diagnostics, offered) in [(vec![], false), (vec![declaration], true)] {
+        let titles: Vec<String> = server
+            .backend
+            .code_action_impl(CodeActionParams {
+                text_document: TextDocumentIdentifier { uri: uri.clone() },
+                range: tower_lsp::lsp_types::Range::new(cursor, cursor),
+                context: CodeActionContext {
+                    diagnostics,
+                    ..Default::default()
+                },
+                work_done_progress_params: Default::default(),
+                partial_result_params: Default::default(),
+            })
+            .await
+            .unwrap()
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|a| match a {
+                CodeActionOrCommand::CodeAction(action) => Some(action.title),
+                CodeActionOrCommand::Command(_) => None,
+            })
+            .collect();
+        let imports = titles.contains(&"Import 'com.example.UserDirectory'".to_string());
+        assert_eq!(imports, offered, "got: {titles:?}");
+    }
+}
+
+/// Entity names in JPQL query strings must name an `@Entity` class, by its simple
+/// name or the one given in `@Entity(name = ...)`; the misspelled one is reported
+/// at its position inside the string.
+#[tokio::test]
+async fn unresolved_jpql_entity_reported() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let uri = Url::parse("file:///tmp/CustomerFinder.java").unwrap();
+    let content = r#"package com.example;
+
+import org.springframework.data.jpa.repository.Query;
+
+interface CustomerFinder {
+    @Query("SELECT c FROM Customer c")
+    Object all();
+
+    @Query("SELECT c FROM Custmer c")
+    Object misspelled();
+
+    @Query("SELECT p FROM PurchaseOrder p")
+    Object renamed();
+
+    @Query(value = "SELECT * FROM customers", nativeQuery = true)
+    Object rows();
+}
+"#;
+    server
+        .backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "java".to_string(),
+                version: 1,
+                text: content.to_string(),
+            },
+        })
+        .await;
+
+    let diags = server
+        .backend
+        .compute_diagnostics(&uri)
+        .await
+        .expect("compute_diagnostics returned None");
+
+    let unresolved: Vec<_> = diags
+        .iter()
+        .filter(|d| {
+            d.code
+                == Some(tower_lsp::lsp_types::NumberOrString::String(
+                    "unresolved_entity".to_string(),
+                ))
+        })
+        .collect();
+    assert_eq!(unresolved.len(), 1, "got: {diags:?}");
+    assert_eq!(unresolved[0].range.start, Position::new(8, 26));
+    assert_eq!(unresolved[0].message, "Cannot resolve entity 'Custmer'");
+}
+
+/// `mappedBy` must name a field of the entity on the other side of the relationship,
+/// its own or one inherited from a `@MappedSuperclass`.
+#[tokio::test]
+async fn unresolved_mapped_by_reported() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let uri = Url::parse("file:///tmp/Store.java").unwrap();
+    let content = r#"package com.example;
+
+import java.util.List;
+import jakarta.persistence.Entity;
+import jakarta.persistence.OneToMany;
+
+@Entity
+class Store {
+    @OneToMany(mappedBy = "shop")
+    private List<Purchase> purchases;
+
+    @OneToMany(mappedBy = "store")
+    private List<Purchase> misnamed;
+
+    @OneToMany(mappedBy = "placedBy")
+    private List<Purchase> inherited;
+}
+"#;
+    server
+        .backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "java".to_string(),
+                version: 1,
+                text: content.to_string(),
+            },
+        })
+        .await;
+
+    let diags = server
+        .backend
+        .compute_diagnostics(&uri)
+        .await
+        .expect("compute_diagnostics returned None");
+
+    let unresolved: Vec<_> = diags
+        .iter()
+        .filter(|d| {
+            d.code
+                == Some(tower_lsp::lsp_types::NumberOrString::String(
+                    "unresolved_mapped_by".to_string(),
+                ))
+        })
+        .collect();
+    assert_eq!(unresolved.len(), 1, "got: {diags:?}");
+    assert_eq!(unresolved[0].range.start, Position::new(11, 27));
+    assert_eq!(
+        unresolved[0].message,
+        "Cannot resolve field 'store' in 'Purchase'"
+    );
+}
+
+/// Annotation attribute values must match the element types of the annotation.
+#[tokio::test]
+async fn annotation_attribute_type_mismatch_reported() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let uri = Url::parse("file:///tmp/Ledger.java").unwrap();
+    let content = r#"package com.example;
+
+@Audited(level = AuditLevel.FULL, exclude = {String.class, Integer.class}, category = "billing")
+class Ledger {
+    @Audited(level = "FULL", exclude = {String.class, "Integer"}, category = AuditLevel.BASIC)
+    void post() {}
+}
+"#;
+    server
+        .backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "java".to_string(),
+                version: 1,
+                text: content.to_string(),
+            },
+        })
+        .await;
+
+    let diags = server
+        .backend
+        .compute_diagnostics(&uri)
+        .await
+        .expect("compute_diagnostics returned None");
+
+    let mismatches: Vec<_> = diags
+        .iter()
+        .filter(|d| {
+            d.code
+                == Some(tower_lsp::lsp_types::NumberOrString::String(
+                    "annotation_attribute_type_mismatch".to_string(),
+                ))
+        })
+        .map(|d| (d.range.start, d.message.as_str()))
+        .collect();
+    assert_eq!(
+        mismatches,
+        vec![
+            (
+                Position::new(4, 21),
+                "Incompatible value for 'level': expected AuditLevel, found a string"
+            ),
+            (
+                Position::new(4, 54),
+                "Incompatible value for 'exclude': expected Class<?>[], found a string"
+            ),
+            (
+                Position::new(4, 77),
+                "Incompatible value for 'category': expected String, found a constant of AuditLevel"
+            ),
+        ],
+        "got: {diags:?}"
+    );
+}
+
+/// Braces are a closure in Groovy, as in Spock's `@IgnoreIf({ ... })`, not an array.
+#[tokio::test]
+async fn groovy_closure_attribute_is_not_an_array() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let uri = Url::parse("file:///tmp/LedgerSpec.groovy").unwrap();
+    let content = r#"package com.example
+
+@Audited(exclude = { true }, level = 'FULL')
+class LedgerSpec {
+}
+"#;
+    server
+        .backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "groovy".to_string(),
+                version: 1,
+                text: content.to_string(),
+            },
+        })
+        .await;
+
+    let diags = server
+        .backend
+        .compute_diagnostics(&uri)
+        .await
+        .expect("compute_diagnostics returned None");
+
+    let mismatches: Vec<_> = diags
+        .iter()
+        .filter(|d| {
+            d.code
+                == Some(tower_lsp::lsp_types::NumberOrString::String(
+                    "annotation_attribute_type_mismatch".to_string(),
+                ))
+        })
+        .map(|d| (d.range.start, d.message.as_str()))
+        .collect();
+    assert_eq!(
+        mismatches,
+        vec![(
+            Position::new(2, 37),
+            "Incompatible value for 'level': expected AuditLevel, found a string"
+        )],
+        "got: {diags:?}"
+    );
+}
+
+/// Every indexed file gets a workspace report, and reports the client already holds
+/// come back unchanged.
+#[tokio::test]
+async fn workspace_diagnostics_report_unchanged_files() {
+    use tower_lsp::lsp_types::{
+        PreviousResultId, WorkspaceDiagnosticParams, WorkspaceDiagnosticReportResult,
+        WorkspaceDocumentDiagnosticReport,
+    };
+
+    let server = get_test_server("groovy-gradle-multi").await;
+    let params = |previous_result_ids| WorkspaceDiagnosticParams {
+        identifier: None,
+        previous_result_ids,
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    };
+
+    let WorkspaceDiagnosticReportResult::Report(first) = server
+        .backend
+        .workspace_diagnostic(params(vec![]))
+        .await
+        .unwrap()
+    else {
+        panic!("expected a full workspace report");
+    };
+    assert!(!first.items.is_empty(), "indexed files should be reported");
+
+    let previous: Vec<PreviousResultId> = first
+        .items
+        .iter()
+        .map(|item| match item {
+            WorkspaceDocumentDiagnosticReport::Full(r) => PreviousResultId {
+                uri: r.uri.clone(),
+                value: r.full_document_diagnostic_report.result_id.clone().unwrap(),
+            },
+            WorkspaceDocumentDiagnosticReport::Unchanged(_) => {
+                panic!("nothing was reported before")
+            }
+        })
+        .collect();
+
+    let WorkspaceDiagnosticReportResult::Report(second) = server
+        .backend
+        .workspace_diagnostic(params(previous))
+        .await
+        .unwrap()
+    else {
+        panic!("expected a full workspace report");
+    };
+    assert_eq!(second.items.len(), first.items.len());
+    assert!(
+        second
+            .items
+            .iter()
+            .all(|item| matches!(item, WorkspaceDocumentDiagnosticReport::Unchanged(_)))
+    );
+}
+
+/// Two files of one source set declaring the same class each get a duplicate_class
+/// diagnostic pointing at the other.
+#[tokio::test]
+async fn duplicate_class_reported_on_both_files() {
+    use tower_lsp::lsp_types::{DidSaveTextDocumentParams, Location};
+
+    let server = get_test_server("polyglot-spring").await;
+    let demo = std::env::current_dir()
+        .unwrap()
+        .join("tests/fixtures/polyglot-spring/src/main/java/com/example/demo");
+    let files = [demo.join("TwinA.java"), demo.join("TwinB.java")];
+    let uris: Vec<Url> = files
+        .iter()
+        .map(|f| Url::from_file_path(f).unwrap())
+        .collect();
+
+    for (file, uri) in files.iter().zip(&uris) {
+        std::fs::write(file, "package com.example;\n\npublic class Twin {\n}\n").unwrap();
+        server
+            .backend
+            .did_save(DidSaveTextDocumentParams {
+                text_document: TextDocumentIdentifier { uri: uri.clone() },
+                text: None,
+            })
+            .await;
+    }
+    let mut related = Vec::new();
+    for uri in &uris {
+        let diags = server.backend.compute_diagnostics(uri).await.unwrap();
+        let duplicate = diags
+            .iter()
+            .find(|d| {
+                d.code
+                    == Some(tower_lsp::lsp_types::NumberOrString::String(
+                        "duplicate_class".to_string(),
+                    ))
+            })
+            .cloned();
+        related.push(duplicate.and_then(|d| d.related_information));
+    }
+
+    for file in &files {
+        std::fs::remove_file(file).unwrap();
+    }
+
+    let twin_at = |uri: &Url| {
+        Location::new(
+            uri.clone(),
+            tower_lsp::lsp_types::Range::new(Position::new(2, 13), Position::new(2, 17)),
+        )
+    };
+    let locations: Vec<Option<Vec<Location>>> = related
+        .into_iter()
+        .map(|r| r.map(|infos| infos.into_iter().map(|i| i.location).collect()))
+        .collect();
+    assert_eq!(
+        locations,
+        vec![Some(vec![twin_at(&uris[1])]), Some(vec![twin_at(&uris[0])])]
+    );
+}
+
+/// The polyglot-spring Java sources declare `com.example` but live under
+/// `com/example/demo`; the fix rewrites the declaration to the directory's package.
+#[tokio::test]
+async fn package_mismatch_offers_rewrite_quick_fix() {
+    let server = get_test_server("polyglot-spring").await;
+    let path = std::env::current_dir()
+        .unwrap()
+        .join("tests/fixtures/polyglot-spring/src/main/java/com/example/demo/AuditLevel.java");
+    let uri = Url::from_file_path(&path).unwrap();
+
+    let diags = server.backend.compute_diagnostics(&uri).await.unwrap();
+    let mismatch: Vec<_> = diags
+        .iter()
+        .filter(|d| {
+            d.code
+                == Some(tower_lsp::lsp_types::NumberOrString::String(
+                    "package_mismatch".to_string(),
+                ))
+        })
+        .cloned()
+        .collect();
+    assert_eq!(mismatch.len(), 1, "got: {diags:?}");
+    assert_eq!(
+        mismatch[0].range,
+        tower_lsp::lsp_types::Range::new(Position::new(0, 8), Position::new(0, 19))
+    );
+
+    let actions = server
+        .backend
+        .code_action_impl(CodeActionParams {
+            text_document: TextDocumentIdentifier { uri: uri.clone() },
+            range: mismatch[0].range,
+            context: CodeActionContext {
+                diagnostics: mismatch.clone(),
+                ..Default::default()
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+        .await
+        .unwrap()
+        .expect("expected a package quick fix");
+
+    // The client advertised no file rename support, so only the rewrite is offered.
+    assert_eq!(actions.len(), 1, "got: {actions:?}");
+    let CodeActionOrCommand::CodeAction(action) = &actions[0] else {
+        panic!("expected a code action, got: {actions:?}");
+    };
+    assert_eq!(action.title, "Set package to 'com.example.demo'");
+    let edits = &action.edit.as_ref().unwrap().changes.as_ref().unwrap()[&uri];
+    assert_eq!(edits[0].range, mismatch[0].range);
+    assert_eq!(edits[0].new_text, "com.example.demo");
+}
+
+/// `Greeter` exists in both wildcard-imported packages: the reference is flagged and
+/// each candidate is offered as an explicit import.
+#[tokio::test]
+async fn ambiguous_wildcard_import_offers_explicit_imports() {
+    let server = get_test_server("polyglot-spring").await;
+    let uri = open_ambiguous_greeter_client(&server).await;
+
+    let diags = server
+        .backend
+        .compute_diagnostics(&uri)
+        .await
+        .expect("compute_diagnostics returned None");
+    let ambiguous: Vec<_> = diags
+        .iter()
+        .filter(|d| {
+            d.code
+                == Some(tower_lsp::lsp_types::NumberOrString::String(
+                    "ambiguous_import".to_string(),
+                ))
+        })
+        .cloned()
+        .collect();
+    assert_eq!(ambiguous.len(), 1, "got: {diags:?}");
+    assert!(!has_code(&diags, "unresolved_symbol"), "got: {diags:?}");
+
+    let actions = server
+        .backend
+        .code_action_impl(CodeActionParams {
+            text_document: TextDocumentIdentifier { uri: uri.clone() },
+            range: ambiguous[0].range,
+            context: CodeActionContext {
+                diagnostics: ambiguous.clone(),
+                ..Default::default()
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+        .await
+        .unwrap()
+        .expect("expected import quick fixes");
+
+    let titles: Vec<_> = actions
+        .iter()
+        .filter_map(|a| match a {
+            CodeActionOrCommand::CodeAction(action) => Some(action.title.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        titles,
+        vec!["Import 'com.example.Greeter'", "Import 'com.other.Greeter'"]
+    );
+    let CodeActionOrCommand::CodeAction(action) = &actions[1] else {
+        unreachable!();
+    };
+    let edits = &action.edit.as_ref().unwrap().changes.as_ref().unwrap()[&uri];
+    assert_eq!(edits[0].range.start, Position::new(4, 0));
+    assert_eq!(edits[0].new_text, "import com.other.Greeter;\n");
+}
+
+/// `lspintar:disable` comments and `@SuppressWarnings("lspintar:...")` silence a
+/// rule in their scope, and each remaining diagnostic offers to insert them.
+#[tokio::test]
+async fn suppression_directives_silence_diagnostics() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let uri = Url::parse("file:///tmp/Suppressed.java").unwrap();
+    let content = r#"package com.other;
+
+public class Suppressed {
+    private MissingOne first; // lspintar:disable unresolved_symbol
+    // lspintar:disable unresolved_symbol
+    private MissingTwo second;
+    private MissingThree third;
+
+    @SuppressWarnings("lspintar:unresolved_symbol")
+    MissingFour run() {
+        return null;
+    }
+}
+"#;
+    server
+        .backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "java".to_string(),
+                version: 1,
+                text: content.to_string(),
+            },
+        })
+        .await;
+
+    let diags = server
+        .backend
+        .compute_diagnostics(&uri)
+        .await
+        .expect("compute_diagnostics returned None");
+    let unresolved: Vec<_> = diags
+        .iter()
+        .filter(|d| {
+            d.code
+                == Some(tower_lsp::lsp_types::NumberOrString::String(
+                    "unresolved_symbol".to_string(),
+                ))
+        })
+        .cloned()
+        .collect();
+    assert_eq!(unresolved.len(), 1, "got: {diags:?}");
+    assert_eq!(unresolved[0].range.start.line, 6);
+
+    let actions = server
+        .backend
+        .code_action_impl(CodeActionParams {
+            text_document: TextDocumentIdentifier { uri: uri.clone() },
+            range: unresolved[0].range,
+            context: CodeActionContext {
+                diagnostics: unresolved.clone(),
+                ..Default::default()
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+        .await
+        .unwrap()
+        .expect("expected suppression quick fixes");
+    let edit = |title: &str| {
+        actions
+            .iter()
+            .find_map(|a| match a {
+                CodeActionOrCommand::CodeAction(a) if a.title == title => {
+                    Some(a.edit.as_ref().unwrap().changes.as_ref().unwrap()[&uri][0].clone())
+                }
+                _ => None,
+            })
+            .unwrap_or_else(|| panic!("expected '{title}', got: {actions:?}"))
+    };
+
+    let line = edit("Suppress 'unresolved_symbol' on this line");
+    assert_eq!(line.range.start, Position::new(6, 31));
+    assert_eq!(line.new_text, " // lspintar:disable unresolved_symbol");
+    let file = edit("Suppress 'unresolved_symbol' in this file");
+    assert_eq!(file.range.start, Position::new(0, 0));
+    assert_eq!(file.new_text, "// lspintar:disable unresolved_symbol\n");
+}
+
+/// On a Java field, accessor generation skips the accessors the class declares,
+/// setters of final fields and names booleans' getters `is...`.
+#[tokio::test]
+async fn accessors_generated_for_java_fields() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let uri = Url::parse("file:///tmp/Account.java").unwrap();
+    let content = r#"package com.example;
+
+public class Account {
+    private String owner;
+    private final long id = 1;
+    private boolean active;
+
+    public String getOwner() {
+        return owner;
+    }
+}
+"#;
+    server
+        .backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "java".to_string(),
+                version: 1,
+                text: content.to_string(),
+            },
+        })
+        .await;
+
+    let actions_at = |line: u32| {
+        let backend = &server.backend;
+        let uri = uri.clone();
+        async move {
+            let cursor = Position::new(line, 20);
+            backend
+                .code_action_impl(CodeActionParams {
+                    text_document: TextDocumentIdentifier { uri },
+                    range: tower_lsp::lsp_types::Range::new(cursor, cursor),
+                    context: CodeActionContext {
+                        only: Some(vec![tower_lsp::lsp_types::CodeActionKind::SOURCE]),
+                        ..Default::default()
+                    },
+                    work_done_progress_params: Default::default(),
+                    partial_result_params: Default::default(),
+                })
+                .await
+                .unwrap()
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|a| match a {
+                    CodeActionOrCommand::CodeAction(a) => Some(a),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        }
+    };
+    let titles = |actions: &[tower_lsp::lsp_types::CodeAction]| {
+        actions
+            .iter()
+            .map(|a| a.title.clone())
+            .filter(|t| t.starts_with("Generate"))
+            .collect::<Vec<_>>()
+    };
+
+    let owner = actions_at(3).await;
+    assert_eq!(
+        titles(&owner),
+        vec![
+            "Generate setter for 'owner'",
+            "Generate fluent setter for 'owner'"
+        ]
+    );
+    let setter = &owner[0].edit.as_ref().unwrap().changes.as_ref().unwrap()[&uri][0];
+    assert_eq!(setter.range.start, Position::new(10, 0));
+    assert_eq!(
+        setter.new_text,
+        "\n    public void setOwner(String owner) {\n        this.owner = owner;\n    }\n"
+    );
+
+    assert_eq!(
+        titles(&actions_at(4).await),
+        vec!["Generate getter for 'id'"]
+    );
+
+    let active = actions_at(5).await;
+    let both = active
+        .iter()
+        .find(|a| a.title == "Generate getter and setter for 'active'")
+        .expect("expected getter and setter");
+    let text = &both.edit.as_ref().unwrap().changes.as_ref().unwrap()[&uri][0].new_text;
+    assert!(text.contains("public boolean isActive() {"), "{text}");
+    assert!(
+        text.contains("public void setActive(boolean active) {"),
+        "{text}"
+    );
+}
+
+#[tokio::test]
+async fn explicit_type_replaces_groovy_def() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let uri = Url::parse("file:///tmp/Greeting.groovy").unwrap();
+    let content = r#"package com.example
+
+class Greeting {
+    void run() {
+        def message = "hello"
+        def count = 42
+        def a = 1, b = 2
+        println(message + count)
+    }
+}
+"#;
+    server
+        .backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "groovy".to_string(),
+                version: 1,
+                text: content.to_string(),
+            },
+        })
+        .await;
+
+    let declare_at = |line: u32| {
+        let backend = &server.backend;
+        let uri = uri.clone();
+        async move {
+            let cursor = Position::new(line, 9);
+            backend
+                .code_action_impl(CodeActionParams {
+                    text_document: TextDocumentIdentifier { uri },
+                    range: tower_lsp::lsp_types::Range::new(cursor, cursor),
+                    context: CodeActionContext {
+                        only: Some(vec![tower_lsp::lsp_types::CodeActionKind::QUICKFIX]),
+                        ..Default::default()
+                    },
+                    work_done_progress_params: Default::default(),
+                    partial_result_params: Default::default(),
+                })
+                .await
+                .unwrap()
+                .unwrap_or_default()
+                .into_iter()
+                .find_map(|a| match a {
+                    CodeActionOrCommand::CodeAction(a) if a.title.starts_with("Declare") => Some(a),
+                    _ => None,
+                })
+        }
+    };
+
+    let message = declare_at(4).await.expect("expected an explicit type for 'message'");
+    assert_eq!(message.title, "Declare 'message' as 'String'");
+    let edits = &message.edit.as_ref().unwrap().changes.as_ref().unwrap()[&uri];
+    assert_eq!(edits.len(), 1);
+    assert_eq!(
+        edits[0].range,
+        tower_lsp::lsp_types::Range::new(Position::new(4, 8), Position::new(4, 11))
+    );
+    assert_eq!(edits[0].new_text, "String");
+
+    let count = declare_at(5).await.expect("expected an explicit type for 'count'");
+    assert_eq!(count.title, "Declare 'count' as 'Integer'");
+
+    assert!(declare_at(6).await.is_none());
+}
+
+#[tokio::test]
+async fn unused_imports_reported_and_removed() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let uri = Url::parse("file:///tmp/Inventory.java").unwrap();
+    let content = r#"package com.example;
+
+import java.util.List;
+import java.util.Map;
+import java.util.Set; // lspintar:disable unused_import
+import static java.util.Objects.requireNonNull;
+
+/** Backed by a {@link Map}. */
+class Inventory {
+    void check(Object item) {
+        requireNonNull(item);
+    }
+}
+"#;
+    server
+        .backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "java".to_string(),
+                version: 1,
+                text: content.to_string(),
+            },
+        })
+        .await;
+
+    let diags = server
+        .backend
+        .compute_diagnostics(&uri)
+        .await
+        .expect("compute_diagnostics returned None");
+    let unused: Vec<_> = diags
+        .into_iter()
+        .filter(|d| {
+            d.code
+                == Some(tower_lsp::lsp_types::NumberOrString::String(
+                    "unused_import".to_string(),
+                ))
+        })
+        .collect();
+    assert_eq!(unused.len(), 1, "got: {unused:?}");
+    assert_eq!(unused[0].message, "Unused import java.util.List");
+    assert_eq!(
+        unused[0].range,
+        tower_lsp::lsp_types::Range::new(Position::new(2, 7), Position::new(2, 21))
+    );
+
+    let actions: Vec<tower_lsp::lsp_types::CodeAction> = server
+        .backend
+        .code_action_impl(CodeActionParams {
+            text_document: TextDocumentIdentifier { uri: uri.clone() },
+            range: unused[0].range,
+            context: CodeActionContext {
+                diagnostics: unused.clone(),
+                only: Some(vec![tower_lsp::lsp_types::CodeActionKind::QUICKFIX]),
+                ..Default::default()
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+        .await
+        .unwrap()
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|a| match a {
+            CodeActionOrCommand::CodeAction(a) => Some(a),
+            _ => None,
+        })
+        .collect();
+    let removal = |title: &str| {
+        let action = actions
+            .iter()
+            .find(|a| a.title == title)
+            .unwrap_or_else(|| panic!("expected '{title}'"));
+        action.edit.as_ref().unwrap().changes.as_ref().unwrap()[&uri].clone()
+    };
+    let line = tower_lsp::lsp_types::Range::new(Position::new(2, 0), Position::new(3, 0));
+
+    let single = removal("Remove unused import 'java.util.List'");
+    assert_eq!(single.len(), 1);
+    assert_eq!(single[0].range, line);
+    assert_eq!(single[0].new_text, "");
+
+    // The suppressed `Set` import stays.
+    let all = removal("Remove all unused imports");
+    assert_eq!(all.len(), 1);
+    assert_eq!(all[0].range, line);
+}
+
+#[tokio::test]
+async fn extract_constant_from_java_literal() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let uri = Url::parse("file:///tmp/Retrier.java").unwrap();
+    let content = r#"package com.example;
+
+public class Retrier {
+    private int attempts;
+
+    void run() {
+        log("max retries");
+        log("max retries");
+        int limit = 42;
+    }
+}
+"#;
+    server
+        .backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "java".to_string(),
+                version: 1,
+                text: content.to_string(),
+            },
+        })
+        .await;
+
+    let extract_at = |cursor: Position| {
+        let backend = &server.backend;
+        let uri = uri.clone();
+        async move {
+            backend
+                .code_action_impl(CodeActionParams {
+                    text_document: TextDocumentIdentifier { uri },
+                    range: tower_lsp::lsp_types::Range::new(cursor, cursor),
+                    context: CodeActionContext {
+                        only: Some(vec![tower_lsp::lsp_types::CodeActionKind::REFACTOR]),
+                        ..Default::default()
+                    },
+                    work_done_progress_params: Default::default(),
+                    partial_result_params: Default::default(),
+                })
+                .await
+                .unwrap()
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|a| match a {
+                    CodeActionOrCommand::CodeAction(a) => Some(a),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        }
+    };
+
+    let actions = extract_at(Position::new(6, 16)).await;
+    let titles: Vec<&str> = actions.iter().map(|a| a.title.as_str()).collect();
+    assert_eq!(
+        titles,
+        [
+            "Extract constant 'MAX_RETRIES'",
+            "Extract constant 'MAX_RETRIES' replacing 2 occurrences"
+        ]
+    );
+    let edits = &actions[1].edit.as_ref().unwrap().changes.as_ref().unwrap()[&uri];
+    assert_eq!(edits.len(), 3);
+    assert_eq!(
+        edits[0].new_text,
+        "    private static final String MAX_RETRIES = \"max retries\";\n\n"
+    );
+    assert_eq!(edits[0].range.start, Position::new(3, 0));
+    assert_eq!(
+        edits[2].range,
+        tower_lsp::lsp_types::Range::new(Position::new(7, 12), Position::new(7, 25))
+    );
+    assert_eq!(edits[2].new_text, "MAX_RETRIES");
+
+    let number = extract_at(Position::new(8, 20)).await;
+    assert_eq!(number.len(), 1);
+    let edits = &number[0].edit.as_ref().unwrap().changes.as_ref().unwrap()[&uri];
+    assert_eq!(
+        edits[0].new_text,
+        "    private static final int CONSTANT = 42;\n\n"
+    );
+
+    assert!(extract_at(Position::new(8, 12)).await.is_empty());
+}
+
+#[tokio::test]
+async fn unreachable_code_dimmed_with_removal() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let uri = Url::parse("file:///tmp/Dead.java").unwrap();
+    let content = r#"package com.example;
+
+public class Dead {
+    int run(int x) {
+        if (false) {
+            x++;
+        }
+        return x;
+        // trailing note
+    }
+
+    int pick(int x) {
+        if (x > 0) {
+            return 1;
+            x--;
+        }
+        return 0;
+    }
+}
+"#;
+    server
+        .backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "java".to_string(),
+                version: 1,
+                text: content.to_string(),
+            },
+        })
+        .await;
+
+    let diags = server
+        .backend
+        .compute_diagnostics(&uri)
+        .await
+        .expect("compute_diagnostics returned None");
+    let unreachable: Vec<_> = diags
+        .into_iter()
+        .filter(|d| {
+            d.code
+                == Some(tower_lsp::lsp_types::NumberOrString::String(
+                    "unreachable_code".to_string(),
+                ))
+        })
+        .collect();
+    let ranges: Vec<_> = unreachable.iter().map(|d| d.range).collect();
+    assert_eq!(
+        ranges,
+        [
+            tower_lsp::lsp_types::Range::new(Position::new(4, 19), Position::new(6, 9)),
+            tower_lsp::lsp_types::Range::new(Position::new(14, 12), Position::new(14, 16)),
+        ]
+    );
+    assert!(unreachable.iter().all(|d| {
+        d.tags == Some(vec![tower_lsp::lsp_types::DiagnosticTag::UNNECESSARY])
+            && d.severity == Some(tower_lsp::lsp_types::DiagnosticSeverity::HINT)
+    }));
+
+    let removal = |diagnostic: &tower_lsp::lsp_types::Diagnostic| {
+        let backend = &server.backend;
+        let uri = uri.clone();
+        let diagnostic = diagnostic.clone();
+        async move {
+            let actions = backend
+                .code_action_impl(CodeActionParams {
+                    text_document: TextDocumentIdentifier { uri: uri.clone() },
+                    range: diagnostic.range,
+                    context: CodeActionContext {
+                        diagnostics: vec![diagnostic],
+                        only: Some(vec![tower_lsp::lsp_types::CodeActionKind::QUICKFIX]),
+                        ..Default::default()
+                    },
+                    work_done_progress_params: Default::default(),
+                    partial_result_params: Default::default(),
+                })
+                .await
+                .unwrap()
+                .unwrap_or_default();
+            actions
+                .into_iter()
+                .find_map(|a| match a {
+                    CodeActionOrCommand::CodeAction(a) if a.title == "Remove unreachable code" => {
+                        Some(a.edit?.changes?.remove(&uri)?)
+                    }
+                    _ => None,
+                })
+                .expect("expected 'Remove unreachable code'")
+        }
+    };
+
+    let if_false = removal(&unreachable[0]).await;
+    assert_eq!(
+        if_false[0].range,
+        tower_lsp::lsp_types::Range::new(Position::new(4, 0), Position::new(7, 0))
+    );
+    let after_return = removal(&unreachable[1]).await;
+    assert_eq!(
+        after_return[0].range,
+        tower_lsp::lsp_types::Range::new(Position::new(14, 0), Position::new(15, 0))
+    );
+    assert_eq!(after_return[0].new_text, "");
+}
+
+/// In Kotlin, an `if (false)` is replaced by the statements of its `else` block, not
+/// the braced block, which would be a lambda that never runs.
+#[tokio::test]
+async fn unreachable_if_false_keeps_else_statements() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let uri = Url::parse("file:///tmp/DeadBranch.kt").unwrap();
+    let content = r#"package com.example
+
+class DeadBranch {
+    fun run(x: Int): Int {
+        if (false) {
+            println(x)
+        } else {
+            println(x + 1)
+            println(x + 2)
+        }
+        return x
+    }
+}
+"#;
+    server
+        .backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "kotlin".to_string(),
+                version: 1,
+                text: content.to_string(),
+            },
+        })
+        .await;
+
+    let diags = server
+        .backend
+        .compute_diagnostics(&uri)
+        .await
+        .expect("compute_diagnostics returned None");
+    let unreachable: Vec<_> = diags
+        .into_iter()
+        .filter(|d| {
+            d.code
+                == Some(tower_lsp::lsp_types::NumberOrString::String(
+                    "unreachable_code".to_string(),
+                ))
+        })
+        .collect();
+    assert_eq!(unreachable.len(), 1, "got: {unreachable:?}");
+
+    let actions = server
+        .backend
+        .code_action_impl(CodeActionParams {
+            text_document: TextDocumentIdentifier { uri: uri.clone() },
+            range: unreachable[0].range,
+            context: CodeActionContext {
+                diagnostics: unreachable,
+                only: Some(vec![tower_lsp::lsp_types::CodeActionKind::QUICKFIX]),
+                ..Default::default()
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+        .await
+        .unwrap()
+        .unwrap_or_default();
+    let edits = actions
+        .into_iter()
+        .find_map(|a| match a {
+            CodeActionOrCommand::CodeAction(a) if a.title == "Remove unreachable code" => {
+                Some(a.edit?.changes?.remove(&uri)?)
+            }
+            _ => None,
+        })
+        .expect("expected 'Remove unreachable code'");
+    assert_eq!(
+        edits[0].range,
+        tower_lsp::lsp_types::Range::new(Position::new(4, 8), Position::new(9, 9))
+    );
+    assert_eq!(edits[0].new_text, "println(x + 1)\n        println(x + 2)");
+}
@@ -48,3 +48,54 @@ async fn did_save_reindexes_file() {
         "new method should be indexed after save"
     );
 }
+
+#[tokio::test]
+async fn did_save_refreshes_supertype_closure() {
+    let server = get_test_server("polyglot-spring").await;
+    let root = env::current_dir().expect("cannot get current dir");
+    let file_path =
+        root.join("tests/fixtures/polyglot-spring/src/main/java/com/example/demo/GreeterCopy.java");
+    let uri = Url::from_file_path(&file_path).expect("cannot parse URI");
+
+    let mut closures = Vec::new();
+    for superclass in ["LoudGreeter", "PlainGreeter"] {
+        let content = format!(
+            "package com.example;\n\npublic class GreeterCopy extends {superclass} {{\n}}\n"
+        );
+        fs::write(&file_path, content).expect("cannot write fixture");
+        server
+            .backend
+            .did_save(DidSaveTextDocumentParams {
+                text_document: TextDocumentIdentifier { uri: uri.clone() },
+                text: None,
+            })
+            .await;
+        let closure = server
+            .backend
+            .supertype_closure("com.example.GreeterCopy")
+            .await;
+        closures.push(
+            closure
+                .iter()
+                .map(|s| s.fully_qualified_name.clone())
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    fs::remove_file(&file_path).expect("cannot remove fixture");
+
+    assert_eq!(
+        closures,
+        vec![
+            vec![
+                "com.example.LoudGreeter".to_string(),
+                "com.example.FormalGreeter".to_string(),
+                "com.example.Greeter".to_string(),
+            ],
+            vec![
+                "com.example.PlainGreeter".to_string(),
+                "com.example.Greeter".to_string(),
+            ],
+        ]
+    );
+}
@@ -0,0 +1,111 @@
+use std::sync::Arc;
+
+use pretty_assertions::assert_eq;
+use tower_lsp::{
+    LanguageServer,
+    lsp_types::{
+        Color, ColorPresentationParams, DidOpenTextDocumentParams, DocumentColorParams,
+        PartialResultParams, Position, Range, TextDocumentIdentifier, TextDocumentItem, Url,
+        WorkDoneProgressParams,
+    },
+};
+
+use crate::util::get_test_server;
+
+mod util;
+
+const CONTENT: &str = r##"package com.example
+
+val Primary = Color(0xFF336699)
+const val ACCENT = "#80FF0000"
+val Parsed = parseColor(0xFF000000)
+"##;
+
+async fn open_theme() -> (Arc<util::TestServer>, Url) {
+    let server = get_test_server("polyglot-spring").await;
+    let uri = Url::parse("file:///tmp/Theme.kt").unwrap();
+    server
+        .backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "kotlin".to_string(),
+                version: 1,
+                text: CONTENT.to_string(),
+            },
+        })
+        .await;
+    (server, uri)
+}
+
+#[tokio::test]
+async fn document_color_finds_compose_and_hex_string_colors() {
+    let (server, uri) = open_theme().await;
+
+    let colors = server
+        .backend
+        .document_color(DocumentColorParams {
+            text_document: TextDocumentIdentifier { uri },
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+        })
+        .await
+        .unwrap();
+
+    let ranges: Vec<Range> = colors.iter().map(|c| c.range).collect();
+    assert_eq!(
+        ranges,
+        vec![
+            Range::new(Position::new(2, 14), Position::new(2, 31)),
+            Range::new(Position::new(3, 19), Position::new(3, 30)),
+        ]
+    );
+    assert_eq!(colors[0].color.alpha, 1.0);
+    assert_eq!(colors[0].color.red, 0x33 as f32 / 255.0);
+    assert_eq!(colors[1].color.alpha, 0x80 as f32 / 255.0);
+    assert_eq!(colors[1].color.red, 1.0);
+}
+
+#[tokio::test]
+async fn color_presentation_keeps_the_literal_spelling() {
+    let (server, uri) = open_theme().await;
+    let green = Color {
+        red: 0.0,
+        green: 1.0,
+        blue: 0.0,
+        alpha: 1.0,
+    };
+
+    for (range, expected) in [
+        (
+            Range::new(Position::new(2, 14), Position::new(2, 31)),
+            "Color(0xFF00FF00)",
+        ),
+        (
+            Range::new(Position::new(3, 19), Position::new(3, 30)),
+            "\"#00FF00\"",
+        ),
+    ] {
+        let presentations = server
+            .backend
+            .color_presentation(ColorPresentationParams {
+                text_document: TextDocumentIdentifier { uri: uri.clone() },
+                color: green,
+                range,
+                work_done_progress_params: WorkDoneProgressParams::default(),
+                partial_result_params: PartialResultParams::default(),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(presentations.len(), 1);
+        assert_eq!(presentations[0].label, expected);
+        assert_eq!(
+            presentations[0]
+                .text_edit
+                .as_ref()
+                .map(|e| e.new_text.as_str()),
+            Some(expected)
+        );
+    }
+}
@@ -0,0 +1,56 @@
+use std::env;
+
+use pretty_assertions::assert_eq;
+use tower_lsp::{
+    LanguageServer,
+    lsp_types::{
+        DocumentLinkParams, PartialResultParams, Position, Range, TextDocumentIdentifier, Url,
+        WorkDoneProgressParams,
+    },
+};
+
+use crate::util::get_test_server;
+
+mod util;
+
+#[tokio::test]
+async fn imports_link_to_their_declaring_files() {
+    let server = get_test_server("groovy-gradle-multi").await;
+    let root = env::current_dir().expect("cannot get current dir");
+    let fixture = root.join("tests/fixtures/groovy-gradle-multi");
+
+    let params = DocumentLinkParams {
+        text_document: TextDocumentIdentifier {
+            uri: Url::from_file_path(
+                fixture.join("api/src/main/groovy/com/example/api/UserController.groovy"),
+            )
+            .unwrap(),
+        },
+        work_done_progress_params: WorkDoneProgressParams::default(),
+        partial_result_params: PartialResultParams::default(),
+    };
+    let links = server.backend.document_link(params).await.unwrap().unwrap();
+
+    let core = fixture.join("core/src/main/groovy/com/example/core");
+    let expected: Vec<(Range, Url)> = [
+        (2, "BaseService"),
+        (3, "DataProcessor"),
+        (4, "DataProcessResult"),
+    ]
+    .into_iter()
+    .map(|(line, name)| {
+        let end = "com.example.core.".len() + name.len();
+        (
+            Range::new(Position::new(line, 7), Position::new(line, 7 + end as u32)),
+            Url::from_file_path(core.join(format!("{name}.groovy"))).unwrap(),
+        )
+    })
+    .collect();
+    assert_eq!(
+        links
+            .into_iter()
+            .map(|l| (l.range, l.target.unwrap()))
+            .collect::<Vec<_>>(),
+        expected
+    );
+}
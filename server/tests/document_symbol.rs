@@ -0,0 +1,244 @@
+use std::env;
+
+use tower_lsp::{
+    LanguageServer,
+    lsp_types::{
+        DidOpenTextDocumentParams, DocumentSymbol, DocumentSymbolParams, DocumentSymbolResponse,
+        SymbolKind, TextDocumentIdentifier, TextDocumentItem, Url,
+    },
+};
+
+use crate::util::get_test_server;
+
+mod util;
+
+/// `(name, kind, children)` of each symbol, for comparing whole outlines.
+fn shape(symbols: &[DocumentSymbol]) -> Vec<(String, SymbolKind, Vec<(String, SymbolKind)>)> {
+    symbols
+        .iter()
+        .map(|s| {
+            let children = s
+                .children
+                .iter()
+                .flatten()
+                .map(|c| (c.name.clone(), c.kind))
+                .collect();
+            (s.name.clone(), s.kind, children)
+        })
+        .collect()
+}
+
+async fn outline(relative: &str) -> Vec<DocumentSymbol> {
+    let path = env::current_dir()
+        .unwrap()
+        .join("tests/fixtures/polyglot-spring")
+        .join(relative);
+    outline_of(Url::from_file_path(path).unwrap(), None).await
+}
+
+/// The outline of `uri`, opened with `text` when given.
+async fn outline_of(uri: Url, text: Option<&str>) -> Vec<DocumentSymbol> {
+    let server = get_test_server("polyglot-spring").await;
+    if let Some(text) = text {
+        server
+            .backend
+            .did_open(DidOpenTextDocumentParams {
+                text_document: TextDocumentItem {
+                    uri: uri.clone(),
+                    language_id: "groovy".to_string(),
+                    version: 1,
+                    text: text.to_string(),
+                },
+            })
+            .await;
+    }
+    let response = server
+        .backend
+        .document_symbol(DocumentSymbolParams {
+            text_document: TextDocumentIdentifier { uri },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+        .await
+        .unwrap();
+    match response {
+        Some(DocumentSymbolResponse::Nested(symbols)) => symbols,
+        other => panic!("expected nested symbols, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn document_symbols_nest_members_under_their_type() {
+    for file in [
+        "src/main/java/com/example/demo/JavaService.java",
+        "src/main/groovy/com/example/demo/GroovyService.groovy",
+    ] {
+        let symbols = outline(file).await;
+        let name = if file.ends_with(".java") {
+            "JavaService"
+        } else {
+            "GroovyService"
+        };
+        assert_eq!(
+            shape(&symbols),
+            vec![(
+                name.to_string(),
+                SymbolKind::CLASS,
+                vec![("process".to_string(), SymbolKind::METHOD)]
+            )],
+            "{file}"
+        );
+        let process = &symbols[0].children.as_ref().unwrap()[0];
+        assert_eq!(process.detail.as_deref(), Some("String"), "{file}");
+    }
+}
+
+#[tokio::test]
+async fn document_symbols_for_kotlin() {
+    let symbols = outline("src/main/kotlin/com/example/demo/UserRepository.kt").await;
+    assert_eq!(
+        shape(&symbols),
+        vec![(
+            "UserRepository".to_string(),
+            SymbolKind::CLASS,
+            vec![
+                ("findById".to_string(), SymbolKind::METHOD),
+                ("save".to_string(), SymbolKind::METHOD),
+            ]
+        )]
+    );
+    let class = &symbols[0];
+    assert_eq!(class.selection_range.start.line, 5);
+    assert!(class.range.start <= class.selection_range.start);
+}
+
+#[tokio::test]
+async fn document_symbols_for_gradle_blocks() {
+    let symbols = outline("build.gradle").await;
+    let names: Vec<(&str, SymbolKind)> =
+        symbols.iter().map(|s| (s.name.as_str(), s.kind)).collect();
+    assert_eq!(
+        names,
+        vec![
+            ("plugins", SymbolKind::NAMESPACE),
+            ("repositories", SymbolKind::NAMESPACE),
+            ("dependencies", SymbolKind::NAMESPACE),
+            ("kotlin", SymbolKind::NAMESPACE),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn document_symbols_name_blocks_by_label() {
+    let jenkinsfile = r#"pipeline {
+    stages {
+        stage('Build') {
+            steps {
+                sh 'gradle build'
+            }
+        }
+        stage("Test") {
+        }
+    }
+}
+"#;
+    let symbols = outline_of(
+        Url::parse("file:///tmp/pipeline/Jenkinsfile").unwrap(),
+        Some(jenkinsfile),
+    )
+    .await;
+    assert_eq!(
+        shape(&symbols),
+        vec![(
+            "pipeline".to_string(),
+            SymbolKind::NAMESPACE,
+            vec![("stages".to_string(), SymbolKind::NAMESPACE)]
+        )]
+    );
+    let stages = &symbols[0].children.as_ref().unwrap()[0];
+    assert_eq!(
+        shape(stages.children.as_ref().unwrap()),
+        vec![
+            (
+                "Build".to_string(),
+                SymbolKind::NAMESPACE,
+                vec![("steps".to_string(), SymbolKind::NAMESPACE)]
+            ),
+            ("Test".to_string(), SymbolKind::NAMESPACE, vec![]),
+        ]
+    );
+    let build = &stages.children.as_ref().unwrap()[0];
+    assert_eq!(build.detail.as_deref(), Some("stage"));
+}
+
+#[tokio::test]
+async fn document_symbols_for_spock_blocks() {
+    let spec = r#"import spock.lang.Specification
+
+class CalculatorSpec extends Specification {
+    def "adds two numbers"() {
+        given: "a calculator"
+        def calculator = new Calculator()
+
+        when:
+        def sum = calculator.add(1, 2)
+
+        then:
+        sum == 3
+    }
+}
+"#;
+    let symbols = outline_of(
+        Url::parse("file:///tmp/CalculatorSpec.groovy").unwrap(),
+        Some(spec),
+    )
+    .await;
+    let feature = &symbols[0].children.as_ref().unwrap()[0];
+    let blocks = feature.children.as_ref().unwrap();
+    let labels: Vec<&str> = blocks.iter().map(|b| b.name.as_str()).collect();
+    assert_eq!(labels, vec!["given", "when", "then"]);
+    assert!(blocks.iter().all(|b| b.kind == SymbolKind::KEY));
+    assert_eq!(blocks[0].detail.as_deref(), Some("a calculator"));
+    assert_eq!(blocks[0].range.end.line, 5);
+}
+
+#[tokio::test]
+async fn document_symbols_tell_enum_constants_and_objects_apart() {
+    let priority = outline("src/main/java/com/example/demo/Priority.java").await;
+    assert_eq!(
+        shape(&priority),
+        vec![(
+            "Priority".to_string(),
+            SymbolKind::ENUM,
+            vec![
+                ("LOW".to_string(), SymbolKind::ENUM_MEMBER),
+                ("HIGH".to_string(), SymbolKind::ENUM_MEMBER),
+                ("fallback".to_string(), SymbolKind::METHOD),
+            ]
+        )]
+    );
+
+    let shade = outline("src/main/kotlin/com/example/demo/Shade.kt").await;
+    let members = &shade[0].children.as_ref().unwrap();
+    let kinds: Vec<(&str, SymbolKind)> =
+        members.iter().map(|s| (s.name.as_str(), s.kind)).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            ("hex", SymbolKind::FIELD),
+            ("LIGHT", SymbolKind::ENUM_MEMBER),
+            ("DARK", SymbolKind::ENUM_MEMBER),
+            ("inverse", SymbolKind::METHOD),
+        ]
+    );
+
+    let limits = outline("src/main/kotlin/com/example/demo/Limits.kt").await;
+    assert_eq!(limits[0].name, "Limits");
+    assert_eq!(limits[0].kind, SymbolKind::OBJECT);
+    let retries = limits[0]
+        .children
+        .iter()
+        .flatten()
+        .find(|s| s.name == "retries");
+    assert_eq!(retries.map(|s| s.kind), Some(SymbolKind::METHOD));
+}
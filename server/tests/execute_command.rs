@@ -0,0 +1,197 @@
+use std::env;
+
+use tower_lsp::{
+    LanguageServer,
+    lsp_types::{
+        CodeActionContext, CodeActionKind, CodeActionOrCommand, CodeActionParams,
+        DidOpenTextDocumentParams, ExecuteCommandParams, PartialResultParams, Position, Range,
+        TextDocumentIdentifier, TextDocumentItem, TextDocumentPositionParams, TextEdit, Url,
+        WorkDoneProgressParams, WorkspaceEdit,
+    },
+};
+
+use crate::util::get_test_server;
+
+mod util;
+
+fn command(name: &str) -> ExecuteCommandParams {
+    ExecuteCommandParams {
+        command: name.to_string(),
+        arguments: vec![],
+        work_done_progress_params: WorkDoneProgressParams::default(),
+    }
+}
+
+#[tokio::test]
+async fn refresh_dependencies_without_build_changes() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let result = server
+        .backend
+        .execute_command(command("lspintar.refreshDependencies"))
+        .await
+        .unwrap();
+
+    assert_eq!(result, Some(serde_json::json!({ "added": 0, "removed": 0 })));
+}
+
+#[tokio::test]
+async fn unknown_command_is_rejected() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let result = server.backend.execute_command(command("lspintar.doesNotExist")).await;
+
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn copy_reference_of_method_and_type() {
+    let server = get_test_server("polyglot-spring").await;
+    let root = env::current_dir().expect("cannot get current dir");
+    let uri = Url::from_file_path(
+        root.join("tests/fixtures/polyglot-spring/src/main/groovy/com/example/demo/Controller.groovy"),
+    )
+    .expect("cannot parse root URI");
+
+    for (position, expected) in [
+        (Position::new(31, 45), "com.example.GroovyService#process(String)"),
+        (Position::new(14, 8), "com.example.GroovyService"),
+    ] {
+        let position = TextDocumentPositionParams {
+            text_document: TextDocumentIdentifier { uri: uri.clone() },
+            position,
+        };
+        let mut params = command("lspintar.copyReference");
+        params.arguments = vec![serde_json::to_value(position).unwrap()];
+
+        let result = server.backend.execute_command(params).await.unwrap();
+
+        assert_eq!(result, Some(serde_json::json!(expected)));
+    }
+}
+
+#[tokio::test]
+async fn copy_reference_without_position_is_rejected() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let result = server.backend.execute_command(command("lspintar.copyReference")).await;
+
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn explain_resolution_of_method() {
+    let server = get_test_server("polyglot-spring").await;
+    let root = env::current_dir().expect("cannot get current dir");
+    let uri = Url::from_file_path(
+        root.join("tests/fixtures/polyglot-spring/src/main/groovy/com/example/demo/Controller.groovy"),
+    )
+    .expect("cannot parse root URI");
+    let position = TextDocumentPositionParams {
+        text_document: TextDocumentIdentifier { uri },
+        position: Position::new(31, 45),
+    };
+    let mut params = command("lspintar.explainResolution");
+    params.arguments = vec![serde_json::to_value(position).unwrap()];
+
+    let result = server.backend.execute_command(params).await.unwrap().unwrap();
+
+    assert_eq!(result["resolver"], "symbol");
+    assert_eq!(result["tried"].as_array().unwrap().len(), 6);
+    assert_eq!(
+        result["targets"][0]["symbol"],
+        "com.example.GroovyService#process(String)"
+    );
+    assert_eq!(result["targets"][0]["kind"], "Function");
+    assert_eq!(result["targets"][0]["container"], "com.example.GroovyService");
+    assert_eq!(result["fallbacks"], serde_json::json!([]));
+}
+
+#[tokio::test]
+async fn explain_resolution_without_position_toggles_trace() {
+    let server = get_test_server("polyglot-spring").await;
+
+    for expected in [true, false] {
+        let result = server
+            .backend
+            .execute_command(command("lspintar.explainResolution"))
+            .await
+            .unwrap();
+
+        assert_eq!(result, Some(serde_json::json!({ "trace": expected })));
+    }
+}
+
+#[tokio::test]
+async fn organize_imports_returns_the_code_action_edit() {
+    let server = get_test_server("polyglot-spring").await;
+    let uri = Url::parse("file:///tmp/Imports.java").unwrap();
+    let content = r#"package com.example;
+
+import java.util.Map;
+import static java.util.Collections.emptyList;
+import java.util.List;
+import java.util.Set;
+
+public class Imports {
+    List<String> names = emptyList();
+    Map<String, String> byName;
+}
+"#;
+    server
+        .backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "java".to_string(),
+                version: 1,
+                text: content.to_string(),
+            },
+        })
+        .await;
+
+    let mut params = command("lspintar.organizeImports");
+    params.arguments =
+        vec![serde_json::to_value(TextDocumentIdentifier { uri: uri.clone() }).unwrap()];
+    let result = server.backend.execute_command(params).await.unwrap().unwrap();
+    let edit: WorkspaceEdit = serde_json::from_value(result).unwrap();
+
+    assert_eq!(
+        edit.changes.as_ref().unwrap()[&uri],
+        vec![TextEdit {
+            range: Range::new(Position::new(2, 0), Position::new(5, 21)),
+            new_text: "import java.util.List;\nimport java.util.Map;\n\n\
+                       import static java.util.Collections.emptyList;"
+                .to_string(),
+        }]
+    );
+
+    let actions = server
+        .backend
+        .code_action(CodeActionParams {
+            text_document: TextDocumentIdentifier { uri },
+            range: Range::default(),
+            context: CodeActionContext {
+                only: Some(vec![CodeActionKind::SOURCE_ORGANIZE_IMPORTS]),
+                ..Default::default()
+            },
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    let [CodeActionOrCommand::CodeAction(action)] = actions.as_slice() else {
+        panic!("Expected one code action, got {actions:?}");
+    };
+    assert_eq!(action.edit, Some(edit));
+}
+
+#[tokio::test]
+async fn organize_imports_without_document_is_rejected() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let result = server.backend.execute_command(command("lspintar.organizeImports")).await;
+
+    assert!(result.is_err());
+}
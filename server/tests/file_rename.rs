@@ -0,0 +1,104 @@
+use std::{env, path::Path};
+
+use lspintar_server::file_rename::moved_package;
+use tower_lsp::{
+    LanguageServer,
+    lsp_types::{FileRename, Position, Range, RenameFilesParams, TextEdit, Url, WorkspaceEdit},
+};
+
+use crate::util::get_test_server;
+
+mod util;
+
+fn edits_for<'a>(edit: &'a WorkspaceEdit, file: &Path) -> &'a Vec<TextEdit> {
+    let uri = Url::from_file_path(file).expect("bad path");
+    edit.changes
+        .as_ref()
+        .and_then(|changes| changes.get(&uri))
+        .unwrap_or_else(|| panic!("no edits for {}", file.display()))
+}
+
+#[test]
+fn moved_package_follows_java_source_root() {
+    assert_eq!(
+        moved_package(
+            Path::new("/p/src/main/java/com/acme/web/Api.java"),
+            Path::new("/p/src/main/java/com/acme/api/Api.java"),
+            "com.acme.web",
+        ),
+        Some("com.acme.api".to_string())
+    );
+}
+
+#[test]
+fn moved_package_keeps_omitted_kotlin_root_package() {
+    assert_eq!(
+        moved_package(
+            Path::new("/p/src/main/kotlin/web/Api.kt"),
+            Path::new("/p/src/main/kotlin/api/v2/Api.kt"),
+            "com.acme.web",
+        ),
+        Some("com.acme.api.v2".to_string())
+    );
+}
+
+#[test]
+fn moved_package_of_rename_in_place_is_none() {
+    assert_eq!(
+        moved_package(
+            Path::new("/p/src/main/kotlin/web/Api.kt"),
+            Path::new("/p/src/main/kotlin/web/Endpoint.kt"),
+            "com.acme.web",
+        ),
+        None
+    );
+}
+
+#[tokio::test]
+async fn will_rename_files_moves_kotlin_class_to_subpackage() {
+    let server = get_test_server("polyglot-spring").await;
+    let root = env::current_dir()
+        .expect("cannot get current dir")
+        .join("tests/fixtures/polyglot-spring/src/main");
+    let kotlin = root.join("kotlin/com/example/demo");
+    let old = kotlin.join("User.kt");
+    let new = kotlin.join("model/User.kt");
+
+    let edit = server
+        .backend
+        .will_rename_files(RenameFilesParams {
+            files: vec![FileRename {
+                old_uri: Url::from_file_path(&old).unwrap().to_string(),
+                new_uri: Url::from_file_path(&new).unwrap().to_string(),
+            }],
+        })
+        .await
+        .unwrap()
+        .expect("expected a workspace edit");
+
+    assert!(edits_for(&edit, &old).contains(&TextEdit::new(
+        Range::new(Position::new(0, 8), Position::new(0, 19)),
+        "com.example.model".to_string(),
+    )));
+    // `import com.example.User as Member`
+    assert!(
+        edits_for(&edit, &kotlin.join("MemberRegistry.kt")).contains(&TextEdit::new(
+            Range::new(Position::new(2, 7), Position::new(2, 18)),
+            "com.example.model".to_string(),
+        ))
+    );
+    // Same-package users get an import.
+    assert!(
+        edits_for(&edit, &kotlin.join("UserRepository.kt"))
+            .iter()
+            .any(|e| e.new_text == "import com.example.model.User\n")
+    );
+    assert!(
+        edits_for(
+            &edit,
+            &root.join("groovy/com/example/demo/Controller.groovy")
+        )
+        .iter()
+        .any(|e| e.new_text == "import com.example.model.User\n")
+    );
+}
@@ -0,0 +1,5 @@
+package com.example
+
+expect class Platform() {
+    val name: String
+}
@@ -0,0 +1,5 @@
+package com.example
+
+actual class Platform actual constructor() {
+    actual val name: String = "JVM"
+}
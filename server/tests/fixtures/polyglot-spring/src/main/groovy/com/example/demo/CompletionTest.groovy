@@ -21,4 +21,9 @@ class CompletionTest {
         Closure myClosure = {}
         myClosure.
     }
+
+    void testEnclosingMembers() {
+        // testLocalsBeforeGlobals is a method of this class, callable without a qualifier
+        testLoc
+    }
 }
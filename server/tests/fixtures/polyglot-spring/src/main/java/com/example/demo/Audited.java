@@ -0,0 +1,9 @@
+package com.example;
+
+public @interface Audited {
+    AuditLevel level() default AuditLevel.BASIC;
+
+    Class<?>[] exclude() default {};
+
+    String category() default "";
+}
@@ -0,0 +1,8 @@
+package com.example;
+
+public class FormalGreeter implements Greeter {
+    @Override
+    public String greet(String name) {
+        return "Good day, " + name;
+    }
+}
@@ -0,0 +1,7 @@
+package com.example;
+
+public interface Greeter {
+    default String greet(String name) {
+        return "Hello, " + name;
+    }
+}
@@ -0,0 +1,18 @@
+package com.example;
+
+public class LoudGreeter extends FormalGreeter {
+    private final String suffix;
+
+    public LoudGreeter() {
+        this("!");
+    }
+
+    public LoudGreeter(String suffix) {
+        super();
+        this.suffix = suffix;
+    }
+
+    public String shout(String name) {
+        return super.greet(name).toUpperCase() + suffix;
+    }
+}
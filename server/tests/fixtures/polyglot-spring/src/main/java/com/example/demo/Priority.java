@@ -0,0 +1,18 @@
+package com.example;
+
+public enum Priority {
+    LOW(1, "low"),
+    HIGH(10, "high");
+
+    private final int weight;
+    private final String label;
+
+    Priority(int weight, String label) {
+        this.weight = weight;
+        this.label = label;
+    }
+
+    public static Priority fallback() {
+        return Priority.HIGH;
+    }
+}
@@ -0,0 +1,8 @@
+package com.example;
+
+import jakarta.persistence.Entity;
+
+@Entity(name = "PurchaseOrder")
+public class Purchase extends TrackedEntity {
+    private Shop shop;
+}
@@ -0,0 +1,8 @@
+package com.example;
+
+public class TaskRunner {
+    public void runAll() {
+        Runnable task = () -> System.out.println("running");
+        task.run();
+    }
+}
@@ -0,0 +1,8 @@
+package com.example;
+
+import jakarta.persistence.MappedSuperclass;
+
+@MappedSuperclass
+public abstract class TrackedEntity {
+    protected Customer placedBy;
+}
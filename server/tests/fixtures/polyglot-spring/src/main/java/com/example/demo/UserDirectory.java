@@ -0,0 +1,18 @@
+package com.example;
+
+import org.jetbrains.annotations.Nullable;
+
+public class UserDirectory {
+    @Nullable
+    public String findEmail(String name) {
+        return null;
+    }
+
+    public String displayName(String name) {
+        return name;
+    }
+
+    String cachedEmail(String name) {
+        return null;
+    }
+}
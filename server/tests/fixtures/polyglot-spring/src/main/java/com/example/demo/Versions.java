@@ -0,0 +1,11 @@
+package com.example;
+
+public class Versions {
+    public static final String PREFIX = "v";
+    public static final String RELEASE = "v" + 2 + ".0";
+    public static final long BUILD = 42L;
+
+    public String describe() {
+        return RELEASE + "-" + BUILD;
+    }
+}
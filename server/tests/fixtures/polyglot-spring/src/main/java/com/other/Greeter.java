@@ -0,0 +1,4 @@
+package com.other;
+
+public interface Greeter {
+}
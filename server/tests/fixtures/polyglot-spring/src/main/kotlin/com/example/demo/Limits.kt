@@ -0,0 +1,10 @@
+package com.example
+
+object Limits {
+    const val MAX_RETRIES = 3
+    const val GREETING = "Hello, " + "world"
+
+    fun retries(): Int = MAX_RETRIES
+
+    fun greeting(): String = GREETING
+}
@@ -0,0 +1,10 @@
+package com.example
+
+import com.example.User as Member
+
+typealias UserStore = UserRepository
+typealias MemberCallback = (Member) -> Unit
+
+class MemberRegistry(private val store: UserStore) {
+    fun first(): Member? = null
+}
@@ -0,0 +1,8 @@
+package com.example
+
+enum class Shade(val hex: String) {
+    LIGHT("#FFFFFF"),
+    DARK("#000000");
+
+    fun inverse(): Shade = if (this == LIGHT) Shade.DARK else LIGHT
+}
@@ -0,0 +1,7 @@
+package com.example;
+
+public class Clock {
+    public long now() {
+        return 0L;
+    }
+}
@@ -0,0 +1,7 @@
+package com.example;
+
+public class ClockFixtures {
+    public static Clock frozen() {
+        return new Clock();
+    }
+}
@@ -5,12 +5,16 @@ use pretty_assertions::assert_eq;
 use tower_lsp::{
     LanguageServer,
     lsp_types::{
-        GotoDefinitionParams, GotoDefinitionResponse, Location, PartialResultParams, Position,
-        Range, TextDocumentIdentifier, TextDocumentPositionParams, Url, WorkDoneProgressParams,
+        DidOpenTextDocumentParams, GotoDefinitionParams, GotoDefinitionResponse, Location,
+        PartialResultParams, Position, Range, TextDocumentIdentifier, TextDocumentItem,
+        TextDocumentPositionParams, Url, WorkDoneProgressParams,
     },
 };
 
-use crate::util::get_test_server;
+use crate::util::{
+    get_standalone_test_server, get_test_server, get_test_server_with_options,
+    open_ambiguous_greeter_client,
+};
 
 mod util;
 
@@ -99,6 +103,79 @@ async fn gtd_static_member() {
     assert_eq!(result.unwrap(), GotoDefinitionResponse::from(location));
 }
 
+#[tokio::test]
+async fn gtd_static_member_without_search_limits() {
+    let server = get_test_server_with_options(
+        "groovy-gradle-multi",
+        serde_json::json!({"type_search_timeout_ms": 0, "member_search_timeout_ms": 0}),
+    )
+    .await;
+
+    let root = env::current_dir().expect("cannot get current dir");
+
+    let params = GotoDefinitionParams {
+        text_document_position_params: TextDocumentPositionParams {
+            text_document: TextDocumentIdentifier {
+                uri: Url::from_file_path(root.join("tests/fixtures/groovy-gradle-multi/api/src/main/groovy/com/example/api/UserController.groovy"))
+                    .expect("cannot parse root URI"),
+            },
+            position: Position::new(47, 37),
+        },
+        work_done_progress_params: WorkDoneProgressParams::default(),
+        partial_result_params: PartialResultParams::default(),
+    };
+
+    let result = server.backend.goto_definition(params).await.unwrap();
+
+    let location = Location::new(
+        Url::from_file_path(root.join(
+            HOME_DIR.join("Projects/lspintar-ws/lspintar/server/tests/fixtures/groovy-gradle-multi/core/src/main/groovy/com/example/core/DataProcessor.groovy",
+        )))
+        .unwrap(),
+        Range::new(Position::new(5, 21), Position::new(5, 35)),
+    );
+
+    assert_eq!(result, Some(GotoDefinitionResponse::from(location)));
+}
+
+// With the clock paused, time jumps to the member search's 1 ms deadline as soon
+// as the search waits on the index, so it always runs out and the definition falls
+// back to the receiver's type.
+#[tokio::test(start_paused = true)]
+async fn gtd_static_member_falls_back_to_type_when_member_search_times_out() {
+    let server = get_test_server_with_options(
+        "groovy-gradle-multi",
+        serde_json::json!({"type_search_timeout_ms": 0, "member_search_timeout_ms": 1}),
+    )
+    .await;
+
+    let root = env::current_dir().expect("cannot get current dir");
+
+    let params = GotoDefinitionParams {
+        text_document_position_params: TextDocumentPositionParams {
+            text_document: TextDocumentIdentifier {
+                uri: Url::from_file_path(root.join("tests/fixtures/groovy-gradle-multi/api/src/main/groovy/com/example/api/UserController.groovy"))
+                    .expect("cannot parse root URI"),
+            },
+            position: Position::new(47, 37),
+        },
+        work_done_progress_params: WorkDoneProgressParams::default(),
+        partial_result_params: PartialResultParams::default(),
+    };
+
+    let result = server.backend.goto_definition(params).await.unwrap();
+
+    let location = Location::new(
+        Url::from_file_path(root.join(
+            HOME_DIR.join("Projects/lspintar-ws/lspintar/server/tests/fixtures/groovy-gradle-multi/core/src/main/groovy/com/example/core/DataProcessor.groovy",
+        )))
+        .unwrap(),
+        Range::new(Position::new(4, 10), Position::new(4, 23)),
+    );
+
+    assert_eq!(result, Some(GotoDefinitionResponse::from(location)));
+}
+
 #[tokio::test]
 async fn gtd_this_member() {
     let server = get_test_server("groovy-gradle-multi").await;
@@ -640,3 +717,458 @@ async fn gtd_resolve_chain_external() {
     assert_eq!(location.range.start.line, 536);
     assert_eq!(location.range.start.character, 25);
 }
+
+#[tokio::test]
+async fn gtd_functional_interface_call_to_local_lambda() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let root = env::current_dir().expect("cannot get current dir");
+    let uri = Url::from_file_path(
+        root.join("tests/fixtures/polyglot-spring/src/main/java/com/example/demo/TaskRunner.java"),
+    )
+    .expect("cannot parse root URI");
+
+    let params = GotoDefinitionParams {
+        text_document_position_params: TextDocumentPositionParams {
+            text_document: TextDocumentIdentifier { uri: uri.clone() },
+            position: Position::new(5, 14),
+        },
+        work_done_progress_params: WorkDoneProgressParams::default(),
+        partial_result_params: PartialResultParams::default(),
+    };
+
+    let result = server.backend.goto_definition(params).await.unwrap();
+    let location = match result {
+        Some(GotoDefinitionResponse::Scalar(loc)) => loc,
+        other => panic!("Expected scalar location, got {other:?}"),
+    };
+
+    assert_eq!(location.uri, uri);
+    assert_eq!(location.range.start, Position::new(4, 24));
+}
+
+/// A call on a local whose type resolves to nothing is not taken for the single
+/// abstract method of a functional interface, so it does not jump to the lambda.
+#[tokio::test]
+async fn gtd_unresolved_type_call_not_sent_to_lambda() {
+    let content = r#"package com.example;
+
+class Jobs {
+    void schedule() {
+        Missing task = () -> System.out.println("running");
+        task.start();
+    }
+}
+"#;
+    let result = gtd_in_open_document(
+        "file:///tmp/Jobs.java",
+        "java",
+        content,
+        Position::new(5, 14),
+    )
+    .await;
+
+    // `Missing` is not indexed, so `start` has nowhere to go, least of all to the
+    // lambda assigned to `task`.
+    assert!(result.is_none(), "got: {result:?}");
+}
+
+async fn gtd_in_open_document(
+    uri: &str,
+    language_id: &str,
+    content: &str,
+    position: Position,
+) -> Option<GotoDefinitionResponse> {
+    let server = get_test_server("polyglot-spring").await;
+    let uri = Url::parse(uri).unwrap();
+    server
+        .backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: language_id.to_string(),
+                version: 1,
+                text: content.to_string(),
+            },
+        })
+        .await;
+
+    let params = GotoDefinitionParams {
+        text_document_position_params: TextDocumentPositionParams {
+            text_document: TextDocumentIdentifier { uri },
+            position,
+        },
+        work_done_progress_params: WorkDoneProgressParams::default(),
+        partial_result_params: PartialResultParams::default(),
+    };
+    // A position that resolves to nothing may be answered with an error.
+    server.backend.goto_definition(params).await.ok().flatten()
+}
+
+#[tokio::test]
+async fn gtd_class_for_name_string() {
+    let content = r#"package com.example;
+
+class Loader {
+    Object load() throws Exception {
+        return Class.forName("com.example.PlainGreeter").newInstance();
+    }
+}
+"#;
+    let result =
+        gtd_in_open_document("file:///tmp/Loader.java", "java", content, Position::new(4, 40))
+            .await;
+
+    let location = match result {
+        Some(GotoDefinitionResponse::Scalar(loc)) => loc,
+        other => panic!("Expected scalar location, got {other:?}"),
+    };
+    assert!(location.uri.path().ends_with("demo/PlainGreeter.java"));
+    assert_eq!(location.range.start.line, 2);
+}
+
+#[tokio::test]
+async fn gtd_spring_xml_bean_class() {
+    let content = r#"<beans>
+    <bean id="greeter" class="com.example.FormalGreeter"/>
+</beans>
+"#;
+    let result =
+        gtd_in_open_document("file:///tmp/context.xml", "xml", content, Position::new(1, 45))
+            .await;
+
+    let location = match result {
+        Some(GotoDefinitionResponse::Scalar(loc)) => loc,
+        other => panic!("Expected scalar location, got {other:?}"),
+    };
+    assert!(location.uri.path().ends_with("demo/FormalGreeter.java"));
+}
+
+#[tokio::test]
+async fn gtd_component_scan_package_string() {
+    let content = r#"package com.example;
+
+import org.springframework.context.annotation.ComponentScan;
+
+@ComponentScan(basePackages = "com.example")
+class ScanConfig {}
+"#;
+    let result = gtd_in_open_document(
+        "file:///tmp/ScanConfig.java",
+        "java",
+        content,
+        Position::new(4, 35),
+    )
+    .await;
+
+    let locations = match result {
+        Some(GotoDefinitionResponse::Array(locs)) => locs,
+        other => panic!("Expected package members, got {other:?}"),
+    };
+    assert!(
+        locations
+            .iter()
+            .any(|loc| loc.uri.path().ends_with("demo/PlainGreeter.java"))
+    );
+}
+
+#[tokio::test]
+async fn gtd_spring_xml_bean_ref() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let root = env::current_dir().expect("cannot get current dir");
+    let uri = Url::from_file_path(
+        root.join("tests/fixtures/polyglot-spring/src/main/resources/applicationContext.xml"),
+    )
+    .expect("cannot parse root URI");
+
+    let params = GotoDefinitionParams {
+        text_document_position_params: TextDocumentPositionParams {
+            text_document: TextDocumentIdentifier { uri: uri.clone() },
+            position: Position::new(4, 38),
+        },
+        work_done_progress_params: WorkDoneProgressParams::default(),
+        partial_result_params: PartialResultParams::default(),
+    };
+
+    let result = server.backend.goto_definition(params).await.unwrap();
+    let location = match result {
+        Some(GotoDefinitionResponse::Scalar(loc)) => loc,
+        other => panic!("Expected scalar location, got {other:?}"),
+    };
+
+    assert_eq!(location.uri, uri);
+    assert_eq!(location.range.start, Position::new(2, 14));
+}
+
+#[tokio::test]
+async fn gtd_jpql_entity_field_and_alias() {
+    let content = r#"package com.example;
+
+import org.springframework.data.jpa.repository.Query;
+
+interface CustomerQueries {
+    @Query("SELECT c FROM Customer c WHERE c.name = :name")
+    Customer byName(String name);
+}
+"#;
+    let uri = "file:///tmp/CustomerQueries.java";
+
+    let entity = match gtd_in_open_document(uri, "java", content, Position::new(5, 28)).await {
+        Some(GotoDefinitionResponse::Scalar(loc)) => loc,
+        other => panic!("Expected entity location, got {other:?}"),
+    };
+    assert!(entity.uri.path().ends_with("demo/Customer.java"));
+
+    let field = match gtd_in_open_document(uri, "java", content, Position::new(5, 46)).await {
+        Some(GotoDefinitionResponse::Scalar(loc)) => loc,
+        other => panic!("Expected field location, got {other:?}"),
+    };
+    assert!(field.uri.path().ends_with("demo/Customer.java"));
+    assert_eq!(field.range.start.line, 6);
+
+    let alias = match gtd_in_open_document(uri, "java", content, Position::new(5, 19)).await {
+        Some(GotoDefinitionResponse::Scalar(loc)) => loc,
+        other => panic!("Expected alias location, got {other:?}"),
+    };
+    assert_eq!(alias.range.start, Position::new(5, 35));
+}
+
+#[tokio::test]
+async fn gtd_mapped_by_to_owning_field() {
+    let content = r#"package com.example;
+
+import java.util.List;
+import jakarta.persistence.Entity;
+import jakarta.persistence.OneToMany;
+
+@Entity
+class Shop {
+    @OneToMany(mappedBy = "shop")
+    private List<Purchase> purchases;
+}
+"#;
+    let result =
+        gtd_in_open_document("file:///tmp/Shop.java", "java", content, Position::new(8, 29)).await;
+
+    let location = match result {
+        Some(GotoDefinitionResponse::Scalar(loc)) => loc,
+        other => panic!("Expected scalar location, got {other:?}"),
+    };
+    assert!(location.uri.path().ends_with("demo/Purchase.java"));
+    assert_eq!(location.range.start.line, 6);
+}
+
+#[tokio::test]
+async fn gtd_local_variable_in_standalone_file() {
+    let server = get_standalone_test_server().await;
+    let uri = Url::parse("file:///tmp/lspintar-standalone/Scratch.java").unwrap();
+    let content = r#"public class Scratch {
+    void run() {
+        String greeting = "hi";
+        System.out.println(greeting);
+    }
+}
+"#;
+    server
+        .backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "java".to_string(),
+                version: 1,
+                text: content.to_string(),
+            },
+        })
+        .await;
+
+    let params = GotoDefinitionParams {
+        text_document_position_params: TextDocumentPositionParams {
+            text_document: TextDocumentIdentifier { uri: uri.clone() },
+            position: Position::new(3, 30),
+        },
+        work_done_progress_params: WorkDoneProgressParams::default(),
+        partial_result_params: PartialResultParams::default(),
+    };
+    let result = server.backend.goto_definition(params).await.unwrap();
+    let location = match result {
+        Some(GotoDefinitionResponse::Scalar(loc)) => loc,
+        other => panic!("Expected scalar location, got {other:?}"),
+    };
+
+    assert_eq!(location.uri, uri);
+    assert_eq!(location.range.start, Position::new(2, 15));
+}
+
+#[tokio::test]
+async fn gtd_duplicate_fqn_prefers_requesting_source_set() {
+    let content = r#"package com.example;
+
+class ClockUser {
+    Clock clock = new Clock();
+}
+"#;
+    for (source_set, expected) in [("main", "src/main/java"), ("test", "src/test/java")] {
+        let uri = format!("file:///tmp/app/src/{source_set}/java/com/example/ClockUser.java");
+        let result = gtd_in_open_document(&uri, "java", content, Position::new(3, 5)).await;
+        let location = match result {
+            Some(GotoDefinitionResponse::Scalar(loc)) => loc,
+            other => panic!("Expected scalar location, got {other:?}"),
+        };
+
+        assert!(
+            location
+                .uri
+                .path()
+                .ends_with(&format!("{expected}/com/example/demo/Clock.java")),
+            "{source_set}: {}",
+            location.uri
+        );
+    }
+}
+
+#[tokio::test]
+async fn gtd_wildcard_ambiguous_type_lists_candidates() {
+    let server = get_test_server("polyglot-spring").await;
+    let uri = open_ambiguous_greeter_client(&server).await;
+    let result = server
+        .backend
+        .goto_definition(GotoDefinitionParams {
+            text_document_position_params: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier { uri },
+                position: Position::new(6, 6),
+            },
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+        })
+        .await
+        .unwrap();
+
+    let locations = match result {
+        Some(GotoDefinitionResponse::Array(locations)) => locations,
+        other => panic!("Expected a location per candidate, got {other:?}"),
+    };
+    let mut paths: Vec<String> = locations.iter().map(|l| l.uri.path().to_string()).collect();
+    paths.sort();
+    assert_eq!(paths.len(), 2, "got: {paths:?}");
+    assert!(paths[0].ends_with("com/example/demo/Greeter.java"), "{paths:?}");
+    assert!(paths[1].ends_with("com/other/Greeter.java"), "{paths:?}");
+}
+
+#[tokio::test]
+async fn gtd_expect_declaration_lists_actuals() {
+    let server = get_test_server("polyglot-spring").await;
+    let root = env::current_dir().expect("cannot get current dir");
+    let fixture = root.join("tests/fixtures/polyglot-spring/src");
+
+    let params = GotoDefinitionParams {
+        text_document_position_params: TextDocumentPositionParams {
+            text_document: TextDocumentIdentifier {
+                uri: Url::from_file_path(
+                    fixture.join("commonMain/kotlin/com/example/demo/Platform.kt"),
+                )
+                .unwrap(),
+            },
+            position: Position::new(2, 15),
+        },
+        work_done_progress_params: WorkDoneProgressParams::default(),
+        partial_result_params: PartialResultParams::default(),
+    };
+
+    let result = server.backend.goto_definition(params).await.unwrap();
+    let location = match result {
+        Some(GotoDefinitionResponse::Scalar(loc)) => loc,
+        other => panic!("Expected scalar location, got {other:?}"),
+    };
+
+    assert_eq!(
+        location.uri,
+        Url::from_file_path(fixture.join("jvmMain/kotlin/com/example/demo/Platform.kt")).unwrap()
+    );
+    assert_eq!(location.range.start.line, 2);
+}
+
+#[tokio::test]
+async fn gtd_type_alias_and_import_alias_resolve_to_aliased_declaration() {
+    let server = get_test_server("polyglot-spring").await;
+    let root = env::current_dir().expect("cannot get current dir");
+    let kotlin = root.join("tests/fixtures/polyglot-spring/src/main/kotlin/com/example/demo");
+
+    // `UserStore` is a type alias, `Member` an import alias.
+    for (position, expected_file, expected_line) in [
+        (Position::new(7, 42), "UserRepository.kt", 5),
+        (Position::new(8, 19), "User.kt", 2),
+    ] {
+        let params = GotoDefinitionParams {
+            text_document_position_params: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier {
+                    uri: Url::from_file_path(kotlin.join("MemberRegistry.kt")).unwrap(),
+                },
+                position,
+            },
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+        };
+
+        let result = server.backend.goto_definition(params).await.unwrap();
+        let location = match result {
+            Some(GotoDefinitionResponse::Scalar(loc)) => loc,
+            other => panic!("Expected scalar location, got {other:?}"),
+        };
+
+        assert_eq!(
+            location.uri,
+            Url::from_file_path(kotlin.join(expected_file)).unwrap()
+        );
+        assert_eq!(location.range.start.line, expected_line);
+    }
+}
+
+#[tokio::test]
+async fn gtd_super_and_constructor_delegation() {
+    let server = get_test_server("polyglot-spring").await;
+    let root = env::current_dir().expect("cannot get current dir");
+    let java = root.join("tests/fixtures/polyglot-spring/src/main/java/com/example/demo");
+
+    // `this("!")`, `super()` with only an implicit constructor, `super.greet(name)`.
+    for (position, expected_file, expected_start) in [
+        (
+            Position::new(6, 8),
+            "LoudGreeter.java",
+            Position::new(9, 11),
+        ),
+        (
+            Position::new(10, 8),
+            "FormalGreeter.java",
+            Position::new(2, 13),
+        ),
+        (
+            Position::new(15, 21),
+            "FormalGreeter.java",
+            Position::new(4, 18),
+        ),
+    ] {
+        let params = GotoDefinitionParams {
+            text_document_position_params: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier {
+                    uri: Url::from_file_path(java.join("LoudGreeter.java")).unwrap(),
+                },
+                position,
+            },
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+        };
+
+        let result = server.backend.goto_definition(params).await.unwrap();
+        let location = match result {
+            Some(GotoDefinitionResponse::Scalar(loc)) => loc,
+            other => panic!("Expected scalar location, got {other:?}"),
+        };
+
+        assert_eq!(
+            location.uri,
+            Url::from_file_path(java.join(expected_file)).unwrap()
+        );
+        assert_eq!(location.range.start, expected_start, "{position:?}");
+    }
+}
@@ -1,5 +1,6 @@
 use std::env;
 
+use lspintar_server::implementable::ImplementableKind;
 use pretty_assertions::assert_eq;
 use tower_lsp::{
     LanguageServer,
@@ -136,3 +137,79 @@ async fn gti_method_implementation() {
 
     assert_eq!(result.unwrap(), GotoImplementationResponse::from(location));
 }
+
+/// Go-to-implementation on a Java `default` method lists the declaration, the
+/// overriding method, and the class inheriting the default (at its class name).
+/// `LoudGreeter` inherits `FormalGreeter`'s override, so it is not listed.
+#[tokio::test]
+async fn gti_default_method_lists_overrides_and_inheritors() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let root = env::current_dir().expect("cannot get current dir");
+    let demo = root.join("tests/fixtures/polyglot-spring/src/main/java/com/example/demo");
+
+    let params = GotoImplementationParams {
+        text_document_position_params: TextDocumentPositionParams {
+            text_document: TextDocumentIdentifier {
+                uri: Url::from_file_path(demo.join("Greeter.java"))
+                    .expect("cannot parse root URI"),
+            },
+            position: Position::new(3, 20),
+        },
+        work_done_progress_params: WorkDoneProgressParams::default(),
+        partial_result_params: PartialResultParams::default(),
+    };
+
+    let result = server.backend.goto_implementation(params).await.unwrap();
+
+    let location = |file: &str, line: u32, start: u32, end: u32| {
+        Location::new(
+            Url::from_file_path(demo.join(file)).unwrap(),
+            Range {
+                start: Position::new(line, start),
+                end: Position::new(line, end),
+            },
+        )
+    };
+    assert_eq!(
+        result,
+        Some(GotoImplementationResponse::Array(vec![
+            location("Greeter.java", 3, 19, 24),
+            location("FormalGreeter.java", 4, 18, 23),
+            location("PlainGreeter.java", 2, 13, 25),
+        ]))
+    );
+}
+
+#[tokio::test]
+async fn implementable_symbols_count_subtypes_and_overrides() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let root = env::current_dir().expect("cannot get current dir");
+    let demo = root.join("tests/fixtures/polyglot-spring/src/main/java/com/example/demo");
+    let uri = Url::from_file_path(demo.join("Greeter.java")).expect("cannot parse root URI");
+
+    let symbols = server.backend.implementable_symbols(&uri).await;
+
+    let summary: Vec<(&str, ImplementableKind, usize)> = symbols
+        .iter()
+        .map(|s| (s.name.as_str(), s.kind, s.count))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("Greeter", ImplementableKind::Type, 3),
+            ("greet", ImplementableKind::Method, 1),
+        ]
+    );
+    assert_eq!(
+        symbols[1].targets,
+        vec![Location::new(
+            Url::from_file_path(demo.join("FormalGreeter.java")).unwrap(),
+            Range {
+                start: Position::new(4, 18),
+                end: Position::new(4, 23),
+            },
+        )]
+    );
+}
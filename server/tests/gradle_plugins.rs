@@ -0,0 +1,176 @@
+use std::fs;
+
+use lspintar_server::gradle_plugins::{
+    PluginRequest, included_builds, is_core_plugin, local_plugins, plugin_registrations,
+    plugin_requests,
+};
+use tower_lsp::{
+    LanguageServer,
+    lsp_types::{
+        GotoDefinitionParams, GotoDefinitionResponse, HoverContents, HoverParams, Location,
+        PartialResultParams, Position, Range, TextDocumentIdentifier, TextDocumentPositionParams,
+        Url, WorkDoneProgressParams,
+    },
+};
+
+use crate::util::get_test_server;
+
+mod util;
+
+fn position_params(uri: Url, position: Position) -> TextDocumentPositionParams {
+    TextDocumentPositionParams {
+        text_document: TextDocumentIdentifier { uri },
+        position,
+    }
+}
+
+#[test]
+fn plugin_requests_in_both_dsls() {
+    let groovy =
+        "plugins {\n    id 'java'\n    id 'com.github.johnrengelman.shadow' version '8.1.1'\n}\n";
+    assert_eq!(
+        plugin_requests(groovy),
+        vec![
+            PluginRequest {
+                id: "java".to_string(),
+                version: None,
+                range: Range::new(Position::new(1, 8), Position::new(1, 12)),
+            },
+            PluginRequest {
+                id: "com.github.johnrengelman.shadow".to_string(),
+                version: Some("8.1.1".to_string()),
+                range: Range::new(Position::new(2, 8), Position::new(2, 39)),
+            },
+        ]
+    );
+
+    let kotlin = "plugins {\n    kotlin(\"jvm\") version \"1.9.22\"\n    id(\"org.springframework.boot\") version \"3.2.0\" apply false\n}\n\ndependencies {\n    implementation(\"org.slf4j:slf4j-api:2.0.9\")\n}\n";
+    assert_eq!(
+        plugin_requests(kotlin),
+        vec![
+            PluginRequest {
+                id: "org.jetbrains.kotlin.jvm".to_string(),
+                version: Some("1.9.22".to_string()),
+                range: Range::new(Position::new(1, 12), Position::new(1, 15)),
+            },
+            PluginRequest {
+                id: "org.springframework.boot".to_string(),
+                version: Some("3.2.0".to_string()),
+                range: Range::new(Position::new(2, 8), Position::new(2, 32)),
+            },
+        ]
+    );
+
+    assert!(is_core_plugin("java-library"));
+    assert!(is_core_plugin(
+        "org.gradle.toolchains.foojay-resolver-convention"
+    ));
+    assert!(!is_core_plugin("org.springframework.boot"));
+}
+
+#[test]
+fn plugin_registrations_and_included_builds() {
+    let build = "gradlePlugin {\n    plugins {\n        create(\"conventions\") {\n            id = \"com.example.conventions\"\n            implementationClass = \"com.example.ConventionsPlugin\"\n        }\n    }\n}\n";
+    assert_eq!(
+        plugin_registrations(build),
+        vec![(
+            "com.example.conventions".to_string(),
+            Range::new(Position::new(3, 18), Position::new(3, 41)),
+            Some("com.example.ConventionsPlugin".to_string()),
+        )]
+    );
+    // A registration is not a request.
+    assert!(plugin_requests(build).is_empty());
+
+    let settings = "pluginManagement {\n    includeBuild(\"build-logic\")\n}\nincludeBuild 'tools'\ninclude 'app'\n";
+    assert_eq!(included_builds(settings), vec!["build-logic", "tools"]);
+}
+
+#[tokio::test]
+async fn plugin_ids_resolve_to_local_plugins() {
+    let server = get_test_server("polyglot-spring").await;
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().canonicalize().unwrap();
+    fs::write(
+        root.join("settings.gradle.kts"),
+        "includeBuild(\"build-logic\")\ninclude(\"app\")\n",
+    )
+    .unwrap();
+    let scripts = root.join("buildSrc/src/main/kotlin");
+    fs::create_dir_all(&scripts).unwrap();
+    fs::write(
+        scripts.join("java-conventions.gradle.kts"),
+        "plugins {\n    java\n}\n",
+    )
+    .unwrap();
+    fs::create_dir_all(root.join("build-logic")).unwrap();
+    fs::write(
+        root.join("build-logic/build.gradle.kts"),
+        "gradlePlugin {\n    plugins {\n        create(\"release\") {\n            id = \"com.example.release\"\n            implementationClass = \"com.example.ReleasePlugin\"\n        }\n    }\n}\n",
+    )
+    .unwrap();
+    fs::create_dir_all(root.join("app")).unwrap();
+    fs::write(
+        root.join("app/build.gradle.kts"),
+        "plugins {\n    id(\"java-conventions\")\n    id(\"com.example.release\")\n}\n",
+    )
+    .unwrap();
+    let build = Url::from_file_path(root.join("app/build.gradle.kts")).unwrap();
+
+    let ids: Vec<String> = local_plugins(&root).into_iter().map(|p| p.id).collect();
+    assert!(ids.contains(&"java-conventions".to_string()), "{ids:?}");
+    assert!(ids.contains(&"com.example.release".to_string()), "{ids:?}");
+
+    let result = server
+        .backend
+        .goto_definition(GotoDefinitionParams {
+            text_document_position_params: position_params(build.clone(), Position::new(1, 12)),
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+        })
+        .await
+        .unwrap();
+    let start = Position::new(0, 0);
+    assert_eq!(
+        result,
+        Some(GotoDefinitionResponse::from(Location::new(
+            Url::from_file_path(scripts.join("java-conventions.gradle.kts")).unwrap(),
+            Range::new(start, start),
+        )))
+    );
+
+    let result = server
+        .backend
+        .goto_definition(GotoDefinitionParams {
+            text_document_position_params: position_params(build.clone(), Position::new(2, 12)),
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+        })
+        .await
+        .unwrap();
+    let start = Position::new(3, 18);
+    assert_eq!(
+        result,
+        Some(GotoDefinitionResponse::from(Location::new(
+            Url::from_file_path(root.join("build-logic/build.gradle.kts")).unwrap(),
+            Range::new(start, start),
+        )))
+    );
+
+    let hover = server
+        .backend
+        .hover(HoverParams {
+            text_document_position_params: position_params(build, Position::new(2, 12)),
+            work_done_progress_params: WorkDoneProgressParams::default(),
+        })
+        .await
+        .unwrap()
+        .expect("expected a hover on the plugin id");
+    let HoverContents::Markup(markup) = hover.contents else {
+        panic!("expected markdown");
+    };
+    assert_eq!(
+        markup.value,
+        "**com.example.release**\n\nImplementation class: `com.example.ReleasePlugin`"
+    );
+}
@@ -0,0 +1,92 @@
+use std::fs;
+
+use tower_lsp::{
+    LanguageServer,
+    lsp_types::{
+        GotoDefinitionParams, GotoDefinitionResponse, Location, PartialResultParams, Position,
+        Range, TextDocumentIdentifier, TextDocumentPositionParams, Url, WorkDoneProgressParams,
+    },
+};
+
+use crate::util::get_test_server;
+
+mod util;
+
+fn params(uri: Url, position: Position) -> GotoDefinitionParams {
+    GotoDefinitionParams {
+        text_document_position_params: TextDocumentPositionParams {
+            text_document: TextDocumentIdentifier { uri },
+            position,
+        },
+        work_done_progress_params: WorkDoneProgressParams::default(),
+        partial_result_params: PartialResultParams::default(),
+    }
+}
+
+#[tokio::test]
+async fn task_reference_resolves_to_registration_in_applied_script() {
+    let server = get_test_server("polyglot-spring").await;
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().canonicalize().unwrap();
+    fs::create_dir(root.join("gradle")).unwrap();
+    fs::write(
+        root.join("gradle/docs.gradle"),
+        "tasks.register('docs', Copy) {\n    from 'src/docs'\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("build.gradle"),
+        "apply from: 'gradle/docs.gradle'\n\ntasks.named(\"build\") {\n    dependsOn 'docs', 'jar'\n}\n",
+    )
+    .unwrap();
+    let build = Url::from_file_path(root.join("build.gradle")).unwrap();
+
+    let result = server
+        .backend
+        .goto_definition(params(build.clone(), Position::new(3, 16)))
+        .await
+        .unwrap();
+    let start = Position::new(0, 16);
+    assert_eq!(
+        result,
+        Some(GotoDefinitionResponse::from(Location::new(
+            Url::from_file_path(root.join("gradle/docs.gradle")).unwrap(),
+            Range::new(start, start),
+        )))
+    );
+
+    // `jar` comes from a plugin; no script registers it.
+    let result = server
+        .backend
+        .goto_definition(params(build, Position::new(3, 24)))
+        .await
+        .unwrap();
+    assert_eq!(result, None);
+}
+
+#[tokio::test]
+async fn task_registrations_in_kotlin_dsl() {
+    let server = get_test_server("polyglot-spring").await;
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().canonicalize().unwrap();
+    fs::write(
+        root.join("build.gradle.kts"),
+        "val docs by tasks.registering\n\ntasks.register<Copy>(\"site\") {\n    dependsOn(docs, \"docs\")\n}\n",
+    )
+    .unwrap();
+    let build = Url::from_file_path(root.join("build.gradle.kts")).unwrap();
+
+    let result = server
+        .backend
+        .goto_definition(params(build.clone(), Position::new(3, 22)))
+        .await
+        .unwrap();
+    let start = Position::new(0, 4);
+    assert_eq!(
+        result,
+        Some(GotoDefinitionResponse::from(Location::new(
+            build,
+            Range::new(start, start),
+        )))
+    );
+}
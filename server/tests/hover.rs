@@ -4,8 +4,9 @@ use pretty_assertions::assert_eq;
 use tower_lsp::{
     LanguageServer,
     lsp_types::{
-        Hover, HoverContents, HoverParams, MarkupContent, MarkupKind, Position,
-        TextDocumentIdentifier, TextDocumentPositionParams, Url, WorkDoneProgressParams,
+        DidOpenTextDocumentParams, Hover, HoverContents, HoverParams, MarkupContent, MarkupKind,
+        Position, TextDocumentIdentifier, TextDocumentItem, TextDocumentPositionParams, Url,
+        WorkDoneProgressParams,
     },
 };
 
@@ -172,3 +173,252 @@ async fn hover_method() {
 
     assert_eq!(result.unwrap(), hover);
 }
+
+#[tokio::test]
+async fn hover_alias_shows_alias_and_aliased_type() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let root = env::current_dir().expect("cannot get current dir");
+    let uri = Url::from_file_path(root.join(
+        "tests/fixtures/polyglot-spring/src/main/kotlin/com/example/demo/MemberRegistry.kt",
+    ))
+    .expect("cannot parse root URI");
+
+    for (position, alias, aliased) in [
+        (
+            Position::new(7, 42),
+            "typealias UserStore = UserRepository",
+            "class UserRepository",
+        ),
+        (
+            Position::new(8, 19),
+            "import com.example.User as Member",
+            "class User",
+        ),
+    ] {
+        let params = HoverParams {
+            text_document_position_params: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier { uri: uri.clone() },
+                position,
+            },
+            work_done_progress_params: WorkDoneProgressParams::default(),
+        };
+
+        let result = server.backend.hover(params).await.unwrap();
+        let markup = match result {
+            Some(Hover {
+                contents: HoverContents::Markup(markup),
+                ..
+            }) => markup,
+            other => panic!("Expected markup hover, got {other:?}"),
+        };
+        assert!(markup.value.contains(alias), "{}", markup.value);
+        assert!(markup.value.contains(aliased), "{}", markup.value);
+    }
+}
+
+#[tokio::test]
+async fn hover_constant_shows_value() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let root = env::current_dir().expect("cannot get current dir");
+    let java = Url::from_file_path(
+        root.join("tests/fixtures/polyglot-spring/src/main/java/com/example/demo/Versions.java"),
+    )
+    .expect("cannot parse root URI");
+    let kotlin = Url::from_file_path(
+        root.join("tests/fixtures/polyglot-spring/src/main/kotlin/com/example/demo/Limits.kt"),
+    )
+    .expect("cannot parse root URI");
+
+    for (uri, position, expected) in [
+        (&java, Position::new(8, 16), "String RELEASE = \"v2.0\""),
+        (&java, Position::new(8, 32), "long BUILD = 42L"),
+        (&kotlin, Position::new(6, 26), "MAX_RETRIES = 3"),
+        (&kotlin, Position::new(8, 31), "GREETING = \"Hello, world\""),
+    ] {
+        let params = HoverParams {
+            text_document_position_params: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier { uri: uri.clone() },
+                position,
+            },
+            work_done_progress_params: WorkDoneProgressParams::default(),
+        };
+
+        let result = server.backend.hover(params).await.unwrap();
+        let markup = match result {
+            Some(Hover {
+                contents: HoverContents::Markup(markup),
+                ..
+            }) => markup,
+            other => panic!("Expected markup hover, got {other:?}"),
+        };
+        assert!(markup.value.contains(expected), "{}", markup.value);
+    }
+}
+
+#[tokio::test]
+async fn hover_enum_constant_shows_ordinal_and_arguments() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let root = env::current_dir().expect("cannot get current dir");
+    let java = Url::from_file_path(
+        root.join("tests/fixtures/polyglot-spring/src/main/java/com/example/demo/Priority.java"),
+    )
+    .expect("cannot parse root URI");
+    let kotlin = Url::from_file_path(
+        root.join("tests/fixtures/polyglot-spring/src/main/kotlin/com/example/demo/Shade.kt"),
+    )
+    .expect("cannot parse root URI");
+
+    for (uri, position, expected) in [
+        (
+            &java,
+            Position::new(15, 25),
+            "```java\npackage com.example\n\nenum Priority\n    HIGH(10, \"high\")\n```\nOrdinal: 1",
+        ),
+        (
+            &java,
+            Position::new(3, 5),
+            "```java\npackage com.example\n\nenum Priority\n    LOW(1, \"low\")\n```\nOrdinal: 0",
+        ),
+        (
+            &kotlin,
+            Position::new(6, 53),
+            "```kotlin\npackage com.example\n\nenum class Shade\n    DARK(\"#000000\")\n```\nOrdinal: 1",
+        ),
+        (
+            &kotlin,
+            Position::new(6, 63),
+            "```kotlin\npackage com.example\n\nenum class Shade\n    LIGHT(\"#FFFFFF\")\n```\nOrdinal: 0",
+        ),
+    ] {
+        let params = HoverParams {
+            text_document_position_params: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier { uri: uri.clone() },
+                position,
+            },
+            work_done_progress_params: WorkDoneProgressParams::default(),
+        };
+
+        let result = server.backend.hover(params).await.unwrap();
+        let markup = match result {
+            Some(Hover {
+                contents: HoverContents::Markup(markup),
+                ..
+            }) => markup,
+            other => panic!("Expected markup hover, got {other:?}"),
+        };
+        assert_eq!(markup.value, expected);
+    }
+}
+
+#[tokio::test]
+async fn hover_java_method_shows_nullability_as_kotlin_sees_it() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let uri = Url::parse("file:///tmp/EmailHover.kt").unwrap();
+    let content = r#"package com.example
+
+class EmailHover(private val directory: UserDirectory) {
+    fun show(name: String) {
+        println(directory.findEmail(name))
+        println(directory.displayName(name))
+    }
+}
+"#;
+    server
+        .backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "kotlin".to_string(),
+                version: 1,
+                text: content.to_string(),
+            },
+        })
+        .await;
+
+    for (position, nullability, kotlin_type) in [
+        (
+            Position::new(4, 28),
+            "**Nullable** (`@Nullable`)",
+            "```kotlin\nString?\n```",
+        ),
+        (
+            Position::new(5, 28),
+            "**Platform type**",
+            "```kotlin\nString!\n```",
+        ),
+    ] {
+        let params = HoverParams {
+            text_document_position_params: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier { uri: uri.clone() },
+                position,
+            },
+            work_done_progress_params: WorkDoneProgressParams::default(),
+        };
+
+        let result = server.backend.hover(params).await.unwrap();
+        let markup = match result {
+            Some(Hover {
+                contents: HoverContents::Markup(markup),
+                ..
+            }) => markup,
+            other => panic!("Expected markup hover, got {other:?}"),
+        };
+        assert!(markup.value.contains(nullability), "{}", markup.value);
+        assert!(markup.value.ends_with(kotlin_type), "{}", markup.value);
+    }
+}
+
+#[tokio::test]
+async fn hover_annotation_attribute_name_and_enum_value() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let uri = Url::parse("file:///tmp/AuditedHover.java").unwrap();
+    let content = r#"package com.example;
+
+@Audited(level = AuditLevel.FULL)
+public class AuditedHover {}
+"#;
+    server
+        .backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "java".to_string(),
+                version: 1,
+                text: content.to_string(),
+            },
+        })
+        .await;
+
+    for (position, expected) in [
+        (
+            Position::new(2, 11),
+            "```java\nAuditLevel level\n```\nElement of `@com.example.Audited`",
+        ),
+        (
+            Position::new(2, 30),
+            "```java\npackage com.example\n\nenum AuditLevel\n    FULL\n```\nOrdinal: 1",
+        ),
+    ] {
+        let params = HoverParams {
+            text_document_position_params: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier { uri: uri.clone() },
+                position,
+            },
+            work_done_progress_params: WorkDoneProgressParams::default(),
+        };
+
+        let markup = match server.backend.hover(params).await.unwrap() {
+            Some(Hover {
+                contents: HoverContents::Markup(markup),
+                ..
+            }) => markup,
+            other => panic!("Expected markup hover, got {other:?}"),
+        };
+        assert_eq!(markup.value, expected);
+    }
+}
@@ -0,0 +1,42 @@
+use std::collections::HashMap;
+
+use lspintar_server::import_preferences::ImportPreferences;
+
+#[test]
+fn import_preferences_default_to_project_first() {
+    let preferences: ImportPreferences = serde_json::from_value(serde_json::json!({})).unwrap();
+    assert_eq!(preferences, ImportPreferences::default());
+    assert!(preferences.prefer_project_types);
+
+    let mut candidates = vec![("org.lib.Widget", true), ("com.example.Widget", false)];
+    candidates.sort_by(|a, b| preferences.compare(*a, *b, &HashMap::new()));
+    assert_eq!(candidates[0].0, "com.example.Widget");
+}
+
+#[test]
+fn configured_packages_outrank_import_counts() {
+    let preferences: ImportPreferences = serde_json::from_value(serde_json::json!({
+        "preferred_packages": ["java.util"],
+        "demoted_packages": ["java.awt"],
+    }))
+    .unwrap();
+
+    let counts = HashMap::from([
+        ("java.awt.List".to_string(), 5),
+        ("com.example.List".to_string(), 2),
+    ]);
+    let mut candidates = vec![
+        ("java.awt.List", true),
+        ("com.example.List", false),
+        ("java.util.List", true),
+    ];
+    candidates.sort_by(|a, b| preferences.compare(*a, *b, &counts));
+    assert_eq!(
+        candidates.iter().map(|(fqn, _)| *fqn).collect::<Vec<_>>(),
+        vec!["java.util.List", "com.example.List", "java.awt.List"]
+    );
+
+    // Subpackages match; a shared name prefix does not.
+    assert_eq!(preferences.package_rank("java.util.concurrent.Future"), 0);
+    assert_eq!(preferences.package_rank("java.utility.Helper"), 1);
+}
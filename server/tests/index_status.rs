@@ -0,0 +1,59 @@
+use lspintar_server::index_status::{IndexingSettings, IndexingStatus, WhileIndexing};
+use tower_lsp::{
+    LanguageServer,
+    lsp_types::{
+        GotoDefinitionParams, PartialResultParams, Position, TextDocumentIdentifier,
+        TextDocumentPositionParams, Url, WorkDoneProgressParams,
+    },
+};
+
+use crate::util::get_test_server;
+
+mod util;
+
+#[tokio::test]
+async fn index_status_lists_each_subproject() {
+    let server = get_test_server("groovy-gradle-multi").await;
+
+    let projects = server.backend.index_status().await.unwrap();
+
+    let names: Vec<&str> = projects.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, vec!["groovy-gradle-multi", "api", "app", "core"]);
+    assert!(
+        projects
+            .iter()
+            .all(|p| p.indexing_status == IndexingStatus::Done)
+    );
+}
+
+#[test]
+fn indexing_settings_default_to_partial_results() {
+    let settings: IndexingSettings = serde_json::from_value(serde_json::json!({})).unwrap();
+    assert_eq!(settings.while_indexing, WhileIndexing::Partial);
+
+    let settings: IndexingSettings =
+        serde_json::from_value(serde_json::json!({ "while_indexing": "wait" })).unwrap();
+    assert_eq!(settings.while_indexing, WhileIndexing::Wait);
+}
+
+#[tokio::test]
+async fn definition_is_not_held_back_once_indexed() {
+    let server = get_test_server("groovy-gradle-multi").await;
+    let root = std::env::current_dir().unwrap();
+    let uri = Url::from_file_path(root.join(
+        "tests/fixtures/groovy-gradle-multi/app/src/main/groovy/com/example/app/Application.groovy",
+    ))
+    .unwrap();
+
+    let request = server.backend.goto_definition(GotoDefinitionParams {
+        text_document_position_params: TextDocumentPositionParams {
+            text_document: TextDocumentIdentifier { uri },
+            position: Position::new(0, 0),
+        },
+        work_done_progress_params: WorkDoneProgressParams::default(),
+        partial_result_params: PartialResultParams::default(),
+    });
+
+    let result = tokio::time::timeout(std::time::Duration::from_secs(5), request).await;
+    assert!(result.is_ok(), "definition waited on an indexed project");
+}
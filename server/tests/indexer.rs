@@ -4,6 +4,8 @@ use kotlin::KotlinSupport;
 use lsp_core::build_tools::{BuildToolHandler, gradle::GradleHandler};
 use lspintar_server::{
     Indexer, Repository,
+    indexer::without_workspace_outputs,
+    lsp_convert::AsLspLocation,
     models::{
         external_symbol::ExternalSymbol,
         symbol::{Symbol, SymbolMetadata, SymbolParameter},
@@ -11,7 +13,10 @@ use lspintar_server::{
 };
 use pretty_assertions::assert_eq;
 use sqlx::types::Json;
-use std::{path::Path, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 use uuid::Uuid;
 
 #[tokio::test]
@@ -70,6 +75,9 @@ async fn index_groovy_class() {
                 type_params: None,
                 generic_param_types: None,
                 method_type_params: None,
+                constant_value: None,
+                entity_name: None,
+                synthetic_accessor: None,
             }),
             last_modified: 0,
         }
@@ -129,6 +137,9 @@ async fn index_groovy_gradle_single_workspace() {
                 type_params: None,
                 generic_param_types: None,
                 method_type_params: None,
+                constant_value: None,
+                entity_name: None,
+                synthetic_accessor: None,
             }),
             last_modified: 0,
         }
@@ -175,6 +186,9 @@ async fn index_groovy_gradle_single_workspace() {
                 type_params: None,
                 generic_param_types: None,
                 method_type_params: None,
+                constant_value: None,
+                entity_name: None,
+                synthetic_accessor: None,
             }),
             last_modified: 0,
         }
@@ -221,6 +235,9 @@ async fn index_groovy_gradle_single_workspace() {
                 type_params: None,
                 generic_param_types: None,
                 method_type_params: None,
+                constant_value: None,
+                entity_name: None,
+                synthetic_accessor: None,
             }),
             last_modified: 0,
         }
@@ -267,6 +284,9 @@ async fn index_groovy_gradle_single_workspace() {
                 type_params: None,
                 generic_param_types: None,
                 method_type_params: None,
+                constant_value: None,
+                entity_name: None,
+                synthetic_accessor: None,
             }),
             last_modified: 0,
         }
@@ -326,6 +346,9 @@ async fn index_groovy_class_multi_project() {
                 type_params: None,
                 generic_param_types: None,
                 method_type_params: None,
+                constant_value: None,
+                entity_name: None,
+                synthetic_accessor: None,
             }),
             last_modified: 0,
         }
@@ -385,6 +408,9 @@ async fn index_groovy_method() {
                 type_params: None,
                 generic_param_types: None,
                 method_type_params: None,
+                constant_value: None,
+                entity_name: None,
+                synthetic_accessor: None,
             }),
             last_modified: 0,
         }
@@ -444,6 +470,9 @@ async fn index_groovy_nested_class() {
                 type_params: None,
                 generic_param_types: None,
                 method_type_params: None,
+                constant_value: None,
+                entity_name: None,
+                synthetic_accessor: None,
             }),
             last_modified: 0,
         }
@@ -503,6 +532,9 @@ async fn index_groovy_field() {
                 type_params: None,
                 generic_param_types: None,
                 method_type_params: None,
+                constant_value: Some("1000".to_string()),
+                entity_name: None,
+                synthetic_accessor: None,
             }),
             last_modified: 0,
         }
@@ -567,6 +599,9 @@ async fn index_groovy_inheritance() {
                 type_params: None,
                 generic_param_types: None,
                 method_type_params: None,
+                constant_value: None,
+                entity_name: None,
+                synthetic_accessor: None,
             }),
             last_modified: 0,
         }
@@ -602,6 +637,9 @@ async fn index_groovy_inheritance() {
                 type_params: None,
                 generic_param_types: None,
                 method_type_params: None,
+                constant_value: None,
+                entity_name: None,
+                synthetic_accessor: None,
             }),
             last_modified: 0,
         }
@@ -683,6 +721,9 @@ async fn index_kotlin_data_class() {
                 type_params: None,
                 generic_param_types: None,
                 method_type_params: None,
+                constant_value: None,
+                entity_name: None,
+                synthetic_accessor: None,
             }),
             last_modified: 0,
         }
@@ -728,6 +769,9 @@ async fn index_kotlin_data_class() {
                 type_params: None,
                 generic_param_types: None,
                 method_type_params: None,
+                constant_value: None,
+                entity_name: None,
+                synthetic_accessor: None,
             }),
             last_modified: 0,
         }
@@ -814,6 +858,9 @@ async fn index_external_dep_source_jar() {
                 type_params: None,
                 generic_param_types: None,
                 method_type_params: None,
+                constant_value: None,
+                entity_name: None,
+                synthetic_accessor: None,
             },),
             last_modified: 0,
             file_type: "java".to_string(),
@@ -897,6 +944,9 @@ async fn index_external_dep_jar() {
                 type_params: None,
                 generic_param_types: None,
                 method_type_params: None,
+                constant_value: None,
+                entity_name: None,
+                synthetic_accessor: None,
             },),
             last_modified: 0,
             file_type: "java".to_string(),
@@ -904,6 +954,143 @@ async fn index_external_dep_jar() {
     );
 }
 
+#[tokio::test]
+async fn external_class_sources_are_parsed_once() {
+    let db_name = Uuid::new_v4();
+    let db_dir = format!("file:{}?mode=memory", db_name);
+    let repo = Arc::new(Repository::new(&db_dir).await.unwrap());
+    let path = Path::new("tests/fixtures/groovy-gradle-single");
+
+    let gradle_handler = GradleHandler;
+    let dep_jars = gradle_handler.get_dependency_paths(&path).unwrap();
+
+    let (jar_path, src_jar_path) = dep_jars
+        .into_iter()
+        .find_map(|(jar, src)| match (jar, src) {
+            (Some(jar), Some(src)) if jar.to_string_lossy().contains("groovy-json") => {
+                Some((jar, src))
+            }
+            _ => None,
+        })
+        .expect("groovy-json jars not found");
+    let src_jar = src_jar_path.to_string_lossy().to_string();
+
+    let mut indexer = Indexer::new(Arc::clone(&repo));
+    indexer.register_language("groovy", Arc::new(GroovySupport::new()));
+    indexer.register_language("java", Arc::new(JavaSupport::new()));
+    indexer
+        .index_external_deps(
+            vec![(Some(jar_path), Some(src_jar_path))],
+            |_, _| {},
+            |_, _| {},
+        )
+        .await;
+
+    let symbol = repo
+        .find_external_symbol_by_fqn("groovy.json.JsonBuilder")
+        .await
+        .expect("Query failed")
+        .expect("External symbol should be found");
+    assert!(symbol.needs_decompilation);
+    assert!(
+        !repo
+            .is_external_class_source_parsed(&src_jar, "groovy.json.JsonBuilder")
+            .await
+            .unwrap()
+    );
+
+    let enriched = symbol.with_sources(Some(&indexer)).await;
+
+    assert!(!enriched.needs_decompilation);
+    assert_eq!(enriched.source_file_path, "groovy/json/JsonBuilder.java");
+    assert!(
+        repo.is_external_class_source_parsed(&src_jar, "groovy.json.JsonBuilder")
+            .await
+            .unwrap()
+    );
+    let members = repo
+        .find_external_symbols_by_fqn_and_jar("groovy.json.JsonBuilder#toPrettyString", &src_jar)
+        .await
+        .unwrap();
+    assert!(
+        !members.is_empty(),
+        "member signatures of the class should be stored"
+    );
+}
+
+#[tokio::test]
+async fn external_overloads_are_located_by_signature_in_sources() {
+    let db_name = Uuid::new_v4();
+    let db_dir = format!("file:{}?mode=memory", db_name);
+    let repo = Arc::new(Repository::new(&db_dir).await.unwrap());
+    let path = Path::new("tests/fixtures/groovy-gradle-single");
+
+    let gradle_handler = GradleHandler;
+    let dep_jars = gradle_handler.get_dependency_paths(&path).unwrap();
+
+    let (jar_path, src_jar_path) = dep_jars
+        .into_iter()
+        .find_map(|(jar, src)| match (jar, src) {
+            (Some(jar), Some(src)) if jar.to_string_lossy().contains("groovy-json") => {
+                Some((jar, src))
+            }
+            _ => None,
+        })
+        .expect("groovy-json jars not found");
+    let jar = jar_path.to_string_lossy().to_string();
+
+    let mut indexer = Indexer::new(Arc::clone(&repo));
+    indexer.register_language("groovy", Arc::new(GroovySupport::new()));
+    indexer.register_language("java", Arc::new(JavaSupport::new()));
+    indexer
+        .index_external_deps(
+            vec![(Some(jar_path), Some(src_jar_path))],
+            |_, _| {},
+            |_, _| {},
+        )
+        .await;
+
+    let overloads = repo
+        .find_external_symbols_by_fqn_and_jar("groovy.json.JsonBuilder#call", &jar)
+        .await
+        .unwrap();
+    assert!(overloads.len() > 1, "JsonBuilder#call should be overloaded");
+
+    let mut lines = Vec::new();
+    for overload in overloads {
+        let location = overload
+            .with_sources(Some(&indexer))
+            .await
+            .as_lsp_location()
+            .expect("overload should be located");
+        let source = std::fs::read_to_string(location.uri.to_file_path().unwrap()).unwrap();
+        let line = source
+            .lines()
+            .nth(location.range.start.line as usize)
+            .unwrap();
+        let start = location.range.start.character as usize;
+        assert_eq!(&line[start..start + "call".len()], "call");
+
+        let param_type = overload.metadata.parameters.as_ref().unwrap()[0]
+            .type_name
+            .clone()
+            .unwrap();
+        let simple = param_type
+            .rsplit('.')
+            .next()
+            .unwrap()
+            .trim_end_matches("[]");
+        assert!(
+            line.contains(simple),
+            "{param_type} overload located at `{line}`"
+        );
+        lines.push(location.range.start.line);
+    }
+    lines.sort();
+    lines.dedup();
+    assert!(lines.len() > 1, "overloads should not share a location");
+}
+
 #[tokio::test]
 async fn index_jdk_dep_source_jar() {
     let db_name = Uuid::new_v4();
@@ -971,6 +1158,9 @@ async fn index_jdk_dep_source_jar() {
                 type_params: None,
                 generic_param_types: None,
                 method_type_params: None,
+                constant_value: None,
+                entity_name: None,
+                synthetic_accessor: None,
             },),
             last_modified: 0,
             file_type: "java".to_string(),
@@ -1062,9 +1252,242 @@ async fn index_external_annotation_dep_jar() {
                 type_params: None,
                 generic_param_types: None,
                 method_type_params: None,
+                constant_value: None,
+                entity_name: None,
+                synthetic_accessor: None,
             },),
             last_modified: 0,
             file_type: "java".to_string(),
         }
     );
 }
+
+#[tokio::test]
+async fn find_stale_files_compares_content_hashes() {
+    let db_name = Uuid::new_v4();
+    let db_dir = format!("file:{}?mode=memory", db_name);
+    let repo = Arc::new(Repository::new(&db_dir).await.unwrap());
+    let dir = tempfile::tempdir().unwrap();
+    let kept = dir.path().join("Kept.groovy");
+    let edited = dir.path().join("Edited.groovy");
+    let deleted = dir.path().join("Deleted.groovy");
+    std::fs::write(&kept, "package com.example\n\nclass Kept {}\n").unwrap();
+    std::fs::write(&edited, "package com.example\n\nclass Edited {}\n").unwrap();
+    std::fs::write(&deleted, "package com.example\n\nclass Deleted {}\n").unwrap();
+
+    let mut indexer = Indexer::new(Arc::clone(&repo));
+    indexer.register_language("groovy", Arc::new(GroovySupport::new()));
+    indexer
+        .index_workspace(dir.path(), |_, _| {}, |_, _| {})
+        .await
+        .expect("Indexing failed");
+
+    let (changed, removed) = indexer.find_stale_files(dir.path()).await.unwrap();
+    assert!(changed.is_empty(), "unchanged files reported stale: {changed:?}");
+    assert!(removed.is_empty(), "no file was removed: {removed:?}");
+
+    std::fs::write(&edited, "package com.example\n\nclass Edited { String name }\n").unwrap();
+    std::fs::remove_file(&deleted).unwrap();
+    let added = dir.path().join("Added.groovy");
+    std::fs::write(&added, "package com.example\n\nclass Added {}\n").unwrap();
+
+    let (mut changed, removed) = indexer.find_stale_files(dir.path()).await.unwrap();
+    changed.sort();
+    assert_eq!(changed, vec![added, edited]);
+    assert_eq!(removed, vec![deleted.to_string_lossy().to_string()]);
+}
+
+#[test]
+fn workspace_build_outputs_are_not_external_deps() {
+    let root = Path::new("/work/shop");
+    let deps = vec![
+        (
+            Some(PathBuf::from("/home/u/.gradle/caches/guava-33.0.0.jar")),
+            Some(PathBuf::from("/home/u/.gradle/caches/guava-33.0.0-sources.jar")),
+        ),
+        (Some(PathBuf::from("/work/shop/core/build/libs/core.jar")), None),
+        (Some(PathBuf::from("/work/shop/api/target/api-1.0.jar")), None),
+        (Some(PathBuf::from("/work/shop/libs/vendor.jar")), None),
+    ];
+
+    let kept = without_workspace_outputs(deps, root);
+    assert_eq!(
+        kept,
+        vec![
+            (
+                Some(PathBuf::from("/home/u/.gradle/caches/guava-33.0.0.jar")),
+                Some(PathBuf::from("/home/u/.gradle/caches/guava-33.0.0-sources.jar")),
+            ),
+            (Some(PathBuf::from("/work/shop/libs/vendor.jar")), None),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn replacing_a_files_symbols_drops_what_it_no_longer_declares() {
+    let db_name = Uuid::new_v4();
+    let db_dir = format!("file:{}?mode=memory", db_name);
+    let repo = Arc::new(Repository::new(&db_dir).await.unwrap());
+    let path = Path::new("/tmp/com/example/Task.groovy");
+
+    let mut indexer = Indexer::new(Arc::clone(&repo));
+    indexer.register_language("groovy", Arc::new(GroovySupport::new()));
+
+    let (symbols, mut supers) = indexer
+        .index_content(
+            path,
+            "package com.example\n\nclass Task implements Runnable {\n    void run() {}\n}\n",
+        )
+        .unwrap()
+        .unwrap();
+    // The same supertype reported twice is recorded once.
+    supers.extend(supers.clone());
+    let file_path = path.to_string_lossy();
+    repo.replace_file_symbols(&file_path, &symbols, &supers)
+        .await
+        .unwrap();
+    repo.replace_file_symbols(&file_path, &symbols, &supers)
+        .await
+        .unwrap();
+
+    let implementors = repo
+        .find_super_impls_by_short_name("Runnable")
+        .await
+        .unwrap();
+    assert_eq!(implementors.len(), 1);
+
+    let (symbols, supers) = indexer
+        .index_content(path, "package com.example\n")
+        .unwrap()
+        .unwrap();
+    repo.replace_file_symbols(&file_path, &symbols, &supers)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        repo.find_symbol_by_fqn("com.example.Task").await.unwrap(),
+        None
+    );
+    assert!(
+        repo.find_super_impls_by_short_name("Runnable")
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn paths_under_a_path_root_follow_it_when_it_moves() {
+    let db_name = Uuid::new_v4();
+    let db_dir = format!("file:{}?mode=memory", db_name);
+    let repo = Arc::new(Repository::new(&db_dir).await.unwrap());
+    let mut indexer = Indexer::new(Arc::clone(&repo));
+    indexer.register_language("groovy", Arc::new(GroovySupport::new()));
+
+    // Indexed before the root is known, as an index predating path roots was.
+    let path = Path::new("/work/app/src/com/example/Task.groovy");
+    let (symbols, supers) = indexer
+        .index_content(path, "package com.example\n\nclass Task {}\n")
+        .unwrap()
+        .unwrap();
+    repo.replace_file_symbols(&path.to_string_lossy(), &symbols, &supers)
+        .await
+        .unwrap();
+    repo.adopt_path_root("ROOT", Path::new("/work/app"))
+        .await
+        .unwrap();
+    let task = repo.find_symbol_by_fqn("com.example.Task").await.unwrap();
+    assert_eq!(task.unwrap().file_path, path.to_string_lossy());
+
+    repo.adopt_path_root("ROOT", Path::new("/moved/app"))
+        .await
+        .unwrap();
+    let moved = "/moved/app/src/com/example/Task.groovy";
+    let task = repo.find_symbol_by_fqn("com.example.Task").await.unwrap();
+    assert_eq!(task.unwrap().file_path, moved);
+    assert_eq!(repo.find_symbols_by_file_path(moved).await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn kotlin_top_level_declarations_get_a_file_facade() {
+    let db_name = Uuid::new_v4();
+    let db_dir = format!("file:{}?mode=memory", db_name);
+    let repo = Arc::new(Repository::new(&db_dir).await.unwrap());
+    let mut indexer = Indexer::new(Arc::clone(&repo));
+    indexer.register_language("kt", Arc::new(KotlinSupport::new()));
+
+    let content = r#"@file:JvmName("StringUtils")
+package com.example
+
+class Formatter
+class Parser
+
+const val SEPARATOR = ","
+var isVerbose = false
+val greeting: String = "hi"
+private fun helper() = 1
+fun shout(text: String): String = text.uppercase()
+"#;
+    let (symbols, _) = indexer
+        .index_content(Path::new("/tmp/com/example/strings.kt"), content)
+        .unwrap()
+        .unwrap();
+    let fqns: Vec<&str> = symbols
+        .iter()
+        .map(|s| s.fully_qualified_name.as_str())
+        .collect();
+    for fqn in [
+        "com.example.Formatter",
+        "com.example.Parser",
+        "com.example.shout",
+        "com.example.greeting",
+        "com.example.StringUtils",
+        "com.example.StringUtils#shout",
+        "com.example.StringUtils#SEPARATOR",
+        "com.example.StringUtils#isVerbose",
+        "com.example.StringUtils#setVerbose",
+        "com.example.StringUtils#getGreeting",
+    ] {
+        assert!(fqns.contains(&fqn), "missing {fqn} in {fqns:?}");
+    }
+    assert!(!fqns.iter().any(|f| f.ends_with("#helper")), "{fqns:?}");
+    assert!(!fqns.contains(&"com.example.StringUtils#setGreeting"));
+
+    let shout = symbols
+        .iter()
+        .find(|s| s.fully_qualified_name == "com.example.StringUtils#shout")
+        .unwrap();
+    assert_eq!(shout.symbol_type, "Function");
+    assert!(shout.modifiers.contains(&"static".to_string()));
+    assert_eq!(shout.metadata.return_type.as_deref(), Some("String"));
+    assert!(!shout.is_synthetic_accessor());
+
+    // The accessors are marked, having no declaration of their own.
+    let getter = symbols
+        .iter()
+        .find(|s| s.fully_qualified_name == "com.example.StringUtils#getGreeting")
+        .unwrap();
+    assert!(getter.is_synthetic_accessor());
+
+    let (symbols, _) = indexer
+        .index_content(
+            Path::new("/tmp/com/example/strings.kt"),
+            "package com.example\n\nfun shout(text: String): String = text.uppercase()\n",
+        )
+        .unwrap()
+        .unwrap();
+    assert!(
+        symbols
+            .iter()
+            .any(|s| s.fully_qualified_name == "com.example.StringsKt#shout")
+    );
+
+    let (symbols, _) = indexer
+        .index_content(
+            Path::new("/tmp/com/example/Shapes.kt"),
+            "package com.example\n\nclass Circle\nclass Square\n",
+        )
+        .unwrap()
+        .unwrap();
+    assert!(!symbols.iter().any(|s| s.short_name == "ShapesKt"));
+}
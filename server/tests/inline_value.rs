@@ -0,0 +1,79 @@
+use tower_lsp::{
+    LanguageServer,
+    lsp_types::{
+        DidOpenTextDocumentParams, InlineValue, InlineValueContext, InlineValueParams, Position,
+        Range, TextDocumentIdentifier, TextDocumentItem, Url, WorkDoneProgressParams,
+    },
+};
+
+use crate::util::get_test_server;
+
+mod util;
+
+const CONTENT: &str = r#"package com.example;
+
+public class Totals {
+    public int sum(int limit) {
+        int total = 0;
+        int step = limit * 2;
+        total += step;
+        total += limit;
+        return total;
+    }
+}
+"#;
+
+#[tokio::test]
+async fn inline_values_up_to_the_stopped_location() {
+    let server = get_test_server("polyglot-spring").await;
+    let uri = Url::parse("file:///tmp/Totals.java").unwrap();
+    server
+        .backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "java".to_string(),
+                version: 1,
+                text: CONTENT.to_string(),
+            },
+        })
+        .await;
+
+    let result = server
+        .backend
+        .inline_value(InlineValueParams {
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            text_document: TextDocumentIdentifier { uri },
+            range: Range::new(Position::new(0, 0), Position::new(11, 0)),
+            context: InlineValueContext {
+                frame_id: 1,
+                stopped_location: Range::new(Position::new(6, 8), Position::new(6, 22)),
+            },
+        })
+        .await
+        .unwrap();
+
+    let lookups: Vec<(u32, String)> = match result {
+        Some(values) => values
+            .into_iter()
+            .filter_map(|v| match v {
+                InlineValue::VariableLookup(l) => Some((l.range.start.line, l.variable_name?)),
+                _ => None,
+            })
+            .collect(),
+        other => panic!("expected inline values, got {other:?}"),
+    };
+
+    assert!(lookups.contains(&(6, "total".to_string())), "{lookups:?}");
+    assert!(lookups.contains(&(6, "step".to_string())), "{lookups:?}");
+    assert!(
+        lookups.iter().all(|(line, _)| *line <= 6),
+        "nothing after the stopped location: {lookups:?}"
+    );
+    assert!(
+        lookups
+            .iter()
+            .all(|(_, name)| name != "sum" && name != "Totals"),
+        "only variables: {lookups:?}"
+    );
+}
@@ -0,0 +1,109 @@
+use std::{env, path::PathBuf};
+
+use lspintar_server::{code_action::QuickFix, moved_files::paired_moves};
+use tower_lsp::{
+    LanguageServer,
+    lsp_types::{
+        DidChangeWatchedFilesParams, DidOpenTextDocumentParams, FileChangeType, FileEvent,
+        NumberOrString, Position, Range, TextDocumentItem, Url,
+    },
+};
+
+use crate::util::get_test_server;
+
+mod util;
+
+#[test]
+fn paired_moves_pairs_files_by_name() {
+    let deleted = vec![
+        PathBuf::from("/p/web/Api.kt"),
+        PathBuf::from("/p/web/Gone.kt"),
+    ];
+    let created = vec![
+        PathBuf::from("/p/api/Api.kt"),
+        PathBuf::from("/p/api/New.kt"),
+    ];
+    assert_eq!(
+        paired_moves(&deleted, &created),
+        vec![(
+            PathBuf::from("/p/web/Api.kt"),
+            PathBuf::from("/p/api/Api.kt")
+        )]
+    );
+}
+
+#[test]
+fn paired_moves_skips_ambiguous_names() {
+    let deleted = vec![PathBuf::from("/p/web/Api.kt")];
+    let created = vec![
+        PathBuf::from("/p/api/Api.kt"),
+        PathBuf::from("/p/v2/Api.kt"),
+    ];
+    assert!(paired_moves(&deleted, &created).is_empty());
+}
+
+#[tokio::test]
+async fn import_of_moved_type_is_unresolved_with_fix() {
+    let server = get_test_server("polyglot-spring").await;
+    let uri = Url::parse("file:///tmp/StaleUserImport.kt").unwrap();
+    let content =
+        "package com.example.demo\n\nimport com.example.demo.User\n\nfun member(): User? = null\n";
+    server
+        .backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "kotlin".to_string(),
+                version: 1,
+                text: content.to_string(),
+            },
+        })
+        .await;
+
+    let diagnostics = server.backend.compute_diagnostics(&uri).await.unwrap();
+    let diagnostic = diagnostics
+        .iter()
+        .find(|d| d.code == Some(NumberOrString::String("unresolved_import".to_string())))
+        .expect("expected an unresolved_import diagnostic");
+
+    assert_eq!(
+        diagnostic.range,
+        Range::new(Position::new(2, 7), Position::new(2, 28))
+    );
+    let fix: QuickFix = serde_json::from_value(diagnostic.data.clone().unwrap()).unwrap();
+    assert_eq!(fix.edits[0].new_text, "com.example.User");
+}
+
+#[tokio::test]
+async fn moved_file_is_rekeyed_in_index() {
+    let server = get_test_server("polyglot-spring").await;
+    let kotlin = env::current_dir()
+        .expect("cannot get current dir")
+        .join("tests/fixtures/polyglot-spring/src/main/kotlin/com/example/demo");
+    let old = kotlin.join("User.kt");
+    let new = kotlin.join("model/User.kt");
+
+    server
+        .backend
+        .did_change_watched_files(DidChangeWatchedFilesParams {
+            changes: vec![
+                FileEvent {
+                    uri: Url::from_file_path(&old).unwrap(),
+                    typ: FileChangeType::DELETED,
+                },
+                FileEvent {
+                    uri: Url::from_file_path(&new).unwrap(),
+                    typ: FileChangeType::CREATED,
+                },
+            ],
+        })
+        .await;
+
+    let repo = server.backend.repo.get().unwrap();
+    let user = repo
+        .find_symbol_by_fqn("com.example.User")
+        .await
+        .unwrap()
+        .expect("com.example.User is still indexed");
+    assert_eq!(PathBuf::from(user.file_path), new);
+}
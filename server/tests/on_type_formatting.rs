@@ -0,0 +1,115 @@
+use lspintar_server::on_type_formatting::on_type_edits;
+use tower_lsp::lsp_types::{FormattingOptions, Position, TextEdit};
+
+fn spaces() -> FormattingOptions {
+    FormattingOptions {
+        tab_size: 4,
+        insert_spaces: true,
+        ..Default::default()
+    }
+}
+
+/// `content` with `edits`, each replacing the indentation of a line.
+fn apply(content: &str, edits: &[TextEdit]) -> String {
+    let mut lines: Vec<String> = content.split('\n').map(str::to_string).collect();
+    for edit in edits {
+        assert_eq!(edit.range.start.line, edit.range.end.line);
+        assert_eq!(edit.range.start.character, 0);
+        let line = &mut lines[edit.range.start.line as usize];
+        let end = edit.range.end.character as usize;
+        *line = format!("{}{}", edit.new_text, &line[end..]);
+    }
+    lines.join("\n")
+}
+
+#[test]
+fn closing_brace_reindents_groovy_closure() {
+    let content = "def run() {
+    items.each { item ->
+  println item
+        def total =
+        item + 1
+      }";
+
+    let edits = on_type_edits(content, Position::new(5, 7), "}", &spaces());
+
+    assert_eq!(
+        apply(content, &edits),
+        "def run() {
+    items.each { item ->
+        println item
+        def total =
+            item + 1
+    }"
+    );
+}
+
+#[test]
+fn closing_brace_indents_statements_under_case_labels() {
+    let content = "        switch (x) {
+case 1:
+foo();
+break;
+default:
+bar();
+}";
+
+    let edits = on_type_edits(content, Position::new(6, 1), "}", &spaces());
+
+    assert_eq!(
+        apply(content, &edits),
+        "        switch (x) {
+            case 1:
+                foo();
+                break;
+            default:
+                bar();
+        }"
+    );
+}
+
+#[test]
+fn closing_brace_leaves_multiline_strings() {
+    let content = "def f() {
+def s = \"\"\"
+  keep {
+\"\"\"
+}";
+
+    let edits = on_type_edits(content, Position::new(4, 1), "}", &spaces());
+
+    assert_eq!(
+        apply(content, &edits),
+        "def f() {
+    def s = \"\"\"
+  keep {
+\"\"\"
+}"
+    );
+}
+
+#[test]
+fn newline_indents_new_line_with_tabs() {
+    let content = "class A {\n\tvoid f() {\n\n\t}\n}";
+    let options = FormattingOptions {
+        tab_size: 4,
+        insert_spaces: false,
+        ..Default::default()
+    };
+
+    let edits = on_type_edits(content, Position::new(2, 0), "\n", &options);
+
+    assert_eq!(
+        apply(content, &edits),
+        "class A {\n\tvoid f() {\n\t\t\n\t}\n}"
+    );
+}
+
+#[test]
+fn brace_in_string_closes_nothing() {
+    let content = "void f() {\n  log(\"}\")";
+
+    let edits = on_type_edits(content, Position::new(1, 8), "}", &spaces());
+
+    assert!(edits.is_empty());
+}
@@ -0,0 +1,182 @@
+//! Property tests for position math and identifier extraction.  Random edits
+//! with multi-byte content must never make the language supports panic, byte
+//! offsets must agree with tree-sitter's points, and the three languages must
+//! extract the same identifier and qualifier from the same member chain.
+
+use groovy::GroovySupport;
+use java::JavaSupport;
+use kotlin::KotlinSupport;
+use lsp_core::{
+    language_support::LanguageSupport,
+    ts_helper::{get_node_at_position, node_contains_position, position_to_byte_offset},
+};
+use proptest::{prelude::*, sample::Index};
+use tower_lsp::lsp_types::Position;
+
+const JAVA_TEMPLATE: &str = "package com.example;\n\nclass Sample {\n    // é 名前 🎉\n    String name = \"ünïcödé\";\n\n    void run(int count) {\n        String label = name.trim().toUpperCase();\n        helper(label, count);\n    }\n}\n";
+const KOTLIN_TEMPLATE: &str = "package com.example\n\nclass Sample {\n    // é 名前 🎉\n    val name = \"ünïcödé\"\n\n    fun run(count: Int) {\n        val label = name.trim().uppercase()\n        helper(label, count)\n    }\n}\n";
+const GROOVY_TEMPLATE: &str = "package com.example\n\nclass Sample {\n    // é 名前 🎉\n    String name = \"ünïcödé\"\n\n    void run(int count) {\n        def label = name.trim().toUpperCase()\n        helper(label, count)\n    }\n}\n";
+
+fn languages() -> Vec<(Box<dyn LanguageSupport>, &'static str)> {
+    vec![
+        (Box::new(JavaSupport::new()), JAVA_TEMPLATE),
+        (Box::new(KotlinSupport::new()), KOTLIN_TEMPLATE),
+        (Box::new(GroovySupport::new()), GROOVY_TEMPLATE),
+    ]
+}
+
+fn fragment() -> impl Strategy<Value = &'static str> {
+    prop::sample::select(vec![
+        "x", "名前", "é", "🎉", ".", "?.", "::", "(", ")", "((", "{", "}", "[", "]", "<", ">",
+        "\"", "\"é(\"", "'", "$", "${", "->", "=", ",", ";", " ", "\n", "\r\n", "// ü\n", "/*",
+        "*/", "@", "new ", "this.", "super.", "it", "fun ", "def ", "class ",
+    ])
+}
+
+/// `template` with random fragments inserted and random characters removed.
+fn edited(template: &'static str) -> impl Strategy<Value = String> {
+    prop::collection::vec((any::<Index>(), fragment(), any::<bool>()), 0..12).prop_map(
+        move |edits| {
+            let mut content = template.to_string();
+            for (at, fragment, insert) in edits {
+                let boundaries: Vec<usize> = content
+                    .char_indices()
+                    .map(|(i, _)| i)
+                    .chain([content.len()])
+                    .collect();
+                let offset = *at.get(&boundaries);
+                if insert {
+                    content.insert_str(offset, fragment);
+                } else if offset < content.len() {
+                    content.remove(offset);
+                }
+            }
+            content
+        },
+    )
+}
+
+/// A language index into [`languages`] and an edited copy of its template.
+fn language_and_content() -> impl Strategy<Value = (usize, String)> {
+    prop_oneof![
+        edited(JAVA_TEMPLATE).prop_map(|c| (0, c)),
+        edited(KOTLIN_TEMPLATE).prop_map(|c| (1, c)),
+        edited(GROOVY_TEMPLATE).prop_map(|c| (2, c)),
+    ]
+}
+
+fn is_identifier(kind: &str) -> bool {
+    matches!(kind, "identifier" | "simple_identifier" | "type_identifier")
+}
+
+proptest! {
+    #[test]
+    fn random_edits_never_panic(
+        (language, content) in language_and_content(),
+        line in 0..16u32,
+        character in 0..64u32,
+    ) {
+        let (lang, _) = languages().swap_remove(language);
+        let position = Position::new(line, character);
+        if let Some((tree, _)) = lang.parse_str(&content) {
+            lang.find_ident_at_position(&tree, &content, &position);
+            lang.get_type_at_position(tree.root_node(), &content, &position);
+            lang.get_literal_type(&tree, &content, &position);
+            lang.find_variable_declaration(&tree, &content, "label", &position);
+            lang.find_declarations_in_scope(&tree, &content, &position);
+            lang.extract_call_arguments(&tree, &content, &position);
+            get_node_at_position(&tree, &content, &position);
+        }
+    }
+
+    #[test]
+    fn byte_offset_is_a_char_boundary(
+        (_, content) in language_and_content(),
+        line in 0..16u32,
+        character in 0..64u32,
+    ) {
+        let offset = position_to_byte_offset(&content, &Position::new(line, character));
+        prop_assert!(offset <= content.len());
+        prop_assert!(content.is_char_boundary(offset));
+    }
+
+    #[test]
+    fn identifier_positions_round_trip(language in 0..3usize, edits in prop::collection::vec(any::<Index>(), 0..4)) {
+        let (lang, template) = languages().swap_remove(language);
+        let mut content = template.to_string();
+        // Shift later columns by multi-byte characters without breaking the syntax.
+        for at in edits {
+            let lines: Vec<usize> = content.match_indices("\n    ").map(|(i, _)| i + 1).collect();
+            if !lines.is_empty() {
+                content.insert_str(*at.get(&lines), "/*é名*/");
+            }
+        }
+        let (tree, _) = lang.parse_str(&content).expect("template parses");
+
+        let mut stack = vec![tree.root_node()];
+        while let Some(node) = stack.pop() {
+            if is_identifier(node.kind()) {
+                // Positions count characters, tree-sitter columns bytes.
+                let start = node.start_position();
+                let line_start = node.start_byte() - start.column;
+                let character = content[line_start..node.start_byte()].chars().count();
+                let position = Position::new(start.row as u32, character as u32);
+                prop_assert_eq!(position_to_byte_offset(&content, &position), node.start_byte());
+                let found = get_node_at_position(&tree, &content, &position);
+                prop_assert!(found.is_some_and(|n| n.byte_range().contains(&node.start_byte())));
+                let point = Position::new(start.row as u32, start.column as u32);
+                prop_assert!(node_contains_position(&node, &point));
+            }
+            let mut cursor = node.walk();
+            stack.extend(node.children(&mut cursor));
+        }
+    }
+
+    #[test]
+    fn member_chains_agree_between_languages(
+        segments in prop::collection::vec(("x[a-z0-9]{0,5}", any::<bool>()), 2..5),
+    ) {
+        let chain = segments
+            .iter()
+            .enumerate()
+            .map(|(i, (name, call))| {
+                if *call && i + 1 < segments.len() { format!("{name}()") } else { name.clone() }
+            })
+            .collect::<Vec<_>>()
+            .join(".");
+        let chain = format!("{chain}()");
+        let member = &segments.last().unwrap().0;
+        let qualifier = segments[..segments.len() - 1]
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>()
+            .join("#");
+        let position = Position::new(3, (8 + chain.len() - member.len() - 2) as u32);
+
+        let sources = [
+            (
+                Box::new(JavaSupport::new()) as Box<dyn LanguageSupport>,
+                format!("class T {{\n    void m() {{\n        // é 名前 🎉\n        {chain};\n    }}\n}}\n"),
+            ),
+            (
+                Box::new(KotlinSupport::new()),
+                format!("class T {{\n    fun m() {{\n        // é 名前 🎉\n        {chain}\n    }}\n}}\n"),
+            ),
+            (
+                Box::new(GroovySupport::new()),
+                format!("class T {{\n    void m() {{\n        // é 名前 🎉\n        {chain}\n    }}\n}}\n"),
+            ),
+        ];
+        for (lang, content) in sources {
+            let (tree, _) = lang.parse_str(&content).expect("chain parses");
+            prop_assert_eq!(
+                lang.find_ident_at_position(&tree, &content, &position)
+                    .map(|(ident, chain)| (ident, chain.map(|c| c.to_string()))),
+                Some((member.clone(), Some(qualifier.clone()))),
+                "{:?} in {}",
+                lang.get_language(),
+                content
+            );
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use lspintar_server::project_cycles::{ProjectReference, dependency_cycles, project_references};
+use tower_lsp::lsp_types::{Position, Range};
+
+#[test]
+fn project_dependencies_in_both_dsls() {
+    let content = "dependencies {\n    implementation project(':core')\n    api(project(path: \":shared\"))\n}\n\nproject(':app') {\n}\n";
+    assert_eq!(
+        project_references(content),
+        vec![
+            ProjectReference {
+                path: ":core".to_string(),
+                range: Range::new(Position::new(1, 28), Position::new(1, 33)),
+            },
+            ProjectReference {
+                path: ":shared".to_string(),
+                range: Range::new(Position::new(2, 23), Position::new(2, 30)),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_configurations_are_no_project_dependencies() {
+    let content = "dependencies {\n    testImplementation project(':app')\n    integrationTestImplementation(project(':app'))\n    testFixturesApi project(':app')\n    runtimeOnly project(':app')\n}\n";
+    assert_eq!(
+        project_references(content),
+        vec![ProjectReference {
+            path: ":app".to_string(),
+            range: Range::new(Position::new(4, 25), Position::new(4, 29)),
+        }]
+    );
+}
+
+#[test]
+fn cycles_between_projects() {
+    let graph: BTreeMap<String, BTreeSet<String>> = [
+        (":app", vec![":core"]),
+        (":core", vec![":app", ":util"]),
+        (":util", vec![]),
+        (":self", vec![":self"]),
+    ]
+    .into_iter()
+    .map(|(project, dependencies)| {
+        (
+            project.to_string(),
+            dependencies.into_iter().map(str::to_string).collect(),
+        )
+    })
+    .collect();
+    assert_eq!(
+        dependency_cycles(&graph),
+        vec![
+            vec![":app".to_string(), ":core".to_string()],
+            vec![":self".to_string()],
+        ]
+    );
+}
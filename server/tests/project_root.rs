@@ -0,0 +1,55 @@
+use lspintar_server::project_root::RootSettings;
+
+#[test]
+fn root_settings_default_to_client_root() {
+    let settings = RootSettings::from_initialization_options(&serde_json::json!({})).unwrap();
+    let client_root = std::path::Path::new("/work/monorepo");
+    assert_eq!(settings.resolve(client_root), client_root);
+
+    // Top-level keys belong to other settings.
+    let settings = RootSettings::from_initialization_options(
+        &serde_json::json!({ "projectRoot": "services/billing" }),
+    )
+    .unwrap();
+    assert_eq!(settings, RootSettings::default());
+}
+
+#[test]
+fn project_root_overrides_client_root() {
+    let dir = tempfile::tempdir().unwrap();
+    let service = dir.path().join("services/billing");
+    std::fs::create_dir_all(&service).unwrap();
+
+    let settings = RootSettings::from_initialization_options(
+        &serde_json::json!({ "lspintar": { "projectRoot": "services/billing" } }),
+    )
+    .unwrap();
+    assert_eq!(settings.resolve(dir.path()), service);
+
+    // A root that does not exist is ignored.
+    let settings = RootSettings::from_initialization_options(
+        &serde_json::json!({ "lspintar": { "projectRoot": "services/missing" } }),
+    )
+    .unwrap();
+    assert_eq!(settings.resolve(dir.path()), dir.path());
+}
+
+#[test]
+fn root_markers_pick_nearest_marked_ancestor() {
+    let dir = tempfile::tempdir().unwrap();
+    let service = dir.path().join("services/billing/src");
+    std::fs::create_dir_all(&service).unwrap();
+    std::fs::write(dir.path().join("settings.gradle"), "").unwrap();
+
+    let settings = RootSettings::from_initialization_options(
+        &serde_json::json!({ "lspintar": { "rootMarkers": ["settings.gradle"] } }),
+    )
+    .unwrap();
+    assert_eq!(settings.resolve(&service), dir.path());
+
+    std::fs::write(dir.path().join("services/billing/settings.gradle"), "").unwrap();
+    assert_eq!(
+        settings.resolve(&service),
+        dir.path().join("services/billing")
+    );
+}
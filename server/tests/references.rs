@@ -3,7 +3,7 @@ use std::env;
 use tower_lsp::{
     LanguageServer,
     lsp_types::{
-        PartialResultParams, Position, ReferenceContext, ReferenceParams,
+        PartialResultParams, Position, Range, ReferenceContext, ReferenceParams,
         TextDocumentIdentifier, TextDocumentPositionParams, Url, WorkDoneProgressParams,
     },
 };
@@ -190,3 +190,75 @@ async fn references_returns_none_for_unknown_position() {
     // which may or may not appear elsewhere. The handler must not panic.
     let _ = result;
 }
+
+/// Spring XML bean definitions naming a class by FQN are references to it.
+#[tokio::test]
+async fn references_include_spring_xml_class_attributes() {
+    let server = get_test_server("polyglot-spring").await;
+    let root = env::current_dir().expect("cannot get current dir");
+    let task_runner =
+        root.join("tests/fixtures/polyglot-spring/src/main/java/com/example/demo/TaskRunner.java");
+    let context =
+        root.join("tests/fixtures/polyglot-spring/src/main/resources/applicationContext.xml");
+
+    let params = ReferenceParams {
+        text_document_position: TextDocumentPositionParams {
+            text_document: TextDocumentIdentifier {
+                uri: Url::from_file_path(&task_runner).expect("cannot parse URI"),
+            },
+            // "TaskRunner" class name declaration (line 2, col 13)
+            position: Position::new(2, 13),
+        },
+        context: ReferenceContext {
+            include_declaration: true,
+        },
+        work_done_progress_params: WorkDoneProgressParams::default(),
+        partial_result_params: PartialResultParams::default(),
+    };
+
+    let locations = server
+        .backend
+        .references(params)
+        .await
+        .unwrap()
+        .expect("references for TaskRunner");
+    let xml_uri = Url::from_file_path(&context).expect("cannot parse URI");
+    assert!(
+        locations.iter().any(|loc| loc.uri == xml_uri
+            && loc.range == Range::new(Position::new(2, 45), Position::new(2, 55))),
+        "the bean class attribute should be reported, got {locations:?}"
+    );
+}
+
+/// From an XML bean reference, references list the bean's id and every `ref` to it.
+#[tokio::test]
+async fn references_of_spring_bean_from_xml() {
+    let server = get_test_server("polyglot-spring").await;
+    let root = env::current_dir().expect("cannot get current dir");
+    let context =
+        root.join("tests/fixtures/polyglot-spring/src/main/resources/applicationContext.xml");
+    let uri = Url::from_file_path(&context).expect("cannot parse URI");
+
+    let params = ReferenceParams {
+        text_document_position: TextDocumentPositionParams {
+            text_document: TextDocumentIdentifier { uri: uri.clone() },
+            // ref="taskRunner" (line 4, col 38)
+            position: Position::new(4, 38),
+        },
+        context: ReferenceContext {
+            include_declaration: true,
+        },
+        work_done_progress_params: WorkDoneProgressParams::default(),
+        partial_result_params: PartialResultParams::default(),
+    };
+
+    let locations = server
+        .backend
+        .references(params)
+        .await
+        .unwrap()
+        .expect("references for taskRunner");
+    let starts: Vec<Position> = locations.iter().map(|l| l.range.start).collect();
+    assert_eq!(starts, vec![Position::new(2, 14), Position::new(4, 35)]);
+    assert!(locations.iter().all(|l| l.uri == uri));
+}
@@ -5,12 +5,14 @@ use std::env;
 use tower_lsp::{
     LanguageServer,
     lsp_types::{
-        PartialResultParams, Position, Range, RenameParams, TextDocumentIdentifier,
-        TextDocumentPositionParams, Url, WorkDoneProgressParams, WorkspaceEdit,
+        ChangeAnnotationWorkspaceEditClientCapabilities, ClientCapabilities, DocumentChanges,
+        OneOf, PartialResultParams, Position, PrepareRenameResponse, Range, RenameParams,
+        TextDocumentIdentifier, TextDocumentPositionParams, Url, WorkDoneProgressParams,
+        WorkspaceClientCapabilities, WorkspaceEdit, WorkspaceEditClientCapabilities,
     },
 };
 
-use crate::util::get_test_server;
+use crate::util::{get_test_server, get_test_server_with_capabilities};
 
 mod util;
 
@@ -78,6 +80,35 @@ async fn rename_java_class_across_files() {
     }
 }
 
+/// XML bean definitions name classes by FQN; renaming the class renames the
+/// simple name there too.
+#[tokio::test]
+async fn rename_class_updates_spring_xml() {
+    let server = get_test_server("polyglot-spring").await;
+    let root = env::current_dir().expect("cwd");
+    let task_runner =
+        root.join("tests/fixtures/polyglot-spring/src/main/java/com/example/demo/TaskRunner.java");
+    let context =
+        root.join("tests/fixtures/polyglot-spring/src/main/resources/applicationContext.xml");
+
+    // `public class TaskRunner` — TaskRunner identifier at (line 2, col 13).
+    let params = rename_params(task_runner, Position::new(2, 13), "JobRunner");
+    let edit = server
+        .backend
+        .rename(params)
+        .await
+        .expect("rename Ok")
+        .expect("WorkspaceEdit returned");
+
+    let xml_edits = edits_for(&edit, &context).expect("XML edits");
+    assert_eq!(xml_edits.len(), 1);
+    assert_eq!(xml_edits[0].new_text, "JobRunner");
+    assert_eq!(
+        xml_edits[0].range,
+        Range::new(Position::new(2, 45), Position::new(2, 55))
+    );
+}
+
 /// Renaming the Kotlin class `KotlinService` must rename the declaration and
 /// every reference to it across the workspace.
 #[tokio::test]
@@ -176,6 +207,74 @@ async fn rename_function_propagates_to_overrides() {
     );
 }
 
+/// With a client that supports change annotations, the edits renaming the
+/// Kotlin override are annotated as needing confirmation while the interface
+/// declaration itself stays a plain edit.
+#[tokio::test]
+async fn rename_function_annotates_override_edits() {
+    let capabilities = ClientCapabilities {
+        workspace: Some(WorkspaceClientCapabilities {
+            workspace_edit: Some(WorkspaceEditClientCapabilities {
+                document_changes: Some(true),
+                change_annotation_support: Some(
+                    ChangeAnnotationWorkspaceEditClientCapabilities::default(),
+                ),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let server = get_test_server_with_capabilities("polyglot-spring", capabilities).await;
+    let root = env::current_dir().expect("cwd");
+    let base = root.join(
+        "tests/fixtures/polyglot-spring/src/main/java/com/example/demo/BaseRepository.java",
+    );
+    let user_repo = root.join(
+        "tests/fixtures/polyglot-spring/src/main/kotlin/com/example/demo/UserRepository.kt",
+    );
+
+    let params = rename_params(base.clone(), Position::new(3, 6), "findByIdentifier");
+    let edit = server
+        .backend
+        .rename(params)
+        .await
+        .expect("rename Ok")
+        .expect("WorkspaceEdit returned");
+
+    assert!(edit.changes.is_none(), "annotated edits use documentChanges");
+    let annotations = edit.change_annotations.as_ref().expect("change annotations");
+    let Some(DocumentChanges::Edits(document_edits)) = edit.document_changes.as_ref() else {
+        panic!("expected text document edits");
+    };
+    let edits_in = |file: &std::path::Path| {
+        let uri = Url::from_file_path(file).expect("bad path");
+        document_edits
+            .iter()
+            .find(|e| e.text_document.uri == uri)
+            .map(|e| e.edits.clone())
+            .unwrap_or_default()
+    };
+
+    assert!(
+        edits_in(&base)
+            .iter()
+            .any(|e| matches!(e, OneOf::Left(e) if e.range.start == Position::new(3, 6))),
+        "interface declaration should be a plain edit"
+    );
+    let override_annotation = edits_in(&user_repo)
+        .iter()
+        .find_map(|e| match e {
+            OneOf::Right(annotated) => Some(annotated.annotation_id.clone()),
+            OneOf::Left(_) => None,
+        })
+        .expect("Kotlin override should be an annotated edit");
+    assert_eq!(
+        annotations[&override_annotation].needs_confirmation,
+        Some(true)
+    );
+}
+
 // --------------------------------------------------------------------------
 // Local variable rename — scope-aware, shadow-aware
 // --------------------------------------------------------------------------
@@ -227,6 +326,60 @@ async fn rename_local_is_single_file() {
     }
 }
 
+// --------------------------------------------------------------------------
+// prepareRename
+// --------------------------------------------------------------------------
+
+/// prepareRename on a class declaration returns the identifier's range with the
+/// current name as placeholder.
+#[tokio::test]
+async fn prepare_rename_returns_identifier_range() {
+    let server = get_test_server("polyglot-spring").await;
+    let root = env::current_dir().expect("cwd");
+    let java_service =
+        root.join("tests/fixtures/polyglot-spring/src/main/java/com/example/demo/JavaService.java");
+
+    let response = server
+        .backend
+        .prepare_rename(TextDocumentPositionParams {
+            text_document: TextDocumentIdentifier {
+                uri: Url::from_file_path(java_service).expect("bad path"),
+            },
+            position: Position::new(5, 15),
+        })
+        .await
+        .expect("prepareRename Ok");
+
+    assert_eq!(
+        response,
+        Some(PrepareRenameResponse::RangeWithPlaceholder {
+            range: Range::new(Position::new(5, 13), Position::new(5, 24)),
+            placeholder: "JavaService".to_string(),
+        })
+    );
+}
+
+/// A keyword is not renameable: prepareRename fails before the client asks
+/// for a new name.
+#[tokio::test]
+async fn prepare_rename_rejects_keyword() {
+    let server = get_test_server("polyglot-spring").await;
+    let root = env::current_dir().expect("cwd");
+    let java_service =
+        root.join("tests/fixtures/polyglot-spring/src/main/java/com/example/demo/JavaService.java");
+
+    let result = server
+        .backend
+        .prepare_rename(TextDocumentPositionParams {
+            text_document: TextDocumentIdentifier {
+                uri: Url::from_file_path(java_service).expect("bad path"),
+            },
+            position: Position::new(5, 2),
+        })
+        .await;
+    assert!(result.is_err(), "got: {result:?}");
+}
+
 // --------------------------------------------------------------------------
 // Invalid identifier rejection
 // --------------------------------------------------------------------------
@@ -0,0 +1,92 @@
+use std::env;
+
+use lspintar_server::resolve_trace::{Outcome, ResolveTrace, TraceStep};
+use pretty_assertions::assert_eq;
+use tower_lsp::lsp_types::{Position, TextDocumentIdentifier, TextDocumentPositionParams, Url};
+
+use crate::util::get_test_server;
+
+mod util;
+
+fn controller_position(line: u32, character: u32) -> TextDocumentPositionParams {
+    let root = env::current_dir().expect("cannot get current dir");
+    TextDocumentPositionParams {
+        text_document: TextDocumentIdentifier {
+            uri: Url::from_file_path(root.join(
+                "tests/fixtures/polyglot-spring/src/main/groovy/com/example/demo/Controller.groovy",
+            ))
+            .expect("cannot parse root URI"),
+        },
+        position: Position::new(line, character),
+    }
+}
+
+fn step<'t>(trace: &'t ResolveTrace, strategy: &str) -> &'t TraceStep {
+    trace
+        .steps
+        .iter()
+        .find(|s| s.strategy == strategy)
+        .unwrap_or_else(|| panic!("no {strategy} step"))
+}
+
+#[tokio::test]
+async fn resolve_trace_of_instance_method() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let trace = server
+        .backend
+        .resolve_trace(controller_position(31, 45))
+        .await
+        .unwrap();
+
+    let strategies: Vec<&str> = trace.steps.iter().map(|s| s.strategy.as_str()).collect();
+    assert_eq!(
+        strategies[..6],
+        [
+            "jpql",
+            "mappedBy",
+            "gradleTask",
+            "gradlePlugin",
+            "resource",
+            "actualDeclaration",
+        ]
+    );
+    assert_eq!(
+        strategies[strategies.len() - 3..],
+        ["symbol", "stringReference", "springBean"]
+    );
+    assert_eq!(step(&trace, "lambda").outcome, Outcome::NotFound);
+    let member = step(&trace, "member");
+    assert_eq!(member.outcome, Outcome::Found);
+    assert_eq!(member.detail.as_deref(), Some("on groovyService"));
+    assert_eq!(
+        member.results,
+        vec!["com.example.GroovyService#process(String)"]
+    );
+    assert!(!strategies.contains(&"local"), "got: {strategies:?}");
+    assert_eq!(step(&trace, "symbol").outcome, Outcome::Found);
+    assert_eq!(step(&trace, "stringReference").outcome, Outcome::Skipped);
+    assert_eq!(trace.resolver.as_deref(), Some("symbol"));
+    assert_eq!(trace.targets.len(), 1);
+}
+
+#[tokio::test]
+async fn resolve_trace_of_type() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let trace = server
+        .backend
+        .resolve_trace(controller_position(14, 8))
+        .await
+        .unwrap();
+
+    let imports = step(&trace, "imports");
+    assert_eq!(imports.outcome, Outcome::Found);
+    assert_eq!(imports.results, vec!["com.example.GroovyService"]);
+    let project = step(&trace, "project");
+    assert_eq!(project.outcome, Outcome::Found);
+    assert_eq!(project.results, vec!["com.example.GroovyService"]);
+    // Found in the project, the type is not looked up in the libraries.
+    assert!(trace.steps.iter().all(|s| s.strategy != "external"));
+    assert_eq!(trace.resolver.as_deref(), Some("symbol"));
+}
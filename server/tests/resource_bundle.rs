@@ -0,0 +1,125 @@
+use tower_lsp::{
+    LanguageServer,
+    lsp_types::{
+        DidOpenTextDocumentParams, GotoDefinitionParams, GotoDefinitionResponse, Location,
+        PartialResultParams, Position, TextDocumentIdentifier, TextDocumentItem,
+        TextDocumentPositionParams, Url, WorkDoneProgressParams,
+    },
+};
+
+use crate::util::{TestServer, get_test_server, get_test_server_with_options};
+
+mod util;
+
+const GREETING: &str = r#"package com.example;
+
+import java.util.ResourceBundle;
+
+public class Greeting {
+    String greet() {
+        ResourceBundle bundle = ResourceBundle.getBundle("messages");
+        return bundle.getString("greeting.hello") + bundle.getString("greeting.missing");
+    }
+}
+"#;
+
+async fn open_greeting(server: &TestServer) -> Url {
+    let uri = Url::parse("file:///tmp/Greeting.java").unwrap();
+    server
+        .backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "java".to_string(),
+                version: 1,
+                text: GREETING.to_string(),
+            },
+        })
+        .await;
+    uri
+}
+
+async fn definitions(server: &TestServer, uri: Url, position: Position) -> Vec<Location> {
+    let result = server
+        .backend
+        .goto_definition(GotoDefinitionParams {
+            text_document_position_params: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier { uri },
+                position,
+            },
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+        })
+        .await
+        .unwrap();
+    match result {
+        Some(GotoDefinitionResponse::Scalar(location)) => vec![location],
+        Some(GotoDefinitionResponse::Array(locations)) => locations,
+        other => panic!("expected locations, got {other:?}"),
+    }
+}
+
+fn file_name(location: &Location) -> String {
+    location.uri.path().rsplit('/').next().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn bundle_key_resolves_to_each_locale() {
+    let server = get_test_server("polyglot-spring").await;
+    let uri = open_greeting(&server).await;
+
+    let locations = definitions(&server, uri, Position::new(7, 35)).await;
+    let found: Vec<(String, Position)> = locations
+        .iter()
+        .map(|l| (file_name(l), l.range.start))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("messages.properties".to_string(), Position::new(1, 0)),
+            ("messages_fr.properties".to_string(), Position::new(0, 0)),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn bundle_name_resolves_to_its_files() {
+    let server = get_test_server("polyglot-spring").await;
+    let uri = open_greeting(&server).await;
+
+    let locations = definitions(&server, uri, Position::new(6, 60)).await;
+    let files: Vec<String> = locations.iter().map(file_name).collect();
+    assert_eq!(files, vec!["messages.properties", "messages_fr.properties"]);
+}
+
+#[tokio::test]
+async fn unknown_keys_are_reported_when_enabled() {
+    let server = get_test_server_with_options(
+        "polyglot-spring",
+        serde_json::json!({"check_resource_keys": true}),
+    )
+    .await;
+    let uri = open_greeting(&server).await;
+
+    let diagnostics = server.backend.compute_diagnostics(&uri).await.unwrap();
+    let unknown: Vec<_> = diagnostics
+        .iter()
+        .filter(|d| {
+            d.code
+                == Some(tower_lsp::lsp_types::NumberOrString::String(
+                    "unknown_resource_key".to_string(),
+                ))
+        })
+        .collect();
+    assert_eq!(unknown.len(), 1, "{unknown:?}");
+    assert_eq!(unknown[0].range.start, Position::new(7, 70));
+
+    let server = get_test_server("polyglot-spring").await;
+    let uri = open_greeting(&server).await;
+    let diagnostics = server.backend.compute_diagnostics(&uri).await.unwrap();
+    assert!(
+        diagnostics
+            .iter()
+            .all(|d| d.message != "No resource bundle defines 'greeting.missing'")
+    );
+}
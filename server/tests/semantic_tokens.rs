@@ -0,0 +1,290 @@
+use lspintar_server::semantic_tokens::legend;
+use tower_lsp::{
+    LanguageServer,
+    lsp_types::{
+        DidChangeTextDocumentParams, DidOpenTextDocumentParams, Position, Range, SemanticToken,
+        SemanticTokensDeltaParams, SemanticTokensFullDeltaResult, SemanticTokensParams,
+        SemanticTokensRangeParams, SemanticTokensRangeResult, SemanticTokensResult,
+        TextDocumentContentChangeEvent, TextDocumentIdentifier, TextDocumentItem, Url,
+        VersionedTextDocumentIdentifier,
+    },
+};
+
+use crate::util::get_test_server;
+
+mod util;
+
+const CONTENT: &str = r#"package com.example;
+
+public class Totals {
+    private static final int LIMIT = 10;
+
+    public int sum(int count) {
+        int total = count;
+        return total + LIMIT;
+    }
+}
+"#;
+
+/// `(line, start, type, modifiers)` of each token, by legend name.
+fn decode(data: &[SemanticToken]) -> Vec<(u32, u32, String, Vec<String>)> {
+    let legend = legend();
+    let (mut line, mut start) = (0, 0);
+    data.iter()
+        .map(|token| {
+            line += token.delta_line;
+            start = if token.delta_line == 0 {
+                start + token.delta_start
+            } else {
+                token.delta_start
+            };
+            let modifiers = legend
+                .token_modifiers
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| token.token_modifiers_bitset & (1 << i) != 0)
+                .map(|(_, m)| m.as_str().to_string())
+                .collect();
+            let token_type = legend.token_types[token.token_type as usize].as_str();
+            (line, start, token_type.to_string(), modifiers)
+        })
+        .collect()
+}
+
+fn flatten(data: &[SemanticToken]) -> Vec<u32> {
+    data.iter()
+        .flat_map(|t| {
+            [
+                t.delta_line,
+                t.delta_start,
+                t.length,
+                t.token_type,
+                t.token_modifiers_bitset,
+            ]
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn semantic_tokens_full_delta_and_range() {
+    let server = get_test_server("polyglot-spring").await;
+    let uri = Url::parse("file:///tmp/Totals.java").unwrap();
+    server
+        .backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "java".to_string(),
+                version: 1,
+                text: CONTENT.to_string(),
+            },
+        })
+        .await;
+
+    let full = || {
+        let backend = &server.backend;
+        let uri = uri.clone();
+        async move {
+            match backend
+                .semantic_tokens_full(SemanticTokensParams {
+                    work_done_progress_params: Default::default(),
+                    partial_result_params: Default::default(),
+                    text_document: TextDocumentIdentifier { uri },
+                })
+                .await
+                .unwrap()
+            {
+                Some(SemanticTokensResult::Tokens(tokens)) => tokens,
+                other => panic!("expected tokens, got {other:?}"),
+            }
+        }
+    };
+
+    let first = full().await;
+    let tokens = decode(&first.data);
+    let token_at = |line: u32, start: u32| {
+        tokens
+            .iter()
+            .find(|t| t.0 == line && t.1 == start)
+            .map(|t| {
+                (
+                    t.2.as_str(),
+                    t.3.iter().map(String::as_str).collect::<Vec<_>>(),
+                )
+            })
+    };
+    assert_eq!(token_at(2, 13), Some(("class", vec!["declaration"])));
+    assert_eq!(
+        token_at(3, 29),
+        Some(("property", vec!["declaration", "static", "readonly"]))
+    );
+    assert_eq!(token_at(5, 15), Some(("method", vec!["declaration"])));
+    assert_eq!(token_at(5, 23), Some(("parameter", vec!["declaration"])));
+    assert_eq!(token_at(6, 12), Some(("variable", vec!["declaration"])));
+    assert_eq!(token_at(6, 20), Some(("parameter", vec![])));
+    assert_eq!(token_at(7, 15), Some(("variable", vec![])));
+    assert_eq!(
+        token_at(7, 23),
+        Some(("property", vec!["static", "readonly"]))
+    );
+
+    // Unchanged text is answered from the cache.
+    assert_eq!(full().await.result_id, first.result_id);
+
+    let edited = CONTENT.replace("total + LIMIT;", "total + LIMIT + count;");
+    server
+        .backend
+        .did_change(DidChangeTextDocumentParams {
+            text_document: VersionedTextDocumentIdentifier {
+                uri: uri.clone(),
+                version: 2,
+            },
+            content_changes: vec![TextDocumentContentChangeEvent {
+                range: None,
+                range_length: None,
+                text: edited,
+            }],
+        })
+        .await;
+    let delta = server
+        .backend
+        .semantic_tokens_full_delta(SemanticTokensDeltaParams {
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+            text_document: TextDocumentIdentifier { uri: uri.clone() },
+            previous_result_id: first.result_id.clone().unwrap(),
+        })
+        .await
+        .unwrap();
+    let Some(SemanticTokensFullDeltaResult::TokensDelta(delta)) = delta else {
+        panic!("expected a delta, got {delta:?}");
+    };
+    assert_eq!(delta.edits.len(), 1);
+    let edit = &delta.edits[0];
+    let mut patched = flatten(&first.data);
+    patched.splice(
+        edit.start as usize..(edit.start + edit.delete_count) as usize,
+        flatten(edit.data.as_deref().unwrap_or_default()),
+    );
+    let second = full().await;
+    assert_eq!(second.result_id, delta.result_id);
+    assert_eq!(patched, flatten(&second.data));
+
+    // A result id the server no longer has gets every token.
+    let stale = server
+        .backend
+        .semantic_tokens_full_delta(SemanticTokensDeltaParams {
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+            text_document: TextDocumentIdentifier { uri: uri.clone() },
+            previous_result_id: first.result_id.unwrap(),
+        })
+        .await
+        .unwrap();
+    assert!(matches!(
+        stale,
+        Some(SemanticTokensFullDeltaResult::Tokens(_))
+    ));
+
+    let range = server
+        .backend
+        .semantic_tokens_range(SemanticTokensRangeParams {
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+            text_document: TextDocumentIdentifier { uri },
+            range: Range::new(Position::new(6, 0), Position::new(7, 0)),
+        })
+        .await
+        .unwrap();
+    let Some(SemanticTokensRangeResult::Tokens(range)) = range else {
+        panic!("expected tokens, got {range:?}");
+    };
+    let in_range: Vec<(u32, u32, String)> = decode(&range.data)
+        .into_iter()
+        .map(|(line, start, token_type, _)| (line, start, token_type))
+        .collect();
+    assert_eq!(
+        in_range,
+        [
+            (6, 12, "variable".to_string()),
+            (6, 20, "parameter".to_string())
+        ]
+    );
+}
+
+#[tokio::test]
+async fn semantic_tokens_resolve_each_binding_in_its_scope() {
+    let server = get_test_server("polyglot-spring").await;
+    let uri = Url::parse("file:///tmp/Retry.java").unwrap();
+    let content = r#"public class Retry {
+    public void run(java.util.List<Integer> values) {
+        try {
+            int e = 1;
+            values.add(e);
+        } catch (RuntimeException e) {
+            values.add(e.hashCode());
+        }
+    }
+}
+"#;
+    server
+        .backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "java".to_string(),
+                version: 1,
+                text: content.to_string(),
+            },
+        })
+        .await;
+
+    // A range request first: the full request after it reuses its tokens.
+    let range = server
+        .backend
+        .semantic_tokens_range(SemanticTokensRangeParams {
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+            text_document: TextDocumentIdentifier { uri: uri.clone() },
+            range: Range::new(Position::new(3, 0), Position::new(7, 0)),
+        })
+        .await
+        .unwrap();
+    let Some(SemanticTokensRangeResult::Tokens(range)) = range else {
+        panic!("expected tokens, got {range:?}");
+    };
+    let full = server
+        .backend
+        .semantic_tokens_full(SemanticTokensParams {
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+            text_document: TextDocumentIdentifier { uri },
+        })
+        .await
+        .unwrap();
+    let Some(SemanticTokensResult::Tokens(full)) = full else {
+        panic!("expected tokens, got {full:?}");
+    };
+
+    let locals: Vec<(u32, u32, String)> = decode(&range.data)
+        .into_iter()
+        .filter(|(_, _, token_type, _)| matches!(token_type.as_str(), "variable" | "parameter"))
+        .map(|(line, start, token_type, _)| (line, start, token_type))
+        .collect();
+    assert_eq!(
+        locals,
+        [
+            (3, 16, "variable".to_string()),
+            (4, 12, "parameter".to_string()),
+            (4, 23, "variable".to_string()),
+            (5, 34, "parameter".to_string()),
+            (6, 12, "parameter".to_string()),
+            (6, 23, "parameter".to_string()),
+        ]
+    );
+    let full_in_range: Vec<_> = decode(&full.data)
+        .into_iter()
+        .filter(|(line, ..)| (3..7).contains(line))
+        .collect();
+    assert_eq!(decode(&range.data), full_in_range);
+}
@@ -0,0 +1,53 @@
+use std::{path::Path, sync::Arc};
+
+use groovy::GroovySupport;
+use lspintar_server::{Indexer, Repository, supertypes::descendants};
+use uuid::Uuid;
+
+#[tokio::test]
+async fn descendants_follow_subclasses() {
+    let db_name = Uuid::new_v4();
+    let db_dir = format!("file:{}?mode=memory", db_name);
+    let repo = Arc::new(Repository::new(&db_dir).await.unwrap());
+    let mut indexer = Indexer::new(Arc::clone(&repo));
+    indexer.register_language("groovy", Arc::new(GroovySupport::new()));
+
+    let files = [
+        (
+            "/tmp/api/Service.groovy",
+            "package com.example.api\n\ninterface Service {}\n",
+        ),
+        (
+            "/tmp/impl/BaseService.groovy",
+            "package com.example.impl\n\nimport com.example.api.Service\n\nclass BaseService implements Service {}\n",
+        ),
+        (
+            "/tmp/app/AppService.groovy",
+            "package com.example.app\n\nimport com.example.impl.BaseService\n\nclass AppService extends BaseService {}\n",
+        ),
+    ];
+    for (path, content) in files {
+        let (symbols, supers) = indexer
+            .index_content(Path::new(path), content)
+            .unwrap()
+            .unwrap();
+        repo.replace_file_symbols(path, &symbols, &supers)
+            .await
+            .unwrap();
+    }
+
+    let found: Vec<String> = descendants(&repo, "com.example.api.Service")
+        .await
+        .into_iter()
+        .map(|s| s.fully_qualified_name)
+        .collect();
+    assert_eq!(
+        found,
+        vec!["com.example.impl.BaseService", "com.example.app.AppService"]
+    );
+    assert!(
+        descendants(&repo, "com.example.app.AppService")
+            .await
+            .is_empty()
+    );
+}
@@ -0,0 +1,81 @@
+use lspintar_server::{
+    models::symbol::{Symbol, SymbolMetadata},
+    synthetic_members::providers,
+};
+use sqlx::types::Json;
+
+fn class(file_type: &str, annotations: &[&str]) -> Symbol {
+    Symbol {
+        id: None,
+        short_name: "Account".to_string(),
+        package_name: "com.example".to_string(),
+        fully_qualified_name: "com.example.Account".to_string(),
+        parent_name: None,
+        file_path: "/src/com/example/Account".to_string(),
+        file_type: file_type.to_string(),
+        symbol_type: "Class".to_string(),
+        modifiers: Json(vec![]),
+        line_start: 0,
+        line_end: 0,
+        char_start: 0,
+        char_end: 0,
+        ident_line_start: 0,
+        ident_line_end: 0,
+        ident_char_start: 0,
+        ident_char_end: 0,
+        metadata: Json(SymbolMetadata {
+            parameters: None,
+            return_type: None,
+            documentation: None,
+            annotations: Some(annotations.iter().map(|a| a.to_string()).collect()),
+            generic_return_type: None,
+            type_params: None,
+            generic_param_types: None,
+            method_type_params: None,
+            constant_value: None,
+            entity_name: None,
+            synthetic_accessor: None,
+        }),
+        last_modified: 0,
+    }
+}
+
+fn declared_names(class: &Symbol, member: &str) -> Vec<String> {
+    providers(&class.file_type)
+        .iter()
+        .flat_map(|provider| provider.declared_names(class, member))
+        .collect()
+}
+
+#[test]
+fn java_property_stands_for_bean_getters() {
+    assert_eq!(
+        declared_names(&class("java", &[]), "balance"),
+        vec!["getBalance", "isBalance"]
+    );
+}
+
+#[test]
+fn lombok_accessors_stand_for_fields() {
+    let data = class("java", &["Data"]);
+    assert!(declared_names(&data, "getBalance").contains(&"balance".to_string()));
+    assert!(declared_names(&data, "setBalance").contains(&"balance".to_string()));
+
+    let getter = class("java", &["Getter"]);
+    assert!(!declared_names(&getter, "setBalance").contains(&"balance".to_string()));
+    assert!(!declared_names(&class("java", &[]), "getBalance").contains(&"balance".to_string()));
+}
+
+#[test]
+fn groovy_properties_and_accessors_stand_for_each_other() {
+    let groovy = class("groovy", &[]);
+    assert!(declared_names(&groovy, "balance").contains(&"getBalance".to_string()));
+    assert!(declared_names(&groovy, "isActive").contains(&"active".to_string()));
+    assert!(declared_names(&groovy, "setBalance").contains(&"balance".to_string()));
+}
+
+#[test]
+fn kotlin_types_have_no_synthetic_members() {
+    assert!(declared_names(&class("kotlin", &["Data"]), "getBalance").is_empty());
+    assert!(declared_names(&class("kotlin", &[]), "balance").is_empty());
+}
@@ -0,0 +1,234 @@
+//! Integration tests for the `lspintar/tests` and `lspintar/testLocation` requests.
+
+use lspintar_server::test_discovery::{
+    TestFramework, TestItem, TestItemKind, TestLocationParams, TestsParams,
+};
+use tower_lsp::{
+    LanguageServer,
+    lsp_types::{
+        DidOpenTextDocumentParams, Location, Position, TextDocumentIdentifier, TextDocumentItem,
+        Url,
+    },
+};
+
+use crate::util::get_test_server;
+
+mod util;
+
+async fn discover(uri: &str, language_id: &str, content: &str) -> Vec<TestItem> {
+    let server = get_test_server("polyglot-spring").await;
+    let uri = Url::parse(uri).unwrap();
+    server
+        .backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: language_id.to_string(),
+                version: 1,
+                text: content.to_string(),
+            },
+        })
+        .await;
+
+    server
+        .backend
+        .tests(TestsParams {
+            text_document: Some(TextDocumentIdentifier { uri }),
+        })
+        .await
+        .expect("tests request failed")
+}
+
+async fn locate(class_name: &str, test_name: &str) -> Option<Location> {
+    let server = get_test_server("polyglot-spring").await;
+    server
+        .backend
+        .test_location(TestLocationParams {
+            class_name: class_name.to_string(),
+            test_name: test_name.to_string(),
+        })
+        .await
+        .expect("testLocation request failed")
+}
+
+fn labels(items: &[TestItem]) -> Vec<&str> {
+    items.iter().map(|i| i.label.as_str()).collect()
+}
+
+/// JUnit 5 test methods are found by annotation; helpers are not tests.
+#[tokio::test]
+async fn discovers_junit5_test_methods() {
+    let content = r#"package com.example;
+
+import org.junit.jupiter.api.Test;
+import org.junit.jupiter.params.ParameterizedTest;
+
+class CalculatorTest {
+    @Test
+    void adds() {}
+
+    @ParameterizedTest
+    void subtracts(int a) {}
+
+    private int helper() { return 1; }
+}
+"#;
+    let items = discover("file:///tmp/CalculatorTest.java", "java", content).await;
+
+    assert_eq!(labels(&items), vec!["CalculatorTest"]);
+    let class = &items[0];
+    assert_eq!(class.id, "com.example.CalculatorTest");
+    assert_eq!(class.framework, TestFramework::JUnit5);
+    assert_eq!(labels(&class.children), vec!["adds", "subtracts"]);
+    assert_eq!(class.children[0].id, "com.example.CalculatorTest#adds");
+}
+
+/// `org.junit.Test` marks a JUnit 4 test.
+#[tokio::test]
+async fn discovers_junit4_test_methods() {
+    let content = r#"package com.example;
+
+import org.junit.Test;
+
+public class LegacyTest {
+    @Test
+    public void works() {}
+}
+"#;
+    let items = discover("file:///tmp/LegacyTest.java", "java", content).await;
+
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].framework, TestFramework::JUnit4);
+    assert_eq!(labels(&items[0].children), vec!["works"]);
+}
+
+/// Spock feature methods are listed by their string names; fixture methods are not.
+#[tokio::test]
+async fn discovers_spock_features() {
+    let content = r#"package com.example
+
+import spock.lang.Specification
+
+class StackSpec extends Specification {
+    def setup() {}
+
+    def "pushes an element"() {
+        expect:
+        true
+    }
+}
+"#;
+    let items = discover("file:///tmp/StackSpec.groovy", "groovy", content).await;
+
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].framework, TestFramework::Spock);
+    assert_eq!(labels(&items[0].children), vec!["pushes an element"]);
+}
+
+/// Kotest string-named tests are nested under their containers.
+#[tokio::test]
+async fn discovers_nested_kotest_tests() {
+    let content = r#"package com.example
+
+import io.kotest.core.spec.style.FunSpec
+
+class MathSpec : FunSpec({
+    context("addition") {
+        test("adds two numbers") {
+        }
+    }
+    test("multiplies") {
+    }
+})
+"#;
+    let items = discover("file:///tmp/MathSpec.kt", "kotlin", content).await;
+
+    assert_eq!(items.len(), 1);
+    let spec = &items[0];
+    assert_eq!(spec.framework, TestFramework::Kotest);
+    assert_eq!(labels(&spec.children), vec!["addition", "multiplies"]);
+
+    let addition = &spec.children[0];
+    assert_eq!(addition.kind, TestItemKind::Container);
+    assert_eq!(labels(&addition.children), vec!["adds two numbers"]);
+    assert_eq!(
+        addition.children[0].id,
+        "com.example.MathSpec#addition -- adds two numbers"
+    );
+    assert_eq!(spec.children[1].kind, TestItemKind::Test);
+}
+
+/// A Kotest report line names a nested test by its container path.
+#[tokio::test]
+async fn locates_kotest_test_by_display_name() {
+    let content = r#"package com.example
+
+import io.kotest.core.spec.style.DescribeSpec
+
+class ParserSpec : DescribeSpec({
+    describe("parser") {
+        it("reads numbers") {
+        }
+    }
+})
+"#;
+    discover("file:///tmp/ParserSpec.kt", "kotlin", content).await;
+
+    let location = locate("com.example.ParserSpec", "parser -- reads numbers")
+        .await
+        .expect("test location");
+    assert_eq!(location.uri.as_str(), "file:///tmp/ParserSpec.kt");
+    assert_eq!(location.range.start, Position::new(6, 11));
+}
+
+/// Unrolled Spock iterations are matched against the feature's name template.
+#[tokio::test]
+async fn locates_unrolled_spock_iteration() {
+    let content = r#"package com.example
+
+import spock.lang.Specification
+
+class MaxSpec extends Specification {
+    def "max of #a and #b is #c"() {
+        expect:
+        Math.max(a, b) == c
+
+        where:
+        a | b | c
+        1 | 3 | 3
+    }
+}
+"#;
+    discover("file:///tmp/MaxSpec.groovy", "groovy", content).await;
+
+    let location = locate("com.example.MaxSpec", "max of 1 and 3 is 3")
+        .await
+        .expect("test location");
+    assert_eq!(location.range.start.line, 5);
+}
+
+/// JUnit reports methods with their parameter list; unknown tests fall back to
+/// the class declaration.
+#[tokio::test]
+async fn locates_junit_method_and_falls_back_to_class() {
+    let content = r#"package com.example;
+
+import org.junit.jupiter.api.Test;
+
+class GreeterTest {
+    @Test
+    void greets() {}
+}
+"#;
+    discover("file:///tmp/GreeterTest.java", "java", content).await;
+
+    let method = locate("com.example.GreeterTest", "greets()")
+        .await
+        .expect("method location");
+    assert_eq!(method.range.start.line, 6);
+
+    let class = locate("GreeterTest", "removed()")
+        .await
+        .expect("class location");
+    assert_eq!(class.range.start.line, 4);
+}
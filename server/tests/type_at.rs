@@ -0,0 +1,55 @@
+use std::env;
+
+use pretty_assertions::assert_eq;
+use tower_lsp::lsp_types::{Position, TextDocumentIdentifier, TextDocumentPositionParams, Url};
+
+use crate::util::get_test_server;
+
+mod util;
+
+fn controller_position(line: u32, character: u32) -> TextDocumentPositionParams {
+    let root = env::current_dir().expect("cannot get current dir");
+    TextDocumentPositionParams {
+        text_document: TextDocumentIdentifier {
+            uri: Url::from_file_path(root.join(
+                "tests/fixtures/polyglot-spring/src/main/groovy/com/example/demo/Controller.groovy",
+            ))
+            .expect("cannot parse root URI"),
+        },
+        position: Position::new(line, character),
+    }
+}
+
+#[tokio::test]
+async fn type_at_method_call_field_and_local() {
+    let server = get_test_server("polyglot-spring").await;
+
+    for (line, character, expected) in [
+        (31, 45, "String"),
+        (40, 10, "UserRepository"),
+        (40, 28, "User"),
+    ] {
+        let result = server
+            .backend
+            .type_at(controller_position(line, character))
+            .await
+            .unwrap();
+        assert_eq!(
+            result.as_deref(),
+            Some(expected),
+            "type at {line}:{character}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn type_at_literal() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let result = server
+        .backend
+        .type_at(controller_position(37, 44))
+        .await
+        .unwrap();
+    assert_eq!(result.as_deref(), Some("Long"));
+}
@@ -7,7 +7,10 @@ use tower_lsp::LanguageServer;
 use lspintar_server::{Repository, server::Backend};
 use tower_lsp::{
     LspService,
-    lsp_types::{InitializeParams, InitializedParams, Url},
+    lsp_types::{
+        ClientCapabilities, DidOpenTextDocumentParams, InitializeParams, InitializedParams,
+        TextDocumentItem, Url,
+    },
 };
 
 use dashmap::DashMap;
@@ -19,20 +22,35 @@ pub struct TestServer {
 }
 
 impl TestServer {
-    async fn new(fixture: &str) -> Self {
+    async fn new(
+        fixture: &str,
+        capabilities: ClientCapabilities,
+        options: Option<serde_json::Value>,
+    ) -> Self {
+        let root = env::current_dir().expect("cannot get current dir");
+        let root_uri = Url::from_file_path(root.join("tests/fixtures").join(fixture))
+            .expect("cannot parse root URI");
+        Self::with_root(Some(root_uri), capabilities, options).await
+    }
+
+    async fn with_root(
+        root_uri: Option<Url>,
+        capabilities: ClientCapabilities,
+        options: Option<serde_json::Value>,
+    ) -> Self {
         let temp_file = tempfile::NamedTempFile::new().unwrap();
         let db_dir = format!("sqlite:{}", temp_file.path().display());
         let repo = Arc::new(Repository::new(&db_dir).await.unwrap());
         let (service, _socket) = LspService::new(|client| Backend::new(client));
         let backend = service.inner().clone();
         backend.repo.set(repo).ok();
-        let root = env::current_dir().expect("cannot get current dir");
 
-        let mut init_params = InitializeParams::default();
-        init_params.root_uri = Some(
-            Url::from_file_path(root.join("tests/fixtures").join(fixture))
-                .expect("cannot parse root URI"),
-        );
+        let init_params = InitializeParams {
+            capabilities,
+            root_uri,
+            initialization_options: options,
+            ..Default::default()
+        };
 
         backend.initialize(init_params).await.unwrap();
         backend.initialized(InitializedParams {}).await;
@@ -53,9 +71,67 @@ pub async fn get_test_server(fixture: &'static str) -> Arc<TestServer> {
         .clone();
 
     let server = cell
-        .get_or_init(|| async { Arc::new(TestServer::new(fixture).await) })
+        .get_or_init(|| async {
+            Arc::new(TestServer::new(fixture, ClientCapabilities::default(), None).await)
+        })
         .await
         .clone();
 
     server
 }
+
+/// A fresh, uncached server whose client advertises `capabilities`.  Use this
+/// only for behaviour that depends on client capabilities, since every call
+/// indexes the fixture again.
+#[allow(dead_code)]
+pub async fn get_test_server_with_capabilities(
+    fixture: &'static str,
+    capabilities: ClientCapabilities,
+) -> TestServer {
+    TestServer::new(fixture, capabilities, None).await
+}
+
+/// A fresh, uncached server started with `options` as its initialization options.
+#[allow(dead_code)]
+pub async fn get_test_server_with_options(
+    fixture: &'static str,
+    options: serde_json::Value,
+) -> TestServer {
+    TestServer::new(fixture, ClientCapabilities::default(), Some(options)).await
+}
+
+/// A fresh server started without a workspace root, as editors do for a file
+/// opened on its own.
+#[allow(dead_code)]
+pub async fn get_standalone_test_server() -> TestServer {
+    TestServer::with_root(None, ClientCapabilities::default(), None).await
+}
+
+/// Opens a `polyglot-spring` file referencing `Greeter` through wildcard imports of
+/// both packages declaring one, `com.example` and `com.other`.  Returns its URI;
+/// the reference is at line 6, column 4.
+#[allow(dead_code)]
+pub async fn open_ambiguous_greeter_client(server: &TestServer) -> Url {
+    let uri = Url::parse("file:///tmp/GreeterClient.java").unwrap();
+    let content = r#"package com.client;
+
+import com.example.*;
+import com.other.*;
+
+class GreeterClient {
+    Greeter greeter;
+}
+"#;
+    server
+        .backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "java".to_string(),
+                version: 1,
+                text: content.to_string(),
+            },
+        })
+        .await;
+    uri
+}
@@ -0,0 +1,96 @@
+use std::{fs, sync::Arc};
+
+use groovy::GroovySupport;
+use lspintar_server::{
+    Indexer, Repository,
+    constants::{
+        APP_VERSION, DB_PATH_FRAGMENT, INDEX_PATH_FRAGMENT, MANIFEST_PATH_FRAGMENT,
+        WORKSPACE_PATH_ROOT,
+    },
+    warm_cache::{export_cache, import_cache},
+};
+use serde_json::json;
+
+#[tokio::test]
+async fn exported_cache_is_relocated_on_import() {
+    let built = tempfile::tempdir().unwrap();
+    let exported = tempfile::tempdir().unwrap();
+    let checkout = tempfile::tempdir().unwrap();
+    let built_root = built.path().canonicalize().unwrap();
+    let checkout_root = checkout.path().canonicalize().unwrap();
+
+    let source = built_root.join("src/com/example/Service.groovy");
+    let db_path = built_root.join(DB_PATH_FRAGMENT);
+    fs::create_dir_all(db_path.parent().unwrap()).unwrap();
+    let repo = Arc::new(Repository::new(&db_path.to_string_lossy()).await.unwrap());
+    repo.adopt_path_root(WORKSPACE_PATH_ROOT, &built_root)
+        .await
+        .unwrap();
+    let mut indexer = Indexer::new(Arc::clone(&repo));
+    indexer.register_language("groovy", Arc::new(GroovySupport::new()));
+    let (symbols, supers) = indexer
+        .index_content(&source, "package com.example\n\ninterface Service {}\n")
+        .unwrap()
+        .unwrap();
+    repo.replace_file_symbols(&source.to_string_lossy(), &symbols, &supers)
+        .await
+        .unwrap();
+
+    fs::write(built_root.join(INDEX_PATH_FRAGMENT), APP_VERSION).unwrap();
+    let jar = dirs::home_dir()
+        .unwrap()
+        .join(".gradle/caches/modules-2/lib.jar");
+    fs::write(
+        built_root.join(MANIFEST_PATH_FRAGMENT),
+        json!([[jar, built_root.join("build/libs/app.jar")]]).to_string(),
+    )
+    .unwrap();
+
+    export_cache(&repo, &built_root, exported.path())
+        .await
+        .unwrap();
+    let manifest = fs::read_to_string(exported.path().join(MANIFEST_PATH_FRAGMENT)).unwrap();
+    assert_eq!(
+        manifest,
+        json!([[
+            "${HOME}/.gradle/caches/modules-2/lib.jar",
+            "${ROOT}/build/libs/app.jar"
+        ]])
+        .to_string()
+    );
+
+    import_cache(exported.path(), &checkout_root).await.unwrap();
+    let imported = Repository::new(&checkout_root.join(DB_PATH_FRAGMENT).to_string_lossy())
+        .await
+        .unwrap();
+    imported.add_path_root(WORKSPACE_PATH_ROOT, &checkout_root);
+    let relocated = checkout_root.join("src/com/example/Service.groovy");
+    let found = imported
+        .find_symbols_by_file_path(&relocated.to_string_lossy())
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].fully_qualified_name, "com.example.Service");
+
+    let manifest = fs::read_to_string(checkout_root.join(MANIFEST_PATH_FRAGMENT)).unwrap();
+    assert_eq!(
+        manifest,
+        json!([[jar, checkout_root.join("build/libs/app.jar")]]).to_string()
+    );
+    assert!(checkout_root.join(INDEX_PATH_FRAGMENT).exists());
+}
+
+#[tokio::test]
+async fn import_refuses_a_cache_from_another_version() {
+    let exported = tempfile::tempdir().unwrap();
+    let checkout = tempfile::tempdir().unwrap();
+    fs::create_dir_all(exported.path().join(".lspintar")).unwrap();
+    fs::write(exported.path().join(INDEX_PATH_FRAGMENT), "0.0.0-old").unwrap();
+
+    assert!(
+        import_cache(exported.path(), checkout.path())
+            .await
+            .is_err()
+    );
+    assert!(!checkout.path().join(DB_PATH_FRAGMENT).exists());
+}
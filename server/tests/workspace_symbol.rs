@@ -0,0 +1,57 @@
+use tower_lsp::{
+    LanguageServer,
+    lsp_types::{SymbolKind, WorkspaceSymbolParams},
+};
+
+use crate::util::get_test_server;
+
+mod util;
+
+fn params(query: &str) -> WorkspaceSymbolParams {
+    WorkspaceSymbolParams {
+        query: query.to_string(),
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    }
+}
+
+#[tokio::test]
+async fn workspace_symbol_matches_camel_humps() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let symbols = server.backend.symbol(params("JvSvc")).await.unwrap().unwrap();
+    let first = symbols.first().expect("JavaService should match");
+    assert_eq!(first.name, "JavaService");
+    assert_eq!(first.kind, SymbolKind::CLASS);
+    assert!(first.location.uri.path().ends_with("demo/JavaService.java"));
+
+    let symbols = server.backend.symbol(params("usrrepo")).await.unwrap().unwrap();
+    assert_eq!(
+        symbols.first().map(|s| s.name.as_str()),
+        Some("UserRepository")
+    );
+}
+
+#[tokio::test]
+async fn workspace_symbol_finds_methods_by_initials() {
+    let server = get_test_server("polyglot-spring").await;
+
+    let symbols = server.backend.symbol(params("fBI")).await.unwrap().unwrap();
+    let mut containers: Vec<_> = symbols
+        .iter()
+        .filter(|s| s.name == "findById")
+        .map(|s| {
+            assert_eq!(s.kind, SymbolKind::METHOD);
+            s.container_name.clone().unwrap_or_default()
+        })
+        .collect();
+    containers.sort();
+    assert_eq!(
+        containers,
+        vec!["com.example.BaseRepository", "com.example.UserRepository"]
+    );
+
+    // Lowercase letters continue a hump; they do not start one mid-word.
+    let symbols = server.backend.symbol(params("fyd")).await.unwrap().unwrap();
+    assert!(symbols.iter().all(|s| s.name != "findById"), "{symbols:?}");
+}